@@ -0,0 +1,40 @@
+//! Pack-level analyses built on top of the parsed model.
+//!
+//! Each submodule inspects a `QuestDatabase` (usually together with an
+//! `ItemIndex`) and returns structured findings rather than printing, so
+//! callers can render or serialize the results however they like.
+
+pub mod chains;
+pub mod cluster;
+pub mod consume;
+pub mod coverage;
+pub mod cycles;
+pub mod economy;
+pub mod item_flow;
+pub mod layout;
+pub mod loot;
+pub mod namespace;
+pub mod similarity;
+pub mod softlock;
+
+pub use chains::{ChainEdit, ChainSuggestion, flatten_suggestions};
+pub use cluster::{ItemCluster, cluster_by_items, jaccard};
+pub use consume::{
+    ConsumeFinding, ConsumeIssue, ItemSource, RETRIEVAL_TASK_ID, audit_consumption,
+    classify_item_source,
+};
+pub use coverage::{CoverageReport, KeyItem, KeyItemCoverage, coverage_report, read_key_items};
+pub use cycles::{PrerequisiteCycle, find_cycles};
+pub use economy::{EconomyReport, EconomyThresholds, RepeatableEconomy, economy_report};
+pub use item_flow::{PrerequisiteSuggestion, suggest_prerequisites};
+pub use layout::{LayoutIssue, LayoutLimits, check_layout};
+pub use loot::{
+    LootFinding, LootIssue, RewardPool, RewardRoll, audit_loot, expected_values, reward_roll,
+    roll_issues, roll_value,
+};
+pub use namespace::{
+    HighPartGroup, NamespaceConvention, NamespaceReport, NamespaceRule, NamespaceViolation,
+    namespace_report,
+};
+pub use similarity::{QuestMatch, SimilarityOptions, similar_quests};
+pub use softlock::{Softlock, detect_softlocks};
@@ -0,0 +1,124 @@
+//! Suggestions for flattening over-deep linear quest chains.
+//!
+//! A link `a -> b` is *single-dependent* when `b` is the only quest depending
+//! on `a` and `a` is the only prerequisite of `b`. Maximal runs of such links
+//! form chains the player must walk one quest at a time. Chains longer than a
+//! configured depth are reported together with refactoring candidates:
+//!
+//! - `Merge`: the upstream quest hands out no rewards, so its tasks could be
+//!   folded into the next quest without losing anything.
+//! - `Parallelize`: none of the quest's required items are rewarded earlier in
+//!   the chain, so it could hang off the chain head instead of the previous
+//!   quest.
+use super::consume::dependents_map;
+use crate::index::{ItemIndex, required_item_set};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+/// A refactoring candidate inside a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainEdit {
+    /// Fold `absorb` (which has no rewards) into its single dependent `keep`.
+    Merge { absorb: QuestId, keep: QuestId },
+    /// Re-parent `quest` from `current_parent` to `attach_to` (the chain head).
+    Parallelize {
+        quest: QuestId,
+        current_parent: QuestId,
+        attach_to: QuestId,
+    },
+}
+
+/// An over-deep chain and the edits suggested for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSuggestion {
+    /// Quests of the chain, head first.
+    pub chain: Vec<QuestId>,
+    /// Merge candidates in chain order, followed by re-parenting candidates
+    /// in chain order.
+    pub edits: Vec<ChainEdit>,
+}
+
+/// Distinct prerequisites of any kind.
+fn all_prereqs(quest: &Quest) -> BTreeSet<QuestId> {
+    quest
+        .prerequisites
+        .iter()
+        .chain(&quest.required_prerequisites)
+        .chain(&quest.optional_prerequisites)
+        .copied()
+        .collect()
+}
+
+/// Find chains longer than `max_depth` quests made of single-dependent links
+/// and suggest merge/parallelization candidates. Results are ordered by chain
+/// head id.
+pub fn flatten_suggestions(
+    db: &QuestDatabase,
+    index: &ItemIndex,
+    max_depth: usize,
+) -> Vec<ChainSuggestion> {
+    let dependents = dependents_map(db);
+    // next[a] = b for every single-dependent link a -> b
+    let next = |a: &QuestId| -> Option<QuestId> {
+        let [b] = dependents.get(a)?.as_slice() else {
+            return None;
+        };
+        let prereqs = all_prereqs(db.quests.get(b)?);
+        (prereqs.len() == 1 && prereqs.contains(a)).then_some(*b)
+    };
+    let linked: HashSet<QuestId> = db.quests.keys().filter_map(next).collect();
+
+    let mut heads: Vec<&QuestId> = db
+        .quests
+        .keys()
+        .filter(|q| !linked.contains(q) && next(q).is_some())
+        .collect();
+    heads.sort();
+
+    let mut out = Vec::new();
+    for head in heads {
+        let mut chain = vec![*head];
+        let mut at = *head;
+        while let Some(b) = next(&at) {
+            if chain.contains(&b) {
+                break;
+            }
+            chain.push(b);
+            at = b;
+        }
+        if chain.len() <= max_depth {
+            continue;
+        }
+
+        let mut edits = Vec::new();
+        for pair in chain.windows(2) {
+            if db.quests[&pair[0]].rewards.is_empty() {
+                edits.push(ChainEdit::Merge {
+                    absorb: pair[0],
+                    keep: pair[1],
+                });
+            }
+        }
+        for i in 2..chain.len() {
+            let needs = required_item_set(&db.quests[&chain[i]]);
+            let supplied_earlier = needs.iter().any(|item| {
+                index
+                    .rewarded_by(item)
+                    .iter()
+                    .any(|g| chain[1..i].contains(&g.quest))
+            });
+            if !supplied_earlier {
+                edits.push(ChainEdit::Parallelize {
+                    quest: chain[i],
+                    current_parent: chain[i - 1],
+                    attach_to: chain[0],
+                });
+            }
+        }
+        out.push(ChainSuggestion { chain, edits });
+    }
+    out
+}
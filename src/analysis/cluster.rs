@@ -0,0 +1,157 @@
+//! Quest clustering by shared item requirements.
+//!
+//! Quests are compared by the Jaccard similarity of the item sets their tasks
+//! require (`|A ∩ B| / |A ∪ B|`). Clustering is single-linkage: the most
+//! similar pairs are joined first until `k` groups remain, so a cluster is a
+//! set of quests connected by strongly overlapping requirements. Pairs with no
+//! common item are never joined, which means fewer than `k` merges can happen
+//! and many quests stay on their own. Clusters of two or more quests point at
+//! redundant quests or candidates for consolidation.
+use crate::index::{ItemKey, required_item_set};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A group of quests with overlapping item requirements.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemCluster {
+    /// Member quests, ascending.
+    pub quests: Vec<QuestId>,
+    /// Items required by every member.
+    pub shared_items: Vec<ItemKey>,
+    /// Similarity of the weakest link that joined the cluster.
+    pub min_similarity: f64,
+}
+
+/// Jaccard similarity of two item sets (0 when both are empty).
+pub fn jaccard(a: &BTreeSet<ItemKey>, b: &BTreeSet<ItemKey>) -> f64 {
+    let shared = a.intersection(b).count();
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        0.0
+    } else {
+        shared as f64 / union as f64
+    }
+}
+
+/// Minimal union-find over indices.
+struct Components {
+    parent: Vec<usize>,
+}
+
+impl Components {
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+}
+
+/// Group quests that require items into at most `k` single-linkage clusters
+/// and return the clusters with two or more quests, most cohesive first.
+///
+/// Quests without item requirements are ignored.
+pub fn cluster_by_items(db: &QuestDatabase, k: usize) -> Vec<ItemCluster> {
+    let mut ids: Vec<QuestId> = db.quests.keys().copied().collect();
+    ids.sort();
+    let sets: Vec<(QuestId, BTreeSet<ItemKey>)> = ids
+        .into_iter()
+        .map(|id| (id, required_item_set(&db.quests[&id])))
+        .filter(|(_, set)| !set.is_empty())
+        .collect();
+
+    // candidate pairs share at least one item; find them through an inverted index
+    let mut by_item: HashMap<&ItemKey, Vec<usize>> = HashMap::new();
+    for (i, (_, set)) in sets.iter().enumerate() {
+        for item in set {
+            by_item.entry(item).or_default().push(i);
+        }
+    }
+    let mut pairs: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for members in by_item.values() {
+        for (n, &a) in members.iter().enumerate() {
+            for &b in &members[n + 1..] {
+                pairs.insert((a, b));
+            }
+        }
+    }
+    let mut edges: Vec<(f64, usize, usize)> = pairs
+        .into_iter()
+        .map(|(a, b)| (jaccard(&sets[a].1, &sets[b].1), a, b))
+        .collect();
+    edges.sort_by(|x, y| y.0.total_cmp(&x.0).then((x.1, x.2).cmp(&(y.1, y.2))));
+
+    let mut comps = Components {
+        parent: (0..sets.len()).collect(),
+    };
+    let mut remaining = sets.len();
+    let mut weakest: HashMap<usize, f64> = HashMap::new();
+    for (sim, a, b) in edges {
+        if remaining <= k {
+            break;
+        }
+        let (ra, rb) = (comps.find(a), comps.find(b));
+        if ra == rb {
+            continue;
+        }
+        comps.parent[rb] = ra;
+        remaining -= 1;
+        let w = [weakest.remove(&ra), weakest.remove(&rb), Some(sim)]
+            .into_iter()
+            .flatten()
+            .fold(f64::INFINITY, f64::min);
+        weakest.insert(ra, w);
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..sets.len() {
+        let root = comps.find(i);
+        groups.entry(root).or_default().push(i);
+    }
+    let mut clusters: Vec<ItemCluster> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(root, members)| {
+            let mut shared = sets[members[0]].1.clone();
+            for m in &members[1..] {
+                shared.retain(|item| sets[*m].1.contains(item));
+            }
+            ItemCluster {
+                quests: members.iter().map(|m| sets[*m].0).collect(),
+                shared_items: shared.into_iter().collect(),
+                min_similarity: weakest[&root],
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.min_similarity
+            .total_cmp(&a.min_similarity)
+            .then_with(|| a.quests.cmp(&b.quests))
+    });
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(items: &[&str]) -> BTreeSet<ItemKey> {
+        items
+            .iter()
+            .map(|id| ItemKey {
+                id: id.to_string(),
+                damage: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn jaccard_similarity() {
+        assert_eq!(jaccard(&set(&["a", "b"]), &set(&["b", "c"])), 1.0 / 3.0);
+        assert_eq!(jaccard(&set(&["a"]), &set(&["a"])), 1.0);
+        assert_eq!(jaccard(&set(&[]), &set(&[])), 0.0);
+    }
+}
@@ -0,0 +1,167 @@
+//! Consume-vs-detect audit for retrieval tasks.
+//!
+//! Retrieval tasks either only detect items in the player's inventory or take
+//! them away (`consume`). Consuming is a design decision with economic impact:
+//! an item that is taken by one quest and needed again by a later quest must
+//! be obtainable twice. When the only sources of that item inside the quest
+//! book are one-shot rewards, the player may end up short.
+use crate::index::{ItemIndex, ItemKey};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Task id of BetterQuesting's standard item retrieval task.
+pub const RETRIEVAL_TASK_ID: &str = "bq_standard:retrieval";
+
+/// Where an item comes from, as far as the quest book can tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemSource {
+    /// No reward grants the item; it is presumably crafted, mined or bought.
+    Unrewarded,
+    /// Only non-repeatable quests grant the item, so the supply is finite.
+    Finite,
+    /// At least one repeatable quest grants the item.
+    Renewable,
+}
+
+/// A single inconsistency found by `audit_consumption`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConsumeIssue {
+    /// `autoConsume` is set while `consume` is not, so the flag has no effect.
+    AutoConsumeWithoutConsume,
+    /// The task consumes an item with a finite quest-book supply that later
+    /// quests (descendants in the prerequisite graph) also require.
+    ConsumesFiniteItemNeededLater {
+        /// The consumed item.
+        item: ItemKey,
+        /// Later quests requiring the same item, in ascending id order.
+        later: Vec<QuestId>,
+    },
+}
+
+/// Finding reported for one retrieval task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumeFinding {
+    /// Quest owning the task.
+    pub quest: QuestId,
+    /// Index of the task inside the quest.
+    pub task_index: Option<usize>,
+    /// What is wrong with the task.
+    pub issue: ConsumeIssue,
+}
+
+/// Returns true when the quest can be completed more than once.
+pub(crate) fn is_repeatable(quest: &Quest) -> bool {
+    quest
+        .properties
+        .as_ref()
+        .and_then(|p| p.repeat_time)
+        .is_some_and(|t| t >= 0)
+}
+
+/// Classify how `item` can be obtained from the quest book's rewards.
+pub fn classify_item_source(db: &QuestDatabase, index: &ItemIndex, item: &ItemKey) -> ItemSource {
+    let grants = index.rewarded_by(item);
+    if grants.is_empty() {
+        ItemSource::Unrewarded
+    } else if grants
+        .iter()
+        .any(|g| db.quests.get(&g.quest).is_some_and(is_repeatable))
+    {
+        ItemSource::Renewable
+    } else {
+        ItemSource::Finite
+    }
+}
+
+/// Map each quest to the quests that list it as any kind of prerequisite.
+pub(crate) fn dependents_map(db: &QuestDatabase) -> HashMap<QuestId, Vec<QuestId>> {
+    let mut dependents: HashMap<QuestId, Vec<QuestId>> = HashMap::new();
+    for (qid, quest) in &db.quests {
+        let mut seen: HashSet<QuestId> = HashSet::new();
+        let all = quest
+            .prerequisites
+            .iter()
+            .chain(&quest.required_prerequisites)
+            .chain(&quest.optional_prerequisites);
+        for p in all {
+            if seen.insert(*p) {
+                dependents.entry(*p).or_default().push(*qid);
+            }
+        }
+    }
+    dependents
+}
+
+/// Breadth-first walk collecting every transitive dependent of `start`.
+pub(crate) fn descendants(
+    dependents: &HashMap<QuestId, Vec<QuestId>>,
+    start: QuestId,
+) -> HashSet<QuestId> {
+    let mut seen: HashSet<QuestId> = HashSet::new();
+    let mut queue: VecDeque<QuestId> = VecDeque::from([start]);
+    while let Some(q) = queue.pop_front() {
+        for d in dependents.get(&q).into_iter().flatten() {
+            if *d != start && seen.insert(*d) {
+                queue.push_back(*d);
+            }
+        }
+    }
+    seen
+}
+
+/// Audit every retrieval task in `db` for inconsistent consume settings.
+///
+/// Findings are returned in ascending quest id order, then task order.
+pub fn audit_consumption(db: &QuestDatabase, index: &ItemIndex) -> Vec<ConsumeFinding> {
+    let dependents = dependents_map(db);
+    let mut findings = Vec::new();
+
+    let mut ids: Vec<&QuestId> = db.quests.keys().collect();
+    ids.sort();
+    for qid in ids {
+        let quest = &db.quests[qid];
+        // descendants are only needed for quests that actually consume something
+        let mut later_cache: Option<HashSet<QuestId>> = None;
+        for task in quest
+            .tasks
+            .iter()
+            .filter(|t| t.task_id == RETRIEVAL_TASK_ID)
+        {
+            let consume = task.consume.unwrap_or(false);
+            if task.auto_consume.unwrap_or(false) && !consume {
+                findings.push(ConsumeFinding {
+                    quest: *qid,
+                    task_index: task.index,
+                    issue: ConsumeIssue::AutoConsumeWithoutConsume,
+                });
+            }
+            if !consume {
+                continue;
+            }
+            let later_set = later_cache.get_or_insert_with(|| descendants(&dependents, *qid));
+            for stack in &task.required_items {
+                let item = ItemKey::from_stack(stack);
+                if classify_item_source(db, index, &item) != ItemSource::Finite {
+                    continue;
+                }
+                let mut later: Vec<QuestId> = index
+                    .required_by(&item)
+                    .iter()
+                    .map(|r| r.quest)
+                    .filter(|q| later_set.contains(q))
+                    .collect();
+                later.dedup();
+                if !later.is_empty() {
+                    findings.push(ConsumeFinding {
+                        quest: *qid,
+                        task_index: task.index,
+                        issue: ConsumeIssue::ConsumesFiniteItemNeededLater { item, later },
+                    });
+                }
+            }
+        }
+    }
+    findings
+}
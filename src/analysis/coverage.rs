@@ -0,0 +1,102 @@
+//! Quest book coverage of a pack's key progression items.
+//!
+//! Pack authors usually know which machines and materials gate progression.
+//! Given that list (one `mod:item[:damage]` per line, see `read_key_items`),
+//! `coverage_report` tells which of them no quest mentions in a task or a
+//! reward, i.e. progression content the quest book forgot to cover.
+use crate::error::Result;
+use crate::index::{ItemIndex, ItemKey, split_damage};
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Read;
+
+/// An item the quest book is expected to cover.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct KeyItem {
+    /// Namespaced item id.
+    pub id: String,
+    /// Damage / meta value; `None` matches every damage value of `id`.
+    pub damage: Option<i32>,
+}
+
+impl KeyItem {
+    /// Returns true if `key` is this item.
+    pub fn matches(&self, key: &ItemKey) -> bool {
+        key.id == self.id && self.damage.is_none_or(|d| d == key.damage)
+    }
+}
+
+/// Read a key item list: one `mod:item` or `mod:item:damage` per line.
+/// Blank lines and text after `#` are ignored.
+pub fn read_key_items<R: Read>(mut r: R) -> Result<Vec<KeyItem>> {
+    let mut text = String::new();
+    r.read_to_string(&mut text)?;
+    Ok(text
+        .lines()
+        .map(|l| l.split_once('#').map_or(l, |(before, _)| before).trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| {
+            let (id, damage) = split_damage(l);
+            KeyItem { id, damage }
+        })
+        .collect())
+}
+
+/// How one key item is referenced by the quest book.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyItemCoverage {
+    /// The key item.
+    pub item: KeyItem,
+    /// Quests with a task requiring the item, ascending.
+    pub required_by: Vec<QuestId>,
+    /// Quests with a reward granting the item, ascending.
+    pub rewarded_by: Vec<QuestId>,
+}
+
+impl KeyItemCoverage {
+    /// Returns true when no quest references the item.
+    pub fn is_missing(&self) -> bool {
+        self.required_by.is_empty() && self.rewarded_by.is_empty()
+    }
+}
+
+/// Coverage of every key item, in list order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// One entry per key item.
+    pub items: Vec<KeyItemCoverage>,
+}
+
+impl CoverageReport {
+    /// Key items no quest references.
+    pub fn missing(&self) -> Vec<&KeyItem> {
+        self.items
+            .iter()
+            .filter(|c| c.is_missing())
+            .map(|c| &c.item)
+            .collect()
+    }
+}
+
+/// Look up every key item in `index`.
+pub fn coverage_report(index: &ItemIndex, key_items: &[KeyItem]) -> CoverageReport {
+    let known = index.items();
+    let items = key_items
+        .iter()
+        .map(|item| {
+            let mut required_by = BTreeSet::new();
+            let mut rewarded_by = BTreeSet::new();
+            for key in known.iter().filter(|k| item.matches(k)) {
+                required_by.extend(index.required_by(key).iter().map(|r| r.quest));
+                rewarded_by.extend(index.rewarded_by(key).iter().map(|g| g.quest));
+            }
+            KeyItemCoverage {
+                item: item.clone(),
+                required_by: required_by.into_iter().collect(),
+                rewarded_by: rewarded_by.into_iter().collect(),
+            }
+        })
+        .collect();
+    CoverageReport { items }
+}
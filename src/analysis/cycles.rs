@@ -0,0 +1,85 @@
+//! Every prerequisite cycle in a pack, not just the first.
+//!
+//! `importance` stops at the first cycle it meets with `CycleDetected`. Pack
+//! authors fixing a broken book want all of them at once, so `find_cycles`
+//! reports each cyclic strongly connected component together with the quest
+//! names and one concrete loop to break.
+use crate::graph::{condensation, dependents_graph};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+/// A group of quests that lock each other through prerequisites.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrerequisiteCycle {
+    /// Every quest of the strongly connected component, ascending.
+    pub members: Vec<QuestId>,
+    /// Display names of `members` (formatting codes removed, empty when the
+    /// quest has no name), in the same order.
+    pub names: Vec<String>,
+    /// A shortest loop through the smallest member, in prerequisite ->
+    /// dependent order; the first quest is not repeated at the end.
+    pub path: Vec<QuestId>,
+}
+
+/// Shortest loop from `start` back to itself using only `members`.
+fn shortest_loop(
+    graph: &BTreeMap<QuestId, BTreeSet<QuestId>>,
+    members: &[QuestId],
+    start: QuestId,
+) -> Vec<QuestId> {
+    let inside = |q: &QuestId| members.binary_search(q).is_ok();
+    let mut parent: HashMap<QuestId, QuestId> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(q) = queue.pop_front() {
+        for d in graph.get(&q).into_iter().flatten().filter(|d| inside(d)) {
+            if *d == start {
+                let mut path = vec![q];
+                let mut at = q;
+                while let Some(p) = parent.get(&at) {
+                    path.push(*p);
+                    at = *p;
+                }
+                path.reverse();
+                return path;
+            }
+            if !parent.contains_key(d) {
+                parent.insert(*d, q);
+                queue.push_back(*d);
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// All prerequisite cycles in `db`, ordered by their smallest member.
+/// Quests listing themselves as a prerequisite form one-quest cycles.
+pub fn find_cycles(db: &QuestDatabase) -> Vec<PrerequisiteCycle> {
+    let graph = dependents_graph(db);
+    condensation(db)
+        .components
+        .into_iter()
+        .filter(|c| c.cyclic)
+        .map(|c| {
+            let names = c
+                .members
+                .iter()
+                .map(|q| {
+                    db.quests
+                        .get(q)
+                        .and_then(|quest| quest.name())
+                        .map(strip_formatting)
+                        .unwrap_or_default()
+                })
+                .collect();
+            let path = shortest_loop(&graph, &c.members, c.members[0]);
+            PrerequisiteCycle {
+                members: c.members,
+                names,
+                path,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,138 @@
+//! Daily reward throughput of repeatable quests.
+//!
+//! A repeatable quest can be claimed again once its `repeatTime` cooldown
+//! (in ticks of real time, 20 per second) has passed, so over a day a player
+//! can claim it at most `TICKS_PER_DAY / repeatTime` times. Multiplying by the
+//! expected reward value from `loot::expected_values` gives the most value
+//! per day the quest can hand one player; summed over every repeatable quest
+//! it bounds the loot a player can farm from the quest book.
+//!
+//! A quest is flagged as exploitable when its daily value exceeds
+//! `EconomyThresholds::max_value_per_day`, or when it rewards anything at all
+//! with a cooldown shorter than `EconomyThresholds::min_cooldown_ticks`
+//! (including a zero cooldown, which only limits claims by how fast the
+//! player can redo the tasks).
+use super::consume::is_repeatable;
+use super::loot::expected_values;
+use crate::index::ItemKey;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Ticks in a real-time day.
+pub const TICKS_PER_DAY: f64 = 20.0 * 60.0 * 60.0 * 24.0;
+
+/// Limits above which a repeatable quest is flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EconomyThresholds {
+    /// Highest acceptable reward value per player per day.
+    pub max_value_per_day: f64,
+    /// Shortest acceptable cooldown for a quest with valuable rewards.
+    pub min_cooldown_ticks: i32,
+}
+
+impl Default for EconomyThresholds {
+    /// No more than 1000 value a day, no cooldown under a minute.
+    fn default() -> Self {
+        EconomyThresholds {
+            max_value_per_day: 1000.0,
+            min_cooldown_ticks: 20 * 60,
+        }
+    }
+}
+
+/// Claim economics of one repeatable quest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepeatableEconomy {
+    /// The repeatable quest.
+    pub quest: QuestId,
+    /// `repeatTime` in ticks.
+    pub cooldown_ticks: i32,
+    /// Claims per day; `None` when the cooldown is zero.
+    pub claims_per_day: Option<f64>,
+    /// Expected reward value of one claim.
+    pub value_per_claim: f64,
+    /// Expected reward value per day; `None` when unbounded.
+    pub value_per_day: Option<f64>,
+    /// The quest breaks one of the `EconomyThresholds`.
+    pub exploitable: bool,
+}
+
+/// Result of `economy_report`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EconomyReport {
+    /// Every repeatable quest: unbounded ones first, then by value per day
+    /// (highest first), then by quest id.
+    pub quests: Vec<RepeatableEconomy>,
+    /// Most reward value one player can claim per day from the quests with
+    /// a cooldown.
+    pub max_value_per_day: f64,
+    /// Repeatable quests with rewards and no cooldown, whose daily value has
+    /// no bound.
+    pub unbounded: Vec<QuestId>,
+}
+
+impl EconomyReport {
+    /// The quests flagged as exploitable, in report order.
+    pub fn exploitable(&self) -> impl Iterator<Item = &RepeatableEconomy> {
+        self.quests.iter().filter(|q| q.exploitable)
+    }
+}
+
+/// Claim economics of every repeatable quest in `db`, pricing rewards with
+/// `prices` (unpriced items are worth 0).
+pub fn economy_report(
+    db: &QuestDatabase,
+    prices: &HashMap<ItemKey, f64>,
+    thresholds: &EconomyThresholds,
+) -> EconomyReport {
+    let values = expected_values(db, prices);
+    let mut quests: Vec<RepeatableEconomy> = db
+        .quests
+        .values()
+        .filter(|q| is_repeatable(q))
+        .map(|quest| {
+            let cooldown_ticks = quest
+                .properties
+                .as_ref()
+                .and_then(|p| p.repeat_time)
+                .unwrap_or(0);
+            let value_per_claim = values.get(&quest.id).copied().unwrap_or(0.0);
+            let claims_per_day =
+                (cooldown_ticks > 0).then(|| TICKS_PER_DAY / cooldown_ticks as f64);
+            let value_per_day = match claims_per_day {
+                Some(claims) => Some(claims * value_per_claim),
+                None if value_per_claim > 0.0 => None,
+                None => Some(0.0),
+            };
+            let exploitable = value_per_claim > 0.0
+                && (cooldown_ticks < thresholds.min_cooldown_ticks
+                    || value_per_day.is_none_or(|v| v > thresholds.max_value_per_day));
+            RepeatableEconomy {
+                quest: quest.id,
+                cooldown_ticks,
+                claims_per_day,
+                value_per_claim,
+                value_per_day,
+                exploitable,
+            }
+        })
+        .collect();
+    quests.sort_by(|a, b| {
+        let rank = |q: &RepeatableEconomy| q.value_per_day.unwrap_or(f64::INFINITY);
+        rank(b)
+            .partial_cmp(&rank(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.quest.cmp(&b.quest))
+    });
+    EconomyReport {
+        max_value_per_day: quests.iter().filter_map(|q| q.value_per_day).sum(),
+        unbounded: quests
+            .iter()
+            .filter(|q| q.value_per_day.is_none())
+            .map(|q| q.quest)
+            .collect(),
+        quests,
+    }
+}
@@ -0,0 +1,86 @@
+//! Missing prerequisite suggestions from item flow.
+//!
+//! When a quest asks for an item that only some quest's rewards hand out,
+//! the quest granting it is probably meant to come first. For every item a
+//! quest's tasks require, the quests rewarding it are looked up in the
+//! `ItemIndex`; if none of them is already a (transitive) prerequisite, each
+//! one that does not itself depend on the quest is suggested as a new
+//! prerequisite.
+//!
+//! Each item contributes a confidence of `1/n` when `n` quests reward it,
+//! halved when the item is only one option of a choice reward. The
+//! contributions of all items pointing at the same prerequisite combine as
+//! `1 - Π(1 - c)`, so several items leading back to one quest make a strong
+//! case. Items no quest rewards (gathered or crafted in the world) suggest
+//! nothing.
+use crate::graph::QuestGraph;
+use crate::index::{ItemIndex, ItemKey, required_item_set};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A prerequisite the item flow suggests adding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrerequisiteSuggestion {
+    /// The quest that should get the prerequisite.
+    pub quest: QuestId,
+    /// The suggested prerequisite.
+    pub prerequisite: QuestId,
+    /// Items `quest` requires that `prerequisite` rewards, sorted.
+    pub items: Vec<ItemKey>,
+    /// Confidence in `(0, 1]`.
+    pub confidence: f64,
+}
+
+/// Prerequisites suggested for the quests of `db`, most confident first,
+/// ties by quest then prerequisite id.
+pub fn suggest_prerequisites(db: &QuestDatabase, index: &ItemIndex) -> Vec<PrerequisiteSuggestion> {
+    let graph = QuestGraph::build(db);
+    let mut out = Vec::new();
+    for quest in crate::ordering::sorted_quests(db) {
+        // prerequisite -> (items, product of (1 - confidence))
+        let mut found: BTreeMap<QuestId, (Vec<ItemKey>, f64)> = BTreeMap::new();
+        for item in required_item_set(quest) {
+            let grants: Vec<_> = index
+                .rewarded_by(&item)
+                .iter()
+                .filter(|g| g.quest != quest.id)
+                .collect();
+            if grants.is_empty() || grants.iter().any(|g| graph.is_ancestor(g.quest, quest.id)) {
+                continue;
+            }
+            let mut sources: BTreeMap<QuestId, bool> = BTreeMap::new();
+            for grant in &grants {
+                // a quest granting the item outright beats one offering it as a choice
+                let choice_only = sources.get(&grant.quest).copied().unwrap_or(true);
+                sources.insert(grant.quest, choice_only && grant.choice);
+            }
+            let share = 1.0 / sources.len() as f64;
+            for (source, choice_only) in sources {
+                if graph.is_ancestor(quest.id, source) {
+                    continue;
+                }
+                let confidence = if choice_only { share / 2.0 } else { share };
+                let entry = found.entry(source).or_insert_with(|| (Vec::new(), 1.0));
+                entry.0.push(item.clone());
+                entry.1 *= 1.0 - confidence;
+            }
+        }
+        out.extend(found.into_iter().map(|(prerequisite, (items, doubt))| {
+            PrerequisiteSuggestion {
+                quest: quest.id,
+                prerequisite,
+                items,
+                confidence: 1.0 - doubt,
+            }
+        }));
+    }
+    out.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.quest.cmp(&b.quest))
+            .then_with(|| a.prerequisite.cmp(&b.prerequisite))
+    });
+    out
+}
@@ -0,0 +1,213 @@
+//! Questline layout checks.
+//!
+//! BetterQuesting draws every questline entry as a tile at `(x, y)` of size
+//! `size_x` by `size_y`. It does not care whether tiles overlap, so a pack
+//! edited by hand (or merged from two sources) can end up with quests
+//! stacked on top of each other, where only the top one can be clicked, or
+//! parked thousands of pixels away where nobody scrolls. `check_layout`
+//! reports, per questline:
+//!
+//! - entries sharing a position, as one group per position;
+//! - other pairs of tiles that overlap;
+//! - tiles without a positive width and height;
+//! - tiles not fully inside `LayoutLimits`.
+//!
+//! Missing coordinates count as `0` and missing sizes as
+//! `LayoutLimits::default_size`, as in game.
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Canvas area tiles are expected in, and the tile size of entries without
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutLimits {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+    pub default_size: i32,
+}
+
+impl Default for LayoutLimits {
+    /// 24 pixel tiles within 4096 pixels of the origin either way.
+    fn default() -> Self {
+        LayoutLimits {
+            min_x: -4096,
+            min_y: -4096,
+            max_x: 4096,
+            max_y: 4096,
+            default_size: 24,
+        }
+    }
+}
+
+/// A problem with the placement of questline entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LayoutIssue {
+    /// Several entries at the same position, in entry order.
+    DuplicatePosition {
+        questline: QuestId,
+        x: i32,
+        y: i32,
+        quests: Vec<QuestId>,
+    },
+    /// Two tiles at different positions overlap; `first` comes first in the
+    /// questline.
+    Overlap {
+        questline: QuestId,
+        first: QuestId,
+        second: QuestId,
+    },
+    /// A tile with zero or negative width or height, which cannot be clicked.
+    EmptyTile {
+        questline: QuestId,
+        quest: QuestId,
+        size_x: i32,
+        size_y: i32,
+    },
+    /// A tile not fully inside the `LayoutLimits` area.
+    OutOfBounds {
+        questline: QuestId,
+        quest: QuestId,
+        x: i32,
+        y: i32,
+    },
+}
+
+/// An entry's tile in questline coordinates, placed the way the quest book
+/// places it: missing coordinates count as `0` and missing sizes as the
+/// given default. Stored sizes are kept even when not positive. Shared by the
+/// layout checks and the questline drawings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Tile {
+    pub(crate) quest: QuestId,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+}
+
+impl Tile {
+    pub(crate) fn new(entry: &QuestLineEntry, default_size: i32) -> Self {
+        Tile {
+            quest: entry.quest_id,
+            x: entry.x.unwrap_or(0),
+            y: entry.y.unwrap_or(0),
+            width: entry.size_x.unwrap_or(default_size),
+            height: entry.size_y.unwrap_or(default_size),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    pub(crate) fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    pub(crate) fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    pub(crate) fn center(&self) -> (f64, f64) {
+        (
+            self.x as f64 + self.width as f64 / 2.0,
+            self.y as f64 + self.height as f64 / 2.0,
+        )
+    }
+
+    fn overlaps(&self, other: &Tile) -> bool {
+        (self.x as i64) < other.right()
+            && (other.x as i64) < self.right()
+            && (self.y as i64) < other.bottom()
+            && (other.y as i64) < self.bottom()
+    }
+
+    fn inside(&self, limits: &LayoutLimits) -> bool {
+        self.x >= limits.min_x
+            && self.y >= limits.min_y
+            && self.right() <= limits.max_x as i64
+            && self.bottom() <= limits.max_y as i64
+    }
+}
+
+/// Layout problems of one questline, in the order described above.
+fn check_questline(line: &QuestLine, limits: &LayoutLimits) -> Vec<LayoutIssue> {
+    let questline = line.id;
+    let tiles: Vec<Tile> = line
+        .entries
+        .iter()
+        .map(|e| Tile::new(e, limits.default_size))
+        .collect();
+    let mut out = Vec::new();
+
+    let mut by_position: BTreeMap<(i32, i32), Vec<usize>> = BTreeMap::new();
+    for (i, tile) in tiles.iter().enumerate() {
+        by_position.entry((tile.x, tile.y)).or_default().push(i);
+    }
+    let mut stacked: Vec<&Vec<usize>> = by_position.values().filter(|g| g.len() > 1).collect();
+    stacked.sort_by_key(|group| group[0]);
+    for group in stacked {
+        let first = tiles[group[0]];
+        out.push(LayoutIssue::DuplicatePosition {
+            questline,
+            x: first.x,
+            y: first.y,
+            quests: group.iter().map(|i| tiles[*i].quest).collect(),
+        });
+    }
+
+    // sweep over tiles sorted by left edge; only tiles starting before the
+    // current one ends can overlap it
+    let mut by_left: Vec<usize> = (0..tiles.len()).filter(|i| !tiles[*i].is_empty()).collect();
+    by_left.sort_by_key(|i| (tiles[*i].x, *i));
+    let mut overlaps = Vec::new();
+    for (n, &a) in by_left.iter().enumerate() {
+        for &b in &by_left[n + 1..] {
+            if tiles[b].x as i64 >= tiles[a].right() {
+                break;
+            }
+            let same_position = (tiles[a].x, tiles[a].y) == (tiles[b].x, tiles[b].y);
+            if !same_position && tiles[a].overlaps(&tiles[b]) {
+                overlaps.push((a.min(b), a.max(b)));
+            }
+        }
+    }
+    overlaps.sort();
+    out.extend(overlaps.into_iter().map(|(a, b)| LayoutIssue::Overlap {
+        questline,
+        first: tiles[a].quest,
+        second: tiles[b].quest,
+    }));
+
+    for tile in tiles.iter().filter(|t| t.is_empty()) {
+        out.push(LayoutIssue::EmptyTile {
+            questline,
+            quest: tile.quest,
+            size_x: tile.width,
+            size_y: tile.height,
+        });
+    }
+    for tile in tiles.iter().filter(|t| !t.inside(limits)) {
+        out.push(LayoutIssue::OutOfBounds {
+            questline,
+            quest: tile.quest,
+            x: tile.x,
+            y: tile.y,
+        });
+    }
+    out
+}
+
+/// Layout problems of every questline, in questline order.
+pub fn check_layout(db: &QuestDatabase, limits: &LayoutLimits) -> Vec<LayoutIssue> {
+    sorted_questlines(db)
+        .into_iter()
+        .flat_map(|line| check_questline(line, limits))
+        .collect()
+}
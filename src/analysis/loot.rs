@@ -0,0 +1,259 @@
+//! Expected value and sanity audit of randomized rewards.
+//!
+//! BetterQuesting's own rewards are deterministic, but loot-box style reward
+//! handlers keep weighted pools next to the standard fields. After NBT key
+//! normalization a reward entry can carry:
+//!
+//! - `rewards` / `items`: stacks always granted,
+//! - `choices`: alternatives the player picks one of, or, when any stack has a
+//!   `weight`, alternatives drawn at random in proportion to their weights,
+//! - `pools`: a list of `{weight, rewards}` entries of which one is drawn at
+//!   random in proportion to `weight` (missing weights count as 1).
+//!
+//! `reward_roll` turns an entry into a `RewardRoll`; `expected_values` prices
+//! every quest's rolls and `audit_loot` reports pools that cannot be drawn
+//! sensibly.
+use crate::index::ItemKey;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Tolerance used when checking that probabilities sum to 1.
+const PROBABILITY_EPSILON: f64 = 1e-6;
+
+/// One weighted outcome of a random draw.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RewardPool {
+    /// Relative weight; `None` when the stored value is not a number.
+    pub weight: Option<f64>,
+    /// Stacks granted when the pool is drawn.
+    pub items: Vec<ItemStack>,
+}
+
+/// The structure of one reward entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RewardRoll {
+    /// Stacks always granted.
+    pub fixed: Vec<ItemStack>,
+    /// Alternatives the player picks one of.
+    pub choice: Vec<ItemStack>,
+    /// Outcomes of which exactly one is drawn at random.
+    pub pools: Vec<RewardPool>,
+}
+
+impl RewardRoll {
+    /// Returns true when claiming the reward involves a random draw.
+    pub fn is_random(&self) -> bool {
+        !self.pools.is_empty()
+    }
+}
+
+fn stacks(v: Option<&Value>) -> Vec<ItemStack> {
+    match v {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|i| serde_json::from_value(i.clone()).ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the fixed, choice and weighted parts of `reward`.
+pub fn reward_roll(reward: &Reward) -> RewardRoll {
+    let mut roll = RewardRoll {
+        fixed: reward.items.clone(),
+        ..RewardRoll::default()
+    };
+    if reward
+        .choices
+        .iter()
+        .any(|s| s.extra.contains_key("weight"))
+    {
+        roll.pools = reward
+            .choices
+            .iter()
+            .map(|s| {
+                let mut item = s.clone();
+                let weight = item
+                    .extra
+                    .remove("weight")
+                    .map_or(Some(1.0), |w| w.as_f64());
+                RewardPool {
+                    weight,
+                    items: vec![item],
+                }
+            })
+            .collect();
+    } else {
+        roll.choice = reward.choices.clone();
+    }
+    if let Some(Value::Array(pools)) = reward.extra.get("pools") {
+        roll.pools.extend(pools.iter().map(|p| RewardPool {
+            weight: p.get("weight").map_or(Some(1.0), Value::as_f64),
+            items: stacks(p.get("rewards").or_else(|| p.get("items"))),
+        }));
+    }
+    roll
+}
+
+/// Value of `stacks` given per-item prices; unpriced items are worth 0.
+fn stacks_value(stacks: &[ItemStack], prices: &HashMap<ItemKey, f64>) -> f64 {
+    stacks
+        .iter()
+        .map(|s| {
+            let price = prices.get(&ItemKey::from_stack(s)).copied().unwrap_or(0.0);
+            price * s.count.unwrap_or(1) as f64
+        })
+        .sum()
+}
+
+/// Usable weight of a pool: invalid or negative weights never draw.
+fn draw_weight(pool: &RewardPool) -> f64 {
+    pool.weight
+        .filter(|w| w.is_finite() && *w > 0.0)
+        .unwrap_or(0.0)
+}
+
+/// Expected value of claiming a reward once: fixed items, plus the best
+/// choice (the player picks the most valuable option), plus the
+/// weight-averaged value of the pools.
+pub fn roll_value(roll: &RewardRoll, prices: &HashMap<ItemKey, f64>) -> f64 {
+    let fixed = stacks_value(&roll.fixed, prices);
+    let choice = roll
+        .choice
+        .iter()
+        .map(|s| stacks_value(std::slice::from_ref(s), prices))
+        .fold(0.0, f64::max);
+    let total: f64 = roll.pools.iter().map(draw_weight).sum();
+    let random = if total > 0.0 {
+        roll.pools
+            .iter()
+            .map(|p| draw_weight(p) / total * stacks_value(&p.items, prices))
+            .sum()
+    } else {
+        0.0
+    };
+    fixed + choice + random
+}
+
+/// Expected reward value of every quest in `db`, summed over its rewards.
+pub fn expected_values(
+    db: &QuestDatabase,
+    prices: &HashMap<ItemKey, f64>,
+) -> HashMap<QuestId, f64> {
+    db.quests
+        .iter()
+        .map(|(qid, quest)| {
+            let value = quest
+                .rewards
+                .iter()
+                .map(|r| roll_value(&reward_roll(r), prices))
+                .sum();
+            (*qid, value)
+        })
+        .collect()
+}
+
+/// A problem with the random part of a reward.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LootIssue {
+    /// The pool's weight is not a finite, non-negative number.
+    InvalidWeight {
+        /// Index of the pool in `RewardRoll::pools`.
+        pool: usize,
+    },
+    /// No pool has a positive weight, so nothing can ever be drawn.
+    NothingDrawable,
+    /// The weights are fractions no larger than 1, so they read as
+    /// probabilities, but they do not add up to 1.
+    ProbabilitiesDoNotSumToOne {
+        /// The actual sum.
+        total: f64,
+    },
+    /// The item can never be granted: its pool has zero weight, or the stack
+    /// is empty (count below 1, air or no item id).
+    UnobtainableItem {
+        /// Index of the pool in `RewardRoll::pools`.
+        pool: usize,
+        /// The item.
+        item: ItemKey,
+    },
+    /// The pool grants nothing when drawn.
+    EmptyPool {
+        /// Index of the pool in `RewardRoll::pools`.
+        pool: usize,
+    },
+}
+
+/// Finding reported for one reward.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootFinding {
+    /// Quest owning the reward.
+    pub quest: QuestId,
+    /// Index of the reward inside the quest.
+    pub reward_index: Option<usize>,
+    /// What is wrong with the reward.
+    pub issue: LootIssue,
+}
+
+fn is_empty_stack(stack: &ItemStack) -> bool {
+    stack.id.is_empty() || stack.id == "minecraft:air" || stack.count.is_some_and(|c| c < 1)
+}
+
+/// Issues with the random pools of `roll`.
+pub fn roll_issues(roll: &RewardRoll) -> Vec<LootIssue> {
+    let mut issues = Vec::new();
+    if !roll.is_random() {
+        return issues;
+    }
+    for (i, pool) in roll.pools.iter().enumerate() {
+        if !pool.weight.is_some_and(|w| w.is_finite() && w >= 0.0) {
+            issues.push(LootIssue::InvalidWeight { pool: i });
+        }
+        if pool.items.is_empty() {
+            issues.push(LootIssue::EmptyPool { pool: i });
+        }
+        let drawable = draw_weight(pool) > 0.0;
+        for stack in &pool.items {
+            if !drawable || is_empty_stack(stack) {
+                issues.push(LootIssue::UnobtainableItem {
+                    pool: i,
+                    item: ItemKey::from_stack(stack),
+                });
+            }
+        }
+    }
+    let total: f64 = roll.pools.iter().map(draw_weight).sum();
+    if total <= 0.0 {
+        issues.push(LootIssue::NothingDrawable);
+    } else if roll.pools.iter().all(|p| draw_weight(p) <= 1.0)
+        && roll.pools.iter().any(|p| draw_weight(p).fract() != 0.0)
+        && (total - 1.0).abs() > PROBABILITY_EPSILON
+    {
+        issues.push(LootIssue::ProbabilitiesDoNotSumToOne { total });
+    }
+    issues
+}
+
+/// Audit every randomized reward in `db`, in ascending quest id order.
+pub fn audit_loot(db: &QuestDatabase) -> Vec<LootFinding> {
+    let mut ids: Vec<&QuestId> = db.quests.keys().collect();
+    ids.sort();
+    let mut out = Vec::new();
+    for qid in ids {
+        for reward in &db.quests[qid].rewards {
+            out.extend(
+                roll_issues(&reward_roll(reward))
+                    .into_iter()
+                    .map(|issue| LootFinding {
+                        quest: *qid,
+                        reward_index: reward.index,
+                        issue,
+                    }),
+            );
+        }
+    }
+    out
+}
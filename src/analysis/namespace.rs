@@ -0,0 +1,262 @@
+//! QuestId namespace partitioning report.
+//!
+//! Multi-author packs commonly reserve ranges of the high id part per chapter
+//! or author so that quests created in parallel never collide. This module
+//! groups quests by high part and checks them against a user-supplied
+//! `NamespaceConvention` (typically loaded from JSON next to the pack).
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One reserved range of high id parts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceRule {
+    /// Human readable owner of the range (chapter, author, addon...).
+    pub name: String,
+    /// Lowest high part belonging to this namespace (inclusive).
+    pub high_min: i64,
+    /// Highest high part belonging to this namespace (inclusive).
+    pub high_max: i64,
+    /// Questlines whose entries must use this namespace. Empty means the rule
+    /// does not constrain questline membership.
+    #[serde(default)]
+    pub questlines: Vec<QuestId>,
+}
+
+impl NamespaceRule {
+    /// Returns true if `high` falls inside the reserved range.
+    pub fn contains(&self, high: i64) -> bool {
+        (self.high_min..=self.high_max).contains(&high)
+    }
+}
+
+/// A pack's id namespace convention: a list of reserved ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceConvention {
+    /// Reserved ranges, checked in order.
+    pub rules: Vec<NamespaceRule>,
+}
+
+impl NamespaceConvention {
+    /// The first rule whose range contains `high`.
+    pub fn rule_for(&self, high: i64) -> Option<&NamespaceRule> {
+        self.rules.iter().find(|r| r.contains(high))
+    }
+}
+
+/// Quests sharing one high part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighPartGroup {
+    /// The shared high part.
+    pub high: i64,
+    /// Namespace owning the high part, if any rule covers it.
+    pub namespace: Option<String>,
+    /// Quests in the group, ascending.
+    pub quests: Vec<QuestId>,
+}
+
+/// A breach of the namespace convention.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NamespaceViolation {
+    /// Two rules reserve overlapping ranges.
+    OverlappingRules { first: String, second: String },
+    /// The quest's high part is not covered by any rule.
+    Unassigned { quest: QuestId },
+    /// The quest sits in a questline reserved for another namespace.
+    WrongQuestline {
+        quest: QuestId,
+        questline: QuestId,
+        expected: String,
+    },
+}
+
+/// Result of `namespace_report`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceReport {
+    /// Quests grouped by high part, ascending.
+    pub groups: Vec<HighPartGroup>,
+    /// Violations in a stable order: rule overlaps, unassigned quests, then
+    /// questline mismatches.
+    pub violations: Vec<NamespaceViolation>,
+}
+
+/// Group quest ids by high part and check them against `convention`.
+///
+/// With an empty convention the report only contains the grouping.
+pub fn namespace_report(db: &QuestDatabase, convention: &NamespaceConvention) -> NamespaceReport {
+    let mut report = NamespaceReport::default();
+    let enforce = !convention.rules.is_empty();
+
+    for (i, a) in convention.rules.iter().enumerate() {
+        for b in &convention.rules[i + 1..] {
+            if a.high_min <= b.high_max && b.high_min <= a.high_max {
+                report
+                    .violations
+                    .push(NamespaceViolation::OverlappingRules {
+                        first: a.name.clone(),
+                        second: b.name.clone(),
+                    });
+            }
+        }
+    }
+
+    let mut by_high: BTreeMap<i64, Vec<QuestId>> = BTreeMap::new();
+    for qid in db.quests.keys() {
+        by_high.entry(qid.high_i64()).or_default().push(*qid);
+    }
+    for (high, mut quests) in by_high {
+        quests.sort();
+        let namespace = convention.rule_for(high).map(|r| r.name.clone());
+        if enforce && namespace.is_none() {
+            report.violations.extend(
+                quests
+                    .iter()
+                    .map(|q| NamespaceViolation::Unassigned { quest: *q }),
+            );
+        }
+        report.groups.push(HighPartGroup {
+            high,
+            namespace,
+            quests,
+        });
+    }
+
+    let mut line_ids: Vec<&QuestId> = db.questlines.keys().collect();
+    line_ids.sort();
+    for lid in line_ids {
+        let Some(rule) = convention.rules.iter().find(|r| r.questlines.contains(lid)) else {
+            continue;
+        };
+        let mut members: Vec<QuestId> = db.questlines[lid]
+            .entries
+            .iter()
+            .map(|e| e.quest_id)
+            .filter(|q| !rule.contains(q.high_i64()))
+            .collect();
+        members.sort();
+        members.dedup();
+        report.violations.extend(
+            members
+                .into_iter()
+                .map(|q| NamespaceViolation::WrongQuestline {
+                    quest: q,
+                    questline: *lid,
+                    expected: rule.name.clone(),
+                }),
+        );
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn db_with(ids: &[(i32, i32)], line: &[(i32, i32)]) -> QuestDatabase {
+        let mut quests = HashMap::new();
+        for (h, l) in ids {
+            let id = QuestId::from_parts(*h, *l);
+            quests.insert(
+                id,
+                Quest {
+                    id,
+                    properties: None,
+                    tasks: vec![],
+                    rewards: vec![],
+                    prerequisites: vec![],
+                    required_prerequisites: vec![],
+                    optional_prerequisites: vec![],
+                },
+            );
+        }
+        let lid = QuestId::from_parts(0, 100);
+        let entries = line
+            .iter()
+            .map(|(h, l)| QuestLineEntry {
+                index: None,
+                quest_id: QuestId::from_parts(*h, *l),
+                x: None,
+                y: None,
+                size_x: None,
+                size_y: None,
+                extra: HashMap::new(),
+            })
+            .collect();
+        let questline = QuestLine {
+            id: lid,
+            properties: None,
+            entries,
+            extra: HashMap::new(),
+        };
+        QuestDatabase {
+            settings: None,
+            quests,
+            questlines: HashMap::from([(lid, questline)]),
+            questline_order: vec![lid],
+        }
+    }
+
+    fn rule(name: &str, min: i64, max: i64, lines: Vec<QuestId>) -> NamespaceRule {
+        NamespaceRule {
+            name: name.to_string(),
+            high_min: min,
+            high_max: max,
+            questlines: lines,
+        }
+    }
+
+    #[test]
+    fn groups_by_high_part() {
+        let db = db_with(&[(0, 1), (0, 2), (5, 1)], &[]);
+        let report = namespace_report(&db, &NamespaceConvention::default());
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].quests.len(), 2);
+        assert_eq!(report.groups[1].high, 5);
+        assert!(report.violations.is_empty());
+    }
+
+    #[test]
+    fn wide_high_parts_are_not_truncated() {
+        let mut db = db_with(&[(0, 1)], &[]);
+        let wide = QuestId::from_parts_i64(1 << 32, 1);
+        let mut quest = db.quests[&QuestId::from_parts(0, 1)].clone();
+        quest.id = wide;
+        db.quests.insert(wide, quest);
+        let report = namespace_report(&db, &NamespaceConvention::default());
+        let highs: Vec<i64> = report.groups.iter().map(|g| g.high).collect();
+        assert_eq!(highs, [0, 1 << 32]);
+    }
+
+    #[test]
+    fn flags_unassigned_overlaps_and_questline_mismatches() {
+        let db = db_with(&[(0, 1), (1, 1), (9, 1)], &[(0, 1), (1, 1)]);
+        let convention = NamespaceConvention {
+            rules: vec![
+                rule("base", 0, 0, vec![QuestId::from_parts(0, 100)]),
+                rule("addon", 1, 2, vec![]),
+                rule("late", 2, 3, vec![]),
+            ],
+        };
+        let report = namespace_report(&db, &convention);
+        assert_eq!(
+            report.violations,
+            vec![
+                NamespaceViolation::OverlappingRules {
+                    first: "addon".into(),
+                    second: "late".into()
+                },
+                NamespaceViolation::Unassigned {
+                    quest: QuestId::from_parts(9, 1)
+                },
+                NamespaceViolation::WrongQuestline {
+                    quest: QuestId::from_parts(1, 1),
+                    questline: QuestId::from_parts(0, 100),
+                    expected: "base".into()
+                },
+            ]
+        );
+    }
+}
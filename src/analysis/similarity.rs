@@ -0,0 +1,176 @@
+//! Similar quests across two packs.
+//!
+//! Authors porting quests from one pack to another (or comparing two kitchen
+//! sink packs that borrowed from the same source) want to know which quests
+//! correspond even after ids were renumbered and names touched up.
+//! `similar_quests` scores pairs of quests, one from each database, by:
+//!
+//! - name similarity: `1 - edit distance / longer length` of the names with
+//!   formatting codes, case and repeated whitespace ignored;
+//! - task similarity: the Jaccard similarity of the task types and required
+//!   items of the two quests (left out when neither quest has tasks);
+//!
+//! combined as the weighted mean of the parts present. Only pairs sharing a
+//! name word or a required item are scored; task types are shared by most
+//! quests of a pack and count towards the score only. Quests whose names
+//! differ in every word and which share no required item are never paired.
+use crate::index::{ItemKey, loose_name_key};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// Weights and cut-off of `similar_quests`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityOptions {
+    /// Weight of the name similarity.
+    pub name_weight: f64,
+    /// Weight of the task similarity.
+    pub task_weight: f64,
+    /// Lowest score reported.
+    pub min_score: f64,
+}
+
+impl Default for SimilarityOptions {
+    /// Names and tasks weigh the same; scores from 0.8 are reported.
+    fn default() -> Self {
+        SimilarityOptions {
+            name_weight: 0.5,
+            task_weight: 0.5,
+            min_score: 0.8,
+        }
+    }
+}
+
+/// A quest of the left database resembling one of the right.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestMatch {
+    pub left: QuestId,
+    pub right: QuestId,
+    /// Name similarity in `[0, 1]`.
+    pub name_similarity: f64,
+    /// Task similarity in `[0, 1]`; `None` when neither quest has tasks.
+    pub task_similarity: Option<f64>,
+    /// Combined score in `[0, 1]`.
+    pub score: f64,
+}
+
+/// What a quest is compared by.
+struct Profile {
+    id: QuestId,
+    name: String,
+    features: BTreeSet<String>,
+}
+
+impl Profile {
+    /// Name words and required items, the keys candidate pairs must share.
+    fn block_keys(&self) -> impl Iterator<Item = &str> {
+        words(&self.name).chain(
+            self.features
+                .iter()
+                .map(String::as_str)
+                .filter(|f| f.starts_with("item ")),
+        )
+    }
+}
+
+fn profiles(db: &QuestDatabase) -> Vec<Profile> {
+    crate::ordering::sorted_quests(db)
+        .into_iter()
+        .map(|quest| {
+            let mut features = BTreeSet::new();
+            for task in &quest.tasks {
+                features.insert(format!("task {}", task.task_id));
+                for item in &task.required_items {
+                    let key = ItemKey::from_stack(item);
+                    features.insert(format!("item {}:{}", key.id, key.damage));
+                }
+            }
+            Profile {
+                id: quest.id,
+                name: loose_name_key(quest.name().unwrap_or_default()),
+                features,
+            }
+        })
+        .collect()
+}
+
+/// Words of a loose name key, punctuation dropped.
+fn words(name: &str) -> impl Iterator<Item = &str> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// `1 - edit distance / longer length`; 1 for two empty names.
+fn name_similarity(a: &str, b: &str) -> f64 {
+    let longer = a.chars().count().max(b.chars().count());
+    if longer == 0 {
+        return 1.0;
+    }
+    1.0 - crate::schema::levenshtein(a, b) as f64 / longer as f64
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> Option<f64> {
+    let shared = a.intersection(b).count();
+    let union = a.len() + b.len() - shared;
+    (union > 0).then(|| shared as f64 / union as f64)
+}
+
+/// Pairs of quests, one from `left` and one from `right`, scoring at least
+/// `options.min_score`; best first, ties by left then right id.
+pub fn similar_quests(
+    left: &QuestDatabase,
+    right: &QuestDatabase,
+    options: &SimilarityOptions,
+) -> Vec<QuestMatch> {
+    let right_profiles = profiles(right);
+    // name words and required items -> indices into `right_profiles`
+    let mut blocks: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, profile) in right_profiles.iter().enumerate() {
+        for key in profile.block_keys() {
+            blocks.entry(key).or_default().push(i);
+        }
+    }
+
+    let mut out = Vec::new();
+    for a in profiles(left) {
+        let mut candidates: BTreeSet<usize> = BTreeSet::new();
+        for key in a.block_keys() {
+            if let Some(found) = blocks.get(key) {
+                candidates.extend(found);
+            }
+        }
+        for b in candidates.into_iter().map(|i| &right_profiles[i]) {
+            let name_similarity = name_similarity(&a.name, &b.name);
+            let task_similarity = jaccard(&a.features, &b.features);
+            let score = match task_similarity {
+                Some(tasks) => {
+                    let total = options.name_weight + options.task_weight;
+                    if total > 0.0 {
+                        (options.name_weight * name_similarity + options.task_weight * tasks)
+                            / total
+                    } else {
+                        0.0
+                    }
+                }
+                None => name_similarity,
+            };
+            if score >= options.min_score {
+                out.push(QuestMatch {
+                    left: a.id,
+                    right: b.id,
+                    name_similarity,
+                    task_similarity,
+                    score,
+                });
+            }
+        }
+    }
+    out.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.left.cmp(&b.left))
+            .then_with(|| a.right.cmp(&b.right))
+    });
+    out
+}
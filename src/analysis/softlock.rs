@@ -0,0 +1,280 @@
+//! Softlock detection for consumed items with a finite supply.
+//!
+//! For every item that the quest book only hands out through non-repeatable
+//! rewards, we simulate an adversarial but legal completion order: the player
+//! completes the mandatory ancestors of a quest that needs the item, but first
+//! also completes other quests that consume it. If the item budget drops below
+//! what the target quest asks for, the player is stranded.
+//!
+//! Exploring every completion order is exponential, so consumers are added
+//! greedily (largest net drain first). Each added consumer brings its own
+//! mandatory ancestors, which keeps the sequence legal, and the search stops as
+//! soon as the target becomes impossible, so the reported sequence is short.
+//! Suppliers outside the sequence that the player could still complete before
+//! the target count towards it, and a shortage reached without any consumer
+//! in the sequence is not reported.
+//! Choice rewards are not counted as supply since the player may pick another
+//! option.
+use super::consume::{ItemSource, classify_item_source, dependents_map, descendants};
+use crate::index::{ItemIndex, ItemKey};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// A completion order that leaves a quest unable to obtain a consumed item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Softlock {
+    /// The item that runs out.
+    pub item: ItemKey,
+    /// Quest that can no longer be completed.
+    pub stranded_quest: QuestId,
+    /// Amount of the item the stranded quest asks for.
+    pub needed: i64,
+    /// Amount still available when the stranded quest is reached.
+    pub available: i64,
+    /// Completion order (prerequisites respected) leading to the softlock.
+    pub sequence: Vec<QuestId>,
+}
+
+/// Per-item demand and supply, grouped by quest.
+struct ItemFlow {
+    /// quest -> [(count, consumed)] for each task requiring the item
+    demand: HashMap<QuestId, Vec<(i64, bool)>>,
+    /// quest -> guaranteed amount granted on completion
+    supply: HashMap<QuestId, i64>,
+}
+
+impl ItemFlow {
+    fn new(index: &ItemIndex, item: &ItemKey) -> Self {
+        let mut demand: HashMap<QuestId, Vec<(i64, bool)>> = HashMap::new();
+        for r in index.required_by(item) {
+            demand
+                .entry(r.quest)
+                .or_default()
+                .push((r.count as i64, r.consumed));
+        }
+        let mut supply: HashMap<QuestId, i64> = HashMap::new();
+        for g in index.rewarded_by(item).iter().filter(|g| !g.choice) {
+            *supply.entry(g.quest).or_default() += g.count as i64;
+        }
+        ItemFlow { demand, supply }
+    }
+
+    /// Run `order` starting with `budget` items and return the remaining
+    /// budget, or `Err((quest, needed, available))` for the first quest whose
+    /// requirement cannot be met.
+    fn simulate(
+        &self,
+        order: &[QuestId],
+        mut budget: i64,
+    ) -> std::result::Result<i64, (QuestId, i64, i64)> {
+        for q in order {
+            for (count, consumed) in self.demand.get(q).into_iter().flatten() {
+                if budget < *count {
+                    return Err((*q, *count, budget));
+                }
+                if *consumed {
+                    budget -= count;
+                }
+            }
+            budget += self.supply.get(q).copied().unwrap_or(0);
+        }
+        Ok(budget)
+    }
+
+    /// Whether `quest` has a task consuming the item.
+    fn consumes(&self, quest: &QuestId) -> bool {
+        self.demand
+            .get(quest)
+            .is_some_and(|tasks| tasks.iter().any(|(_, consumed)| *consumed))
+    }
+}
+
+/// Mandatory prerequisites of a quest (optional/OR prerequisites are skipped
+/// because the player may satisfy them with another quest).
+fn mandatory_prereqs(quest: &Quest) -> impl Iterator<Item = &QuestId> {
+    quest
+        .prerequisites
+        .iter()
+        .chain(&quest.required_prerequisites)
+}
+
+/// Add `start` and all of its mandatory ancestors to `set`.
+fn add_with_ancestors(db: &QuestDatabase, start: QuestId, set: &mut BTreeSet<QuestId>) {
+    let mut stack = vec![start];
+    while let Some(q) = stack.pop() {
+        if !set.insert(q) {
+            continue;
+        }
+        if let Some(quest) = db.quests.get(&q) {
+            stack.extend(mandatory_prereqs(quest).copied());
+        }
+    }
+}
+
+/// Order `set` so every quest comes after its mandatory prerequisites,
+/// breaking ties by ascending id. Returns `None` if the subset has a cycle.
+fn order_subset(db: &QuestDatabase, set: &BTreeSet<QuestId>) -> Option<Vec<QuestId>> {
+    let mut indegree: HashMap<QuestId, usize> = set.iter().map(|q| (*q, 0)).collect();
+    let mut children: HashMap<QuestId, Vec<QuestId>> = HashMap::new();
+    for q in set {
+        let Some(quest) = db.quests.get(q) else {
+            continue;
+        };
+        let parents: HashSet<&QuestId> = mandatory_prereqs(quest).collect();
+        for p in parents.into_iter().filter(|p| set.contains(p)) {
+            *indegree.entry(*q).or_default() += 1;
+            children.entry(*p).or_default().push(*q);
+        }
+    }
+    let mut ready: BTreeSet<QuestId> = indegree
+        .iter()
+        .filter(|(_, d)| **d == 0)
+        .map(|(q, _)| *q)
+        .collect();
+    let mut order = Vec::with_capacity(set.len());
+    while let Some(q) = ready.pop_first() {
+        order.push(q);
+        for c in children.get(&q).into_iter().flatten() {
+            let d = indegree.get_mut(c)?;
+            *d -= 1;
+            if *d == 0 {
+                ready.insert(*c);
+            }
+        }
+    }
+    (order.len() == set.len()).then_some(order)
+}
+
+/// Detect completion orders that strand players without a consumed item.
+///
+/// Only items with an `ItemSource::Finite` supply are simulated; items that
+/// are never rewarded are assumed to be obtainable outside the quest book.
+/// Results are sorted by item, then stranded quest id.
+pub fn detect_softlocks(db: &QuestDatabase, index: &ItemIndex) -> Vec<Softlock> {
+    let dependents = dependents_map(db);
+    let mut out = Vec::new();
+
+    for item in index.items() {
+        if classify_item_source(db, index, item) != ItemSource::Finite {
+            continue;
+        }
+        let flow = ItemFlow::new(index, item);
+        if !flow
+            .demand
+            .values()
+            .flatten()
+            .any(|(_, consumed)| *consumed)
+        {
+            continue;
+        }
+        let mut consumers: Vec<QuestId> = flow
+            .demand
+            .keys()
+            .filter(|q| flow.consumes(q))
+            .copied()
+            .collect();
+        consumers.sort();
+        let mut targets: Vec<QuestId> = flow.demand.keys().copied().collect();
+        targets.sort();
+
+        for target in targets {
+            if let Some(lock) = strand_target(db, &dependents, &flow, &consumers, item, target) {
+                out.push(lock);
+            }
+        }
+    }
+    out
+}
+
+/// Supply granted by suppliers outside `chosen` that can still be completed
+/// before `target`: they are not the target or one of its descendants, and
+/// their mandatory ancestors can be ordered.
+fn spare_supply(
+    db: &QuestDatabase,
+    flow: &ItemFlow,
+    chosen: &BTreeSet<QuestId>,
+    after_target: &HashSet<QuestId>,
+    target: QuestId,
+) -> i64 {
+    let mut spare = 0;
+    for (supplier, count) in &flow.supply {
+        if *supplier == target || chosen.contains(supplier) || after_target.contains(supplier) {
+            continue;
+        }
+        let mut closure = BTreeSet::new();
+        add_with_ancestors(db, *supplier, &mut closure);
+        if !closure.contains(&target) && order_subset(db, &closure).is_some() {
+            spare += count;
+        }
+    }
+    spare
+}
+
+/// Greedily look for a legal sequence that makes `target` impossible.
+fn strand_target(
+    db: &QuestDatabase,
+    dependents: &HashMap<QuestId, Vec<QuestId>>,
+    flow: &ItemFlow,
+    consumers: &[QuestId],
+    item: &ItemKey,
+    target: QuestId,
+) -> Option<Softlock> {
+    let after_target = descendants(dependents, target);
+    let mut chosen: BTreeSet<QuestId> = BTreeSet::new();
+    add_with_ancestors(db, target, &mut chosen);
+    chosen.remove(&target);
+    let mut candidates: Vec<QuestId> = consumers
+        .iter()
+        .copied()
+        .filter(|c| *c != target && !after_target.contains(c))
+        .collect();
+
+    loop {
+        // the target always goes last; its mandatory ancestors are in `chosen`
+        let order = order_subset(db, &chosen)?;
+        // if the ancestors alone already fail, the failing ancestor is
+        // reported as its own target instead
+        let current = flow.simulate(&order, 0).ok()?;
+        // suppliers outside the sequence can still be completed before the
+        // target, so their rewards count towards it
+        let spare = spare_supply(db, flow, &chosen, &after_target, target);
+        if let Err((_, needed, available)) = flow.simulate(&[target], current + spare) {
+            // without a consumer in the sequence the item is simply short,
+            // which is not a softlock
+            if !order.iter().any(|q| flow.consumes(q)) {
+                return None;
+            }
+            return Some(Softlock {
+                item: item.clone(),
+                stranded_quest: target,
+                needed,
+                available,
+                sequence: order,
+            });
+        }
+
+        // pick the consumer whose addition leaves the smallest legal budget
+        let mut best: Option<(i64, QuestId, BTreeSet<QuestId>)> = None;
+        for c in &candidates {
+            let mut extended = chosen.clone();
+            add_with_ancestors(db, *c, &mut extended);
+            if extended.contains(&target) {
+                continue;
+            }
+            let Some(order) = order_subset(db, &extended) else {
+                continue;
+            };
+            if let Ok(budget) = flow.simulate(&order, 0)
+                && budget < current
+                && best.as_ref().is_none_or(|(b, _, _)| budget < *b)
+            {
+                best = Some((budget, *c, extended));
+            }
+        }
+        let (_, picked, extended) = best?;
+        candidates.retain(|c| *c != picked && !extended.contains(c));
+        chosen = extended;
+    }
+}
@@ -0,0 +1,231 @@
+//! Reading quest data straight from modpack zip archives.
+//!
+//! Client and server packs ship `config/betterquesting/DefaultQuests` somewhere
+//! inside a zip, usually below a pack-specific top-level folder.
+//! `ZipDataSource` loads the JSON files under any `DefaultQuests` folder into
+//! memory and serves them through `QuestDataSource`; `find_default_quests`
+//! locates the folder so callers do not need to know the archive layout.
+//! With the `memmap2` feature, `MappedZipDataSource` serves the same files
+//! from a memory-mapped archive, decompressing each one only when it is read.
+use crate::db::{QuestDataSource, parse_default_quests_dir_from_source};
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+const DEFAULT_QUESTS: &str = "DefaultQuests";
+
+/// In-memory view of the quest files of a zip archive.
+#[derive(Debug, Clone, Default)]
+pub struct ZipDataSource {
+    files: BTreeMap<String, String>,
+    dirs: BTreeSet<String>,
+}
+
+fn archive_err(e: zip::result::ZipError) -> ParseError {
+    ParseError::Archive(e.to_string())
+}
+
+impl ZipDataSource {
+    /// Read the JSON files below any `DefaultQuests` folder of the archive.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(reader).map_err(archive_err)?;
+        let mut source = ZipDataSource::default();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).map_err(archive_err)?;
+            let name = file.name().trim_end_matches('/').to_string();
+            if file.is_dir() || !is_quest_file(&name) {
+                continue;
+            }
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            insert_parents(&mut source.dirs, &name);
+            source.files.insert(name, text);
+        }
+        Ok(source)
+    }
+
+    /// Read an archive held in memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_reader(std::io::Cursor::new(bytes))
+    }
+
+    /// Open a zip file from disk.
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Path of the shallowest `DefaultQuests` folder in the archive.
+    pub fn find_default_quests(&self) -> Option<String> {
+        shallowest_default_quests(&self.dirs)
+    }
+
+    /// Parse the archive's `DefaultQuests` folder.
+    pub fn parse(&self) -> Result<QuestDatabase> {
+        parse_default_quests(self, self.find_default_quests())
+    }
+}
+
+/// Whether the archive entry `name` is a JSON file below a `DefaultQuests`
+/// folder.
+fn is_quest_file(name: &str) -> bool {
+    name.ends_with(".json") && name.split('/').any(|part| part == DEFAULT_QUESTS)
+}
+
+/// Record every folder above the archive entry `name`.
+fn insert_parents(dirs: &mut BTreeSet<String>, name: &str) {
+    let mut parent = name;
+    while let Some(pos) = parent.rfind('/') {
+        parent = &parent[..pos];
+        dirs.insert(parent.to_string());
+    }
+}
+
+fn shallowest_default_quests(dirs: &BTreeSet<String>) -> Option<String> {
+    dirs.iter()
+        .filter(|d| d.rsplit('/').next() == Some(DEFAULT_QUESTS))
+        .min_by_key(|d| (d.matches('/').count(), d.len()))
+        .cloned()
+}
+
+fn parse_default_quests(
+    source: &dyn QuestDataSource,
+    root: Option<String>,
+) -> Result<QuestDatabase> {
+    let root =
+        root.ok_or_else(|| ParseError::Archive("no DefaultQuests folder in archive".to_string()))?;
+    parse_default_quests_dir_from_source(source, &root)
+}
+
+/// Names of the files and folders directly inside `path`.
+fn children<'a>(
+    files: impl Iterator<Item = &'a String>,
+    dirs: &'a BTreeSet<String>,
+    path: &str,
+) -> Vec<String> {
+    let prefix = format!("{}/", path.trim_end_matches('/'));
+    let children: BTreeSet<String> = files
+        .chain(dirs.iter())
+        .filter_map(|p| p.strip_prefix(&prefix))
+        .filter(|rest| !rest.is_empty() && !rest.contains('/'))
+        .map(str::to_string)
+        .collect();
+    children.into_iter().collect()
+}
+
+fn no_such_file(path: &str) -> ParseError {
+    ParseError::Archive(format!("no such file in archive: {}", path))
+}
+
+impl QuestDataSource for ZipDataSource {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        Ok(children(self.files.keys(), &self.dirs, path))
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        self.dirs.contains(path.trim_end_matches('/'))
+    }
+    fn is_file(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| no_such_file(path))
+    }
+}
+
+/// A memory map shared between the clones of a `MappedZipDataSource`.
+#[cfg(feature = "memmap2")]
+#[derive(Debug, Clone)]
+struct SharedMap(std::sync::Arc<memmap2::Mmap>);
+
+#[cfg(feature = "memmap2")]
+impl AsRef<[u8]> for SharedMap {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The quest files of a memory-mapped zip archive.
+///
+/// Opening only reads the archive's central directory; each file is
+/// decompressed from the map when it is read, so the archive is never copied
+/// into memory as a whole. See `crate::mmap` for the caveats of mapping.
+#[cfg(feature = "memmap2")]
+#[derive(Debug, Clone)]
+pub struct MappedZipDataSource {
+    archive: zip::ZipArchive<std::io::Cursor<SharedMap>>,
+    files: BTreeSet<String>,
+    dirs: BTreeSet<String>,
+}
+
+#[cfg(feature = "memmap2")]
+impl MappedZipDataSource {
+    /// Map the zip file at `path` read-only and index its quest files.
+    pub fn open(path: &Path) -> Result<Self> {
+        let map = SharedMap(std::sync::Arc::new(crate::mmap::map_file(path)?));
+        let archive = zip::ZipArchive::new(std::io::Cursor::new(map)).map_err(archive_err)?;
+        let mut files = BTreeSet::new();
+        let mut dirs = BTreeSet::new();
+        for name in archive.file_names() {
+            if name.ends_with('/') || !is_quest_file(name) {
+                continue;
+            }
+            insert_parents(&mut dirs, name);
+            files.insert(name.to_string());
+        }
+        Ok(MappedZipDataSource {
+            archive,
+            files,
+            dirs,
+        })
+    }
+
+    /// Path of the shallowest `DefaultQuests` folder in the archive.
+    pub fn find_default_quests(&self) -> Option<String> {
+        shallowest_default_quests(&self.dirs)
+    }
+
+    /// Parse the archive's `DefaultQuests` folder.
+    pub fn parse(&self) -> Result<QuestDatabase> {
+        parse_default_quests(self, self.find_default_quests())
+    }
+}
+
+#[cfg(feature = "memmap2")]
+impl QuestDataSource for MappedZipDataSource {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        Ok(children(self.files.iter(), &self.dirs, path))
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        self.dirs.contains(path.trim_end_matches('/'))
+    }
+    fn is_file(&self, path: &str) -> bool {
+        self.files.contains(path)
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        if !self.files.contains(path) {
+            return Err(no_such_file(path));
+        }
+        // clones share the map and the parsed central directory
+        let mut archive = self.archive.clone();
+        let mut file = archive.by_name(path).map_err(archive_err)?;
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+/// Parse the quest database of a modpack zip on disk.
+pub fn parse_pack_zip(path: &Path) -> Result<QuestDatabase> {
+    ZipDataSource::open(path)?.parse()
+}
+
+/// Like `parse_pack_zip`, reading the archive through a memory map (see
+/// `MappedZipDataSource`).
+#[cfg(feature = "memmap2")]
+pub fn parse_pack_zip_mmap(path: &Path) -> Result<QuestDatabase> {
+    MappedZipDataSource::open(path)?.parse()
+}
@@ -0,0 +1,468 @@
+//! Arena-backed, read-only copy of the quest model.
+//!
+//! Short-lived analysis runs over big packs spend a noticeable share of their
+//! time in the allocator: every quest owns strings, vectors and `extra` hash
+//! maps, and dropping the database frees each of them individually. The types
+//! here mirror `crate::model` with every string, list and map allocated from
+//! one `bumpalo::Bump`, so building is a series of pointer bumps and dropping
+//! the arena releases everything at once.
+//!
+//! The arena types hold only references and `Copy` data and never need
+//! `Drop`. Lists are slices; quests are sorted by id and maps by key, so
+//! lookups are binary searches. Quest settings are not copied.
+//!
+//! `load_default_quests_dir` parses a pack straight into the arena: each
+//! quest file is parsed and copied before the next one is read, so the heap
+//! never holds more than one quest at a time. `ArenaQuestDatabase::from_db`
+//! copies a database that is already loaded.
+use crate::db::{
+    FsDataSource, ParseOptions, QuestDataSource, parse_quest_file,
+    parse_questlines_dir_from_source, quest_file_paths, resolve_questline_refs,
+};
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use bumpalo::Bump;
+use serde_json::{Number, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// JSON value with strings, arrays and objects in the arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+    /// JSON `null`.
+    Null,
+    /// JSON boolean.
+    Bool(bool),
+    /// JSON number.
+    Number(&'a Number),
+    /// JSON string.
+    String(&'a str),
+    /// JSON array.
+    Array(&'a [ArenaValue<'a>]),
+    /// JSON object.
+    Object(ArenaMap<'a>),
+}
+
+impl ArenaValue<'_> {
+    /// Copy back into a heap `serde_json::Value`.
+    pub fn to_value(&self) -> Value {
+        match self {
+            ArenaValue::Null => Value::Null,
+            ArenaValue::Bool(b) => Value::Bool(*b),
+            ArenaValue::Number(n) => Value::Number((*n).clone()),
+            ArenaValue::String(s) => Value::String(s.to_string()),
+            ArenaValue::Array(a) => Value::Array(a.iter().map(ArenaValue::to_value).collect()),
+            ArenaValue::Object(m) => Value::Object(
+                m.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// String-keyed map stored as a slice sorted by key.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ArenaMap<'a>(&'a [(&'a str, ArenaValue<'a>)]);
+
+impl<'a> ArenaMap<'a> {
+    /// Value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&'a ArenaValue<'a>> {
+        let i = self.0.binary_search_by(|(k, _)| (*k).cmp(key)).ok()?;
+        Some(&self.0[i].1)
+    }
+
+    /// Entries in key order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a (&'a str, ArenaValue<'a>)> {
+        self.0.iter()
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true when the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Arena counterpart of `ItemStack`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaItem<'a> {
+    /// Item identifier (namespaced id like "minecraft:stone").
+    pub id: &'a str,
+    /// Optional damage / meta value.
+    pub damage: Option<i32>,
+    /// Optional stack count.
+    pub count: Option<i32>,
+    /// Ore dictionary name if present.
+    pub oredict: Option<&'a str>,
+    /// Any additional, unmodeled NBT/json data.
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `Task`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaTask<'a> {
+    /// Optional index within the containing quest.
+    pub index: Option<usize>,
+    /// Canonical identifier for the task implementation.
+    pub task_id: &'a str,
+    /// Items required by this task (if applicable).
+    pub required_items: &'a [ArenaItem<'a>],
+    /// Whether NBT is ignored when matching items.
+    pub ignore_nbt: Option<bool>,
+    /// Whether partial item matches count.
+    pub partial_match: Option<bool>,
+    /// Whether items are consumed without a submit click.
+    pub auto_consume: Option<bool>,
+    /// Whether submitted items are consumed.
+    pub consume: Option<bool>,
+    /// Whether items are detected across the party.
+    pub group_detect: Option<bool>,
+    /// Task-specific or unknown fields.
+    pub options: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `Reward`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaReward<'a> {
+    pub index: Option<usize>,
+    pub reward_id: &'a str,
+    pub items: &'a [ArenaItem<'a>],
+    pub choices: &'a [ArenaItem<'a>],
+    pub ignore_disabled: Option<bool>,
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `QuestProperties`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaProperties<'a> {
+    pub name: &'a str,
+    pub desc: Option<&'a str>,
+    pub desc_pages: &'a [&'a str],
+    pub icon: Option<ArenaItem<'a>>,
+    pub is_main: Option<bool>,
+    pub is_silent: Option<bool>,
+    pub auto_claim: Option<bool>,
+    pub global_share: Option<bool>,
+    pub is_global: Option<bool>,
+    pub locked_progress: Option<i32>,
+    pub repeat_time: Option<i32>,
+    pub repeat_relative: Option<bool>,
+    pub simultaneous: Option<bool>,
+    pub party_single_reward: Option<bool>,
+    pub quest_logic: Option<&'a str>,
+    pub task_logic: Option<&'a str>,
+    pub visibility: Option<&'a str>,
+    pub snd_complete: Option<&'a str>,
+    pub snd_update: Option<&'a str>,
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `Quest`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaQuest<'a> {
+    pub id: QuestId,
+    pub properties: Option<&'a ArenaProperties<'a>>,
+    pub tasks: &'a [ArenaTask<'a>],
+    pub rewards: &'a [ArenaReward<'a>],
+    pub prerequisites: &'a [QuestId],
+    pub required_prerequisites: &'a [QuestId],
+    pub optional_prerequisites: &'a [QuestId],
+}
+
+impl<'a> ArenaQuest<'a> {
+    /// Quest name, if the quest has properties.
+    pub fn name(&self) -> Option<&'a str> {
+        self.properties.map(|p| p.name)
+    }
+}
+
+/// Arena counterpart of `QuestLineEntry`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaQuestLineEntry<'a> {
+    pub index: Option<usize>,
+    pub quest_id: QuestId,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub size_x: Option<i32>,
+    pub size_y: Option<i32>,
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `QuestLineProperties`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaLineProperties<'a> {
+    pub name: &'a str,
+    pub desc: Option<&'a str>,
+    pub desc_pages: &'a [&'a str],
+    pub icon: Option<ArenaItem<'a>>,
+    pub visibility: Option<&'a str>,
+    pub bg_image: Option<&'a str>,
+    pub bg_size: Option<i32>,
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `QuestLine`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaQuestLine<'a> {
+    pub id: QuestId,
+    pub properties: Option<&'a ArenaLineProperties<'a>>,
+    pub entries: &'a [ArenaQuestLineEntry<'a>],
+    pub extra: ArenaMap<'a>,
+}
+
+/// Arena counterpart of `QuestDatabase` (without settings).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaQuestDatabase<'a> {
+    /// Quests sorted by id.
+    pub quests: &'a [ArenaQuest<'a>],
+    /// Questlines in `questline_order`, then any unordered lines by id.
+    pub questlines: &'a [ArenaQuestLine<'a>],
+}
+
+/// Copies heap model values into one arena.
+struct Builder<'a> {
+    bump: &'a Bump,
+}
+
+impl<'a> Builder<'a> {
+    fn str(&self, s: &str) -> &'a str {
+        self.bump.alloc_str(s)
+    }
+
+    fn opt_str(&self, s: &Option<String>) -> Option<&'a str> {
+        s.as_deref().map(|s| self.str(s))
+    }
+
+    fn value(&self, v: &Value) -> ArenaValue<'a> {
+        match v {
+            Value::Null => ArenaValue::Null,
+            Value::Bool(b) => ArenaValue::Bool(*b),
+            Value::Number(n) => ArenaValue::Number(self.bump.alloc(n.clone())),
+            Value::String(s) => ArenaValue::String(self.str(s)),
+            Value::Array(a) => ArenaValue::Array(
+                self.bump
+                    .alloc_slice_fill_iter(a.iter().map(|v| self.value(v))),
+            ),
+            Value::Object(m) => {
+                let entries = self
+                    .bump
+                    .alloc_slice_fill_iter(m.iter().map(|(k, v)| (self.str(k), self.value(v))));
+                entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                ArenaValue::Object(ArenaMap(entries))
+            }
+        }
+    }
+
+    fn map(&self, m: &HashMap<String, Value>) -> ArenaMap<'a> {
+        let entries = self
+            .bump
+            .alloc_slice_fill_iter(m.iter().map(|(k, v)| (self.str(k), self.value(v))));
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        ArenaMap(entries)
+    }
+
+    fn ids(&self, ids: &[QuestId]) -> &'a [QuestId] {
+        self.bump.alloc_slice_copy(ids)
+    }
+
+    fn item(&self, i: &ItemStack) -> ArenaItem<'a> {
+        ArenaItem {
+            id: self.str(&i.id),
+            damage: i.damage,
+            count: i.count,
+            oredict: self.opt_str(&i.oredict),
+            extra: self.map(&i.extra),
+        }
+    }
+
+    fn items(&self, items: &[ItemStack]) -> &'a [ArenaItem<'a>] {
+        self.bump
+            .alloc_slice_fill_iter(items.iter().map(|i| self.item(i)))
+    }
+
+    fn properties(&self, p: &QuestProperties) -> &'a ArenaProperties<'a> {
+        self.bump.alloc(ArenaProperties {
+            name: self.str(&p.name),
+            desc: self.opt_str(&p.desc),
+            desc_pages: self
+                .bump
+                .alloc_slice_fill_iter(p.desc_pages.iter().map(|s| self.str(s))),
+            icon: p.icon.as_ref().map(|i| self.item(i)),
+            is_main: p.is_main,
+            is_silent: p.is_silent,
+            auto_claim: p.auto_claim,
+            global_share: p.global_share,
+            is_global: p.is_global,
+            locked_progress: p.locked_progress,
+            repeat_time: p.repeat_time,
+            repeat_relative: p.repeat_relative,
+            simultaneous: p.simultaneous,
+            party_single_reward: p.party_single_reward,
+            quest_logic: self.opt_str(&p.quest_logic),
+            task_logic: self.opt_str(&p.task_logic),
+            visibility: self.opt_str(&p.visibility),
+            snd_complete: self.opt_str(&p.snd_complete),
+            snd_update: self.opt_str(&p.snd_update),
+            extra: self.map(&p.extra),
+        })
+    }
+
+    fn line_properties(&self, p: &QuestLineProperties) -> &'a ArenaLineProperties<'a> {
+        self.bump.alloc(ArenaLineProperties {
+            name: self.str(&p.name),
+            desc: self.opt_str(&p.desc),
+            desc_pages: self
+                .bump
+                .alloc_slice_fill_iter(p.desc_pages.iter().map(|s| self.str(s))),
+            icon: p.icon.as_ref().map(|i| self.item(i)),
+            visibility: self.opt_str(&p.visibility),
+            bg_image: self.opt_str(&p.bg_image),
+            bg_size: p.bg_size,
+            extra: self.map(&p.extra),
+        })
+    }
+
+    fn quest(&self, q: &Quest) -> ArenaQuest<'a> {
+        ArenaQuest {
+            id: q.id,
+            properties: q.properties.as_ref().map(|p| self.properties(p)),
+            tasks: self
+                .bump
+                .alloc_slice_fill_iter(q.tasks.iter().map(|t| ArenaTask {
+                    index: t.index,
+                    task_id: self.str(&t.task_id),
+                    required_items: self.items(&t.required_items),
+                    ignore_nbt: t.ignore_nbt,
+                    partial_match: t.partial_match,
+                    auto_consume: t.auto_consume,
+                    consume: t.consume,
+                    group_detect: t.group_detect,
+                    options: self.map(&t.options),
+                })),
+            rewards: self
+                .bump
+                .alloc_slice_fill_iter(q.rewards.iter().map(|r| ArenaReward {
+                    index: r.index,
+                    reward_id: self.str(&r.reward_id),
+                    items: self.items(&r.items),
+                    choices: self.items(&r.choices),
+                    ignore_disabled: r.ignore_disabled,
+                    extra: self.map(&r.extra),
+                })),
+            prerequisites: self.ids(&q.prerequisites),
+            required_prerequisites: self.ids(&q.required_prerequisites),
+            optional_prerequisites: self.ids(&q.optional_prerequisites),
+        }
+    }
+
+    /// Questlines in `order`, then any unordered lines by id.
+    fn questlines(
+        &self,
+        lines: &HashMap<QuestId, QuestLine>,
+        order: &[QuestId],
+    ) -> &'a [ArenaQuestLine<'a>] {
+        let mut line_ids: Vec<QuestId> = order
+            .iter()
+            .filter(|id| lines.contains_key(id))
+            .copied()
+            .collect();
+        let mut unordered: Vec<QuestId> = lines
+            .keys()
+            .filter(|id| !line_ids.contains(id))
+            .copied()
+            .collect();
+        unordered.sort();
+        line_ids.extend(unordered);
+        let ordered: Vec<&QuestLine> = line_ids.iter().filter_map(|id| lines.get(id)).collect();
+        self.bump
+            .alloc_slice_fill_iter(ordered.into_iter().map(|l| self.questline(l)))
+    }
+
+    fn questline(&self, l: &QuestLine) -> ArenaQuestLine<'a> {
+        ArenaQuestLine {
+            id: l.id,
+            properties: l.properties.as_ref().map(|p| self.line_properties(p)),
+            entries: self.bump.alloc_slice_fill_iter(l.entries.iter().map(|e| {
+                ArenaQuestLineEntry {
+                    index: e.index,
+                    quest_id: e.quest_id,
+                    x: e.x,
+                    y: e.y,
+                    size_x: e.size_x,
+                    size_y: e.size_y,
+                    extra: self.map(&e.extra),
+                }
+            })),
+            extra: self.map(&l.extra),
+        }
+    }
+}
+
+impl<'a> ArenaQuestDatabase<'a> {
+    /// Copy `db` into `bump`.
+    pub fn from_db(bump: &'a Bump, db: &QuestDatabase) -> Self {
+        let b = Builder { bump };
+        let quests = bump.alloc_slice_fill_iter(db.quests.values().map(|q| b.quest(q)));
+        quests.sort_unstable_by_key(|q| q.id);
+        let questlines = b.questlines(&db.questlines, &db.questline_order);
+        ArenaQuestDatabase { quests, questlines }
+    }
+
+    /// Quest with the given id.
+    pub fn quest(&self, id: QuestId) -> Option<&'a ArenaQuest<'a>> {
+        let i = self.quests.binary_search_by_key(&id, |q| q.id).ok()?;
+        Some(&self.quests[i])
+    }
+
+    /// Questline with the given id.
+    pub fn questline(&self, id: QuestId) -> Option<&'a ArenaQuestLine<'a>> {
+        self.questlines.iter().find(|l| l.id == id)
+    }
+}
+
+/// Parse a DefaultQuests folder on disk into `bump`.
+pub fn load_default_quests_dir<'a>(bump: &'a Bump, root: &Path) -> Result<ArenaQuestDatabase<'a>> {
+    load_default_quests_dir_from_source(bump, &FsDataSource, &root.to_string_lossy())
+}
+
+/// Parse the DefaultQuests folder at `root` of `source` into `bump`, one
+/// quest file at a time. Fails like `db::parse_default_quests_dir_from_source`
+/// on unreadable files, duplicate ids and questline entries naming missing
+/// quests. Settings are not read.
+pub fn load_default_quests_dir_from_source<'a>(
+    bump: &'a Bump,
+    source: &dyn QuestDataSource,
+    root: &str,
+) -> Result<ArenaQuestDatabase<'a>> {
+    if !source.is_dir(root) {
+        return Err(ParseError::InvalidFormat(format!("not a dir: {}", root)));
+    }
+    let b = Builder { bump };
+    let mut seen = HashSet::new();
+    let mut quests = Vec::new();
+    for path in quest_file_paths(source, root)? {
+        let quest = parse_quest_file(source, &path)?;
+        if !seen.insert(quest.id) {
+            return Err(ParseError::DuplicateQuestId(path));
+        }
+        quests.push(b.quest(&quest));
+    }
+    quests.sort_unstable_by_key(|q| q.id);
+    let quests: &'a [ArenaQuest<'a>] = bump.alloc_slice_copy(&quests);
+
+    let (mut lines, order) =
+        parse_questlines_dir_from_source(source, &format!("{}/QuestLines", root), &mut Err)?;
+    resolve_questline_refs(
+        &mut lines,
+        |id| quests.binary_search_by_key(id, |q| q.id).is_ok(),
+        &ParseOptions::default(),
+    )?;
+    let questlines = b.questlines(&lines, &order);
+    Ok(ArenaQuestDatabase { quests, questlines })
+}
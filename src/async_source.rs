@@ -0,0 +1,146 @@
+//! Parsing from asynchronous storage.
+//!
+//! `AsyncQuestDataSource` is the async counterpart of `db::QuestDataSource`,
+//! for services that keep packs in object storage or behind HTTP and must not
+//! block their executor on I/O. Its futures are `Send`, so parsing can run in
+//! spawned tokio tasks.
+//!
+//! `parse_default_quests_dir_from_source_async` first fetches the files the
+//! parse reads (the settings file, `Quests/*.json` and every questline
+//! folder's `.json` files) into a `db::MemoryDataSource`, awaiting the source
+//! once per listing and file, and then runs the ordinary parser over that
+//! copy; parsing itself does no I/O and so stays synchronous.
+//! `AsyncFsDataSource` reads the local filesystem through `tokio::fs`.
+use crate::db::{
+    MemoryDataSource, ParseOptions, ParseOutcome, parse_default_quests_dir_from_source,
+    parse_default_quests_dir_with_options,
+};
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use std::future::{Future, ready};
+
+/// Async file and directory access for quest parsing. Paths are
+/// `/`-separated, as for `db::QuestDataSource`.
+pub trait AsyncQuestDataSource {
+    /// List entries in a directory (returns file/dir names, not full paths).
+    fn list_dir(&self, path: &str) -> impl Future<Output = Result<Vec<String>>> + Send;
+    /// Returns true if the path is a directory.
+    fn is_dir(&self, path: &str) -> impl Future<Output = bool> + Send;
+    /// Returns true if the path is a file.
+    fn is_file(&self, path: &str) -> impl Future<Output = bool> + Send;
+    /// Reads the file at path to a string.
+    fn read_to_string(&self, path: &str) -> impl Future<Output = Result<String>> + Send;
+}
+
+/// `AsyncQuestDataSource` backed by the local filesystem via `tokio::fs`;
+/// needs a tokio runtime.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncFsDataSource;
+
+impl AsyncQuestDataSource for AsyncFsDataSource {
+    fn list_dir(&self, path: &str) -> impl Future<Output = Result<Vec<String>>> + Send {
+        let path = path.to_string();
+        async move {
+            let mut names = Vec::new();
+            let mut entries = tokio::fs::read_dir(&path).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+            names.sort();
+            Ok(names)
+        }
+    }
+    fn is_dir(&self, path: &str) -> impl Future<Output = bool> + Send {
+        let path = path.to_string();
+        async move { tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_dir()) }
+    }
+    fn is_file(&self, path: &str) -> impl Future<Output = bool> + Send {
+        let path = path.to_string();
+        async move { tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_file()) }
+    }
+    fn read_to_string(&self, path: &str) -> impl Future<Output = Result<String>> + Send {
+        let path = path.to_string();
+        async move { Ok(tokio::fs::read_to_string(&path).await?) }
+    }
+}
+
+/// Files in memory are ready at once.
+impl AsyncQuestDataSource for MemoryDataSource {
+    fn list_dir(&self, path: &str) -> impl Future<Output = Result<Vec<String>>> + Send {
+        ready(crate::db::QuestDataSource::list_dir(self, path))
+    }
+    fn is_dir(&self, path: &str) -> impl Future<Output = bool> + Send {
+        ready(crate::db::QuestDataSource::is_dir(self, path))
+    }
+    fn is_file(&self, path: &str) -> impl Future<Output = bool> + Send {
+        ready(crate::db::QuestDataSource::is_file(self, path))
+    }
+    fn read_to_string(&self, path: &str) -> impl Future<Output = Result<String>> + Send {
+        ready(crate::db::QuestDataSource::read_to_string(self, path))
+    }
+}
+
+/// Copy the `.json` files of `dir` (when it is a directory) into `files`.
+async fn fetch_json_files<S: AsyncQuestDataSource>(
+    source: &S,
+    dir: &str,
+    files: &mut MemoryDataSource,
+) -> Result<()> {
+    if !source.is_dir(dir).await {
+        return Ok(());
+    }
+    for entry in source.list_dir(dir).await? {
+        let path = format!("{}/{}", dir, entry);
+        if path.ends_with(".json") && source.is_file(&path).await {
+            let text = source.read_to_string(&path).await?;
+            files.insert(path, text);
+        }
+    }
+    Ok(())
+}
+
+/// Fetch every file the parse of `root` reads.
+async fn fetch_pack<S: AsyncQuestDataSource>(source: &S, root: &str) -> Result<MemoryDataSource> {
+    if !source.is_dir(root).await {
+        return Err(ParseError::InvalidFormat(format!("not a dir: {}", root)));
+    }
+    let mut files = MemoryDataSource::new();
+    // The parser checks the root itself, so it must exist in the copy.
+    crate::db::QuestDataSink::create_dir_all(&mut files, root)?;
+    for name in ["QuestSettings.json", "QuestSettings"] {
+        let path = format!("{}/{}", root, name);
+        if source.is_file(&path).await {
+            let text = source.read_to_string(&path).await?;
+            files.insert(path, text);
+            break;
+        }
+    }
+    fetch_json_files(source, &format!("{}/Quests", root), &mut files).await?;
+    let qlines_dir = format!("{}/QuestLines", root);
+    if source.is_dir(&qlines_dir).await {
+        for entry in source.list_dir(&qlines_dir).await? {
+            let line_dir = format!("{}/{}", qlines_dir, entry);
+            fetch_json_files(source, &line_dir, &mut files).await?;
+        }
+    }
+    Ok(files)
+}
+
+/// Async `db::parse_default_quests_dir_from_source`.
+pub async fn parse_default_quests_dir_from_source_async<S: AsyncQuestDataSource>(
+    source: &S,
+    root: &str,
+) -> Result<QuestDatabase> {
+    let files = fetch_pack(source, root).await?;
+    parse_default_quests_dir_from_source(&files, root)
+}
+
+/// Async `db::parse_default_quests_dir_with_options`.
+pub async fn parse_default_quests_dir_with_options_async<S: AsyncQuestDataSource>(
+    source: &S,
+    root: &str,
+    options: &ParseOptions,
+) -> Result<ParseOutcome> {
+    let files = fetch_pack(source, root).await?;
+    parse_default_quests_dir_with_options(&files, root, options)
+}
@@ -0,0 +1,169 @@
+//! Batch statistics over many packs.
+//!
+//! `analyze_packs` parses each pack (a DefaultQuests folder, or a pack zip
+//! with the `zip` feature) and summarizes its size and the task/reward types
+//! it uses. `aggregate` combines the per-pack numbers into ecosystem-wide
+//! averages and the task types most packs rely on. Averages are rounded to
+//! `export::FLOAT_PRECISION` decimals.
+use crate::error::Result;
+use crate::export::{FLOAT_PRECISION, round_float};
+use crate::model::QuestDatabase;
+use crate::stats::DepthStats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Size and composition of one pack, as used for batch comparisons; for the
+/// single-pack report see `report::PackStats`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackSummary {
+    /// Path the pack was read from.
+    pub path: PathBuf,
+    /// Quests in the pack.
+    pub quests: usize,
+    /// Questlines in the pack.
+    pub questlines: usize,
+    /// Tasks over all quests.
+    pub tasks: usize,
+    /// Rewards over all quests.
+    pub rewards: usize,
+    /// Prerequisite links (required and optional).
+    pub prerequisite_links: usize,
+    /// Quests with a repeat time set.
+    pub repeatable_quests: usize,
+    /// Number of tasks per task type id.
+    pub task_types: BTreeMap<String, usize>,
+    /// Number of rewards per reward type id.
+    pub reward_types: BTreeMap<String, usize>,
+    /// Prerequisite depth distribution.
+    pub depth: DepthStats,
+}
+
+/// Numbers across several packs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchSummary {
+    /// Packs included.
+    pub packs: usize,
+    /// Mean number of quests per pack.
+    pub avg_quests: f64,
+    /// Mean number of questlines per pack.
+    pub avg_questlines: f64,
+    /// Tasks per quest, over the quests of all packs.
+    pub avg_tasks_per_quest: f64,
+    /// Prerequisite links per quest, over the quests of all packs.
+    pub avg_prerequisites_per_quest: f64,
+    /// Task types with the number of packs using them, most common first
+    /// (ties by name).
+    pub common_task_types: Vec<(String, usize)>,
+    /// Reward types with the number of packs using them, most common first.
+    pub common_reward_types: Vec<(String, usize)>,
+}
+
+/// Summary of one parsed pack.
+pub fn pack_summary(path: &Path, db: &QuestDatabase) -> PackSummary {
+    let mut stats = PackSummary {
+        path: path.to_path_buf(),
+        quests: db.quests.len(),
+        questlines: db.questlines.len(),
+        tasks: 0,
+        rewards: 0,
+        prerequisite_links: 0,
+        repeatable_quests: 0,
+        task_types: BTreeMap::new(),
+        reward_types: BTreeMap::new(),
+        depth: DepthStats::of(db),
+    };
+    for quest in db.quests.values() {
+        stats.tasks += quest.tasks.len();
+        stats.rewards += quest.rewards.len();
+        stats.prerequisite_links +=
+            quest.required_prerequisites.len() + quest.optional_prerequisites.len();
+        if quest
+            .properties
+            .as_ref()
+            .and_then(|p| p.repeat_time)
+            .is_some_and(|t| t >= 0)
+        {
+            stats.repeatable_quests += 1;
+        }
+        for task in &quest.tasks {
+            *stats.task_types.entry(task.task_id.clone()).or_default() += 1;
+        }
+        for reward in &quest.rewards {
+            *stats
+                .reward_types
+                .entry(reward.reward_id.clone())
+                .or_default() += 1;
+        }
+    }
+    stats
+}
+
+/// Parse a pack from a DefaultQuests folder or, with the `zip` feature, a
+/// pack archive.
+pub fn load_pack(path: &Path) -> Result<QuestDatabase> {
+    if path.is_dir() {
+        return crate::db::parse_default_quests_dir(path);
+    }
+    #[cfg(feature = "zip")]
+    {
+        crate::archive::parse_pack_zip(path)
+    }
+    #[cfg(not(feature = "zip"))]
+    {
+        Err(crate::error::ParseError::InvalidFormat(format!(
+            "{} is not a directory (pack archives need the `zip` feature)",
+            path.display()
+        )))
+    }
+}
+
+/// Parse and summarize each pack. Results are in input order; a pack that
+/// fails to parse yields the `load_pack` error as is, without stopping the
+/// batch. This is why it returns `Vec<Result<PackSummary>>` rather than a
+/// plain list of summaries: callers decide whether to skip or abort on a
+/// broken pack.
+pub fn analyze_packs<P: AsRef<Path>>(paths: &[P]) -> Vec<Result<PackSummary>> {
+    paths
+        .iter()
+        .map(|p| {
+            let path = p.as_ref();
+            load_pack(path).map(|db| pack_summary(path, &db))
+        })
+        .collect()
+}
+
+fn by_pack_count(types: impl Iterator<Item = String>) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for t in types {
+        *counts.entry(t).or_default() += 1;
+    }
+    let mut out: Vec<(String, usize)> = counts.into_iter().collect();
+    out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    out
+}
+
+/// Combine per-pack statistics. Averages over zero packs are 0.
+pub fn aggregate(stats: &[PackSummary]) -> BatchSummary {
+    let n = stats.len();
+    let avg = |total: usize, over: usize| {
+        if over == 0 {
+            0.0
+        } else {
+            round_float(total as f64 / over as f64, FLOAT_PRECISION)
+        }
+    };
+    let sum = |f: fn(&PackSummary) -> usize| stats.iter().map(f).sum::<usize>();
+    let quests = sum(|s| s.quests);
+    BatchSummary {
+        packs: n,
+        avg_quests: avg(quests, n),
+        avg_questlines: avg(sum(|s| s.questlines), n),
+        avg_tasks_per_quest: avg(sum(|s| s.tasks), quests),
+        avg_prerequisites_per_quest: avg(sum(|s| s.prerequisite_links), quests),
+        common_task_types: by_pack_count(stats.iter().flat_map(|s| s.task_types.keys().cloned())),
+        common_reward_types: by_pack_count(
+            stats.iter().flat_map(|s| s.reward_types.keys().cloned()),
+        ),
+    }
+}
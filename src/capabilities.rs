@@ -0,0 +1,221 @@
+//! Machine-readable report of what this build can read.
+//!
+//! Several readers depend on cargo features (`.dat` files need `nbt`, zipped
+//! packs need `zip`), and packs from BetterQuesting forks use id encodings and
+//! layouts that older versions of the crate did not understand. Tools chaining
+//! this crate with others can call `capabilities` (or read `to_json`) up front
+//! and refuse a pack they cannot handle with `Capabilities::require`, instead
+//! of failing halfway through a parse.
+//!
+//! The report only grows: new formats, encodings and handlers are appended,
+//! and `CAPABILITIES_VERSION` is bumped when a field's meaning changes.
+use crate::error::{ParseError, Result};
+use crate::model::HUNT_TASK_ID;
+use crate::schema::{FieldScope, known_handlers};
+use serde::{Deserialize, Serialize};
+
+/// Version of the report layout.
+pub const CAPABILITIES_VERSION: u32 = 1;
+
+/// A way quest data can be stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputFormat {
+    /// A `DefaultQuests` folder of JSON files (`db::parse_default_quests_dir`).
+    DefaultQuestsDir,
+    /// One quest JSON file (`parser::parse_quest_from_file`).
+    QuestFile,
+    /// Binary NBT `QuestDatabase.dat` / `DefaultQuests.dat` (`dat`).
+    NbtDat,
+    /// A `DefaultQuests` folder inside a zip archive (`archive`).
+    Zip,
+    /// A quest table in CSV (`import::from_csv`).
+    Csv,
+    /// An FTB Quests `quests` folder of SNBT files (`import::ftb`).
+    FtbQuestsDir,
+    /// bq_standard loot chest groups in `DefaultLoot.json` (`default_loot`).
+    DefaultLoot,
+}
+
+impl InputFormat {
+    /// Every format, in report order.
+    pub const ALL: [InputFormat; 7] = [
+        InputFormat::DefaultQuestsDir,
+        InputFormat::QuestFile,
+        InputFormat::NbtDat,
+        InputFormat::Zip,
+        InputFormat::Csv,
+        InputFormat::FtbQuestsDir,
+        InputFormat::DefaultLoot,
+    ];
+
+    /// The cargo feature the format needs, if any.
+    pub fn feature(self) -> Option<&'static str> {
+        match self {
+            InputFormat::NbtDat => Some("nbt"),
+            InputFormat::Zip => Some("zip"),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name for messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            InputFormat::DefaultQuestsDir => "DefaultQuests folders",
+            InputFormat::QuestFile => "single quest files",
+            InputFormat::NbtDat => "NBT .dat files",
+            InputFormat::Zip => "zipped packs",
+            InputFormat::Csv => "CSV quest tables",
+            InputFormat::FtbQuestsDir => "FTB Quests folders",
+            InputFormat::DefaultLoot => "DefaultLoot.json loot tables",
+        }
+    }
+
+    /// Whether this build can read the format.
+    pub fn is_available(self) -> bool {
+        self.feature()
+            .is_none_or(|feature| FEATURES.contains(&(feature, true)))
+    }
+}
+
+/// Support for one `InputFormat` in this build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatSupport {
+    pub format: InputFormat,
+    pub available: bool,
+    /// Cargo feature enabling the format, if it needs one.
+    pub feature: Option<String>,
+}
+
+/// How a quest id can be written in a pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdEncoding {
+    /// `questIDHigh`/`questIDLow` holding 32-bit values (classic packs).
+    HighLow32,
+    /// `questIDHigh`/`questIDLow` holding full 64-bit longs (random 128-bit
+    /// ids, as in GTNH).
+    HighLow64,
+    /// A UUID string under `questID` (BetterQuesting 3).
+    UuidString,
+}
+
+/// A layout used by BetterQuesting forks that the parser understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForkExtension {
+    /// Descriptions split into pages (`desc` lists, `desc2`, `desc3`, ...).
+    PaginatedDescriptions,
+}
+
+/// What this build of the crate supports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    /// `CAPABILITIES_VERSION` of the report.
+    pub version: u32,
+    /// Version of the crate.
+    pub crate_version: String,
+    pub formats: Vec<FormatSupport>,
+    pub id_encodings: Vec<IdEncoding>,
+    pub fork_extensions: Vec<ForkExtension>,
+    /// Task type ids whose fields are known (checked by `schema`); other
+    /// types still parse, with their fields kept as raw options.
+    pub task_handlers: Vec<String>,
+    /// Task type ids with typed accessors on `model::Task`.
+    pub typed_task_handlers: Vec<String>,
+    /// Reward type ids whose fields are known.
+    pub reward_handlers: Vec<String>,
+    /// Cargo features this build was compiled with.
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    /// Whether `format` can be read.
+    pub fn supports(&self, format: InputFormat) -> bool {
+        self.formats
+            .iter()
+            .any(|f| f.format == format && f.available)
+    }
+
+    /// Fail with `ParseError::Unsupported`, naming the missing feature, when
+    /// `format` cannot be read.
+    pub fn require(&self, format: InputFormat) -> Result<()> {
+        if self.supports(format) {
+            return Ok(());
+        }
+        let reason = match format.feature() {
+            Some(feature) => format!(
+                "reading {} needs the `{}` feature, which this build lacks",
+                format.description(),
+                feature
+            ),
+            None => format!("reading {} is not supported", format.description()),
+        };
+        Err(ParseError::Unsupported(reason))
+    }
+
+    /// Whether the fields of task type `task_id` are known.
+    pub fn knows_task(&self, task_id: &str) -> bool {
+        self.task_handlers.iter().any(|t| t == task_id)
+    }
+
+    /// Whether the fields of reward type `reward_id` are known.
+    pub fn knows_reward(&self, reward_id: &str) -> bool {
+        self.reward_handlers.iter().any(|r| r == reward_id)
+    }
+}
+
+/// Every cargo feature with whether it is enabled.
+const FEATURES: &[(&str, bool)] = &[
+    ("async", cfg!(feature = "async")),
+    ("bumpalo", cfg!(feature = "bumpalo")),
+    ("chrono", cfg!(feature = "chrono")),
+    ("im", cfg!(feature = "im")),
+    ("lz4", cfg!(feature = "lz4")),
+    ("memmap2", cfg!(feature = "memmap2")),
+    ("nbt", cfg!(feature = "nbt")),
+    ("pdf", cfg!(feature = "pdf")),
+    ("roaring", cfg!(feature = "roaring")),
+    ("simd-json", cfg!(feature = "simd-json")),
+    ("site", cfg!(feature = "site")),
+    ("toml", cfg!(feature = "toml")),
+    ("tui", cfg!(feature = "tui")),
+    ("yaml", cfg!(feature = "yaml")),
+    ("zip", cfg!(feature = "zip")),
+];
+
+/// The capabilities of this build.
+pub fn capabilities() -> Capabilities {
+    let strings = |ids: Vec<&str>| ids.into_iter().map(String::from).collect();
+    Capabilities {
+        version: CAPABILITIES_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        formats: InputFormat::ALL
+            .into_iter()
+            .map(|format| FormatSupport {
+                format,
+                available: format.is_available(),
+                feature: format.feature().map(String::from),
+            })
+            .collect(),
+        id_encodings: vec![
+            IdEncoding::HighLow32,
+            IdEncoding::HighLow64,
+            IdEncoding::UuidString,
+        ],
+        fork_extensions: vec![ForkExtension::PaginatedDescriptions],
+        task_handlers: strings(known_handlers(FieldScope::Task)),
+        typed_task_handlers: vec![HUNT_TASK_ID.to_string()],
+        reward_handlers: strings(known_handlers(FieldScope::Reward)),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string())
+            .collect(),
+    }
+}
+
+/// `capabilities` as pretty JSON.
+pub fn to_json() -> Result<String> {
+    Ok(serde_json::to_string_pretty(&capabilities())?)
+}
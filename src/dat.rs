@@ -0,0 +1,237 @@
+//! Binary NBT quest databases (`QuestDatabase.dat`, `DefaultQuests.dat`).
+//!
+//! Server worlds keep quest data as (usually gzipped) binary NBT instead of
+//! the JSON export. `read_nbt` decodes such a file into the same tagged JSON
+//! BetterQuesting writes (`"name:8"`, numeric-keyed lists, ...), so the rest
+//! of the pipeline is unchanged: quests go through `parser::quest_from_json`
+//! and everything else through `nbt_norm::normalize_value`.
+//!
+//! The root compound holds the quests as a `questDatabase` list, the
+//! questlines as a `questLines` list (each line carrying its entries in a
+//! `quests` list) and the settings under `questSettings`.
+use crate::db::{
+    ParseOptions, questline_entry_from_map, questline_from_map, resolve_questline_refs,
+};
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+const TAG_END: u8 = 0;
+const TAG_COMPOUND: u8 = 10;
+
+/// Nesting limit, so a corrupt file fails instead of overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+/// Decode an NBT document, gzipped or not, into tagged JSON. The root tag
+/// must be a compound; its name is discarded.
+pub fn read_nbt<R: Read>(r: R) -> Result<Value> {
+    let mut r = BufReader::new(r);
+    if r.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        read_root(&mut flate2::bufread::GzDecoder::new(r))
+    } else {
+        read_root(&mut r)
+    }
+}
+
+fn read_root(r: &mut impl Read) -> Result<Value> {
+    let tag = read_u8(r)?;
+    if tag != TAG_COMPOUND {
+        return Err(ParseError::InvalidFormat(format!(
+            "NBT root is tag {}, expected a compound",
+            tag
+        )));
+    }
+    read_string(r)?;
+    read_payload(r, TAG_COMPOUND, 0)
+}
+
+fn read_exact<const N: usize>(r: &mut impl Read) -> Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u8(r: &mut impl Read) -> Result<u8> {
+    Ok(read_exact::<1>(r)?[0])
+}
+
+fn read_i32(r: &mut impl Read) -> Result<i32> {
+    Ok(i32::from_be_bytes(read_exact(r)?))
+}
+
+fn read_len(r: &mut impl Read) -> Result<usize> {
+    let n = read_i32(r)?;
+    usize::try_from(n).map_err(|_| ParseError::InvalidFormat(format!("negative NBT length {}", n)))
+}
+
+/// NBT strings are modified UTF-8; anything plain UTF-8 cannot represent
+/// (encoded NULs, surrogate pairs) is replaced.
+fn read_string(r: &mut impl Read) -> Result<String> {
+    let len = u16::from_be_bytes(read_exact(r)?) as usize;
+    let mut buf = vec![0u8; len];
+    r.read_exact(&mut buf)?;
+    Ok(String::from_utf8(buf)
+        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned()))
+}
+
+fn float(f: f64) -> Value {
+    Number::from_f64(f).map_or(Value::Null, Value::Number)
+}
+
+fn read_payload(r: &mut impl Read, tag: u8, depth: usize) -> Result<Value> {
+    if depth > MAX_DEPTH {
+        return Err(ParseError::InvalidFormat(
+            "NBT nested too deeply".to_string(),
+        ));
+    }
+    Ok(match tag {
+        1 => Value::from(read_u8(r)? as i8),
+        2 => Value::from(i16::from_be_bytes(read_exact(r)?)),
+        3 => Value::from(read_i32(r)?),
+        4 => Value::from(i64::from_be_bytes(read_exact(r)?)),
+        5 => float(f32::from_be_bytes(read_exact(r)?) as f64),
+        6 => float(f64::from_be_bytes(read_exact(r)?)),
+        7 => {
+            // read what is there instead of trusting the length up front, so
+            // a corrupt length cannot allocate gigabytes
+            let len = read_len(r)?;
+            let mut buf = Vec::new();
+            r.take(len as u64).read_to_end(&mut buf)?;
+            if buf.len() != len {
+                return Err(ParseError::InvalidFormat(format!(
+                    "NBT byte array of {} bytes ends after {}",
+                    len,
+                    buf.len()
+                )));
+            }
+            Value::Array(buf.into_iter().map(|b| Value::from(b as i8)).collect())
+        }
+        8 => Value::String(read_string(r)?),
+        9 => {
+            let elem = read_u8(r)?;
+            let len = read_len(r)?;
+            let mut list = Map::new();
+            for i in 0..len {
+                list.insert(format!("{}:{}", i, elem), read_payload(r, elem, depth + 1)?);
+            }
+            Value::Object(list)
+        }
+        TAG_COMPOUND => {
+            let mut map = Map::new();
+            loop {
+                let tag = read_u8(r)?;
+                if tag == TAG_END {
+                    break;
+                }
+                let name = read_string(r)?;
+                map.insert(
+                    format!("{}:{}", name, tag),
+                    read_payload(r, tag, depth + 1)?,
+                );
+            }
+            Value::Object(map)
+        }
+        11 => {
+            let len = read_len(r)?;
+            let ints = (0..len).map(|_| read_i32(r).map(Value::from));
+            Value::Array(ints.collect::<Result<_>>()?)
+        }
+        12 => {
+            let len = read_len(r)?;
+            let longs = (0..len).map(|_| Ok(Value::from(i64::from_be_bytes(read_exact(r)?))));
+            Value::Array(longs.collect::<Result<_>>()?)
+        }
+        other => {
+            return Err(ParseError::InvalidFormat(format!(
+                "unknown NBT tag {}",
+                other
+            )));
+        }
+    })
+}
+
+/// Elements of a tagged NBT list stored under `key` (any type suffix), in
+/// list order.
+fn tagged_list(root: &Map<String, Value>, key: &str) -> Vec<Value> {
+    let Some(Value::Object(list)) = root
+        .iter()
+        .find(|(k, _)| k.rsplit_once(':').map(|(n, _)| n) == Some(key))
+        .map(|(_, v)| v)
+    else {
+        return Vec::new();
+    };
+    let mut items: Vec<(usize, &Value)> = list
+        .iter()
+        .filter_map(|(k, v)| Some((k.split(':').next()?.parse().ok()?, v)))
+        .collect();
+    items.sort_by_key(|(i, _)| *i);
+    items.into_iter().map(|(_, v)| v.clone()).collect()
+}
+
+/// Build a `QuestDatabase` from a decoded (tagged) NBT root compound.
+fn database_from_nbt(root: Value) -> Result<QuestDatabase> {
+    let Value::Object(root) = root else {
+        return Err(ParseError::InvalidFormat(
+            "NBT root is not a compound".to_string(),
+        ));
+    };
+
+    let mut quests: HashMap<QuestId, Quest> = HashMap::new();
+    for (i, v) in tagged_list(&root, "questDatabase").into_iter().enumerate() {
+        let quest = crate::parser::quest_from_json(v)?;
+        if quests.insert(quest.id, quest).is_some() {
+            return Err(ParseError::DuplicateQuestId(format!("questDatabase/{}", i)));
+        }
+    }
+
+    let mut questlines: HashMap<QuestId, QuestLine> = HashMap::new();
+    let mut questline_order = Vec::new();
+    for (i, v) in tagged_list(&root, "questLines").into_iter().enumerate() {
+        let Value::Object(map) = crate::nbt_norm::normalize_value(v) else {
+            continue;
+        };
+        let mut line = questline_from_map(&map)?;
+        if let Some(Value::Array(entries)) = map.get("quests") {
+            for entry in entries.iter().filter_map(Value::as_object) {
+                line.entries.push(questline_entry_from_map(entry)?);
+            }
+        }
+        questline_order.push(line.id);
+        if questlines.insert(line.id, line).is_some() {
+            return Err(ParseError::DuplicateQuestId(format!("questLines/{}", i)));
+        }
+    }
+    resolve_questline_refs(
+        &mut questlines,
+        |id| quests.contains_key(id),
+        &ParseOptions::default(),
+    )?;
+
+    let settings = root
+        .iter()
+        .find(|(k, _)| k.rsplit_once(':').map(|(n, _)| n) == Some("questSettings"))
+        .map(|(_, v)| {
+            crate::db::parse_settings_value(&crate::nbt_norm::normalize_value(v.clone()))
+        });
+
+    Ok(QuestDatabase {
+        settings,
+        quests,
+        questlines,
+        questline_order,
+    })
+}
+
+/// Parse a binary NBT quest database from a reader.
+pub fn parse_quest_database_from_reader<R: Read>(r: R) -> Result<QuestDatabase> {
+    database_from_nbt(read_nbt(r)?)
+}
+
+/// Parse a `QuestDatabase.dat` / `DefaultQuests.dat` file.
+pub fn parse_quest_database_dat(path: &Path) -> Result<QuestDatabase> {
+    parse_quest_database_from_reader(std::fs::File::open(path)?)
+}
@@ -9,10 +9,12 @@
 //! `nbt_norm::normalize_value`. The module is filesystem-agnostic: callers provide
 //! a `QuestDataSource` implementation which abstracts listing directories and
 //! reading files (this makes testing easier and keeps parsing logic decoupled
-//! from IO).
+//! from IO). `FsDataSource` reads a folder on disk; `MemoryDataSource` holds
+//! the files in memory, for tests and synthetic packs.
 //!
 //! The primary entry point is `parse_default_quests_dir_from_source`. IDs are
-//! constructed from "High"/"Low" components (e.g. `questIDHigh`/`questIDLow`),
+//! constructed from "High"/"Low" components (e.g. `questIDHigh`/`questIDLow`)
+//! or, in BetterQuesting 3 packs, from UUID strings (`questID`, `questLineID`),
 //! and questline parsing validates references - missing or duplicate IDs yield
 //! `crate::error::ParseError` values rather than panics.
 //!
@@ -20,11 +22,23 @@
 //! `betterquesting` object, and finally falls back to top-level keys.
 //!
 //! Public functions return `Result<...>` to allow callers to handle parse errors.
+//!
+//! The reverse direction, `write_default_quests_dir`, lays a `QuestDatabase`
+//! out in the same folder structure through a `QuestDataSink`, restoring the
+//! BetterQuesting key names and NBT type suffixes (`nbt_norm::tag_value`) so
+//! the mod can load the result. `write_changes_to_sink` updates such a folder
+//! in place from `edit::DatabaseChange` events.
+use crate::edit::DatabaseChange;
 use crate::error::{ParseError, Result};
+use crate::identity::IdAliases;
+use crate::metrics::ParseStage;
 use crate::model::*;
+use crate::nbt_norm::{get_int, get_quest_id};
 use crate::quest_id::QuestId;
-use serde_json::Value;
-use std::collections::HashMap;
+use crate::validate::ValidationIssue;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// Type alias for the result of parsing a questline directory.
 type QuestlineDirParseResult = (Option<QuestLine>, Vec<(QuestId, QuestLineEntry)>);
@@ -41,146 +55,599 @@ pub trait QuestDataSource {
     fn read_to_string(&self, path: &str) -> Result<String>;
 }
 
+/// `QuestDataSource` backed by the local filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsDataSource;
+
+impl QuestDataSource for FsDataSource {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            names.push(entry?.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_dir()
+    }
+    fn is_file(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_file()
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        Ok(std::fs::read_to_string(path)?)
+    }
+}
+
+/// `QuestDataSource` serving files held in memory, keyed by `/`-separated
+/// path; folders are implied by the file paths. Also a `QuestDataSink`, so
+/// a database written to it parses back without touching the disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryDataSource {
+    files: BTreeMap<String, String>,
+    dirs: BTreeSet<String>,
+}
+
+impl MemoryDataSource {
+    /// An empty source.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the file at `path`, creating its parent folders.
+    pub fn insert(&mut self, path: impl Into<String>, contents: impl Into<String>) {
+        let path = path.into();
+        let mut parent = path.as_str();
+        while let Some(pos) = parent.rfind('/') {
+            parent = &parent[..pos];
+            self.dirs.insert(parent.to_string());
+        }
+        self.files.insert(path, contents.into());
+    }
+
+    /// Remove the file at `path`, returning its contents. Folders stay.
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        self.files.remove(path)
+    }
+
+    /// Contents of the file at `path`.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Paths of all files, sorted.
+    pub fn paths(&self) -> impl Iterator<Item = &str> + '_ {
+        self.files.keys().map(String::as_str)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MemoryDataSource {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut source = MemoryDataSource::new();
+        for (path, contents) in iter {
+            source.insert(path, contents);
+        }
+        source
+    }
+}
+
+impl QuestDataSource for MemoryDataSource {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        let path = path.trim_end_matches('/');
+        if !self.dirs.contains(path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no such directory: {}", path),
+            )
+            .into());
+        }
+        let prefix = format!("{}/", path);
+        let children: BTreeSet<&str> = self
+            .files
+            .keys()
+            .chain(self.dirs.iter())
+            .filter_map(|p| p.strip_prefix(&prefix))
+            .filter(|rest| !rest.is_empty() && !rest.contains('/'))
+            .collect();
+        Ok(children.into_iter().map(str::to_string).collect())
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        self.dirs.contains(path.trim_end_matches('/'))
+    }
+    fn is_file(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no such file: {}", path),
+            )
+            .into()
+        })
+    }
+}
+
+/// Parse a DefaultQuests folder on disk.
+pub fn parse_default_quests_dir(root: &std::path::Path) -> Result<QuestDatabase> {
+    parse_default_quests_dir_from_source(&FsDataSource, &root.to_string_lossy())
+}
+
+/// Options for `parse_default_quests_dir_with_options`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Fail with `MissingQuestReference` on the first questline entry pointing
+    /// at a missing quest (the default). When false, such entries are dropped
+    /// and reported as `ParseWarning`s.
+    pub strict_refs: bool,
+    /// Quest ids naming the same quest, unified before references are
+    /// checked (see `identity::IdAliases::unify`). Empty by default.
+    pub id_aliases: IdAliases,
+    /// Fail with `UnknownFields` when a file has keys BetterQuesting does not
+    /// read (see `schema::unknown_fields`). Off by default; costs a second
+    /// read of every file.
+    pub deny_unknown_fields: bool,
+    /// What to do about prerequisites naming quests that do not exist.
+    /// `Ignore` by default, since real packs ship such references.
+    pub missing_prerequisites: PrerequisiteCheck,
+}
+
+/// Handling of prerequisites pointing at missing quests while parsing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrerequisiteCheck {
+    /// Keep them without comment.
+    #[default]
+    Ignore,
+    /// Keep them and report each as a `ParseWarning::MissingPrerequisite`.
+    Warn,
+    /// Fail with `ParseError::Invalid` holding the first
+    /// `ValidationIssue::MissingPrerequisite` (by quest id).
+    Strict,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            strict_refs: true,
+            id_aliases: IdAliases::default(),
+            deny_unknown_fields: false,
+            missing_prerequisites: PrerequisiteCheck::Ignore,
+        }
+    }
+}
+
+/// A problem tolerated by a non-strict parse.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParseWarning {
+    /// A questline entry referenced a missing quest and was dropped.
+    DanglingQuestlineEntry {
+        questline: QuestId,
+        quest_id: QuestId,
+    },
+    /// A quest stored under an alias id was dropped because its canonical id
+    /// is present too.
+    DuplicateAlias { alias: QuestId, canonical: QuestId },
+    /// A quest lists a prerequisite that does not exist; it was kept.
+    MissingPrerequisite { quest: QuestId, missing: QuestId },
+}
+
+/// Result of `parse_default_quests_dir_with_options`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOutcome {
+    /// The parsed database, with the tolerated problems already handled.
+    pub db: QuestDatabase,
+    /// Tolerated problems: duplicate aliases, then dangling questline
+    /// entries by questline and quest id, then missing prerequisites by
+    /// quest id.
+    pub warnings: Vec<ParseWarning>,
+}
+
 /// Parse the DefaultQuests folder into a QuestDatabase using an abstract data source.
 pub fn parse_default_quests_dir_from_source(
     source: &dyn QuestDataSource,
     root: &str,
 ) -> Result<QuestDatabase> {
+    Ok(parse_default_quests_dir_with_options(source, root, &ParseOptions::default())?.db)
+}
+
+/// Like `parse_default_quests_dir_from_source`, with `options` controlling
+/// how broken references are handled.
+pub fn parse_default_quests_dir_with_options(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+) -> Result<ParseOutcome> {
+    parse_default_quests_dir_staged(source, root, options, |_| {})
+}
+
+/// Where parse steps send the errors they can skip past: returning `Err`
+/// stops the parse, `Ok` carries on without the broken file or entry.
+pub(crate) type ErrorSink<'a> = &'a mut dyn FnMut(ParseError) -> Result<()>;
+
+/// Parse the DefaultQuests folder at `root`, collecting every problem instead
+/// of stopping at the first, so all of them can be fixed in one go.
+///
+/// Unreadable files, files that fail to parse, duplicate ids (the first file
+/// wins) and, as `options` asks, unknown fields and broken references are
+/// reported as errors, and the database holds everything else; broken
+/// questline entries are dropped, missing prerequisites kept. Errors come in
+/// the order the parse meets them: schema, settings, quest files and
+/// questline folders in `ordering::natural_cmp` order, then references. The
+/// database is empty when `root` cannot be read at all. Tolerated problems
+/// (`ParseWarning`s) are not reported.
+pub fn parse_default_quests_dir_collect(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+) -> (QuestDatabase, Vec<ParseError>) {
+    let mut errors = Vec::new();
+    let mut collect = |e| {
+        errors.push(e);
+        Ok(())
+    };
+    let db = match parse_pack(source, root, options, |_| {}, &mut collect) {
+        Ok(outcome) => outcome.db,
+        Err(e) => {
+            errors.push(e);
+            QuestDatabase::default()
+        }
+    };
+    (db, errors)
+}
+
+/// The full parse, calling `enter` as each `ParseStage` begins.
+pub(crate) fn parse_default_quests_dir_staged(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+    enter: impl FnMut(ParseStage),
+) -> Result<ParseOutcome> {
+    parse_pack(source, root, options, enter, &mut Err)
+}
+
+/// The full parse, sending skippable errors to `report`.
+fn parse_pack(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+    mut enter: impl FnMut(ParseStage),
+    report: ErrorSink<'_>,
+) -> Result<ParseOutcome> {
     if !source.is_dir(root) {
         return Err(ParseError::InvalidFormat(format!("not a dir: {}", root)));
     }
-
-    // settings: optional file named QuestSettings.json or QuestSettings
-    let mut settings: Option<QuestSettings> = None;
-    let settings_paths = ["QuestSettings.json", "QuestSettings"];
-    for p in &settings_paths {
-        let fp = format!("{}/{}", root, p);
-        if source.is_file(&fp) {
-            settings = Some(parse_settings_file_from_source(source, &fp)?);
-            break;
+    if options.deny_unknown_fields {
+        enter(ParseStage::Schema);
+        let unknown = crate::schema::unknown_fields(source, root)?;
+        if !unknown.is_empty() {
+            report(ParseError::UnknownFields(unknown))?;
         }
     }
+    enter(ParseStage::Settings);
+    let settings = match parse_settings_from_source(source, root) {
+        Ok(settings) => settings,
+        Err(e) => {
+            report(e)?;
+            None
+        }
+    };
 
     // parse quests
+    enter(ParseStage::Quests);
     let mut quests: HashMap<QuestId, Quest> = HashMap::new();
-    let quests_dir = format!("{}/Quests", root);
-    if source.is_dir(&quests_dir) {
-        for entry in source.list_dir(&quests_dir)? {
-            let path = format!("{}/{}", &quests_dir, entry);
-            if source.is_file(&path) && path.ends_with(".json") {
-                let s = source.read_to_string(&path)?;
-                // Deserialize into the RawQuest directly; normalization happens during conversion
-                let raw: crate::model_raw::RawQuest = serde_json::from_str(&s)?;
-                let quest = Quest::from_raw(raw)?;
-                if quests.insert(quest.id, quest).is_some() {
-                    return Err(ParseError::DuplicateQuestId(path));
-                }
+    for path in quest_file_paths(source, root)? {
+        match parse_quest_file(source, &path) {
+            Ok(quest) if quests.contains_key(&quest.id) => {
+                report(ParseError::DuplicateQuestId(path))?
+            }
+            Ok(quest) => {
+                quests.insert(quest.id, quest);
             }
+            Err(e) => report(e)?,
         }
     }
 
     // parse questlines
+    enter(ParseStage::Questlines);
     let (questlines, questline_order) =
-        parse_questlines_dir_from_source(source, &format!("{}/QuestLines", root))?;
+        parse_questlines_dir_from_source(source, &format!("{}/QuestLines", root), report)?;
+    let mut db = QuestDatabase {
+        settings,
+        quests,
+        questlines,
+        questline_order,
+    };
+    enter(ParseStage::References);
+    let mut warnings: Vec<ParseWarning> = options
+        .id_aliases
+        .unify(&mut db)
+        .into_iter()
+        .map(|(alias, canonical)| ParseWarning::DuplicateAlias { alias, canonical })
+        .collect();
+    let quests = &db.quests;
+    let lenient = ParseOptions {
+        strict_refs: false,
+        ..ParseOptions::default()
+    };
+    for warning in
+        resolve_questline_refs(&mut db.questlines, |id| quests.contains_key(id), &lenient)?
+    {
+        match warning {
+            ParseWarning::DanglingQuestlineEntry {
+                questline,
+                quest_id,
+            } if options.strict_refs => report(ParseError::MissingQuestReference {
+                questline,
+                quest_id,
+            })?,
+            warning => warnings.push(warning),
+        }
+    }
+    if options.missing_prerequisites != PrerequisiteCheck::Ignore {
+        for issue in crate::validate::prerequisite_issues(&db) {
+            let ValidationIssue::MissingPrerequisite { quest, missing } = issue else {
+                continue;
+            };
+            if options.missing_prerequisites == PrerequisiteCheck::Strict {
+                report(ParseError::Invalid(issue))?;
+            } else {
+                warnings.push(ParseWarning::MissingPrerequisite { quest, missing });
+            }
+        }
+    }
 
-    // resolve references (strict: fail on missing quest)
-    for (qlid, qline) in &questlines {
-        for entry in &qline.entries {
-            if !quests.contains_key(&entry.quest_id) {
-                return Err(ParseError::MissingQuestReference {
-                    questline: qlid.as_u64(),
-                    quest_id: entry.quest_id,
-                });
+    Ok(ParseOutcome { db, warnings })
+}
+
+/// Parse a single questline of the DefaultQuests folder at `root` together
+/// with the quests its entries reference, for editors opening one chapter of
+/// a large pack.
+///
+/// Only the `QuestLine.json` files are read until `line_id` is found. Quests
+/// are first looked up as `Quests/<id>.json` (the writer's layout); any that
+/// are not found there are located by scanning quest file headers, and only
+/// matching files are parsed in full. Prerequisites outside the questline
+/// are not followed, so they may point at quests missing from the result.
+/// Fails with `InvalidFormat` when there is no such questline and with
+/// `MissingQuestReference` when an entry names a quest that does not exist.
+pub fn parse_questline_only(
+    source: &dyn QuestDataSource,
+    root: &str,
+    line_id: QuestId,
+) -> Result<QuestDatabase> {
+    if !source.is_dir(root) {
+        return Err(ParseError::InvalidFormat(format!("not a dir: {}", root)));
+    }
+    let qlines_dir = format!("{}/QuestLines", root);
+    let mut found = None;
+    if source.is_dir(&qlines_dir) {
+        for entry in source.list_dir(&qlines_dir)? {
+            let path = format!("{}/{}", qlines_dir, entry);
+            if source.is_dir(&path)
+                && parse_questline_file_from_source(source, &path)?.is_some_and(|l| l.id == line_id)
+            {
+                found = load_questline_dir(source, &path, &mut Err)?;
+                break;
+            }
+        }
+    }
+    let line = found.ok_or_else(|| {
+        ParseError::InvalidFormat(format!("no questline {} in {}", line_id, root))
+    })?;
+
+    let wanted: BTreeSet<QuestId> = line.entries.iter().map(|e| e.quest_id).collect();
+    let mut quests: HashMap<QuestId, Quest> = HashMap::new();
+    for id in &wanted {
+        let path = format!("{}/Quests/{}.json", root, id);
+        if source.is_file(&path) {
+            let quest = parse_quest_file(source, &path)?;
+            if quest.id == *id {
+                quests.insert(quest.id, quest);
+            }
+        }
+    }
+    if quests.len() < wanted.len() {
+        for path in quest_file_paths(source, root)? {
+            let text = source.read_to_string(&path)?;
+            let id = crate::lazy::parse_quest_header(&text)
+                .map_err(|e| e.in_file(path.as_str(), None))?
+                .id;
+            if wanted.contains(&id) && !quests.contains_key(&id) {
+                quests.insert(id, parse_quest_file(source, &path)?);
+                if quests.len() == wanted.len() {
+                    break;
+                }
             }
         }
     }
 
+    let mut questlines = HashMap::from([(line.id, line)]);
+    resolve_questline_refs(
+        &mut questlines,
+        |id| quests.contains_key(id),
+        &ParseOptions::default(),
+    )?;
     Ok(QuestDatabase {
-        settings,
+        settings: parse_settings_from_source(source, root)?,
         quests,
         questlines,
-        questline_order,
+        questline_order: vec![line_id],
     })
 }
 
-/// Parse the QuestLines directory into a map of QuestLine and their order.
-fn parse_questlines_dir_from_source(
+/// Read and parse the JSON file at `path`, naming it in errors.
+pub(crate) fn read_json_file(source: &dyn QuestDataSource, path: &str) -> Result<Value> {
+    source
+        .read_to_string(path)
+        .and_then(|s| crate::parser::json_value(s.into_bytes()))
+        .map_err(|e| e.in_file(path, None))
+}
+
+/// Parse the quest file at `path`. Errors are `ParseError::InFile` for the
+/// file; values of the wrong shape are located by a pointer to their
+/// top-level field.
+pub(crate) fn parse_quest_file(source: &dyn QuestDataSource, path: &str) -> Result<Quest> {
+    // Normalize NBT-suffixed keys first, exactly like single-file parsing
+    crate::parser::quest_from_json(read_json_file(source, path)?).map_err(|e| {
+        let pointer = match e {
+            // Only read the file again on failure, to keep the good path lean
+            ParseError::Json(_) => read_json_file(source, path)
+                .ok()
+                .and_then(|v| crate::parser::failing_quest_field(&v)),
+            _ => None,
+        };
+        e.in_file(path, pointer)
+    })
+}
+
+/// Parse the optional settings file (`QuestSettings.json` or `QuestSettings`).
+pub(crate) fn parse_settings_from_source(
+    source: &dyn QuestDataSource,
+    root: &str,
+) -> Result<Option<QuestSettings>> {
+    for p in ["QuestSettings.json", "QuestSettings"] {
+        let fp = format!("{}/{}", root, p);
+        if source.is_file(&fp) {
+            return Ok(Some(parse_settings_file_from_source(source, &fp)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Paths of the quest files below `root/Quests`, in `ordering::natural_cmp`
+/// order.
+pub(crate) fn quest_file_paths(source: &dyn QuestDataSource, root: &str) -> Result<Vec<String>> {
+    let quests_dir = format!("{}/Quests", root);
+    let mut out = Vec::new();
+    if source.is_dir(&quests_dir) {
+        let mut entries = source.list_dir(&quests_dir)?;
+        entries.sort_by(|a, b| crate::ordering::natural_cmp(a, b));
+        for entry in entries {
+            let path = format!("{}/{}", quests_dir, entry);
+            if source.is_file(&path) && path.ends_with(".json") {
+                out.push(path);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Resolve questline entries against the parsed quests. Strict mode fails on
+/// the first missing quest; otherwise dangling entries are dropped and
+/// returned as warnings.
+pub(crate) fn resolve_questline_refs(
+    questlines: &mut HashMap<QuestId, QuestLine>,
+    exists: impl Fn(&QuestId) -> bool,
+    options: &ParseOptions,
+) -> Result<Vec<ParseWarning>> {
+    let mut dangling = Vec::new();
+    for (qlid, qline) in questlines.iter_mut() {
+        for entry in &qline.entries {
+            if exists(&entry.quest_id) {
+                continue;
+            }
+            if options.strict_refs {
+                return Err(ParseError::MissingQuestReference {
+                    questline: *qlid,
+                    quest_id: entry.quest_id,
+                });
+            }
+            dangling.push((*qlid, entry.quest_id));
+        }
+        qline.entries.retain(|e| exists(&e.quest_id));
+    }
+    dangling.sort();
+    Ok(dangling
+        .into_iter()
+        .map(
+            |(questline, quest_id)| ParseWarning::DanglingQuestlineEntry {
+                questline,
+                quest_id,
+            },
+        )
+        .collect())
+}
+
+/// Parse the QuestLines directory into a map of QuestLine and their order,
+/// which is the order of their folders by `ordering::natural_cmp`.
+/// Folders that fail to parse are sent to `report` and skipped, as are
+/// questlines whose id an earlier folder already used.
+pub(crate) fn parse_questlines_dir_from_source(
     source: &dyn QuestDataSource,
     qlines_dir: &str,
+    report: ErrorSink<'_>,
 ) -> Result<(HashMap<QuestId, QuestLine>, Vec<QuestId>)> {
     let mut questlines: HashMap<QuestId, QuestLine> = HashMap::new();
     let mut questline_order: Vec<QuestId> = Vec::new();
     if source.is_dir(qlines_dir) {
-        for entry in source.list_dir(qlines_dir)? {
+        let mut entries = source.list_dir(qlines_dir)?;
+        entries.sort_by(|a, b| crate::ordering::natural_cmp(a, b));
+        for entry in entries {
             let path = format!("{}/{}", qlines_dir, entry);
-            if source.is_dir(&path) {
-                let (qline_opt, entries) = parse_questline_dir_from_source(source, &path)?;
-                if let Some(mut qline) = qline_opt {
-                    let mut sorted_entries: Vec<(QuestId, QuestLineEntry)> = entries;
-                    sorted_entries.sort_by_key(|(qid, _entry)| qid.as_u64());
-                    for (_qid, entry) in sorted_entries {
-                        qline.entries.push(entry);
-                    }
-                    if questlines.insert(qline.id, qline).is_some() {
-                        return Err(ParseError::DuplicateQuestId(path));
-                    }
+            if !source.is_dir(&path) {
+                continue;
+            }
+            match load_questline_dir(source, &path, report) {
+                Ok(Some(qline)) if questlines.contains_key(&qline.id) => {
+                    report(ParseError::DuplicateQuestId(path))?
                 }
+                Ok(Some(qline)) => {
+                    questline_order.push(qline.id);
+                    questlines.insert(qline.id, qline);
+                }
+                Ok(None) => {}
+                Err(e) => report(e)?,
             }
         }
     }
-    if questline_order.is_empty() {
-        questline_order = questlines.keys().cloned().collect();
-    }
     Ok((questlines, questline_order))
 }
 
+/// Parse a questline directory into its questline with the entries sorted
+/// by quest id, or `None` when it has no `QuestLine.json`. Entry files that
+/// fail to parse are sent to `report` and skipped.
+fn load_questline_dir(
+    source: &dyn QuestDataSource,
+    path: &str,
+    report: ErrorSink<'_>,
+) -> Result<Option<QuestLine>> {
+    let (qline_opt, mut entries) = parse_questline_dir_from_source(source, path, report)?;
+    Ok(qline_opt.map(|mut qline| {
+        entries.sort_by_key(|(qid, _entry)| *qid);
+        qline
+            .entries
+            .extend(entries.into_iter().map(|(_qid, entry)| entry));
+        qline
+    }))
+}
+
+/// Parse only the `QuestLine.json` of a questline directory (without entries).
+fn parse_questline_file_from_source(
+    source: &dyn QuestDataSource,
+    path: &str,
+) -> Result<Option<QuestLine>> {
+    let qline_json = format!("{}/QuestLine.json", path);
+    if !source.is_file(&qline_json) {
+        return Ok(None);
+    }
+    let v = read_json_file(source, &qline_json)?;
+    // Normalize only the questline object for field extraction
+    match crate::nbt_norm::normalize_value(v) {
+        Value::Object(map) => Ok(Some(
+            questline_from_map(&map).map_err(|e| e.in_file(qline_json, None))?,
+        )),
+        _ => Ok(None),
+    }
+}
+
 /// Parse a single questline directory, returning the QuestLine (if present) and its entries.
 fn parse_questline_dir_from_source(
     source: &dyn QuestDataSource,
     path: &str,
+    report: ErrorSink<'_>,
 ) -> Result<QuestlineDirParseResult> {
-    let qline_json = format!("{}/QuestLine.json", path);
-    let mut qline_opt: Option<QuestLine> = None;
-    if source.is_file(&qline_json) {
-        let s = source.read_to_string(&qline_json)?;
-        let v: Value = serde_json::from_str(&s)?;
-        // Normalize only the questline object for field extraction
-        let norm = crate::nbt_norm::normalize_value(v);
-        if let Value::Object(map) = norm {
-            let high = map
-                .get("questLineIDHigh")
-                .and_then(|x| x.as_i64())
-                .map(|n| n as i32)
-                .unwrap_or(0);
-            let low = map
-                .get("questLineIDLow")
-                .and_then(|x| x.as_i64())
-                .map(|n| n as i32)
-                .unwrap_or(0);
-            let id = QuestId::from_parts(high, low);
-            let props = map.get("properties").and_then(|p| {
-                if let Some(obj) = p.as_object() {
-                    if let Some(bqv) = obj.get("betterquesting") {
-                        let bq_norm = crate::nbt_norm::normalize_value(bqv.clone());
-                        serde_json::from_value::<QuestProperties>(bq_norm).ok()
-                    } else if let Some((_k, inner)) = obj.iter().next() {
-                        let inner_norm = crate::nbt_norm::normalize_value(inner.clone());
-                        serde_json::from_value::<QuestProperties>(inner_norm).ok()
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            });
-            qline_opt = Some(QuestLine {
-                id,
-                properties: props,
-                entries: Vec::new(),
-                extra: HashMap::new(),
-            });
-        }
-    }
+    let qline_opt = parse_questline_file_from_source(source, path)?;
     let mut entries: Vec<(QuestId, QuestLineEntry)> = Vec::new();
     if source.is_dir(path) {
         for entry in source.list_dir(path)? {
@@ -189,8 +656,10 @@ fn parse_questline_dir_from_source(
                 if entry == "QuestLine.json" {
                     continue;
                 }
-                if let Some((qid, entry)) = parse_questline_entry_file_from_source(source, &p)? {
-                    entries.push((qid, entry));
+                match parse_questline_entry_file_from_source(source, &p) {
+                    Ok(Some((qid, entry))) => entries.push((qid, entry)),
+                    Ok(None) => {}
+                    Err(e) => report(e)?,
                 }
             }
         }
@@ -203,48 +672,77 @@ fn parse_questline_entry_file_from_source(
     source: &dyn QuestDataSource,
     p: &str,
 ) -> Result<Option<(QuestId, QuestLineEntry)>> {
-    let s = source.read_to_string(p)?;
-    let v: Value = serde_json::from_str(&s)?;
+    let v = read_json_file(source, p)?;
     // Normalize this entry object before extracting fields
     let norm = crate::nbt_norm::normalize_value(v);
     if let Value::Object(map) = norm {
-        let high = map
-            .get("questIDHigh")
-            .and_then(|x| x.as_i64())
-            .map(|n| n as i32)
-            .unwrap_or(0);
-        let low = map
-            .get("questIDLow")
-            .and_then(|x| x.as_i64())
-            .map(|n| n as i32)
-            .unwrap_or(0);
-        let qid = QuestId::from_parts(high, low);
-        let entry = QuestLineEntry {
-            index: None,
-            quest_id: qid,
-            x: map.get("x").and_then(|x| x.as_i64().map(|n| n as i32)),
-            y: map.get("y").and_then(|x| x.as_i64().map(|n| n as i32)),
-            size_x: map.get("sizeX").and_then(|x| x.as_i64().map(|n| n as i32)),
-            size_y: map.get("sizeY").and_then(|x| x.as_i64().map(|n| n as i32)),
-            extra: HashMap::new(),
-        };
-        Ok(Some((qid, entry)))
+        let entry = questline_entry_from_map(&map).map_err(|e| e.in_file(p, None))?;
+        Ok(Some((entry.quest_id, entry)))
     } else {
         Ok(None)
     }
 }
 
+/// Questline properties from a normalized `betterquesting` block, with a
+/// paginated description collected into pages.
+fn line_properties(v: Value) -> Option<QuestLineProperties> {
+    let Value::Object(mut map) = v else {
+        return None;
+    };
+    let desc = map.remove("desc");
+    let mut props = serde_json::from_value::<QuestLineProperties>(Value::Object(map)).ok()?;
+    let mut pages = crate::model::description_pages(desc.as_ref(), &mut props.extra);
+    pages.append(&mut props.desc_pages);
+    props.set_pages(pages);
+    Some(props)
+}
+
+/// Build a questline (without entries) from its normalized object.
+pub(crate) fn questline_from_map(map: &Map<String, Value>) -> Result<QuestLine> {
+    let id = get_quest_id(map, "questLineIDHigh", "questLineIDLow")?;
+    let props = map.get("properties").and_then(|p| {
+        if let Some(obj) = p.as_object() {
+            if let Some(bqv) = obj.get("betterquesting") {
+                line_properties(crate::nbt_norm::normalize_value(bqv.clone()))
+            } else if let Some((_k, inner)) = obj.iter().next() {
+                line_properties(crate::nbt_norm::normalize_value(inner.clone()))
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    });
+    Ok(QuestLine {
+        id,
+        properties: props,
+        entries: Vec::new(),
+        extra: HashMap::new(),
+    })
+}
+
+/// Build a questline entry from its normalized object.
+pub(crate) fn questline_entry_from_map(map: &Map<String, Value>) -> Result<QuestLineEntry> {
+    Ok(QuestLineEntry {
+        index: None,
+        quest_id: get_quest_id(map, "questIDHigh", "questIDLow")?,
+        x: get_int(map, "x")?,
+        y: get_int(map, "y")?,
+        size_x: get_int(map, "sizeX")?,
+        size_y: get_int(map, "sizeY")?,
+        extra: HashMap::new(),
+    })
+}
+
 fn parse_settings_file_from_source(
     source: &dyn QuestDataSource,
     path: &str,
 ) -> Result<QuestSettings> {
-    let s = source.read_to_string(path)?;
-    let v: Value = serde_json::from_str(&s)?;
-    // Do targeted normalization inside parse_settings_value if needed; pass raw value here
-    Ok(parse_settings_value(&v))
+    let v = read_json_file(source, path)?;
+    Ok(parse_settings_value(&crate::nbt_norm::normalize_value(v)))
 }
 
-fn parse_settings_value(v: &Value) -> QuestSettings {
+pub(crate) fn parse_settings_value(v: &Value) -> QuestSettings {
     let mut version: Option<String> = None;
     let mut extra: HashMap<String, Value> = HashMap::new();
 
@@ -307,3 +805,488 @@ fn parse_settings_value(v: &Value) -> QuestSettings {
 
     QuestSettings { version, extra }
 }
+
+/// Abstracts file/directory creation for writing quest data.
+pub trait QuestDataSink {
+    /// Create a directory and any missing parents.
+    fn create_dir_all(&mut self, path: &str) -> Result<()>;
+    /// Write a file, replacing any existing contents.
+    fn write_file(&mut self, path: &str, contents: &str) -> Result<()>;
+    /// Delete a file. Sinks that cannot delete report an error, which makes
+    /// `write_changes_to_sink` unusable for changes removing files.
+    fn remove_file(&mut self, path: &str) -> Result<()> {
+        Err(ParseError::Other(format!("cannot remove {}", path)))
+    }
+}
+
+/// `QuestDataSink` backed by the local filesystem.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsDataSink;
+
+impl QuestDataSink for FsDataSink {
+    fn create_dir_all(&mut self, path: &str) -> Result<()> {
+        Ok(std::fs::create_dir_all(path)?)
+    }
+    fn write_file(&mut self, path: &str, contents: &str) -> Result<()> {
+        Ok(std::fs::write(path, contents)?)
+    }
+    fn remove_file(&mut self, path: &str) -> Result<()> {
+        Ok(std::fs::remove_file(path)?)
+    }
+}
+
+impl QuestDataSink for MemoryDataSource {
+    fn create_dir_all(&mut self, path: &str) -> Result<()> {
+        let mut dir = path.trim_end_matches('/');
+        while !dir.is_empty() {
+            self.dirs.insert(dir.to_string());
+            dir = dir.rfind('/').map_or("", |pos| &dir[..pos]);
+        }
+        Ok(())
+    }
+    fn write_file(&mut self, path: &str, contents: &str) -> Result<()> {
+        self.insert(path, contents);
+        Ok(())
+    }
+    fn remove_file(&mut self, path: &str) -> Result<()> {
+        match self.remove(path) {
+            Some(_) => Ok(()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no such file: {}", path),
+            )
+            .into()),
+        }
+    }
+}
+
+/// Insert `value` under `key` unless it is `None`.
+fn put<T: Into<Value>>(m: &mut Map<String, Value>, key: &str, value: Option<T>) {
+    if let Some(v) = value {
+        m.insert(key.to_string(), v.into());
+    }
+}
+
+fn extra_map(extra: &HashMap<String, Value>) -> Map<String, Value> {
+    extra.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+fn id_value(id: QuestId, high_key: &str, low_key: &str) -> Map<String, Value> {
+    let mut m = Map::new();
+    m.insert(high_key.to_string(), id.high_i64().into());
+    m.insert(low_key.to_string(), id.low_i64().into());
+    m
+}
+
+fn item_value(item: &ItemStack) -> Value {
+    let mut m = extra_map(&item.extra);
+    m.insert("id".to_string(), item.id.clone().into());
+    put(&mut m, "Count", item.count);
+    put(&mut m, "Damage", item.damage);
+    put(&mut m, "OreDict", item.oredict.clone());
+    Value::Object(m)
+}
+
+fn items_value(items: &[ItemStack]) -> Value {
+    Value::Array(items.iter().map(item_value).collect())
+}
+
+fn properties_value(p: &QuestProperties) -> Value {
+    let mut m = extra_map(&p.extra);
+    m.insert("name".to_string(), p.name.clone().into());
+    put(&mut m, "desc", p.desc.clone());
+    for (i, page) in p.desc_pages.iter().enumerate() {
+        m.insert(format!("desc{}", i + 2), page.clone().into());
+    }
+    put(&mut m, "icon", p.icon.as_ref().map(item_value));
+    put(&mut m, "isMain", p.is_main);
+    put(&mut m, "isSilent", p.is_silent);
+    put(&mut m, "autoClaim", p.auto_claim);
+    put(&mut m, "globalShare", p.global_share);
+    put(&mut m, "isGlobal", p.is_global);
+    put(&mut m, "lockedProgress", p.locked_progress);
+    put(&mut m, "repeatTime", p.repeat_time);
+    put(&mut m, "repeat_relative", p.repeat_relative);
+    put(&mut m, "simultaneous", p.simultaneous);
+    put(&mut m, "partySingleReward", p.party_single_reward);
+    put(&mut m, "questLogic", p.quest_logic.clone());
+    put(&mut m, "taskLogic", p.task_logic.clone());
+    put(&mut m, "visibility", p.visibility.clone());
+    put(&mut m, "snd_complete", p.snd_complete.clone());
+    put(&mut m, "snd_update", p.snd_update.clone());
+    json!({ "betterquesting": m })
+}
+
+fn task_value(i: usize, t: &Task) -> Value {
+    let mut m = extra_map(&t.options);
+    m.insert("taskID".to_string(), t.task_id.clone().into());
+    m.insert("index".to_string(), t.index.unwrap_or(i).into());
+    // empty NBT lists read back as `{}`, which the typed item lists reject
+    if !t.required_items.is_empty() {
+        m.insert("requiredItems".to_string(), items_value(&t.required_items));
+    }
+    put(&mut m, "ignoreNBT", t.ignore_nbt);
+    put(&mut m, "partialMatch", t.partial_match);
+    put(&mut m, "autoConsume", t.auto_consume);
+    put(&mut m, "consume", t.consume);
+    put(&mut m, "groupDetect", t.group_detect);
+    Value::Object(m)
+}
+
+fn reward_value(i: usize, r: &Reward) -> Value {
+    let mut m = extra_map(&r.extra);
+    m.insert("rewardID".to_string(), r.reward_id.clone().into());
+    m.insert("index".to_string(), r.index.unwrap_or(i).into());
+    if !r.items.is_empty() {
+        m.insert("rewards".to_string(), items_value(&r.items));
+    }
+    if !r.choices.is_empty() {
+        m.insert("choices".to_string(), items_value(&r.choices));
+    }
+    put(&mut m, "ignoreDisabled", r.ignore_disabled);
+    Value::Object(m)
+}
+
+/// A quest in the (untagged) on-disk layout.
+fn quest_value(q: &Quest) -> Result<Value> {
+    let props = q.properties.as_ref().ok_or_else(|| {
+        ParseError::InvalidFormat(format!("quest {} has no properties to write", q.id))
+    })?;
+    let prereq = |id: &QuestId| Value::Object(id_value(*id, "questIDHigh", "questIDLow"));
+    // every prerequisite goes into preRequisites; the optional ones are
+    // repeated in optionalPreRequisites, which is how the parser splits them
+    let mut all: Vec<QuestId> = Vec::new();
+    for id in q
+        .prerequisites
+        .iter()
+        .chain(&q.required_prerequisites)
+        .chain(&q.optional_prerequisites)
+    {
+        if !all.contains(id) {
+            all.push(*id);
+        }
+    }
+    let mut m = id_value(q.id, "questIDHigh", "questIDLow");
+    m.insert("properties".to_string(), properties_value(props));
+    m.insert(
+        "preRequisites".to_string(),
+        Value::Array(all.iter().map(prereq).collect()),
+    );
+    if !q.optional_prerequisites.is_empty() {
+        m.insert(
+            "optionalPreRequisites".to_string(),
+            Value::Array(q.optional_prerequisites.iter().map(prereq).collect()),
+        );
+    }
+    m.insert(
+        "tasks".to_string(),
+        Value::Array(
+            q.tasks
+                .iter()
+                .enumerate()
+                .map(|(i, t)| task_value(i, t))
+                .collect(),
+        ),
+    );
+    m.insert(
+        "rewards".to_string(),
+        Value::Array(
+            q.rewards
+                .iter()
+                .enumerate()
+                .map(|(i, r)| reward_value(i, r))
+                .collect(),
+        ),
+    );
+    Ok(Value::Object(m))
+}
+
+fn write_tagged(sink: &mut dyn QuestDataSink, path: &str, v: Value) -> Result<()> {
+    let text = crate::normalize::canonical_json(&crate::nbt_norm::tag_value(v));
+    sink.write_file(path, &text)
+}
+
+/// Write `db` below `root` in the DefaultQuests layout read by
+/// `parse_default_quests_dir_from_source`: `QuestSettings.json`,
+/// `Quests/<id>.json`, and one `QuestLines/<n>/` folder per questline (in
+/// `questline_order`) holding `QuestLine.json` plus one file per entry.
+///
+/// Existing files are overwritten but stale ones are not removed, so write
+/// into an empty directory. Quests without properties cannot be represented
+/// and yield `ParseError::InvalidFormat`.
+pub fn write_default_quests_dir_to_sink(
+    sink: &mut dyn QuestDataSink,
+    root: &str,
+    db: &QuestDatabase,
+) -> Result<()> {
+    sink.create_dir_all(root)?;
+    if let Some(settings) = &db.settings {
+        let mut m = extra_map(&settings.extra);
+        put(&mut m, "version", settings.version.clone());
+        let v = json!({ "properties": { "betterquesting": m } });
+        write_tagged(sink, &format!("{}/QuestSettings.json", root), v)?;
+    }
+
+    let quests_dir = format!("{}/Quests", root);
+    sink.create_dir_all(&quests_dir)?;
+    for quest in crate::ordering::sorted_quests(db) {
+        let path = format!("{}/{}.json", quests_dir, quest.id);
+        write_tagged(sink, &path, quest_value(quest)?)?;
+    }
+
+    for (n, lid) in written_questline_order(db).iter().enumerate() {
+        let line = &db.questlines[lid];
+        let dir = format!("{}/QuestLines/{}", root, n);
+        sink.create_dir_all(&dir)?;
+        write_tagged(
+            sink,
+            &format!("{}/QuestLine.json", dir),
+            questline_value(line),
+        )?;
+        for (i, entry) in line.entries.iter().enumerate() {
+            write_tagged(sink, &format!("{}/{}.json", dir, i), entry_value(entry))?;
+        }
+    }
+    Ok(())
+}
+
+/// Questlines in the order they are written: `questline_order` first, then
+/// any others by id. The position is the `QuestLines/<n>` folder number.
+fn written_questline_order(db: &QuestDatabase) -> Vec<QuestId> {
+    crate::ordering::questline_ids(db)
+}
+
+fn line_properties_value(p: &QuestLineProperties) -> Value {
+    let mut m = extra_map(&p.extra);
+    m.insert("name".to_string(), p.name.clone().into());
+    put(&mut m, "desc", p.desc.clone());
+    for (i, page) in p.desc_pages.iter().enumerate() {
+        m.insert(format!("desc{}", i + 2), page.clone().into());
+    }
+    put(&mut m, "icon", p.icon.as_ref().map(item_value));
+    put(&mut m, "visibility", p.visibility.clone());
+    put(&mut m, "bg_image", p.bg_image.clone());
+    put(&mut m, "bg_size", p.bg_size);
+    json!({ "betterquesting": m })
+}
+
+fn questline_value(line: &QuestLine) -> Value {
+    let mut m = extra_map(&line.extra);
+    m.extend(id_value(line.id, "questLineIDHigh", "questLineIDLow"));
+    put(
+        &mut m,
+        "properties",
+        line.properties.as_ref().map(line_properties_value),
+    );
+    Value::Object(m)
+}
+
+fn entry_value(entry: &QuestLineEntry) -> Value {
+    let mut m = extra_map(&entry.extra);
+    m.extend(id_value(entry.quest_id, "questIDHigh", "questIDLow"));
+    put(&mut m, "x", entry.x);
+    put(&mut m, "y", entry.y);
+    put(&mut m, "sizeX", entry.size_x);
+    put(&mut m, "sizeY", entry.size_y);
+    Value::Object(m)
+}
+
+/// Entry files of one questline touched by a batch of changes.
+#[derive(Default)]
+struct DirtyLine {
+    /// Single entries rewritten in place.
+    entries: BTreeSet<usize>,
+    /// Every entry from this index on shifted and is rewritten.
+    shifted_from: Option<usize>,
+    /// Entries added minus entries removed.
+    delta: i64,
+}
+
+/// Bring a folder written by `write_default_quests_dir_to_sink` from the
+/// state before `changes` to the state of `db` (after them), touching only
+/// the affected files: one quest file per changed quest and the shifted or
+/// moved entry files of changed questlines. Files of removed quests and
+/// questline entries past the new end are deleted through
+/// `QuestDataSink::remove_file`.
+pub fn write_changes_to_sink(
+    sink: &mut dyn QuestDataSink,
+    root: &str,
+    db: &QuestDatabase,
+    changes: &[DatabaseChange],
+) -> Result<()> {
+    let mut quests: BTreeSet<QuestId> = BTreeSet::new();
+    let mut lines: BTreeMap<QuestId, DirtyLine> = BTreeMap::new();
+    for change in changes {
+        match change {
+            DatabaseChange::QuestAdded { quest } | DatabaseChange::QuestRemoved { quest } => {
+                quests.insert(quest.id);
+            }
+            DatabaseChange::PropertyChanged { quest, .. }
+            | DatabaseChange::PrereqAdded { quest, .. }
+            | DatabaseChange::PrereqRemoved { quest, .. } => {
+                quests.insert(*quest);
+            }
+            DatabaseChange::EntryAdded {
+                questline, index, ..
+            }
+            | DatabaseChange::EntryRemoved {
+                questline, index, ..
+            } => {
+                let dirty = lines.entry(*questline).or_default();
+                dirty.shifted_from = Some(dirty.shifted_from.map_or(*index, |i| i.min(*index)));
+                dirty.delta += if matches!(change, DatabaseChange::EntryAdded { .. }) {
+                    1
+                } else {
+                    -1
+                };
+            }
+            DatabaseChange::EntryMoved {
+                questline, quest, ..
+            } => {
+                if let Some(i) = db
+                    .questlines
+                    .get(questline)
+                    .and_then(|l| l.entries.iter().position(|e| e.quest_id == *quest))
+                {
+                    lines.entry(*questline).or_default().entries.insert(i);
+                }
+            }
+        }
+    }
+
+    let quests_dir = format!("{}/Quests", root);
+    for id in quests {
+        let path = format!("{}/{}.json", quests_dir, id);
+        match db.quests.get(&id) {
+            Some(quest) => write_tagged(sink, &path, quest_value(quest)?)?,
+            None => sink.remove_file(&path)?,
+        }
+    }
+
+    let order = written_questline_order(db);
+    for (lid, dirty) in lines {
+        let (Some(n), Some(line)) = (
+            order.iter().position(|l| *l == lid),
+            db.questlines.get(&lid),
+        ) else {
+            continue;
+        };
+        let dir = format!("{}/QuestLines/{}", root, n);
+        let len = line.entries.len();
+        let mut indices = dirty.entries;
+        indices.extend(dirty.shifted_from.map_or(len, |i| i.min(len))..len);
+        for i in indices.into_iter().filter(|i| *i < len) {
+            write_tagged(
+                sink,
+                &format!("{}/{}.json", dir, i),
+                entry_value(&line.entries[i]),
+            )?;
+        }
+        let old_len = (len as i64 - dirty.delta).max(0) as usize;
+        for i in len..old_len {
+            sink.remove_file(&format!("{}/{}.json", dir, i))?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply `changes` to a DefaultQuests folder on disk, see
+/// `write_changes_to_sink`.
+pub fn write_changes(
+    db: &QuestDatabase,
+    changes: &[DatabaseChange],
+    root: &std::path::Path,
+) -> Result<()> {
+    write_changes_to_sink(&mut FsDataSink, &root.to_string_lossy(), db, changes)
+}
+
+/// `QuestDataSink` on the local filesystem that leaves files whose contents
+/// would not change untouched, so their mtimes survive.
+#[derive(Debug, Clone, Default)]
+pub struct IncrementalFsSink {
+    /// Files written because they were missing or differed.
+    pub written: Vec<String>,
+    /// Files left alone because their contents matched.
+    pub unchanged: Vec<String>,
+}
+
+impl QuestDataSink for IncrementalFsSink {
+    fn create_dir_all(&mut self, path: &str) -> Result<()> {
+        Ok(std::fs::create_dir_all(path)?)
+    }
+    fn write_file(&mut self, path: &str, contents: &str) -> Result<()> {
+        let same = std::fs::read(path).is_ok_and(|old| old == contents.as_bytes());
+        if same {
+            self.unchanged.push(path.to_string());
+        } else {
+            std::fs::write(path, contents)?;
+            self.written.push(path.to_string());
+        }
+        Ok(())
+    }
+    fn remove_file(&mut self, path: &str) -> Result<()> {
+        Ok(std::fs::remove_file(path)?)
+    }
+}
+
+/// What `write_default_quests_dir_incremental` did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteSummary {
+    /// Files created or rewritten, in write order.
+    pub written: Vec<String>,
+    /// Number of files whose contents already matched.
+    pub unchanged: usize,
+    /// Stale `.json` files deleted, sorted.
+    pub removed: Vec<String>,
+}
+
+/// Write `db` over an existing DefaultQuests folder, rewriting only the
+/// files whose contents change and deleting quest and questline files the
+/// database no longer produces (emptied questline folders go too). Unchanged
+/// files keep their mtimes, so a git-tracked pack only shows real edits.
+pub fn write_default_quests_dir_incremental(
+    db: &QuestDatabase,
+    root: &std::path::Path,
+) -> Result<WriteSummary> {
+    let mut sink = IncrementalFsSink::default();
+    let root_str = root.to_string_lossy();
+    write_default_quests_dir_to_sink(&mut sink, &root_str, db)?;
+
+    let produced: std::collections::HashSet<std::path::PathBuf> = sink
+        .written
+        .iter()
+        .chain(&sink.unchanged)
+        .map(std::path::PathBuf::from)
+        .collect();
+    let mut existing = Vec::new();
+    for dir in [root.join("Quests"), root.join("QuestLines")] {
+        if dir.is_dir() {
+            crate::normalize::json_files(&dir, &mut existing)?;
+        }
+    }
+    existing.sort();
+    let mut removed = Vec::new();
+    for path in existing.into_iter().filter(|p| !produced.contains(p)) {
+        std::fs::remove_file(&path)?;
+        removed.push(path.to_string_lossy().into_owned());
+    }
+    let lines_dir = root.join("QuestLines");
+    if lines_dir.is_dir() {
+        for entry in std::fs::read_dir(&lines_dir)? {
+            let path = entry?.path();
+            if path.is_dir() && std::fs::read_dir(&path)?.next().is_none() {
+                std::fs::remove_dir(&path)?;
+            }
+        }
+    }
+    Ok(WriteSummary {
+        written: sink.written,
+        unchanged: sink.unchanged.len(),
+        removed,
+    })
+}
+
+/// Write `db` to a DefaultQuests folder on disk.
+pub fn write_default_quests_dir(db: &QuestDatabase, root: &std::path::Path) -> Result<()> {
+    write_default_quests_dir_to_sink(&mut FsDataSink, &root.to_string_lossy(), db)
+}
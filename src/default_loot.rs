@@ -0,0 +1,218 @@
+//! bq_standard loot chest configuration (`DefaultLoot.json`).
+//!
+//! bq_standard's loot chests are an item, `bq_standard:loot_chest`, handed out
+//! by ordinary item rewards. Opening one draws a loot group from
+//! `DefaultLoot.json`, which sits next to the `DefaultQuests` folder, and then
+//! one weighted entry of that group's item pools. The file holds a `groups`
+//! list of `{ID, name, weight, rewards}`, each reward being a `{weight, items}`
+//! pool.
+//!
+//! Which groups a chest can draw depends on its damage value, the rarity
+//! (0 to 100). BetterQuesting sorts the groups by weight and walks their
+//! cumulative weights up to a random point between `0.75 * rarity / 100` and
+//! `0.75 * rarity / 100 + 0.25` of the total, so common chests reach the light
+//! groups and rare ones the heavy groups. `LootTable::groups_for_rarity`
+//! returns every group the draw can land on and `LootTable::chests` links the
+//! loot chests rewarded by quests to them. Damage values above 100 are the
+//! special chests carrying their loot in NBT; they link to no group.
+use crate::db::{QuestDataSource, read_json_file};
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::nbt_norm::{get_int, normalize_value};
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+
+/// Item id of bq_standard's loot chest.
+pub const LOOT_CHEST_ITEM: &str = "bq_standard:loot_chest";
+
+/// Highest loot chest damage value that is a rarity.
+pub const MAX_RARITY: i32 = 100;
+
+/// One weighted item pool of a loot group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootEntry {
+    /// Relative weight within the group.
+    pub weight: i32,
+    /// Stacks granted together when the entry is drawn.
+    pub items: Vec<ItemStack>,
+}
+
+/// A loot group of `DefaultLoot.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootGroup {
+    /// `ID` of the group (its position when not stored).
+    pub id: i32,
+    pub name: String,
+    /// Relative weight among the groups.
+    pub weight: i32,
+    pub entries: Vec<LootEntry>,
+}
+
+/// The parsed `DefaultLoot.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootTable {
+    /// Groups in file order.
+    pub groups: Vec<LootGroup>,
+}
+
+/// A loot chest handed out by a quest reward.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootChestReward {
+    pub quest: QuestId,
+    /// Position of the reward within the quest.
+    pub reward: usize,
+    /// Damage value of the chest.
+    pub rarity: i32,
+    /// Number of chests.
+    pub count: i32,
+    /// Ids of the groups the chest can draw, by ascending weight.
+    pub groups: Vec<i32>,
+}
+
+fn group_from_value(position: usize, value: &Value) -> Result<LootGroup> {
+    let map = value.as_object().ok_or_else(|| {
+        ParseError::InvalidFormat(format!("loot group {} is not an object", position))
+    })?;
+    let entries = match map.get("rewards") {
+        Some(Value::Array(entries)) => entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_object().ok_or_else(|| {
+                    ParseError::InvalidFormat("loot entry is not an object".to_string())
+                })?;
+                let items = match entry.get("items") {
+                    Some(items) => serde_json::from_value(items.clone())?,
+                    None => Vec::new(),
+                };
+                Ok(LootEntry {
+                    weight: get_int(entry, "weight")?.unwrap_or(1),
+                    items,
+                })
+            })
+            .collect::<Result<_>>()?,
+        _ => Vec::new(),
+    };
+    Ok(LootGroup {
+        id: get_int(map, "ID")?.unwrap_or(position as i32),
+        name: map
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        weight: get_int(map, "weight")?.unwrap_or(1),
+        entries,
+    })
+}
+
+impl LootTable {
+    /// Parse the tagged JSON of `DefaultLoot.json`.
+    pub fn from_value(value: Value) -> Result<Self> {
+        let value = normalize_value(value);
+        let groups = match value.get("groups") {
+            Some(Value::Array(groups)) => groups,
+            // an empty NBT list is written as an empty object
+            Some(Value::Object(groups)) if groups.is_empty() => return Ok(LootTable::default()),
+            _ => {
+                return Err(ParseError::InvalidFormat(
+                    "loot file has no groups list".to_string(),
+                ));
+            }
+        };
+        let groups = groups
+            .iter()
+            .enumerate()
+            .map(|(i, g)| group_from_value(i, g))
+            .collect::<Result<_>>()?;
+        Ok(LootTable { groups })
+    }
+
+    /// Parse `DefaultLoot.json` from a reader.
+    pub fn from_reader<R: Read>(r: R) -> Result<Self> {
+        Self::from_value(serde_json::from_reader(r)?)
+    }
+
+    /// Parse the `DefaultLoot.json` at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_reader(std::io::BufReader::new(std::fs::File::open(path)?))
+            .map_err(|e| e.in_file(path.display().to_string(), None))
+    }
+
+    /// The group with `id`.
+    pub fn group(&self, id: i32) -> Option<&LootGroup> {
+        self.groups.iter().find(|g| g.id == id)
+    }
+
+    /// Ids of the groups a chest of `rarity` (0 to 100) can draw, by
+    /// ascending weight. Groups of weight 0 or less are never drawn.
+    pub fn groups_for_rarity(&self, rarity: i32) -> Vec<i32> {
+        let mut groups: Vec<&LootGroup> = self.groups.iter().filter(|g| g.weight > 0).collect();
+        groups.sort_by_key(|g| g.weight);
+        let total: f64 = groups.iter().map(|g| g.weight as f64).sum();
+        let low = 0.75 * rarity.clamp(0, MAX_RARITY) as f64 / MAX_RARITY as f64 * total;
+        let high = low + total / 4.0;
+        let mut reached = 0.0;
+        let mut out = Vec::new();
+        for group in groups {
+            let before = reached;
+            reached += group.weight as f64;
+            if reached >= low && before < high {
+                out.push(group.id);
+            }
+        }
+        out
+    }
+
+    /// Every loot chest rewarded by the quests of `db`, by quest id and
+    /// reward position, linked to the groups it can draw.
+    pub fn chests(&self, db: &QuestDatabase) -> Vec<LootChestReward> {
+        let mut out = Vec::new();
+        for quest in crate::ordering::sorted_quests(db) {
+            for (position, reward) in quest.rewards.iter().enumerate() {
+                for stack in reward.items.iter().chain(&reward.choices) {
+                    if stack.id != LOOT_CHEST_ITEM {
+                        continue;
+                    }
+                    let rarity = stack.damage.unwrap_or(0);
+                    out.push(LootChestReward {
+                        quest: quest.id,
+                        reward: position,
+                        rarity,
+                        count: stack.count.unwrap_or(1),
+                        groups: if rarity <= MAX_RARITY {
+                            self.groups_for_rarity(rarity)
+                        } else {
+                            Vec::new()
+                        },
+                    });
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Parse the `DefaultLoot.json` belonging to the `DefaultQuests` folder at
+/// `root`: the one next to the folder, or else one inside it. `None` when the
+/// pack has neither.
+pub fn parse_default_loot_from_source(
+    source: &dyn QuestDataSource,
+    root: &str,
+) -> Result<Option<LootTable>> {
+    let root = root.trim_end_matches('/');
+    let sibling = match root.rsplit_once('/') {
+        Some((parent, _)) => format!("{}/DefaultLoot.json", parent),
+        None => "DefaultLoot.json".to_string(),
+    };
+    for path in [sibling, format!("{}/DefaultLoot.json", root)] {
+        if source.is_file(&path) {
+            let value = read_json_file(source, &path)?;
+            return LootTable::from_value(value)
+                .map(Some)
+                .map_err(|e| e.in_file(path, None));
+        }
+    }
+    Ok(None)
+}
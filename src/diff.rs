@@ -0,0 +1,299 @@
+//! Differences between two versions of a quest database.
+//!
+//! `compare` matches quests and questlines by id and reports what was added,
+//! removed or changed. Tasks and rewards are compared structurally but
+//! reported through their `crate::text` renderings, which is what a reviewer
+//! or changelog wants to read. Other quest and questline properties are
+//! compared field by field, using the model's field names (the same names
+//! `edit::Editor::set_property` takes). All lists are sorted by id.
+use crate::export::plain_line_name;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::{render_reward, render_task, strip_formatting};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// A quest or questline identified by id and display name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestRef {
+    /// Quest or questline id.
+    pub id: QuestId,
+    /// Plain-text name (empty when unnamed).
+    pub name: String,
+}
+
+/// A property whose value differs between the two versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyChange {
+    /// Property name, e.g. `quest_logic` or `repeat_time`.
+    pub field: String,
+    /// Value in the old version (`None` when unset).
+    pub old: Option<Value>,
+    /// Value in the new version (`None` when unset).
+    pub new: Option<Value>,
+}
+
+/// One change to a quest present in both versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuestChange {
+    /// The name changed.
+    Renamed { old: String, new: String },
+    /// The description changed.
+    DescriptionChanged,
+    /// Prerequisites (required and optional alike) were added or removed.
+    PrerequisitesChanged {
+        added: Vec<QuestId>,
+        removed: Vec<QuestId>,
+    },
+    /// The task list changed; one rendered line per task.
+    TasksChanged { old: Vec<String>, new: Vec<String> },
+    /// The reward list changed; one rendered line per reward.
+    RewardsChanged { old: Vec<String>, new: Vec<String> },
+    /// Other properties (logic, repeat time, visibility, ...) changed.
+    PropertiesChanged { fields: Vec<PropertyChange> },
+}
+
+/// Changes to a single quest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestDiff {
+    /// The quest, named as in the new version.
+    pub quest: QuestRef,
+    /// Changes in a fixed order: name, description, prerequisites, tasks,
+    /// rewards, other properties.
+    pub changes: Vec<QuestChange>,
+}
+
+/// Changes to a questline present in both versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestLineDiff {
+    /// The questline, named as in the new version.
+    pub questline: QuestRef,
+    /// Previous name when the line was renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_name: Option<String>,
+    /// Quests placed on the line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<QuestId>,
+    /// Quests taken off the line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<QuestId>,
+    /// Quests whose position or size on the line changed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub moved: Vec<QuestId>,
+    /// Changed properties other than the name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<PropertyChange>,
+}
+
+/// Result of `compare`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbDiff {
+    /// Quests only in the new version.
+    pub added_quests: Vec<QuestRef>,
+    /// Quests only in the old version.
+    pub removed_quests: Vec<QuestRef>,
+    /// Quests in both versions that differ.
+    pub changed_quests: Vec<QuestDiff>,
+    /// Questlines only in the new version.
+    pub added_questlines: Vec<QuestRef>,
+    /// Questlines only in the old version.
+    pub removed_questlines: Vec<QuestRef>,
+    /// Questlines in both versions that differ.
+    pub changed_questlines: Vec<QuestLineDiff>,
+}
+
+impl DbDiff {
+    /// Returns true if the two databases are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.added_quests.is_empty()
+            && self.removed_quests.is_empty()
+            && self.changed_quests.is_empty()
+            && self.added_questlines.is_empty()
+            && self.removed_questlines.is_empty()
+            && self.changed_questlines.is_empty()
+    }
+}
+
+fn quest_ref(quest: &Quest) -> QuestRef {
+    QuestRef {
+        id: quest.id,
+        name: strip_formatting(quest.name().unwrap_or_default()),
+    }
+}
+
+fn line_ref(line: &QuestLine) -> QuestRef {
+    QuestRef {
+        id: line.id,
+        name: plain_line_name(line),
+    }
+}
+
+fn all_prereqs(quest: &Quest) -> BTreeSet<QuestId> {
+    quest
+        .prerequisites
+        .iter()
+        .chain(&quest.required_prerequisites)
+        .chain(&quest.optional_prerequisites)
+        .copied()
+        .collect()
+}
+
+/// Set properties as a map from field name to value; unset fields (`null`)
+/// are left out.
+fn property_values<P: Serialize>(props: Option<&P>) -> serde_json::Map<String, Value> {
+    match props.map(serde_json::to_value) {
+        Some(Ok(Value::Object(m))) => m.into_iter().filter(|(_, v)| !v.is_null()).collect(),
+        _ => serde_json::Map::new(),
+    }
+}
+
+/// Field-level differences between two property sets, sorted by field and
+/// ignoring the fields in `skip`.
+fn property_changes<P: Serialize>(
+    old: Option<&P>,
+    new: Option<&P>,
+    skip: &[&str],
+) -> Vec<PropertyChange> {
+    let (mut before, mut after) = (property_values(old), property_values(new));
+    let fields: BTreeSet<String> = before.keys().chain(after.keys()).cloned().collect();
+    fields
+        .into_iter()
+        .filter(|f| !skip.contains(&f.as_str()))
+        .filter_map(|field| {
+            let (old, new) = (before.remove(&field), after.remove(&field));
+            (old != new).then_some(PropertyChange { field, old, new })
+        })
+        .collect()
+}
+
+fn diff_quest(old: &Quest, new: &Quest) -> Vec<QuestChange> {
+    let mut changes = Vec::new();
+    let (old_name, new_name) = (quest_ref(old).name, quest_ref(new).name);
+    if old_name != new_name {
+        changes.push(QuestChange::Renamed {
+            old: old_name,
+            new: new_name,
+        });
+    }
+    let desc = |q: &Quest| q.properties.as_ref().and_then(|p| p.desc.clone());
+    if desc(old) != desc(new) {
+        changes.push(QuestChange::DescriptionChanged);
+    }
+    let (before, after) = (all_prereqs(old), all_prereqs(new));
+    if before != after {
+        changes.push(QuestChange::PrerequisitesChanged {
+            added: after.difference(&before).copied().collect(),
+            removed: before.difference(&after).copied().collect(),
+        });
+    }
+    if old.tasks != new.tasks {
+        changes.push(QuestChange::TasksChanged {
+            old: old.tasks.iter().map(render_task).collect(),
+            new: new.tasks.iter().map(render_task).collect(),
+        });
+    }
+    if old.rewards != new.rewards {
+        changes.push(QuestChange::RewardsChanged {
+            old: old.rewards.iter().map(render_reward).collect(),
+            new: new.rewards.iter().map(render_reward).collect(),
+        });
+    }
+    let fields = property_changes(
+        old.properties.as_ref(),
+        new.properties.as_ref(),
+        &["name", "desc"],
+    );
+    if !fields.is_empty() {
+        changes.push(QuestChange::PropertiesChanged { fields });
+    }
+    changes
+}
+
+/// Position and size of a questline entry.
+type Placement = (Option<i32>, Option<i32>, Option<i32>, Option<i32>);
+
+fn diff_line(old: &QuestLine, new: &QuestLine) -> Option<QuestLineDiff> {
+    let layout = |line: &QuestLine| -> HashMap<QuestId, Placement> {
+        line.entries
+            .iter()
+            .map(|e| (e.quest_id, (e.x, e.y, e.size_x, e.size_y)))
+            .collect()
+    };
+    let (before, after) = (layout(old), layout(new));
+    let mut added: Vec<QuestId> = after
+        .keys()
+        .filter(|q| !before.contains_key(q))
+        .copied()
+        .collect();
+    let mut removed: Vec<QuestId> = before
+        .keys()
+        .filter(|q| !after.contains_key(q))
+        .copied()
+        .collect();
+    let mut moved: Vec<QuestId> = after
+        .iter()
+        .filter(|(q, pos)| before.get(q).is_some_and(|old| old != *pos))
+        .map(|(q, _)| *q)
+        .collect();
+    added.sort();
+    removed.sort();
+    moved.sort();
+    let old_name = Some(plain_line_name(old)).filter(|n| *n != plain_line_name(new));
+    let properties = property_changes(old.properties.as_ref(), new.properties.as_ref(), &["name"]);
+    if added.is_empty()
+        && removed.is_empty()
+        && moved.is_empty()
+        && old_name.is_none()
+        && properties.is_empty()
+    {
+        return None;
+    }
+    Some(QuestLineDiff {
+        questline: line_ref(new),
+        old_name,
+        added,
+        removed,
+        moved,
+        properties,
+    })
+}
+
+/// Compare two databases, `old` being the earlier version.
+pub fn compare(old: &QuestDatabase, new: &QuestDatabase) -> DbDiff {
+    let mut diff = DbDiff::default();
+
+    let mut ids: Vec<&QuestId> = old.quests.keys().chain(new.quests.keys()).collect();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        match (old.quests.get(id), new.quests.get(id)) {
+            (None, Some(q)) => diff.added_quests.push(quest_ref(q)),
+            (Some(q), None) => diff.removed_quests.push(quest_ref(q)),
+            (Some(a), Some(b)) if a != b => {
+                let changes = diff_quest(a, b);
+                if !changes.is_empty() {
+                    diff.changed_quests.push(QuestDiff {
+                        quest: quest_ref(b),
+                        changes,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut ids: Vec<&QuestId> = old.questlines.keys().chain(new.questlines.keys()).collect();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        match (old.questlines.get(id), new.questlines.get(id)) {
+            (None, Some(l)) => diff.added_questlines.push(line_ref(l)),
+            (Some(l), None) => diff.removed_questlines.push(line_ref(l)),
+            (Some(a), Some(b)) => diff.changed_questlines.extend(diff_line(a, b)),
+            _ => {}
+        }
+    }
+    diff
+}
@@ -0,0 +1,816 @@
+//! Editing API with a stable change event model.
+//!
+//! Every mutation made through an `Editor` is expressed as `DatabaseChange`
+//! events. Each event carries enough data to be reverted (`inverse`), which is
+//! what undo/redo is built on, and tells consumers exactly what was touched:
+//! `db::write_changes_to_sink` rewrites only the affected files instead of
+//! the whole pack.
+//!
+//! Events serialize with a `kind` tag and are applied with `apply`; an editor
+//! user can persist or replay them independently of the editor itself.
+//!
+//! For one-off edits without history, `QuestDatabase` has mutation methods
+//! (`add_quest`, `remove_quest`, `update_properties`, `add_prerequisite`,
+//! `move_questline_entry`) built on the same events.
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One atomic mutation of a `QuestDatabase`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DatabaseChange {
+    /// A quest was inserted.
+    QuestAdded { quest: Box<Quest> },
+    /// A quest was deleted; `quest` is its last state.
+    QuestRemoved { quest: Box<Quest> },
+    /// A quest property changed. `field` is the property's model name (e.g.
+    /// `name`, `quest_logic`) or an unmodeled key kept in `extra`; `None`
+    /// means unset.
+    PropertyChanged {
+        quest: QuestId,
+        field: String,
+        old: Option<Value>,
+        new: Option<Value>,
+    },
+    /// A prerequisite was inserted into the lists of `quest` named in
+    /// `positions`, at the recorded index of each.
+    PrereqAdded {
+        quest: QuestId,
+        prerequisite: QuestId,
+        positions: PrereqPositions,
+    },
+    /// A prerequisite was removed from the lists of `quest` named in
+    /// `positions`; each index is where it sat before the removal.
+    PrereqRemoved {
+        quest: QuestId,
+        prerequisite: QuestId,
+        positions: PrereqPositions,
+    },
+    /// An entry was inserted into a questline at `index`.
+    EntryAdded {
+        questline: QuestId,
+        index: usize,
+        entry: QuestLineEntry,
+    },
+    /// The entry at `index` was removed from a questline.
+    EntryRemoved {
+        questline: QuestId,
+        index: usize,
+        entry: QuestLineEntry,
+    },
+    /// A questline entry changed position.
+    EntryMoved {
+        questline: QuestId,
+        quest: QuestId,
+        from: (Option<i32>, Option<i32>),
+        to: (Option<i32>, Option<i32>),
+    },
+}
+
+/// Index of a prerequisite in each prerequisite list of a quest; `None` for
+/// the lists a `PrereqAdded`/`PrereqRemoved` leaves alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrereqPositions {
+    pub prerequisites: Option<usize>,
+    pub required_prerequisites: Option<usize>,
+    pub optional_prerequisites: Option<usize>,
+}
+
+impl PrereqPositions {
+    /// Where `prerequisite` currently sits in each list of `quest`.
+    fn of(quest: &Quest, prerequisite: QuestId) -> PrereqPositions {
+        let find = |list: &[QuestId]| list.iter().position(|p| *p == prerequisite);
+        PrereqPositions {
+            prerequisites: find(&quest.prerequisites),
+            required_prerequisites: find(&quest.required_prerequisites),
+            optional_prerequisites: find(&quest.optional_prerequisites),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == PrereqPositions::default()
+    }
+
+    fn lists<'a>(&self, quest: &'a mut Quest) -> Vec<(&'a mut Vec<QuestId>, usize)> {
+        [
+            (&mut quest.prerequisites, self.prerequisites),
+            (
+                &mut quest.required_prerequisites,
+                self.required_prerequisites,
+            ),
+            (
+                &mut quest.optional_prerequisites,
+                self.optional_prerequisites,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(list, index)| index.map(|i| (list, i)))
+        .collect()
+    }
+}
+
+impl DatabaseChange {
+    /// The change undoing this one.
+    pub fn inverse(&self) -> DatabaseChange {
+        use DatabaseChange::*;
+        match self.clone() {
+            QuestAdded { quest } => QuestRemoved { quest },
+            QuestRemoved { quest } => QuestAdded { quest },
+            PropertyChanged {
+                quest,
+                field,
+                old,
+                new,
+            } => PropertyChanged {
+                quest,
+                field,
+                old: new,
+                new: old,
+            },
+            PrereqAdded {
+                quest,
+                prerequisite,
+                positions,
+            } => PrereqRemoved {
+                quest,
+                prerequisite,
+                positions,
+            },
+            PrereqRemoved {
+                quest,
+                prerequisite,
+                positions,
+            } => PrereqAdded {
+                quest,
+                prerequisite,
+                positions,
+            },
+            EntryAdded {
+                questline,
+                index,
+                entry,
+            } => EntryRemoved {
+                questline,
+                index,
+                entry,
+            },
+            EntryRemoved {
+                questline,
+                index,
+                entry,
+            } => EntryAdded {
+                questline,
+                index,
+                entry,
+            },
+            EntryMoved {
+                questline,
+                quest,
+                from,
+                to,
+            } => EntryMoved {
+                questline,
+                quest,
+                from: to,
+                to: from,
+            },
+        }
+    }
+}
+
+fn quest_mut(db: &mut QuestDatabase, id: QuestId) -> Result<&mut Quest> {
+    db.quests
+        .get_mut(&id)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no quest {}", id)))
+}
+
+fn questline_mut(db: &mut QuestDatabase, id: QuestId) -> Result<&mut QuestLine> {
+    db.questlines
+        .get_mut(&id)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no questline {}", id)))
+}
+
+/// `props` as a JSON object (model field names).
+fn properties_object(props: &QuestProperties) -> Result<serde_json::Map<String, Value>> {
+    match serde_json::to_value(props)? {
+        Value::Object(m) => Ok(m),
+        other => Err(ParseError::InvalidFormat(format!(
+            "properties serialized to {} instead of an object",
+            other
+        ))),
+    }
+}
+
+/// Properties of `quest` as a JSON object (model field names).
+fn property_map(quest: &Quest) -> Result<serde_json::Map<String, Value>> {
+    let props = quest.properties.as_ref().ok_or_else(|| {
+        ParseError::InvalidFormat(format!("quest {} has no properties", quest.id))
+    })?;
+    properties_object(props)
+}
+
+/// Current value of property `field` of `quest` (`None` when unset).
+pub fn property(quest: &Quest, field: &str) -> Result<Option<Value>> {
+    Ok(property_map(quest)?.remove(field).filter(|v| !v.is_null()))
+}
+
+/// Apply `change` to `db`. Fails without modifying `db` when the change does
+/// not fit (unknown quest or questline, duplicate quest, entry index out of
+/// range, property value of the wrong type).
+pub fn apply(db: &mut QuestDatabase, change: &DatabaseChange) -> Result<()> {
+    match change {
+        DatabaseChange::QuestAdded { quest } => {
+            if db.quests.contains_key(&quest.id) {
+                return Err(ParseError::DuplicateQuestId(quest.id.to_string()));
+            }
+            db.quests.insert(quest.id, (**quest).clone());
+        }
+        DatabaseChange::QuestRemoved { quest } => {
+            quest_mut(db, quest.id)?;
+            db.quests.remove(&quest.id);
+        }
+        DatabaseChange::PropertyChanged {
+            quest, field, new, ..
+        } => {
+            let q = quest_mut(db, *quest)?;
+            let mut m = property_map(q)?;
+            match new {
+                Some(v) => m.insert(field.clone(), v.clone()),
+                None => m.remove(field),
+            };
+            q.properties = Some(serde_json::from_value(Value::Object(m))?);
+        }
+        DatabaseChange::PrereqAdded {
+            quest,
+            prerequisite,
+            positions,
+        } => {
+            let q = quest_mut(db, *quest)?;
+            let lists = positions.lists(q);
+            for (list, index) in &lists {
+                if *index > list.len() || list.contains(prerequisite) {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "cannot insert prerequisite {} of quest {} at {}",
+                        prerequisite, quest, index
+                    )));
+                }
+            }
+            for (list, index) in lists {
+                list.insert(index, *prerequisite);
+            }
+        }
+        DatabaseChange::PrereqRemoved {
+            quest,
+            prerequisite,
+            positions,
+        } => {
+            let q = quest_mut(db, *quest)?;
+            let lists = positions.lists(q);
+            for (list, index) in &lists {
+                if list.get(*index) != Some(prerequisite) {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "quest {} has no prerequisite {} at {}",
+                        quest, prerequisite, index
+                    )));
+                }
+            }
+            for (list, index) in lists {
+                list.remove(index);
+            }
+        }
+        DatabaseChange::EntryAdded {
+            questline,
+            index,
+            entry,
+        } => {
+            let line = questline_mut(db, *questline)?;
+            if *index > line.entries.len() {
+                return Err(ParseError::NumberOutOfRange {
+                    field: "entry index".to_string(),
+                    value: index.to_string(),
+                });
+            }
+            line.entries.insert(*index, entry.clone());
+        }
+        DatabaseChange::EntryRemoved {
+            questline, index, ..
+        } => {
+            let line = questline_mut(db, *questline)?;
+            if *index >= line.entries.len() {
+                return Err(ParseError::NumberOutOfRange {
+                    field: "entry index".to_string(),
+                    value: index.to_string(),
+                });
+            }
+            line.entries.remove(*index);
+        }
+        DatabaseChange::EntryMoved {
+            questline,
+            quest,
+            to,
+            ..
+        } => {
+            let line = questline_mut(db, *questline)?;
+            let entry = line
+                .entries
+                .iter_mut()
+                .find(|e| e.quest_id == *quest)
+                .ok_or(ParseError::MissingQuestReference {
+                    questline: *questline,
+                    quest_id: *quest,
+                })?;
+            (entry.x, entry.y) = *to;
+        }
+    }
+    Ok(())
+}
+
+/// Apply `changes` in order. On error, the ones already applied are rolled
+/// back and `db` is left unchanged; should the rollback itself fail, the
+/// error says so and `db` is left partially edited.
+pub fn apply_all(db: &mut QuestDatabase, changes: &[DatabaseChange]) -> Result<()> {
+    for (i, change) in changes.iter().enumerate() {
+        if let Err(e) = apply(db, change) {
+            for done in changes[..i].iter().rev() {
+                apply(db, &done.inverse()).map_err(|undo| {
+                    ParseError::Other(format!("{}; rolling back failed: {}", e, undo))
+                })?;
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Changes deleting quest `id` and every reference to it: prerequisite
+/// entries of other quests first, then questline entries, then the quest.
+fn remove_quest_changes(db: &QuestDatabase, id: QuestId) -> Result<Vec<DatabaseChange>> {
+    let quest = db
+        .quests
+        .get(&id)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no quest {}", id)))?;
+    let mut changes = Vec::new();
+    let mut dependents: Vec<&Quest> = db.quests.values().collect();
+    dependents.sort_by_key(|q| q.id);
+    for q in dependents {
+        let positions = PrereqPositions::of(q, id);
+        if !positions.is_empty() {
+            changes.push(DatabaseChange::PrereqRemoved {
+                quest: q.id,
+                prerequisite: id,
+                positions,
+            });
+        }
+    }
+    let mut lines: Vec<&QuestLine> = db.questlines.values().collect();
+    lines.sort_by_key(|l| l.id);
+    for line in lines {
+        // from the back so earlier indices stay valid
+        for (index, entry) in line.entries.iter().enumerate().rev() {
+            if entry.quest_id == id {
+                changes.push(DatabaseChange::EntryRemoved {
+                    questline: line.id,
+                    index,
+                    entry: entry.clone(),
+                });
+            }
+        }
+    }
+    changes.push(DatabaseChange::QuestRemoved {
+        quest: Box::new(quest.clone()),
+    });
+    Ok(changes)
+}
+
+/// The change making `prerequisite` a required (or optional) prerequisite of
+/// `quest`; empty when it already is one. Both quests must exist and differ.
+fn add_prerequisite_changes(
+    db: &QuestDatabase,
+    quest: QuestId,
+    prerequisite: QuestId,
+    optional: bool,
+) -> Result<Vec<DatabaseChange>> {
+    if quest == prerequisite {
+        return Err(ParseError::InvalidFormat(format!(
+            "quest {} cannot require itself",
+            quest
+        )));
+    }
+    if !db.quests.contains_key(&prerequisite) {
+        return Err(ParseError::InvalidFormat(format!(
+            "no quest {}",
+            prerequisite
+        )));
+    }
+    let q = db
+        .quests
+        .get(&quest)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no quest {}", quest)))?;
+    // append to each list of the group that lacks it
+    let present = PrereqPositions::of(q, prerequisite);
+    let append = |list: &[QuestId], index: Option<usize>| match index {
+        Some(_) => None,
+        None => Some(list.len()),
+    };
+    let positions = if optional {
+        PrereqPositions {
+            optional_prerequisites: append(
+                &q.optional_prerequisites,
+                present.optional_prerequisites,
+            ),
+            ..Default::default()
+        }
+    } else {
+        PrereqPositions {
+            prerequisites: append(&q.prerequisites, present.prerequisites),
+            required_prerequisites: append(
+                &q.required_prerequisites,
+                present.required_prerequisites,
+            ),
+            ..Default::default()
+        }
+    };
+    if positions.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(vec![DatabaseChange::PrereqAdded {
+        quest,
+        prerequisite,
+        positions,
+    }])
+}
+
+/// The change appending `entry` to `questline`; empty when the questline
+/// already lists the quest. The questline and the quest must exist.
+fn add_entry_changes(
+    db: &QuestDatabase,
+    questline: QuestId,
+    entry: QuestLineEntry,
+) -> Result<Vec<DatabaseChange>> {
+    let line = db
+        .questlines
+        .get(&questline)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no questline {}", questline)))?;
+    if !db.quests.contains_key(&entry.quest_id) {
+        return Err(ParseError::MissingQuestReference {
+            questline,
+            quest_id: entry.quest_id,
+        });
+    }
+    if line.entries.iter().any(|e| e.quest_id == entry.quest_id) {
+        return Ok(Vec::new());
+    }
+    Ok(vec![DatabaseChange::EntryAdded {
+        questline,
+        index: line.entries.len(),
+        entry,
+    }])
+}
+
+fn move_entry_change(
+    db: &QuestDatabase,
+    questline: QuestId,
+    quest: QuestId,
+    x: i32,
+    y: i32,
+) -> Result<DatabaseChange> {
+    let line = db
+        .questlines
+        .get(&questline)
+        .ok_or_else(|| ParseError::InvalidFormat(format!("no questline {}", questline)))?;
+    let from = line
+        .entries
+        .iter()
+        .find(|e| e.quest_id == quest)
+        .map(|e| (e.x, e.y))
+        .ok_or(ParseError::MissingQuestReference {
+            questline,
+            quest_id: quest,
+        })?;
+    Ok(DatabaseChange::EntryMoved {
+        questline,
+        quest,
+        from,
+        to: (Some(x), Some(y)),
+    })
+}
+
+/// Direct mutation without history.
+///
+/// These methods edit the database in place through the same
+/// `DatabaseChange` events as `Editor`, so cross-references stay consistent
+/// and a failed call leaves the database unchanged. Each returns the changes
+/// it applied, ready for `db::write_changes`.
+impl QuestDatabase {
+    /// Insert a new quest. Fails when the id is taken.
+    pub fn add_quest(&mut self, quest: Quest) -> Result<Vec<DatabaseChange>> {
+        let changes = vec![DatabaseChange::QuestAdded {
+            quest: Box::new(quest),
+        }];
+        apply_all(self, &changes)?;
+        Ok(changes)
+    }
+
+    /// Delete a quest and strip it from every prerequisite list and
+    /// questline.
+    pub fn remove_quest(&mut self, id: QuestId) -> Result<Vec<DatabaseChange>> {
+        let changes = remove_quest_changes(self, id)?;
+        apply_all(self, &changes)?;
+        Ok(changes)
+    }
+
+    /// Edit the properties of `quest` with `update`. Every field that
+    /// changed becomes one `PropertyChanged` event. Fails when the quest is
+    /// missing or has no properties.
+    pub fn update_properties(
+        &mut self,
+        quest: QuestId,
+        update: impl FnOnce(&mut QuestProperties),
+    ) -> Result<Vec<DatabaseChange>> {
+        let q = quest_mut(self, quest)?;
+        let before = property_map(q)?;
+        let mut props = q.properties.clone().ok_or_else(|| {
+            ParseError::InvalidFormat(format!("quest {} has no properties", quest))
+        })?;
+        update(&mut props);
+        let after = properties_object(&props)?;
+        let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        let set = |m: &serde_json::Map<String, Value>, f: &str| {
+            m.get(f).filter(|v| !v.is_null()).cloned()
+        };
+        let changes: Vec<DatabaseChange> = fields
+            .into_iter()
+            .filter_map(|field| {
+                let (old, new) = (set(&before, field), set(&after, field));
+                (old != new).then(|| DatabaseChange::PropertyChanged {
+                    quest,
+                    field: field.clone(),
+                    old,
+                    new,
+                })
+            })
+            .collect();
+        apply_all(self, &changes)?;
+        Ok(changes)
+    }
+
+    /// Make `prerequisite` a required (or optional) prerequisite of `quest`.
+    /// Both quests must exist and differ; adding an existing prerequisite
+    /// changes nothing.
+    pub fn add_prerequisite(
+        &mut self,
+        quest: QuestId,
+        prerequisite: QuestId,
+        optional: bool,
+    ) -> Result<Vec<DatabaseChange>> {
+        let changes = add_prerequisite_changes(self, quest, prerequisite, optional)?;
+        apply_all(self, &changes)?;
+        Ok(changes)
+    }
+
+    /// Move the entry of `quest` in `questline` to `(x, y)`.
+    pub fn move_questline_entry(
+        &mut self,
+        questline: QuestId,
+        quest: QuestId,
+        x: i32,
+        y: i32,
+    ) -> Result<Vec<DatabaseChange>> {
+        let changes = vec![move_entry_change(self, questline, quest, x, y)?];
+        apply_all(self, &changes)?;
+        Ok(changes)
+    }
+}
+
+/// A `QuestDatabase` with undo/redo history.
+///
+/// Each editing method is one undo step made of one or more
+/// `DatabaseChange`s, unless a transaction is open: between `begin` and
+/// `commit` all edits form a single step, and `rollback` reverts them.
+/// Transactions nest; an inner commit folds its edits into the enclosing
+/// transaction.
+///
+/// Applied changes, including those replayed by `undo`, `redo` and
+/// `rollback`, queue up until `take_changes` hands them to a consumer such as
+/// the writer.
+#[derive(Debug, Clone)]
+pub struct Editor {
+    db: QuestDatabase,
+    undo: Vec<Vec<DatabaseChange>>,
+    redo: Vec<Vec<DatabaseChange>>,
+    /// Changes of each open transaction, outermost first.
+    open: Vec<Vec<DatabaseChange>>,
+    pending: Vec<DatabaseChange>,
+}
+
+impl Editor {
+    /// Start editing `db` with an empty history.
+    pub fn new(db: QuestDatabase) -> Self {
+        Editor {
+            db,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            open: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// The current state.
+    pub fn db(&self) -> &QuestDatabase {
+        &self.db
+    }
+
+    /// Finish editing and return the database.
+    pub fn into_inner(self) -> QuestDatabase {
+        self.db
+    }
+
+    /// Changes applied since the last call, in order.
+    pub fn take_changes(&mut self) -> Vec<DatabaseChange> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Apply `changes` as a single step; on error `db` is left unchanged.
+    fn run(&mut self, changes: Vec<DatabaseChange>) -> Result<()> {
+        apply_all(&mut self.db, &changes)?;
+        self.pending.extend(changes);
+        Ok(())
+    }
+
+    /// Apply `changes` as one undoable step (or as part of the open
+    /// transaction) and clear the redo history.
+    pub fn edit(&mut self, changes: Vec<DatabaseChange>) -> Result<()> {
+        self.run(changes.clone())?;
+        if let Some(open) = self.open.last_mut() {
+            open.extend(changes);
+        } else if !changes.is_empty() {
+            self.undo.push(changes);
+            self.redo.clear();
+        }
+        Ok(())
+    }
+
+    /// Open a transaction: edits until the matching `commit` form one undo
+    /// step.
+    pub fn begin(&mut self) {
+        self.open.push(Vec::new());
+    }
+
+    /// Returns true while a transaction is open.
+    pub fn in_transaction(&self) -> bool {
+        !self.open.is_empty()
+    }
+
+    fn no_transaction() -> ParseError {
+        ParseError::Other("no open transaction".to_string())
+    }
+
+    /// Close the innermost transaction, keeping its edits.
+    pub fn commit(&mut self) -> Result<()> {
+        let changes = self.open.pop().ok_or_else(Self::no_transaction)?;
+        if let Some(outer) = self.open.last_mut() {
+            outer.extend(changes);
+        } else if !changes.is_empty() {
+            self.undo.push(changes);
+            self.redo.clear();
+        }
+        Ok(())
+    }
+
+    /// Close the innermost transaction, reverting its edits.
+    pub fn rollback(&mut self) -> Result<()> {
+        let changes = self.open.last().ok_or_else(Self::no_transaction)?;
+        let inverse = changes.iter().rev().map(DatabaseChange::inverse).collect();
+        self.run(inverse)?;
+        self.open.pop();
+        Ok(())
+    }
+
+    /// Insert a new quest.
+    pub fn add_quest(&mut self, quest: Quest) -> Result<()> {
+        self.edit(vec![DatabaseChange::QuestAdded {
+            quest: Box::new(quest),
+        }])
+    }
+
+    /// Delete a quest together with every prerequisite and questline entry
+    /// referring to it, so the database stays consistent.
+    pub fn remove_quest(&mut self, id: QuestId) -> Result<()> {
+        let changes = remove_quest_changes(&self.db, id)?;
+        self.edit(changes)
+    }
+
+    /// Set (or with `None`, unset) a quest property. Writing the current
+    /// value records no step.
+    pub fn set_property(
+        &mut self,
+        quest: QuestId,
+        field: &str,
+        value: Option<Value>,
+    ) -> Result<()> {
+        let old = property(quest_mut(&mut self.db, quest)?, field)?;
+        if old == value {
+            return Ok(());
+        }
+        self.edit(vec![DatabaseChange::PropertyChanged {
+            quest,
+            field: field.to_string(),
+            old,
+            new: value,
+        }])
+    }
+
+    /// Make `prerequisite` a required (or optional) prerequisite of `quest`.
+    /// Both quests must exist and differ; adding an existing prerequisite
+    /// records no step.
+    pub fn add_prerequisite(
+        &mut self,
+        quest: QuestId,
+        prerequisite: QuestId,
+        optional: bool,
+    ) -> Result<()> {
+        let changes = add_prerequisite_changes(&self.db, quest, prerequisite, optional)?;
+        self.edit(changes)
+    }
+
+    /// Drop `prerequisite` from every prerequisite list of `quest`.
+    pub fn remove_prerequisite(&mut self, quest: QuestId, prerequisite: QuestId) -> Result<()> {
+        let positions = PrereqPositions::of(quest_mut(&mut self.db, quest)?, prerequisite);
+        if positions.is_empty() {
+            return Ok(());
+        }
+        self.edit(vec![DatabaseChange::PrereqRemoved {
+            quest,
+            prerequisite,
+            positions,
+        }])
+    }
+
+    /// Append an entry to a questline. The quest must exist; a quest the
+    /// questline already lists records no step.
+    pub fn add_entry(&mut self, questline: QuestId, entry: QuestLineEntry) -> Result<()> {
+        let changes = add_entry_changes(&self.db, questline, entry)?;
+        self.edit(changes)
+    }
+
+    /// Move the entry of `quest` in a questline to `(x, y)`.
+    pub fn move_entry(&mut self, questline: QuestId, quest: QuestId, x: i32, y: i32) -> Result<()> {
+        let change = move_entry_change(&self.db, questline, quest, x, y)?;
+        self.edit(vec![change])
+    }
+
+    /// Returns true if there is a step to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is a step to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    fn check_no_transaction(&self) -> Result<()> {
+        if self.in_transaction() {
+            return Err(ParseError::Other(
+                "cannot undo or redo inside a transaction".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Revert the last step. Returns false when there is nothing to undo.
+    /// Fails while a transaction is open.
+    pub fn undo(&mut self) -> Result<bool> {
+        self.check_no_transaction()?;
+        let Some(step) = self.undo.pop() else {
+            return Ok(false);
+        };
+        let inverse = step.iter().rev().map(DatabaseChange::inverse).collect();
+        if let Err(e) = self.run(inverse) {
+            self.undo.push(step);
+            return Err(e);
+        }
+        self.redo.push(step);
+        Ok(true)
+    }
+
+    /// Re-apply the last undone step. Returns false when there is nothing to
+    /// redo. Fails while a transaction is open.
+    pub fn redo(&mut self) -> Result<bool> {
+        self.check_no_transaction()?;
+        let Some(step) = self.redo.pop() else {
+            return Ok(false);
+        };
+        if let Err(e) = self.run(step.clone()) {
+            self.redo.push(step);
+            return Err(e);
+        }
+        self.undo.push(step);
+        Ok(true)
+    }
+}
@@ -5,19 +5,55 @@ use thiserror::Error;
 pub enum ParseError {
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
+    #[cfg(feature = "simd-json")]
+    #[error("json error: {0}")]
+    SimdJson(#[from] simd_json::Error),
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("invalid format: {0}")]
     InvalidFormat(String),
 
+    /// A file of a pack failed to parse. `pointer` is a JSON pointer to the
+    /// offending value when it could be located.
+    #[error(
+        "{path}{}: {source}",
+        .pointer.as_ref().map(|p| format!(" at {}", p)).unwrap_or_default()
+    )]
+    InFile {
+        path: String,
+        pointer: Option<String>,
+        source: Box<ParseError>,
+    },
+
     #[error("duplicate quest id from file: {0}")]
     DuplicateQuestId(String),
 
     #[error(
         "missing quest reference: questline {questline} references missing quest id {quest_id:?}"
     )]
-    MissingQuestReference { questline: u64, quest_id: QuestId },
+    MissingQuestReference {
+        questline: QuestId,
+        quest_id: QuestId,
+    },
+
+    /// A quest name given by the user matches several quests, listed in id
+    /// order.
+    #[error(
+        "ambiguous quest name {name:?}: {} quests match ({})",
+        .matches.len(),
+        .matches.iter().map(QuestId::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    AmbiguousName { name: String, matches: Vec<QuestId> },
+
+    #[error("invalid pack: {}", crate::validate::describe(.0))]
+    Invalid(crate::validate::ValidationIssue),
+
+    #[error("unknown fields: {}", crate::schema::describe(.0))]
+    UnknownFields(Vec<crate::schema::UnknownField>),
+
+    #[error("number out of range for {field}: {value}")]
+    NumberOutOfRange { field: String, value: String },
 
     #[error("cycle detected in prerequisites: {0:?}")]
     CycleDetected(Vec<QuestId>),
@@ -25,8 +61,33 @@ pub enum ParseError {
     #[error("alpha out of range: {0}")]
     AlphaOutOfRange(f64),
 
+    #[error("archive error: {0}")]
+    Archive(String),
+
+    #[error("export error: {0}")]
+    Export(String),
+
+    /// This build cannot do what was asked, usually for lack of a cargo
+    /// feature.
+    #[error("unsupported: {0}")]
+    Unsupported(String),
+
     #[error("other: {0}")]
     Other(String),
 }
 
+impl ParseError {
+    /// Wrap the error as `InFile` for `path`, unless it already names a file.
+    pub fn in_file(self, path: impl Into<String>, pointer: Option<String>) -> Self {
+        match self {
+            ParseError::InFile { .. } => self,
+            source => ParseError::InFile {
+                path: path.into(),
+                pointer,
+                source: Box::new(source),
+            },
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ParseError>;
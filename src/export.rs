@@ -0,0 +1,208 @@
+//! Exporters turning a `QuestDatabase` into other formats.
+//!
+//! Every exporter walks quests and questlines in the orders of
+//! `crate::ordering`, so repeated exports of the same pack are byte-identical.
+//! Format-specific dependencies are behind cargo features named after the
+//! format (e.g. `toml`, `yaml`).
+//!
+//! Floating point values (importance scores, weights) are written through
+//! `format_float`/`round_float` with `FLOAT_PRECISION` decimals. Scores are
+//! sums accumulated in hash-map order, so their last bits can differ between
+//! runs and machines; fixed precision keeps exported diffs clean.
+
+pub mod anki;
+pub mod changelog;
+pub mod corpus;
+pub mod csv;
+pub mod cypher;
+pub mod d3;
+pub mod dot;
+pub mod ftb;
+pub mod graphml;
+pub mod handlers;
+pub mod ical;
+pub mod markdown;
+pub mod matrix;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod scores;
+pub mod summary;
+pub mod svg;
+
+pub use corpus::corpus;
+
+use crate::importance::weighted_prerequisites;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+pub(crate) use crate::ordering::sorted_quests;
+use crate::quest_id::QuestId;
+use crate::text::{render_name, strip_formatting};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Quote a CSV field (RFC 4180) when it contains a separator, quote or newline.
+pub(crate) fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Join fields into one CSV record terminated by `\n`.
+pub(crate) fn csv_record<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let mut line: Vec<Cow<'a, str>> = Vec::new();
+    for f in fields {
+        line.push(csv_field(f));
+    }
+    let mut out = line.join(",");
+    out.push('\n');
+    out
+}
+
+/// Escape text for inclusion in HTML/XML element content or attributes.
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Questline name without formatting codes or surrounding whitespace; empty
+/// when the questline has none.
+pub(crate) fn plain_line_name(line: &QuestLine) -> String {
+    line.properties
+        .as_ref()
+        .map(|p| strip_formatting(&p.name).trim().to_string())
+        .unwrap_or_default()
+}
+
+/// `plain_line_name`, or `Questline <id>` for questlines without a name, as
+/// shown in page titles and file names.
+pub(crate) fn line_name(line: &QuestLine) -> String {
+    Some(plain_line_name(line))
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("Questline {}", line.id))
+}
+
+/// Plain quest name, or `Quest <id>` for missing and unnamed quests.
+pub(crate) fn quest_title(db: &QuestDatabase, quest: &QuestId) -> String {
+    db.quests
+        .get(quest)
+        .map(|q| render_name(q).trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("Quest {}", quest))
+}
+
+/// Questline names (plain text) per quest, in questline order.
+pub(crate) fn questlines_of(db: &QuestDatabase) -> HashMap<QuestId, Vec<String>> {
+    let mut out: HashMap<QuestId, Vec<String>> = HashMap::new();
+    for line in sorted_questlines(db) {
+        let name = plain_line_name(line);
+        if name.is_empty() {
+            continue;
+        }
+        for entry in &line.entries {
+            let names = out.entry(entry.quest_id).or_default();
+            if !names.contains(&name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    out
+}
+
+/// Prerequisite links `(quest, prerequisite, optional)` between existing
+/// quests, ordered by quest then prerequisite id. Required links come from
+/// `prerequisites` when `required_prerequisites` is empty.
+pub(crate) fn prerequisite_links(db: &QuestDatabase) -> Vec<(QuestId, QuestId, bool)> {
+    let mut out = Vec::new();
+    for quest in sorted_quests(db) {
+        let required = if quest.required_prerequisites.is_empty() {
+            &quest.prerequisites
+        } else {
+            &quest.required_prerequisites
+        };
+        let mut prereqs: Vec<(QuestId, bool)> = required
+            .iter()
+            .map(|p| (*p, false))
+            .chain(quest.optional_prerequisites.iter().map(|p| (*p, true)))
+            .filter(|(p, _)| db.quests.contains_key(p))
+            .collect();
+        prereqs.sort();
+        prereqs.dedup_by_key(|(p, _)| *p);
+        out.extend(prereqs.into_iter().map(|(p, opt)| (quest.id, p, opt)));
+    }
+    out
+}
+
+/// `prerequisite_links` with the weight each link carries in
+/// `importance::weighted_prerequisites`; links that carry none (prerequisites
+/// of `XOR` quests) get 0.0.
+pub(crate) fn weighted_links(db: &QuestDatabase) -> Vec<(QuestId, QuestId, bool, f64)> {
+    let weights: HashMap<(QuestId, QuestId), f64> = db
+        .quests
+        .values()
+        .flat_map(|q| {
+            weighted_prerequisites(q)
+                .into_iter()
+                .map(move |(p, w)| ((q.id, p), w))
+        })
+        .collect();
+    prerequisite_links(db)
+        .into_iter()
+        .map(|(q, p, optional)| {
+            let w = weights.get(&(q, p)).copied().unwrap_or(0.0);
+            (q, p, optional, w)
+        })
+        .collect()
+}
+
+/// Decimal places used for floating point values in every export.
+pub const FLOAT_PRECISION: usize = 6;
+
+/// Format `value` with exactly `precision` decimals.
+///
+/// Negative zero (including values that round to it) is written as zero, and
+/// non-finite values as `NaN`, `inf` or `-inf`.
+pub fn format_float(value: f64, precision: usize) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let text = format!("{:.*}", precision, value);
+    match text.strip_prefix('-') {
+        Some(abs) if abs.bytes().all(|b| b == b'0' || b == b'.') => abs.to_string(),
+        _ => text,
+    }
+}
+
+/// Round `value` to `precision` decimals, for exporters that emit numbers
+/// (JSON) rather than text. The result prints as the same digits as
+/// `format_float`, minus trailing zeros.
+pub fn round_float(value: f64, precision: usize) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    format_float(value, precision).parse().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_precision_and_no_negative_zero() {
+        assert_eq!(format_float(0.1 + 0.2, 6), "0.300000");
+        assert_eq!(format_float(-0.0000001, 6), "0.000000");
+        assert_eq!(format_float(-1.5, 2), "-1.50");
+        assert_eq!(format_float(f64::NAN, 6), "NaN");
+    }
+}
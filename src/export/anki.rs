@@ -0,0 +1,98 @@
+//! Flashcard deck export for Anki.
+//!
+//! Each named quest becomes one note: the front is the quest name, the back
+//! is its description followed by the task list. The output is Anki's "notes
+//! in plain text" CSV format with file headers, so it can be imported as-is
+//! (File → Import) and converted into an `.apkg` from there. Quest text goes
+//! through `crate::text`, so cards read the same as other plain-text exports.
+use super::{csv_record, escape_html, sorted_quests};
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::{render_description, render_name, render_task};
+use std::collections::HashMap;
+
+/// One flashcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    /// Quest the card was generated from.
+    pub quest: QuestId,
+    /// Plain-text quest name.
+    pub front: String,
+    /// Plain-text description, then one line per task.
+    pub back: String,
+    /// Anki tags: the names of the questlines containing the quest.
+    pub tags: Vec<String>,
+}
+
+/// Turn a questline name into an Anki tag (tags cannot contain spaces).
+fn tag_for(name: &str) -> String {
+    let plain = crate::text::strip_formatting(name);
+    let words: Vec<&str> = plain.split_whitespace().collect();
+    words.join("_")
+}
+
+/// Build one card per quest with a non-empty name, in ascending id order.
+pub fn cards(db: &QuestDatabase) -> Vec<Card> {
+    let mut lines_of: HashMap<QuestId, Vec<String>> = HashMap::new();
+    for line in sorted_questlines(db) {
+        let Some(props) = &line.properties else {
+            continue;
+        };
+        let tag = tag_for(&props.name);
+        if tag.is_empty() {
+            continue;
+        }
+        for entry in &line.entries {
+            let tags = lines_of.entry(entry.quest_id).or_default();
+            if !tags.contains(&tag) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+
+    sorted_quests(db)
+        .into_iter()
+        .filter_map(|q| {
+            let front = render_name(q).trim().to_string();
+            if front.is_empty() {
+                return None;
+            }
+            let mut back = q
+                .properties
+                .as_ref()
+                .map(render_description)
+                .unwrap_or_default();
+            if !q.tasks.is_empty() {
+                if !back.is_empty() {
+                    back.push_str("\n\n");
+                }
+                back.push_str("Tasks:");
+                for task in &q.tasks {
+                    back.push_str("\n- ");
+                    back.push_str(&render_task(task));
+                }
+            }
+            Some(Card {
+                quest: q.id,
+                front,
+                back,
+                tags: lines_of.remove(&q.id).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Render `db` as an Anki-importable CSV deck (front, back, tags).
+///
+/// Fields are HTML with line breaks as `<br>`, which Anki displays verbatim.
+pub fn to_anki_csv(db: &QuestDatabase) -> String {
+    let mut out = String::from("#separator:Comma\n#html:true\n#tags column:3\n");
+    for card in cards(db) {
+        let front = escape_html(&card.front);
+        let back = escape_html(&card.back).replace('\n', "<br>");
+        let tags = card.tags.join(" ");
+        out.push_str(&csv_record([front.as_str(), back.as_str(), tags.as_str()]));
+    }
+    out
+}
@@ -0,0 +1,243 @@
+//! Human-readable Markdown changelog from a `crate::diff::DbDiff`.
+//!
+//! Lines are phrased for release notes ("Added 12 quests to 'Tier 3: MV'",
+//! "Reward of 'First Steps' changed from 4x minecraft:bread to 8x
+//! minecraft:bread"). `Verbosity` picks between per-category counts, one line
+//! per change, and one line per change with the affected quests and items
+//! spelled out.
+use crate::diff::{DbDiff, PropertyChange, QuestChange, QuestRef};
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How much detail the changelog contains.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verbosity {
+    /// Counts per questline and per category only.
+    Brief,
+    /// One line per added, removed or changed quest and questline.
+    #[default]
+    Normal,
+    /// Like `Normal`, with quest lists, task changes and prerequisite ids.
+    Detailed,
+}
+
+fn quoted(r: &QuestRef) -> String {
+    if r.name.is_empty() {
+        format!("quest {}", r.id)
+    } else {
+        format!("'{}'", r.name)
+    }
+}
+
+fn plural(n: usize, word: &str) -> String {
+    if n == 1 {
+        format!("1 {}", word)
+    } else {
+        format!("{} {}s", n, word)
+    }
+}
+
+/// "changed from a to b" lines for two rendered lists: element-wise when the
+/// lengths match, otherwise the whole lists.
+fn list_changes(what: &str, quest: &str, old: &[String], new: &[String]) -> Vec<String> {
+    if old.len() == new.len() {
+        old.iter()
+            .zip(new)
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| format!("{} of {} changed from {} to {}", what, quest, a, b))
+            .collect()
+    } else {
+        let show = |l: &[String]| {
+            if l.is_empty() {
+                "nothing".to_string()
+            } else {
+                l.join("; ")
+            }
+        };
+        vec![format!(
+            "{}s of {} changed from {} to {}",
+            what,
+            quest,
+            show(old),
+            show(new)
+        )]
+    }
+}
+
+fn setting_value(value: &Option<serde_json::Value>) -> String {
+    match value {
+        None => "unset".to_string(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// One line per changed setting when `detailed`, otherwise one line naming
+/// the changed settings.
+fn setting_changes(owner: &str, fields: &[PropertyChange], detailed: bool) -> Vec<String> {
+    if detailed {
+        fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "Setting {} of {} changed from {} to {}",
+                    f.field,
+                    owner,
+                    setting_value(&f.old),
+                    setting_value(&f.new)
+                )
+            })
+            .collect()
+    } else {
+        let names: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+        vec![format!(
+            "Settings of {} changed ({})",
+            owner,
+            names.join(", ")
+        )]
+    }
+}
+
+fn id_list(ids: &[QuestId], names: &HashMap<QuestId, String>) -> String {
+    ids.iter()
+        .map(|id| {
+            names
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| format!("quest {}", id))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn quest_change_lines(
+    quest: &QuestRef,
+    change: &QuestChange,
+    verbosity: Verbosity,
+    names: &HashMap<QuestId, String>,
+) -> Vec<String> {
+    let q = quoted(quest);
+    let detailed = verbosity >= Verbosity::Detailed;
+    match change {
+        QuestChange::Renamed { old, new } => vec![format!("Renamed '{}' to '{}'", old, new)],
+        QuestChange::DescriptionChanged => vec![format!("Description of {} changed", q)],
+        QuestChange::PrerequisitesChanged { added, removed } if detailed => {
+            let mut parts = Vec::new();
+            if !added.is_empty() {
+                parts.push(format!("now requires {}", id_list(added, names)));
+            }
+            if !removed.is_empty() {
+                parts.push(format!("no longer requires {}", id_list(removed, names)));
+            }
+            vec![format!("{} {}", q, parts.join(" and "))]
+        }
+        QuestChange::PrerequisitesChanged { .. } => {
+            vec![format!("Prerequisites of {} changed", q)]
+        }
+        QuestChange::TasksChanged { old, new } if detailed => list_changes("Task", &q, old, new),
+        QuestChange::TasksChanged { .. } => vec![format!("Tasks of {} changed", q)],
+        QuestChange::RewardsChanged { old, new } => list_changes("Reward", &q, old, new),
+        QuestChange::PropertiesChanged { fields } => setting_changes(&q, fields, detailed),
+    }
+}
+
+fn section(out: &mut String, title: &str, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    }
+    out.push_str(&format!("### {}\n\n", title));
+    for line in lines {
+        out.push_str(&format!("- {}\n", line));
+    }
+    out.push('\n');
+}
+
+/// Render `diff` as a Markdown changelog. Returns `"No changes.\n"` for an
+/// empty diff.
+pub fn to_markdown(diff: &DbDiff, verbosity: Verbosity) -> String {
+    if diff.is_empty() {
+        return "No changes.\n".to_string();
+    }
+    let names: HashMap<QuestId, String> = diff
+        .added_quests
+        .iter()
+        .chain(&diff.removed_quests)
+        .chain(diff.changed_quests.iter().map(|q| &q.quest))
+        .map(|r| (r.id, quoted(r)))
+        .collect();
+    let detailed = verbosity >= Verbosity::Detailed;
+
+    let mut lines_section = Vec::new();
+    for line in &diff.added_questlines {
+        lines_section.push(format!("Added questline {}", quoted(line)));
+    }
+    for line in &diff.removed_questlines {
+        lines_section.push(format!("Removed questline {}", quoted(line)));
+    }
+    for line in &diff.changed_questlines {
+        let l = quoted(&line.questline);
+        if let Some(old) = &line.old_name {
+            lines_section.push(format!("Renamed questline '{}' to {}", old, l));
+        }
+        if !line.added.is_empty() {
+            let mut s = format!("Added {} to {}", plural(line.added.len(), "quest"), l);
+            if detailed {
+                s.push_str(&format!(": {}", id_list(&line.added, &names)));
+            }
+            lines_section.push(s);
+        }
+        if !line.removed.is_empty() {
+            let mut s = format!("Removed {} from {}", plural(line.removed.len(), "quest"), l);
+            if detailed {
+                s.push_str(&format!(": {}", id_list(&line.removed, &names)));
+            }
+            lines_section.push(s);
+        }
+        if detailed && !line.moved.is_empty() {
+            lines_section.push(format!(
+                "Moved {} in {}",
+                plural(line.moved.len(), "quest"),
+                l
+            ));
+        }
+        if !line.properties.is_empty() {
+            lines_section.extend(setting_changes(
+                &format!("questline {}", l),
+                &line.properties,
+                detailed,
+            ));
+        }
+    }
+
+    let mut quest_section = Vec::new();
+    if verbosity == Verbosity::Brief {
+        for (verb, n) in [
+            ("Added", diff.added_quests.len()),
+            ("Removed", diff.removed_quests.len()),
+            ("Changed", diff.changed_quests.len()),
+        ] {
+            if n > 0 {
+                quest_section.push(format!("{} {}", verb, plural(n, "quest")));
+            }
+        }
+    } else {
+        for q in &diff.added_quests {
+            quest_section.push(format!("Added {}", quoted(q)));
+        }
+        for q in &diff.removed_quests {
+            quest_section.push(format!("Removed {}", quoted(q)));
+        }
+        for q in &diff.changed_quests {
+            for change in &q.changes {
+                quest_section.extend(quest_change_lines(&q.quest, change, verbosity, &names));
+            }
+        }
+    }
+
+    let mut out = String::new();
+    section(&mut out, "Questlines", &lines_section);
+    section(&mut out, "Quests", &quest_section);
+    out
+}
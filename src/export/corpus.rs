@@ -0,0 +1,132 @@
+//! Plain-text quest documents for search and embedding pipelines.
+//!
+//! `corpus` turns every quest into one self-contained document: its name,
+//! questlines, prerequisites (by name), cleaned description, tasks and
+//! rewards, both as fields and joined into a single `text` ready to be
+//! chunked and embedded. Document ids are derived from quest ids only, so
+//! re-exporting an edited pack updates documents in place in a vector store
+//! instead of duplicating them. `to_jsonl` writes one JSON document per line,
+//! the input format most ingestion tools accept.
+use super::{questlines_of, sorted_quests};
+use crate::error::Result;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::{render_description, render_name, render_reward, render_task};
+use serde::{Deserialize, Serialize};
+
+/// One quest as a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestDocument {
+    /// Stable document id, `quest:<quest id>`.
+    pub id: String,
+    /// Quest id.
+    pub quest: String,
+    /// Plain-text name, `Quest <id>` for unnamed quests.
+    pub name: String,
+    /// Names of the questlines listing the quest, in questline order.
+    pub questlines: Vec<String>,
+    /// Names of the required prerequisites.
+    pub prerequisites: Vec<String>,
+    /// Names of the optional prerequisites.
+    pub optional_prerequisites: Vec<String>,
+    /// Description without formatting codes, pages separated by blank lines.
+    pub description: String,
+    /// One line per task.
+    pub tasks: Vec<String>,
+    /// One line per reward.
+    pub rewards: Vec<String>,
+    /// All of the above as one text.
+    pub text: String,
+}
+
+fn quest_name(quest: &Quest) -> String {
+    let name = render_name(quest).trim().to_string();
+    if name.is_empty() {
+        format!("Quest {}", quest.id)
+    } else {
+        name
+    }
+}
+
+fn names(db: &QuestDatabase, ids: &[QuestId]) -> Vec<String> {
+    ids.iter()
+        .map(|id| {
+            db.quests
+                .get(id)
+                .map(quest_name)
+                .unwrap_or_else(|| format!("Quest {}", id))
+        })
+        .collect()
+}
+
+/// The `text` of a document: a header of labelled lines, the description,
+/// then task and reward lists; empty parts are left out.
+fn document_text(doc: &QuestDocument) -> String {
+    let mut out = format!("Quest: {}\n", doc.name);
+    for (label, list) in [
+        ("Questline", &doc.questlines),
+        ("Requires", &doc.prerequisites),
+        ("Requires one of", &doc.optional_prerequisites),
+    ] {
+        if !list.is_empty() {
+            out.push_str(&format!("{}: {}\n", label, list.join(", ")));
+        }
+    }
+    if !doc.description.is_empty() {
+        out.push('\n');
+        out.push_str(&doc.description);
+        out.push('\n');
+    }
+    for (label, list) in [("Tasks", &doc.tasks), ("Rewards", &doc.rewards)] {
+        if !list.is_empty() {
+            out.push_str(&format!("\n{}:\n", label));
+            for item in list {
+                out.push_str(&format!("- {}\n", item));
+            }
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// One document per quest, in ascending id order.
+pub fn corpus(db: &QuestDatabase) -> Vec<QuestDocument> {
+    let lines = questlines_of(db);
+    sorted_quests(db)
+        .into_iter()
+        .map(|quest| {
+            let required = if quest.required_prerequisites.is_empty() {
+                &quest.prerequisites
+            } else {
+                &quest.required_prerequisites
+            };
+            let mut doc = QuestDocument {
+                id: format!("quest:{}", quest.id),
+                quest: quest.id.to_string(),
+                name: quest_name(quest),
+                questlines: lines.get(&quest.id).cloned().unwrap_or_default(),
+                prerequisites: names(db, required),
+                optional_prerequisites: names(db, &quest.optional_prerequisites),
+                description: quest
+                    .properties
+                    .as_ref()
+                    .map(render_description)
+                    .unwrap_or_default(),
+                tasks: quest.tasks.iter().map(render_task).collect(),
+                rewards: quest.rewards.iter().map(render_reward).collect(),
+                text: String::new(),
+            };
+            doc.text = document_text(&doc);
+            doc
+        })
+        .collect()
+}
+
+/// `corpus` as JSON Lines: one document object per line.
+pub fn to_jsonl(db: &QuestDatabase) -> Result<String> {
+    let mut out = String::new();
+    for doc in corpus(db) {
+        out.push_str(&serde_json::to_string(&doc)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
@@ -0,0 +1,203 @@
+//! Flat CSV tables of a quest database, for spreadsheets.
+//!
+//! `to_tables` produces four tables with fixed columns, linked by the quest
+//! id column:
+//!
+//! - `quests.csv`: one row per quest,
+//! - `tasks.csv`: one row per required item of each task (a task without
+//!   items gets one row with empty item columns),
+//! - `rewards.csv`: one row per granted or selectable item of each reward
+//!   (likewise for rewards without items),
+//! - `prereqs.csv`: one row per prerequisite link, including links to quests
+//!   missing from the database.
+//!
+//! Names and descriptions are plain text (formatting codes removed); unset
+//! values are empty cells.
+use super::{csv_record, questlines_of, sorted_quests};
+use crate::error::Result;
+use crate::model::*;
+use crate::text::{render_description, render_name};
+use std::path::Path;
+
+/// Header of `quests.csv`.
+pub const QUEST_COLUMNS: [&str; 11] = [
+    "id",
+    "name",
+    "description",
+    "questlines",
+    "is_main",
+    "quest_logic",
+    "task_logic",
+    "repeat_time",
+    "visibility",
+    "tasks",
+    "rewards",
+];
+/// Header of `tasks.csv`.
+pub const TASK_COLUMNS: [&str; 8] = [
+    "quest_id",
+    "task_index",
+    "task_id",
+    "item",
+    "damage",
+    "count",
+    "oredict",
+    "consume",
+];
+/// Header of `rewards.csv`.
+pub const REWARD_COLUMNS: [&str; 8] = [
+    "quest_id",
+    "reward_index",
+    "reward_id",
+    "choice",
+    "item",
+    "damage",
+    "count",
+    "oredict",
+];
+/// Header of `prereqs.csv`.
+pub const PREREQ_COLUMNS: [&str; 3] = ["quest_id", "prerequisite_id", "optional"];
+
+/// The four tables as CSV text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvTables {
+    /// `quests.csv`.
+    pub quests: String,
+    /// `tasks.csv`.
+    pub tasks: String,
+    /// `rewards.csv`.
+    pub rewards: String,
+    /// `prereqs.csv`.
+    pub prereqs: String,
+}
+
+impl CsvTables {
+    /// Write the tables as `quests.csv`, `tasks.csv`, `rewards.csv` and
+    /// `prereqs.csv` into `dir`, creating it if needed.
+    pub fn write_to_dir(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for (name, table) in [
+            ("quests.csv", &self.quests),
+            ("tasks.csv", &self.tasks),
+            ("rewards.csv", &self.rewards),
+            ("prereqs.csv", &self.prereqs),
+        ] {
+            std::fs::write(dir.join(name), table)?;
+        }
+        Ok(())
+    }
+}
+
+fn cell<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Item columns (`item, damage, count, oredict`), empty for `None`.
+fn item_cells(item: Option<&ItemStack>) -> [String; 4] {
+    match item {
+        Some(s) => [
+            s.id.clone(),
+            cell(s.damage),
+            cell(s.count),
+            s.oredict.clone().unwrap_or_default(),
+        ],
+        None => Default::default(),
+    }
+}
+
+fn record(fields: &[String]) -> String {
+    csv_record(fields.iter().map(String::as_str))
+}
+
+/// Flatten `db` into the four tables, quests in ascending id order and
+/// tasks/rewards in their stored order.
+pub fn to_tables(db: &QuestDatabase) -> CsvTables {
+    let lines_of = questlines_of(db);
+    let mut t = CsvTables {
+        quests: csv_record(QUEST_COLUMNS),
+        tasks: csv_record(TASK_COLUMNS),
+        rewards: csv_record(REWARD_COLUMNS),
+        prereqs: csv_record(PREREQ_COLUMNS),
+    };
+    for quest in sorted_quests(db) {
+        let id = quest.id.to_string();
+        let props = quest.properties.as_ref();
+        t.quests.push_str(&record(&[
+            id.clone(),
+            render_name(quest).trim().to_string(),
+            props.map(render_description).unwrap_or_default(),
+            lines_of
+                .get(&quest.id)
+                .map(|l| l.join(";"))
+                .unwrap_or_default(),
+            cell(props.and_then(|p| p.is_main)),
+            cell(props.and_then(|p| p.quest_logic.as_ref())),
+            cell(props.and_then(|p| p.task_logic.as_ref())),
+            cell(props.and_then(|p| p.repeat_time)),
+            cell(props.and_then(|p| p.visibility.as_ref())),
+            quest.tasks.len().to_string(),
+            quest.rewards.len().to_string(),
+        ]));
+
+        for (i, task) in quest.tasks.iter().enumerate() {
+            let head = [
+                id.clone(),
+                task.index.unwrap_or(i).to_string(),
+                task.task_id.clone(),
+            ];
+            let items: Vec<Option<&ItemStack>> = if task.required_items.is_empty() {
+                vec![None]
+            } else {
+                task.required_items.iter().map(Some).collect()
+            };
+            for item in items {
+                let mut row = head.to_vec();
+                row.extend(item_cells(item));
+                row.push(cell(task.consume));
+                t.tasks.push_str(&record(&row));
+            }
+        }
+
+        for (i, reward) in quest.rewards.iter().enumerate() {
+            let head = [
+                id.clone(),
+                reward.index.unwrap_or(i).to_string(),
+                reward.reward_id.clone(),
+            ];
+            let mut items: Vec<(bool, Option<&ItemStack>)> = reward
+                .items
+                .iter()
+                .map(|s| (false, Some(s)))
+                .chain(reward.choices.iter().map(|s| (true, Some(s))))
+                .collect();
+            if items.is_empty() {
+                items.push((false, None));
+            }
+            for (choice, item) in items {
+                let mut row = head.to_vec();
+                row.push(choice.to_string());
+                row.extend(item_cells(item));
+                t.rewards.push_str(&record(&row));
+            }
+        }
+
+        let mut prereqs: Vec<(String, bool)> = Vec::new();
+        let required = quest
+            .prerequisites
+            .iter()
+            .chain(&quest.required_prerequisites)
+            .map(|p| (p, false));
+        for (p, optional) in required.chain(quest.optional_prerequisites.iter().map(|p| (p, true)))
+        {
+            let p = p.to_string();
+            if !prereqs.iter().any(|(q, _)| *q == p) {
+                prereqs.push((p, optional));
+            }
+        }
+        for (p, optional) in prereqs {
+            t.prereqs
+                .push_str(&record(&[id.clone(), p, optional.to_string()]));
+        }
+    }
+    t
+}
@@ -0,0 +1,102 @@
+//! Neo4j export of the quest graph.
+//!
+//! Quests become `:Quest` nodes keyed by their id (as a string, so wide ids
+//! survive) with the plain-text name and the names of the questlines they
+//! appear on. Each prerequisite becomes a `(quest)-[:REQUIRES]->(prerequisite)`
+//! relationship with an `optional` flag. Prerequisites pointing at quests that
+//! are not in the database are skipped.
+//!
+//! `to_cypher` emits a script for `cypher-shell`; `to_import_csv` emits the
+//! node/relationship CSV pair for `neo4j-admin database import`, which is
+//! much faster for large packs.
+use super::{csv_record, prerequisite_links, questlines_of, sorted_quests};
+use crate::model::*;
+use crate::text::render_name;
+
+/// Quote a Cypher string literal.
+fn cypher_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('\'');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            _ => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// Render `db` as a Cypher script: a uniqueness constraint, one `CREATE` per
+/// quest, then one `MATCH ... CREATE` per prerequisite link.
+pub fn to_cypher(db: &QuestDatabase) -> String {
+    let lines_of = questlines_of(db);
+    let mut out = String::from(
+        "CREATE CONSTRAINT quest_id IF NOT EXISTS FOR (q:Quest) REQUIRE q.id IS UNIQUE;\n",
+    );
+    for quest in sorted_quests(db) {
+        let questlines: Vec<String> = lines_of
+            .get(&quest.id)
+            .into_iter()
+            .flatten()
+            .map(|n| cypher_string(n))
+            .collect();
+        out.push_str(&format!(
+            "CREATE (:Quest {{id: {}, name: {}, questlines: [{}]}});\n",
+            cypher_string(&quest.id.to_string()),
+            cypher_string(render_name(quest).trim()),
+            questlines.join(", ")
+        ));
+    }
+    for (quest, prereq, optional) in prerequisite_links(db) {
+        out.push_str(&format!(
+            "MATCH (a:Quest {{id: {}}}), (b:Quest {{id: {}}}) CREATE (a)-[:REQUIRES {{optional: {}}}]->(b);\n",
+            cypher_string(&quest.to_string()),
+            cypher_string(&prereq.to_string()),
+            optional
+        ));
+    }
+    out
+}
+
+/// Render `db` as the `(nodes, relationships)` CSV pair for
+/// `neo4j-admin database import`. Questline lists use `;`, the importer's
+/// default array delimiter.
+pub fn to_import_csv(db: &QuestDatabase) -> (String, String) {
+    let lines_of = questlines_of(db);
+    let mut nodes = csv_record(["id:ID(Quest)", "name", "questlines:string[]", ":LABEL"]);
+    for quest in sorted_quests(db) {
+        let id = quest.id.to_string();
+        let name = render_name(quest).trim().to_string();
+        let questlines = lines_of
+            .get(&quest.id)
+            .map(|l| l.join(";"))
+            .unwrap_or_default();
+        nodes.push_str(&csv_record([
+            id.as_str(),
+            name.as_str(),
+            questlines.as_str(),
+            "Quest",
+        ]));
+    }
+    let mut rels = csv_record([
+        ":START_ID(Quest)",
+        ":END_ID(Quest)",
+        "optional:boolean",
+        ":TYPE",
+    ]);
+    for (quest, prereq, optional) in prerequisite_links(db) {
+        let (a, b) = (quest.to_string(), prereq.to_string());
+        let optional = optional.to_string();
+        rels.push_str(&csv_record([
+            a.as_str(),
+            b.as_str(),
+            optional.as_str(),
+            "REQUIRES",
+        ]));
+    }
+    (nodes, rels)
+}
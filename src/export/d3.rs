@@ -0,0 +1,107 @@
+//! D3 force-directed graph JSON.
+//!
+//! The `{nodes, links}` document most D3 force layout examples load: quests
+//! become nodes and prerequisites links from the prerequisite to the quest
+//! requiring it, referencing nodes by `id`. A node's `group` is the 1-based
+//! position in questline order (`ordering::questline_ids`) of the first questline listing it (0 for
+//! quests on no questline), so colouring by group colours by chapter; its
+//! `value` is the importance score, when scores are given. A link's `value`
+//! is the weight the link carries in importance scoring.
+//!
+//! Ids are strings because JavaScript numbers cannot hold every 64-bit id.
+//! Prerequisites pointing at quests that are not in the database are skipped.
+use super::{FLOAT_PRECISION, round_float, sorted_quests, weighted_links};
+use crate::error::Result;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::{render_name, strip_formatting};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A quest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct D3Node {
+    /// Quest id, as in `QuestId`'s `Display`.
+    pub id: String,
+    /// Plain-text quest name.
+    pub name: String,
+    /// Questline number, 0 for none.
+    pub group: usize,
+    /// Name of the `group` questline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub questline: Option<String>,
+    /// Importance score, 0 without scores.
+    pub value: f64,
+}
+
+/// A prerequisite link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct D3Link {
+    /// The prerequisite.
+    pub source: String,
+    /// The quest requiring it.
+    pub target: String,
+    /// Importance weight of the link.
+    pub value: f64,
+    /// The link is an optional prerequisite.
+    pub optional: bool,
+}
+
+/// The whole graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct D3Graph {
+    /// Every quest, in id order.
+    pub nodes: Vec<D3Node>,
+    /// Every prerequisite link.
+    pub links: Vec<D3Link>,
+}
+
+/// Build the graph of `db`. `scores` (e.g. from
+/// `importance::compute_importance_scores`) fills the node values; quests
+/// without a score get 0.
+pub fn to_d3(db: &QuestDatabase, scores: Option<&HashMap<QuestId, f64>>) -> D3Graph {
+    let mut groups: HashMap<QuestId, (usize, String)> = HashMap::new();
+    for (i, line) in sorted_questlines(db).into_iter().enumerate() {
+        let name = line
+            .properties
+            .as_ref()
+            .map(|p| strip_formatting(&p.name).trim().to_string())
+            .unwrap_or_default();
+        for entry in &line.entries {
+            groups
+                .entry(entry.quest_id)
+                .or_insert_with(|| (i + 1, name.clone()));
+        }
+    }
+    let nodes = sorted_quests(db)
+        .into_iter()
+        .map(|quest| {
+            let group = groups.get(&quest.id);
+            D3Node {
+                id: quest.id.to_string(),
+                name: render_name(quest).trim().to_string(),
+                group: group.map_or(0, |(g, _)| *g),
+                questline: group.map(|(_, name)| name.clone()),
+                value: scores
+                    .and_then(|s| s.get(&quest.id))
+                    .map_or(0.0, |v| round_float(*v, FLOAT_PRECISION)),
+            }
+        })
+        .collect();
+    let links = weighted_links(db)
+        .into_iter()
+        .map(|(quest, prereq, optional, weight)| D3Link {
+            source: prereq.to_string(),
+            target: quest.to_string(),
+            value: round_float(weight, FLOAT_PRECISION),
+            optional,
+        })
+        .collect();
+    D3Graph { nodes, links }
+}
+
+/// `to_d3` as pretty-printed JSON.
+pub fn to_d3_json(db: &QuestDatabase, scores: Option<&HashMap<QuestId, f64>>) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&to_d3(db, scores))?)
+}
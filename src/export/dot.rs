@@ -0,0 +1,152 @@
+//! Graphviz (DOT) export of the condensed prerequisite graph.
+//!
+//! Each strongly connected component becomes one node, so the drawing stays
+//! a DAG even when the pack has prerequisite cycles. Cyclic components are
+//! drawn as red boxes listing every mutually locked quest; the other nodes
+//! are labelled `name (id)`. Edges point from prerequisite to dependent.
+//!
+//! `quest_graph_to_dot` draws the uncondensed graph instead, one node per
+//! quest, with every edge labelled by its importance weight (1 required,
+//! `1/n` for each of `n` optional prerequisites) and optional edges dashed.
+//! `quest_graph_to_dot_with` can keep `HIDDEN` and `SECRET` quests from being
+//! spoiled by a shared map: drawn as grey unnamed nodes, or left out with a
+//! graph label counting them.
+use super::{FLOAT_PRECISION, format_float, sorted_quests, weighted_links};
+use crate::graph::{Component, condensation};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use std::collections::HashSet;
+
+/// Escape text for a double-quoted DOT string.
+fn dot_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn quest_label(db: &QuestDatabase, quest: &QuestId) -> String {
+    let name = db
+        .quests
+        .get(quest)
+        .and_then(|q| q.name())
+        .map(strip_formatting)
+        .unwrap_or_default();
+    dot_string(&format!("{} ({})", name, quest))
+}
+
+fn node(db: &QuestDatabase, index: usize, component: &Component) -> String {
+    let labels: Vec<String> = component
+        .members
+        .iter()
+        .map(|q| quest_label(db, q))
+        .collect();
+    if component.cyclic {
+        format!(
+            "  c{} [label=\"Cycle of {}\\n{}\", shape=box, color=red]\n",
+            index,
+            component.members.len(),
+            labels.join("\\n")
+        )
+    } else {
+        format!("  c{} [label=\"{}\"]\n", index, labels.join("\\n"))
+    }
+}
+
+/// Render the condensation of the prerequisite graph as a DOT digraph.
+/// Nodes are named `c<n>` after their index in `graph::condensation`.
+pub fn condensation_to_dot(db: &QuestDatabase) -> String {
+    let graph = condensation(db);
+    let mut out = String::from("digraph condensation {\n");
+    for (i, component) in graph.components.iter().enumerate() {
+        out.push_str(&node(db, i, component));
+    }
+    for (from, to) in &graph.edges {
+        out.push_str(&format!("  c{} -> c{}\n", from, to));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// How `quest_graph_to_dot_with` draws quests whose visibility is concealed
+/// (see `QuestVisibility::is_concealed`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConcealedQuests {
+    /// Like any other quest.
+    #[default]
+    Show,
+    /// As grey dashed nodes labelled `??? (<id>)`, with grey links.
+    Dim,
+    /// Not at all, nor their links; a graph label gives their number.
+    Exclude,
+}
+
+/// Options for `quest_graph_to_dot_with`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DotOptions {
+    pub concealed: ConcealedQuests,
+}
+
+/// Render every quest and prerequisite link as a DOT digraph. Nodes are
+/// named `q<id>`; each edge carries the importance weight both as its label
+/// and as a `weight` attribute (Graphviz layouts treat it as an edge
+/// priority, and other tools read it as the edge weight).
+pub fn quest_graph_to_dot(db: &QuestDatabase) -> String {
+    quest_graph_to_dot_with(db, &DotOptions::default())
+}
+
+/// `quest_graph_to_dot` with concealed quests drawn as `options` says.
+pub fn quest_graph_to_dot_with(db: &QuestDatabase, options: &DotOptions) -> String {
+    let concealed: HashSet<QuestId> = match options.concealed {
+        ConcealedQuests::Show => HashSet::new(),
+        _ => db
+            .quests
+            .values()
+            .filter(|q| q.visibility().is_some_and(|v| v.is_concealed()))
+            .map(|q| q.id)
+            .collect(),
+    };
+    let exclude = options.concealed == ConcealedQuests::Exclude;
+    let mut out = String::from("digraph quests {\n");
+    if exclude && !concealed.is_empty() {
+        out.push_str(&format!(
+            "  label=\"{} hidden quest{} not shown\"\n",
+            concealed.len(),
+            if concealed.len() == 1 { "" } else { "s" }
+        ));
+    }
+    for quest in sorted_quests(db) {
+        if !concealed.contains(&quest.id) {
+            out.push_str(&format!(
+                "  q{} [label=\"{}\"]\n",
+                quest.id,
+                quest_label(db, &quest.id)
+            ));
+        } else if !exclude {
+            out.push_str(&format!(
+                "  q{} [label=\"??? ({})\", style=dashed, color=gray, fontcolor=gray]\n",
+                quest.id, quest.id
+            ));
+        }
+    }
+    for (quest, prereq, optional, weight) in weighted_links(db) {
+        let touches_concealed = concealed.contains(&quest) || concealed.contains(&prereq);
+        if exclude && touches_concealed {
+            continue;
+        }
+        let weight = format_float(weight, FLOAT_PRECISION);
+        out.push_str(&format!(
+            "  q{} -> q{} [weight={}, label=\"{}\"{}{}]\n",
+            prereq,
+            quest,
+            weight,
+            weight,
+            if optional { ", style=dashed" } else { "" },
+            if touches_concealed {
+                ", color=gray"
+            } else {
+                ""
+            }
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
@@ -0,0 +1,520 @@
+//! FTB Quests export, for packs moving off BetterQuesting.
+//!
+//! `to_ftb_quests` maps a database onto the SNBT files of an FTB Quests
+//! `config/ftbquests/quests` folder: `data.snbt` and one
+//! `chapters/<name>.snbt` per questline, in questline order. The mapping is
+//! best effort:
+//!
+//! - a quest lands in the chapter of the first questline listing it and is a
+//!   quest link in later ones; quests on no questline go to an extra
+//!   `unsorted` chapter. Positions are converted from pixels to FTB's grid
+//!   (24 pixels, BetterQuesting's default tile size, per unit);
+//! - required prerequisites become dependencies (`OR` logic becomes
+//!   `one_completed`); optional prerequisites, which never gate a quest, are
+//!   dropped;
+//! - retrieval, checkbox, hunt and XP tasks and item, XP and command rewards
+//!   are converted. Other tasks become checkmarks so the quest stays
+//!   completable, other rewards are dropped, and both are listed as
+//!   `ConversionNote`s, as are ore dictionary requirements (exported as the
+//!   item) and item damage values, which modern versions no longer have.
+//!
+//! FTB object ids are 16 hex digits; they are derived from the
+//! BetterQuesting ids, so exporting the same pack twice gives the same files.
+//! Text keeps its `§` formatting codes, which FTB Quests renders.
+use super::line_name;
+use super::markdown::slug;
+use super::sorted_quests;
+use crate::analysis::consume::is_repeatable;
+use crate::error::Result;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// FTB Quests file format version written to `data.snbt`.
+pub const FTB_FORMAT_VERSION: i32 = 13;
+
+/// Pixels of a BetterQuesting layout per FTB grid unit.
+const PIXELS_PER_UNIT: f64 = 24.0;
+
+/// One file of the export, relative to the `quests` folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FtbFile {
+    pub path: String,
+    pub snbt: String,
+}
+
+/// Something that could not be carried over exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionNote {
+    pub quest: QuestId,
+    pub message: String,
+}
+
+/// Result of `to_ftb_quests`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FtbExport {
+    pub files: Vec<FtbFile>,
+    /// Lossy conversions, by quest in export order.
+    pub notes: Vec<ConversionNote>,
+}
+
+/// A value in SNBT.
+enum Snbt {
+    Str(String),
+    Bool(bool),
+    Byte(i8),
+    Int(i32),
+    Long(i64),
+    Double(f64),
+    List(Vec<Snbt>),
+    Compound(Vec<(&'static str, Snbt)>),
+}
+
+fn quote(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Snbt {
+    fn str(text: impl Into<String>) -> Self {
+        Snbt::Str(text.into())
+    }
+
+    /// Write the value the way FTB Quests formats its files: compounds and
+    /// lists of compounds one entry per line, tab-indented.
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = |out: &mut String, depth: usize| out.extend((0..depth).map(|_| '\t'));
+        match self {
+            Snbt::Str(s) => out.push_str(&quote(s)),
+            Snbt::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Snbt::Byte(n) => out.push_str(&format!("{}b", n)),
+            Snbt::Int(n) => out.push_str(&n.to_string()),
+            Snbt::Long(n) => out.push_str(&format!("{}L", n)),
+            Snbt::Double(n) => out.push_str(&format!("{:?}d", n)),
+            Snbt::List(items) if items.iter().any(|i| matches!(i, Snbt::Compound(_))) => {
+                out.push_str("[\n");
+                for item in items {
+                    indent(out, depth + 1);
+                    item.write(out, depth + 1);
+                    out.push('\n');
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            Snbt::List(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write(out, depth);
+                }
+                out.push(']');
+            }
+            Snbt::Compound(entries) => {
+                out.push_str("{\n");
+                for (key, value) in entries {
+                    indent(out, depth + 1);
+                    out.push_str(key);
+                    out.push_str(": ");
+                    value.write(out, depth + 1);
+                    out.push('\n');
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+        }
+    }
+
+    fn to_file(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+}
+
+/// A 16 hex digit FTB id for `key` (FNV-1a), never zero.
+fn ftb_id(key: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016X}", hash.max(1))
+}
+
+fn option_i64(options: &HashMap<String, serde_json::Value>, key: &str) -> Option<i64> {
+    options.get(key).and_then(serde_json::Value::as_i64)
+}
+
+/// BetterQuesting flags are stored as 0/1 bytes or booleans.
+fn option_flag(options: &HashMap<String, serde_json::Value>, key: &str) -> bool {
+    match options.get(key) {
+        Some(serde_json::Value::Bool(b)) => *b,
+        Some(v) => v.as_i64().is_some_and(|n| n != 0),
+        None => false,
+    }
+}
+
+/// Converts one quest, collecting its notes.
+struct Converter<'a> {
+    quest: QuestId,
+    notes: &'a mut Vec<ConversionNote>,
+}
+
+impl Converter<'_> {
+    fn note(&mut self, message: String) {
+        self.notes.push(ConversionNote {
+            quest: self.quest,
+            message,
+        });
+    }
+
+    fn item(&mut self, stack: &ItemStack) -> Snbt {
+        if stack.oredict.as_deref().is_some_and(|o| !o.is_empty()) {
+            self.note(format!(
+                "ore dictionary `{}` exported as {}",
+                stack.oredict.as_deref().unwrap_or_default(),
+                stack.id
+            ));
+        }
+        if stack.damage.is_some_and(|d| d != 0) {
+            self.note(format!("damage value of {} dropped", stack));
+        }
+        Snbt::Compound(vec![("id", Snbt::str(&stack.id)), ("Count", Snbt::Byte(1))])
+    }
+
+    fn tasks(&mut self, quest: &Quest) -> Vec<Snbt> {
+        let mut out = Vec::new();
+        for (i, task) in quest.tasks.iter().enumerate() {
+            let id = |n: usize| {
+                (
+                    "id",
+                    Snbt::Str(ftb_id(&format!("task {} {} {}", quest.id, i, n))),
+                )
+            };
+            if let Some(target) = task.hunt_target() {
+                out.push(Snbt::Compound(vec![
+                    id(0),
+                    ("type", Snbt::str("kill")),
+                    ("entity", Snbt::Str(target.target_entity)),
+                    ("value", Snbt::Long(target.required_kills as i64)),
+                ]));
+                continue;
+            }
+            match task.task_id.as_str() {
+                "bq_standard:retrieval" => {
+                    for (n, stack) in task.required_items.iter().enumerate() {
+                        let mut fields = vec![
+                            id(n),
+                            ("type", Snbt::str("item")),
+                            ("item", self.item(stack)),
+                            ("count", Snbt::Long(stack.count.unwrap_or(1) as i64)),
+                        ];
+                        if task.consume == Some(true) {
+                            fields.push(("consume_items", Snbt::Bool(true)));
+                        }
+                        out.push(Snbt::Compound(fields));
+                    }
+                }
+                "bq_standard:checkbox" => {
+                    out.push(Snbt::Compound(vec![
+                        id(0),
+                        ("type", Snbt::str("checkmark")),
+                    ]));
+                }
+                "bq_standard:xp" => {
+                    out.push(Snbt::Compound(vec![
+                        id(0),
+                        ("type", Snbt::str("xp")),
+                        (
+                            "value",
+                            Snbt::Long(option_i64(&task.options, "amount").unwrap_or(1)),
+                        ),
+                        (
+                            "points",
+                            Snbt::Bool(!option_flag(&task.options, "isLevels")),
+                        ),
+                    ]));
+                }
+                other => {
+                    self.note(format!("task `{}` exported as a checkmark", other));
+                    out.push(Snbt::Compound(vec![
+                        id(0),
+                        ("type", Snbt::str("checkmark")),
+                    ]));
+                }
+            }
+        }
+        out
+    }
+
+    fn rewards(&mut self, quest: &Quest) -> Vec<Snbt> {
+        let mut out = Vec::new();
+        for (i, reward) in quest.rewards.iter().enumerate() {
+            let id = |n: usize| {
+                (
+                    "id",
+                    Snbt::Str(ftb_id(&format!("reward {} {} {}", quest.id, i, n))),
+                )
+            };
+            match reward.reward_id.as_str() {
+                "bq_standard:item" => {
+                    for (n, stack) in reward.items.iter().enumerate() {
+                        let mut fields = vec![
+                            id(n),
+                            ("type", Snbt::str("item")),
+                            ("item", self.item(stack)),
+                        ];
+                        let count = stack.count.unwrap_or(1);
+                        if count != 1 {
+                            fields.push(("count", Snbt::Int(count)));
+                        }
+                        out.push(Snbt::Compound(fields));
+                    }
+                }
+                "bq_standard:xp" => {
+                    let amount = option_i64(&reward.extra, "amount").unwrap_or(0);
+                    let amount = i32::try_from(amount).unwrap_or(i32::MAX);
+                    out.push(if option_flag(&reward.extra, "isLevels") {
+                        Snbt::Compound(vec![
+                            id(0),
+                            ("type", Snbt::str("xp_levels")),
+                            ("xp_levels", Snbt::Int(amount)),
+                        ])
+                    } else {
+                        Snbt::Compound(vec![
+                            id(0),
+                            ("type", Snbt::str("xp")),
+                            ("xp", Snbt::Int(amount)),
+                        ])
+                    });
+                }
+                "bq_standard:command" => {
+                    let command = reward
+                        .extra
+                        .get("command")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or_default()
+                        .replace("VAR_NAME", "@p");
+                    out.push(Snbt::Compound(vec![
+                        id(0),
+                        ("type", Snbt::str("command")),
+                        ("command", Snbt::Str(command)),
+                    ]));
+                }
+                other => self.note(format!("reward `{}` not exported", other)),
+            }
+        }
+        out
+    }
+
+    /// `quest` at grid position `(x, y)`.
+    fn quest(&mut self, quest: &Quest, (x, y): (f64, f64)) -> Snbt {
+        let mut fields = vec![
+            ("id", Snbt::Str(ftb_id(&format!("quest {}", quest.id)))),
+            ("x", Snbt::Double(x)),
+            ("y", Snbt::Double(y)),
+        ];
+        if let Some(props) = &quest.properties {
+            fields.push(("title", Snbt::str(&props.name)));
+            let mut lines: Vec<Snbt> = Vec::new();
+            for (i, page) in props.pages().into_iter().enumerate() {
+                if i > 0 {
+                    lines.push(Snbt::str("{@pagebreak}"));
+                }
+                lines.extend(page.lines().map(Snbt::str));
+            }
+            if !lines.is_empty() {
+                fields.push(("description", Snbt::List(lines)));
+            }
+            if let Some(icon) = &props.icon {
+                fields.push(("icon", Snbt::str(&icon.id)));
+            }
+            match props
+                .quest_logic
+                .as_deref()
+                .map(str::to_ascii_uppercase)
+                .as_deref()
+            {
+                None | Some("AND") => {}
+                Some("OR") => fields.push(("dependency_requirement", Snbt::str("one_completed"))),
+                Some(other) => self.note(format!("quest logic {} exported as AND", other)),
+            }
+            if props.visibility_kind() == Some(QuestVisibility::Hidden) {
+                fields.push(("invisible", Snbt::Bool(true)));
+            }
+        }
+        if is_repeatable(quest) {
+            fields.push(("can_repeat", Snbt::Bool(true)));
+        }
+        let mut dependencies = quest.required_prerequisites.clone();
+        dependencies.sort();
+        dependencies.dedup();
+        if !dependencies.is_empty() {
+            let ids = dependencies
+                .iter()
+                .map(|p| Snbt::Str(ftb_id(&format!("quest {}", p))))
+                .collect();
+            fields.push(("dependencies", Snbt::List(ids)));
+        }
+        let tasks = self.tasks(quest);
+        if !tasks.is_empty() {
+            fields.push(("tasks", Snbt::List(tasks)));
+        }
+        let rewards = self.rewards(quest);
+        if !rewards.is_empty() {
+            fields.push(("rewards", Snbt::List(rewards)));
+        }
+        Snbt::Compound(fields)
+    }
+}
+
+/// Grid position of a questline entry.
+fn grid_position(entry: &QuestLineEntry) -> (f64, f64) {
+    let unit = |v: Option<i32>| v.unwrap_or(0) as f64 / PIXELS_PER_UNIT;
+    (unit(entry.x), unit(entry.y))
+}
+
+fn chapter(
+    key: &str,
+    file_name: &str,
+    order: usize,
+    title: &str,
+    quests: Vec<Snbt>,
+    links: Vec<Snbt>,
+) -> Snbt {
+    let mut fields = vec![
+        ("id", Snbt::Str(ftb_id(&format!("chapter {}", key)))),
+        ("filename", Snbt::str(file_name)),
+        ("group", Snbt::str("")),
+        ("order_index", Snbt::Int(order as i32)),
+        ("title", Snbt::str(title)),
+        ("quests", Snbt::List(quests)),
+    ];
+    if !links.is_empty() {
+        fields.push(("quest_links", Snbt::List(links)));
+    }
+    Snbt::Compound(fields)
+}
+
+/// Convert `db` into the files of an FTB Quests `quests` folder.
+pub fn to_ftb_quests(db: &QuestDatabase) -> FtbExport {
+    let mut notes = Vec::new();
+    let mut files = vec![FtbFile {
+        path: "data.snbt".to_string(),
+        snbt: Snbt::Compound(vec![("version", Snbt::Int(FTB_FORMAT_VERSION))]).to_file(),
+    }];
+    let mut placed: HashSet<QuestId> = HashSet::new();
+    let mut used_names: HashSet<String> = HashSet::new();
+    let mut unique_name = |name: String| {
+        let mut candidate = name.clone();
+        let mut n = 2;
+        while !used_names.insert(candidate.clone()) {
+            candidate = format!("{}_{}", name, n);
+            n += 1;
+        }
+        candidate
+    };
+
+    let lines = sorted_questlines(db);
+    for (order, line) in lines.iter().enumerate() {
+        let file_name = unique_name(slug(&line_name(line)).replace('-', "_"));
+        let mut quests = Vec::new();
+        let mut links = Vec::new();
+        for entry in &line.entries {
+            let Some(quest) = db.quests.get(&entry.quest_id) else {
+                continue;
+            };
+            if placed.insert(quest.id) {
+                let mut converter = Converter {
+                    quest: quest.id,
+                    notes: &mut notes,
+                };
+                quests.push(converter.quest(quest, grid_position(entry)));
+            } else {
+                let (x, y) = grid_position(entry);
+                let key = format!("link {} {}", line.id, quest.id);
+                links.push(Snbt::Compound(vec![
+                    ("id", Snbt::Str(ftb_id(&key))),
+                    (
+                        "linked_quest",
+                        Snbt::Str(ftb_id(&format!("quest {}", quest.id))),
+                    ),
+                    ("x", Snbt::Double(x)),
+                    ("y", Snbt::Double(y)),
+                ]));
+            }
+        }
+        let title = line.properties.as_ref().map(|p| p.name.as_str());
+        files.push(FtbFile {
+            path: format!("chapters/{}.snbt", file_name),
+            snbt: chapter(
+                &line.id.to_string(),
+                &file_name,
+                order,
+                title.unwrap_or(&line_name(line)),
+                quests,
+                links,
+            )
+            .to_file(),
+        });
+    }
+
+    let loose: Vec<Snbt> = sorted_quests(db)
+        .into_iter()
+        .filter(|q| !placed.contains(&q.id))
+        .enumerate()
+        .map(|(i, quest)| {
+            let mut converter = Converter {
+                quest: quest.id,
+                notes: &mut notes,
+            };
+            // rows of eight, one free cell between quests
+            converter.quest(quest, ((i % 8) as f64 * 2.0, (i / 8) as f64 * 2.0))
+        })
+        .collect();
+    if !loose.is_empty() {
+        let file_name = unique_name("unsorted".to_string());
+        files.push(FtbFile {
+            path: format!("chapters/{}.snbt", file_name),
+            snbt: chapter(
+                "unsorted",
+                &file_name,
+                lines.len(),
+                "Unsorted",
+                loose,
+                vec![],
+            )
+            .to_file(),
+        });
+    }
+    FtbExport { files, notes }
+}
+
+/// Write `to_ftb_quests` into `dir` (the `config/ftbquests/quests` folder,
+/// created if needed) and return the conversion notes.
+pub fn write_ftb_quests(db: &QuestDatabase, dir: &Path) -> Result<Vec<ConversionNote>> {
+    let export = to_ftb_quests(db);
+    for file in &export.files {
+        let path = dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &file.snbt)?;
+    }
+    Ok(export.notes)
+}
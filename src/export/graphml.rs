@@ -0,0 +1,73 @@
+//! GraphML export of the prerequisite graph, for yEd, Cytoscape or Gephi.
+//!
+//! Quests become nodes (id `q<quest id>`) carrying the plain-text name, the
+//! questlines they appear on (joined with `; `) and, when scores are given,
+//! their importance. Each prerequisite becomes an edge from the prerequisite
+//! to the quest requiring it, with a `kind` of `required` or `optional` and
+//! the `weight` the link carries in importance scoring.
+//! Prerequisites pointing at quests that are not in the database are skipped.
+use super::{
+    FLOAT_PRECISION, escape_html, format_float, questlines_of, sorted_quests, weighted_links,
+};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::render_name;
+use std::collections::HashMap;
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="questline" for="node" attr.name="questline" attr.type="string"/>
+  <key id="importance" for="node" attr.name="importance" attr.type="double"/>
+  <key id="kind" for="edge" attr.name="kind" attr.type="string"/>
+  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>
+  <graph id="quests" edgedefault="directed">
+"#;
+
+fn node_id(id: QuestId) -> String {
+    escape_html(&format!("q{}", id))
+}
+
+fn data(key: &str, value: &str) -> String {
+    format!(
+        "      <data key=\"{}\">{}</data>\n",
+        key,
+        escape_html(value)
+    )
+}
+
+/// Render `db` as a GraphML document. `scores` (e.g. from
+/// `importance::compute_importance_scores`) fills the `importance` node
+/// attribute; quests without a score, or every quest when `scores` is
+/// `None`, leave it out.
+pub fn to_graphml(db: &QuestDatabase, scores: Option<&HashMap<QuestId, f64>>) -> String {
+    let lines_of = questlines_of(db);
+    let mut out = String::from(HEADER);
+    for quest in sorted_quests(db) {
+        out.push_str(&format!("    <node id=\"{}\">\n", node_id(quest.id)));
+        out.push_str(&data("name", render_name(quest).trim()));
+        if let Some(lines) = lines_of.get(&quest.id) {
+            out.push_str(&data("questline", &lines.join("; ")));
+        }
+        if let Some(score) = scores.and_then(|s| s.get(&quest.id)) {
+            out.push_str(&data("importance", &format_float(*score, FLOAT_PRECISION)));
+        }
+        out.push_str("    </node>\n");
+    }
+    for (i, (quest, prereq, optional, weight)) in weighted_links(db).into_iter().enumerate() {
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+            i,
+            node_id(prereq),
+            node_id(quest)
+        ));
+        out.push_str(&data(
+            "kind",
+            if optional { "optional" } else { "required" },
+        ));
+        out.push_str(&data("weight", &format_float(weight, FLOAT_PRECISION)));
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
@@ -0,0 +1,132 @@
+//! Task and reward option schemas observed in a pack.
+//!
+//! Tasks and rewards are open-ended: every handler id (`bq_standard:retrieval`,
+//! `bq_rf:rf_charge`, ...) reads options of its own, and the model keeps the
+//! ones it does not type in `Task::options` / `Reward::extra`. `handler_schemas`
+//! reports, per handler, which option keys the pack actually uses, in how
+//! many entries, and with which JSON value types: a schema by example showing
+//! which handlers deserve typed variants next and documenting forks' handlers.
+//!
+//! Keys are the on-disk names without NBT type suffixes (`requiredItems`,
+//! `ignoreNBT`); modelled fields are reported when set (lists when not empty)
+//! and under the type the model stores them as, so 0/1 flags read as `bool`.
+//! The handler id and `index` are left out.
+use crate::error::Result;
+use crate::model::*;
+use crate::schema::FieldScope;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How one option key is used by a handler's entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObservedKey {
+    /// Entries with the key.
+    pub count: usize,
+    /// Entries by value type: `null`, `bool`, `integer`, `number`, `string`,
+    /// `array` or `object`.
+    pub types: BTreeMap<String, usize>,
+}
+
+/// Option keys observed for one task or reward handler.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandlerSchema {
+    /// `FieldScope::Task` or `FieldScope::Reward`.
+    pub scope: FieldScope,
+    /// Handler id (`taskID` / `rewardID`).
+    pub handler: String,
+    /// Entries using the handler.
+    pub count: usize,
+    /// Observed keys by name.
+    pub keys: BTreeMap<String, ObservedKey>,
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn record(schema: &mut HandlerSchema, key: &str, value_type: &str) {
+    let key = schema.keys.entry(key.to_string()).or_default();
+    key.count += 1;
+    *key.types.entry(value_type.to_string()).or_default() += 1;
+}
+
+/// Record the modelled fields that are set and every extra option.
+fn observe<'a>(
+    schema: &mut HandlerSchema,
+    lists: &[(&str, &[ItemStack])],
+    flags: &[(&str, Option<bool>)],
+    extra: impl Iterator<Item = (&'a String, &'a Value)>,
+) {
+    schema.count += 1;
+    for (key, list) in lists {
+        if !list.is_empty() {
+            record(schema, key, "array");
+        }
+    }
+    for (key, flag) in flags {
+        if flag.is_some() {
+            record(schema, key, "bool");
+        }
+    }
+    for (key, value) in extra {
+        record(schema, key, type_name(value));
+    }
+}
+
+/// Option schemas of every task handler, then every reward handler, each by
+/// handler id.
+pub fn handler_schemas(db: &QuestDatabase) -> Vec<HandlerSchema> {
+    let mut tasks: BTreeMap<&str, HandlerSchema> = BTreeMap::new();
+    let mut rewards: BTreeMap<&str, HandlerSchema> = BTreeMap::new();
+    let schema = |scope: FieldScope, handler: &str| HandlerSchema {
+        scope,
+        handler: handler.to_string(),
+        count: 0,
+        keys: BTreeMap::new(),
+    };
+    for quest in db.quests.values() {
+        for task in &quest.tasks {
+            let entry = tasks
+                .entry(&task.task_id)
+                .or_insert_with(|| schema(FieldScope::Task, &task.task_id));
+            observe(
+                entry,
+                &[("requiredItems", &task.required_items)],
+                &[
+                    ("ignoreNBT", task.ignore_nbt),
+                    ("partialMatch", task.partial_match),
+                    ("autoConsume", task.auto_consume),
+                    ("consume", task.consume),
+                    ("groupDetect", task.group_detect),
+                ],
+                task.options.iter(),
+            );
+        }
+        for reward in &quest.rewards {
+            let entry = rewards
+                .entry(&reward.reward_id)
+                .or_insert_with(|| schema(FieldScope::Reward, &reward.reward_id));
+            observe(
+                entry,
+                &[("rewards", &reward.items), ("choices", &reward.choices)],
+                &[("ignoreDisabled", reward.ignore_disabled)],
+                reward.extra.iter(),
+            );
+        }
+    }
+    tasks.into_values().chain(rewards.into_values()).collect()
+}
+
+/// `handler_schemas` as pretty JSON.
+pub fn to_json(db: &QuestDatabase) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&handler_schemas(db))?)
+}
@@ -0,0 +1,176 @@
+//! iCalendar feed of repeatable quest cooldowns.
+//!
+//! For every repeatable quest a player has completed, the feed contains one
+//! event at the moment the quest becomes available again. Server communities
+//! subscribe to the feed for daily/weekly quest reminders. Only cooldowns that
+//! end after the reference time are included; quests already available again
+//! need no reminder.
+use crate::model::*;
+use crate::progress::{PlayerProgress, Timestamp, next_repeat_at};
+use crate::quest_id::QuestId;
+use crate::text::render_name;
+
+/// A repeatable quest becoming available again for one player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Availability {
+    /// The repeatable quest.
+    pub quest: QuestId,
+    /// Player UUID whose cooldown ends.
+    pub player: String,
+    /// When the quest reopens.
+    pub available_at: Timestamp,
+}
+
+/// Cooldowns ending after `now`, ordered by time, quest and player.
+pub fn upcoming(
+    db: &QuestDatabase,
+    progress: &PlayerProgress,
+    now: Timestamp,
+) -> Vec<Availability> {
+    let mut out = Vec::new();
+    for (qid, qp) in &progress.quests {
+        let Some(props) = db.quests.get(qid).and_then(|q| q.properties.as_ref()) else {
+            continue;
+        };
+        for completion in &qp.completed {
+            match next_repeat_at(props, completion.timestamp) {
+                Some(at) if at > now => out.push(Availability {
+                    quest: *qid,
+                    player: completion.player.clone(),
+                    available_at: at,
+                }),
+                _ => {}
+            }
+        }
+    }
+    out.sort_by(|a, b| {
+        (a.available_at, a.quest, &a.player).cmp(&(b.available_at, b.quest, &b.player))
+    });
+    out
+}
+
+/// Render upcoming cooldowns as an iCalendar (RFC 5545) document.
+///
+/// `now` is used both to select upcoming events and as the `DTSTAMP` of every
+/// event, so the output is reproducible for a given input.
+pub fn to_ical(db: &QuestDatabase, progress: &PlayerProgress, now: Timestamp) -> String {
+    let stamp = format_utc(now.millis());
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(
+        &mut out,
+        "PRODID:-//better_questing_tools//quest cooldowns//EN",
+    );
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    for a in upcoming(db, progress, now) {
+        let name = db
+            .quests
+            .get(&a.quest)
+            .map(render_name)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("Quest {}", a.quest));
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(
+            &mut out,
+            &format!("UID:quest-{}-{}@better-questing-tools", a.quest, a.player),
+        );
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART:{}", format_utc(a.available_at.millis())),
+        );
+        push_line(
+            &mut out,
+            &format!(
+                "SUMMARY:{}",
+                escape_text(&format!("{} is available again", name))
+            ),
+        );
+        push_line(
+            &mut out,
+            &format!(
+                "DESCRIPTION:{}",
+                escape_text(&format!("Quest {} for player {}", a.quest, a.player))
+            ),
+        );
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Escape a TEXT value (RFC 5545 §3.3.11).
+fn escape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append a content line, folded at 75 octets and terminated by CRLF.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Format epoch milliseconds as an iCalendar UTC date-time (`YYYYMMDDTHHMMSSZ`).
+fn format_utc(ms: i64) -> String {
+    let secs = ms.div_euclid(1000);
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    // Civil-from-days conversion (proleptic Gregorian calendar).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(format_utc(0), "19700101T000000Z");
+        assert_eq!(format_utc(1_700_000_000_123), "20231114T221320Z");
+        assert_eq!(format_utc(951_782_400_000), "20000229T000000Z");
+    }
+
+    #[test]
+    fn folds_long_lines() {
+        let mut out = String::new();
+        push_line(&mut out, &"x".repeat(80));
+        assert_eq!(out, format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5)));
+    }
+}
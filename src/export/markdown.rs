@@ -0,0 +1,241 @@
+//! Markdown questbook: one document per questline.
+//!
+//! `questbook` renders every questline, in questline order, as a page with a
+//! section per entry: the quest name as heading, its description, a
+//! prerequisite list, its tasks and its rewards. Prerequisites link to the
+//! quest's section on the first page that lists it (`02-iron-age.md#quest-12`
+//! across pages, `#quest-12` on the same page); quests that are on no
+//! questline are named without a link. `write_questbook` writes the pages to
+//! a folder, numbered so that a directory listing keeps the book order.
+//!
+//! Text is plain (formatting codes removed) with Markdown syntax escaped, so
+//! quest names cannot break the layout.
+use super::{line_name, quest_title, sorted_quests};
+use crate::error::Result;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::{render_description, render_reward, render_task, strip_formatting};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// One rendered questline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestbookPage {
+    /// The questline the page documents.
+    pub questline: QuestId,
+    /// File name of the page, e.g. `01-getting-started.md`.
+    pub file_name: String,
+    /// Markdown text.
+    pub markdown: String,
+}
+
+/// Backslash-escape characters with inline Markdown meaning.
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '~'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Lowercase ASCII slug of `name`, or `questline` when nothing is left.
+pub(super) fn slug(name: &str) -> String {
+    let mut out = String::new();
+    for c in strip_formatting(name).chars() {
+        if c.is_ascii_alphanumeric() {
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    let trimmed = out.trim_end_matches('-');
+    if trimmed.is_empty() {
+        "questline".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn anchor(quest: &QuestId) -> String {
+    format!("quest-{}", quest)
+}
+
+/// Description text as Markdown: escaped, line breaks kept.
+fn description(props: &impl DescriptionPages) -> String {
+    let lines: Vec<String> = render_description(props)
+        .lines()
+        .map(|l| escape_markdown(l.trim_end()))
+        .collect();
+    // A trailing backslash is a hard line break; blank lines stay paragraph
+    // breaks.
+    let mut out = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        out.push_str(line);
+        if i + 1 < lines.len() {
+            let next_blank = lines[i + 1].is_empty();
+            if line.is_empty() || next_blank {
+                out.push('\n');
+            } else {
+                out.push_str("\\\n");
+            }
+        }
+    }
+    out
+}
+
+/// Append a bold heading and a bullet list, unless `items` is empty.
+fn push_list(out: &mut String, heading: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    out.push_str(&format!("**{}:**\n\n", heading));
+    for item in items {
+        out.push_str(&format!("- {}\n", escape_markdown(item)));
+    }
+    out.push('\n');
+}
+
+fn section(
+    db: &QuestDatabase,
+    quest: &Quest,
+    file_name: &str,
+    pages_of: &HashMap<QuestId, &str>,
+) -> String {
+    let mut out = format!(
+        "<a id=\"{}\"></a>\n\n## {}\n\n",
+        anchor(&quest.id),
+        escape_markdown(&quest_title(db, &quest.id))
+    );
+    if let Some(desc) = quest.properties.as_ref().map(description)
+        && !desc.is_empty()
+    {
+        out.push_str(&desc);
+        out.push_str("\n\n");
+    }
+    let prereqs: Vec<(QuestId, bool)> = quest
+        .required_prerequisites
+        .iter()
+        .map(|p| (*p, false))
+        .chain(quest.optional_prerequisites.iter().map(|p| (*p, true)))
+        .collect();
+    if !prereqs.is_empty() {
+        out.push_str("**Requires:**\n\n");
+        for (p, optional) in prereqs {
+            let title = escape_markdown(&quest_title(db, &p));
+            let link = match pages_of.get(&p) {
+                Some(page) if *page == file_name => format!("[{}](#{})", title, anchor(&p)),
+                Some(page) => format!("[{}]({}#{})", title, page, anchor(&p)),
+                None => title,
+            };
+            out.push_str(&format!(
+                "- {}{}\n",
+                link,
+                if optional { " (optional)" } else { "" }
+            ));
+        }
+        out.push('\n');
+    }
+    let tasks: Vec<String> = quest.tasks.iter().map(render_task).collect();
+    push_list(&mut out, "Tasks", &tasks);
+    let rewards: Vec<String> = quest.rewards.iter().map(render_reward).collect();
+    push_list(&mut out, "Rewards", &rewards);
+    out
+}
+
+/// Render one page per questline, in `ordering::questline_ids` order. Entries appear in
+/// questline order; entries naming missing quests are skipped.
+pub fn questbook(db: &QuestDatabase) -> Vec<QuestbookPage> {
+    let lines = sorted_questlines(db);
+    let file_names: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{:02}-{}.md", i + 1, slug(&line_name(line))))
+        .collect();
+    let mut pages_of: HashMap<QuestId, &str> = HashMap::new();
+    for (line, file_name) in lines.iter().zip(&file_names) {
+        for entry in &line.entries {
+            pages_of.entry(entry.quest_id).or_insert(file_name);
+        }
+    }
+
+    lines
+        .iter()
+        .zip(&file_names)
+        .map(|(line, file_name)| {
+            let mut out = format!("# {}\n\n", escape_markdown(&line_name(line)));
+            if let Some(desc) = line.properties.as_ref().map(description)
+                && !desc.is_empty()
+            {
+                out.push_str(&desc);
+                out.push_str("\n\n");
+            }
+            for entry in &line.entries {
+                if let Some(quest) = db.quests.get(&entry.quest_id) {
+                    out.push_str(&section(db, quest, file_name, &pages_of));
+                }
+            }
+            QuestbookPage {
+                questline: line.id,
+                file_name: file_name.clone(),
+                markdown: format!("{}\n", out.trim_end()),
+            }
+        })
+        .collect()
+}
+
+/// Markdown index linking every page of `pages`, followed by the quests that
+/// are on no questline and so have no section of their own.
+pub fn questbook_index(db: &QuestDatabase, pages: &[QuestbookPage]) -> String {
+    let mut out = String::from("# Questbook\n\n");
+    for page in pages {
+        let name = db
+            .questlines
+            .get(&page.questline)
+            .map(line_name)
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "- [{}]({})\n",
+            escape_markdown(&name),
+            page.file_name
+        ));
+    }
+    let listed: HashSet<QuestId> = db
+        .questlines
+        .values()
+        .flat_map(|l| l.entries.iter().map(|e| e.quest_id))
+        .collect();
+    let loose: Vec<&Quest> = sorted_quests(db)
+        .into_iter()
+        .filter(|q| !listed.contains(&q.id))
+        .collect();
+    if !loose.is_empty() {
+        out.push_str("\n## Quests on no questline\n\n");
+        for quest in loose {
+            out.push_str(&format!(
+                "- {} ({})\n",
+                escape_markdown(&quest_title(db, &quest.id)),
+                quest.id
+            ));
+        }
+    }
+    out
+}
+
+/// Write the questbook to `dir` (created if needed): `README.md` with the
+/// index and one file per questline.
+pub fn write_questbook(db: &QuestDatabase, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let pages = questbook(db);
+    std::fs::write(dir.join("README.md"), questbook_index(db, &pages))?;
+    for page in &pages {
+        std::fs::write(dir.join(&page.file_name), &page.markdown)?;
+    }
+    Ok(())
+}
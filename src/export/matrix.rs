@@ -0,0 +1,55 @@
+//! Weighted adjacency matrix of the prerequisite graph, for numeric tools
+//! (NumPy, R, spreadsheets).
+//!
+//! Rows and columns follow ascending quest id. Cell `(i, j)` holds the
+//! importance weight of the link from prerequisite `i` to dependent `j`
+//! (1 required, `1/n` for each of `n` optional prerequisites, 0 for links of
+//! `XOR` quests) and is empty when there is no link, so weighted and absent
+//! links stay distinguishable.
+use super::{FLOAT_PRECISION, csv_record, format_float, sorted_quests, weighted_links};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use std::collections::HashMap;
+
+/// Dense weighted adjacency matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdjacencyMatrix {
+    /// Quest of each row and column.
+    pub quests: Vec<QuestId>,
+    /// `weights[i][j]`: weight of the link from `quests[i]` to `quests[j]`.
+    pub weights: Vec<Vec<Option<f64>>>,
+}
+
+impl AdjacencyMatrix {
+    /// Build the matrix of `db`; prerequisites missing from the database
+    /// are left out.
+    pub fn build(db: &QuestDatabase) -> Self {
+        let quests: Vec<QuestId> = sorted_quests(db).iter().map(|q| q.id).collect();
+        let pos: HashMap<QuestId, usize> =
+            quests.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+        let mut weights = vec![vec![None; quests.len()]; quests.len()];
+        for (quest, prereq, _, weight) in weighted_links(db) {
+            weights[pos[&prereq]][pos[&quest]] = Some(weight);
+        }
+        AdjacencyMatrix { quests, weights }
+    }
+
+    /// CSV with a header row and a leading column of quest ids.
+    pub fn to_csv(&self) -> String {
+        let ids: Vec<String> = self.quests.iter().map(QuestId::to_string).collect();
+        let mut out = csv_record(std::iter::once("").chain(ids.iter().map(String::as_str)));
+        for (id, row) in ids.iter().zip(&self.weights) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|w| {
+                    w.map(|w| format_float(w, FLOAT_PRECISION))
+                        .unwrap_or_default()
+                })
+                .collect();
+            out.push_str(&csv_record(
+                std::iter::once(id.as_str()).chain(cells.iter().map(String::as_str)),
+            ));
+        }
+        out
+    }
+}
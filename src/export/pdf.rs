@@ -0,0 +1,406 @@
+//! Printable PDF pack report.
+//!
+//! `to_pdf` lays out a text-only report of one pack on A4 pages: the pack
+//! statistics of `batch::pack_summary`, one short summary per questline in
+//! questline order and a table of the most important quests (scored like
+//! `report::compare_databases` does). `write_pdf_reports` renders a report for
+//! every pack of a batch, for progression reviews shared with people who do
+//! not run the tools themselves.
+//!
+//! The PDF uses the standard Helvetica fonts, so no font files are embedded;
+//! text is plain (formatting codes removed) and characters outside Latin-1
+//! print as `?`.
+use super::{line_name, quest_title};
+use crate::batch::{load_pack, pack_summary};
+use crate::error::{ParseError, Result};
+use crate::importance::{compute_importance_scores, ranking};
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::report::IMPORTANCE_ALPHA;
+use crate::stats::quest_depths;
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const REGULAR: Name<'static> = Name(b"F1");
+const BOLD: Name<'static> = Name(b"F2");
+
+/// Column offsets of the importance table: rank, score, id, name.
+const RANKING_COLUMNS: &[f32] = &[0.0, 36.0, 100.0, 230.0];
+/// Column offsets of two-column tables.
+const PAIR_COLUMNS: &[f32] = &[0.0, 300.0];
+
+/// What goes into the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfReportOptions {
+    /// Report title; `write_pdf_reports` uses the pack's file name when
+    /// unset.
+    pub title: Option<String>,
+    /// Rows of the importance table.
+    pub top: usize,
+}
+
+impl Default for PdfReportOptions {
+    fn default() -> Self {
+        PdfReportOptions {
+            title: None,
+            top: 25,
+        }
+    }
+}
+
+/// One line of the report before pagination.
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Title(String),
+    Heading(String),
+    Subheading(String),
+    Text(String),
+    /// Cells at the given column offsets; bold for table headers.
+    Row {
+        cells: Vec<String>,
+        columns: &'static [f32],
+        bold: bool,
+    },
+    Gap,
+}
+
+impl Line {
+    fn size(&self) -> f32 {
+        match self {
+            Line::Title(_) => 20.0,
+            Line::Heading(_) => 14.0,
+            Line::Subheading(_) => 11.0,
+            _ => 10.0,
+        }
+    }
+
+    fn height(&self) -> f32 {
+        match self {
+            Line::Gap => 6.0,
+            Line::Heading(_) => self.size() * 2.0,
+            _ => self.size() * 1.4,
+        }
+    }
+}
+
+fn row(cells: Vec<String>, columns: &'static [f32], bold: bool) -> Line {
+    Line::Row {
+        cells,
+        columns,
+        bold,
+    }
+}
+
+/// Text in the single-byte encoding of the standard fonts.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match u8::try_from(c) {
+            Ok(b) if b >= 0x20 => b,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// `text` cut to roughly `width` points at `size`, marked with `...`.
+fn fit(text: &str, width: f32, size: f32) -> String {
+    // Half the font size is a generous average Helvetica glyph width.
+    let max = ((width / (size * 0.5)) as usize).max(4);
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max - 3).collect();
+    format!("{}...", cut.trim_end())
+}
+
+fn stats_lines(db: &QuestDatabase, out: &mut Vec<Line>) {
+    let stats = pack_summary(Path::new(""), db);
+    out.push(Line::Heading("Statistics".to_string()));
+    let numbers = [
+        ("Quests", stats.quests.to_string()),
+        ("Questlines", stats.questlines.to_string()),
+        ("Tasks", stats.tasks.to_string()),
+        ("Rewards", stats.rewards.to_string()),
+        ("Prerequisite links", stats.prerequisite_links.to_string()),
+        ("Repeatable quests", stats.repeatable_quests.to_string()),
+        (
+            "Prerequisite depth",
+            format!(
+                "median {}, 90th percentile {}, max {}",
+                stats.depth.median, stats.depth.p90, stats.depth.max
+            ),
+        ),
+    ];
+    for (label, value) in numbers {
+        out.push(row(vec![label.to_string(), value], PAIR_COLUMNS, false));
+    }
+    for (heading, types) in [
+        ("Task type", &stats.task_types),
+        ("Reward type", &stats.reward_types),
+    ] {
+        if types.is_empty() {
+            continue;
+        }
+        let mut types: Vec<(&String, &usize)> = types.iter().collect();
+        types.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        out.push(Line::Gap);
+        out.push(row(
+            vec![heading.to_string(), "Count".to_string()],
+            PAIR_COLUMNS,
+            true,
+        ));
+        for (id, count) in types {
+            out.push(row(
+                vec![id.clone(), count.to_string()],
+                PAIR_COLUMNS,
+                false,
+            ));
+        }
+    }
+}
+
+fn questline_lines(
+    db: &QuestDatabase,
+    scores: Option<&HashMap<QuestId, f64>>,
+    out: &mut Vec<Line>,
+) {
+    let lines = sorted_questlines(db);
+    if lines.is_empty() {
+        return;
+    }
+    let depths = quest_depths(db);
+    out.push(Line::Heading("Questlines".to_string()));
+    for line in lines {
+        let quests: Vec<&Quest> = line
+            .entries
+            .iter()
+            .filter_map(|e| db.quests.get(&e.quest_id))
+            .collect();
+        out.push(Line::Subheading(line_name(line)));
+        let tasks: usize = quests.iter().map(|q| q.tasks.len()).sum();
+        let rewards: usize = quests.iter().map(|q| q.rewards.len()).sum();
+        let mut summary = format!(
+            "{} quests, {} tasks, {} rewards",
+            quests.len(),
+            tasks,
+            rewards
+        );
+        let line_depths = quests.iter().filter_map(|q| depths.get(&q.id));
+        if let (Some(min), Some(max)) = (line_depths.clone().min(), line_depths.max()) {
+            summary.push_str(&format!(", depth {} to {}", min, max));
+        }
+        out.push(Line::Text(summary));
+        if let Some(scores) = scores {
+            let mut ranked: Vec<(QuestId, f64)> = quests
+                .iter()
+                .filter_map(|q| scores.get(&q.id).map(|s| (q.id, *s)))
+                .collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let top: Vec<String> = ranked
+                .iter()
+                .take(3)
+                .map(|(id, _)| quest_title(db, id))
+                .collect();
+            if !top.is_empty() {
+                out.push(Line::Text(format!("Most important: {}", top.join(", "))));
+            }
+        }
+        out.push(Line::Gap);
+    }
+}
+
+fn ranking_lines(
+    db: &QuestDatabase,
+    scores: &Result<HashMap<QuestId, f64>>,
+    top: usize,
+    out: &mut Vec<Line>,
+) {
+    out.push(Line::Heading("Most important quests".to_string()));
+    let scores = match scores {
+        Ok(scores) => scores,
+        Err(e) => {
+            out.push(Line::Text(format!("Importance scores unavailable: {}", e)));
+            return;
+        }
+    };
+    out.push(row(
+        ["#", "Score", "Id", "Quest"].map(str::to_string).to_vec(),
+        RANKING_COLUMNS,
+        true,
+    ));
+    for (i, (id, score)) in ranking(scores).into_iter().take(top).enumerate() {
+        out.push(row(
+            vec![
+                (i + 1).to_string(),
+                format!("{:.4}", score),
+                id.to_string(),
+                quest_title(db, &id),
+            ],
+            RANKING_COLUMNS,
+            false,
+        ));
+    }
+}
+
+fn report_lines(db: &QuestDatabase, title: &str, options: &PdfReportOptions) -> Vec<Line> {
+    let scores = compute_importance_scores(db, IMPORTANCE_ALPHA, true, true);
+    let mut out = vec![Line::Title(title.to_string()), Line::Gap];
+    stats_lines(db, &mut out);
+    out.push(Line::Gap);
+    questline_lines(db, scores.as_ref().ok(), &mut out);
+    ranking_lines(db, &scores, options.top, &mut out);
+    out
+}
+
+fn show(content: &mut Content, font: Name, size: f32, x: f32, y: f32, text: &str) {
+    content
+        .begin_text()
+        .set_font(font, size)
+        .next_line(x, y)
+        .show(Str(&encode(text)))
+        .end_text();
+}
+
+/// Lay `lines` out on pages, returning one content stream per page.
+fn paginate(lines: &[Line]) -> Vec<Content> {
+    let text_width = PAGE_WIDTH - 2.0 * MARGIN;
+    let mut pages = Vec::new();
+    let mut content = Content::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for (i, line) in lines.iter().enumerate() {
+        // Keep headings with the line that follows them.
+        let needed = match line {
+            Line::Heading(_) | Line::Subheading(_) => {
+                line.height() + lines.get(i + 1).map_or(0.0, Line::height)
+            }
+            _ => line.height(),
+        };
+        if y - needed < MARGIN {
+            pages.push(std::mem::replace(&mut content, Content::new()));
+            y = PAGE_HEIGHT - MARGIN;
+            if matches!(line, Line::Gap) {
+                continue;
+            }
+        }
+        y -= line.height();
+        let size = line.size();
+        match line {
+            Line::Title(text) | Line::Heading(text) | Line::Subheading(text) => {
+                let text = fit(text, text_width, size);
+                show(&mut content, BOLD, size, MARGIN, y, &text);
+            }
+            Line::Text(text) => {
+                let text = fit(text, text_width, size);
+                show(&mut content, REGULAR, size, MARGIN, y, &text);
+            }
+            Line::Row {
+                cells,
+                columns,
+                bold,
+            } => {
+                let font = if *bold { BOLD } else { REGULAR };
+                for (c, (cell, x)) in cells.iter().zip(columns.iter()).enumerate() {
+                    let next = columns.get(c + 1).copied().unwrap_or(text_width);
+                    let text = fit(cell, next - x - 6.0, size);
+                    show(&mut content, font, size, MARGIN + x, y, &text);
+                }
+            }
+            Line::Gap => {}
+        }
+    }
+    pages.push(content);
+    let total = pages.len();
+    for (i, content) in pages.iter_mut().enumerate() {
+        let footer = format!("{} / {}", i + 1, total);
+        show(
+            content,
+            REGULAR,
+            8.0,
+            PAGE_WIDTH - MARGIN - 24.0,
+            MARGIN / 2.0,
+            &footer,
+        );
+    }
+    pages
+}
+
+/// Render the report of `db` as PDF bytes. The title defaults to
+/// `Quest pack report`.
+pub fn to_pdf(db: &QuestDatabase, options: &PdfReportOptions) -> Vec<u8> {
+    let title = options.title.as_deref().unwrap_or("Quest pack report");
+    let pages = paginate(&report_lines(db, title, options));
+
+    let catalog_id = Ref::new(1);
+    let tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    let info_id = Ref::new(5);
+    // Page i is object 6 + 2i, its content stream the one after.
+    let page_ids: Vec<Ref> = (0..pages.len())
+        .map(|i| Ref::new(6 + 2 * i as i32))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(tree_id);
+    pdf.pages(tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages.len() as i32);
+    pdf.type1_font(regular_id).base_font(Name(b"Helvetica"));
+    pdf.type1_font(bold_id).base_font(Name(b"Helvetica-Bold"));
+    pdf.document_info(info_id)
+        .title(TextStr(title))
+        .producer(TextStr("BetterQuestingTools"));
+    for (page_id, content) in page_ids.into_iter().zip(pages) {
+        let content_id = Ref::new(page_id.get() + 1);
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(tree_id)
+            .contents(content_id);
+        let mut resources = page.resources();
+        resources
+            .fonts()
+            .pair(REGULAR, regular_id)
+            .pair(BOLD, bold_id);
+        drop(resources);
+        drop(page);
+        pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+}
+
+/// Write the report of every pack (a DefaultQuests folder, or a pack zip
+/// with the `zip` feature) to `out_dir` (created if needed) as
+/// `<pack file name>.pdf`, titled with the pack name unless
+/// `options.title` is set. Results are the written paths in input order; a
+/// pack that fails yields its error without stopping the batch.
+pub fn write_pdf_reports<P: AsRef<Path>>(
+    packs: &[P],
+    out_dir: &Path,
+    options: &PdfReportOptions,
+) -> Vec<Result<PathBuf>> {
+    packs
+        .iter()
+        .map(|p| {
+            let path = p.as_ref();
+            let wrap =
+                |e: ParseError| ParseError::InvalidFormat(format!("{}: {}", path.display(), e));
+            let db = load_pack(path).map_err(wrap)?;
+            let name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "pack".to_string());
+            let options = PdfReportOptions {
+                title: options.title.clone().or_else(|| Some(name.clone())),
+                ..options.clone()
+            };
+            std::fs::create_dir_all(out_dir)?;
+            let out = out_dir.join(format!("{}.pdf", name));
+            std::fs::write(&out, to_pdf(&db, &options))?;
+            Ok(out)
+        })
+        .collect()
+}
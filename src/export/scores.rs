@@ -0,0 +1,65 @@
+//! Importance score tables.
+//!
+//! Renders the output of `importance::compute_importance_scores` as a ranked
+//! CSV or JSON table, in the order of `importance::ranking`.
+use super::{FLOAT_PRECISION, csv_record, format_float};
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// One row of the ranking.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreRow {
+    /// 1-based rank, highest score first.
+    pub rank: usize,
+    /// Quest id.
+    pub id: QuestId,
+    /// Quest name, empty when the quest is unknown or unnamed.
+    pub name: String,
+    /// Score rounded to `FLOAT_PRECISION` decimals.
+    pub score: f64,
+}
+
+/// Rank `scores` descending, ties broken by ascending quest id.
+pub fn ranked_rows(db: &QuestDatabase, scores: &HashMap<QuestId, f64>) -> Vec<ScoreRow> {
+    crate::importance::ranking(scores)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, score))| ScoreRow {
+            rank: i + 1,
+            id,
+            name: db
+                .quests
+                .get(&id)
+                .and_then(|q| q.name())
+                .map(crate::text::strip_formatting)
+                .unwrap_or_default(),
+            score,
+        })
+        .collect()
+}
+
+/// Render the ranking as CSV with a `rank,id,name,score` header.
+pub fn to_csv(db: &QuestDatabase, scores: &HashMap<QuestId, f64>) -> String {
+    let mut out = String::from("rank,id,name,score\n");
+    for row in ranked_rows(db, scores) {
+        let rank = row.rank.to_string();
+        let id = row.id.to_string();
+        let score = format_float(row.score, FLOAT_PRECISION);
+        out.push_str(&csv_record([
+            rank.as_str(),
+            id.as_str(),
+            row.name.as_str(),
+            score.as_str(),
+        ]));
+    }
+    out
+}
+
+/// Render the ranking as a pretty-printed JSON array.
+pub fn to_json(db: &QuestDatabase, scores: &HashMap<QuestId, f64>) -> Result<String> {
+    serde_json::to_string_pretty(&ranked_rows(db, scores))
+        .map_err(|e| ParseError::Export(e.to_string()))
+}
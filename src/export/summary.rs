@@ -0,0 +1,109 @@
+//! Condensed, human-reviewable pack summary.
+//!
+//! The summary keeps only what a reviewer scans for in a pull request: quest
+//! names, prerequisites and the rewards handed out. Ids are rendered as
+//! strings because TOML integers are signed 64-bit and BetterQuesting ids are
+//! not.
+use crate::model::*;
+use crate::ordering::{sorted_questlines, sorted_quests};
+use crate::quest_id::QuestId;
+use crate::text::render_reward;
+use serde::{Deserialize, Serialize};
+
+/// Summary of a single quest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestSummary {
+    /// Quest id.
+    pub id: String,
+    /// Display name (empty when the quest has no properties).
+    pub name: String,
+    /// Required prerequisite ids.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prerequisites: Vec<String>,
+    /// Optional (one-of) prerequisite ids.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub optional_prerequisites: Vec<String>,
+    /// One line per reward, e.g. `4x minecraft:bread`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rewards: Vec<String>,
+}
+
+/// Summary of a questline: its name and member quests in entry order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestLineSummary {
+    /// Questline id.
+    pub id: String,
+    /// Questline name, if the line has properties.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Member quest ids.
+    pub quests: Vec<String>,
+}
+
+/// Top-level summary document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackSummary {
+    /// Number of quests in the database.
+    pub quest_count: usize,
+    /// Questlines in presentation order.
+    #[serde(default)]
+    pub questlines: Vec<QuestLineSummary>,
+    /// Quests in ascending id order.
+    #[serde(default)]
+    pub quests: Vec<QuestSummary>,
+}
+
+fn ids(list: &[QuestId]) -> Vec<String> {
+    list.iter().map(QuestId::to_string).collect()
+}
+
+/// Build the summary of `db`.
+pub fn summarize(db: &QuestDatabase) -> PackSummary {
+    let questlines = sorted_questlines(db)
+        .into_iter()
+        .map(|line| QuestLineSummary {
+            id: line.id.to_string(),
+            name: line.properties.as_ref().map(|p| p.name.clone()),
+            quests: line
+                .entries
+                .iter()
+                .map(|e| e.quest_id.to_string())
+                .collect(),
+        })
+        .collect();
+
+    let quests = sorted_quests(db)
+        .into_iter()
+        .map(|q| QuestSummary {
+            id: q.id.to_string(),
+            name: q.name().unwrap_or_default().to_string(),
+            prerequisites: if q.required_prerequisites.is_empty() {
+                ids(&q.prerequisites)
+            } else {
+                ids(&q.required_prerequisites)
+            },
+            optional_prerequisites: ids(&q.optional_prerequisites),
+            rewards: q.rewards.iter().map(render_reward).collect(),
+        })
+        .collect();
+
+    PackSummary {
+        quest_count: db.quests.len(),
+        questlines,
+        quests,
+    }
+}
+
+/// Render the summary of `db` as TOML.
+#[cfg(feature = "toml")]
+pub fn to_toml(db: &QuestDatabase) -> crate::error::Result<String> {
+    toml::to_string_pretty(&summarize(db))
+        .map_err(|e| crate::error::ParseError::Export(e.to_string()))
+}
+
+/// Render the summary of `db` as YAML.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(db: &QuestDatabase) -> crate::error::Result<String> {
+    serde_norway::to_string(&summarize(db))
+        .map_err(|e| crate::error::ParseError::Export(e.to_string()))
+}
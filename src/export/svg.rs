@@ -0,0 +1,253 @@
+//! SVG drawings of questline layouts.
+//!
+//! `questline_to_svg` draws a questline the way the quest book lays it out:
+//! every entry as a tile at its `x`/`y` with its size, captioned with the
+//! quest name, and an arrow from each prerequisite on the same questline to
+//! its dependent (dashed for optional prerequisites). Arrows run between tile
+//! edges and are drawn below the tiles. Entries naming missing quests are
+//! drawn as dashed tiles captioned with their id, main quests in a stronger
+//! color. Hovering a tile shows the quest name and id. Tiles are placed by
+//! the same rules as `analysis::check_layout`: missing coordinates count as
+//! `0` and missing sizes as `SvgOptions::default_size`.
+//!
+//! The drawing is sized to the tiles plus `SvgOptions::margin`, so it is
+//! meant for documentation and layout review rather than as a pixel-exact
+//! copy of the game screen. `write_questline_svgs` writes one file per
+//! questline, named like the Markdown questbook pages.
+use super::line_name;
+use super::markdown::slug;
+use super::{escape_html, format_float};
+use crate::analysis::layout::Tile;
+use crate::error::Result;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Knobs for `questline_to_svg_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Width and height of entries without a size.
+    pub default_size: i32,
+    /// Free space around the tiles, also holding the captions of the bottom
+    /// row.
+    pub margin: i32,
+    /// Caption font size, in layout units.
+    pub font_size: i32,
+    /// Wrap the tile of every existing quest in a link to `quest-<id>.html`,
+    /// the quest pages of `site::build_site`.
+    pub quest_links: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            default_size: 24,
+            margin: 24,
+            font_size: 6,
+            quest_links: false,
+        }
+    }
+}
+
+/// One drawn questline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestlineSvg {
+    /// The questline drawn.
+    pub questline: QuestId,
+    /// File name of the drawing, e.g. `01-getting-started.svg`.
+    pub file_name: String,
+    /// SVG document.
+    pub svg: String,
+}
+
+/// Distance along the unit vector `(dx, dy)` from the center of `tile` to
+/// its edge.
+fn edge_distance(tile: &Tile, dx: f64, dy: f64) -> f64 {
+    let along = |half: f64, d: f64| {
+        if d == 0.0 {
+            f64::INFINITY
+        } else {
+            half / d.abs()
+        }
+    };
+    along(tile.width as f64 / 2.0, dx).min(along(tile.height as f64 / 2.0, dy))
+}
+
+fn num(value: f64) -> String {
+    let text = format_float(value, 1);
+    text.strip_suffix(".0").map(str::to_string).unwrap_or(text)
+}
+
+/// An arrow from the edge of `from` to the edge of `to`, or `None` when the
+/// tiles overlap so much that there is nothing to draw.
+fn arrow(from: &Tile, to: &Tile, optional: bool) -> Option<String> {
+    let (x1, y1) = from.center();
+    let (x2, y2) = to.center();
+    let length = (x2 - x1).hypot(y2 - y1);
+    if length == 0.0 {
+        return None;
+    }
+    let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+    let start = edge_distance(from, dx, dy);
+    let end = length - edge_distance(to, dx, dy);
+    if end <= start {
+        return None;
+    }
+    let dash = if optional {
+        " stroke-dasharray=\"3 2\""
+    } else {
+        ""
+    };
+    Some(format!(
+        "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" class=\"prerequisite\"{} marker-end=\"url(#arrow)\"/>\n",
+        num(x1 + dx * start),
+        num(y1 + dy * start),
+        num(x1 + dx * end),
+        num(y1 + dy * end),
+        dash
+    ))
+}
+
+/// Draw `line` with `SvgOptions::default()`.
+pub fn questline_to_svg(db: &QuestDatabase, line: &QuestLine) -> String {
+    questline_to_svg_with(db, line, &SvgOptions::default())
+}
+
+/// Draw `line` with `options`.
+pub fn questline_to_svg_with(db: &QuestDatabase, line: &QuestLine, options: &SvgOptions) -> String {
+    let tiles: Vec<Tile> = line
+        .entries
+        .iter()
+        .map(|e| Tile::new(e, options.default_size))
+        .collect();
+    // a quest listed twice gets its arrows at its first tile only
+    let mut first_tile: HashMap<QuestId, usize> = HashMap::new();
+    for (i, tile) in tiles.iter().enumerate() {
+        first_tile.entry(tile.quest).or_insert(i);
+    }
+
+    let margin = options.margin as f64;
+    let bounds = tiles
+        .iter()
+        .map(|t| (t.x as f64, t.y as f64, t.right() as f64, t.bottom() as f64));
+    let (left, top, right, bottom) = bounds
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+        .unwrap_or_default();
+    let (left, top) = (left - margin, top - margin);
+    let (width, height) = (right + margin - left, bottom + margin - top);
+
+    let name = line_name(line);
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"{}\">\n",
+        num(left),
+        num(top),
+        num(width),
+        num(height),
+        num(width),
+        num(height),
+        options.font_size
+    );
+    out.push_str(&format!("  <title>{}</title>\n", escape_html(&name)));
+    out.push_str(concat!(
+        "  <defs>\n",
+        "    <marker id=\"arrow\" viewBox=\"0 0 6 6\" refX=\"6\" refY=\"3\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\n",
+        "      <path d=\"M0,0 L6,3 L0,6 z\" fill=\"#555\"/>\n",
+        "    </marker>\n",
+        "  </defs>\n",
+        "  <style>\n",
+        "    .prerequisite { stroke: #555; stroke-width: 1; }\n",
+        "    .quest rect { fill: #e8e0c8; stroke: #6b5a3a; stroke-width: 1; }\n",
+        "    .quest.main rect { fill: #f4d58d; stroke: #a06000; }\n",
+        "    .quest.missing rect { fill: none; stroke: #b00; stroke-dasharray: 2 2; }\n",
+        "    .quest text { fill: #222; text-anchor: middle; }\n",
+        "  </style>\n",
+    ));
+
+    // arrows first so the tiles cover their ends
+    for (i, tile) in tiles.iter().enumerate() {
+        let Some(quest) = db.quests.get(&tile.quest) else {
+            continue;
+        };
+        if first_tile[&quest.id] != i {
+            continue;
+        }
+        let mut prereqs: Vec<(QuestId, bool)> = quest
+            .required_prerequisites
+            .iter()
+            .map(|p| (*p, false))
+            .chain(quest.optional_prerequisites.iter().map(|p| (*p, true)))
+            .collect();
+        prereqs.sort();
+        prereqs.dedup_by_key(|(p, _)| *p);
+        for (prereq, optional) in prereqs {
+            if let Some(from) = first_tile.get(&prereq)
+                && let Some(arrow) = arrow(&tiles[*from], tile, optional)
+            {
+                out.push_str(&arrow);
+            }
+        }
+    }
+
+    for tile in &tiles {
+        let quest = db.quests.get(&tile.quest);
+        let caption = quest
+            .and_then(|q| q.name())
+            .map(|n| strip_formatting(n).trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| tile.quest.to_string());
+        let main = quest
+            .and_then(|q| q.properties.as_ref())
+            .and_then(|p| p.is_main)
+            .unwrap_or(false);
+        let class = match (quest, main) {
+            (None, _) => "quest missing",
+            (Some(_), true) => "quest main",
+            (Some(_), false) => "quest",
+        };
+        let (cx, _) = tile.center();
+        let mut group = format!(
+            "<g class=\"{}\">\n    <title>{} ({})</title>\n    <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\"/>\n    <text x=\"{}\" y=\"{}\">{}</text>\n  </g>",
+            class,
+            escape_html(&caption),
+            tile.quest,
+            tile.x,
+            tile.y,
+            tile.width,
+            tile.height,
+            num(cx),
+            tile.bottom() + options.font_size as i64,
+            escape_html(&caption)
+        );
+        if options.quest_links && quest.is_some() {
+            group = format!("<a href=\"quest-{}.html\">{}</a>", tile.quest, group);
+        }
+        out.push_str(&format!("  {}\n", group));
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Draw every questline, in `ordering::questline_ids` order.
+pub fn questline_svgs(db: &QuestDatabase, options: &SvgOptions) -> Vec<QuestlineSvg> {
+    sorted_questlines(db)
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| QuestlineSvg {
+            questline: line.id,
+            file_name: format!("{:02}-{}.svg", i + 1, slug(&line_name(line))),
+            svg: questline_to_svg_with(db, line, options),
+        })
+        .collect()
+}
+
+/// Write `questline_svgs` to `dir` (created if needed).
+pub fn write_questline_svgs(db: &QuestDatabase, dir: &Path, options: &SvgOptions) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for drawing in questline_svgs(db, options) {
+        std::fs::write(dir.join(&drawing.file_name), &drawing.svg)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,97 @@
+//! Quest files from sample modpack archives, for tests.
+//!
+//! The crate's snapshot tests run over the client packs listed in
+//! `samples/links.txt`, and downstream tools want the same corpus.
+//! `sample_archives` lists the zips of a folder, `quest_entries` reads the
+//! quest JSON files of one archive into memory, and `sample_quests` parses
+//! every quest of every archive into one map.
+//!
+//! Only files below `config/betterquesting/DefaultQuests/Quests/` are read;
+//! use `archive::ZipDataSource` to load a complete pack with its questlines.
+use crate::error::{ParseError, Result};
+use crate::model::Quest;
+use crate::parser::parse_quest_from_reader;
+use crate::quest_id::QuestId;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Path fragment identifying quest files inside a pack archive.
+pub const QUESTS_PATH: &str = "config/betterquesting/DefaultQuests/Quests/";
+
+/// One quest file read from a sample archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestEntry {
+    /// The archive the file was read from.
+    pub archive: PathBuf,
+    /// Full path of the file inside the archive.
+    pub name: String,
+    /// Raw file contents.
+    pub contents: Vec<u8>,
+}
+
+impl QuestEntry {
+    /// Parse the file as a quest.
+    pub fn parse(&self) -> Result<Quest> {
+        parse_quest_from_reader(self.contents.as_slice())
+    }
+}
+
+fn archive_err(e: zip::result::ZipError) -> ParseError {
+    ParseError::Archive(e.to_string())
+}
+
+/// The `.zip` files directly inside `dir`, sorted by path.
+pub fn sample_archives(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// The quest JSON files of the archive at `path`, in archive order.
+pub fn quest_entries(path: &Path) -> Result<Vec<QuestEntry>> {
+    let data = std::fs::read(path)?;
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(archive_err)?;
+    let mut out = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(archive_err)?;
+        let name = file.name().to_string();
+        if !name.ends_with(".json") || !name.contains(QUESTS_PATH) {
+            continue;
+        }
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        out.push(QuestEntry {
+            archive: path.to_path_buf(),
+            name,
+            contents,
+        });
+    }
+    Ok(out)
+}
+
+/// The quest JSON files of every archive in `dir`.
+pub fn sample_quest_entries(dir: &Path) -> Result<Vec<QuestEntry>> {
+    let mut out = Vec::new();
+    for path in sample_archives(dir)? {
+        out.extend(quest_entries(&path)?);
+    }
+    Ok(out)
+}
+
+/// Parse every quest of every archive in `dir`. When several archives
+/// contain the same quest id, the first one read wins.
+pub fn sample_quests(dir: &Path) -> Result<HashMap<QuestId, Quest>> {
+    let mut quests = HashMap::new();
+    for entry in sample_quest_entries(dir)? {
+        let quest = entry.parse()?;
+        quests.entry(quest.id).or_insert(quest);
+    }
+    Ok(quests)
+}
@@ -0,0 +1,428 @@
+//! Algorithms over the prerequisite graph.
+//!
+//! Edges run from a prerequisite to the quest that requires it; required and
+//! optional prerequisites count alike and references to missing quests are
+//! ignored. Nodes and neighbours are visited in ascending id order, so every
+//! result is deterministic.
+//!
+//! `QuestDatabase::ancestors_of` and `descendants_of` answer single
+//! reachability queries; `QuestGraph` precomputes the closure for many.
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Adjacency lists (prerequisite -> dependents), keyed and sorted by id.
+pub(crate) fn dependents_graph(db: &QuestDatabase) -> BTreeMap<QuestId, BTreeSet<QuestId>> {
+    let mut graph: BTreeMap<QuestId, BTreeSet<QuestId>> =
+        db.quests.keys().map(|q| (*q, BTreeSet::new())).collect();
+    for (qid, quest) in &db.quests {
+        let all = quest
+            .prerequisites
+            .iter()
+            .chain(&quest.required_prerequisites)
+            .chain(&quest.optional_prerequisites);
+        for p in all {
+            if let Some(deps) = graph.get_mut(p) {
+                deps.insert(*qid);
+            }
+        }
+    }
+    graph
+}
+
+/// A completion order respecting prerequisites: every quest comes after all
+/// of its (present) prerequisites. Among the quests available at each step
+/// the smallest id goes first, so the order is deterministic.
+///
+/// Fails with `CycleDetected` listing the members of the first cyclic
+/// strongly connected component when no such order exists.
+pub fn topological_order(db: &QuestDatabase) -> Result<Vec<QuestId>> {
+    let graph = dependents_graph(db);
+    let mut pending: HashMap<QuestId, usize> = graph.keys().map(|q| (*q, 0)).collect();
+    for deps in graph.values() {
+        for d in deps {
+            if let Some(n) = pending.get_mut(d) {
+                *n += 1;
+            }
+        }
+    }
+    let mut ready: BTreeSet<QuestId> = pending
+        .iter()
+        .filter(|(_, n)| **n == 0)
+        .map(|(q, _)| *q)
+        .collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(q) = ready.pop_first() {
+        order.push(q);
+        for d in graph.get(&q).into_iter().flatten() {
+            let Some(n) = pending.get_mut(d) else {
+                continue;
+            };
+            *n -= 1;
+            if *n == 0 {
+                ready.insert(*d);
+            }
+        }
+    }
+    if order.len() < graph.len() {
+        let cycle = condensation(db)
+            .components
+            .into_iter()
+            .find(|c| c.cyclic)
+            .map(|c| c.members)
+            .unwrap_or_default();
+        return Err(ParseError::CycleDetected(cycle));
+    }
+    Ok(order)
+}
+
+/// A strongly connected component of the prerequisite graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Component {
+    /// Member quests, ascending.
+    pub members: Vec<QuestId>,
+    /// True when the members are mutually locked: more than one member, or a
+    /// single quest listing itself as a prerequisite.
+    pub cyclic: bool,
+}
+
+/// The prerequisite graph with every strongly connected component collapsed
+/// into one node. The result is always acyclic.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condensation {
+    /// Components ordered by their smallest member.
+    pub components: Vec<Component>,
+    /// Edges between components as indices into `components`, sorted and
+    /// without duplicates.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Condensation {
+    /// Index of the component containing `quest`.
+    pub fn component_of(&self, quest: QuestId) -> Option<usize> {
+        self.components
+            .iter()
+            .position(|c| c.members.binary_search(&quest).is_ok())
+    }
+
+    /// Returns true if any component is cyclic.
+    pub fn has_cycles(&self) -> bool {
+        self.components.iter().any(|c| c.cyclic)
+    }
+}
+
+/// Strongly connected components (Tarjan's algorithm, iterative so long
+/// chains cannot overflow the stack). Members are sorted, components ordered
+/// by their smallest member.
+pub fn strongly_connected_components(db: &QuestDatabase) -> Vec<Vec<QuestId>> {
+    let graph = dependents_graph(db);
+    let nodes: Vec<QuestId> = graph.keys().copied().collect();
+    let pos: HashMap<QuestId, usize> = nodes.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+    let adj: Vec<Vec<usize>> = graph
+        .values()
+        .map(|deps| deps.iter().map(|d| pos[d]).collect())
+        .collect();
+
+    let mut index: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut low = vec![0; nodes.len()];
+    let mut on_stack = vec![false; nodes.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut next_index = 0;
+    let mut out: Vec<Vec<QuestId>> = Vec::new();
+
+    for root in 0..nodes.len() {
+        if index[root].is_some() {
+            continue;
+        }
+        // (node, next neighbour to visit)
+        let mut work: Vec<(usize, usize)> = vec![(root, 0)];
+        while let Some(&mut (v, ref mut next)) = work.last_mut() {
+            if *next == 0 && index[v].is_none() {
+                index[v] = Some(next_index);
+                low[v] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = adj[v].get(*next) {
+                *next += 1;
+                match index[w] {
+                    None => work.push((w, 0)),
+                    Some(iw) if on_stack[w] => low[v] = low[v].min(iw),
+                    Some(_) => {}
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if Some(low[v]) == index[v] {
+                let mut members = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    members.push(nodes[w]);
+                    if w == v {
+                        break;
+                    }
+                }
+                members.sort();
+                out.push(members);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+/// Collapse every strongly connected component into a single node.
+pub fn condensation(db: &QuestDatabase) -> Condensation {
+    let graph = dependents_graph(db);
+    let members = strongly_connected_components(db);
+    let comp_of: HashMap<QuestId, usize> = members
+        .iter()
+        .enumerate()
+        .flat_map(|(i, m)| m.iter().map(move |q| (*q, i)))
+        .collect();
+
+    let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+    let mut self_loops: BTreeSet<usize> = BTreeSet::new();
+    for (from, deps) in &graph {
+        for to in deps {
+            let (a, b) = (comp_of[from], comp_of[to]);
+            if a == b {
+                self_loops.insert(a);
+            } else {
+                edges.insert((a, b));
+            }
+        }
+    }
+    let components = members
+        .into_iter()
+        .enumerate()
+        .map(|(i, members)| Component {
+            cyclic: members.len() > 1 || self_loops.contains(&i),
+            members,
+        })
+        .collect();
+    Condensation {
+        components,
+        edges: edges.into_iter().collect(),
+    }
+}
+
+/// Fixed-size set of node indices. With the `roaring` feature this is a
+/// compressed roaring bitmap, which stays small for packs with thousands of
+/// quests and mostly sparse ancestor sets; otherwise a plain bit vector.
+#[cfg(feature = "roaring")]
+mod bits {
+    #[derive(Debug, Clone, Default)]
+    pub(super) struct Bits(roaring::RoaringBitmap);
+
+    impl Bits {
+        pub(super) fn new(_len: usize) -> Self {
+            Bits::default()
+        }
+        pub(super) fn insert(&mut self, i: usize) {
+            self.0.insert(i as u32);
+        }
+        pub(super) fn contains(&self, i: usize) -> bool {
+            self.0.contains(i as u32)
+        }
+        pub(super) fn union_with(&mut self, other: &Bits) {
+            self.0 |= &other.0;
+        }
+        pub(super) fn len(&self) -> usize {
+            self.0.len() as usize
+        }
+        pub(super) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+            self.0.iter().map(|i| i as usize)
+        }
+    }
+}
+
+#[cfg(not(feature = "roaring"))]
+mod bits {
+    #[derive(Debug, Clone, Default)]
+    pub(super) struct Bits(Vec<u64>);
+
+    impl Bits {
+        pub(super) fn new(len: usize) -> Self {
+            Bits(vec![0; len.div_ceil(64)])
+        }
+        pub(super) fn insert(&mut self, i: usize) {
+            self.0[i / 64] |= 1 << (i % 64);
+        }
+        pub(super) fn contains(&self, i: usize) -> bool {
+            self.0.get(i / 64).is_some_and(|w| w & (1 << (i % 64)) != 0)
+        }
+        pub(super) fn union_with(&mut self, other: &Bits) {
+            for (a, b) in self.0.iter_mut().zip(&other.0) {
+                *a |= b;
+            }
+        }
+        pub(super) fn len(&self) -> usize {
+            self.0.iter().map(|w| w.count_ones() as usize).sum()
+        }
+        pub(super) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+            self.0.iter().enumerate().flat_map(|(n, w)| {
+                (0..64)
+                    .filter(move |b| w & (1 << b) != 0)
+                    .map(move |b| n * 64 + b)
+            })
+        }
+    }
+}
+
+/// Precomputed transitive closure of the prerequisite graph, for tools that
+/// ask "is A (transitively) required before B" many times.
+///
+/// Every strongly connected component gets one ancestor set, built in
+/// topological order of the condensation as the union of its direct
+/// predecessors' sets and members, so construction costs one set union per
+/// condensation edge. Queries are then an index lookup plus a bit test.
+#[derive(Debug, Clone)]
+pub struct QuestGraph {
+    /// Quests in ascending id order; positions are the bit indices.
+    nodes: Vec<QuestId>,
+    pos: HashMap<QuestId, usize>,
+    /// Component of each node.
+    component: Vec<usize>,
+    /// Ancestors of every member of each component.
+    ancestors: Vec<bits::Bits>,
+}
+
+impl QuestGraph {
+    /// Compute the ancestor sets of every quest in `db`.
+    pub fn build(db: &QuestDatabase) -> Self {
+        let graph = condensation(db);
+        let nodes: Vec<QuestId> = dependents_graph(db).into_keys().collect();
+        let pos: HashMap<QuestId, usize> = nodes.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+        let mut component = vec![0; nodes.len()];
+        for (c, comp) in graph.components.iter().enumerate() {
+            for q in &comp.members {
+                component[pos[q]] = c;
+            }
+        }
+
+        let n = graph.components.len();
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut pending = vec![0usize; n];
+        let mut succs: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for &(from, to) in &graph.edges {
+            preds[to].push(from);
+            succs[from].push(to);
+            pending[to] += 1;
+        }
+        let mut ancestors: Vec<bits::Bits> = vec![bits::Bits::new(nodes.len()); n];
+        let mut ready: Vec<usize> = (0..n).filter(|c| pending[*c] == 0).collect();
+        while let Some(c) = ready.pop() {
+            let mut set = bits::Bits::new(nodes.len());
+            for &p in &preds[c] {
+                set.union_with(&ancestors[p]);
+                for q in &graph.components[p].members {
+                    set.insert(pos[q]);
+                }
+            }
+            // Members of a cycle require each other, and themselves.
+            if graph.components[c].cyclic {
+                for q in &graph.components[c].members {
+                    set.insert(pos[q]);
+                }
+            }
+            ancestors[c] = set;
+            for &s in &succs[c] {
+                pending[s] -= 1;
+                if pending[s] == 0 {
+                    ready.push(s);
+                }
+            }
+        }
+        QuestGraph {
+            nodes,
+            pos,
+            component,
+            ancestors,
+        }
+    }
+
+    /// True when `a` is a direct or transitive prerequisite of `b`. A quest
+    /// is its own ancestor only when it lies on a prerequisite cycle; unknown
+    /// quests have no ancestors.
+    pub fn is_ancestor(&self, a: QuestId, b: QuestId) -> bool {
+        match (self.pos.get(&a), self.pos.get(&b)) {
+            (Some(&a), Some(&b)) => self.ancestors[self.component[b]].contains(a),
+            _ => false,
+        }
+    }
+
+    /// All transitive prerequisites of `quest`, in ascending id order.
+    pub fn ancestors(&self, quest: QuestId) -> Vec<QuestId> {
+        self.pos
+            .get(&quest)
+            .map(|&i| {
+                self.ancestors[self.component[i]]
+                    .iter()
+                    .map(|a| self.nodes[a])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Number of transitive prerequisites of `quest`.
+    pub fn ancestor_count(&self, quest: QuestId) -> usize {
+        self.pos
+            .get(&quest)
+            .map_or(0, |&i| self.ancestors[self.component[i]].len())
+    }
+}
+
+/// Every quest reachable from `start` through `next`, in ascending id order.
+/// `start` itself is included only when a walk leads back to it.
+fn reachable<'a, I>(start: QuestId, mut next: impl FnMut(QuestId) -> I) -> Vec<QuestId>
+where
+    I: Iterator<Item = &'a QuestId>,
+{
+    let mut seen: BTreeSet<QuestId> = BTreeSet::new();
+    let mut stack = vec![start];
+    while let Some(q) = stack.pop() {
+        for n in next(q) {
+            if seen.insert(*n) {
+                stack.push(*n);
+            }
+        }
+    }
+    seen.into_iter().collect()
+}
+
+impl QuestDatabase {
+    /// All direct and transitive prerequisites of `quest`, in ascending id
+    /// order. Like `QuestGraph::ancestors`, but without precomputing the
+    /// whole closure, for one-off queries. A quest is its own ancestor only
+    /// when it lies on a prerequisite cycle.
+    pub fn ancestors_of(&self, quest: QuestId) -> Vec<QuestId> {
+        reachable(quest, |q| {
+            self.quests
+                .get(&q)
+                .into_iter()
+                .flat_map(|quest| {
+                    quest
+                        .prerequisites
+                        .iter()
+                        .chain(&quest.required_prerequisites)
+                        .chain(&quest.optional_prerequisites)
+                })
+                .filter(|p| self.quests.contains_key(p))
+        })
+    }
+
+    /// All quests that directly or transitively require `quest`, in
+    /// ascending id order: everything its completion helps unlock. A quest is
+    /// its own descendant only when it lies on a prerequisite cycle.
+    pub fn descendants_of(&self, quest: QuestId) -> Vec<QuestId> {
+        let graph = dependents_graph(self);
+        reachable(quest, |q| graph.get(&q).into_iter().flatten())
+    }
+}
@@ -0,0 +1,184 @@
+//! Resolution of quest ids that name the same quest.
+//!
+//! Packs in the middle of a migration from legacy `questIDHigh`/`questIDLow`
+//! ids to UUIDs can refer to one quest by both: the quest file already uses
+//! its UUID while an older questline entry or prerequisite still uses the
+//! legacy id, or both versions of the quest file are present. Without help,
+//! the database then holds phantom duplicates and dangling references.
+//!
+//! `IdAliases` maps alias ids to canonical ones. Aliases come from a mapping
+//! file (`read_mapping`) or from the name heuristic `by_name`, and `unify`
+//! rewrites a database so that only canonical ids remain. Passing aliases in
+//! `db::ParseOptions` unifies while parsing, before questline references are
+//! checked.
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+
+/// Alias to canonical quest id mapping. Questline ids are a separate
+/// namespace and are left alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdAliases {
+    aliases: BTreeMap<QuestId, QuestId>,
+}
+
+/// Parse an id written as a packed legacy number (as printed by `QuestId`'s
+/// `Display`) or as a UUID.
+fn parse_id(text: &str) -> Option<QuestId> {
+    match text.parse::<u64>() {
+        Ok(packed) => Some(QuestId::from_u64(packed)),
+        Err(_) => QuestId::from_uuid_str(text),
+    }
+}
+
+impl IdAliases {
+    /// No aliases.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `alias` another name of `canonical`. Aliases map directly to
+    /// canonical ids; chains are not followed.
+    pub fn insert(&mut self, alias: QuestId, canonical: QuestId) {
+        if alias != canonical {
+            self.aliases.insert(alias, canonical);
+        }
+    }
+
+    /// Returns true if there are no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// The canonical id of `id` (`id` itself when it is no alias).
+    pub fn resolve(&self, id: QuestId) -> QuestId {
+        self.aliases.get(&id).copied().unwrap_or(id)
+    }
+
+    /// Alias and canonical id pairs, by alias.
+    pub fn iter(&self) -> impl Iterator<Item = (QuestId, QuestId)> + '_ {
+        self.aliases.iter().map(|(a, c)| (*a, *c))
+    }
+
+    /// Read a mapping file: one `alias canonical` pair per line, the ids
+    /// separated by whitespace, `,` or `=`. Ids are packed legacy numbers or
+    /// UUIDs; blank lines and lines starting with `#` are skipped.
+    pub fn read_mapping<R: Read>(mut r: R) -> Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let mut out = IdAliases::new();
+        for (no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts: Vec<&str> = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == '=')
+                .filter(|p| !p.is_empty())
+                .collect();
+            let ids: Vec<QuestId> = parts.iter().filter_map(|p| parse_id(p)).collect();
+            if parts.len() != 2 || ids.len() != 2 {
+                return Err(ParseError::InvalidFormat(format!(
+                    "id mapping line {}: expected two ids, got '{}'",
+                    no + 1,
+                    line
+                )));
+            }
+            out.insert(ids[0], ids[1]);
+        }
+        Ok(out)
+    }
+
+    /// Guess aliases from quest names: when exactly one legacy id (fitting
+    /// `i32` parts) and exactly one wide UUID id share a name (formatting
+    /// codes and surrounding whitespace ignored), the legacy id becomes an
+    /// alias of the UUID.
+    pub fn by_name(db: &QuestDatabase) -> Self {
+        let mut by_name: BTreeMap<String, (Vec<QuestId>, Vec<QuestId>)> = BTreeMap::new();
+        for quest in db.quests.values() {
+            let Some(name) = quest.name() else { continue };
+            let name = strip_formatting(name).trim().to_string();
+            if name.is_empty() {
+                continue;
+            }
+            let (legacy, wide) = by_name.entry(name).or_default();
+            if quest.id.fits_i32() {
+                legacy.push(quest.id);
+            } else {
+                wide.push(quest.id);
+            }
+        }
+        let mut out = IdAliases::new();
+        for (legacy, wide) in by_name.values() {
+            if let ([alias], [canonical]) = (legacy.as_slice(), wide.as_slice()) {
+                out.insert(*alias, *canonical);
+            }
+        }
+        out
+    }
+
+    /// Rewrite `db` to canonical quest ids: quest keys, prerequisites and the
+    /// quests of questline entries. Questline ids and the questline order are
+    /// not touched. An alias quest whose canonical id is also present is a
+    /// phantom duplicate and is dropped; references that collapse onto the
+    /// same id are kept once. Returns the dropped `(alias, canonical)` pairs,
+    /// by alias.
+    pub fn unify(&self, db: &mut QuestDatabase) -> Vec<(QuestId, QuestId)> {
+        let mut dropped = Vec::new();
+        if self.is_empty() {
+            return dropped;
+        }
+
+        let mut quests: Vec<_> = std::mem::take(&mut db.quests).into_values().collect();
+        quests.sort_by_key(|q| q.id);
+        let present: HashSet<QuestId> = quests.iter().map(|q| q.id).collect();
+        for mut quest in quests {
+            let canonical = self.resolve(quest.id);
+            if canonical != quest.id && present.contains(&canonical) {
+                dropped.push((quest.id, canonical));
+                continue;
+            }
+            quest.id = canonical;
+            for list in [
+                &mut quest.prerequisites,
+                &mut quest.required_prerequisites,
+                &mut quest.optional_prerequisites,
+            ] {
+                self.resolve_list(list);
+            }
+            db.quests.insert(canonical, quest);
+        }
+
+        for line in db.questlines.values_mut() {
+            let mut seen = HashSet::new();
+            line.entries.retain_mut(|e| {
+                e.quest_id = self.resolve(e.quest_id);
+                seen.insert(e.quest_id)
+            });
+        }
+        dropped
+    }
+
+    /// Resolve every id of `list`, keeping the first of any repeats.
+    fn resolve_list(&self, list: &mut Vec<QuestId>) {
+        let mut seen = HashSet::new();
+        list.retain_mut(|id| {
+            *id = self.resolve(*id);
+            seen.insert(*id)
+        });
+    }
+}
+
+impl FromIterator<(QuestId, QuestId)> for IdAliases {
+    fn from_iter<I: IntoIterator<Item = (QuestId, QuestId)>>(iter: I) -> Self {
+        let mut out = IdAliases::new();
+        for (alias, canonical) in iter {
+            out.insert(alias, canonical);
+        }
+        out
+    }
+}
@@ -0,0 +1,314 @@
+//! Quest import from spreadsheet rows.
+//!
+//! Writers who do not want to touch JSON can author quests in a spreadsheet
+//! and export it as CSV. The first row names the columns; `CsvMapping` says
+//! which column holds what, so sheets keep their own headings. Each further
+//! row becomes one quest:
+//!
+//! - the name and (optional) description become the quest properties,
+//! - the items column (`16x minecraft:log; minecraft:wool:14`) becomes one
+//!   `bq_standard:retrieval` task, with the first item as icon,
+//! - the prerequisites column lists quest names, resolved against the other
+//!   rows first and then against the quests already in the pack (with
+//!   `QuestDatabase::resolve_name`, so small typos are forgiven), and rows
+//!   may refer to quests further down the sheet,
+//! - the chapter column places the quest on the questline of that name
+//!   (created when missing), laid out like `wizard::generate_retrieval_quests`
+//!   does.
+//!
+//! Ids are allocated above the highest legacy id in use. Nothing is added
+//! when any row fails to import; errors name the CSV record.
+//!
+//! FTB Quests packs are read by `ftb`.
+
+pub mod ftb;
+
+use crate::analysis::RETRIEVAL_TASK_ID;
+use crate::error::{ParseError, Result};
+use crate::index::split_damage;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use crate::wizard::{GridLayout, chapter_line, free_ids, parse_csv, properties};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Column headings of a quest sheet (compared case-insensitively). The name
+/// column is required; optional columns may be missing from the sheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvMapping {
+    /// Quest names; required, and every row needs one.
+    pub name: String,
+    /// Quest descriptions.
+    pub desc: Option<String>,
+    /// Items to collect, separated by `separator`.
+    pub items: Option<String>,
+    /// Names of required quests, separated by `separator`.
+    pub prerequisites: Option<String>,
+    /// Name of the questline the quest goes on.
+    pub chapter: Option<String>,
+    /// Separator of list cells.
+    pub separator: char,
+    /// Placement of the questline entries.
+    pub layout: GridLayout,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            name: "name".to_string(),
+            desc: Some("desc".to_string()),
+            items: Some("items".to_string()),
+            prerequisites: Some("prerequisites".to_string()),
+            chapter: Some("chapter".to_string()),
+            separator: ';',
+            layout: GridLayout::default(),
+        }
+    }
+}
+
+/// One quest row of a sheet, after splitting its cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct QuestRow {
+    record: usize,
+    name: String,
+    desc: Option<String>,
+    items: Vec<ItemStack>,
+    prerequisites: Vec<String>,
+    chapter: Option<String>,
+}
+
+fn csv_error(record: usize, message: impl std::fmt::Display) -> ParseError {
+    ParseError::InvalidFormat(format!("CSV record {}: {}", record, message))
+}
+
+/// Parse `[<count>x ]<id>[:<damage>]`, e.g. `16x minecraft:log`.
+fn parse_item(record: usize, text: &str) -> Result<ItemStack> {
+    let (count, id) = match text.split_once(char::is_whitespace) {
+        Some((n, rest)) => {
+            let count: i32 = n
+                .strip_suffix(['x', 'X'])
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| csv_error(record, format!("invalid item count in {:?}", text)))?;
+            (count, rest.trim())
+        }
+        None => (1, text),
+    };
+    if count < 1 {
+        return Err(ParseError::NumberOutOfRange {
+            field: format!("item count (CSV record {})", record),
+            value: count.to_string(),
+        });
+    }
+    let (id, damage) = split_damage(id);
+    Ok(ItemStack {
+        id,
+        damage,
+        count: Some(count),
+        oredict: None,
+        extra: HashMap::new(),
+    })
+}
+
+fn split_list(cell: &str, separator: char) -> impl Iterator<Item = &str> {
+    cell.split(separator)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Read the sheet into rows, checking the header against `mapping`.
+fn read_quest_rows(text: &str, mapping: &CsvMapping) -> Result<Vec<QuestRow>> {
+    let mut records = parse_csv(text)?.into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| ParseError::InvalidFormat("CSV has no header row".to_string()))?;
+    let column = |name: &Option<String>| {
+        let name = name.as_deref()?;
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+    };
+    let name_col = column(&Some(mapping.name.clone())).ok_or_else(|| {
+        ParseError::InvalidFormat(format!("CSV has no {:?} column", mapping.name))
+    })?;
+    let desc_col = column(&mapping.desc);
+    let items_col = column(&mapping.items);
+    let prereq_col = column(&mapping.prerequisites);
+    let chapter_col = column(&mapping.chapter);
+
+    let mut rows = Vec::new();
+    for (i, record) in records.enumerate() {
+        // the header is record 1
+        let no = i + 2;
+        let cell = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+        };
+        let name = cell(Some(name_col)).ok_or_else(|| csv_error(no, "missing quest name"))?;
+        let items = cell(items_col)
+            .map(|c| {
+                split_list(c, mapping.separator)
+                    .map(|item| parse_item(no, item))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+        rows.push(QuestRow {
+            record: no,
+            name: name.to_string(),
+            desc: cell(desc_col).map(str::to_string),
+            items,
+            prerequisites: cell(prereq_col)
+                .map(|c| {
+                    split_list(c, mapping.separator)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            chapter: cell(chapter_col).map(str::to_string),
+        });
+    }
+    Ok(rows)
+}
+
+fn name_key(name: &str) -> String {
+    strip_formatting(name).trim().to_string()
+}
+
+/// Resolve prerequisite names to ids: a unique row of the sheet by exact
+/// name first, then a quest of `db` by `QuestDatabase::resolve_name`.
+fn resolve_prerequisites(
+    db: &QuestDatabase,
+    rows: &[QuestRow],
+    ids: &[QuestId],
+) -> Result<Vec<Vec<QuestId>>> {
+    let mut sheet: HashMap<String, Vec<QuestId>> = HashMap::new();
+    for (row, id) in rows.iter().zip(ids) {
+        sheet.entry(name_key(&row.name)).or_default().push(*id);
+    }
+    rows.iter()
+        .zip(ids)
+        .map(|(row, id)| {
+            let mut resolved = Vec::new();
+            for name in &row.prerequisites {
+                let prereq = match sheet.get(&name_key(name)).map(Vec::as_slice) {
+                    Some([prereq]) => *prereq,
+                    Some(found) => {
+                        return Err(csv_error(
+                            row.record,
+                            format!("{} quests are named {:?}", found.len(), name),
+                        ));
+                    }
+                    None => match db.resolve_name(name) {
+                        Ok(Some(prereq)) => prereq,
+                        Ok(None) => {
+                            return Err(csv_error(
+                                row.record,
+                                format!("no quest named {:?}", name),
+                            ));
+                        }
+                        Err(e @ ParseError::AmbiguousName { .. }) => {
+                            return Err(csv_error(row.record, e));
+                        }
+                        Err(e) => return Err(e),
+                    },
+                };
+                if prereq == *id {
+                    return Err(csv_error(row.record, "quest requires itself"));
+                }
+                if !resolved.contains(&prereq) {
+                    resolved.push(prereq);
+                }
+            }
+            Ok(resolved)
+        })
+        .collect()
+}
+
+fn row_quest(id: QuestId, row: &QuestRow, prerequisites: Vec<QuestId>) -> Quest {
+    let mut props = properties(row.name.clone());
+    props.desc = row.desc.clone();
+    props.icon = row.items.first().map(|item| ItemStack {
+        count: Some(1),
+        ..item.clone()
+    });
+    let tasks = if row.items.is_empty() {
+        Vec::new()
+    } else {
+        vec![Task {
+            index: Some(0),
+            task_id: RETRIEVAL_TASK_ID.to_string(),
+            required_items: row.items.clone(),
+            ignore_nbt: None,
+            partial_match: None,
+            auto_consume: None,
+            consume: None,
+            group_detect: None,
+            options: HashMap::new(),
+        }]
+    };
+    Quest {
+        id,
+        properties: Some(props),
+        tasks,
+        rewards: Vec::new(),
+        prerequisites: prerequisites.clone(),
+        required_prerequisites: prerequisites,
+        optional_prerequisites: Vec::new(),
+    }
+}
+
+/// Add one quest per row of the CSV sheet in `reader` to `db`, as described
+/// in the module docs, and return the new ids in row order.
+pub fn import_csv<R: Read>(
+    db: &mut QuestDatabase,
+    mut reader: R,
+    mapping: &CsvMapping,
+) -> Result<Vec<QuestId>> {
+    if mapping.layout.columns == 0 {
+        return Err(ParseError::NumberOutOfRange {
+            field: "columns".to_string(),
+            value: "0".to_string(),
+        });
+    }
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let rows = read_quest_rows(&text, mapping)?;
+    let ids: Vec<QuestId> = free_ids(db.quests.keys(), rows.len())?
+        .map(|low| QuestId::from_parts_i64(0, low))
+        .collect();
+    let prerequisites = resolve_prerequisites(db, &rows, &ids)?;
+
+    let layout = &mapping.layout;
+    for ((row, id), prereqs) in rows.iter().zip(&ids).zip(prerequisites) {
+        if let Some(chapter) = &row.chapter {
+            let line = chapter_line(db, chapter)?;
+            let slot = line.entries.len();
+            line.entries.push(QuestLineEntry {
+                index: None,
+                quest_id: *id,
+                x: Some((slot % layout.columns) as i32 * layout.spacing),
+                y: Some((slot / layout.columns) as i32 * layout.spacing),
+                size_x: Some(layout.size),
+                size_y: Some(layout.size),
+                extra: HashMap::new(),
+            });
+        }
+        db.quests.insert(*id, row_quest(*id, row, prereqs));
+    }
+    Ok(ids)
+}
+
+/// A new database holding the quests of the CSV sheet in `reader`, with ids
+/// counting from 0.
+pub fn from_csv<R: Read>(reader: R, mapping: &CsvMapping) -> Result<QuestDatabase> {
+    let mut db = QuestDatabase {
+        settings: None,
+        quests: HashMap::new(),
+        questlines: HashMap::new(),
+        questline_order: Vec::new(),
+    };
+    import_csv(&mut db, reader, mapping)?;
+    Ok(db)
+}
@@ -0,0 +1,533 @@
+//! Import of an FTB Quests `quests` folder.
+//!
+//! The inverse of `export::ftb`: `from_ftb_source` reads
+//! `chapters/*.snbt` below an FTB Quests `config/ftbquests/quests` folder
+//! and builds a `QuestDatabase`, so tools written against this crate work on
+//! FTB packs too. Files are SNBT, read by `parse_snbt` into the same JSON
+//! shape as the NBT readers produce (without type suffixes).
+//!
+//! - chapters become questlines in `order_index` order (ties in natural file
+//!   name order), with their subtitle as description; quests become quests on
+//!   their chapter's line and quest links further entries. Grid positions
+//!   are scaled by 24 pixels per unit;
+//! - FTB's 16 hex digit ids become `QuestId::from_u64`, so references stay
+//!   intact and ids are stable between imports;
+//! - dependencies become required prerequisites (`one_completed` logic
+//!   becomes `OR`), `can_repeat` a zero repeat time and `invisible` hidden
+//!   visibility;
+//! - item, checkmark, kill and XP tasks and item, XP, XP level and command
+//!   rewards become their `bq_standard` counterparts. Other types keep their
+//!   fields as options under an `ftbquests:<type>` id.
+//!
+//! Fields without a BetterQuesting counterpart (subtitles of quests, shapes,
+//! chapter groups, `data.snbt`) are dropped.
+use crate::db::{FsDataSource, QuestDataSource};
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::ordering::natural_cmp;
+use crate::quest_id::QuestId;
+use crate::wizard::properties;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Pixels of a BetterQuesting layout per FTB grid unit.
+const PIXELS_PER_UNIT: f64 = 24.0;
+
+/// Reader of SNBT text.
+struct SnbtReader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+/// A bare SNBT token ends at whitespace or punctuation.
+fn ends_token(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ',' | ':' | '{' | '}' | '[' | ']' | '"' | '\'')
+}
+
+impl SnbtReader<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::InvalidFormat(format!("SNBT: {} at byte {}", message, self.pos))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    /// Skip whitespace and the commas separating entries, which FTB Quests
+    /// leaves out in favour of line breaks.
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() && c != ',' {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, wanted: char) -> Result<()> {
+        self.skip_separators();
+        if self.peek() != Some(wanted) {
+            return Err(self.error(&format!("expected `{}`", wanted)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String> {
+        self.pos += 1;
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, escaped)) => out.push(escaped),
+                    None => break,
+                },
+                c if c == quote => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn token(&mut self) -> &str {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if ends_token(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn key(&mut self) -> Result<String> {
+        self.skip_separators();
+        match self.peek() {
+            Some(q @ ('"' | '\'')) => self.quoted(q),
+            _ => {
+                let key = self.token();
+                if key.is_empty() {
+                    return Err(self.error("expected a key"));
+                }
+                Ok(key.to_string())
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_separators();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let mut map = Map::new();
+                loop {
+                    self.skip_separators();
+                    if self.peek() == Some('}') {
+                        self.pos += 1;
+                        return Ok(Value::Object(map));
+                    }
+                    let key = self.key()?;
+                    self.expect(':')?;
+                    map.insert(key, self.value()?);
+                }
+            }
+            Some('[') => {
+                self.pos += 1;
+                // typed arrays: [B; 1b, 2b], [I; 1, 2], [L; 1L]
+                let rest = &self.text[self.pos..];
+                if matches!(rest.get(..2), Some("B;" | "I;" | "L;")) {
+                    self.pos += 2;
+                }
+                let mut items = Vec::new();
+                loop {
+                    self.skip_separators();
+                    if self.peek() == Some(']') {
+                        self.pos += 1;
+                        return Ok(Value::Array(items));
+                    }
+                    items.push(self.value()?);
+                }
+            }
+            Some(q @ ('"' | '\'')) => Ok(Value::String(self.quoted(q)?)),
+            Some(_) => {
+                let token = self.token();
+                if token.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(bare_value(token))
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+}
+
+/// Interpret an unquoted token: a boolean, a number with an optional type
+/// suffix, or else a string.
+fn bare_value(token: &str) -> Value {
+    match token {
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+    let (digits, suffix) = match token.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&token[..i], Some(c.to_ascii_lowercase())),
+        _ => (token, None),
+    };
+    match suffix {
+        None | Some('b' | 's' | 'l') => {
+            if let Ok(n) = digits.parse::<i64>() {
+                return Value::Number(n.into());
+            }
+        }
+        _ => {}
+    }
+    match suffix {
+        None | Some('f' | 'd') => {
+            if let Some(n) = digits.parse::<f64>().ok().and_then(Number::from_f64) {
+                return Value::Number(n);
+            }
+        }
+        _ => {}
+    }
+    Value::String(token.to_string())
+}
+
+/// Parse one SNBT document.
+pub fn parse_snbt(text: &str) -> Result<Value> {
+    let mut reader = SnbtReader { text, pos: 0 };
+    let value = reader.value()?;
+    reader.skip_separators();
+    if reader.pos < text.len() {
+        return Err(reader.error("trailing characters"));
+    }
+    Ok(value)
+}
+
+/// An FTB object id (hex string, or number in old files) as a `QuestId`.
+fn ftb_id(value: &Value) -> Option<QuestId> {
+    match value {
+        Value::String(s) => u64::from_str_radix(s, 16).ok().map(QuestId::from_u64),
+        Value::Number(n) => n.as_i64().map(|n| QuestId::from_u64(n as u64)),
+        _ => None,
+    }
+}
+
+fn string(map: &Map<String, Value>, key: &str) -> Option<String> {
+    map.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn number(map: &Map<String, Value>, key: &str) -> Option<f64> {
+    map.get(key).and_then(Value::as_f64)
+}
+
+fn flag(map: &Map<String, Value>, key: &str) -> bool {
+    match map.get(key) {
+        Some(Value::Bool(b)) => *b,
+        Some(v) => v.as_i64().is_some_and(|n| n != 0),
+        None => false,
+    }
+}
+
+fn pixels(units: f64) -> i32 {
+    (units * PIXELS_PER_UNIT).round() as i32
+}
+
+/// Lines of an FTB text list split into pages at `{@pagebreak}`.
+fn pages(lines: Option<&Value>) -> Vec<String> {
+    let Some(Value::Array(lines)) = lines else {
+        return Vec::new();
+    };
+    let mut pages = vec![Vec::new()];
+    for line in lines.iter().filter_map(Value::as_str) {
+        if line == "{@pagebreak}" {
+            pages.push(Vec::new());
+        } else if let Some(page) = pages.last_mut() {
+            page.push(line);
+        }
+    }
+    pages
+        .into_iter()
+        .map(|lines| lines.join("\n"))
+        .filter(|page| !page.is_empty())
+        .collect()
+}
+
+/// An item given as a plain id or as `{id, Count, tag}`, with `count`.
+fn stack(item: Option<&Value>, count: Option<i64>) -> Option<ItemStack> {
+    let (id, stored, tag) = match item? {
+        Value::String(id) => (id.clone(), None, None),
+        Value::Object(map) => (
+            string(map, "id")?,
+            map.get("Count").and_then(Value::as_i64),
+            map.get("tag").cloned(),
+        ),
+        _ => return None,
+    };
+    let count = count.or(stored).unwrap_or(1);
+    Some(ItemStack {
+        id,
+        damage: None,
+        count: Some(i32::try_from(count).unwrap_or(i32::MAX)),
+        oredict: None,
+        extra: tag
+            .map(|tag| ("tag".to_string(), tag))
+            .into_iter()
+            .collect(),
+    })
+}
+
+/// The fields of an unconverted task or reward, minus its id and type.
+fn raw_fields(map: &Map<String, Value>) -> HashMap<String, Value> {
+    map.iter()
+        .filter(|(k, _)| !matches!(k.as_str(), "id" | "type"))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+fn entry_type(map: &Map<String, Value>) -> String {
+    let kind = string(map, "type").unwrap_or_default();
+    kind.strip_prefix("ftbquests:").unwrap_or(&kind).to_string()
+}
+
+fn task(index: usize, map: &Map<String, Value>) -> Task {
+    let mut task = Task {
+        index: Some(index),
+        task_id: String::new(),
+        required_items: Vec::new(),
+        ignore_nbt: None,
+        partial_match: None,
+        auto_consume: None,
+        consume: None,
+        group_detect: None,
+        options: HashMap::new(),
+    };
+    let count = map.get("count").and_then(Value::as_i64);
+    match entry_type(map).as_str() {
+        "item" if stack(map.get("item"), count).is_some() => {
+            task.task_id = "bq_standard:retrieval".to_string();
+            task.required_items = stack(map.get("item"), count).into_iter().collect();
+            if map.contains_key("consume_items") {
+                task.consume = Some(flag(map, "consume_items"));
+            }
+        }
+        "checkmark" => task.task_id = "bq_standard:checkbox".to_string(),
+        "kill" if map.contains_key("entity") => {
+            task.task_id = HUNT_TASK_ID.to_string();
+            task.options
+                .insert("target".to_string(), map["entity"].clone());
+            let kills = map.get("value").and_then(Value::as_i64).unwrap_or(100);
+            task.options.insert("required".to_string(), kills.into());
+        }
+        "xp" => {
+            task.task_id = "bq_standard:xp".to_string();
+            let amount = map.get("value").and_then(Value::as_i64).unwrap_or(1);
+            task.options.insert("amount".to_string(), amount.into());
+            let levels = !flag(map, "points");
+            task.options
+                .insert("isLevels".to_string(), (levels as i64).into());
+        }
+        other => {
+            task.task_id = format!("ftbquests:{}", other);
+            task.options = raw_fields(map);
+        }
+    }
+    task
+}
+
+fn reward(index: usize, map: &Map<String, Value>) -> Reward {
+    let mut reward = Reward {
+        index: Some(index),
+        reward_id: String::new(),
+        items: Vec::new(),
+        choices: Vec::new(),
+        ignore_disabled: None,
+        extra: HashMap::new(),
+    };
+    let xp = |reward: &mut Reward, key: &str, levels: bool| {
+        reward.reward_id = "bq_standard:xp".to_string();
+        let amount = map.get(key).and_then(Value::as_i64).unwrap_or(0);
+        reward.extra.insert("amount".to_string(), amount.into());
+        reward
+            .extra
+            .insert("isLevels".to_string(), (levels as i64).into());
+    };
+    let count = map.get("count").and_then(Value::as_i64);
+    match entry_type(map).as_str() {
+        "item" if stack(map.get("item"), count).is_some() => {
+            reward.reward_id = "bq_standard:item".to_string();
+            reward.items = stack(map.get("item"), count).into_iter().collect();
+        }
+        "xp" => xp(&mut reward, "xp", false),
+        "xp_levels" => xp(&mut reward, "xp_levels", true),
+        "command" => {
+            reward.reward_id = "bq_standard:command".to_string();
+            let command = string(map, "command")
+                .unwrap_or_default()
+                .replace("@p", "VAR_NAME");
+            reward
+                .extra
+                .insert("command".to_string(), Value::String(command));
+        }
+        other => {
+            reward.reward_id = format!("ftbquests:{}", other);
+            reward.extra = raw_fields(map);
+        }
+    }
+    reward
+}
+
+fn objects(value: Option<&Value>) -> impl Iterator<Item = &Map<String, Value>> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+}
+
+fn quest(id: QuestId, map: &Map<String, Value>) -> Quest {
+    let mut props = properties(string(map, "title").unwrap_or_default());
+    props.set_pages(pages(map.get("description")));
+    props.icon = stack(map.get("icon"), None);
+    if let Some("one_completed" | "one_started") = string(map, "dependency_requirement").as_deref()
+    {
+        props.quest_logic = Some("OR".to_string());
+    }
+    if flag(map, "can_repeat") {
+        props.repeat_time = Some(0);
+    }
+    if flag(map, "invisible") {
+        props.visibility = Some(QuestVisibility::Hidden.as_str().to_string());
+    }
+    let dependencies: Vec<QuestId> = map
+        .get("dependencies")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(ftb_id)
+        .collect();
+    Quest {
+        id,
+        properties: Some(props),
+        tasks: objects(map.get("tasks"))
+            .enumerate()
+            .map(|(i, t)| task(i, t))
+            .collect(),
+        rewards: objects(map.get("rewards"))
+            .enumerate()
+            .map(|(i, r)| reward(i, r))
+            .collect(),
+        prerequisites: dependencies.clone(),
+        required_prerequisites: dependencies,
+        optional_prerequisites: Vec::new(),
+    }
+}
+
+fn entry(index: usize, quest_id: QuestId, map: &Map<String, Value>) -> QuestLineEntry {
+    let size = number(map, "size").map(pixels);
+    QuestLineEntry {
+        index: Some(index),
+        quest_id,
+        x: Some(pixels(number(map, "x").unwrap_or(0.0))),
+        y: Some(pixels(number(map, "y").unwrap_or(0.0))),
+        size_x: size,
+        size_y: size,
+        extra: HashMap::new(),
+    }
+}
+
+/// Add the chapter in `value` to `db`; returns its questline id and order.
+fn add_chapter(db: &mut QuestDatabase, path: &str, value: &Value) -> Result<(QuestId, i64)> {
+    let map = value
+        .as_object()
+        .ok_or_else(|| ParseError::InvalidFormat("chapter is not a compound".to_string()))?;
+    let line_id = map
+        .get("id")
+        .and_then(ftb_id)
+        .ok_or_else(|| ParseError::InvalidFormat("chapter has no id".to_string()))?;
+    let mut line_props = QuestLineProperties {
+        name: string(map, "title")
+            .or_else(|| string(map, "filename"))
+            .unwrap_or_default(),
+        icon: stack(map.get("icon"), None),
+        ..Default::default()
+    };
+    line_props.set_pages(pages(map.get("subtitle")));
+    let mut entries = Vec::new();
+    for quest_map in objects(map.get("quests")) {
+        let id = quest_map
+            .get("id")
+            .and_then(ftb_id)
+            .ok_or_else(|| ParseError::InvalidFormat("quest has no id".to_string()))?;
+        if db.quests.contains_key(&id) {
+            return Err(ParseError::DuplicateQuestId(path.to_string()));
+        }
+        entries.push(entry(entries.len(), id, quest_map));
+        db.quests.insert(id, quest(id, quest_map));
+    }
+    for link in objects(map.get("quest_links")) {
+        if let Some(id) = link.get("linked_quest").and_then(ftb_id) {
+            entries.push(entry(entries.len(), id, link));
+        }
+    }
+    if db.questlines.contains_key(&line_id) {
+        return Err(ParseError::InvalidFormat(format!(
+            "duplicate chapter id {}",
+            line_id
+        )));
+    }
+    db.questlines.insert(
+        line_id,
+        QuestLine {
+            id: line_id,
+            properties: Some(line_props),
+            entries,
+            extra: HashMap::new(),
+        },
+    );
+    let order = map.get("order_index").and_then(Value::as_i64).unwrap_or(0);
+    Ok((line_id, order))
+}
+
+/// Build a database from the FTB Quests `quests` folder at `root`.
+pub fn from_ftb_source(source: &dyn QuestDataSource, root: &str) -> Result<QuestDatabase> {
+    let chapters_dir = format!("{}/chapters", root);
+    if !source.is_dir(&chapters_dir) {
+        return Err(ParseError::InvalidFormat(format!(
+            "{} is not an FTB Quests folder (no chapters folder)",
+            root
+        )));
+    }
+    let mut names: Vec<String> = source
+        .list_dir(&chapters_dir)?
+        .into_iter()
+        .filter(|n| n.ends_with(".snbt"))
+        .collect();
+    names.sort_by(|a, b| natural_cmp(a, b));
+    let mut db = QuestDatabase::default();
+    let mut order = Vec::new();
+    for name in names {
+        let path = format!("{}/{}", chapters_dir, name);
+        let (line, index) = source
+            .read_to_string(&path)
+            .and_then(|text| parse_snbt(&text))
+            .and_then(|value| add_chapter(&mut db, &path, &value))
+            .map_err(|e| e.in_file(&path, None))?;
+        order.push((index, line));
+    }
+    // stable: equal order indices keep natural file name order
+    order.sort_by_key(|(index, _)| *index);
+    db.questline_order = order.into_iter().map(|(_, line)| line).collect();
+    Ok(db)
+}
+
+/// `from_ftb_source` on the local filesystem.
+pub fn from_ftb_dir(dir: &Path) -> Result<QuestDatabase> {
+    from_ftb_source(&FsDataSource, &dir.to_string_lossy())
+}
@@ -1,19 +1,233 @@
 use crate::error::{ParseError, Result};
+use crate::export::{FLOAT_PRECISION, round_float};
+use crate::graph::QuestGraph;
 use crate::model::*;
 use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Prerequisite edges of `quest` with their importance weights: 1.0 for
+/// each required prerequisite, `1/n` for each of `n` optional ones. Quests
+/// with `XOR` logic contribute no edges. Graph exports write these weights
+/// so external analyses see the same edge semantics.
+pub fn weighted_prerequisites(quest: &Quest) -> Vec<(QuestId, f64)> {
+    // Exclude all outgoing prerequisite edges for quests with quest_logic == "XOR"
+    let is_xor = quest
+        .properties
+        .as_ref()
+        .and_then(|props| props.quest_logic.as_deref())
+        .is_some_and(|logic| logic.eq_ignore_ascii_case("XOR"));
+    if is_xor {
+        // Skip adding this quest's prerequisite edges to avoid cycles/weight propagation
+        return Vec::new();
+    }
+    // dedupe prerequisites per quest to avoid double counting
+    let mut seen: HashSet<QuestId> = HashSet::new();
+
+    // prefer explicit required_prerequisites; otherwise fall back to
+    // the generic `prerequisites` list. Optionals come from
+    // `optional_prerequisites` when present.
+    let base_required = if !quest.required_prerequisites.is_empty() {
+        &quest.required_prerequisites
+    } else {
+        &quest.prerequisites
+    };
+    let mut edges: Vec<(QuestId, f64)> = base_required
+        .iter()
+        .filter(|p| seen.insert(**p))
+        .map(|p| (*p, 1.0))
+        .collect();
+
+    // optional edges split weight equally among the group's members
+    let optionals: Vec<QuestId> = quest
+        .optional_prerequisites
+        .iter()
+        .filter(|p| seen.insert(**p))
+        .copied()
+        .collect();
+    let w = 1.0 / (optionals.len() as f64);
+    edges.extend(optionals.into_iter().map(|p| (p, w)));
+    edges
+}
+
+/// How far base scores are propagated to prerequisites.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Propagation {
+    /// A quest gains `alpha * weight * base` from each direct dependent.
+    #[default]
+    OneStep,
+    /// A quest gains `alpha^depth * base` from every descendant, summed over
+    /// every prerequisite path with the path's edge weights multiplied, so
+    /// hubs early in the tree collect the whole content behind them.
+    Full,
+}
+
+/// Weight of the edge from a dependent quest to one of its prerequisites,
+/// given the default weight from `weighted_prerequisites`.
+pub type EdgeWeightFn = dyn Fn(&Quest, QuestId, f64) -> f64 + Send + Sync;
+
+/// Factor applied to a quest's base score.
+pub type QuestWeightFn = dyn Fn(&Quest) -> f64 + Send + Sync;
+
+/// Settings of `compute_importance`, built up from `ImportanceConfig::new`:
+///
+/// ```
+/// use better_questing_tools::importance::{ImportanceConfig, Propagation};
+///
+/// let config = ImportanceConfig::new()
+///     .alpha(0.5)
+///     .use_log(true)
+///     .propagation(Propagation::Full)
+///     // optional prerequisites count half as much as usual
+///     .edge_weight(|quest, prereq, weight| {
+///         if quest.optional_prerequisites.contains(&prereq) {
+///             weight / 2.0
+///         } else {
+///             weight
+///         }
+///     });
+/// ```
+#[derive(Clone)]
+pub struct ImportanceConfig {
+    alpha: f64,
+    use_log: bool,
+    normalize: bool,
+    propagation: Propagation,
+    edge_weight: Option<Arc<EdgeWeightFn>>,
+    quest_weight: Option<Arc<QuestWeightFn>>,
+}
+
+impl Default for ImportanceConfig {
+    /// Alpha 0.25, raw counts, no normalization, one-step propagation and
+    /// the default weights.
+    fn default() -> Self {
+        ImportanceConfig {
+            alpha: 0.25,
+            use_log: false,
+            normalize: false,
+            propagation: Propagation::OneStep,
+            edge_weight: None,
+            quest_weight: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ImportanceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportanceConfig")
+            .field("alpha", &self.alpha)
+            .field("use_log", &self.use_log)
+            .field("normalize", &self.normalize)
+            .field("propagation", &self.propagation)
+            .field("edge_weight", &self.edge_weight.is_some())
+            .field("quest_weight", &self.quest_weight.is_some())
+            .finish()
+    }
+}
+
+impl ImportanceConfig {
+    /// The default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Propagation factor (0.0..=1.0) applied to dependent scores.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Apply ln(1 + raw_count) compression to base scores.
+    pub fn use_log(mut self, use_log: bool) -> Self {
+        self.use_log = use_log;
+        self
+    }
+
+    /// Rescale final scores into [0, 1) (max strictly less than 1).
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+
+    /// How far base scores are propagated to prerequisites.
+    pub fn propagation(mut self, propagation: Propagation) -> Self {
+        self.propagation = propagation;
+        self
+    }
+
+    /// Replace the weight of each prerequisite edge. `weight` is called with
+    /// the dependent quest, the prerequisite and the default weight, and
+    /// should return a finite, non-negative weight. Edges of `XOR` quests
+    /// are never weighted, as with the default weights.
+    pub fn edge_weight(
+        mut self,
+        weight: impl Fn(&Quest, QuestId, f64) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        self.edge_weight = Some(Arc::new(weight));
+        self
+    }
+
+    /// Multiply each quest's base score (after log compression) by
+    /// `weight(quest)`, e.g. to favour quests of the main questlines. The
+    /// factor propagates to prerequisites with the base score.
+    pub fn quest_weight(mut self, weight: impl Fn(&Quest) -> f64 + Send + Sync + 'static) -> Self {
+        self.quest_weight = Some(Arc::new(weight));
+        self
+    }
+}
 
 /// Compute one-step importance scores for quests in `db`.
 ///
 /// - `alpha` is the propagation factor (0.0..1.0) applied to dependent bases.
 /// - `use_log` applies ln(1 + raw_count) compression to base counts.
 /// - `normalize` rescales final scores into [0, 1) (max strictly less than 1).
+///
+/// Shorthand for `compute_importance` with those `ImportanceConfig` settings.
 pub fn compute_importance_scores(
     db: &QuestDatabase,
     alpha: f64,
     use_log: bool,
     normalize: bool,
 ) -> Result<HashMap<QuestId, f64>> {
+    compute_importance_scores_with(db, alpha, use_log, normalize, Propagation::OneStep)
+}
+
+/// Compute importance scores like `compute_importance_scores`, propagating
+/// base scores as selected by `propagation`.
+pub fn compute_importance_scores_with(
+    db: &QuestDatabase,
+    alpha: f64,
+    use_log: bool,
+    normalize: bool,
+    propagation: Propagation,
+) -> Result<HashMap<QuestId, f64>> {
+    let config = ImportanceConfig::new()
+        .alpha(alpha)
+        .use_log(use_log)
+        .normalize(normalize)
+        .propagation(propagation);
+    compute_importance(db, &config)
+}
+
+/// Compute importance scores for quests in `db` as set up by `config`.
+///
+/// A quest's base score is the summed weight of the edges from its direct
+/// dependents, which propagates to its prerequisites as selected by
+/// `ImportanceConfig::propagation`. Fails with `AlphaOutOfRange` for an alpha
+/// outside 0.0..=1.0 and `CycleDetected` when prerequisites form a cycle.
+pub fn compute_importance(
+    db: &QuestDatabase,
+    config: &ImportanceConfig,
+) -> Result<HashMap<QuestId, f64>> {
+    let ImportanceConfig {
+        alpha,
+        use_log,
+        normalize,
+        propagation,
+        ..
+    } = *config;
     if !(0.0..=1.0).contains(&alpha) {
         return Err(ParseError::AlphaOutOfRange(alpha));
     }
@@ -24,59 +238,16 @@ pub fn compute_importance_scores(
     let mut dependents: HashMap<QuestId, Vec<(QuestId, f64)>> = HashMap::new();
 
     for (qid, quest) in &db.quests {
-        // Exclude all outgoing prerequisite edges for quests with quest_logic == "XOR"
-        let is_xor = quest
-            .properties
-            .as_ref()
-            .and_then(|props| props.quest_logic.as_deref())
-            .is_some_and(|logic| logic.eq_ignore_ascii_case("XOR"));
-        if is_xor {
-            // Skip adding this quest's prerequisite edges to avoid cycles/weight propagation
-            continue;
-        }
-        // dedupe prerequisites per quest to avoid double counting
-        let mut seen: HashSet<u64> = HashSet::new();
-
-        // prefer explicit required_prerequisites; otherwise fall back to
-        // the generic `prerequisites` list. Optionals come from
-        // `optional_prerequisites` when present.
-        let base_required = if !quest.required_prerequisites.is_empty() {
-            quest.required_prerequisites.clone()
-        } else {
-            quest.prerequisites.clone()
-        };
-        let base_optionals = quest.optional_prerequisites.clone();
-
-        let mut required: Vec<QuestId> = Vec::new();
-        for p in base_required.into_iter() {
-            if seen.insert(p.as_u64()) {
-                required.push(p);
-            }
-        }
-
-        let mut optionals: Vec<QuestId> = Vec::new();
-        for p in base_optionals.into_iter() {
-            if seen.insert(p.as_u64()) {
-                optionals.push(p);
+        let mut edges = weighted_prerequisites(quest);
+        if let Some(weight) = &config.edge_weight {
+            for (p, w) in &mut edges {
+                *w = weight(quest, *p, *w);
             }
         }
-
         // adjacency should include both required and optional edges for cycle detection
-        let mut adj_list = required.clone();
-        adj_list.extend(optionals.iter().cloned());
-        adj.insert(*qid, adj_list);
-
-        // build dependents: required edges weight 1.0
-        for p in required.iter().cloned() {
-            dependents.entry(p).or_default().push((*qid, 1.0));
-        }
-
-        // optional edges split weight equally among the group's members
-        if !optionals.is_empty() {
-            let w = 1.0 / (optionals.len() as f64);
-            for p in optionals.into_iter() {
-                dependents.entry(p).or_default().push((*qid, w));
-            }
+        adj.insert(*qid, edges.iter().map(|(p, _)| *p).collect());
+        for (p, w) in edges {
+            dependents.entry(p).or_default().push((*qid, w));
         }
     }
 
@@ -95,18 +266,18 @@ pub fn compute_importance_scores(
     }
 
     let mut stack: Vec<QuestId> = Vec::new();
-    let mut pos_in_stack: HashMap<u64, usize> = HashMap::new();
+    let mut pos_in_stack: HashMap<QuestId, usize> = HashMap::new();
 
     fn dfs_visit(
         node: &QuestId,
         adj: &HashMap<QuestId, Vec<QuestId>>,
         color: &mut HashMap<QuestId, Color>,
         stack: &mut Vec<QuestId>,
-        pos_in_stack: &mut HashMap<u64, usize>,
+        pos_in_stack: &mut HashMap<QuestId, usize>,
     ) -> Option<Vec<QuestId>> {
         // mark gray
         color.insert(*node, Color::Gray);
-        pos_in_stack.insert(node.as_u64(), stack.len());
+        pos_in_stack.insert(*node, stack.len());
         stack.push(*node);
 
         if let Some(neis) = adj.get(node) {
@@ -119,7 +290,7 @@ pub fn compute_importance_scores(
                     }
                     Some(Color::Gray) => {
                         // found a cycle: slice from pos_in_stack[nei]..end
-                        if let Some(&start) = pos_in_stack.get(&nei.as_u64()) {
+                        if let Some(&start) = pos_in_stack.get(nei) {
                             let cycle = stack[start..].to_vec();
                             return Some(cycle);
                         } else {
@@ -133,7 +304,7 @@ pub fn compute_importance_scores(
 
         // mark black
         stack.pop();
-        pos_in_stack.remove(&node.as_u64());
+        pos_in_stack.remove(node);
         color.insert(*node, Color::Black);
         None
     }
@@ -149,16 +320,47 @@ pub fn compute_importance_scores(
     // Compute base scores: raw count of dependents (with weights). Keep exact
     // integer counts where possible (we represent as f64 for final math).
     let mut base: HashMap<QuestId, f64> = HashMap::new();
-    for q in db.quests.keys() {
+    for (q, quest) in &db.quests {
         let raw = dependents
             .get(q)
             .map(|v| v.iter().fold(0.0f64, |acc, (_dep, w)| acc + *w))
             .unwrap_or(0.0);
         let val = if use_log { (1.0 + raw).ln() } else { raw };
-        base.insert(*q, val);
+        let factor = config
+            .quest_weight
+            .as_ref()
+            .map_or(1.0, |weight| weight(quest));
+        base.insert(*q, val * factor);
+    }
+
+    let mut score = match propagation {
+        Propagation::OneStep => propagate_one_step(db, &dependents, &base, alpha),
+        Propagation::Full => propagate_full(db, &adj, &dependents, &base, alpha),
+    };
+
+    // Normalize into [0,1) if requested. Ensure max maps strictly less than 1.
+    if normalize {
+        let max = score.values().cloned().fold(f64::NAN, f64::max);
+        if max.is_nan() || max == 0.0 {
+            // nothing to do
+            return Ok(score);
+        }
+        let divisor = max * 1.000000001_f64; // tiny inflation guarantees < 1.0
+        for v in score.values_mut() {
+            *v /= divisor;
+        }
     }
 
-    // Compute propagated one-step score: score = base + alpha * sum_{d in dependents} weight(d->q) * base(d)
+    Ok(score)
+}
+
+/// score = base + alpha * sum_{d in dependents} weight(d->q) * base(d)
+fn propagate_one_step(
+    db: &QuestDatabase,
+    dependents: &HashMap<QuestId, Vec<(QuestId, f64)>>,
+    base: &HashMap<QuestId, f64>,
+    alpha: f64,
+) -> HashMap<QuestId, f64> {
     let mut score: HashMap<QuestId, f64> = HashMap::new();
     for q in db.quests.keys() {
         let b = *base.get(q).unwrap_or(&0.0);
@@ -172,21 +374,164 @@ pub fn compute_importance_scores(
             .unwrap_or(0.0);
         score.insert(*q, b + alpha * prop);
     }
+    score
+}
 
-    // Normalize into [0,1) if requested. Ensure max maps strictly less than 1.
-    if normalize {
-        let max = score.values().cloned().fold(f64::NAN, f64::max);
-        if max.is_nan() || max == 0.0 {
-            // nothing to do
-            return Ok(score);
+/// score = base + alpha * sum_{d in dependents} weight(d->q) * score(d),
+/// which unrolls to the `alpha^depth` sum over all descendants. `adj` must be
+/// acyclic; quests are scored after all of their dependents (Kahn's
+/// algorithm), so deep chains cannot overflow the stack.
+fn propagate_full(
+    db: &QuestDatabase,
+    adj: &HashMap<QuestId, Vec<QuestId>>,
+    dependents: &HashMap<QuestId, Vec<(QuestId, f64)>>,
+    base: &HashMap<QuestId, f64>,
+    alpha: f64,
+) -> HashMap<QuestId, f64> {
+    let mut pending: HashMap<QuestId, usize> = db
+        .quests
+        .keys()
+        .map(|q| (*q, dependents.get(q).map_or(0, Vec::len)))
+        .collect();
+    let mut ready: Vec<QuestId> = pending
+        .iter()
+        .filter(|(_, n)| **n == 0)
+        .map(|(q, _)| *q)
+        .collect();
+    let mut score: HashMap<QuestId, f64> = HashMap::new();
+    while let Some(q) = ready.pop() {
+        let prop = dependents
+            .get(&q)
+            .map(|deps| deps.iter().map(|(d, w)| w * score[d]).sum::<f64>())
+            .unwrap_or(0.0);
+        score.insert(q, base.get(&q).unwrap_or(&0.0) + alpha * prop);
+        for p in adj.get(&q).into_iter().flatten() {
+            if let Some(n) = pending.get_mut(p) {
+                *n -= 1;
+                if *n == 0 {
+                    ready.push(*p);
+                }
+            }
         }
-        let divisor = max * 1.000000001_f64; // tiny inflation guarantees < 1.0
-        for v in score.values_mut() {
-            *v /= divisor;
+    }
+    score
+}
+
+/// Iteration cap for `compute_importance_pagerank`. With `damping < 1` the
+/// error shrinks by at least that factor per round, so the cap is only hit
+/// for tolerances far below `f64` precision.
+const PAGERANK_MAX_ITERATIONS: usize = 10_000;
+
+/// Compute PageRank-style importance scores for quests in `db`.
+///
+/// Every quest passes its score on to its prerequisites, split by the edge
+/// weights used by `compute_importance_scores` (1.0 per required
+/// prerequisite, `1/n` per optional one), so a quest gains from the whole
+/// chain of content behind it rather than only its direct dependents. Quests
+/// without prerequisites spread their score over all quests, and each round
+/// keeps `1 - damping` of the total as a uniform share.
+///
+/// - `damping` is the share of score passed along edges (0.0..1.0, commonly
+///   0.85).
+/// - Iteration stops once the L1 change between rounds drops below
+///   `tolerance` (> 0).
+///
+/// Scores are positive and sum to 1. Unlike the one-step variant, cycles are
+/// allowed. Prerequisites missing from `db` are ignored.
+pub fn compute_importance_pagerank(
+    db: &QuestDatabase,
+    damping: f64,
+    tolerance: f64,
+) -> Result<HashMap<QuestId, f64>> {
+    if !(0.0..1.0).contains(&damping) {
+        return Err(ParseError::NumberOutOfRange {
+            field: "damping".to_string(),
+            value: damping.to_string(),
+        });
+    }
+    if tolerance.is_nan() || tolerance <= 0.0 {
+        return Err(ParseError::NumberOutOfRange {
+            field: "tolerance".to_string(),
+            value: tolerance.to_string(),
+        });
+    }
+
+    // Index quests in id order so the floating point sums are reproducible.
+    let mut ids: Vec<QuestId> = db.quests.keys().copied().collect();
+    ids.sort();
+    let n = ids.len();
+    if n == 0 {
+        return Ok(HashMap::new());
+    }
+    let pos: HashMap<QuestId, usize> = ids.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+
+    // out[i]: prerequisites of quest i with weights normalized to sum to 1
+    let out: Vec<Vec<(usize, f64)>> = ids
+        .iter()
+        .map(|q| {
+            let edges: Vec<(usize, f64)> = weighted_prerequisites(&db.quests[q])
+                .into_iter()
+                .filter_map(|(p, w)| Some((*pos.get(&p)?, w)))
+                .collect();
+            let total: f64 = edges.iter().map(|(_, w)| w).sum();
+            edges.into_iter().map(|(p, w)| (p, w / total)).collect()
+        })
+        .collect();
+
+    let uniform = 1.0 / n as f64;
+    let mut rank = vec![uniform; n];
+    for _ in 0..PAGERANK_MAX_ITERATIONS {
+        let dangling: f64 = (0..n).filter(|i| out[*i].is_empty()).map(|i| rank[i]).sum();
+        let base = (1.0 - damping) * uniform + damping * dangling * uniform;
+        let mut next = vec![base; n];
+        for (i, edges) in out.iter().enumerate() {
+            for (p, w) in edges {
+                next[*p] += damping * rank[i] * w;
+            }
+        }
+        let delta: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if delta < tolerance {
+            break;
         }
     }
+    Ok(ids.into_iter().zip(rank).collect())
+}
 
-    Ok(score)
+/// Number of quests that directly or transitively require each quest of
+/// `db`, i.e. how much content completing it unlocks. Every prerequisite
+/// link counts (required, optional and those of `XOR` quests alike); a quest
+/// on a prerequisite cycle does not count itself.
+pub fn descendant_counts(db: &QuestDatabase) -> HashMap<QuestId, usize> {
+    let graph = QuestGraph::build(db);
+    let mut counts: HashMap<QuestId, usize> = db.quests.keys().map(|q| (*q, 0)).collect();
+    for quest in db.quests.keys() {
+        for ancestor in graph.ancestors(*quest) {
+            // missing prerequisites are not counted
+            if ancestor != *quest
+                && let Some(n) = counts.get_mut(&ancestor)
+            {
+                *n += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Foundational score of every quest: its `descendant_counts` entry as a
+/// share of the other quests in `db`, in [0, 1].
+///
+/// Importance scores look at direct dependents (or decay with depth), so an
+/// early keystone quest that unlocks a whole tier through a single follow-up
+/// ranks low; this metric credits it with everything behind it. Unlike
+/// importance, cycles are allowed. The result fits `export::scores` like any
+/// other score map.
+pub fn compute_foundational_scores(db: &QuestDatabase) -> HashMap<QuestId, f64> {
+    let others = db.quests.len().saturating_sub(1).max(1) as f64;
+    descendant_counts(db)
+        .into_iter()
+        .map(|(q, n)| (q, n as f64 / others))
+        .collect()
 }
 
 /// Order prerequisites for a given quest by importance using the precomputed
@@ -201,12 +546,106 @@ pub fn order_prereqs_for_quest(
         .map(|q| (*q, *scores.get(q).unwrap_or(&0.0)))
         .collect();
 
-    // deterministic sort: score desc, tie-break by QuestId.as_u64() asc
+    // deterministic sort: score desc, tie-break by QuestId asc
     out.sort_by(|(a_id, a_s), (b_id, b_s)| {
         match b_s.partial_cmp(a_s).unwrap_or(std::cmp::Ordering::Equal) {
-            std::cmp::Ordering::Equal => a_id.as_u64().cmp(&b_id.as_u64()),
+            std::cmp::Ordering::Equal => a_id.cmp(b_id),
             ord => ord,
         }
     });
     out
 }
+
+/// Rank quests by score, highest first, ties broken by ascending id.
+///
+/// Scores are compared after rounding to `export::FLOAT_PRECISION` decimals so
+/// floating point noise cannot reorder quests between runs.
+pub fn ranking(scores: &HashMap<QuestId, f64>) -> Vec<(QuestId, f64)> {
+    let mut out: Vec<(QuestId, f64)> = scores
+        .iter()
+        .map(|(id, s)| (*id, round_float(*s, FLOAT_PRECISION)))
+        .collect();
+    out.sort_by(|(a_id, a_s), (b_id, b_s)| b_s.total_cmp(a_s).then_with(|| a_id.cmp(b_id)));
+    out
+}
+
+/// One persisted score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedScore {
+    id: QuestId,
+    score: f64,
+}
+
+/// Write `scores` to `path` as JSON, sorted by quest id so saved files of
+/// two pack versions diff cleanly.
+pub fn save(scores: &HashMap<QuestId, f64>, path: &Path) -> Result<()> {
+    let mut entries: Vec<SavedScore> = scores
+        .iter()
+        .map(|(id, score)| SavedScore {
+            id: *id,
+            score: *score,
+        })
+        .collect();
+    entries.sort_by_key(|e| e.id);
+    let json = serde_json::to_string_pretty(&entries)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Read scores previously written by `save`.
+pub fn load(path: &Path) -> Result<HashMap<QuestId, f64>> {
+    let entries: Vec<SavedScore> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    Ok(entries.into_iter().map(|e| (e.id, e.score)).collect())
+}
+
+/// A quest whose rank changed between two score sets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RankShift {
+    /// The quest.
+    pub quest: QuestId,
+    /// 1-based rank in the old scores.
+    pub old_rank: usize,
+    /// 1-based rank in the new scores.
+    pub new_rank: usize,
+}
+
+impl RankShift {
+    /// Positions gained (positive) or lost (negative).
+    pub fn delta(&self) -> i64 {
+        self.old_rank as i64 - self.new_rank as i64
+    }
+}
+
+/// Quests present in both score sets whose rank moved by more than
+/// `min_shift` positions, largest movement first (ties by quest id).
+pub fn compare(
+    old: &HashMap<QuestId, f64>,
+    new: &HashMap<QuestId, f64>,
+    min_shift: usize,
+) -> Vec<RankShift> {
+    let old_ranks: HashMap<QuestId, usize> = ranking(old)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, _))| (id, i + 1))
+        .collect();
+    let mut out: Vec<RankShift> = ranking(new)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, (quest, _))| {
+            let old_rank = *old_ranks.get(&quest)?;
+            let shift = RankShift {
+                quest,
+                old_rank,
+                new_rank: i + 1,
+            };
+            (shift.delta().unsigned_abs() as usize > min_shift).then_some(shift)
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        b.delta()
+            .unsigned_abs()
+            .cmp(&a.delta().unsigned_abs())
+            .then_with(|| a.quest.cmp(&b.quest))
+    });
+    out
+}
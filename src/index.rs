@@ -0,0 +1,330 @@
+//! Lookup tables built from a parsed `QuestDatabase`.
+//!
+//! The `ItemIndex` maps item identities to the tasks that require them and to
+//! the rewards that hand them out, so analyses can answer "who needs this item"
+//! and "where does it come from" without rescanning every quest.
+//!
+//! The `QuestIndex` answers quest-centric lookups: quests by name, by task
+//! type, by reward type and by required item id. `QuestDatabase::resolve_name`
+//! turns a hand-typed quest name into an id, tolerating small typos.
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Identity of an item for indexing purposes: registry id plus damage/meta.
+///
+/// A missing damage value is treated as `0`, which is what BetterQuesting
+/// assumes when the key is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ItemKey {
+    /// Namespaced item id (e.g. "minecraft:bread").
+    pub id: String,
+    /// Damage / meta value.
+    pub damage: i32,
+}
+
+impl ItemKey {
+    /// Build the key identifying `stack`.
+    pub fn from_stack(stack: &ItemStack) -> Self {
+        ItemKey {
+            id: stack.id.clone(),
+            damage: stack.damage.unwrap_or(0),
+        }
+    }
+}
+
+impl fmt::Display for ItemKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.id, self.damage)
+    }
+}
+
+/// Split `mod:item:damage` into the item id and the damage value. Ids
+/// without a numeric third part are returned unchanged.
+pub(crate) fn split_damage(item: &str) -> (String, Option<i32>) {
+    match item.rsplit_once(':') {
+        Some((id, damage)) if id.contains(':') => match damage.parse() {
+            Ok(d) => (id.to_string(), Some(d)),
+            Err(_) => (item.to_string(), None),
+        },
+        _ => (item.to_string(), None),
+    }
+}
+
+/// Distinct items required by any task of `quest`, sorted.
+pub fn required_item_set(quest: &Quest) -> BTreeSet<ItemKey> {
+    quest
+        .tasks
+        .iter()
+        .flat_map(|t| &t.required_items)
+        .map(ItemKey::from_stack)
+        .collect()
+}
+
+/// A task that requires an item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemRequirement {
+    /// Quest owning the task.
+    pub quest: QuestId,
+    /// Index of the task inside the quest.
+    pub task_index: Option<usize>,
+    /// Required stack size (defaults to 1 when absent).
+    pub count: i32,
+    /// Whether the task takes the items away from the player.
+    pub consumed: bool,
+}
+
+/// A reward that grants an item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemGrant {
+    /// Quest owning the reward.
+    pub quest: QuestId,
+    /// Index of the reward inside the quest.
+    pub reward_index: Option<usize>,
+    /// Granted stack size (defaults to 1 when absent).
+    pub count: i32,
+    /// True when the item is one of several alternatives of a choice reward.
+    pub choice: bool,
+}
+
+/// Item-centric view of a `QuestDatabase`.
+///
+/// Entries are recorded in ascending quest id order so lookups return
+/// deterministic slices.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemIndex {
+    required: HashMap<ItemKey, Vec<ItemRequirement>>,
+    rewarded: HashMap<ItemKey, Vec<ItemGrant>>,
+}
+
+impl ItemIndex {
+    /// Index every task item requirement and reward item in `db`.
+    pub fn build(db: &QuestDatabase) -> Self {
+        let mut index = ItemIndex::default();
+        let mut ids: Vec<&QuestId> = db.quests.keys().collect();
+        ids.sort();
+        for qid in ids {
+            let quest = &db.quests[qid];
+            for task in &quest.tasks {
+                for stack in &task.required_items {
+                    index
+                        .required
+                        .entry(ItemKey::from_stack(stack))
+                        .or_default()
+                        .push(ItemRequirement {
+                            quest: *qid,
+                            task_index: task.index,
+                            count: stack.count.unwrap_or(1),
+                            consumed: task.consume.unwrap_or(false),
+                        });
+                }
+            }
+            for reward in &quest.rewards {
+                let items = reward.items.iter().map(|s| (s, false));
+                let choices = reward.choices.iter().map(|s| (s, true));
+                for (stack, choice) in items.chain(choices) {
+                    index
+                        .rewarded
+                        .entry(ItemKey::from_stack(stack))
+                        .or_default()
+                        .push(ItemGrant {
+                            quest: *qid,
+                            reward_index: reward.index,
+                            count: stack.count.unwrap_or(1),
+                            choice,
+                        });
+                }
+            }
+        }
+        index
+    }
+
+    /// Tasks requiring `item`, in ascending quest id order.
+    pub fn required_by(&self, item: &ItemKey) -> &[ItemRequirement] {
+        self.required.get(item).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Rewards granting `item`, in ascending quest id order.
+    pub fn rewarded_by(&self, item: &ItemKey) -> &[ItemGrant] {
+        self.rewarded.get(item).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// All items referenced by any task or reward, sorted.
+    pub fn items(&self) -> Vec<&ItemKey> {
+        let mut keys: Vec<&ItemKey> = self.required.keys().chain(self.rewarded.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+}
+
+/// Normalized form of a quest name for `QuestIndex::by_name`: formatting
+/// codes removed, surrounding whitespace trimmed, lowercase.
+fn name_key(name: &str) -> String {
+    crate::text::strip_formatting(name).trim().to_lowercase()
+}
+
+/// Quest lookup tables of a `QuestDatabase`.
+///
+/// Every lookup returns quest ids in ascending order, each quest at most
+/// once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuestIndex {
+    by_name: HashMap<String, Vec<QuestId>>,
+    by_task: HashMap<String, Vec<QuestId>>,
+    by_reward: HashMap<String, Vec<QuestId>>,
+    by_item: HashMap<String, Vec<QuestId>>,
+}
+
+fn push_unique(map: &mut HashMap<String, Vec<QuestId>>, key: &str, quest: QuestId) {
+    let ids = map.entry(key.to_string()).or_default();
+    // quests are visited in ascending order, so a duplicate is always last
+    if ids.last() != Some(&quest) {
+        ids.push(quest);
+    }
+}
+
+fn lookup<'a>(map: &'a HashMap<String, Vec<QuestId>>, key: &str) -> &'a [QuestId] {
+    map.get(key).map(Vec::as_slice).unwrap_or(&[])
+}
+
+impl QuestIndex {
+    /// Index the names, task types, reward types and required items of every
+    /// quest in `db`.
+    pub fn build(db: &QuestDatabase) -> Self {
+        let mut index = QuestIndex::default();
+        let mut ids: Vec<&QuestId> = db.quests.keys().collect();
+        ids.sort();
+        for qid in ids {
+            let quest = &db.quests[qid];
+            if let Some(name) = quest.name() {
+                push_unique(&mut index.by_name, &name_key(name), *qid);
+            }
+            for task in &quest.tasks {
+                push_unique(&mut index.by_task, &task.task_id, *qid);
+                for stack in &task.required_items {
+                    push_unique(&mut index.by_item, &stack.id, *qid);
+                }
+            }
+            for reward in &quest.rewards {
+                push_unique(&mut index.by_reward, &reward.reward_id, *qid);
+            }
+        }
+        index
+    }
+
+    /// Quests named `name`, ignoring formatting codes, surrounding
+    /// whitespace and case.
+    pub fn by_name(&self, name: &str) -> &[QuestId] {
+        lookup(&self.by_name, &name_key(name))
+    }
+
+    /// Quests whose name contains `fragment`, compared like `by_name`.
+    pub fn name_contains(&self, fragment: &str) -> Vec<QuestId> {
+        let fragment = name_key(fragment);
+        let mut ids: Vec<QuestId> = self
+            .by_name
+            .iter()
+            .filter(|(name, _)| name.contains(&fragment))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Quests with a task of type `task_id` (e.g. `bq_standard:retrieval`).
+    pub fn by_task_type(&self, task_id: &str) -> &[QuestId] {
+        lookup(&self.by_task, task_id)
+    }
+
+    /// Quests with a reward of type `reward_id` (e.g. `bq_standard:item`).
+    pub fn by_reward_type(&self, reward_id: &str) -> &[QuestId] {
+        lookup(&self.by_reward, reward_id)
+    }
+
+    /// Quests with a task requiring the item `item_id`, with any damage
+    /// value. Use `ItemIndex` to tell damage values apart.
+    pub fn requiring_item(&self, item_id: &str) -> &[QuestId] {
+        lookup(&self.by_item, item_id)
+    }
+
+    /// Distinct task types, sorted.
+    pub fn task_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.by_task.keys().map(String::as_str).collect();
+        types.sort();
+        types
+    }
+
+    /// Distinct reward types, sorted.
+    pub fn reward_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.by_reward.keys().map(String::as_str).collect();
+        types.sort();
+        types
+    }
+}
+
+/// `name_key` with runs of inner whitespace collapsed to one space.
+pub(crate) fn loose_name_key(name: &str) -> String {
+    name_key(name)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl QuestDatabase {
+    /// The quest a human means by `name`, for references typed by hand
+    /// (sheets, patches, scripts). Tried in order, the first level with any
+    /// match deciding:
+    ///
+    /// 1. the exact name, formatting codes and surrounding whitespace ignored,
+    /// 2. the name ignoring case and repeated whitespace,
+    /// 3. the closest names by edit distance, within a quarter of the name's
+    ///    length (at least 1).
+    ///
+    /// `Ok(None)` when nothing matches; fails with `AmbiguousName` when the
+    /// deciding level matches several quests.
+    pub fn resolve_name(&self, name: &str) -> Result<Option<QuestId>> {
+        let mut quests: Vec<(&QuestId, &str)> = self
+            .quests
+            .iter()
+            .filter_map(|(id, q)| q.name().map(|n| (id, n)))
+            .collect();
+        quests.sort();
+        let exact = crate::text::strip_formatting(name).trim().to_string();
+        let loose = loose_name_key(name);
+        // distance of quest name `n` at each level, `None` for no match
+        let level_distance = |level: usize, n: &str| match level {
+            0 => (crate::text::strip_formatting(n).trim() == exact).then_some(0),
+            1 => (loose_name_key(n) == loose).then_some(0),
+            _ => {
+                let d = crate::schema::levenshtein(&loose_name_key(n), &loose);
+                (d <= (loose.chars().count() / 4).max(1)).then_some(d)
+            }
+        };
+        for level in 0..3 {
+            let scored: Vec<(usize, QuestId)> = quests
+                .iter()
+                .filter_map(|(id, n)| level_distance(level, n).map(|d| (d, **id)))
+                .collect();
+            let Some(best) = scored.iter().map(|(d, _)| *d).min() else {
+                continue;
+            };
+            let matches: Vec<QuestId> = scored
+                .into_iter()
+                .filter(|(d, _)| *d == best)
+                .map(|(_, id)| id)
+                .collect();
+            return match matches.as_slice() {
+                [id] => Ok(Some(*id)),
+                _ => Err(ParseError::AmbiguousName {
+                    name: name.to_string(),
+                    matches,
+                }),
+            };
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,288 @@
+//! Minecraft language files and translated quest text.
+//!
+//! Translatable packs store translation keys such as
+//! `betterquesting.quest.123.name` instead of literal text in quest names
+//! and descriptions; the game looks them up in the active language file.
+//! `Lang` loads such a file, either the `key=value` `.lang` format of
+//! Minecraft 1.12 and earlier or the flat JSON object of 1.13+, and
+//! `Lang::localize` substitutes the translations into a database so every
+//! exporter shows the text players see.
+//!
+//! Only whole strings are looked up, as the game does; keys embedded in
+//! running text are handled by `text::TokenExpander` (`translate:key`),
+//! which `Lang::expander` fills from the same table.
+//!
+//! The inverse, `extract_translations`, makes a pack translatable: it moves
+//! the literal texts into a `Lang` under generated keys
+//! (`<prefix>.quest.<id>.name`, `<prefix>.line.<id>.desc`, ...) and leaves
+//! the keys in the database; `Lang::write_lang` then emits the `.lang` file
+//! to ship with the pack.
+use crate::error::{ParseError, Result};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use crate::text::TokenExpander;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Translation key to text table of one language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lang {
+    entries: BTreeMap<String, String>,
+}
+
+/// Escape `value` for a `#PARSE_ESCAPES` `.lang` file, so it fits on one
+/// line; the inverse of `unescape`.
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Resolve the escapes a `#PARSE_ESCAPES` `.lang` file may use.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+impl Lang {
+    /// An empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a `.lang` file: `key=value` lines, split at the first `=`.
+    /// Blank lines, `#` comments and lines without `=` are skipped. When the
+    /// file contains a `#PARSE_ESCAPES` line, `\n`, `\t` and `\\` in values
+    /// are unescaped, as the game does.
+    pub fn read_lang<R: Read>(mut r: R) -> Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let text = text.trim_start_matches('\u{feff}');
+        let parse_escapes = text.lines().any(|l| l.trim() == "#PARSE_ESCAPES");
+        let mut lang = Lang::new();
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = if parse_escapes {
+                unescape(value)
+            } else {
+                value.to_string()
+            };
+            lang.insert(key.trim(), value);
+        }
+        Ok(lang)
+    }
+
+    /// Parse a JSON language file: one object of key to string.
+    pub fn read_json<R: Read>(mut r: R) -> Result<Self> {
+        let mut buf = Vec::new();
+        r.read_to_end(&mut buf)?;
+        let Value::Object(map) = crate::parser::json_value(buf)? else {
+            return Err(ParseError::InvalidFormat(
+                "language file is not a JSON object".to_string(),
+            ));
+        };
+        let mut lang = Lang::new();
+        for (key, value) in map {
+            match value {
+                Value::String(text) => lang.insert(key, text),
+                other => {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "translation of {} is not a string: {}",
+                        key, other
+                    )));
+                }
+            }
+        }
+        Ok(lang)
+    }
+
+    /// Load a language file, as JSON when the extension is `.json` and in
+    /// the `.lang` format otherwise.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+        {
+            Self::read_json(file)
+        } else {
+            Self::read_lang(file)
+        }
+    }
+
+    /// Add or replace the translation of `key`.
+    pub fn insert(&mut self, key: impl Into<String>, text: impl Into<String>) {
+        self.entries.insert(key.into(), text.into());
+    }
+
+    /// Add the entries of `other`, replacing existing ones (a pack's file
+    /// layered over a mod's defaults).
+    pub fn extend(&mut self, other: Lang) {
+        self.entries.extend(other.entries);
+    }
+
+    /// The translation of `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    /// Number of translations.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no translations.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Key and translation pairs, by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// `text` translated when it is a known key (surrounding whitespace
+    /// ignored), `text` itself otherwise.
+    pub fn translate<'a>(&'a self, text: &'a str) -> &'a str {
+        self.get(text.trim()).unwrap_or(text)
+    }
+
+    /// The vanilla keybind expander with every translation added, for
+    /// `translate:key` tokens inside descriptions.
+    pub fn expander(&self) -> TokenExpander {
+        let mut expander = TokenExpander::default();
+        for (key, text) in self.iter() {
+            expander.insert(key, text);
+        }
+        expander
+    }
+
+    /// Write the table as a `.lang` file, sorted by key. The file starts
+    /// with `#PARSE_ESCAPES` and line breaks in values are escaped, so
+    /// multi-line descriptions read back unchanged.
+    pub fn write_lang<W: Write>(&self, mut w: W) -> Result<()> {
+        writeln!(w, "#PARSE_ESCAPES")?;
+        for (key, text) in self.iter() {
+            writeln!(w, "{}={}", key, escape(text))?;
+        }
+        Ok(())
+    }
+
+    /// Write the table as a JSON language file.
+    pub fn write_json<W: Write>(&self, mut w: W) -> Result<()> {
+        serde_json::to_writer_pretty(&mut w, &self.entries)?;
+        writeln!(w)?;
+        Ok(())
+    }
+
+    /// Replace translation keys in quest and questline names and
+    /// description pages by their translations. Returns the number of
+    /// strings replaced.
+    pub fn localize(&self, db: &mut QuestDatabase) -> usize {
+        let mut replaced = 0;
+        let mut apply = |text: &mut String| {
+            if let Some(t) = self.get(text.trim()) {
+                *text = t.to_string();
+                replaced += 1;
+            }
+        };
+        for quest in db.quests.values_mut() {
+            if let Some(props) = quest.properties.as_mut() {
+                apply(&mut props.name);
+                props.desc.iter_mut().for_each(&mut apply);
+                props.desc_pages.iter_mut().for_each(&mut apply);
+            }
+        }
+        for line in db.questlines.values_mut() {
+            if let Some(props) = line.properties.as_mut() {
+                apply(&mut props.name);
+                props.desc.iter_mut().for_each(&mut apply);
+                props.desc_pages.iter_mut().for_each(&mut apply);
+            }
+        }
+        replaced
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Lang {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut lang = Lang::new();
+        for (key, text) in iter {
+            lang.insert(key, text);
+        }
+        lang
+    }
+}
+
+/// Move `text` into `lang` under `key` and leave the key in its place.
+/// Empty texts and texts that already are `key` are left alone.
+fn extract_one(lang: &mut Lang, key: String, text: &mut String) {
+    if text.trim().is_empty() || *text == key {
+        return;
+    }
+    lang.insert(key.clone(), std::mem::replace(text, key));
+}
+
+/// Move the names and description pages of every quest and questline of
+/// `db` into a new `Lang`, replacing them by generated keys:
+/// `<prefix>.quest.<id>.name`, `<prefix>.quest.<id>.desc` for the first
+/// description page and `.desc2`, `.desc3`, ... for the following ones, and
+/// the same below `<prefix>.line.<id>` for questlines. Strings that already
+/// hold their generated key are skipped, so running it again adds nothing.
+/// `Lang::localize` with the result restores the original texts.
+pub fn extract_translations(db: &mut QuestDatabase, prefix: &str) -> Lang {
+    let mut lang = Lang::new();
+    let mut extract = |kind: &str, id: QuestId, name: &mut String, pages: Vec<&mut String>| {
+        let base = format!("{}.{}.{}", prefix, kind, id);
+        extract_one(&mut lang, format!("{}.name", base), name);
+        for (i, page) in pages.into_iter().enumerate() {
+            let key = match i {
+                0 => format!("{}.desc", base),
+                _ => format!("{}.desc{}", base, i + 1),
+            };
+            extract_one(&mut lang, key, page);
+        }
+    };
+    for quest in db.quests.values_mut() {
+        if let Some(props) = quest.properties.as_mut() {
+            let pages = props.desc.iter_mut().chain(&mut props.desc_pages).collect();
+            extract("quest", quest.id, &mut props.name, pages);
+        }
+    }
+    for line in db.questlines.values_mut() {
+        if let Some(props) = line.properties.as_mut() {
+            let pages = props.desc.iter_mut().chain(&mut props.desc_pages).collect();
+            extract("line", line.id, &mut props.name, pages);
+        }
+    }
+    lang
+}
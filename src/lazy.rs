@@ -0,0 +1,307 @@
+//! Two-phase parsing: quest headers up front, bodies on demand.
+//!
+//! Interactive browsers list every quest by name and draw the prerequisite
+//! graph, but only open a handful of quests. `parse_default_quests_dir_lazy`
+//! reads each quest file once, deserializes just the id, properties and
+//! prerequisite fields (tasks, rewards and unknown keys are skipped without
+//! being materialized) and keeps the file text. `LazyQuest::quest` parses the
+//! full quest the first time it is asked for and caches the result.
+//!
+//! With the `lz4` feature, `parse_default_quests_dir_lazy_compressed` keeps
+//! the file text lz4-compressed instead, trading a decompression per body
+//! parse for a much smaller footprint on packs with thousands of quests.
+//! `LazyQuest::unload` drops a cached body again for long browsing sessions.
+//!
+//! Headers go through the same normalization and `Quest::from_raw` logic as
+//! a full parse, so names and the required/optional prerequisite split are
+//! identical to `db::parse_default_quests_dir_from_source`.
+use crate::db::{
+    ParseOptions, QuestDataSource, parse_questlines_dir_from_source, parse_settings_from_source,
+    quest_file_paths, resolve_questline_refs,
+};
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Top-level quest keys (NBT suffix stripped) read in the header phase.
+const HEADER_KEYS: [&str; 6] = [
+    "questIDHigh",
+    "questIDLow",
+    "questID",
+    "properties",
+    "preRequisites",
+    "optionalPreRequisites",
+];
+
+/// The cheap part of a quest: identity, name and prerequisites.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestHeader {
+    /// The quest id.
+    pub id: QuestId,
+    /// Quest name as stored (formatting codes included).
+    pub name: String,
+    /// As `Quest::prerequisites`.
+    pub prerequisites: Vec<QuestId>,
+    /// As `Quest::required_prerequisites`.
+    pub required_prerequisites: Vec<QuestId>,
+    /// As `Quest::optional_prerequisites`.
+    pub optional_prerequisites: Vec<QuestId>,
+}
+
+/// Top-level header fields of a quest document, other keys skipped.
+struct HeaderFields(Map<String, Value>);
+
+impl<'de> Deserialize<'de> for HeaderFields {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        struct FieldsVisitor;
+        impl<'de> Visitor<'de> for FieldsVisitor {
+            type Value = HeaderFields;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a quest object")
+            }
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> std::result::Result<HeaderFields, A::Error> {
+                let mut m = Map::new();
+                while let Some(key) = access.next_key::<String>()? {
+                    let name = key.rsplit_once(':').map_or(key.as_str(), |(n, _)| n);
+                    if HEADER_KEYS.contains(&name) {
+                        let v: Value = access.next_value()?;
+                        m.insert(key, v);
+                    } else {
+                        access.next_value::<IgnoredAny>()?;
+                    }
+                }
+                Ok(HeaderFields(m))
+            }
+        }
+        d.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// Parse the header of a quest document.
+pub fn parse_quest_header(text: &str) -> Result<QuestHeader> {
+    let HeaderFields(fields) = serde_json::from_str(text)?;
+    let quest = crate::parser::quest_from_json(Value::Object(fields))?;
+    Ok(QuestHeader {
+        id: quest.id,
+        name: quest.name().unwrap_or_default().to_string(),
+        prerequisites: quest.prerequisites,
+        required_prerequisites: quest.required_prerequisites,
+        optional_prerequisites: quest.optional_prerequisites,
+    })
+}
+
+/// Unparsed quest file text, as kept until the body is needed.
+#[derive(Debug)]
+enum Source {
+    Text(String),
+    /// lz4 block with the uncompressed size prepended.
+    #[cfg(feature = "lz4")]
+    Lz4(Vec<u8>),
+}
+
+impl Source {
+    fn parse(&self) -> Result<Quest> {
+        match self {
+            Source::Text(text) => crate::parser::parse_quest_from_slice(text.as_bytes()),
+            #[cfg(feature = "lz4")]
+            Source::Lz4(block) => {
+                let text = lz4_flex::decompress_size_prepended(block).map_err(|e| {
+                    ParseError::InvalidFormat(format!("corrupt compressed quest body: {}", e))
+                })?;
+                crate::parser::parse_quest_from_slice(&text)
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Source::Text(text) => text.len(),
+            #[cfg(feature = "lz4")]
+            Source::Lz4(block) => block.len(),
+        }
+    }
+}
+
+/// A quest whose body is parsed on first access.
+#[derive(Debug)]
+pub struct LazyQuest {
+    header: QuestHeader,
+    source: Source,
+    body: OnceLock<Quest>,
+}
+
+impl LazyQuest {
+    /// Parse the header of `text` and keep the text for later.
+    pub fn new(text: String) -> Result<Self> {
+        Ok(LazyQuest {
+            header: parse_quest_header(&text)?,
+            source: Source::Text(text),
+            body: OnceLock::new(),
+        })
+    }
+
+    /// Parse the header of `text` and keep the text lz4-compressed.
+    #[cfg(feature = "lz4")]
+    pub fn new_compressed(text: String) -> Result<Self> {
+        Ok(LazyQuest {
+            header: parse_quest_header(&text)?,
+            source: Source::Lz4(lz4_flex::compress_prepend_size(text.as_bytes())),
+            body: OnceLock::new(),
+        })
+    }
+
+    /// Bytes held for the unparsed body (compressed size when compressed).
+    pub fn stored_len(&self) -> usize {
+        self.source.len()
+    }
+
+    /// Drop the cached body; the next `quest` call parses it again.
+    pub fn unload(&mut self) {
+        self.body = OnceLock::new();
+    }
+
+    /// The header parsed up front.
+    pub fn header(&self) -> &QuestHeader {
+        &self.header
+    }
+
+    /// The quest id, from the header.
+    pub fn id(&self) -> QuestId {
+        self.header.id
+    }
+
+    /// Returns true once the full quest has been parsed.
+    pub fn is_loaded(&self) -> bool {
+        self.body.get().is_some()
+    }
+
+    /// The full quest, parsed on first call. Parse errors are not cached, so
+    /// a failing quest is re-parsed (and fails again) on every call.
+    pub fn quest(&self) -> Result<&Quest> {
+        if let Some(q) = self.body.get() {
+            return Ok(q);
+        }
+        let quest = self.source.parse()?;
+        Ok(self.body.get_or_init(|| quest))
+    }
+
+    fn into_quest(self) -> Result<Quest> {
+        match self.body.into_inner() {
+            Some(q) => Ok(q),
+            None => self.source.parse(),
+        }
+    }
+}
+
+/// A `QuestDatabase` whose quest bodies are parsed on demand.
+#[derive(Debug)]
+pub struct LazyQuestDatabase {
+    /// As `QuestDatabase::settings`.
+    pub settings: Option<QuestSettings>,
+    /// Quests by id, bodies not parsed yet.
+    pub quests: HashMap<QuestId, LazyQuest>,
+    /// As `QuestDatabase::questlines`.
+    pub questlines: HashMap<QuestId, QuestLine>,
+    /// As `QuestDatabase::questline_order`.
+    pub questline_order: Vec<QuestId>,
+}
+
+impl LazyQuestDatabase {
+    /// The header of quest `id`; `None` for unknown ids.
+    pub fn header(&self, id: QuestId) -> Option<&QuestHeader> {
+        self.quests.get(&id).map(LazyQuest::header)
+    }
+
+    /// The full quest `id`, parsed on first access; `None` for unknown ids.
+    pub fn quest(&self, id: QuestId) -> Option<Result<&Quest>> {
+        self.quests.get(&id).map(LazyQuest::quest)
+    }
+
+    /// Number of quests whose body has been parsed.
+    pub fn loaded_count(&self) -> usize {
+        self.quests.values().filter(|q| q.is_loaded()).count()
+    }
+
+    /// Bytes held for unparsed bodies across all quests.
+    pub fn stored_len(&self) -> usize {
+        self.quests.values().map(LazyQuest::stored_len).sum()
+    }
+
+    /// Parse every remaining body and return a regular database.
+    pub fn into_database(self) -> Result<QuestDatabase> {
+        let quests = self
+            .quests
+            .into_iter()
+            .map(|(id, q)| Ok((id, q.into_quest()?)))
+            .collect::<Result<HashMap<_, _>>>()?;
+        Ok(QuestDatabase {
+            settings: self.settings,
+            quests,
+            questlines: self.questlines,
+            questline_order: self.questline_order,
+        })
+    }
+}
+
+/// Parse the DefaultQuests folder, reading only quest headers.
+///
+/// Settings and questlines are parsed fully, and questline references are
+/// checked against the headers exactly as in the eager parse.
+pub fn parse_default_quests_dir_lazy(
+    source: &dyn QuestDataSource,
+    root: &str,
+) -> Result<LazyQuestDatabase> {
+    parse_lazy(source, root, LazyQuest::new)
+}
+
+/// Like `parse_default_quests_dir_lazy`, keeping quest bodies lz4-compressed
+/// in memory until they are accessed.
+#[cfg(feature = "lz4")]
+pub fn parse_default_quests_dir_lazy_compressed(
+    source: &dyn QuestDataSource,
+    root: &str,
+) -> Result<LazyQuestDatabase> {
+    parse_lazy(source, root, LazyQuest::new_compressed)
+}
+
+fn parse_lazy(
+    source: &dyn QuestDataSource,
+    root: &str,
+    make: fn(String) -> Result<LazyQuest>,
+) -> Result<LazyQuestDatabase> {
+    if !source.is_dir(root) {
+        return Err(ParseError::InvalidFormat(format!("not a dir: {}", root)));
+    }
+    let settings = parse_settings_from_source(source, root)?;
+
+    let mut quests: HashMap<QuestId, LazyQuest> = HashMap::new();
+    for path in quest_file_paths(source, root)? {
+        let quest = make(source.read_to_string(&path)?)?;
+        if quests.insert(quest.id(), quest).is_some() {
+            return Err(ParseError::DuplicateQuestId(path));
+        }
+    }
+
+    let (mut questlines, questline_order) =
+        parse_questlines_dir_from_source(source, &format!("{}/QuestLines", root), &mut Err)?;
+    resolve_questline_refs(
+        &mut questlines,
+        |id| quests.contains_key(id),
+        &ParseOptions::default(),
+    )?;
+
+    Ok(LazyQuestDatabase {
+        settings,
+        quests,
+        questlines,
+        questline_order,
+    })
+}
@@ -11,14 +11,62 @@
 //! ```rust,no_run
 //! ```
 
+pub mod analysis;
+#[cfg(feature = "zip")]
+pub mod archive;
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+#[cfg(feature = "async")]
+pub mod async_source;
+pub mod batch;
+pub mod capabilities;
+#[cfg(feature = "nbt")]
+pub mod dat;
 pub mod db;
+pub mod default_loot;
+pub mod diff;
+pub mod edit;
 pub mod error;
+pub mod export;
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
+pub mod graph;
+pub mod identity;
+pub mod import;
 pub mod importance;
+pub mod index;
+pub mod lang;
+pub mod lazy;
+pub mod lint;
+pub mod merge;
+pub mod metrics;
+#[cfg(feature = "memmap2")]
+pub mod mmap;
 pub mod model;
 pub mod model_raw;
 pub mod nbt_norm;
+pub mod normalize;
+pub mod ordering;
+pub mod overlay;
 pub mod parser;
+#[cfg(feature = "im")]
+pub mod persistent;
+pub mod progress;
+pub mod project;
 pub mod quest_id;
+pub mod renumber;
+pub mod report;
+pub mod schema;
+#[cfg(feature = "site")]
+pub mod site;
+pub mod stats;
+pub mod tags;
+pub mod text;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod validate;
+pub mod watcher;
+pub mod wizard;
 
 pub use crate::db::*;
 pub use crate::error::*;
@@ -0,0 +1,341 @@
+//! Content lints with configurable rules.
+//!
+//! Where `validate` reports structural breakage, lints flag quests that load
+//! fine but are probably unfinished or mistyped: no tasks, no description,
+//! items counted zero times, quests unconnected to the rest of the pack,
+//! repeat times below `-1`. Each check is a `LintRule`; a `Linter` runs a set
+//! of them, each at a `Severity` that a `LintConfig` (kept next to the pack,
+//! e.g. as TOML) can raise, lower or switch off. Packs add their own checks by
+//! implementing `LintRule`.
+//!
+//! Findings are sorted by severity (errors first), then rule name, then
+//! subject id.
+use crate::model::*;
+use crate::ordering::sorted_quests;
+use crate::quest_id::QuestId;
+use crate::text::render_description;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// How much a finding matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Worth a look, not necessarily wrong.
+    Info,
+    /// Probably a mistake players will notice.
+    Warning,
+    /// Broken: the quest cannot work as intended.
+    Error,
+}
+
+/// One rule broken by one quest or questline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFinding {
+    /// `LintRule::name` of the rule.
+    pub rule: String,
+    /// How much the finding matters.
+    pub severity: Severity,
+    /// The quest (or questline) the finding is about.
+    pub subject: QuestId,
+    /// What is wrong, for people.
+    pub message: String,
+}
+
+/// A check run by a `Linter`.
+pub trait LintRule {
+    /// Stable kebab-case name, used in findings and `LintConfig`.
+    fn name(&self) -> &'static str;
+    /// Severity of the findings unless configured otherwise.
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+    /// The quests or questlines breaking the rule, each with a message.
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)>;
+}
+
+/// Quests without tasks, which players cannot complete (or complete at once
+/// with `auto_claim`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmptyTasks;
+
+impl LintRule for EmptyTasks {
+    fn name(&self) -> &'static str {
+        "empty-tasks"
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        sorted_quests(db)
+            .into_iter()
+            .filter(|q| q.tasks.is_empty())
+            .map(|q| (q.id, "quest has no tasks".to_string()))
+            .collect()
+    }
+}
+
+/// Quests whose description is missing or blank.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MissingDescription;
+
+impl LintRule for MissingDescription {
+    fn name(&self) -> &'static str {
+        "missing-description"
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        sorted_quests(db)
+            .into_iter()
+            .filter(|q| {
+                q.properties
+                    .as_ref()
+                    .is_none_or(|p| render_description(p).trim().is_empty())
+            })
+            .map(|q| (q.id, "quest has no description".to_string()))
+            .collect()
+    }
+}
+
+/// Task or reward items with a count below one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZeroCountItems;
+
+impl LintRule for ZeroCountItems {
+    fn name(&self) -> &'static str {
+        "zero-count-item"
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        let mut out = Vec::new();
+        for quest in sorted_quests(db) {
+            let mut flag = |what: String, item: &ItemStack| {
+                if let Some(count) = item.count
+                    && count < 1
+                {
+                    out.push((
+                        quest.id,
+                        format!("{} {} has count {}", what, item.id, count),
+                    ));
+                }
+            };
+            for (i, task) in quest.tasks.iter().enumerate() {
+                for item in &task.required_items {
+                    flag(format!("task {} item", task.index.unwrap_or(i)), item);
+                }
+            }
+            for (i, reward) in quest.rewards.iter().enumerate() {
+                let index = reward.index.unwrap_or(i);
+                for item in &reward.items {
+                    flag(format!("reward {} item", index), item);
+                }
+                for item in &reward.choices {
+                    flag(format!("reward {} choice", index), item);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Quests with neither prerequisites nor dependents.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DisconnectedQuests;
+
+impl LintRule for DisconnectedQuests {
+    fn name(&self) -> &'static str {
+        "disconnected-quest"
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        let required: HashSet<QuestId> = db
+            .quests
+            .values()
+            .flat_map(|q| {
+                q.prerequisites
+                    .iter()
+                    .chain(&q.required_prerequisites)
+                    .chain(&q.optional_prerequisites)
+            })
+            .copied()
+            .collect();
+        sorted_quests(db)
+            .into_iter()
+            .filter(|q| {
+                q.prerequisites.is_empty()
+                    && q.required_prerequisites.is_empty()
+                    && q.optional_prerequisites.is_empty()
+                    && !required.contains(&q.id)
+            })
+            .map(|q| {
+                (
+                    q.id,
+                    "quest has no prerequisites and nothing requires it".to_string(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Repeat times below `-1`, the value BetterQuesting uses for quests that
+/// do not repeat.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NegativeRepeatTime;
+
+impl LintRule for NegativeRepeatTime {
+    fn name(&self) -> &'static str {
+        "negative-repeat-time"
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        sorted_quests(db)
+            .into_iter()
+            .filter_map(|q| {
+                let t = q.properties.as_ref()?.repeat_time?;
+                (t < -1).then(|| {
+                    (
+                        q.id,
+                        format!("repeat time {} is negative (use -1 for no repeat)", t),
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Rule severities to change, by rule name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Rules not to run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+    /// Severity overrides.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity: BTreeMap<String, Severity>,
+}
+
+/// A set of rules with their severities.
+pub struct Linter {
+    rules: Vec<(Box<dyn LintRule>, Severity)>,
+}
+
+impl Default for Linter {
+    /// Every built-in rule at its default severity.
+    fn default() -> Self {
+        let mut linter = Linter::empty();
+        linter
+            .add(EmptyTasks)
+            .add(MissingDescription)
+            .add(ZeroCountItems)
+            .add(DisconnectedQuests)
+            .add(NegativeRepeatTime);
+        linter
+    }
+}
+
+impl std::fmt::Debug for Linter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.rules.iter().map(|(rule, sev)| (rule.name(), sev)))
+            .finish()
+    }
+}
+
+impl Linter {
+    /// A linter without rules.
+    pub fn empty() -> Self {
+        Linter { rules: Vec::new() }
+    }
+
+    /// The built-in rules adjusted by `config`. Unknown rule names in the
+    /// config are returned so callers can report typos.
+    pub fn from_config(config: &LintConfig) -> (Self, Vec<String>) {
+        let mut linter = Linter::default();
+        let unknown = linter.configure(config);
+        (linter, unknown)
+    }
+
+    /// Add `rule` at its default severity, replacing a rule of the same
+    /// name.
+    pub fn add(&mut self, rule: impl LintRule + 'static) -> &mut Self {
+        let severity = rule.default_severity();
+        self.rules.retain(|(r, _)| r.name() != rule.name());
+        self.rules.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Remove the rule called `name`; false when there is none.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|(r, _)| r.name() != name);
+        self.rules.len() != before
+    }
+
+    /// Report the rule called `name` at `severity`; false when there is no
+    /// such rule.
+    pub fn set_severity(&mut self, name: &str, severity: Severity) -> bool {
+        match self.rules.iter_mut().find(|(r, _)| r.name() == name) {
+            Some((_, s)) => {
+                *s = severity;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Apply `config`, returning the rule names it mentions that this
+    /// linter does not have.
+    pub fn configure(&mut self, config: &LintConfig) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for (name, severity) in &config.severity {
+            if !self.set_severity(name, *severity) && !config.disabled.contains(name) {
+                unknown.push(name.clone());
+            }
+        }
+        for name in &config.disabled {
+            if !self.remove(name) {
+                unknown.push(name.clone());
+            }
+        }
+        unknown.sort();
+        unknown.dedup();
+        unknown
+    }
+
+    /// Names of the rules, in the order they were added.
+    pub fn rule_names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|(r, _)| r.name()).collect()
+    }
+
+    /// Run every rule over `db`.
+    pub fn run(&self, db: &QuestDatabase) -> Vec<LintFinding> {
+        let mut findings: Vec<LintFinding> = self
+            .rules
+            .iter()
+            .flat_map(|(rule, severity)| {
+                rule.check(db)
+                    .into_iter()
+                    .map(|(subject, message)| LintFinding {
+                        rule: rule.name().to_string(),
+                        severity: *severity,
+                        subject,
+                        message,
+                    })
+            })
+            .collect();
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.rule.cmp(&b.rule))
+                .then_with(|| a.subject.cmp(&b.subject))
+        });
+        findings
+    }
+}
+
+/// Run the built-in rules at their default severities.
+pub fn lint(db: &QuestDatabase) -> Vec<LintFinding> {
+    Linter::default().run(db)
+}
@@ -0,0 +1,273 @@
+//! Checks for combining several quest databases into one pack.
+//!
+//! Quest addons ship their own DefaultQuests folder meant to coexist with the
+//! base pack's. Quest and questline ids are only unique within one folder, so
+//! `check_collisions` reports ids claimed by more than one source and proposes
+//! a remapping for each later source: colliding ids move, in ascending order,
+//! into a contiguous block of legacy (`0:n`) ids above every id in use.
+//! Sources are ranked by their position in the input slice; the first source
+//! claiming an id keeps it.
+//!
+//! `merge` combines the sources into one database, resolving collisions as
+//! chosen by `ConflictStrategy`. Remapping rewrites the moved ids everywhere
+//! inside their source: quest ids, prerequisite references, questline
+//! entries, questline ids and the questline order. References to ids a
+//! source does not define itself (an addon requiring a base pack quest) are
+//! left alone.
+use crate::error::{ParseError, Result};
+use crate::model::{Quest, QuestDatabase, QuestLine};
+use crate::quest_id::QuestId;
+use crate::wizard::free_ids;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Which id space an id belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdKind {
+    /// A quest id.
+    Quest,
+    /// A questline id.
+    Questline,
+}
+
+/// An id used by more than one source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdCollision {
+    /// Quest or questline id space.
+    pub kind: IdKind,
+    /// The contested id.
+    pub id: QuestId,
+    /// Indices of the sources using the id, ascending.
+    pub sources: Vec<usize>,
+}
+
+/// Proposed new ids for the colliding ids of one source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdRemap {
+    /// Index of the source to rewrite.
+    pub source: usize,
+    /// Quest or questline id space.
+    pub kind: IdKind,
+    /// First id of the proposed block.
+    pub first: QuestId,
+    /// Last id of the proposed block.
+    pub last: QuestId,
+    /// Old id to new id, for every remapped id.
+    pub mapping: BTreeMap<QuestId, QuestId>,
+}
+
+/// Result of `check_collisions`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionReport {
+    /// Colliding ids, quests first, then by id.
+    pub collisions: Vec<IdCollision>,
+    /// Proposed remappings, by source then kind.
+    pub remaps: Vec<IdRemap>,
+}
+
+impl CollisionReport {
+    /// Returns true if the sources can be combined as they are.
+    pub fn is_clean(&self) -> bool {
+        self.collisions.is_empty()
+    }
+}
+
+fn ids_of(db: &QuestDatabase, kind: IdKind) -> BTreeSet<QuestId> {
+    match kind {
+        IdKind::Quest => db.quests.keys().copied().collect(),
+        IdKind::Questline => db.questlines.keys().copied().collect(),
+    }
+}
+
+/// Report quest and questline ids shared between `sources` and propose
+/// remapped ranges for every source but the first owner of each id.
+///
+/// Fails with `NumberOutOfRange` when the remapped ids would pass
+/// `i64::MAX`.
+pub fn check_collisions(sources: &[&QuestDatabase]) -> Result<CollisionReport> {
+    let mut report = CollisionReport::default();
+    let mut remaps: BTreeMap<(usize, IdKind), Vec<QuestId>> = BTreeMap::new();
+
+    for kind in [IdKind::Quest, IdKind::Questline] {
+        let per_source: Vec<BTreeSet<QuestId>> =
+            sources.iter().map(|db| ids_of(db, kind)).collect();
+        let mut users: BTreeMap<QuestId, Vec<usize>> = BTreeMap::new();
+        for (i, ids) in per_source.iter().enumerate() {
+            for id in ids {
+                users.entry(*id).or_default().push(i);
+            }
+        }
+        for (id, srcs) in &users {
+            if srcs.len() < 2 {
+                continue;
+            }
+            for s in &srcs[1..] {
+                remaps.entry((*s, kind)).or_default().push(*id);
+            }
+            report.collisions.push(IdCollision {
+                kind,
+                id: *id,
+                sources: srcs.clone(),
+            });
+        }
+
+        // allocate above every legacy id in use so remapped ids stay readable
+        let wanted = remaps
+            .iter()
+            .filter(|((_, k), _)| *k == kind)
+            .map(|(_, ids)| ids.len())
+            .sum();
+        let mut fresh = free_ids(users.keys(), wanted)?;
+        for ((source, k), ids) in remaps.iter().filter(|((_, k), _)| *k == kind) {
+            let mapping: BTreeMap<QuestId, QuestId> = ids
+                .iter()
+                .zip(fresh.by_ref())
+                .map(|(old, low)| (*old, QuestId::from_parts_i64(0, low)))
+                .collect();
+            let (Some(first), Some(last)) = (mapping.values().next(), mapping.values().next_back())
+            else {
+                continue;
+            };
+            report.remaps.push(IdRemap {
+                source: *source,
+                kind: *k,
+                first: *first,
+                last: *last,
+                mapping,
+            });
+        }
+    }
+    report.remaps.sort_by_key(|r| (r.source, r.kind));
+    Ok(report)
+}
+
+/// How `merge` handles ids used by more than one source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Fail on the first collision.
+    #[default]
+    Error,
+    /// Keep the quest or questline of the first source using the id and drop
+    /// the later ones.
+    KeepFirst,
+    /// Move the colliding ids of later sources to the fresh ids proposed by
+    /// `check_collisions`.
+    RemapNewIds,
+}
+
+fn remap_id(mapping: &BTreeMap<QuestId, QuestId>, id: &mut QuestId) {
+    if let Some(new) = mapping.get(id) {
+        *id = *new;
+    }
+}
+
+fn remap_quest(quest: &mut Quest, quests: &BTreeMap<QuestId, QuestId>) {
+    remap_id(quests, &mut quest.id);
+    for id in quest
+        .prerequisites
+        .iter_mut()
+        .chain(&mut quest.required_prerequisites)
+        .chain(&mut quest.optional_prerequisites)
+    {
+        remap_id(quests, id);
+    }
+}
+
+fn remap_line(
+    line: &mut QuestLine,
+    quests: &BTreeMap<QuestId, QuestId>,
+    lines: &BTreeMap<QuestId, QuestId>,
+) {
+    remap_id(lines, &mut line.id);
+    for entry in &mut line.entries {
+        remap_id(quests, &mut entry.quest_id);
+    }
+}
+
+/// A copy of `db` with the quest and questline ids of `remaps` rewritten.
+pub(crate) fn remapped(db: &QuestDatabase, remaps: &[&IdRemap]) -> QuestDatabase {
+    let mapping = |kind: IdKind| -> BTreeMap<QuestId, QuestId> {
+        remaps
+            .iter()
+            .filter(|r| r.kind == kind)
+            .flat_map(|r| r.mapping.iter().map(|(a, b)| (*a, *b)))
+            .collect()
+    };
+    let (quests, lines) = (mapping(IdKind::Quest), mapping(IdKind::Questline));
+    let mut out = db.clone();
+    out.quests = db
+        .quests
+        .values()
+        .map(|q| {
+            let mut q = q.clone();
+            remap_quest(&mut q, &quests);
+            (q.id, q)
+        })
+        .collect();
+    out.questlines = db
+        .questlines
+        .values()
+        .map(|l| {
+            let mut l = l.clone();
+            remap_line(&mut l, &quests, &lines);
+            (l.id, l)
+        })
+        .collect();
+    for id in &mut out.questline_order {
+        remap_id(&lines, id);
+    }
+    out
+}
+
+/// Combine `sources` into one database. Settings come from the first source
+/// that has them; the questline order is the sources' orders concatenated.
+///
+/// With `ConflictStrategy::Error`, a quest id collision fails with
+/// `DuplicateQuestId` and a questline id collision with `InvalidFormat`.
+pub fn merge(sources: &[&QuestDatabase], strategy: ConflictStrategy) -> Result<QuestDatabase> {
+    let report = check_collisions(sources)?;
+    if strategy == ConflictStrategy::Error
+        && let Some(c) = report.collisions.first()
+    {
+        return Err(match c.kind {
+            IdKind::Quest => ParseError::DuplicateQuestId(c.id.to_string()),
+            IdKind::Questline => {
+                ParseError::InvalidFormat(format!("duplicate questline id {}", c.id))
+            }
+        });
+    }
+
+    let mut out = QuestDatabase {
+        settings: None,
+        quests: Default::default(),
+        questlines: Default::default(),
+        questline_order: Vec::new(),
+    };
+    for (i, db) in sources.iter().enumerate() {
+        let remaps: Vec<&IdRemap> = match strategy {
+            ConflictStrategy::RemapNewIds => {
+                report.remaps.iter().filter(|r| r.source == i).collect()
+            }
+            _ => Vec::new(),
+        };
+        let db = remapped(db, &remaps);
+        if out.settings.is_none() {
+            out.settings = db.settings;
+        }
+        // with KeepFirst, ids already taken stay with the earlier source
+        for (id, quest) in db.quests {
+            out.quests.entry(id).or_insert(quest);
+        }
+        for (id, line) in db.questlines {
+            out.questlines.entry(id).or_insert(line);
+        }
+        for id in db.questline_order {
+            if !out.questline_order.contains(&id) {
+                out.questline_order.push(id);
+            }
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,259 @@
+//! Parse-time introspection.
+//!
+//! `parse_default_quests_dir_with_metrics` runs the same parse as
+//! `db::parse_default_quests_dir_with_options` and reports alongside the
+//! result how many files it looked at and read, how many bytes it read and
+//! how long it took, in total and per `ParseStage`. The numbers are gathered
+//! by wrapping the `QuestDataSource`, so they work for any source (folders,
+//! archives, in-memory test data) and let pipelines spot where a large pack
+//! spends its time.
+//!
+//! `parse_default_quests_dir_with_progress` wraps the source the same way
+//! to report each file as it is read, for frontends showing a progress bar.
+use crate::db::{
+    ParseOptions, ParseOutcome, QuestDataSource, parse_default_quests_dir_staged, quest_file_paths,
+};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+/// A phase of `db::parse_default_quests_dir_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseStage {
+    /// The unknown-field check of `ParseOptions::deny_unknown_fields`; only
+    /// runs when that option is set.
+    Schema,
+    /// Reading `QuestSettings`.
+    Settings,
+    /// Listing and parsing `Quests/`.
+    Quests,
+    /// Parsing `QuestLines/` and their entries.
+    Questlines,
+    /// Alias unification and questline reference checks.
+    References,
+}
+
+/// Counters for one stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageMetrics {
+    /// The stage counted.
+    pub stage: ParseStage,
+    /// Paths the source reported as files.
+    pub files_seen: usize,
+    /// Files read.
+    pub files_parsed: usize,
+    /// Bytes of file text read.
+    pub bytes_read: u64,
+    /// Wall-clock time spent in the stage.
+    pub duration: Duration,
+}
+
+/// Counters for a whole parse; the totals are the sums over `per_stage`
+/// plus the initial root check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseMetrics {
+    /// Paths the source reported as files, over all stages.
+    pub files_seen: usize,
+    /// Files read, over all stages.
+    pub files_parsed: usize,
+    /// Bytes of file text read, over all stages.
+    pub bytes_read: u64,
+    /// Wall-clock time of the whole parse.
+    pub duration: Duration,
+    /// Quests in the parsed database.
+    pub quests: usize,
+    /// Questlines in the parsed database.
+    pub questlines: usize,
+    /// Stages in the order they ran.
+    pub per_stage: Vec<StageMetrics>,
+}
+
+/// `QuestDataSource` wrapper counting file checks and reads.
+struct MeteredSource<'a> {
+    inner: &'a dyn QuestDataSource,
+    files_seen: Cell<usize>,
+    files_parsed: Cell<usize>,
+    bytes_read: Cell<u64>,
+}
+
+impl MeteredSource<'_> {
+    fn counters(&self) -> (usize, usize, u64) {
+        (
+            self.files_seen.get(),
+            self.files_parsed.get(),
+            self.bytes_read.get(),
+        )
+    }
+}
+
+impl QuestDataSource for MeteredSource<'_> {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        self.inner.list_dir(path)
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        self.inner.is_dir(path)
+    }
+    fn is_file(&self, path: &str) -> bool {
+        let is_file = self.inner.is_file(path);
+        if is_file {
+            self.files_seen.set(self.files_seen.get() + 1);
+        }
+        is_file
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        let text = self.inner.read_to_string(path)?;
+        self.files_parsed.set(self.files_parsed.get() + 1);
+        self.bytes_read
+            .set(self.bytes_read.get() + text.len() as u64);
+        Ok(text)
+    }
+}
+
+/// Parse the DefaultQuests folder at `root` like
+/// `db::parse_default_quests_dir_with_options`, also returning what the
+/// parse cost. Metrics are only returned for a successful parse.
+pub fn parse_default_quests_dir_with_metrics(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+) -> Result<(ParseOutcome, ParseMetrics)> {
+    let metered = MeteredSource {
+        inner: source,
+        files_seen: Cell::new(0),
+        files_parsed: Cell::new(0),
+        bytes_read: Cell::new(0),
+    };
+    let start = Instant::now();
+    let mut per_stage: Vec<StageMetrics> = Vec::new();
+    // The running stage with the counters and time at which it began.
+    let mut current: Option<(ParseStage, (usize, usize, u64), Instant)> = None;
+    let mut close = |current: &mut Option<(ParseStage, (usize, usize, u64), Instant)>| {
+        if let Some((stage, (seen, parsed, bytes), began)) = current.take() {
+            let (now_seen, now_parsed, now_bytes) = metered.counters();
+            per_stage.push(StageMetrics {
+                stage,
+                files_seen: now_seen - seen,
+                files_parsed: now_parsed - parsed,
+                bytes_read: now_bytes - bytes,
+                duration: began.elapsed(),
+            });
+        }
+    };
+    let outcome = parse_default_quests_dir_staged(&metered, root, options, |stage| {
+        close(&mut current);
+        current = Some((stage, metered.counters(), Instant::now()));
+    })?;
+    close(&mut current);
+    let (files_seen, files_parsed, bytes_read) = metered.counters();
+    let metrics = ParseMetrics {
+        files_seen,
+        files_parsed,
+        bytes_read,
+        duration: start.elapsed(),
+        quests: outcome.db.quests.len(),
+        questlines: outcome.db.questlines.len(),
+        per_stage,
+    };
+    Ok((outcome, metrics))
+}
+
+/// Progress of `parse_default_quests_dir_with_progress`, reported after each
+/// file read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedSoFar<'a> {
+    /// Files read so far, including `current_path`.
+    pub files_done: usize,
+    /// Files the parse will read, counted before it starts.
+    pub files_total: usize,
+    /// The file just read.
+    pub current_path: &'a str,
+}
+
+/// Number of files the parse of `root` reads: the settings file, the quest
+/// files and the `.json` files of every questline folder.
+fn count_parsed_files(source: &dyn QuestDataSource, root: &str) -> Result<usize> {
+    let settings = ["QuestSettings.json", "QuestSettings"]
+        .iter()
+        .any(|p| source.is_file(&format!("{}/{}", root, p)));
+    let mut total = usize::from(settings) + quest_file_paths(source, root)?.len();
+    let qlines_dir = format!("{}/QuestLines", root);
+    if source.is_dir(&qlines_dir) {
+        for line in source.list_dir(&qlines_dir)? {
+            let line_dir = format!("{}/{}", qlines_dir, line);
+            if !source.is_dir(&line_dir) {
+                continue;
+            }
+            for entry in source.list_dir(&line_dir)? {
+                let path = format!("{}/{}", line_dir, entry);
+                if path.ends_with(".json") && source.is_file(&path) {
+                    total += 1;
+                }
+            }
+        }
+    }
+    Ok(total)
+}
+
+/// `QuestDataSource` wrapper reporting reads to a progress callback.
+struct ProgressSource<'a, F: FnMut(ParsedSoFar<'_>)> {
+    inner: &'a dyn QuestDataSource,
+    /// Off during `ParseStage::Schema`, whose reads are repeated later.
+    counting: Cell<bool>,
+    files_done: Cell<usize>,
+    files_total: usize,
+    progress: RefCell<F>,
+}
+
+impl<F: FnMut(ParsedSoFar<'_>)> QuestDataSource for ProgressSource<'_, F> {
+    fn list_dir(&self, path: &str) -> Result<Vec<String>> {
+        self.inner.list_dir(path)
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        self.inner.is_dir(path)
+    }
+    fn is_file(&self, path: &str) -> bool {
+        self.inner.is_file(path)
+    }
+    fn read_to_string(&self, path: &str) -> Result<String> {
+        let text = self.inner.read_to_string(path)?;
+        if self.counting.get() {
+            let files_done = self.files_done.get() + 1;
+            self.files_done.set(files_done);
+            (self.progress.borrow_mut())(ParsedSoFar {
+                files_done,
+                files_total: self.files_total.max(files_done),
+                current_path: path,
+            });
+        }
+        Ok(text)
+    }
+}
+
+/// Parse the DefaultQuests folder at `root` like
+/// `db::parse_default_quests_dir_with_options`, calling `progress` after
+/// every file read. The total is counted by listing the folders first;
+/// the reads of the `deny_unknown_fields` check are not reported.
+pub fn parse_default_quests_dir_with_progress(
+    source: &dyn QuestDataSource,
+    root: &str,
+    options: &ParseOptions,
+    progress: impl FnMut(ParsedSoFar<'_>),
+) -> Result<ParseOutcome> {
+    let files_total = if source.is_dir(root) {
+        count_parsed_files(source, root)?
+    } else {
+        0
+    };
+    let reporting = ProgressSource {
+        inner: source,
+        counting: Cell::new(true),
+        files_done: Cell::new(0),
+        files_total,
+        progress: RefCell::new(progress),
+    };
+    parse_default_quests_dir_staged(&reporting, root, options, |stage| {
+        reporting.counting.set(stage != ParseStage::Schema);
+    })
+}
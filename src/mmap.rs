@@ -0,0 +1,17 @@
+//! Read-only memory maps for large inputs.
+//!
+//! Server-side tooling may parse multi-hundred-megabyte pack archives; mapping
+//! them avoids holding a second copy in a read buffer. The mapping is only
+//! sound while no other process truncates or rewrites the file, which is why
+//! the `_mmap` entry points are opt-in rather than the default.
+use crate::error::Result;
+use std::path::Path;
+
+/// Map `path` read-only.
+pub fn map_file(path: &Path) -> Result<memmap2::Mmap> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: the map is read-only; callers accept (see module docs) that the
+    // file must not be modified while it is mapped.
+    let map = unsafe { memmap2::Mmap::map(&file)? };
+    Ok(map)
+}
@@ -3,11 +3,14 @@ use crate::model_raw::RawQuest;
 impl Quest {
     /// Convert a RawQuest (serde-deserialized) into the optimized Quest model.
     pub fn from_raw(raw: RawQuest) -> Result<Self> {
-        // Extract quest id
-        let id = QuestId::from_parts(
-            raw.quest_id_high.unwrap_or(0) as i32,
-            raw.quest_id_low.unwrap_or(0) as i32,
-        );
+        // Extract quest id: the high/low pair, or a BQ 3 UUID string or integer
+        let id = match (&raw.quest_id_high, &raw.quest_id_low, &raw.quest_id) {
+            (None, None, Some(id)) => crate::nbt_norm::quest_id_from_value(id, "questID")?,
+            _ => QuestId::from_parts_i64(
+                raw.quest_id_high.unwrap_or(0),
+                raw.quest_id_low.unwrap_or(0),
+            ),
+        };
 
         // Build a normalized view of top-level extra fields (strip NBT suffixes and convert numeric maps->arrays)
         let normalized_extra_opt: Option<serde_json::Map<String, serde_json::Value>> =
@@ -26,9 +29,12 @@ impl Quest {
 
         // Properties: extract strongly typed betterquesting block
         fn convert_raw_props(props: &crate::model_raw::RawQuestProperties) -> QuestProperties {
-            QuestProperties {
+            let mut extra = props.extra.clone();
+            let pages = description_pages(props.desc.as_ref(), &mut extra);
+            let mut converted = QuestProperties {
                 name: props.name.clone(),
-                desc: props.desc.clone(),
+                desc: None,
+                desc_pages: Vec::new(),
                 icon: None, // TODO: parse icon if needed
                 is_main: props.is_main,
                 is_silent: props.is_silent,
@@ -45,8 +51,10 @@ impl Quest {
                 visibility: props.visibility.clone(),
                 snd_complete: props.snd_complete.clone(),
                 snd_update: props.snd_update.clone(),
-                extra: props.extra.clone(),
-            }
+                extra,
+            };
+            converted.set_pages(pages);
+            converted
         }
 
         // Try wrapped betterquesting first; otherwise attempt to extract from the extra map (with normalization)
@@ -179,10 +187,11 @@ impl Quest {
                 crate::model_raw::RawTasksWrapper::Array(arr) => {
                     for (i, v) in arr.into_iter().enumerate() {
                         let v_norm = crate::nbt_norm::normalize_value(v);
-                        if let Ok(mut t) = serde_json::from_value::<Task>(v_norm) {
-                            t.index = Some(i);
-                            tasks.push(t);
-                        }
+                        let mut t = serde_json::from_value::<Task>(v_norm).map_err(|e| {
+                            crate::error::ParseError::InvalidFormat(format!("task {}: {}", i, e))
+                        })?;
+                        t.index = Some(i);
+                        tasks.push(t);
                     }
                 }
                 crate::model_raw::RawTasksWrapper::Object(obj) => {
@@ -195,10 +204,14 @@ impl Quest {
                     if let serde_json::Value::Array(arr2) = norm {
                         for (i, v) in arr2.into_iter().enumerate() {
                             let v_norm = crate::nbt_norm::normalize_value(v);
-                            if let Ok(mut t) = serde_json::from_value::<Task>(v_norm) {
-                                t.index = Some(i);
-                                tasks.push(t);
-                            }
+                            let mut t = serde_json::from_value::<Task>(v_norm).map_err(|e| {
+                                crate::error::ParseError::InvalidFormat(format!(
+                                    "task {}: {}",
+                                    i, e
+                                ))
+                            })?;
+                            t.index = Some(i);
+                            tasks.push(t);
                         }
                     }
                 }
@@ -232,10 +245,11 @@ impl Quest {
                 crate::model_raw::RawRewardsWrapper::Array(arr) => {
                     for (i, v) in arr.into_iter().enumerate() {
                         let v_norm = crate::nbt_norm::normalize_value(v);
-                        if let Ok(mut r) = serde_json::from_value::<Reward>(v_norm) {
-                            r.index = Some(i);
-                            rewards.push(r);
-                        }
+                        let mut r = serde_json::from_value::<Reward>(v_norm).map_err(|e| {
+                            crate::error::ParseError::InvalidFormat(format!("reward {}: {}", i, e))
+                        })?;
+                        r.index = Some(i);
+                        rewards.push(r);
                     }
                 }
                 crate::model_raw::RawRewardsWrapper::Object(obj) => {
@@ -247,18 +261,36 @@ impl Quest {
                     if let serde_json::Value::Array(arr2) = norm {
                         for (i, v) in arr2.into_iter().enumerate() {
                             let v_norm = crate::nbt_norm::normalize_value(v);
-                            if let Ok(mut r) = serde_json::from_value::<Reward>(v_norm) {
-                                r.index = Some(i);
-                                rewards.push(r);
-                            }
+                            let mut r = serde_json::from_value::<Reward>(v_norm).map_err(|e| {
+                                crate::error::ParseError::InvalidFormat(format!(
+                                    "reward {}: {}",
+                                    i, e
+                                ))
+                            })?;
+                            r.index = Some(i);
+                            rewards.push(r);
                         }
                     }
                 }
             }
         }
 
-        // Prerequisites
-        fn parse_prereqs(val: Option<crate::model_raw::RawQuestRefs>) -> Vec<QuestId> {
+        // Prerequisites: id objects, or bare UUID strings or integers in BQ 3 packs
+        fn prereq_id(v: &serde_json::Value) -> Result<Option<QuestId>> {
+            match v {
+                serde_json::Value::Object(obj_map) => Ok(Some(crate::nbt_norm::get_quest_id(
+                    obj_map,
+                    "questIDHigh",
+                    "questIDLow",
+                )?)),
+                serde_json::Value::String(_) | serde_json::Value::Number(_) => Ok(Some(
+                    crate::nbt_norm::quest_id_from_value(v, "preRequisites")?,
+                )),
+                _ => Ok(None),
+            }
+        }
+
+        fn parse_prereqs(val: Option<crate::model_raw::RawQuestRefs>) -> Result<Vec<QuestId>> {
             let mut out = Vec::new();
             if let Some(wrapper) = val {
                 match wrapper {
@@ -266,52 +298,30 @@ impl Quest {
                         for (_k, v) in inner {
                             // normalize individual prereq object before inspecting fields
                             let v_norm = crate::nbt_norm::normalize_value(v.clone());
-                            if let serde_json::Value::Object(obj_map) = v_norm {
-                                let high = obj_map
-                                    .get("questIDHigh")
-                                    .and_then(|x| x.as_i64())
-                                    .unwrap_or(0) as i32;
-                                let low = obj_map
-                                    .get("questIDLow")
-                                    .and_then(|x| x.as_i64())
-                                    .unwrap_or(0) as i32;
-                                out.push(QuestId::from_parts(high, low));
-                            }
+                            out.extend(prereq_id(&v_norm)?);
                         }
                     }
                     crate::model_raw::RawQuestRefs::Array(arr) => {
                         for elem in arr {
                             let elem_norm = crate::nbt_norm::normalize_value(elem);
-                            if let serde_json::Value::Object(obj_map) = elem_norm {
-                                let high = obj_map
-                                    .get("questIDHigh")
-                                    .and_then(|x| x.as_i64())
-                                    .unwrap_or(0) as i32;
-                                let low = obj_map
-                                    .get("questIDLow")
-                                    .and_then(|x| x.as_i64())
-                                    .unwrap_or(0) as i32;
-                                out.push(QuestId::from_parts(high, low));
-                            }
+                            out.extend(prereq_id(&elem_norm)?);
                         }
                     }
                 }
             }
-            out
+            Ok(out)
         }
 
-        let all_prereqs = parse_prereqs(raw.pre_requisites);
-        let mut optional_prereqs = parse_prereqs(raw.optional_pre_requisites);
+        let all_prereqs = parse_prereqs(raw.pre_requisites)?;
+        let mut optional_prereqs = parse_prereqs(raw.optional_pre_requisites)?;
 
         // Decide which prereqs are required vs optional
         let mut required_prereqs = Vec::new();
         if !optional_prereqs.is_empty() {
-            let optset: std::collections::HashSet<u64> = optional_prereqs
-                .iter()
-                .map(|q: &QuestId| q.as_u64())
-                .collect();
+            let optset: std::collections::HashSet<QuestId> =
+                optional_prereqs.iter().copied().collect();
             for q in all_prereqs.iter() {
-                if !optset.contains(&q.as_u64()) {
+                if !optset.contains(q) {
                     required_prereqs.push(*q);
                 }
             }
@@ -341,6 +351,7 @@ impl Quest {
         })
     }
 }
+use crate::model_raw::{bool_from_int, list_or_empty_map};
 use crate::quest_id::QuestId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -377,6 +388,80 @@ pub struct Quest {
     pub optional_prerequisites: Vec<QuestId>,
 }
 
+impl Quest {
+    /// The quest's display name, if properties were parsed.
+    pub fn name(&self) -> Option<&str> {
+        self.properties.as_ref().map(|p| p.name.as_str())
+    }
+
+    /// Kill targets of the quest's hunt tasks, in task order.
+    pub fn hunt_targets(&self) -> Vec<HuntTarget> {
+        self.tasks.iter().filter_map(Task::hunt_target).collect()
+    }
+
+    /// The quest's visibility setting, if set to a known value.
+    pub fn visibility(&self) -> Option<QuestVisibility> {
+        self.properties.as_ref()?.visibility_kind()
+    }
+}
+
+/// When the quest book shows a quest, as stored in
+/// `QuestProperties::visibility`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum QuestVisibility {
+    /// Never shown.
+    Hidden,
+    /// Kept secret until the player gets to it.
+    Secret,
+    /// Shown once unlocked.
+    Unlocked,
+    /// Shown once a prerequisite is completed.
+    Normal,
+    /// Shown once completed.
+    Completed,
+    /// Shown when a prerequisite is shown.
+    Chain,
+    /// Always shown.
+    Always,
+}
+
+impl QuestVisibility {
+    /// Parse a stored visibility name, ignoring case.
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            Self::Hidden,
+            Self::Secret,
+            Self::Unlocked,
+            Self::Normal,
+            Self::Completed,
+            Self::Chain,
+            Self::Always,
+        ]
+        .into_iter()
+        .find(|v| v.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// The name BetterQuesting stores.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hidden => "HIDDEN",
+            Self::Secret => "SECRET",
+            Self::Unlocked => "UNLOCKED",
+            Self::Normal => "NORMAL",
+            Self::Completed => "COMPLETED",
+            Self::Chain => "CHAIN",
+            Self::Always => "ALWAYS",
+        }
+    }
+
+    /// Returns true for the settings meant to keep a quest from being
+    /// spoiled (`Hidden` and `Secret`).
+    pub fn is_concealed(self) -> bool {
+        matches!(self, Self::Hidden | Self::Secret)
+    }
+}
+
 /// Human-visible properties for a quest.
 ///
 /// Unknown or extension fields are preserved in the `extra` map so callers can
@@ -385,8 +470,13 @@ pub struct Quest {
 pub struct QuestProperties {
     /// Quest name (required).
     pub name: String,
-    /// Short description or lore text.
+    /// Short description or lore text; the first page when the description
+    /// is paginated.
     pub desc: Option<String>,
+    /// Further description pages after `desc`, in reading order. Empty for
+    /// the usual single-page description.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub desc_pages: Vec<String>,
     /// Icon item for display purposes.
     pub icon: Option<ItemStack>,
     /// Is this quest considered a main quest?
@@ -413,7 +503,8 @@ pub struct QuestProperties {
     pub quest_logic: Option<String>,
     /// Raw per-task logic identifier.
     pub task_logic: Option<String>,
-    /// Visibility hint for UIs (string preserved as-is).
+    /// Visibility hint for UIs (string preserved as-is; see
+    /// `visibility_kind`).
     pub visibility: Option<String>,
     /// Optional completion / update sound identifiers
     pub snd_complete: Option<String>,
@@ -423,6 +514,65 @@ pub struct QuestProperties {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+impl QuestProperties {
+    /// `visibility` as a `QuestVisibility`; `None` when unset or unknown.
+    pub fn visibility_kind(&self) -> Option<QuestVisibility> {
+        self.visibility.as_deref().and_then(QuestVisibility::parse)
+    }
+
+    /// All description pages in reading order: `desc` followed by
+    /// `desc_pages`. Empty when there is no description.
+    pub fn pages(&self) -> Vec<&str> {
+        self.desc
+            .iter()
+            .chain(&self.desc_pages)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Replace the description with `pages`: the first becomes `desc`, the
+    /// rest `desc_pages`.
+    pub fn set_pages(&mut self, pages: Vec<String>) {
+        let mut pages = pages.into_iter();
+        self.desc = pages.next();
+        self.desc_pages = pages.collect();
+    }
+}
+
+/// Collect a quest description stored in one of the paginated layouts used by
+/// BetterQuesting forks into a list of pages.
+///
+/// `desc` may be a string or an array of strings (an NBT list); keys `desc2`,
+/// `desc3`, ... are removed from `extra` and appended in numeric order.
+/// Non-string array items are skipped.
+pub(crate) fn description_pages(
+    desc: Option<&serde_json::Value>,
+    extra: &mut HashMap<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut pages: Vec<String> = match desc {
+        Some(serde_json::Value::String(s)) => vec![s.clone()],
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut numbered: Vec<(u32, String)> = extra
+        .keys()
+        .filter_map(|k| {
+            let n: u32 = k.strip_prefix("desc")?.parse().ok()?;
+            (n >= 2 && extra[k].is_string()).then(|| (n, k.clone()))
+        })
+        .collect();
+    numbered.sort();
+    for (_, key) in numbered {
+        if let Some(serde_json::Value::String(page)) = extra.remove(&key) {
+            pages.push(page);
+        }
+    }
+    pages
+}
+
 /// Simplified ItemStack representation used in tasks/rewards/icons.
 ///
 /// We intentionally keep a small, common subset of item fields (id, damage,
@@ -433,16 +583,30 @@ pub struct ItemStack {
     /// Item identifier (namespaced id like "minecraft:stone").
     pub id: String,
     /// Optional damage / meta value.
+    #[serde(alias = "Damage")]
     pub damage: Option<i32>,
     /// Optional stack count.
+    #[serde(alias = "Count")]
     pub count: Option<i32>,
     /// Ore dictionary name if present.
+    #[serde(alias = "OreDict")]
     pub oredict: Option<String>,
     /// Any additional, unmodeled NBT/json data.
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+impl std::fmt::Display for ItemStack {
+    /// Formats as `<count>x <id>[:<damage>]`, omitting a zero damage value.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x {}", self.count.unwrap_or(1), self.id)?;
+        match self.damage {
+            Some(d) if d != 0 => write!(f, ":{}", d),
+            _ => Ok(()),
+        }
+    }
+}
+
 /// A quest Task entry.
 ///
 /// `task_id` identifies the task implementation/type (plugins will vary). The
@@ -453,35 +617,88 @@ pub struct Task {
     /// Optional index within the containing quest or questline ordering.
     pub index: Option<usize>,
     /// Canonical identifier for the task implementation.
+    #[serde(alias = "taskID")]
     pub task_id: String,
     /// Items required by this task (if applicable).
-    #[serde(default)]
+    #[serde(
+        default,
+        alias = "requiredItems",
+        deserialize_with = "list_or_empty_map"
+    )]
     pub required_items: Vec<ItemStack>,
-    /// Common boolean-like flags found on many task types.
+    /// Common boolean-like flags found on many task types (0/1 in source).
+    #[serde(default, alias = "ignoreNBT", deserialize_with = "bool_from_int")]
     pub ignore_nbt: Option<bool>,
+    #[serde(default, alias = "partialMatch", deserialize_with = "bool_from_int")]
     pub partial_match: Option<bool>,
+    #[serde(default, alias = "autoConsume", deserialize_with = "bool_from_int")]
     pub auto_consume: Option<bool>,
+    #[serde(default, deserialize_with = "bool_from_int")]
     pub consume: Option<bool>,
+    #[serde(default, alias = "groupDetect", deserialize_with = "bool_from_int")]
     pub group_detect: Option<bool>,
     /// Task-specific or unknown fields.
     #[serde(flatten)]
     pub options: HashMap<String, serde_json::Value>,
 }
 
+/// Task id of BetterQuesting's kill task.
+pub const HUNT_TASK_ID: &str = "bq_standard:hunt";
+
+/// What a `bq_standard:hunt` task asks the player to kill.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HuntTarget {
+    /// Entity id (`minecraft:zombie`; older packs use names like `Zombie`).
+    pub target_entity: String,
+    /// Kills needed; BetterQuesting's default of 1 when not stored.
+    pub required_kills: i32,
+    /// Whether the entity's NBT is ignored when matching kills;
+    /// BetterQuesting's default of true when not stored.
+    pub ignore_nbt: bool,
+}
+
+impl Task {
+    /// The kill target of a hunt task, read from `options`. `None` for other
+    /// task types and for hunt tasks without a target or with a kill count
+    /// that is not an integer in `i32` range.
+    pub fn hunt_target(&self) -> Option<HuntTarget> {
+        if self.task_id != HUNT_TASK_ID {
+            return None;
+        }
+        let target_entity = self.options.get("target")?.as_str()?.to_string();
+        let required_kills = match self.options.get("required") {
+            Some(n) => i32::try_from(n.as_i64()?).ok()?,
+            None => 1,
+        };
+        Some(HuntTarget {
+            target_entity,
+            required_kills,
+            ignore_nbt: self.ignore_nbt.unwrap_or(true),
+        })
+    }
+}
+
 /// A quest Reward entry (items / commands / scripted rewards).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Reward {
     /// Optional index within the containing quest.
     pub index: Option<usize>,
     /// Identifier for the reward type/handler.
+    #[serde(alias = "rewardID")]
     pub reward_id: String,
-    /// Items granted by this reward (if any).
-    #[serde(default)]
+    /// Items granted by this reward (if any). BetterQuesting stores these under
+    /// a `rewards` key inside the reward entry.
+    #[serde(default, alias = "rewards", deserialize_with = "list_or_empty_map")]
     pub items: Vec<ItemStack>,
     /// Alternative choices for choice-type rewards.
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "list_or_empty_map"
+    )]
     pub choices: Vec<ItemStack>,
     /// Common boolean-like flag indicating whether disabled rewards are ignored.
+    #[serde(default, alias = "ignoreDisabled", deserialize_with = "bool_from_int")]
     pub ignore_disabled: Option<bool>,
     /// Any unknown or additional fields.
     #[serde(flatten)]
@@ -497,7 +714,7 @@ pub struct QuestLine {
     /// Identifier for the line (also stored as a questline id pair).
     pub id: QuestId,
     /// Optional properties for the line (title, icon, visibility, ...).
-    pub properties: Option<QuestProperties>,
+    pub properties: Option<QuestLineProperties>,
     /// Entries (positions) on the line.
     #[serde(default)]
     pub entries: Vec<QuestLineEntry>,
@@ -506,6 +723,78 @@ pub struct QuestLine {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// Display properties of a questline.
+///
+/// Unlike `QuestProperties` the name may be missing (it is then empty), and
+/// only the fields BetterQuesting reads for questlines are modeled; the
+/// quest-centric flags of packs that set them end up in `extra`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestLineProperties {
+    /// Questline title.
+    #[serde(default)]
+    pub name: String,
+    /// Description shown on the questline page; the first page when the
+    /// description is paginated.
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// Further description pages after `desc`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub desc_pages: Vec<String>,
+    /// Icon item of the questline tab.
+    #[serde(default)]
+    pub icon: Option<ItemStack>,
+    /// Visibility hint for UIs (string preserved as-is).
+    #[serde(default)]
+    pub visibility: Option<String>,
+    /// Background image resource of the questline page.
+    #[serde(default)]
+    pub bg_image: Option<String>,
+    /// Size of the background image.
+    #[serde(default)]
+    pub bg_size: Option<i32>,
+    /// Extra unknown fields.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl QuestLineProperties {
+    /// All description pages in reading order, like `QuestProperties::pages`.
+    pub fn pages(&self) -> Vec<&str> {
+        self.desc
+            .iter()
+            .chain(&self.desc_pages)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Replace the description with `pages`, like
+    /// `QuestProperties::set_pages`.
+    pub fn set_pages(&mut self, pages: Vec<String>) {
+        let mut pages = pages.into_iter();
+        self.desc = pages.next();
+        self.desc_pages = pages.collect();
+    }
+}
+
+/// Properties carrying a (possibly paginated) description, for the text
+/// renderers.
+pub trait DescriptionPages {
+    /// All description pages in reading order.
+    fn description_pages(&self) -> Vec<&str>;
+}
+
+impl DescriptionPages for QuestProperties {
+    fn description_pages(&self) -> Vec<&str> {
+        self.pages()
+    }
+}
+
+impl DescriptionPages for QuestLineProperties {
+    fn description_pages(&self) -> Vec<&str> {
+        self.pages()
+    }
+}
+
 /// A single entry inside a `QuestLine` describing the layout of a quest tile.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct QuestLineEntry {
@@ -542,7 +831,7 @@ pub struct QuestSettings {
 /// `QuestDatabase` ties together parsed quests, questlines and the global
 /// settings. In strict mode (current behavior) references inside questlines are
 /// validated and will cause parsing to fail if dangling.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct QuestDatabase {
     /// Optional global settings (may be absent).
     pub settings: Option<QuestSettings>,
@@ -1,6 +1,6 @@
 use serde::de::{self, Deserializer};
 
-fn bool_from_int<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+pub(crate) fn bool_from_int<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -16,6 +16,19 @@ where
         _ => Err(de::Error::custom("invalid type for bool")),
     }
 }
+
+/// A list that NBT stores empty as `{}` (an empty compound normalizes to an
+/// object, not an array).
+pub(crate) fn list_or_empty_map<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Object(m) if m.is_empty() => Ok(Vec::new()),
+        v => serde_json::from_value(v).map_err(de::Error::custom),
+    }
+}
 // Raw models for deserializing the original quest JSON structure as closely as possible.
 // These are not optimized for library use, but match the input format for serde.
 
@@ -28,6 +41,10 @@ pub struct RawQuest {
     pub quest_id_high: Option<i64>,
     #[serde(rename = "questIDLow")]
     pub quest_id_low: Option<i64>,
+    /// UUID string or integer id used by BetterQuesting 3 instead of the
+    /// high/low pair.
+    #[serde(rename = "questID", default)]
+    pub quest_id: Option<serde_json::Value>,
     pub properties: Option<RawPropertiesWrapper>,
     pub tasks: Option<RawTasksWrapper>,
     pub rewards: Option<RawRewardsWrapper>,
@@ -50,8 +67,9 @@ pub struct RawPropertiesWrapper {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RawQuestProperties {
     pub name: String,
+    /// Description text, or a list of pages in forks that paginate it.
     #[serde(default)]
-    pub desc: Option<String>,
+    pub desc: Option<serde_json::Value>,
     #[serde(default)]
     pub icon: Option<serde_json::Value>,
     #[serde(rename = "isMain", default, deserialize_with = "bool_from_int")]
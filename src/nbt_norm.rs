@@ -1,3 +1,5 @@
+use crate::error::{ParseError, Result};
+use crate::quest_id::QuestId;
 use serde_json::{Map, Value};
 use std::collections::BTreeMap;
 
@@ -66,6 +68,114 @@ pub fn map_to_array_if_numeric(m: &Map<String, Value>) -> Option<Vec<Value>> {
     Some(numeric_keys.into_values().collect())
 }
 
+/// Read an integer field from a normalized object.
+///
+/// Returns `Ok(None)` when the key is absent or not an integer, and an error
+/// when the stored integer does not fit in `T` instead of truncating it.
+pub fn get_int<T: TryFrom<i64>>(m: &Map<String, Value>, key: &str) -> Result<Option<T>> {
+    let Some(Value::Number(n)) = m.get(key) else {
+        return Ok(None);
+    };
+    let out_of_range = || ParseError::NumberOutOfRange {
+        field: key.to_string(),
+        value: n.to_string(),
+    };
+    match n.as_i64() {
+        Some(v) => T::try_from(v).map(Some).map_err(|_| out_of_range()),
+        None if n.is_u64() => Err(out_of_range()),
+        None => Ok(None),
+    }
+}
+
+/// Read a quest id stored as two NBT longs (e.g. `questIDHigh`/`questIDLow`),
+/// defaulting missing parts to 0.
+///
+/// BetterQuesting 3 keys quests by UUID string or single integer instead;
+/// when neither part is present the id is read from the shared stem of the
+/// keys (`questID`, `questLineID`) with `quest_id_from_value`.
+pub fn get_quest_id(m: &Map<String, Value>, high_key: &str, low_key: &str) -> Result<QuestId> {
+    if !m.contains_key(high_key)
+        && !m.contains_key(low_key)
+        && let Some(stem) = high_key.strip_suffix("High")
+        && let Some(v) = m.get(stem)
+    {
+        return quest_id_from_value(v, stem);
+    }
+    let high = get_int::<i64>(m, high_key)?.unwrap_or(0);
+    let low = get_int::<i64>(m, low_key)?.unwrap_or(0);
+    Ok(QuestId::from_parts_i64(high, low))
+}
+
+/// Read a quest id stored as a single value: a UUID string, or an integer
+/// taken as the low part of the id. `field` names the value in the error for
+/// anything else.
+pub fn quest_id_from_value(v: &Value, field: &str) -> Result<QuestId> {
+    let id = match v {
+        Value::String(s) => QuestId::from_uuid_str(s),
+        Value::Number(n) => n.as_i64().map(|low| QuestId::from_parts_i64(0, low)),
+        _ => None,
+    };
+    id.ok_or_else(|| {
+        ParseError::InvalidFormat(format!("{} is not a UUID or integer id: {}", field, v))
+    })
+}
+
+/// Keys BetterQuesting stores as NBT longs.
+const LONG_KEYS: [&str; 4] = [
+    "questIDHigh",
+    "questIDLow",
+    "questLineIDHigh",
+    "questLineIDLow",
+];
+
+/// Inverse of `normalize_value`: add NBT type suffixes to object keys and
+/// turn arrays into numeric-keyed lists, producing the layout BetterQuesting
+/// reads. Booleans become bytes, integers ints (longs when out of range or
+/// named in `LONG_KEYS`, shorts for `Damage`), other numbers doubles. Arrays
+/// of integers stay arrays (int arrays); `null` entries are dropped.
+pub fn tag_value(v: Value) -> Value {
+    match v {
+        Value::Object(m) => Value::Object(tag_map(m)),
+        other => other,
+    }
+}
+
+fn tag_map(m: Map<String, Value>) -> Map<String, Value> {
+    m.into_iter()
+        .filter_map(|(k, v)| tag_entry(&k, v))
+        .collect()
+}
+
+fn tag_entry(key: &str, v: Value) -> Option<(String, Value)> {
+    let (suffix, value) = match v {
+        Value::Null => return None,
+        Value::Bool(b) => (1, Value::from(u8::from(b))),
+        Value::Number(n) => {
+            let suffix = match n.as_i64() {
+                Some(_) if LONG_KEYS.contains(&key) => 4,
+                Some(_) if key == "Damage" => 2,
+                Some(i) if i32::try_from(i).is_ok() => 3,
+                Some(_) => 4,
+                None if n.is_u64() => 4,
+                None => 6,
+            };
+            (suffix, Value::Number(n))
+        }
+        Value::String(s) => (8, Value::String(s)),
+        Value::Object(m) => (10, Value::Object(tag_map(m))),
+        Value::Array(a) if !a.is_empty() && a.iter().all(|e| e.is_i64()) => (11, Value::Array(a)),
+        Value::Array(a) => {
+            let list = a
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, e)| tag_entry(&i.to_string(), e))
+                .collect();
+            (9, Value::Object(list))
+        }
+    };
+    Some((format!("{}:{}", key, suffix), value))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +198,40 @@ mod tests {
             panic!("expected array after normalization");
         }
     }
+
+    #[test]
+    fn tagging_round_trips_through_normalization() {
+        let v = json!({
+            "questIDLow": 3,
+            "name": "x",
+            "flag": true,
+            "Damage": 0,
+            "items": [{ "id": "a" }, { "id": "b" }],
+            "ints": [1, 2],
+            "none": null,
+        });
+        let tagged = tag_value(v);
+        let m = tagged.as_object().unwrap();
+        assert_eq!(m["questIDLow:4"], json!(3));
+        assert_eq!(m["flag:1"], json!(1));
+        assert_eq!(m["Damage:2"], json!(0));
+        assert_eq!(m["items:9"]["1:10"]["id:8"], json!("b"));
+        assert_eq!(m["ints:11"], json!([1, 2]));
+        assert!(!m.contains_key("none"));
+        let back = normalize_value(tagged);
+        assert_eq!(back["items"][1]["id"], json!("b"));
+        assert_eq!(back["name"], json!("x"));
+    }
+
+    #[test]
+    fn int_fields_error_instead_of_truncating() {
+        let v = json!({ "x": 5, "big": 4_294_967_296i64, "huge": u64::MAX, "f": 1.5 });
+        let m = v.as_object().unwrap();
+        assert_eq!(get_int::<i32>(m, "x").unwrap(), Some(5));
+        assert_eq!(get_int::<i32>(m, "missing").unwrap(), None);
+        assert_eq!(get_int::<i32>(m, "f").unwrap(), None);
+        assert!(get_int::<i32>(m, "big").is_err());
+        assert_eq!(get_int::<i64>(m, "big").unwrap(), Some(4_294_967_296));
+        assert!(get_int::<i64>(m, "huge").is_err());
+    }
 }
@@ -0,0 +1,104 @@
+//! Canonical formatting of DefaultQuests JSON files.
+//!
+//! BetterQuesting, hand edits and other tools all format quest files a little
+//! differently, and the mod itself orders list elements `0:10, 1:10, 10:10,
+//! 2:10`. `canonical_json` fixes one layout: two-space indentation, object
+//! keys sorted with numeric list indices in numeric order, and a trailing
+//! newline. `db::write_default_quests_dir` writes this layout, and
+//! `normalize_tree` brings an existing folder to it once, so later diffs only
+//! show real edits.
+use crate::db::{IncrementalFsSink, QuestDataSink, WriteSummary};
+use crate::error::{ParseError, Result};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// Order keys by name, with numeric names (list indices like `10:10`)
+/// compared as numbers.
+fn key_order(a: &str, b: &str) -> Ordering {
+    let name = |k: &str| -> Option<u64> { k.split(':').next()?.parse().ok() };
+    match (name(a), name(b)) {
+        (Some(x), Some(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+fn write_value(out: &mut String, v: &Value, indent: usize) {
+    let pad = |out: &mut String, n: usize| out.extend(std::iter::repeat_n("  ", n));
+    match v {
+        Value::Object(m) if !m.is_empty() => {
+            let mut keys: Vec<&String> = m.keys().collect();
+            keys.sort_by(|a, b| key_order(a, b));
+            out.push_str("{\n");
+            for (i, k) in keys.iter().enumerate() {
+                pad(out, indent + 1);
+                out.push_str(&Value::String((*k).clone()).to_string());
+                out.push_str(": ");
+                write_value(out, &m[*k], indent + 1);
+                out.push_str(if i + 1 < keys.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push('}');
+        }
+        Value::Array(a) if !a.is_empty() => {
+            out.push_str("[\n");
+            for (i, e) in a.iter().enumerate() {
+                pad(out, indent + 1);
+                write_value(out, e, indent + 1);
+                out.push_str(if i + 1 < a.len() { ",\n" } else { "\n" });
+            }
+            pad(out, indent);
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Render `v` in the canonical layout.
+pub fn canonical_json(v: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, v, 0);
+    out.push('\n');
+    out
+}
+
+/// Collect the `.json` files below `dir`, recursively.
+pub(crate) fn json_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            json_files(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "json") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite every `.json` file below `root` in the canonical layout. Values
+/// are kept as they are; only whitespace and key order change. Every file is
+/// parsed before any is written, so a malformed file aborts the run without
+/// touching the tree. Files already in canonical form are left alone.
+pub fn normalize_tree(root: &Path) -> Result<WriteSummary> {
+    let mut files = Vec::new();
+    json_files(root, &mut files)?;
+    files.sort();
+    let mut rendered = Vec::with_capacity(files.len());
+    for path in files {
+        let text = std::fs::read_to_string(&path)?;
+        let v: Value = serde_json::from_str(&text)
+            .map_err(|e| ParseError::InvalidFormat(format!("{}: {}", path.display(), e)))?;
+        rendered.push((path, canonical_json(&v)));
+    }
+    let mut sink = IncrementalFsSink::default();
+    for (path, text) in rendered {
+        sink.write_file(&path.to_string_lossy(), &text)?;
+    }
+    Ok(WriteSummary {
+        written: sink.written,
+        unchanged: sink.unchanged.len(),
+        removed: Vec::new(),
+    })
+}
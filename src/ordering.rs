@@ -0,0 +1,95 @@
+//! The order in which the crate lists quests and questlines.
+//!
+//! Quests and questlines live in hash maps, whose iteration order changes
+//! from run to run. Everything public that lists them (exports, statistics,
+//! validation and analysis reports, written files) uses the orders defined
+//! here, so output is identical for identical input and snapshot tests and
+//! diffs stay stable:
+//!
+//! - quests: ascending `QuestId` (`cmp_quest_ids`);
+//! - questlines: `QuestDatabase::questline_order`, then questlines missing
+//!   from it in ascending id order (`questline_ids`); ids in the order without
+//!   a questline are skipped;
+//! - questline entries: as stored, which after parsing is ascending quest id;
+//! - findings and report rows about quests or questlines: by the orders
+//!   above unless the report documents a ranking of its own (importance
+//!   scores, for example);
+//! - files and folders read by the parser: `natural_cmp` on their names, so
+//!   the `QuestLines/<n>` folders written by `db::write_default_quests_dir`
+//!   parse back into the same `questline_order`.
+use crate::model::{Quest, QuestDatabase, QuestLine};
+use crate::quest_id::QuestId;
+use std::cmp::Ordering;
+
+/// The quest order: ascending id.
+pub fn cmp_quest_ids(a: &QuestId, b: &QuestId) -> Ordering {
+    a.cmp(b)
+}
+
+/// Quests of `db` in ascending id order.
+pub fn sorted_quests(db: &QuestDatabase) -> Vec<&Quest> {
+    let mut quests: Vec<&Quest> = db.quests.values().collect();
+    quests.sort_by(|a, b| cmp_quest_ids(&a.id, &b.id));
+    quests
+}
+
+/// Ids of the questlines of `db`: those in `questline_order` first, then
+/// the others by ascending id.
+pub fn questline_ids(db: &QuestDatabase) -> Vec<QuestId> {
+    let mut ids: Vec<QuestId> = Vec::with_capacity(db.questlines.len());
+    for id in &db.questline_order {
+        if db.questlines.contains_key(id) && !ids.contains(id) {
+            ids.push(*id);
+        }
+    }
+    let mut unordered: Vec<QuestId> = db
+        .questlines
+        .keys()
+        .filter(|id| !ids.contains(id))
+        .copied()
+        .collect();
+    unordered.sort_by(cmp_quest_ids);
+    ids.extend(unordered);
+    ids
+}
+
+/// Questlines of `db` in `questline_ids` order.
+pub fn sorted_questlines(db: &QuestDatabase) -> Vec<&QuestLine> {
+    questline_ids(db)
+        .iter()
+        .map(|id| &db.questlines[id])
+        .collect()
+}
+
+/// Compare names with runs of ASCII digits compared by value, so `2` sorts
+/// before `10` and `line-9` before `line-10`. Names equal by that measure
+/// (`01` and `1`) fall back to plain string order.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a, b);
+    loop {
+        match (x.chars().next(), y.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(c), Some(d)) if c.is_ascii_digit() && d.is_ascii_digit() => {
+                let xn = x.len() - x.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                let yn = y.len() - y.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                let xd = x[..xn].trim_start_matches('0');
+                let yd = y[..yn].trim_start_matches('0');
+                let by_value = xd.len().cmp(&yd.len()).then_with(|| xd.cmp(yd));
+                if by_value != Ordering::Equal {
+                    return by_value;
+                }
+                x = &x[xn..];
+                y = &y[yn..];
+            }
+            (Some(c), Some(d)) => {
+                if c != d {
+                    return c.cmp(&d);
+                }
+                x = &x[c.len_utf8()..];
+                y = &y[d.len_utf8()..];
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Player progress laid over a quest database.
+//!
+//! `ProgressOverlay::build` classifies every quest of a database for one
+//! player as locked, unlocked, completed or claimed, the way the quest book
+//! shows it. Completion and claim flags come from `progress::PlayerProgress`;
+//! whether an unfinished quest is unlocked follows from its required
+//! prerequisites and its `quest_logic`:
+//!
+//! | logic  | unlocked when, of n prerequisites, k are completed |
+//! |--------|----------------------------------------------------|
+//! | `AND`  | k = n (the default, also for unknown values)       |
+//! | `OR`   | k ≥ 1                                              |
+//! | `NAND` | k < n                                              |
+//! | `NOR`  | k = 0                                              |
+//! | `XOR`  | k = 1                                              |
+//! | `XNOR` | k ≠ 1                                              |
+//!
+//! Quests without required prerequisites are always unlocked; optional
+//! prerequisites never gate a quest. Prerequisites missing from the database
+//! count as not completed.
+use crate::model::{Quest, QuestDatabase};
+use crate::progress::PlayerProgress;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// How a quest looks to one player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestState {
+    /// Prerequisites are not met.
+    Locked,
+    /// Available but not completed.
+    Unlocked,
+    /// Completed, rewards not claimed.
+    Completed,
+    /// Completed and rewards claimed.
+    Claimed,
+}
+
+impl QuestState {
+    /// Returns true for `Completed` and `Claimed`.
+    pub fn is_done(self) -> bool {
+        matches!(self, QuestState::Completed | QuestState::Claimed)
+    }
+}
+
+/// Whether `logic` is satisfied by `done` of `total` prerequisites.
+fn logic_met(logic: Option<&str>, done: usize, total: usize) -> bool {
+    match logic.map(str::to_ascii_uppercase).as_deref() {
+        Some("OR" | "ONE_OF" | "ANY") => done >= 1,
+        Some("NAND") => done < total,
+        Some("NOR") => done == 0,
+        Some("XOR") => done == 1,
+        Some("XNOR") => done != 1,
+        _ => done == total,
+    }
+}
+
+/// Logic names `Quest::from_raw` files every prerequisite of as optional.
+fn is_any_of(logic: Option<&str>) -> bool {
+    logic.is_some_and(|l| {
+        ["OR", "ONE_OF", "ANY", "XOR"]
+            .iter()
+            .any(|name| l.eq_ignore_ascii_case(name))
+    })
+}
+
+fn is_unlocked(quest: &Quest, done: impl Fn(&QuestId) -> bool) -> bool {
+    let logic = quest
+        .properties
+        .as_ref()
+        .and_then(|p| p.quest_logic.as_deref());
+    // the parser moves all prerequisites of an OR/XOR quest to the optional
+    // list, so the logic applies to those when nothing is required
+    let prerequisites = if quest.required_prerequisites.is_empty() && is_any_of(logic) {
+        &quest.optional_prerequisites
+    } else {
+        &quest.required_prerequisites
+    };
+    let total = prerequisites.len();
+    if total == 0 {
+        return true;
+    }
+    let completed = prerequisites.iter().filter(|p| done(p)).count();
+    logic_met(logic, completed, total)
+}
+
+/// The state of every quest of a database for one player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressOverlay {
+    /// Player UUID.
+    pub player: String,
+    states: HashMap<QuestId, QuestState>,
+}
+
+impl ProgressOverlay {
+    /// Classify every quest of `db` for `player`. Progress for quests not in
+    /// `db` is ignored.
+    pub fn build(db: &QuestDatabase, progress: &PlayerProgress, player: &str) -> Self {
+        let mut states = HashMap::with_capacity(db.quests.len());
+        for id in db.quests.keys() {
+            let state = match progress
+                .quests
+                .get(id)
+                .and_then(|p| p.completion_of(player))
+            {
+                Some(c) if c.claimed => QuestState::Claimed,
+                Some(_) => QuestState::Completed,
+                None => QuestState::Locked,
+            };
+            states.insert(*id, state);
+        }
+        let done: HashMap<QuestId, bool> =
+            states.iter().map(|(id, s)| (*id, s.is_done())).collect();
+        for (id, state) in states.iter_mut() {
+            if *state == QuestState::Locked
+                && is_unlocked(&db.quests[id], |p| done.get(p).copied().unwrap_or(false))
+            {
+                *state = QuestState::Unlocked;
+            }
+        }
+        ProgressOverlay {
+            player: player.to_string(),
+            states,
+        }
+    }
+
+    /// State of `quest`, or `None` when it is not in the database.
+    pub fn state(&self, quest: QuestId) -> Option<QuestState> {
+        self.states.get(&quest).copied()
+    }
+
+    /// Quests in `state`, sorted.
+    pub fn quests_in(&self, state: QuestState) -> Vec<QuestId> {
+        let mut ids: Vec<QuestId> = self
+            .states
+            .iter()
+            .filter(|(_, s)| **s == state)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Number of quests per state, for every state that occurs.
+    pub fn counts(&self) -> BTreeMap<QuestState, usize> {
+        let mut counts = BTreeMap::new();
+        for state in self.states.values() {
+            *counts.entry(*state).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// One overlay per player appearing in `progress`, by player UUID.
+pub fn overlays(
+    db: &QuestDatabase,
+    progress: &PlayerProgress,
+) -> BTreeMap<String, ProgressOverlay> {
+    progress
+        .players()
+        .into_iter()
+        .map(|player| {
+            (
+                player.to_string(),
+                ProgressOverlay::build(db, progress, player),
+            )
+        })
+        .collect()
+}
@@ -8,24 +8,77 @@ use std::path::Path;
 
 /// Parse a quest from a reader using serde and the raw model, then convert to the optimized model.
 pub fn parse_quest_from_reader<R: Read>(mut r: R) -> Result<Quest> {
-    let mut s = String::new();
-    r.read_to_string(&mut s)?;
-    // Parse input to a serde_json::Value so we can normalize NBT-style keys
-    // (these often include ":<type>" suffixes) before deserializing into the
-    // strongly-typed raw model. Normalization converts keys like
-    // "questIDLow:4" -> "questIDLow" and converts numeric-keyed maps into
-    // arrays where appropriate.
-    let v: Value = serde_json::from_str(&s)?;
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    quest_from_json(json_value(buf)?)
+}
+
+/// Parse a quest from an in-memory JSON document (e.g. a memory-mapped file).
+/// serde_json reads `bytes` in place; with the `simd-json` feature they are
+/// copied once, since simd-json parses a mutable buffer.
+pub fn parse_quest_from_slice(bytes: &[u8]) -> Result<Quest> {
+    // simd-json parses in place, so it needs its own copy of the input
+    #[cfg(feature = "simd-json")]
+    let v = json_value(bytes.to_vec())?;
+    #[cfg(not(feature = "simd-json"))]
+    let v: Value = serde_json::from_slice(bytes)?;
+    quest_from_json(v)
+}
+
+/// Parse JSON text into a `Value`, the entry point of every file-reading
+/// path. With the `simd-json` feature the buffer is parsed in place by
+/// simd-json; the resulting `Value` is the same either way.
+pub(crate) fn json_value(buf: Vec<u8>) -> Result<Value> {
+    #[cfg(feature = "simd-json")]
+    {
+        let mut buf = buf;
+        Ok(simd_json::serde::from_slice(&mut buf)?)
+    }
+    #[cfg(not(feature = "simd-json"))]
+    {
+        Ok(serde_json::from_slice(&buf)?)
+    }
+}
+
+pub(crate) fn quest_from_json(v: Value) -> Result<Quest> {
+    // Normalize NBT-style keys (these often include ":<type>" suffixes)
+    // before deserializing into the strongly-typed raw model. Normalization
+    // converts keys like "questIDLow:4" -> "questIDLow" and converts
+    // numeric-keyed maps into arrays where appropriate.
     let v_norm = crate::nbt_norm::normalize_value(v);
     let raw: RawQuest = serde_json::from_value(v_norm)?;
     Quest::from_raw(raw)
 }
 
+/// JSON pointer to the top-level field of quest document `v` whose value
+/// does not fit the quest model, found by deserializing each field on its
+/// own. The key is given as written, NBT type suffix included.
+pub(crate) fn failing_quest_field(v: &Value) -> Option<String> {
+    let Value::Object(map) = v else {
+        return None;
+    };
+    map.iter()
+        .find(|(key, value)| {
+            let single = serde_json::Map::from_iter([((*key).clone(), (*value).clone())]);
+            let norm = crate::nbt_norm::normalize_value(Value::Object(single));
+            serde_json::from_value::<RawQuest>(norm).is_err()
+        })
+        .map(|(key, _)| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+}
+
 pub fn parse_quest_from_file(path: &Path) -> Result<Quest> {
     let f = File::open(path)?;
     parse_quest_from_reader(f)
 }
 
+/// Parse a quest file through a read-only memory map instead of reading it
+/// into a buffer (with `simd-json` the mapped bytes are still copied, see
+/// `parse_quest_from_slice`). See `crate::mmap` for the caveats.
+#[cfg(feature = "memmap2")]
+pub fn parse_quest_from_file_mmap(path: &Path) -> Result<Quest> {
+    parse_quest_from_slice(&crate::mmap::map_file(path)?)
+}
+
 /// Deprecated: use parse_quest_from_reader or parse_quest_from_file instead.
 pub fn parse_quest_from_value(v: &Value) -> Result<Quest> {
     let raw: RawQuest = serde_json::from_value(v.clone())?;
@@ -0,0 +1,98 @@
+//! Quest database with cheap snapshots for interactive sessions.
+//!
+//! Cloning a `QuestDatabase` copies every quest, which for large packs means
+//! hundreds of megabytes. `PersistentDatabase` keeps quests and questlines in
+//! `im` persistent maps instead: a clone shares all of its nodes with the
+//! original, and editing either copy only duplicates the path to the changed
+//! entry. Taking a `Snapshot` before a bulk edit and restoring it afterwards
+//! are both constant time, whatever the size of the pack.
+//!
+//! Analyses and writers work on `QuestDatabase`; `to_database` converts back
+//! (a full copy) when one of them is needed.
+use crate::model::*;
+use crate::quest_id::QuestId;
+
+/// `QuestDatabase` with structurally shared quest and questline maps.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersistentDatabase {
+    /// Optional global settings (may be absent).
+    pub settings: Option<QuestSettings>,
+    /// Map of quests by their `QuestId`.
+    pub quests: im::HashMap<QuestId, Quest>,
+    /// Questlines keyed by their `QuestId`.
+    pub questlines: im::HashMap<QuestId, QuestLine>,
+    /// Ordering of questlines.
+    pub questline_order: im::Vector<QuestId>,
+}
+
+/// State of a `PersistentDatabase` at one point in time.
+///
+/// Taking, cloning and restoring a snapshot never copies quests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot(PersistentDatabase);
+
+impl Snapshot {
+    /// The database as it was when the snapshot was taken.
+    pub fn database(&self) -> &PersistentDatabase {
+        &self.0
+    }
+}
+
+impl PersistentDatabase {
+    /// Move the contents of `db` into persistent maps.
+    pub fn from_database(db: QuestDatabase) -> Self {
+        PersistentDatabase {
+            settings: db.settings,
+            quests: db.quests.into_iter().collect(),
+            questlines: db.questlines.into_iter().collect(),
+            questline_order: db.questline_order.into_iter().collect(),
+        }
+    }
+
+    /// A regular `QuestDatabase` with a copy of every quest and questline.
+    pub fn to_database(&self) -> QuestDatabase {
+        QuestDatabase {
+            settings: self.settings.clone(),
+            quests: self.quests.iter().map(|(id, q)| (*id, q.clone())).collect(),
+            questlines: self
+                .questlines
+                .iter()
+                .map(|(id, l)| (*id, l.clone()))
+                .collect(),
+            questline_order: self.questline_order.iter().copied().collect(),
+        }
+    }
+
+    /// Quest `id`.
+    pub fn quest(&self, id: QuestId) -> Option<&Quest> {
+        self.quests.get(&id)
+    }
+
+    /// Quest `id` for editing; copies only what this database still shares
+    /// with snapshots.
+    pub fn quest_mut(&mut self, id: QuestId) -> Option<&mut Quest> {
+        self.quests.get_mut(&id)
+    }
+
+    /// Remember the current state.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.clone())
+    }
+
+    /// Go back to the state of `snapshot`, dropping every edit made since.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        *self = snapshot.0.clone();
+    }
+}
+
+impl From<QuestDatabase> for PersistentDatabase {
+    fn from(db: QuestDatabase) -> Self {
+        PersistentDatabase::from_database(db)
+    }
+}
+
+impl From<&PersistentDatabase> for QuestDatabase {
+    fn from(db: &PersistentDatabase) -> Self {
+        db.to_database()
+    }
+}
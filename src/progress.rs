@@ -0,0 +1,358 @@
+//! Player progress parsing.
+//!
+//! BetterQuesting stores completion data in `QuestProgress.json` (one file per
+//! world, or one per player under `QuestProgress/` in newer versions). Each
+//! entry names a quest by `questIDHigh`/`questIDLow` (or a BQ 3 `questID` UUID
+//! string) and lists the players who completed it with an epoch-millisecond
+//! timestamp and whether the rewards were claimed. Keys carry NBT type suffixes and are normalized with
+//! `nbt_norm::normalize_value` before reading, like the quest files.
+//!
+//! Entries also carry per-task state: the players who finished each task
+//! (`completeUsers`) and each player's partial progress (`userProgress`,
+//! whose shape depends on the task type and is kept as JSON).
+//! `PlayerProgress::player` collects everything recorded for one player, and
+//! `unknown_quests` lists entries that no longer match a quest in the
+//! database.
+//!
+//! Timestamps are `Timestamp`s and serialize as epoch milliseconds, as
+//! stored by BetterQuesting. With the `chrono` feature a `Timestamp` holds a
+//! `chrono::DateTime<Utc>` (see `Timestamp::to_datetime`); the serialized
+//! form is the same with and without the feature.
+use crate::error::{ParseError, Result};
+use crate::model::{QuestDatabase, QuestProperties};
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// A point in time read from progress data, serialized as milliseconds since
+/// the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(
+    #[cfg(feature = "chrono")]
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    chrono::DateTime<chrono::Utc>,
+    #[cfg(not(feature = "chrono"))] i64,
+);
+
+impl Timestamp {
+    /// The timestamp `ms` milliseconds after the Unix epoch. `None` only with
+    /// the `chrono` feature, for values outside chrono's range.
+    pub fn from_millis(ms: i64) -> Option<Self> {
+        #[cfg(feature = "chrono")]
+        {
+            chrono::DateTime::from_timestamp_millis(ms).map(Timestamp)
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            Some(Timestamp(ms))
+        }
+    }
+
+    /// Milliseconds since the Unix epoch.
+    pub fn millis(self) -> i64 {
+        #[cfg(feature = "chrono")]
+        {
+            self.0.timestamp_millis()
+        }
+        #[cfg(not(feature = "chrono"))]
+        {
+            self.0
+        }
+    }
+
+    /// The timestamp as a `chrono` date.
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime(self) -> chrono::DateTime<chrono::Utc> {
+        self.0
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(date: chrono::DateTime<chrono::Utc>) -> Self {
+        Timestamp(date)
+    }
+}
+
+/// One player's completion of a quest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Completion {
+    /// Player UUID as stored by BetterQuesting.
+    pub player: String,
+    /// Completion time.
+    pub timestamp: Timestamp,
+    /// Whether the player claimed the rewards.
+    pub claimed: bool,
+}
+
+/// One player's partial progress on a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskPlayerProgress {
+    /// Player UUID.
+    pub player: String,
+    /// The stored progress fields besides the UUID, e.g. `data` with the
+    /// item counts of a retrieval task or `value` for a hunt task.
+    pub data: serde_json::Map<String, Value>,
+}
+
+/// Progress recorded for one task of a quest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    /// Index of the task inside the quest.
+    pub index: Option<usize>,
+    /// Task type, when stored (e.g. `bq_standard:retrieval`).
+    pub task_id: Option<String>,
+    /// Players who completed the task.
+    #[serde(default)]
+    pub completed_by: Vec<String>,
+    /// Partial progress, one entry per player.
+    #[serde(default)]
+    pub players: Vec<TaskPlayerProgress>,
+}
+
+/// Progress recorded for a single quest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestProgress {
+    /// The quest this progress belongs to.
+    pub quest_id: QuestId,
+    /// Completions, one per player.
+    #[serde(default)]
+    pub completed: Vec<Completion>,
+    /// Per-task progress, in file order.
+    #[serde(default)]
+    pub tasks: Vec<TaskProgress>,
+}
+
+impl QuestProgress {
+    /// The completion recorded for `player`, if any.
+    pub fn completion_of(&self, player: &str) -> Option<&Completion> {
+        self.completed.iter().find(|c| c.player == player)
+    }
+}
+
+/// Parsed contents of a progress file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerProgress {
+    /// Progress keyed by quest id.
+    pub quests: HashMap<QuestId, QuestProgress>,
+}
+
+impl PlayerProgress {
+    /// All player UUIDs appearing in the file, sorted.
+    pub fn players(&self) -> Vec<&str> {
+        let mut players: Vec<&str> = self
+            .quests
+            .values()
+            .flat_map(|q| q.completed.iter().map(|c| c.player.as_str()))
+            .collect();
+        players.sort_unstable();
+        players.dedup();
+        players
+    }
+
+    /// Everything recorded for `player`.
+    pub fn player(&self, player: &str) -> PlayerRecord {
+        let mut record = PlayerRecord {
+            player: player.to_string(),
+            ..Default::default()
+        };
+        let mut ids: Vec<&QuestId> = self.quests.keys().collect();
+        ids.sort();
+        for id in ids {
+            let quest = &self.quests[id];
+            if let Some(completion) = quest.completion_of(player) {
+                record.completed.push(*id);
+                if completion.claimed {
+                    record.claimed.push(*id);
+                }
+            }
+            for (i, task) in quest.tasks.iter().enumerate() {
+                let index = task.index.unwrap_or(i);
+                if task.completed_by.iter().any(|p| p == player) {
+                    record.completed_tasks.push((*id, index));
+                }
+                if let Some(p) = task.players.iter().find(|p| p.player == player) {
+                    record.task_progress.push((*id, index, p.data.clone()));
+                }
+            }
+        }
+        record
+    }
+
+    /// Quest ids with recorded progress that are not in `db` (quests removed
+    /// since the world was played), sorted.
+    pub fn unknown_quests(&self, db: &QuestDatabase) -> Vec<QuestId> {
+        let mut ids: Vec<QuestId> = self
+            .quests
+            .keys()
+            .filter(|id| !db.quests.contains_key(id))
+            .copied()
+            .collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// The progress of one player, in ascending quest id order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerRecord {
+    /// Player UUID.
+    pub player: String,
+    /// Quests the player completed.
+    pub completed: Vec<QuestId>,
+    /// Completed quests whose rewards the player claimed.
+    pub claimed: Vec<QuestId>,
+    /// Finished tasks as `(quest, task index)`.
+    pub completed_tasks: Vec<(QuestId, usize)>,
+    /// Partial task progress as `(quest, task index, stored fields)`.
+    pub task_progress: Vec<(QuestId, usize, serde_json::Map<String, Value>)>,
+}
+
+/// Elements of an NBT list after normalization: an array, or an empty object
+/// for lists that had no entries.
+fn list(v: Option<&Value>) -> &[Value] {
+    match v {
+        Some(Value::Array(a)) => a,
+        _ => &[],
+    }
+}
+
+fn int(v: &Value, key: &str) -> Result<Option<i64>> {
+    match v.as_object() {
+        Some(m) => crate::nbt_norm::get_int(m, key),
+        None => Ok(None),
+    }
+}
+
+/// The `Timestamp` of stored epoch milliseconds.
+fn timestamp(ms: i64) -> Result<Timestamp> {
+    Timestamp::from_millis(ms).ok_or_else(|| ParseError::NumberOutOfRange {
+        field: "timestamp".to_string(),
+        value: ms.to_string(),
+    })
+}
+
+fn parse_completion(v: &Value) -> Result<Completion> {
+    let player = v
+        .get("uuid")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ParseError::InvalidFormat("completion without uuid".to_string()))?;
+    Ok(Completion {
+        player: player.to_string(),
+        timestamp: timestamp(int(v, "timestamp")?.unwrap_or(0))?,
+        claimed: int(v, "claimed")?.unwrap_or(0) != 0,
+    })
+}
+
+/// A player UUID stored either as a plain string or as `{ "uuid": ... }`.
+fn player_uuid(v: &Value) -> Option<String> {
+    v.as_str()
+        .or_else(|| v.get("uuid").and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn parse_task_progress(v: &Value) -> Result<TaskProgress> {
+    let index = int(v, "index")?
+        .map(|i| {
+            usize::try_from(i).map_err(|_| ParseError::NumberOutOfRange {
+                field: "index".to_string(),
+                value: i.to_string(),
+            })
+        })
+        .transpose()?;
+    let players = list(v.get("userProgress"))
+        .iter()
+        .filter_map(|p| {
+            let mut data = p.as_object()?.clone();
+            let player = data.remove("uuid")?.as_str()?.to_string();
+            Some(TaskPlayerProgress { player, data })
+        })
+        .collect();
+    Ok(TaskProgress {
+        index,
+        task_id: v.get("taskID").and_then(Value::as_str).map(str::to_string),
+        completed_by: list(v.get("completeUsers"))
+            .iter()
+            .filter_map(player_uuid)
+            .collect(),
+        players,
+    })
+}
+
+/// Parse a normalized progress document.
+fn parse_progress_value(v: &Value) -> Result<PlayerProgress> {
+    let mut progress = PlayerProgress::default();
+    for entry in list(v.get("questProgress")) {
+        let quest_id = match (int(entry, "questIDHigh")?, int(entry, "questIDLow")?) {
+            (Some(high), Some(low)) => QuestId::from_parts_i64(high, low),
+            _ => match entry.get("questID") {
+                Some(id) => crate::nbt_norm::quest_id_from_value(id, "questID")?,
+                None => {
+                    return Err(ParseError::InvalidFormat(
+                        "quest progress without questIDHigh/questIDLow".to_string(),
+                    ));
+                }
+            },
+        };
+        let completed = list(entry.get("completed"))
+            .iter()
+            .map(parse_completion)
+            .collect::<Result<Vec<_>>>()?;
+        let tasks = list(entry.get("tasks"))
+            .iter()
+            .map(parse_task_progress)
+            .collect::<Result<Vec<_>>>()?;
+        let quest = progress
+            .quests
+            .entry(quest_id)
+            .or_insert_with(|| QuestProgress {
+                quest_id,
+                completed: Vec::new(),
+                tasks: Vec::new(),
+            });
+        quest.completed.extend(completed);
+        quest.tasks.extend(tasks);
+    }
+    Ok(progress)
+}
+
+/// Parse a `QuestProgress.json` document from a reader.
+pub fn parse_progress_from_reader<R: Read>(mut r: R) -> Result<PlayerProgress> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    let v = crate::parser::json_value(buf)?;
+    parse_progress_value(&crate::nbt_norm::normalize_value(v))
+}
+
+/// Parse a `QuestProgress.json` file.
+pub fn parse_progress_from_file(path: &Path) -> Result<PlayerProgress> {
+    parse_progress_from_reader(std::fs::File::open(path)?)
+}
+
+/// Milliseconds per game tick; BetterQuesting measures `repeatTime` in ticks.
+const MS_PER_TICK: i64 = 50;
+
+/// When a repeatable quest completed at `completed_at` becomes available
+/// again, or `None` if the quest does not repeat.
+///
+/// With `repeatRelative` the cooldown runs from the completion time; otherwise
+/// BetterQuesting resets on a fixed grid of `repeatTime` intervals counted
+/// from the epoch, so the quest reopens at the next grid boundary.
+pub fn next_repeat_at(props: &QuestProperties, completed_at: Timestamp) -> Option<Timestamp> {
+    let period = i64::from(props.repeat_time?);
+    if period < 0 {
+        return None;
+    }
+    let period = period * MS_PER_TICK;
+    let completed_at = completed_at.millis();
+    let at = if period == 0 || props.repeat_relative.unwrap_or(true) {
+        completed_at.checked_add(period)?
+    } else {
+        (completed_at.div_euclid(period) + 1).checked_mul(period)?
+    };
+    Timestamp::from_millis(at)
+}
@@ -0,0 +1,191 @@
+//! `.bqtproj` project files: editor and tooling state kept beside a pack.
+//!
+//! BetterQuesting only reads its own JSON, so anything the tools here need to
+//! remember about a pack (lint configuration and suppressions, quest tags, the
+//! id namespace convention, questline entries whose positions layout tools
+//! must leave alone) goes into one sidecar file next to the `DefaultQuests`
+//! folder, `DefaultQuests.bqtproj` by default (`project_path`).
+//!
+//! The file is pretty JSON with a `version` field. `Project::load` accepts
+//! every version up to `PROJECT_VERSION` and rejects newer files rather than
+//! dropping what it does not understand; top-level keys it does not know are
+//! kept and written back by `Project::save`, so tools adding sections of their
+//! own do not lose them to older ones.
+use crate::analysis::namespace::NamespaceConvention;
+use crate::error::{ParseError, Result};
+use crate::lint::{LintConfig, LintFinding, Linter};
+use crate::quest_id::QuestId;
+use crate::tags::TagMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// File extension of project files.
+pub const PROJECT_EXTENSION: &str = "bqtproj";
+
+/// Format version written by `Project::save`.
+pub const PROJECT_VERSION: u32 = 1;
+
+/// A lint finding the pack authors have accepted.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LintSuppression {
+    /// `LintRule::name` of the rule.
+    pub rule: String,
+    /// The quest or questline; `None` suppresses the rule everywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<QuestId>,
+}
+
+impl LintSuppression {
+    /// Returns true if the suppression covers `finding`.
+    pub fn matches(&self, finding: &LintFinding) -> bool {
+        self.rule == finding.rule && self.subject.is_none_or(|s| s == finding.subject)
+    }
+}
+
+/// Per-pack tooling state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    /// Format version of the file.
+    pub version: u32,
+    /// Lint rules to disable or re-rate.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub lint: LintConfig,
+    /// Accepted lint findings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressions: Vec<LintSuppression>,
+    /// Quest tags (see `tags`).
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub tags: TagMap,
+    /// Reserved id ranges (see `analysis::namespace`).
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub namespaces: NamespaceConvention,
+    /// Questline entries whose positions layout tools must keep, by
+    /// questline id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub layout_locks: BTreeMap<QuestId, BTreeSet<QuestId>>,
+    /// Top-level keys this version does not know, kept as read.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Project {
+            version: PROJECT_VERSION,
+            lint: LintConfig::default(),
+            suppressions: Vec::new(),
+            tags: TagMap::default(),
+            namespaces: NamespaceConvention::default(),
+            layout_locks: BTreeMap::new(),
+            extra: BTreeMap::new(),
+        }
+    }
+}
+
+/// The default project file of the pack at `pack_root`: a sibling named
+/// after the folder, e.g. `DefaultQuests.bqtproj`.
+pub fn project_path(pack_root: &Path) -> PathBuf {
+    let name = pack_root
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "DefaultQuests".to_string());
+    pack_root.with_file_name(format!("{}.{}", name, PROJECT_EXTENSION))
+}
+
+impl Project {
+    /// Parse a project file from a reader.
+    pub fn from_reader<R: Read>(r: R) -> Result<Self> {
+        let project: Project = serde_json::from_reader(r)?;
+        if project.version == 0 || project.version > PROJECT_VERSION {
+            return Err(ParseError::InvalidFormat(format!(
+                "unsupported project file version {} (supported: 1 to {})",
+                project.version, PROJECT_VERSION
+            )));
+        }
+        Ok(project)
+    }
+
+    /// Load a project file.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Load the project file of the pack at `pack_root`, or an empty project
+    /// when it has none.
+    pub fn load_for_pack(pack_root: &Path) -> Result<Self> {
+        match Self::load(&project_path(pack_root)) {
+            Err(ParseError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Project::default())
+            }
+            other => other,
+        }
+    }
+
+    /// Write the project as pretty JSON at the current `PROJECT_VERSION`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut project = self.clone();
+        project.version = PROJECT_VERSION;
+        std::fs::write(path, serde_json::to_string_pretty(&project)?)?;
+        Ok(())
+    }
+
+    /// Write the project file of the pack at `pack_root`.
+    pub fn save_for_pack(&self, pack_root: &Path) -> Result<()> {
+        self.save(&project_path(pack_root))
+    }
+
+    /// The built-in lint rules adjusted by `lint`, with the rule names it
+    /// mentions that do not exist (see `Linter::from_config`).
+    pub fn linter(&self) -> (Linter, Vec<String>) {
+        Linter::from_config(&self.lint)
+    }
+
+    /// `findings` without the suppressed ones.
+    pub fn unsuppressed(&self, findings: Vec<LintFinding>) -> Vec<LintFinding> {
+        findings
+            .into_iter()
+            .filter(|f| !self.suppressions.iter().any(|s| s.matches(f)))
+            .collect()
+    }
+
+    /// Suppress `rule` for `subject` (everywhere when `None`).
+    pub fn suppress(&mut self, rule: &str, subject: Option<QuestId>) {
+        let suppression = LintSuppression {
+            rule: rule.to_string(),
+            subject,
+        };
+        if !self.suppressions.contains(&suppression) {
+            self.suppressions.push(suppression);
+            self.suppressions.sort();
+        }
+    }
+
+    /// Returns true if layout tools must keep `quest`'s position in
+    /// `questline`.
+    pub fn is_layout_locked(&self, questline: QuestId, quest: QuestId) -> bool {
+        self.layout_locks
+            .get(&questline)
+            .is_some_and(|locked| locked.contains(&quest))
+    }
+
+    /// Lock or unlock `quest`'s position in `questline`.
+    pub fn set_layout_lock(&mut self, questline: QuestId, quest: QuestId, locked: bool) {
+        if locked {
+            self.layout_locks
+                .entry(questline)
+                .or_default()
+                .insert(quest);
+        } else if let Some(entries) = self.layout_locks.get_mut(&questline) {
+            entries.remove(&quest);
+            if entries.is_empty() {
+                self.layout_locks.remove(&questline);
+            }
+        }
+    }
+}
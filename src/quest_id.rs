@@ -1,43 +1,162 @@
 /// Compact representation of a BetterQuesting quest identifier.
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
 ///
-/// Historically, BetterQuesting uses two 32-bit integers (high/low) to form a 64-bit id.
-/// This type stores only a single `u64`, and provides helpers to extract or construct with high/low parts.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord, Copy)]
-pub struct QuestId(u64);
+/// BetterQuesting identifies quests by two NBT longs (`questIDHigh`/`questIDLow`).
+/// Older packs only ever use the low 32 bits of each, which historically let
+/// the id be packed into a single `u64`; newer packs (e.g. GTNH) store random
+/// 128-bit UUIDs. Both parts are kept losslessly as `i64`. `as_u64` still
+/// returns the legacy packing, and ids whose parts fit in `i32` serialize as
+/// that number so existing outputs stay unchanged; wider ids serialize as a
+/// UUID string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
+pub struct QuestId {
+    high: i64,
+    low: i64,
+}
 
 impl QuestId {
     /// New from combined u64
     pub fn from_u64(id: u64) -> Self {
-        QuestId(id)
+        QuestId {
+            high: (id >> 32) as u32 as i32 as i64,
+            low: id as u32 as i32 as i64,
+        }
     }
 
     /// New from explicit signed high/low
     pub fn from_parts(high: i32, low: i32) -> Self {
-        let hi = high as i64 as u64;
-        let lo = low as u32 as u64;
-        QuestId((hi << 32) | lo)
+        Self::from_parts_i64(high as i64, low as i64)
+    }
+
+    /// New from the full NBT long values of `questIDHigh`/`questIDLow`.
+    pub fn from_parts_i64(high: i64, low: i64) -> Self {
+        QuestId { high, low }
     }
 
-    /// The combined value as u64.
+    /// The combined value as u64, packing the low 32 bits of each part.
+    ///
+    /// Lossless only when `fits_i32` is true.
     pub fn as_u64(self) -> u64 {
-        self.0
+        ((self.high as u32 as u64) << 32) | self.low as u32 as u64
+    }
+    /// The high part as signed i32, or `None` when it does not fit.
+    pub fn high_part(self) -> Option<i32> {
+        i32::try_from(self.high).ok()
+    }
+    /// The low part as signed i32, or `None` when it does not fit.
+    pub fn low_part(self) -> Option<i32> {
+        i32::try_from(self.low).ok()
+    }
+    /// The bits of `high_part` as unsigned u32, or `None` when it does not
+    /// fit.
+    pub fn high_u32(self) -> Option<u32> {
+        self.high_part().map(i32::cast_unsigned)
+    }
+    /// The bits of `low_part` as unsigned u32, or `None` when it does not
+    /// fit.
+    pub fn low_u32(self) -> Option<u32> {
+        self.low_part().map(i32::cast_unsigned)
+    }
+    /// The full high part.
+    pub fn high_i64(self) -> i64 {
+        self.high
     }
-    /// The high part as signed i32.
-    pub fn high_part(self) -> i32 {
-        (self.0 >> 32) as u32 as i32
+    /// The full low part.
+    pub fn low_i64(self) -> i64 {
+        self.low
     }
-    /// The low part as signed i32.
-    pub fn low_part(self) -> i32 {
-        (self.0 & 0xFFFF_FFFF) as u32 as i32
+    /// Returns true if both parts fit in `i32`, i.e. the legacy `u64` packing
+    /// represents the id exactly.
+    pub fn fits_i32(self) -> bool {
+        i32::try_from(self.high).is_ok() && i32::try_from(self.low).is_ok()
     }
-    /// The high part as unsigned u32.
-    pub fn high_u32(self) -> u32 {
-        (self.0 >> 32) as u32
+    /// The id as a UUID string (high part first), as used by 128-bit ids.
+    pub fn to_uuid_string(self) -> String {
+        let bits = ((self.high as u64 as u128) << 64) | self.low as u64 as u128;
+        let hex = format!("{:032x}", bits);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
     }
-    /// The low part as unsigned u32.
-    pub fn low_u32(self) -> u32 {
-        self.0 as u32
+    /// Parse a UUID string (with or without dashes) into an id.
+    pub fn from_uuid_str(s: &str) -> Option<Self> {
+        let hex: String = s.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 {
+            return None;
+        }
+        let bits = u128::from_str_radix(&hex, 16).ok()?;
+        Some(Self::from_parts_i64(
+            (bits >> 64) as u64 as i64,
+            bits as u64 as i64,
+        ))
+    }
+}
+
+impl Ord for QuestId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Legacy ids keep their historical u64 order; the full parts only
+        // break ties between wide ids sharing the same packing.
+        (self.as_u64(), self.high, self.low).cmp(&(other.as_u64(), other.high, other.low))
+    }
+}
+
+impl PartialOrd for QuestId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for QuestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.fits_i32() {
+            write!(f, "{}", self.as_u64())
+        } else {
+            f.write_str(&self.to_uuid_string())
+        }
+    }
+}
+
+impl Serialize for QuestId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.fits_i32() {
+            serializer.serialize_u64(self.as_u64())
+        } else {
+            serializer.serialize_str(&self.to_uuid_string())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for QuestId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = QuestId;
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a packed u64 quest id or a UUID string")
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<QuestId, E> {
+                Ok(QuestId::from_u64(v))
+            }
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<QuestId, E> {
+                u64::try_from(v)
+                    .map(QuestId::from_u64)
+                    .map_err(|_| E::custom(format!("negative quest id {}", v)))
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<QuestId, E> {
+                if let Ok(n) = v.parse::<u64>() {
+                    return Ok(QuestId::from_u64(n));
+                }
+                QuestId::from_uuid_str(v)
+                    .ok_or_else(|| E::custom(format!("invalid quest id {:?}", v)))
+            }
+        }
+        deserializer.deserialize_any(Visitor)
     }
 }
 
@@ -51,10 +170,10 @@ mod tests {
         assert_eq!(qid.as_u64(), 0);
         let qid2 = QuestId::from_u64(0);
         assert_eq!(qid, qid2);
-        assert_eq!(qid2.high_part(), 0);
-        assert_eq!(qid2.low_part(), 0);
-        assert_eq!(qid2.high_u32(), 0);
-        assert_eq!(qid2.low_u32(), 0);
+        assert_eq!(qid2.high_part(), Some(0));
+        assert_eq!(qid2.low_part(), Some(0));
+        assert_eq!(qid2.high_u32(), Some(0));
+        assert_eq!(qid2.low_u32(), Some(0));
     }
 
     #[test]
@@ -63,10 +182,10 @@ mod tests {
         let u = qid.as_u64();
         let qid2 = QuestId::from_u64(u);
         assert_eq!(qid, qid2);
-        assert_eq!(qid.high_part(), -1);
-        assert_eq!(qid.low_part(), -1);
-        assert_eq!(qid.high_u32(), 0xFFFF_FFFF);
-        assert_eq!(qid.low_u32(), 0xFFFF_FFFF);
+        assert_eq!(qid.high_part(), Some(-1));
+        assert_eq!(qid.low_part(), Some(-1));
+        assert_eq!(qid.high_u32(), Some(0xFFFF_FFFF));
+        assert_eq!(qid.low_u32(), Some(0xFFFF_FFFF));
     }
 
     #[test]
@@ -77,10 +196,10 @@ mod tests {
         let u = qid.as_u64();
         let qid2 = QuestId::from_u64(u);
         assert_eq!(qid, qid2);
-        assert_eq!(qid.high_part(), i32::MAX);
-        assert_eq!(qid.low_part(), i32::MIN);
-        assert_eq!(qid.high_u32(), i32::MAX as u32);
-        assert_eq!(qid.low_u32(), i32::MIN as u32);
+        assert_eq!(qid.high_part(), Some(i32::MAX));
+        assert_eq!(qid.low_part(), Some(i32::MIN));
+        assert_eq!(qid.high_u32(), Some(i32::MAX as u32));
+        assert_eq!(qid.low_u32(), Some(i32::MIN as u32));
     }
 
     #[test]
@@ -88,7 +207,36 @@ mod tests {
         let qid = QuestId::from_parts(0x12345678, 0x9ABCDEF0u32 as i32);
         let u = qid.as_u64();
         let qid2 = QuestId::from_u64(u);
-        assert_eq!(qid2.high_u32(), 0x12345678);
-        assert_eq!(qid2.low_u32(), 0x9ABCDEF0);
+        assert_eq!(qid2.high_u32(), Some(0x12345678));
+        assert_eq!(qid2.low_u32(), Some(0x9ABCDEF0));
+    }
+
+    #[test]
+    fn questid_wide_parts_are_lossless() {
+        let qid = QuestId::from_parts_i64(0x0123_4567_89ab_cdef, -2);
+        assert!(!qid.fits_i32());
+        assert_eq!(qid.high_i64(), 0x0123_4567_89ab_cdef);
+        assert_eq!(qid.low_i64(), -2);
+        assert_eq!(qid.high_part(), None);
+        assert_eq!(qid.high_u32(), None);
+        assert_eq!(qid.low_part(), Some(-2));
+        let uuid = qid.to_uuid_string();
+        assert_eq!(uuid, "01234567-89ab-cdef-ffff-fffffffffffe");
+        assert_eq!(QuestId::from_uuid_str(&uuid), Some(qid));
+        let json = serde_json::to_value(qid).unwrap();
+        assert_eq!(json, serde_json::json!(uuid));
+        assert_eq!(serde_json::from_value::<QuestId>(json).unwrap(), qid);
+        // same legacy packing, different ids
+        assert_ne!(qid, QuestId::from_u64(qid.as_u64()));
+    }
+
+    #[test]
+    fn questid_legacy_serializes_as_number() {
+        let qid = QuestId::from_parts(1, 2);
+        assert_eq!(
+            serde_json::to_value(qid).unwrap(),
+            serde_json::json!(4294967298u64)
+        );
+        assert_eq!(serde_json::from_str::<QuestId>("4294967298").unwrap(), qid);
     }
 }
@@ -0,0 +1,116 @@
+//! Quest id compaction.
+//!
+//! Packs that have been edited for years end up with sparse ids (quests
+//! 0..40 next to 9000..9100, or UUID-derived ids). `compact_ids` renumbers
+//! every quest and questline into a contiguous block of legacy (`0:n`) ids
+//! and rewrites all references through the same remapping `merge` uses:
+//! prerequisites, questline entries, questline ids and the questline order.
+//!
+//! Quests keep their relative id order; questlines are numbered in
+//! `ordering::questline_ids` order. Ids that are
+//! referenced but not defined are skipped while allocating, so a dangling
+//! reference never ends up pointing at a different quest.
+use crate::error::Result;
+use crate::merge::{IdKind, IdRemap, remapped};
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use crate::wizard::out_of_ids;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Old to new ids of a renumbering. Every defined id is listed, including
+/// those that kept their number.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Renumbering {
+    /// Quest ids.
+    pub quests: BTreeMap<QuestId, QuestId>,
+    /// Questline ids.
+    pub questlines: BTreeMap<QuestId, QuestId>,
+}
+
+impl Renumbering {
+    /// Returns true if no id changed.
+    pub fn is_identity(&self) -> bool {
+        self.quests
+            .iter()
+            .chain(&self.questlines)
+            .all(|(old, new)| old == new)
+    }
+}
+
+/// Map `ids` in order onto `start`, `start + 1`, ... skipping `reserved`.
+/// Fails with `NumberOutOfRange` when the ids would pass `i64::MAX`.
+fn allocate(
+    ids: impl IntoIterator<Item = QuestId>,
+    start: i64,
+    reserved: &BTreeSet<QuestId>,
+) -> Result<BTreeMap<QuestId, QuestId>> {
+    let mut next = Some(start);
+    let mut out = BTreeMap::new();
+    for (i, old) in ids.into_iter().enumerate() {
+        let new = loop {
+            let low = next.ok_or_else(|| out_of_ids(start, i + 1))?;
+            next = low.checked_add(1);
+            let id = QuestId::from_parts_i64(0, low);
+            if !reserved.contains(&id) {
+                break id;
+            }
+        };
+        out.insert(old, new);
+    }
+    Ok(out)
+}
+
+fn remap(kind: IdKind, mapping: BTreeMap<QuestId, QuestId>) -> Option<IdRemap> {
+    Some(IdRemap {
+        source: 0,
+        kind,
+        first: *mapping.values().min()?,
+        last: *mapping.values().max()?,
+        mapping,
+    })
+}
+
+/// Renumber the quests and questlines of `db` into contiguous ids starting
+/// at `start`. Returns the rewritten database and the id mapping, or
+/// `NumberOutOfRange` when the ids would pass `i64::MAX`.
+pub fn compact_ids(db: &QuestDatabase, start: i64) -> Result<(QuestDatabase, Renumbering)> {
+    let quest_ids: BTreeSet<QuestId> = db.quests.keys().copied().collect();
+    let dangling: BTreeSet<QuestId> = db
+        .quests
+        .values()
+        .flat_map(|q| {
+            q.prerequisites
+                .iter()
+                .chain(&q.required_prerequisites)
+                .chain(&q.optional_prerequisites)
+        })
+        .chain(
+            db.questlines
+                .values()
+                .flat_map(|l| l.entries.iter().map(|e| &e.quest_id)),
+        )
+        .filter(|id| !quest_ids.contains(id))
+        .copied()
+        .collect();
+    let quests = allocate(quest_ids, start, &dangling)?;
+
+    let line_ids = crate::ordering::questline_ids(db);
+    let ordered_dangling: BTreeSet<QuestId> = db
+        .questline_order
+        .iter()
+        .filter(|id| !db.questlines.contains_key(id))
+        .copied()
+        .collect();
+    let questlines = allocate(line_ids, start, &ordered_dangling)?;
+
+    let remaps: Vec<IdRemap> = [
+        remap(IdKind::Quest, quests.clone()),
+        remap(IdKind::Questline, questlines.clone()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let remaps: Vec<&IdRemap> = remaps.iter().collect();
+    Ok((remapped(db, &remaps), Renumbering { quests, questlines }))
+}
@@ -0,0 +1,289 @@
+//! Comparison report between two versions of a modpack.
+//!
+//! `compare_databases` combines the structural diff (`crate::diff`), the
+//! movement of quests in the importance ranking (`crate::importance`) and the
+//! validation findings that appeared or went away (`crate::validate`) into a
+//! single `PackComparison`. With the `zip` feature, `compare_packs` reads both
+//! versions straight from their pack archives. The report serializes to JSON
+//! and renders as Markdown for release notes or review comments.
+use crate::diff::{DbDiff, PropertyChange, QuestChange, QuestRef, compare};
+use crate::error::Result;
+use crate::importance::compute_importance_scores;
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use crate::stats::DepthStats;
+use crate::text::strip_formatting;
+use crate::validate::{ValidationIssue, describe, validate};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Propagation factor used when ranking quests for the report.
+pub const IMPORTANCE_ALPHA: f64 = 0.25;
+/// Quests must move more than this many ranks to be reported.
+pub const RANK_SHIFT_THRESHOLD: usize = 10;
+
+/// Size of one pack version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackStats {
+    /// Number of quests.
+    pub quests: usize,
+    /// Number of questlines.
+    pub questlines: usize,
+    /// Prerequisite depth distribution.
+    pub depth: DepthStats,
+}
+
+impl PackStats {
+    fn of(db: &QuestDatabase) -> Self {
+        PackStats {
+            quests: db.quests.len(),
+            questlines: db.questlines.len(),
+            depth: DepthStats::of(db),
+        }
+    }
+}
+
+/// A quest that moved in the importance ranking.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportanceShift {
+    /// The quest, named as in the new version.
+    pub quest: QuestRef,
+    /// 1-based rank in the old version.
+    pub old_rank: usize,
+    /// 1-based rank in the new version.
+    pub new_rank: usize,
+}
+
+/// Everything that changed between two pack versions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackComparison {
+    /// Size of the old version.
+    pub old: PackStats,
+    /// Size of the new version.
+    pub new: PackStats,
+    /// Structural differences.
+    pub diff: DbDiff,
+    /// Quests whose importance rank moved by more than
+    /// `RANK_SHIFT_THRESHOLD`, largest movement first. `None` when either
+    /// version could not be scored (prerequisite cycle).
+    pub importance_shifts: Option<Vec<ImportanceShift>>,
+    /// Validation findings present only in the new version.
+    pub new_issues: Vec<ValidationIssue>,
+    /// Validation findings present only in the old version.
+    pub resolved_issues: Vec<ValidationIssue>,
+}
+
+fn importance_shifts(old: &QuestDatabase, new: &QuestDatabase) -> Option<Vec<ImportanceShift>> {
+    let before = compute_importance_scores(old, IMPORTANCE_ALPHA, true, true).ok()?;
+    let after = compute_importance_scores(new, IMPORTANCE_ALPHA, true, true).ok()?;
+    let shifts = crate::importance::compare(&before, &after, RANK_SHIFT_THRESHOLD)
+        .into_iter()
+        .map(|s| ImportanceShift {
+            quest: QuestRef {
+                id: s.quest,
+                name: new.quests[&s.quest]
+                    .name()
+                    .map(strip_formatting)
+                    .unwrap_or_default(),
+            },
+            old_rank: s.old_rank,
+            new_rank: s.new_rank,
+        })
+        .collect();
+    Some(shifts)
+}
+
+/// Compare two parsed versions of a pack, `old` being the earlier one.
+pub fn compare_databases(old: &QuestDatabase, new: &QuestDatabase) -> PackComparison {
+    let old_issues = validate(old);
+    let new_issues = validate(new);
+    PackComparison {
+        old: PackStats::of(old),
+        new: PackStats::of(new),
+        diff: compare(old, new),
+        importance_shifts: importance_shifts(old, new),
+        new_issues: new_issues
+            .iter()
+            .filter(|i| !old_issues.contains(i))
+            .cloned()
+            .collect(),
+        resolved_issues: old_issues
+            .iter()
+            .filter(|i| !new_issues.contains(i))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Compare two pack zip archives, `old_zip` being the earlier version.
+#[cfg(feature = "zip")]
+pub fn compare_packs(
+    old_zip: &std::path::Path,
+    new_zip: &std::path::Path,
+) -> Result<PackComparison> {
+    let old = crate::archive::parse_pack_zip(old_zip)?;
+    let new = crate::archive::parse_pack_zip(new_zip)?;
+    Ok(compare_databases(&old, &new))
+}
+
+fn quest_label(q: &QuestRef) -> String {
+    if q.name.is_empty() {
+        format!("quest {}", q.id)
+    } else {
+        format!("{} ({})", q.name, q.id)
+    }
+}
+
+fn id_list(ids: &[QuestId]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn field_list(fields: &[PropertyChange]) -> String {
+    let names: Vec<&str> = fields.iter().map(|f| f.field.as_str()).collect();
+    names.join(", ")
+}
+
+fn describe_change(change: &QuestChange) -> String {
+    match change {
+        QuestChange::Renamed { old, new } => format!("renamed from \"{}\" to \"{}\"", old, new),
+        QuestChange::DescriptionChanged => "description changed".to_string(),
+        QuestChange::PrerequisitesChanged { added, removed } => {
+            let mut parts = Vec::new();
+            if !added.is_empty() {
+                parts.push(format!("added {}", id_list(added)));
+            }
+            if !removed.is_empty() {
+                parts.push(format!("removed {}", id_list(removed)));
+            }
+            format!("prerequisites: {}", parts.join("; "))
+        }
+        QuestChange::TasksChanged { .. } => "tasks changed".to_string(),
+        QuestChange::RewardsChanged { .. } => "rewards changed".to_string(),
+        QuestChange::PropertiesChanged { fields } => {
+            format!("properties changed ({})", field_list(fields))
+        }
+    }
+}
+
+impl PackComparison {
+    /// Serialize the report as pretty JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render the report as Markdown. Empty sections are omitted.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let d = &self.diff;
+        // writing to a String cannot fail
+        let _ = writeln!(out, "# Pack comparison\n");
+        let _ = writeln!(out, "| | Old | New |\n|---|---:|---:|");
+        let _ = writeln!(
+            out,
+            "| Quests | {} | {} |",
+            self.old.quests, self.new.quests
+        );
+        let _ = writeln!(
+            out,
+            "| Questlines | {} | {} |",
+            self.old.questlines, self.new.questlines
+        );
+        let (old, new) = (self.old.depth, self.new.depth);
+        let _ = writeln!(out, "| Median depth | {} | {} |", old.median, new.median);
+        let _ = writeln!(out, "| 90th percentile depth | {} | {} |", old.p90, new.p90);
+        let _ = writeln!(out, "| Max depth | {} | {} |", old.max, new.max);
+
+        let mut section = |title: &str, lines: Vec<String>| {
+            if lines.is_empty() {
+                return;
+            }
+            let _ = writeln!(out, "\n## {}\n", title);
+            for line in lines {
+                let _ = writeln!(out, "- {}", line);
+            }
+        };
+        section(
+            "Added quests",
+            d.added_quests.iter().map(quest_label).collect(),
+        );
+        section(
+            "Removed quests",
+            d.removed_quests.iter().map(quest_label).collect(),
+        );
+        section(
+            "Changed quests",
+            d.changed_quests
+                .iter()
+                .map(|q| {
+                    let changes: Vec<String> = q.changes.iter().map(describe_change).collect();
+                    format!("{}: {}", quest_label(&q.quest), changes.join(", "))
+                })
+                .collect(),
+        );
+        section(
+            "Added questlines",
+            d.added_questlines.iter().map(quest_label).collect(),
+        );
+        section(
+            "Removed questlines",
+            d.removed_questlines.iter().map(quest_label).collect(),
+        );
+        section(
+            "Changed questlines",
+            d.changed_questlines
+                .iter()
+                .map(|l| {
+                    let mut parts = Vec::new();
+                    if let Some(old) = &l.old_name {
+                        parts.push(format!("renamed from \"{}\"", old));
+                    }
+                    if !l.added.is_empty() {
+                        parts.push(format!("added {}", id_list(&l.added)));
+                    }
+                    if !l.removed.is_empty() {
+                        parts.push(format!("removed {}", id_list(&l.removed)));
+                    }
+                    if !l.moved.is_empty() {
+                        parts.push(format!("moved {}", id_list(&l.moved)));
+                    }
+                    if !l.properties.is_empty() {
+                        parts.push(format!(
+                            "properties changed ({})",
+                            field_list(&l.properties)
+                        ));
+                    }
+                    format!("{}: {}", quest_label(&l.questline), parts.join("; "))
+                })
+                .collect(),
+        );
+        match &self.importance_shifts {
+            Some(shifts) => section(
+                "Importance shifts",
+                shifts
+                    .iter()
+                    .map(|s| {
+                        format!(
+                            "{}: rank {} -> {}",
+                            quest_label(&s.quest),
+                            s.old_rank,
+                            s.new_rank
+                        )
+                    })
+                    .collect(),
+            ),
+            None => section(
+                "Importance shifts",
+                vec!["not computed: a prerequisite cycle prevents scoring".to_string()],
+            ),
+        }
+        section("New issues", self.new_issues.iter().map(describe).collect());
+        section(
+            "Resolved issues",
+            self.resolved_issues.iter().map(describe).collect(),
+        );
+        out
+    }
+}
@@ -0,0 +1,633 @@
+//! Known BetterQuesting keys and detection of unknown ones.
+//!
+//! The parser is tolerant: keys it does not model end up in the `extra`
+//! maps (or are ignored), so a typo like `repeatTiem` silently produces a
+//! quest without a repeat time. `unknown_fields` walks the files of a
+//! DefaultQuests folder and reports every key that BetterQuesting itself
+//! would not read either, with the file and JSON path it was found at.
+//! `db::ParseOptions::deny_unknown_fields` turns such findings into a parse
+//! error, for pack CI.
+//!
+//! Keys are compared after NBT type suffixes are stripped. Tasks and rewards
+//! are only checked for the `bq_standard` types listed here, since other
+//! mods define their own fields; properties of other mods' namespaces
+//! (anything next to `betterquesting` under `properties`) are not checked.
+//!
+//! Each finding carries a `suggest`ion from the same tables, so a report
+//! reads "unknown key `autoclaim`, did you mean `autoClaim`?".
+use crate::db::{QuestDataSource, quest_file_paths};
+use crate::error::Result;
+use crate::nbt_norm::normalize_value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The kind of object a key belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldScope {
+    /// Top level of a quest file.
+    Quest,
+    /// The `betterquesting` properties of a quest.
+    QuestProperties,
+    /// An entry of a quest's `tasks`.
+    Task,
+    /// An entry of a quest's `rewards`.
+    Reward,
+    /// An item stack (task requirement, reward, icon).
+    Item,
+    /// Top level of a `QuestLine.json`.
+    Questline,
+    /// The `betterquesting` properties of a questline.
+    QuestlineProperties,
+    /// A questline entry file.
+    QuestlineEntry,
+    /// Top level of the settings file.
+    SettingsFile,
+    /// The `betterquesting` properties of the settings file.
+    Settings,
+}
+
+const QUEST: &[&str] = &[
+    "questIDHigh",
+    "questIDLow",
+    "questID",
+    "properties",
+    "tasks",
+    "rewards",
+    "preRequisites",
+    "optionalPreRequisites",
+    "preRequisiteTypes",
+];
+
+const QUEST_PROPERTIES: &[&str] = &[
+    "name",
+    "desc",
+    "icon",
+    "isMain",
+    "isSilent",
+    "autoClaim",
+    "globalShare",
+    "isGlobal",
+    "lockedProgress",
+    "repeatTime",
+    "repeat_relative",
+    "simultaneous",
+    "partySingleReward",
+    "questLogic",
+    "taskLogic",
+    "visibility",
+    "snd_complete",
+    "snd_update",
+    "bg_image",
+    "bg_size",
+    "ignoresView",
+];
+
+const ITEM: &[&str] = &[
+    "id",
+    "Count",
+    "Damage",
+    "OreDict",
+    "tag",
+    "ForgeCaps",
+    "count",
+    "damage",
+    "oredict",
+];
+
+const QUESTLINE: &[&str] = &[
+    "questLineIDHigh",
+    "questLineIDLow",
+    "questLineID",
+    "properties",
+];
+
+const QUESTLINE_PROPERTIES: &[&str] =
+    &["name", "desc", "icon", "visibility", "bg_image", "bg_size"];
+
+const QUESTLINE_ENTRY: &[&str] = &[
+    "questIDHigh",
+    "questIDLow",
+    "questID",
+    "x",
+    "y",
+    "sizeX",
+    "sizeY",
+];
+
+const SETTINGS_FILE: &[&str] = &["properties", "betterquesting", "format", "build"];
+
+const SETTINGS: &[&str] = &[
+    "version",
+    "editMode",
+    "hardcore",
+    "lockTray",
+    "livesDef",
+    "livesMax",
+    "partyEnable",
+    "pack_name",
+    "pack_version",
+    "home_image",
+    "home_anc_x",
+    "home_anc_y",
+    "home_off_x",
+    "home_off_y",
+    "skipHome",
+];
+
+const TASK_COMMON: &[&str] = &["taskID", "index"];
+
+/// Fields of the `bq_standard` task types, besides `TASK_COMMON`.
+const TASKS: &[(&str, &[&str])] = &[
+    (
+        "bq_standard:retrieval",
+        &[
+            "requiredItems",
+            "partialMatch",
+            "ignoreNBT",
+            "consume",
+            "groupDetect",
+            "autoConsume",
+        ],
+    ),
+    (
+        "bq_standard:optional_retrieval",
+        &[
+            "requiredItems",
+            "partialMatch",
+            "ignoreNBT",
+            "consume",
+            "groupDetect",
+            "autoConsume",
+        ],
+    ),
+    (
+        "bq_standard:crafting",
+        &[
+            "requiredItems",
+            "partialMatch",
+            "ignoreNBT",
+            "allowSmelt",
+            "allowCraft",
+            "allowAnvil",
+        ],
+    ),
+    (
+        "bq_standard:hunt",
+        &[
+            "target",
+            "required",
+            "ignoreNBT",
+            "subtypes",
+            "targetNBT",
+            "damageType",
+        ],
+    ),
+    (
+        "bq_standard:meeting",
+        &[
+            "target",
+            "amount",
+            "ignoreNBT",
+            "subtypes",
+            "targetNBT",
+            "range",
+        ],
+    ),
+    (
+        "bq_standard:location",
+        &[
+            "name",
+            "dimension",
+            "biome",
+            "posX",
+            "posY",
+            "posZ",
+            "range",
+            "visible",
+            "hideInfo",
+            "invert",
+            "taxiCabDist",
+            "structure",
+        ],
+    ),
+    ("bq_standard:checkbox", &[]),
+    ("bq_standard:xp", &["amount", "isLevels", "consume"]),
+    (
+        "bq_standard:fluid",
+        &[
+            "requiredFluids",
+            "consume",
+            "groupDetect",
+            "ignoreNbt",
+            "autoConsume",
+        ],
+    ),
+    (
+        "bq_standard:scoreboard",
+        &[
+            "scoreName",
+            "scoreDisp",
+            "type",
+            "target",
+            "unitConversion",
+            "unitSuffix",
+            "operation",
+        ],
+    ),
+    ("bq_standard:block_break", &["blocks"]),
+    ("bq_standard:advancement", &["advancement_id"]),
+    ("bq_standard:trigger", &["trigger", "conditions", "desc"]),
+    (
+        "bq_standard:interact_item",
+        &[
+            "item",
+            "target",
+            "allowMainHand",
+            "allowOffHand",
+            "onInteract",
+            "onHit",
+            "requiredUses",
+            "partialMatch",
+            "ignoreNBT",
+        ],
+    ),
+    (
+        "bq_standard:interact_entity",
+        &[
+            "item",
+            "target",
+            "targetNBT",
+            "ignoreItemNBT",
+            "ignoreTargetNBT",
+            "partialItemMatch",
+            "targetSubtypes",
+            "allowMainHand",
+            "allowOffHand",
+            "onInteract",
+            "onHit",
+            "requiredUses",
+        ],
+    ),
+];
+
+const REWARD_COMMON: &[&str] = &["rewardID", "index"];
+
+/// Fields of the `bq_standard` reward types, besides `REWARD_COMMON`.
+const REWARDS: &[(&str, &[&str])] = &[
+    ("bq_standard:item", &["rewards", "ignoreDisabled"]),
+    ("bq_standard:choice", &["choices"]),
+    (
+        "bq_standard:command",
+        &["command", "title", "hideCmd", "viaPlayer", "description"],
+    ),
+    ("bq_standard:xp", &["amount", "isLevels"]),
+    (
+        "bq_standard:scoreboard",
+        &["score", "type", "value", "relative"],
+    ),
+];
+
+/// Keys holding item stacks (or lists of them), per scope.
+const ITEM_KEYS: &[(FieldScope, &str)] = &[
+    (FieldScope::QuestProperties, "icon"),
+    (FieldScope::QuestlineProperties, "icon"),
+    (FieldScope::Task, "requiredItems"),
+    (FieldScope::Task, "item"),
+    (FieldScope::Reward, "rewards"),
+    (FieldScope::Reward, "choices"),
+];
+
+/// The keys BetterQuesting reads in `scope`. Tasks and rewards need their
+/// `type_id`; `None` is returned for types this table does not describe.
+/// Numbered description pages (`desc2`, `desc3`, ...) are accepted in
+/// properties besides the keys listed.
+pub fn known_fields(scope: FieldScope, type_id: Option<&str>) -> Option<Vec<&'static str>> {
+    let typed = |common: &[&'static str], table: &[(&str, &[&'static str])]| {
+        let (_, fields) = table.iter().find(|(id, _)| Some(*id) == type_id)?;
+        Some(common.iter().chain(fields.iter()).copied().collect())
+    };
+    match scope {
+        FieldScope::Quest => Some(QUEST.to_vec()),
+        FieldScope::QuestProperties => Some(QUEST_PROPERTIES.to_vec()),
+        FieldScope::Task => typed(TASK_COMMON, TASKS),
+        FieldScope::Reward => typed(REWARD_COMMON, REWARDS),
+        FieldScope::Item => Some(ITEM.to_vec()),
+        FieldScope::Questline => Some(QUESTLINE.to_vec()),
+        FieldScope::QuestlineProperties => Some(QUESTLINE_PROPERTIES.to_vec()),
+        FieldScope::QuestlineEntry => Some(QUESTLINE_ENTRY.to_vec()),
+        FieldScope::SettingsFile => Some(SETTINGS_FILE.to_vec()),
+        FieldScope::Settings => Some(SETTINGS.to_vec()),
+    }
+}
+
+/// Task or reward type ids whose fields this module knows, in table order;
+/// empty for other scopes.
+pub fn known_handlers(scope: FieldScope) -> Vec<&'static str> {
+    let table = match scope {
+        FieldScope::Task => TASKS,
+        FieldScope::Reward => REWARDS,
+        _ => return Vec::new(),
+    };
+    table.iter().map(|(id, _)| *id).collect()
+}
+
+/// Spellings that mean a known key but are not a near miss of it: the
+/// crate's own field names where they differ from BetterQuesting's.
+const ALIASES: &[(FieldScope, &str, &str)] = &[
+    (FieldScope::Reward, "items", "rewards"),
+    (FieldScope::QuestProperties, "description", "desc"),
+    (FieldScope::QuestlineProperties, "description", "desc"),
+    (FieldScope::QuestProperties, "main", "isMain"),
+    (FieldScope::QuestProperties, "silent", "isSilent"),
+];
+
+/// Edit distance between `a` and `b` (insertions, deletions and
+/// substitutions of characters).
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// `key` lowercased without underscores, so `auto_claim`, `autoclaim` and
+/// `autoClaim` compare equal.
+fn squashed(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The known key `key` was most likely meant to be, if any: an alias, a
+/// key differing only in case or underscores, or else the closest key by
+/// edit distance (case-insensitive) within a third of the key's length.
+/// Ties go to the key listed first.
+pub fn suggest(scope: FieldScope, type_id: Option<&str>, key: &str) -> Option<&'static str> {
+    if let Some((_, _, canonical)) = ALIASES
+        .iter()
+        .find(|(s, alias, _)| *s == scope && *alias == key)
+    {
+        return Some(canonical);
+    }
+    let known = known_fields(scope, type_id)?;
+    let target = squashed(key);
+    if let Some(k) = known.iter().find(|k| squashed(k) == target) {
+        return Some(k);
+    }
+    let limit = (key.chars().count() / 3).max(1);
+    known
+        .iter()
+        .map(|k| (levenshtein(&k.to_lowercase(), &key.to_lowercase()), *k))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k)
+}
+
+/// A key BetterQuesting does not read.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UnknownField {
+    /// File the key was found in.
+    pub file: String,
+    /// JSON path of the key, e.g. `tasks[0].requiredItems[1].Cont`.
+    pub path: String,
+    /// The key itself (NBT suffix stripped).
+    pub key: String,
+    /// The kind of object holding the key.
+    pub scope: FieldScope,
+    /// The known key it probably should have been (see `suggest`).
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: unknown key `{}` at {}",
+            self.file, self.key, self.path
+        )?;
+        match &self.suggestion {
+            Some(s) => write!(f, ", did you mean `{}`?", s),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Findings joined into one line, for error messages.
+pub(crate) fn describe(fields: &[UnknownField]) -> String {
+    fields
+        .iter()
+        .map(UnknownField::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Collects findings for one file.
+struct Checker<'a> {
+    file: &'a str,
+    found: Vec<UnknownField>,
+}
+
+fn child(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn is_desc_page(scope: FieldScope, key: &str) -> bool {
+    matches!(
+        scope,
+        FieldScope::QuestProperties | FieldScope::QuestlineProperties
+    ) && key
+        .strip_prefix("desc")
+        .and_then(|n| n.parse::<u32>().ok())
+        .is_some_and(|n| n >= 2)
+}
+
+impl Checker<'_> {
+    /// Check the keys of `value` (if it is an object) against `scope`, then
+    /// descend into item stacks.
+    fn object(&mut self, value: &Value, scope: FieldScope, path: &str) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        let type_key = match scope {
+            FieldScope::Task => Some("taskID"),
+            FieldScope::Reward => Some("rewardID"),
+            _ => None,
+        };
+        let type_id = type_key.and_then(|k| map.get(k)).and_then(Value::as_str);
+        let Some(known) = known_fields(scope, type_id) else {
+            return;
+        };
+        let mut keys: Vec<&String> = map.keys().collect();
+        keys.sort();
+        for key in keys {
+            if !known.contains(&key.as_str()) && !is_desc_page(scope, key) {
+                self.found.push(UnknownField {
+                    file: self.file.to_string(),
+                    path: child(path, key),
+                    key: key.clone(),
+                    scope,
+                    suggestion: suggest(scope, type_id, key).map(str::to_string),
+                });
+            }
+        }
+        for (item_scope, key) in ITEM_KEYS {
+            if *item_scope == scope
+                && let Some(items) = map.get(*key)
+            {
+                self.each(items, FieldScope::Item, &child(path, key));
+            }
+        }
+    }
+
+    /// Check `value` as one object of `scope`, or each element of a list.
+    fn each(&mut self, value: &Value, scope: FieldScope, path: &str) {
+        match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.object(item, scope, &format!("{}[{}]", path, i));
+                }
+            }
+            _ => self.object(value, scope, path),
+        }
+    }
+
+    /// Check the `betterquesting` block under `properties` of `map`.
+    fn properties(&mut self, map: &Map<String, Value>, scope: FieldScope) {
+        if let Some(props) = map.get("properties").and_then(|p| p.get("betterquesting")) {
+            self.object(props, scope, "properties.betterquesting");
+        }
+    }
+}
+
+/// Unknown keys of a quest file's JSON (NBT-tagged or plain).
+pub fn quest_unknown_fields(value: &Value, file: &str) -> Vec<UnknownField> {
+    let value = normalize_value(value.clone());
+    let mut checker = Checker {
+        file,
+        found: Vec::new(),
+    };
+    checker.object(&value, FieldScope::Quest, "");
+    if let Value::Object(map) = &value {
+        checker.properties(map, FieldScope::QuestProperties);
+        if let Some(tasks) = map.get("tasks") {
+            checker.each(tasks, FieldScope::Task, "tasks");
+        }
+        if let Some(rewards) = map.get("rewards") {
+            checker.each(rewards, FieldScope::Reward, "rewards");
+        }
+    }
+    checker.found
+}
+
+/// Unknown keys of a `QuestLine.json`.
+pub fn questline_unknown_fields(value: &Value, file: &str) -> Vec<UnknownField> {
+    let value = normalize_value(value.clone());
+    let mut checker = Checker {
+        file,
+        found: Vec::new(),
+    };
+    checker.object(&value, FieldScope::Questline, "");
+    if let Value::Object(map) = &value {
+        checker.properties(map, FieldScope::QuestlineProperties);
+    }
+    checker.found
+}
+
+/// Unknown keys of a questline entry file.
+pub fn entry_unknown_fields(value: &Value, file: &str) -> Vec<UnknownField> {
+    let mut checker = Checker {
+        file,
+        found: Vec::new(),
+    };
+    checker.object(
+        &normalize_value(value.clone()),
+        FieldScope::QuestlineEntry,
+        "",
+    );
+    checker.found
+}
+
+/// Unknown keys of the settings file, in any of the layouts the parser
+/// accepts (`properties.betterquesting`, `betterquesting`, or flat).
+pub fn settings_unknown_fields(value: &Value, file: &str) -> Vec<UnknownField> {
+    let value = normalize_value(value.clone());
+    let mut checker = Checker {
+        file,
+        found: Vec::new(),
+    };
+    let Value::Object(map) = &value else {
+        return Vec::new();
+    };
+    if map.contains_key("properties") {
+        checker.object(&value, FieldScope::SettingsFile, "");
+        checker.properties(map, FieldScope::Settings);
+    } else if let Some(props) = map.get("betterquesting") {
+        checker.object(&value, FieldScope::SettingsFile, "");
+        checker.object(props, FieldScope::Settings, "betterquesting");
+    } else {
+        let flat: Map<String, Value> = map
+            .iter()
+            .filter(|(k, _)| !SETTINGS_FILE.contains(&k.as_str()))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        checker.object(&Value::Object(flat), FieldScope::Settings, "");
+    }
+    checker.found
+}
+
+fn read_json(source: &dyn QuestDataSource, path: &str) -> Result<Value> {
+    crate::parser::json_value(source.read_to_string(path)?.into_bytes())
+}
+
+/// Every unknown key in the DefaultQuests folder at `root`, ordered by file
+/// and path.
+pub fn unknown_fields(source: &dyn QuestDataSource, root: &str) -> Result<Vec<UnknownField>> {
+    let mut found = Vec::new();
+    for name in ["QuestSettings.json", "QuestSettings"] {
+        let path = format!("{}/{}", root, name);
+        if source.is_file(&path) {
+            found.extend(settings_unknown_fields(&read_json(source, &path)?, &path));
+            break;
+        }
+    }
+    for path in quest_file_paths(source, root)? {
+        found.extend(quest_unknown_fields(&read_json(source, &path)?, &path));
+    }
+    let lines_dir = format!("{}/QuestLines", root);
+    if source.is_dir(&lines_dir) {
+        for dir_name in source.list_dir(&lines_dir)? {
+            let dir = format!("{}/{}", lines_dir, dir_name);
+            if !source.is_dir(&dir) {
+                continue;
+            }
+            for name in source.list_dir(&dir)? {
+                let path = format!("{}/{}", dir, name);
+                if !source.is_file(&path) || !name.ends_with(".json") {
+                    continue;
+                }
+                let value = read_json(source, &path)?;
+                if name == "QuestLine.json" {
+                    found.extend(questline_unknown_fields(&value, &path));
+                } else {
+                    found.extend(entry_unknown_fields(&value, &path));
+                }
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
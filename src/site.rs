@@ -0,0 +1,262 @@
+//! Static HTML site for a pack: a headless quest viewer.
+//!
+//! `build_site` renders
+//!
+//! - `index.html`: the questlines in questline order and the quests that
+//!   are on no questline,
+//! - `line-<id>.html` per questline: an inline SVG drawing of the line's
+//!   entries at their in-game positions with prerequisite arrows, followed by
+//!   the entry list,
+//! - `quest-<id>.html` per quest: description, questlines, tasks, rewards and
+//!   links to its prerequisites and dependents.
+//!
+//! Pages are self-contained (inline CSS, no scripts) and link to each other
+//! with relative paths, so the folder written by `write_site` can be served
+//! by any static host or opened from disk.
+use crate::error::Result;
+use crate::export::svg::{SvgOptions, questline_to_svg_with};
+use crate::export::{escape_html, line_name, quest_title, sorted_quests};
+use crate::graph::dependents_graph;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::{render_description, render_reward, render_task};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto;padding:0 1em}\
+svg{max-width:100%;height:auto;border:1px solid #ccc}";
+
+/// One generated file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitePage {
+    /// File name relative to the site root, e.g. `quest-12.html`.
+    pub path: String,
+    /// Complete HTML document.
+    pub html: String,
+}
+
+fn quest_path(quest: &QuestId) -> String {
+    format!("quest-{}.html", quest)
+}
+
+fn line_path(line: &QuestId) -> String {
+    format!("line-{}.html", line)
+}
+
+/// Link to a quest page, or its plain name when the quest is missing.
+fn quest_link(db: &QuestDatabase, quest: &QuestId) -> String {
+    let title = escape_html(&quest_title(db, quest));
+    if db.quests.contains_key(quest) {
+        format!("<a href=\"{}\">{}</a>", quest_path(quest), title)
+    } else {
+        title
+    }
+}
+
+fn document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        STYLE,
+        body
+    )
+}
+
+/// `<p>` paragraphs from plain text; single newlines become `<br>`.
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("<p>{}</p>\n", escape_html(p).replace('\n', "<br>")))
+        .collect()
+}
+
+fn list(heading: &str, items: &[String]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let mut out = format!("<h2>{}</h2>\n<ul>\n", heading);
+    for item in items {
+        out.push_str(&format!("<li>{}</li>\n", item));
+    }
+    out.push_str("</ul>\n");
+    out
+}
+
+/// Prerequisites of `quest` as `(id, optional)`, required ones first.
+fn prerequisites(quest: &Quest) -> Vec<(QuestId, bool)> {
+    let mut out: Vec<(QuestId, bool)> = Vec::new();
+    let all = quest
+        .required_prerequisites
+        .iter()
+        .map(|p| (*p, false))
+        .chain(quest.optional_prerequisites.iter().map(|p| (*p, true)));
+    for (p, optional) in all {
+        if !out.iter().any(|(q, _)| *q == p) {
+            out.push((p, optional));
+        }
+    }
+    out
+}
+
+/// Inline SVG of a questline, drawn by `export::svg` with every tile
+/// linking to its quest page.
+fn line_svg(db: &QuestDatabase, line: &QuestLine) -> String {
+    if line.entries.is_empty() {
+        return String::new();
+    }
+    let options = SvgOptions {
+        quest_links: true,
+        ..SvgOptions::default()
+    };
+    questline_to_svg_with(db, line, &options)
+}
+
+fn index_page(db: &QuestDatabase, lines: &[&QuestLine]) -> SitePage {
+    let mut body = String::from("<h1>Quests</h1>\n");
+    let items: Vec<String> = lines
+        .iter()
+        .map(|l| {
+            format!(
+                "<a href=\"{}\">{}</a> ({} quests)",
+                line_path(&l.id),
+                escape_html(&line_name(l)),
+                l.entries.len()
+            )
+        })
+        .collect();
+    body.push_str(&list("Questlines", &items));
+    let listed: HashSet<QuestId> = db
+        .questlines
+        .values()
+        .flat_map(|l| l.entries.iter().map(|e| e.quest_id))
+        .collect();
+    let loose: Vec<String> = sorted_quests(db)
+        .into_iter()
+        .filter(|q| !listed.contains(&q.id))
+        .map(|q| quest_link(db, &q.id))
+        .collect();
+    body.push_str(&list("Quests on no questline", &loose));
+    SitePage {
+        path: "index.html".to_string(),
+        html: document("Quests", &body),
+    }
+}
+
+fn line_page(db: &QuestDatabase, line: &QuestLine) -> SitePage {
+    let name = line_name(line);
+    let mut body = format!(
+        "<p><a href=\"index.html\">Index</a></p>\n<h1>{}</h1>\n",
+        escape_html(&name)
+    );
+    if let Some(props) = &line.properties {
+        body.push_str(&paragraphs(&render_description(props)));
+    }
+    body.push_str(&line_svg(db, line));
+    let mut seen = HashSet::new();
+    let entries: Vec<String> = line
+        .entries
+        .iter()
+        .filter(|e| seen.insert(e.quest_id))
+        .map(|e| quest_link(db, &e.quest_id))
+        .collect();
+    body.push_str(&list("Quests", &entries));
+    SitePage {
+        path: line_path(&line.id),
+        html: document(&name, &body),
+    }
+}
+
+fn quest_page(
+    db: &QuestDatabase,
+    quest: &Quest,
+    lines_of: &HashMap<QuestId, Vec<&QuestLine>>,
+    dependents: &[QuestId],
+) -> SitePage {
+    let name = quest_title(db, &quest.id);
+    let mut body = format!(
+        "<p><a href=\"index.html\">Index</a></p>\n<h1>{}</h1>\n",
+        escape_html(&name)
+    );
+    if let Some(props) = &quest.properties {
+        body.push_str(&paragraphs(&render_description(props)));
+    }
+    let lines: Vec<String> = lines_of
+        .get(&quest.id)
+        .into_iter()
+        .flatten()
+        .map(|l| {
+            format!(
+                "<a href=\"{}\">{}</a>",
+                line_path(&l.id),
+                escape_html(&line_name(l))
+            )
+        })
+        .collect();
+    body.push_str(&list("Questlines", &lines));
+    let requires: Vec<String> = prerequisites(quest)
+        .into_iter()
+        .map(|(p, optional)| {
+            let link = quest_link(db, &p);
+            if optional {
+                format!("{} (optional)", link)
+            } else {
+                link
+            }
+        })
+        .collect();
+    body.push_str(&list("Requires", &requires));
+    let unlocks: Vec<String> = dependents.iter().map(|d| quest_link(db, d)).collect();
+    body.push_str(&list("Unlocks", &unlocks));
+    let tasks: Vec<String> = quest
+        .tasks
+        .iter()
+        .map(|t| escape_html(&render_task(t)))
+        .collect();
+    body.push_str(&list("Tasks", &tasks));
+    let rewards: Vec<String> = quest
+        .rewards
+        .iter()
+        .map(|r| escape_html(&render_reward(r)))
+        .collect();
+    body.push_str(&list("Rewards", &rewards));
+    SitePage {
+        path: quest_path(&quest.id),
+        html: document(&name, &body),
+    }
+}
+
+/// Render the whole site: the index, then questline pages in
+/// questline order, then quest pages in ascending id order.
+pub fn build_site(db: &QuestDatabase) -> Vec<SitePage> {
+    let lines = sorted_questlines(db);
+    let mut lines_of: HashMap<QuestId, Vec<&QuestLine>> = HashMap::new();
+    for line in &lines {
+        for entry in &line.entries {
+            let of = lines_of.entry(entry.quest_id).or_default();
+            if !of.iter().any(|l| l.id == line.id) {
+                of.push(line);
+            }
+        }
+    }
+    let dependents = dependents_graph(db);
+
+    let mut pages = vec![index_page(db, &lines)];
+    pages.extend(lines.iter().map(|l| line_page(db, l)));
+    pages.extend(sorted_quests(db).into_iter().map(|q| {
+        let deps: Vec<QuestId> = dependents[&q.id].iter().copied().collect();
+        quest_page(db, q, &lines_of, &deps)
+    }));
+    pages
+}
+
+/// Write the site to `dir`, creating it if needed.
+pub fn write_site(db: &QuestDatabase, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for page in build_site(db) {
+        std::fs::write(dir.join(&page.path), page.html)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Dependency depth statistics.
+//!
+//! The depth of a quest is the number of prerequisite steps on the longest
+//! chain leading to it: quests without (present) prerequisites have depth 0,
+//! a quest requiring only those has depth 1, and so on. Quests on a
+//! prerequisite cycle share one depth, since the cycle is measured as a
+//! single node of `graph::condensation`. The histogram and percentiles
+//! quantify how "long" a pack is and compare well across versions.
+//! `quest_depth_ranges` adds the depth along the shortest chain, for tiering
+//! rewards by how soon a quest can be reached.
+use crate::graph::condensation;
+use crate::model::QuestDatabase;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Shortest and longest prerequisite chain leading to a quest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthRange {
+    /// Steps on the shortest chain from a quest without prerequisites.
+    pub min: usize,
+    /// Steps on the longest chain; the quest's depth.
+    pub max: usize,
+}
+
+/// Depth of every quest in `db`.
+pub fn quest_depths(db: &QuestDatabase) -> HashMap<QuestId, usize> {
+    quest_depth_ranges(db)
+        .into_iter()
+        .map(|(q, range)| (q, range.max))
+        .collect()
+}
+
+/// Minimum and maximum depth of every quest in `db`. The minimum counts the
+/// steps on the shortest prerequisite chain from a root (a quest, or a cycle,
+/// without present prerequisites), so it tells how early a quest can be
+/// reached, where the maximum tells how much of the pack lies before it.
+pub fn quest_depth_ranges(db: &QuestDatabase) -> HashMap<QuestId, DepthRange> {
+    let graph = condensation(db);
+    let n = graph.components.len();
+    let mut pending = vec![0usize; n];
+    let mut succs: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(from, to) in &graph.edges {
+        succs[from].push(to);
+        pending[to] += 1;
+    }
+    // Shortest and longest path in the condensation DAG, in topological
+    // order; `min` stays `None` until a predecessor has been seen.
+    let mut min: Vec<Option<usize>> = (0..n).map(|c| (pending[c] == 0).then_some(0)).collect();
+    let mut max = vec![0usize; n];
+    let mut ready: Vec<usize> = (0..n).filter(|c| pending[*c] == 0).collect();
+    while let Some(c) = ready.pop() {
+        let below = min[c].unwrap_or(0) + 1;
+        for &s in &succs[c] {
+            min[s] = Some(min[s].map_or(below, |m| m.min(below)));
+            max[s] = max[s].max(max[c] + 1);
+            pending[s] -= 1;
+            if pending[s] == 0 {
+                ready.push(s);
+            }
+        }
+    }
+    graph
+        .components
+        .iter()
+        .zip(min.into_iter().zip(max))
+        .flat_map(|(comp, (min, max))| {
+            let range = DepthRange {
+                min: min.unwrap_or(0),
+                max,
+            };
+            comp.members.iter().map(move |q| (*q, range))
+        })
+        .collect()
+}
+
+/// Number of quests at each depth: `histogram[d]` counts the quests of depth
+/// `d`. Empty for an empty database.
+pub fn depth_histogram(db: &QuestDatabase) -> Vec<usize> {
+    let depths = quest_depths(db);
+    let mut histogram = vec![0; depths.values().max().map_or(0, |m| m + 1)];
+    for d in depths.values() {
+        histogram[*d] += 1;
+    }
+    histogram
+}
+
+/// Smallest depth at or below which at least `percentile` percent (0..=100)
+/// of the quests in `histogram` lie (nearest-rank method). 0 for an empty
+/// histogram.
+pub fn depth_percentile(histogram: &[usize], percentile: f64) -> usize {
+    let total: usize = histogram.iter().sum();
+    if total == 0 {
+        return 0;
+    }
+    let rank = ((percentile.clamp(0.0, 100.0) / 100.0 * total as f64).ceil() as usize).max(1);
+    let mut seen = 0;
+    for (depth, count) in histogram.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return depth;
+        }
+    }
+    histogram.len() - 1
+}
+
+/// Summary of the depth distribution of a pack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepthStats {
+    /// Median quest depth.
+    pub median: usize,
+    /// 90th percentile quest depth.
+    pub p90: usize,
+    /// Depth of the deepest quest.
+    pub max: usize,
+}
+
+impl DepthStats {
+    /// Depth statistics of `db` (all zero when it has no quests).
+    pub fn of(db: &QuestDatabase) -> Self {
+        Self::from_histogram(&depth_histogram(db))
+    }
+
+    /// Depth statistics of a `depth_histogram`.
+    pub fn from_histogram(histogram: &[usize]) -> Self {
+        DepthStats {
+            median: depth_percentile(histogram, 50.0),
+            p90: depth_percentile(histogram, 90.0),
+            max: histogram.len().saturating_sub(1),
+        }
+    }
+}
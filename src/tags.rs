@@ -0,0 +1,204 @@
+//! Free-form quest tags ("magic", "tech", "optional", ...).
+//!
+//! BetterQuesting has no notion of categories beyond questlines, so tags live
+//! either in a sidecar JSON file next to the pack (`{"<quest id>": ["tag",
+//! ...]}`) or as a marker list stored under `TAGS_EXTRA_KEY` in each quest's
+//! `properties.extra`. `TagMap` reads and writes both.
+//!
+//! `TagMap::filter_db` builds a tag-filtered copy of a database; every
+//! exporter and analysis accepts a `QuestDatabase`, so the filtered copy is
+//! the view to hand them.
+use crate::error::Result;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Read;
+use std::path::Path;
+
+/// Key in `QuestProperties::extra` holding a quest's tag list.
+pub const TAGS_EXTRA_KEY: &str = "bqtTags";
+
+/// Tags assigned to quests.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagMap {
+    tags: BTreeMap<QuestId, BTreeSet<String>>,
+}
+
+/// Which quests a tag-filtered view keeps.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagFilter {
+    /// The quest must carry every one of these tags.
+    #[serde(default)]
+    pub all_of: Vec<String>,
+    /// The quest must carry at least one of these tags (ignored when empty).
+    #[serde(default)]
+    pub any_of: Vec<String>,
+    /// The quest must carry none of these tags.
+    #[serde(default)]
+    pub none_of: Vec<String>,
+}
+
+impl TagFilter {
+    /// Returns true if a quest with `tags` passes the filter.
+    pub fn matches(&self, tags: &BTreeSet<String>) -> bool {
+        self.all_of.iter().all(|t| tags.contains(t))
+            && (self.any_of.is_empty() || self.any_of.iter().any(|t| tags.contains(t)))
+            && !self.none_of.iter().any(|t| tags.contains(t))
+    }
+}
+
+impl TagMap {
+    /// Parse a sidecar tags file from a reader.
+    pub fn from_reader<R: Read>(r: R) -> Result<Self> {
+        Ok(serde_json::from_reader(r)?)
+    }
+
+    /// Load a sidecar tags file.
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Write the tags as a sidecar file (pretty JSON, quests in id order).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Collect the tag markers stored in the quests' `properties.extra`.
+    /// Non-string entries of a marker list are ignored.
+    pub fn from_extra(db: &QuestDatabase) -> Self {
+        let mut map = TagMap::default();
+        for (qid, quest) in &db.quests {
+            let list = quest
+                .properties
+                .as_ref()
+                .and_then(|p| p.extra.get(TAGS_EXTRA_KEY))
+                .and_then(|v| v.as_array());
+            for tag in list.into_iter().flatten().filter_map(|v| v.as_str()) {
+                map.add(*qid, tag);
+            }
+        }
+        map
+    }
+
+    /// Store each tagged quest's tags as a marker list in its
+    /// `properties.extra`, replacing existing markers. Quests without
+    /// properties are skipped; untagged quests lose their marker.
+    pub fn write_extra(&self, db: &mut QuestDatabase) {
+        for (qid, quest) in db.quests.iter_mut() {
+            let Some(props) = quest.properties.as_mut() else {
+                continue;
+            };
+            match self.tags.get(qid) {
+                Some(tags) if !tags.is_empty() => {
+                    props
+                        .extra
+                        .insert(TAGS_EXTRA_KEY.to_string(), serde_json::json!(tags));
+                }
+                _ => {
+                    props.extra.remove(TAGS_EXTRA_KEY);
+                }
+            }
+        }
+    }
+
+    /// Add `tag` to `quest`.
+    pub fn add(&mut self, quest: QuestId, tag: &str) {
+        self.tags.entry(quest).or_default().insert(tag.to_string());
+    }
+
+    /// Remove `tag` from `quest`; returns true if it was present.
+    pub fn remove(&mut self, quest: QuestId, tag: &str) -> bool {
+        let Some(tags) = self.tags.get_mut(&quest) else {
+            return false;
+        };
+        let removed = tags.remove(tag);
+        if tags.is_empty() {
+            self.tags.remove(&quest);
+        }
+        removed
+    }
+
+    /// Add every tag of `other`.
+    pub fn merge(&mut self, other: &TagMap) {
+        for (qid, tags) in &other.tags {
+            self.tags
+                .entry(*qid)
+                .or_default()
+                .extend(tags.iter().cloned());
+        }
+    }
+
+    /// Tags of `quest` (empty when untagged).
+    pub fn tags_of(&self, quest: QuestId) -> BTreeSet<String> {
+        self.tags.get(&quest).cloned().unwrap_or_default()
+    }
+
+    /// Quests carrying `tag`, ascending.
+    pub fn quests_with(&self, tag: &str) -> Vec<QuestId> {
+        self.tags
+            .iter()
+            .filter(|(_, tags)| tags.contains(tag))
+            .map(|(qid, _)| *qid)
+            .collect()
+    }
+
+    /// Quests of `db` passing `filter`, ascending.
+    pub fn select(&self, db: &QuestDatabase, filter: &TagFilter) -> Vec<QuestId> {
+        let mut ids: Vec<QuestId> = db
+            .quests
+            .keys()
+            .filter(|q| filter.matches(&self.tags_of(**q)))
+            .copied()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Number of quests of `db` carrying each tag.
+    pub fn counts(&self, db: &QuestDatabase) -> BTreeMap<String, usize> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for (qid, tags) in &self.tags {
+            if db.quests.contains_key(qid) {
+                for tag in tags {
+                    *counts.entry(tag.clone()).or_default() += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Copy of `db` restricted to quests passing `filter`.
+    ///
+    /// Questline entries pointing at removed quests are dropped, as are
+    /// questlines left empty. Prerequisite lists are kept verbatim, so they may
+    /// name quests outside the view.
+    pub fn filter_db(&self, db: &QuestDatabase, filter: &TagFilter) -> QuestDatabase {
+        let keep: BTreeSet<QuestId> = self.select(db, filter).into_iter().collect();
+        let quests = db
+            .quests
+            .iter()
+            .filter(|(qid, _)| keep.contains(qid))
+            .map(|(qid, q)| (*qid, q.clone()))
+            .collect();
+        let mut questlines = db.questlines.clone();
+        for line in questlines.values_mut() {
+            line.entries.retain(|e| keep.contains(&e.quest_id));
+        }
+        questlines.retain(|_, line| !line.entries.is_empty());
+        let questline_order = db
+            .questline_order
+            .iter()
+            .filter(|lid| questlines.contains_key(lid))
+            .copied()
+            .collect();
+        QuestDatabase {
+            settings: db.settings.clone(),
+            quests,
+            questlines,
+            questline_order,
+        }
+    }
+}
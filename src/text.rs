@@ -0,0 +1,457 @@
+//! Plain-text rendering of quest strings.
+//!
+//! BetterQuesting names and descriptions embed Minecraft formatting codes
+//! (`§` followed by one character, e.g. `§b§l`). Exporters that target plain
+//! text strip those codes and tidy whitespace through the helpers here so
+//! every output format renders quest text the same way. Exporters that can
+//! show styling use `parse_formatted` instead, which splits a string into
+//! `StyledSpan`s carrying color and bold/italic/... attributes.
+//!
+//! Descriptions may also reference keybinds and translation keys, written as
+//! `%key.inventory%` or `translate:item.apple.name`. `TokenExpander` replaces
+//! known tokens with display text; `render_description` expands the vanilla
+//! keybinds, `render_description_with` takes a custom mapping.
+use crate::model::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Remove Minecraft formatting codes (`§` plus the following character).
+pub fn strip_formatting(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// One of the sixteen Minecraft text colors (`§0`..`§f`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextColor {
+    /// `§0`.
+    Black,
+    /// `§1`.
+    DarkBlue,
+    /// `§2`.
+    DarkGreen,
+    /// `§3`.
+    DarkAqua,
+    /// `§4`.
+    DarkRed,
+    /// `§5`.
+    DarkPurple,
+    /// `§6`.
+    Gold,
+    /// `§7`.
+    Gray,
+    /// `§8`.
+    DarkGray,
+    /// `§9`.
+    Blue,
+    /// `§a`.
+    Green,
+    /// `§b`.
+    Aqua,
+    /// `§c`.
+    Red,
+    /// `§d`.
+    LightPurple,
+    /// `§e`.
+    Yellow,
+    /// `§f`.
+    White,
+}
+
+impl TextColor {
+    const ALL: [TextColor; 16] = [
+        TextColor::Black,
+        TextColor::DarkBlue,
+        TextColor::DarkGreen,
+        TextColor::DarkAqua,
+        TextColor::DarkRed,
+        TextColor::DarkPurple,
+        TextColor::Gold,
+        TextColor::Gray,
+        TextColor::DarkGray,
+        TextColor::Blue,
+        TextColor::Green,
+        TextColor::Aqua,
+        TextColor::Red,
+        TextColor::LightPurple,
+        TextColor::Yellow,
+        TextColor::White,
+    ];
+
+    /// The color selected by a format code character (case-insensitive).
+    pub fn from_code(code: char) -> Option<Self> {
+        code.to_digit(16).map(|d| Self::ALL[d as usize])
+    }
+
+    /// The format code character, `0`..`9` or `a`..`f`.
+    pub fn code(self) -> char {
+        let index = Self::ALL.iter().position(|c| *c == self).unwrap_or(0);
+        char::from_digit(index as u32, 16).unwrap_or('0')
+    }
+
+    /// Foreground color as `#rrggbb`, as the game draws it.
+    pub fn hex(self) -> &'static str {
+        match self {
+            TextColor::Black => "#000000",
+            TextColor::DarkBlue => "#0000AA",
+            TextColor::DarkGreen => "#00AA00",
+            TextColor::DarkAqua => "#00AAAA",
+            TextColor::DarkRed => "#AA0000",
+            TextColor::DarkPurple => "#AA00AA",
+            TextColor::Gold => "#FFAA00",
+            TextColor::Gray => "#AAAAAA",
+            TextColor::DarkGray => "#555555",
+            TextColor::Blue => "#5555FF",
+            TextColor::Green => "#55FF55",
+            TextColor::Aqua => "#55FFFF",
+            TextColor::Red => "#FF5555",
+            TextColor::LightPurple => "#FF55FF",
+            TextColor::Yellow => "#FFFF55",
+            TextColor::White => "#FFFFFF",
+        }
+    }
+}
+
+/// Text attributes in effect for a span. The default is unformatted text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextStyle {
+    /// `None` means the renderer's default color.
+    pub color: Option<TextColor>,
+    /// `§l`.
+    pub bold: bool,
+    /// `§o`.
+    pub italic: bool,
+    /// `§n`.
+    pub underlined: bool,
+    /// `§m`.
+    pub strikethrough: bool,
+    /// `§k`: the game cycles random characters in place of the text.
+    pub obfuscated: bool,
+}
+
+impl TextStyle {
+    /// True when no attribute is set.
+    pub fn is_plain(&self) -> bool {
+        *self == TextStyle::default()
+    }
+}
+
+/// A run of text sharing one style.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyledSpan {
+    /// The text, formatting codes removed.
+    pub text: String,
+    /// Attributes applying to all of `text`.
+    pub style: TextStyle,
+}
+
+/// Split `text` into styled spans, applying formatting codes the way the
+/// game does: a color code sets the color and clears the other attributes,
+/// `§k`/`§l`/`§m`/`§n`/`§o` add an attribute and `§r` resets everything.
+/// Codes are case-insensitive; unknown codes (and a trailing `§`) are
+/// dropped. Adjacent text with the same style is merged and empty spans are
+/// left out, so the spans' texts concatenate to `strip_formatting(text)`.
+pub fn parse_formatted(text: &str) -> Vec<StyledSpan> {
+    let mut spans: Vec<StyledSpan> = Vec::new();
+    let mut style = TextStyle::default();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            let Some(code) = chars.next() else {
+                break;
+            };
+            if let Some(color) = TextColor::from_code(code) {
+                style = TextStyle {
+                    color: Some(color),
+                    ..TextStyle::default()
+                };
+            } else {
+                match code.to_ascii_lowercase() {
+                    'k' => style.obfuscated = true,
+                    'l' => style.bold = true,
+                    'm' => style.strikethrough = true,
+                    'n' => style.underlined = true,
+                    'o' => style.italic = true,
+                    'r' => style = TextStyle::default(),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.text.push(c),
+            _ => spans.push(StyledSpan {
+                text: c.to_string(),
+                style,
+            }),
+        }
+    }
+    spans
+}
+
+/// Plain text of `spans`, without any styling.
+pub fn strip(spans: &[StyledSpan]) -> String {
+    spans.iter().map(|s| s.text.as_str()).collect()
+}
+
+/// Plain-text quest name with formatting codes removed.
+pub fn render_name(quest: &Quest) -> String {
+    strip_formatting(quest.name().unwrap_or_default())
+}
+
+/// Default key names of the vanilla keybinds, by translation key.
+const VANILLA_KEYBINDS: &[(&str, &str)] = &[
+    ("key.advancements", "L"),
+    ("key.attack", "Left Button"),
+    ("key.back", "S"),
+    ("key.chat", "T"),
+    ("key.command", "/"),
+    ("key.drop", "Q"),
+    ("key.forward", "W"),
+    ("key.fullscreen", "F11"),
+    ("key.hotbar.1", "1"),
+    ("key.hotbar.2", "2"),
+    ("key.hotbar.3", "3"),
+    ("key.hotbar.4", "4"),
+    ("key.hotbar.5", "5"),
+    ("key.hotbar.6", "6"),
+    ("key.hotbar.7", "7"),
+    ("key.hotbar.8", "8"),
+    ("key.hotbar.9", "9"),
+    ("key.inventory", "E"),
+    ("key.jump", "Space"),
+    ("key.left", "A"),
+    ("key.pickItem", "Middle Button"),
+    ("key.playerlist", "Tab"),
+    ("key.right", "D"),
+    ("key.screenshot", "F2"),
+    ("key.sneak", "Left Shift"),
+    ("key.sprint", "Left Control"),
+    ("key.swapHands", "F"),
+    ("key.togglePerspective", "F5"),
+    ("key.use", "Right Button"),
+];
+
+/// Replaces keybind and translation tokens in quest text.
+///
+/// Two token forms are recognized: `%name%` and `translate:name`, where a
+/// name consists of ASCII letters, digits, `.`, `_` and `-` (a trailing `.`
+/// after `translate:` ends the sentence, not the name). Tokens without
+/// a mapping are left untouched, so a stray `%` sign is harmless.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenExpander {
+    tokens: HashMap<String, String>,
+}
+
+impl Default for TokenExpander {
+    /// An expander knowing the vanilla keybinds with their default keys.
+    fn default() -> Self {
+        let mut expander = TokenExpander::empty();
+        for (token, text) in VANILLA_KEYBINDS {
+            expander.insert(*token, *text);
+        }
+        expander
+    }
+}
+
+impl TokenExpander {
+    /// An expander without any mapping.
+    pub fn empty() -> Self {
+        TokenExpander {
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Map `token` (e.g. `key.inventory`) to `text`, replacing an earlier
+    /// mapping.
+    pub fn insert(&mut self, token: impl Into<String>, text: impl Into<String>) {
+        self.tokens.insert(token.into(), text.into());
+    }
+
+    /// Builder form of `insert`.
+    pub fn with(mut self, token: impl Into<String>, text: impl Into<String>) -> Self {
+        self.insert(token, text);
+        self
+    }
+
+    /// Text `token` expands to, if it is mapped.
+    pub fn get(&self, token: &str) -> Option<&str> {
+        self.tokens.get(token).map(String::as_str)
+    }
+
+    /// `text` with every mapped token replaced.
+    pub fn expand(&self, text: &str) -> String {
+        fn name_len(s: &str) -> usize {
+            s.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')))
+                .unwrap_or(s.len())
+        }
+        const TRANSLATE: &str = "translate:";
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(pos) = rest.find(['%', 't']) {
+            out.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+            let (skip, name) = if let Some(after) = rest.strip_prefix('%') {
+                let len = name_len(after);
+                if len > 0 && after[len..].starts_with('%') {
+                    (len + 2, &after[..len])
+                } else {
+                    (0, "")
+                }
+            } else if let Some(after) = rest.strip_prefix(TRANSLATE) {
+                // a name ends before sentence punctuation
+                let len = after[..name_len(after)].trim_end_matches('.').len();
+                (TRANSLATE.len() + len, &after[..len])
+            } else {
+                (0, "")
+            };
+            match self.get(name) {
+                Some(value) if skip > 0 => {
+                    out.push_str(value);
+                    rest = &rest[skip..];
+                }
+                _ => {
+                    out.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Plain-text description with formatting codes removed, vanilla keybind
+/// tokens expanded and trailing whitespace trimmed. Paginated descriptions
+/// are joined with blank lines. Returns an empty string when there is no
+/// description.
+pub fn render_description(props: &impl DescriptionPages) -> String {
+    render_description_with(props, &TokenExpander::default())
+}
+
+/// `render_description` with the tokens of `expander`.
+pub fn render_description_with(props: &impl DescriptionPages, expander: &TokenExpander) -> String {
+    render_pages_with(props, expander).join("\n\n")
+}
+
+/// Plain-text description pages, rendered like `render_description`. Empty
+/// pages are dropped.
+pub fn render_pages(props: &impl DescriptionPages) -> Vec<String> {
+    render_pages_with(props, &TokenExpander::default())
+}
+
+/// `render_pages` with the tokens of `expander`.
+pub fn render_pages_with(props: &impl DescriptionPages, expander: &TokenExpander) -> Vec<String> {
+    props
+        .description_pages()
+        .into_iter()
+        .map(|d| strip_formatting(&expander.expand(d)).trim_end().to_string())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// One-line summary of a task, e.g. `bq_standard:retrieval: 4x minecraft:bread`.
+pub fn render_task(task: &Task) -> String {
+    if task.required_items.is_empty() {
+        return task.task_id.clone();
+    }
+    let items: Vec<String> = task
+        .required_items
+        .iter()
+        .map(ItemStack::to_string)
+        .collect();
+    format!("{}: {}", task.task_id, items.join(", "))
+}
+
+/// One-line summary of a reward: its items, its choices, or its handler id,
+/// e.g. `4x minecraft:bread` or `choice of 1x a | 1x b`.
+pub fn render_reward(reward: &Reward) -> String {
+    if !reward.items.is_empty() {
+        let items: Vec<String> = reward.items.iter().map(ItemStack::to_string).collect();
+        items.join(", ")
+    } else if !reward.choices.is_empty() {
+        let choices: Vec<String> = reward.choices.iter().map(ItemStack::to_string).collect();
+        format!("choice of {}", choices.join(" | "))
+    } else {
+        reward.reward_id.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_codes() {
+        assert_eq!(strip_formatting("§b§lBold§r text"), "Bold text");
+        assert_eq!(strip_formatting("no codes"), "no codes");
+        // a dangling marker at the end is dropped
+        assert_eq!(strip_formatting("end§"), "end");
+    }
+
+    #[test]
+    fn parses_styled_spans() {
+        let spans = parse_formatted("§6§lGold bold§r plain §oitalic §cred§Kx§");
+        let texts: Vec<&str> = spans.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["Gold bold", " plain ", "italic ", "red", "x"]);
+        assert_eq!(spans[0].style.color, Some(TextColor::Gold));
+        assert!(spans[0].style.bold);
+        assert!(spans[1].style.is_plain());
+        assert!(spans[2].style.italic);
+        // a color code clears bold/italic/...
+        assert_eq!(
+            spans[3].style,
+            TextStyle {
+                color: Some(TextColor::Red),
+                ..TextStyle::default()
+            }
+        );
+        assert!(spans[4].style.obfuscated);
+        assert_eq!(spans[4].style.color, Some(TextColor::Red));
+
+        let source = "§b§lBold§r text§zodd";
+        assert_eq!(strip(&parse_formatted(source)), strip_formatting(source));
+        assert_eq!(TextColor::from_code('A'), Some(TextColor::Green));
+        assert_eq!(TextColor::LightPurple.code(), 'd');
+        assert_eq!(TextColor::Gold.hex(), "#FFAA00");
+    }
+
+    #[test]
+    fn expands_known_tokens() {
+        let expander = TokenExpander::default().with("item.apple.name", "Apple");
+        assert_eq!(
+            expander.expand("Press %key.inventory% to eat a translate:item.apple.name."),
+            "Press E to eat a Apple."
+        );
+        // unknown tokens and percent signs stay as they are
+        assert_eq!(
+            expander.expand("50% of %key.unknown% or translate:nope, 100%"),
+            "50% of %key.unknown% or translate:nope, 100%"
+        );
+        assert_eq!(
+            TokenExpander::empty().expand("%key.inventory%"),
+            "%key.inventory%"
+        );
+    }
+
+    #[test]
+    fn descriptions_expand_vanilla_keybinds() {
+        let props: QuestProperties = serde_json::from_value(serde_json::json!({
+            "name": "Keys",
+            "desc": "§ePress %key.jump% to jump",
+        }))
+        .unwrap();
+        assert_eq!(render_description(&props), "Press Space to jump");
+        let custom = TokenExpander::empty().with("key.jump", "Up");
+        assert_eq!(render_description_with(&props, &custom), "Press Up to jump");
+    }
+}
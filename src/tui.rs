@@ -0,0 +1,477 @@
+//! Interactive terminal explorer for a parsed pack (`tui` feature).
+//!
+//! `run` takes over the terminal with a ratatui view: the questline tree on
+//! the left, details of the selected quest or questline on the right. Keys:
+//!
+//! - `↑`/`↓` (`k`/`j`), `PgUp`/`PgDn`, `Home`/`End`: move the selection
+//! - `Enter`/`Space`/`→`/`←`: expand or collapse a questline
+//! - `1`-`9`: jump to the n-th prerequisite listed in the details
+//! - `Backspace`/`b`: jump back
+//! - `/`: search quest names, `n`: next match
+//! - `q`/`Esc`: quit
+//!
+//! The navigation state lives in `Explorer`, which does not touch the
+//! terminal, so it can be driven key by key and rendered onto any ratatui
+//! backend (e.g. `TestBackend`).
+use crate::error::Result;
+use crate::model::*;
+use crate::ordering::sorted_questlines;
+use crate::quest_id::QuestId;
+use crate::text::{render_description, render_name, render_reward, render_task, strip_formatting};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeSet, HashSet};
+
+/// Rows moved by `PgUp`/`PgDn`.
+const PAGE: isize = 10;
+
+/// A row of the questline tree. `None` stands for the group of quests that
+/// are on no questline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeRow {
+    /// A questline header.
+    Line(Option<QuestId>),
+    /// A quest listed under a questline.
+    Quest(Option<QuestId>, QuestId),
+}
+
+/// Navigation state of the explorer.
+#[derive(Debug)]
+pub struct Explorer<'a> {
+    db: &'a QuestDatabase,
+    /// Questlines in `ordering::questline_ids` order with their quests in entry order,
+    /// then the unlisted quests by id.
+    groups: Vec<(Option<QuestId>, Vec<QuestId>)>,
+    expanded: HashSet<Option<QuestId>>,
+    rows: Vec<TreeRow>,
+    selected: usize,
+    history: Vec<TreeRow>,
+    /// Query being typed after `/`.
+    input: Option<String>,
+    query: String,
+    status: String,
+}
+
+/// All prerequisites of `quest`, ascending, flagged when optional.
+fn prerequisites(quest: &Quest) -> Vec<(QuestId, bool)> {
+    let optional: BTreeSet<QuestId> = quest.optional_prerequisites.iter().copied().collect();
+    let all: BTreeSet<QuestId> = quest
+        .prerequisites
+        .iter()
+        .chain(&quest.required_prerequisites)
+        .chain(&quest.optional_prerequisites)
+        .copied()
+        .collect();
+    all.into_iter()
+        .map(|q| (q, optional.contains(&q)))
+        .collect()
+}
+
+impl<'a> Explorer<'a> {
+    /// Start with every questline collapsed and the first one selected.
+    pub fn new(db: &'a QuestDatabase) -> Self {
+        let mut listed: HashSet<QuestId> = HashSet::new();
+        let mut groups: Vec<(Option<QuestId>, Vec<QuestId>)> = Vec::new();
+        for line in sorted_questlines(db) {
+            let quests: Vec<QuestId> = line.entries.iter().map(|e| e.quest_id).collect();
+            listed.extend(&quests);
+            groups.push((Some(line.id), quests));
+        }
+        let mut unlisted: Vec<QuestId> = db
+            .quests
+            .keys()
+            .filter(|q| !listed.contains(q))
+            .copied()
+            .collect();
+        if !unlisted.is_empty() {
+            unlisted.sort();
+            groups.push((None, unlisted));
+        }
+        let mut explorer = Explorer {
+            db,
+            groups,
+            expanded: HashSet::new(),
+            rows: Vec::new(),
+            selected: 0,
+            history: Vec::new(),
+            input: None,
+            query: String::new(),
+            status: String::new(),
+        };
+        explorer.rebuild_rows();
+        explorer
+    }
+
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        for (line, quests) in &self.groups {
+            self.rows.push(TreeRow::Line(*line));
+            if self.expanded.contains(line) {
+                self.rows
+                    .extend(quests.iter().map(|q| TreeRow::Quest(*line, *q)));
+            }
+        }
+    }
+
+    fn select_row(&mut self, row: TreeRow) -> bool {
+        let line = match row {
+            TreeRow::Line(line) | TreeRow::Quest(line, _) => line,
+        };
+        if matches!(row, TreeRow::Quest(..)) && self.expanded.insert(line) {
+            self.rebuild_rows();
+        }
+        match self.rows.iter().position(|r| *r == row) {
+            Some(i) => {
+                self.selected = i;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Visible rows of the tree.
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    /// The selected row, if the tree is not empty.
+    pub fn selected_row(&self) -> Option<TreeRow> {
+        self.rows.get(self.selected).copied()
+    }
+
+    /// The selected quest, if a quest row is selected.
+    pub fn selected_quest(&self) -> Option<QuestId> {
+        match self.selected_row()? {
+            TreeRow::Quest(_, q) => Some(q),
+            TreeRow::Line(_) => None,
+        }
+    }
+
+    /// Move the selection by `delta` rows, clamped to the tree.
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Expand or collapse the questline of the selected row. Collapsing from
+    /// a quest row selects its questline.
+    pub fn toggle(&mut self) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        match row {
+            TreeRow::Line(line) => {
+                if !self.expanded.remove(&line) {
+                    self.expanded.insert(line);
+                }
+                self.rebuild_rows();
+            }
+            TreeRow::Quest(line, _) => {
+                self.expanded.remove(&line);
+                self.rebuild_rows();
+                self.select_row(TreeRow::Line(line));
+            }
+        }
+    }
+
+    /// Select `quest` under the first questline listing it, expanding that
+    /// questline. Returns false if the quest is not in the tree.
+    pub fn select_quest(&mut self, quest: QuestId) -> bool {
+        let line = self
+            .groups
+            .iter()
+            .find(|(_, quests)| quests.contains(&quest))
+            .map(|(line, _)| *line);
+        match line {
+            Some(line) => self.select_row(TreeRow::Quest(line, quest)),
+            None => false,
+        }
+    }
+
+    /// Jump to the `n`-th (0-based) prerequisite of the selected quest,
+    /// remembering the current row for `back`.
+    pub fn jump_to_prerequisite(&mut self, n: usize) -> bool {
+        let Some(quest) = self.selected_quest().and_then(|q| self.db.quests.get(&q)) else {
+            return false;
+        };
+        let Some((target, _)) = prerequisites(quest).get(n).copied() else {
+            return false;
+        };
+        let from = self.rows[self.selected];
+        if !self.select_quest(target) {
+            self.status = format!("quest {} is not in the pack", target);
+            return false;
+        }
+        self.history.push(from);
+        true
+    }
+
+    /// Return to the row selected before the last prerequisite jump.
+    pub fn back(&mut self) -> bool {
+        match self.history.pop() {
+            Some(row) => self.select_row(row),
+            None => false,
+        }
+    }
+
+    /// Select the next quest after the current row whose name contains
+    /// `query` (case-insensitive), wrapping around.
+    pub fn search(&mut self, query: &str) -> bool {
+        self.query = query.to_lowercase();
+        self.search_next()
+    }
+
+    /// Repeat the last search.
+    pub fn search_next(&mut self) -> bool {
+        if self.query.is_empty() {
+            return false;
+        }
+        let order: Vec<(Option<QuestId>, QuestId)> = self
+            .groups
+            .iter()
+            .flat_map(|(line, quests)| quests.iter().map(move |q| (*line, *q)))
+            .collect();
+        let start = match self.selected_row() {
+            Some(TreeRow::Quest(line, q)) => order
+                .iter()
+                .position(|e| *e == (line, q))
+                .map_or(0, |i| i + 1),
+            Some(TreeRow::Line(line)) => order.iter().position(|e| e.0 == line).unwrap_or(0),
+            None => 0,
+        };
+        let hit = (0..order.len())
+            .map(|i| order[(start + i) % order.len()])
+            .find(|(_, q)| {
+                self.db
+                    .quests
+                    .get(q)
+                    .is_some_and(|quest| render_name(quest).to_lowercase().contains(&self.query))
+            });
+        match hit {
+            Some((line, q)) => {
+                self.status.clear();
+                self.select_row(TreeRow::Quest(line, q))
+            }
+            None => {
+                self.status = format!("no quest matches \"{}\"", self.query);
+                false
+            }
+        }
+    }
+
+    /// Apply a key press. Returns false when the explorer should close.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(input) = self.input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let query = self.input.take().unwrap_or_default();
+                    self.search(&query);
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return true;
+        }
+        self.status.clear();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-PAGE),
+            KeyCode::PageDown => self.move_by(PAGE),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.move_by(isize::MAX),
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right => self.toggle(),
+            KeyCode::Backspace | KeyCode::Char('b') => {
+                self.back();
+            }
+            KeyCode::Char('/') => self.input = Some(String::new()),
+            KeyCode::Char('n') => {
+                self.search_next();
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                self.jump_to_prerequisite(c as usize - '1' as usize);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn quest_name(&self, quest: QuestId) -> String {
+        match self.db.quests.get(&quest).map(render_name) {
+            Some(name) if !name.is_empty() => name,
+            _ => format!("quest {}", quest),
+        }
+    }
+
+    fn line_name(&self, line: Option<QuestId>) -> String {
+        let Some(lid) = line else {
+            return "(no questline)".to_string();
+        };
+        self.db
+            .questlines
+            .get(&lid)
+            .and_then(|l| l.properties.as_ref())
+            .map(|p| strip_formatting(&p.name))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("questline {}", lid))
+    }
+
+    fn tree_item(&self, row: &TreeRow) -> ListItem<'static> {
+        match *row {
+            TreeRow::Line(line) => {
+                let marker = if self.expanded.contains(&line) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                ListItem::new(format!("{} {}", marker, self.line_name(line)))
+                    .style(Style::default().add_modifier(Modifier::BOLD))
+            }
+            TreeRow::Quest(_, q) => ListItem::new(format!("  {}", self.quest_name(q))),
+        }
+    }
+
+    fn quest_details(&self, qid: QuestId) -> Vec<Line<'static>> {
+        let heading = |s: &str| {
+            Line::from(Span::styled(
+                s.to_string(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ))
+        };
+        let mut out = vec![
+            heading(&self.quest_name(qid)),
+            Line::from(format!("id {}", qid)),
+        ];
+        let Some(quest) = self.db.quests.get(&qid) else {
+            out.push(Line::from("missing from the pack"));
+            return out;
+        };
+        let lines: Vec<String> = self
+            .groups
+            .iter()
+            .filter(|(line, quests)| line.is_some() && quests.contains(&qid))
+            .map(|(line, _)| self.line_name(*line))
+            .collect();
+        if !lines.is_empty() {
+            out.push(Line::from(format!("in {}", lines.join(", "))));
+        }
+        if let Some(desc) = quest.properties.as_ref().map(render_description)
+            && !desc.is_empty()
+        {
+            out.push(Line::default());
+            out.extend(desc.lines().map(|l| Line::from(l.to_string())));
+        }
+        let mut section = |title: &str, items: Vec<String>| {
+            if !items.is_empty() {
+                out.push(Line::default());
+                out.push(heading(title));
+                out.extend(items.into_iter().map(|i| Line::from(format!("  {}", i))));
+            }
+        };
+        section("Tasks", quest.tasks.iter().map(render_task).collect());
+        section("Rewards", quest.rewards.iter().map(render_reward).collect());
+        let prereqs = prerequisites(quest)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (p, optional))| {
+                let opt = if optional { " (optional)" } else { "" };
+                format!("[{}] {} ({}){}", i + 1, self.quest_name(p), p, opt)
+            })
+            .collect();
+        section("Prerequisites", prereqs);
+        out
+    }
+
+    fn line_details(&self, line: Option<QuestId>) -> Vec<Line<'static>> {
+        let count = self
+            .groups
+            .iter()
+            .find(|(l, _)| *l == line)
+            .map_or(0, |(_, quests)| quests.len());
+        let mut out = vec![
+            Line::from(Span::styled(
+                self.line_name(line),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(format!("{} quests", count)),
+        ];
+        let desc = line
+            .and_then(|lid| self.db.questlines.get(&lid))
+            .and_then(|l| l.properties.as_ref())
+            .map(render_description)
+            .unwrap_or_default();
+        if !desc.is_empty() {
+            out.push(Line::default());
+            out.extend(desc.lines().map(|l| Line::from(l.to_string())));
+        }
+        out
+    }
+
+    fn status_line(&self) -> String {
+        if let Some(input) = &self.input {
+            format!("/{}", input)
+        } else if !self.status.is_empty() {
+            self.status.clone()
+        } else {
+            "↑↓ move  ⏎ expand  1-9 prerequisite  ⌫ back  / search  n next  q quit".to_string()
+        }
+    }
+
+    /// Draw the explorer onto `frame`.
+    pub fn render(&self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [tree, details] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self.rows.iter().map(|r| self.tree_item(r)).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Questlines"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state =
+            ListState::default().with_selected(self.selected_row().map(|_| self.selected));
+        frame.render_stateful_widget(list, tree, &mut state);
+
+        let text = match self.selected_row() {
+            Some(TreeRow::Quest(_, q)) => self.quest_details(q),
+            Some(TreeRow::Line(line)) => self.line_details(line),
+            None => vec![Line::from("The pack has no quests.")],
+        };
+        let details_widget = Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL).title("Details"))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details_widget, details);
+        frame.render_widget(Paragraph::new(self.status_line()), status);
+    }
+}
+
+/// Browse `db` in the terminal until the user quits. The terminal is
+/// restored before returning, also on error.
+pub fn run(db: &QuestDatabase) -> Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, Explorer::new(db));
+    ratatui::try_restore()?;
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, mut explorer: Explorer) -> Result<()> {
+    loop {
+        terminal.draw(|frame| explorer.render(frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !explorer.handle_key(key)
+        {
+            return Ok(());
+        }
+    }
+}
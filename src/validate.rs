@@ -0,0 +1,332 @@
+//! Structural validation of a parsed `QuestDatabase`.
+//!
+//! Parsing already rejects questline entries pointing at missing quests, but a
+//! database built or edited in memory can still contain dangling references.
+//! `validate` reports them, together with prerequisite cycles and duplicate
+//! quest names, as a list of findings instead of failing on the first one.
+//! `validate_with` takes `ValidationOptions` to tune the checks.
+//!
+//! `QuestDatabase::validate_invariants` is the strict counterpart: it checks
+//! the structural rules every mutation path must preserve (map keys match
+//! ids, questline entries and the questline order point at existing objects,
+//! nothing is listed twice) and fails on any violation. Dangling
+//! prerequisites are not an invariant violation, since real packs ship them.
+use crate::error::{ParseError, Result};
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A structural problem in a database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationIssue {
+    /// A quest lists a prerequisite that does not exist.
+    MissingPrerequisite { quest: QuestId, missing: QuestId },
+    /// A quest lists itself as a prerequisite.
+    SelfPrerequisite { quest: QuestId },
+    /// A questline entry points at a quest that does not exist.
+    MissingQuestlineEntry { questline: QuestId, quest: QuestId },
+    /// Several quests share a name (formatting codes and surrounding
+    /// whitespace ignored). `questline` is set when the check is scoped per
+    /// questline.
+    DuplicateName {
+        name: String,
+        quests: Vec<QuestId>,
+        questline: Option<QuestId>,
+    },
+    /// Prerequisites form a cycle (one cycle is reported).
+    PrerequisiteCycle { cycle: Vec<QuestId> },
+}
+
+/// Where quest names must be unique.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateNameScope {
+    /// Do not check names.
+    Off,
+    /// Across the whole pack.
+    Global,
+    /// Within each questline. Packs often reuse names across chapters on
+    /// purpose, so this is the default.
+    #[default]
+    PerQuestline,
+}
+
+/// Knobs for `validate_with`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationOptions {
+    /// Scope of the duplicate name check.
+    #[serde(default)]
+    pub duplicate_names: DuplicateNameScope,
+}
+
+/// Groups of quests in `quests` sharing a non-empty name, by name.
+fn duplicate_names(
+    db: &QuestDatabase,
+    quests: impl IntoIterator<Item = QuestId>,
+) -> Vec<(String, Vec<QuestId>)> {
+    let mut by_name: BTreeMap<String, BTreeSet<QuestId>> = BTreeMap::new();
+    for id in quests {
+        if let Some(name) = db.quests.get(&id).and_then(|q| q.name()) {
+            let name = strip_formatting(name).trim().to_string();
+            if !name.is_empty() {
+                by_name.entry(name).or_default().insert(id);
+            }
+        }
+    }
+    by_name
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(name, ids)| (name, ids.into_iter().collect()))
+        .collect()
+}
+
+/// Prerequisites of every quest naming the quest itself or a missing quest,
+/// by quest id then prerequisite id. The parser runs this check too (see
+/// `db::PrerequisiteCheck`).
+pub(crate) fn prerequisite_issues(db: &QuestDatabase) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut ids: Vec<&QuestId> = db.quests.keys().collect();
+    ids.sort();
+    for id in &ids {
+        let quest = &db.quests[*id];
+        let mut prereqs: Vec<QuestId> = quest
+            .prerequisites
+            .iter()
+            .chain(&quest.required_prerequisites)
+            .chain(&quest.optional_prerequisites)
+            .copied()
+            .collect();
+        prereqs.sort();
+        prereqs.dedup();
+        for p in prereqs {
+            if p == **id {
+                issues.push(ValidationIssue::SelfPrerequisite { quest: p });
+            } else if !db.quests.contains_key(&p) {
+                issues.push(ValidationIssue::MissingPrerequisite {
+                    quest: **id,
+                    missing: p,
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// One-line description of `issue`.
+pub(crate) fn describe(issue: &ValidationIssue) -> String {
+    let id_list = |ids: &[QuestId]| {
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match issue {
+        ValidationIssue::MissingPrerequisite { quest, missing } => {
+            format!("quest {} requires missing quest {}", quest, missing)
+        }
+        ValidationIssue::SelfPrerequisite { quest } => {
+            format!("quest {} requires itself", quest)
+        }
+        ValidationIssue::MissingQuestlineEntry { questline, quest } => {
+            format!("questline {} lists missing quest {}", questline, quest)
+        }
+        ValidationIssue::DuplicateName {
+            name,
+            quests,
+            questline,
+        } => match questline {
+            Some(line) => format!(
+                "questline {} has several quests named \"{}\": {}",
+                line,
+                name,
+                id_list(quests)
+            ),
+            None => format!("several quests named \"{}\": {}", name, id_list(quests)),
+        },
+        ValidationIssue::PrerequisiteCycle { cycle } => {
+            format!("prerequisite cycle: {}", id_list(cycle))
+        }
+    }
+}
+
+/// Check `db` with the default `ValidationOptions`.
+pub fn validate(db: &QuestDatabase) -> Vec<ValidationIssue> {
+    validate_with(db, &ValidationOptions::default())
+}
+
+/// Check `db` for dangling references, duplicate names and prerequisite
+/// cycles.
+///
+/// Findings are ordered by kind, then by id (duplicate names by questline,
+/// then name).
+pub fn validate_with(db: &QuestDatabase, options: &ValidationOptions) -> Vec<ValidationIssue> {
+    let mut issues = prerequisite_issues(db);
+
+    let mut line_ids: Vec<&QuestId> = db.questlines.keys().collect();
+    line_ids.sort();
+    for lid in line_ids {
+        let mut missing: Vec<QuestId> = db.questlines[lid]
+            .entries
+            .iter()
+            .map(|e| e.quest_id)
+            .filter(|q| !db.quests.contains_key(q))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        issues.extend(
+            missing
+                .into_iter()
+                .map(|quest| ValidationIssue::MissingQuestlineEntry {
+                    questline: *lid,
+                    quest,
+                }),
+        );
+    }
+
+    match options.duplicate_names {
+        DuplicateNameScope::Off => {}
+        DuplicateNameScope::Global => {
+            issues.extend(
+                duplicate_names(db, db.quests.keys().copied())
+                    .into_iter()
+                    .map(|(name, quests)| ValidationIssue::DuplicateName {
+                        name,
+                        quests,
+                        questline: None,
+                    }),
+            );
+        }
+        DuplicateNameScope::PerQuestline => {
+            let mut line_ids: Vec<&QuestId> = db.questlines.keys().collect();
+            line_ids.sort();
+            for lid in line_ids {
+                let entries = db.questlines[lid].entries.iter().map(|e| e.quest_id);
+                issues.extend(
+                    duplicate_names(db, entries)
+                        .into_iter()
+                        .map(|(name, quests)| ValidationIssue::DuplicateName {
+                            name,
+                            quests,
+                            questline: Some(*lid),
+                        }),
+                );
+            }
+        }
+    }
+
+    // importance scoring runs the crate's cycle check
+    if let Err(ParseError::CycleDetected(cycle)) =
+        crate::importance::compute_importance_scores(db, 0.0, false, false)
+        && cycle.len() > 1
+    {
+        issues.push(ValidationIssue::PrerequisiteCycle { cycle });
+    }
+    issues
+}
+
+/// A broken structural rule of a `QuestDatabase`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InvariantViolation {
+    /// A quest is stored under a key other than its id.
+    #[error("quest {id} is stored under key {key}")]
+    QuestKeyMismatch { key: QuestId, id: QuestId },
+    /// A questline is stored under a key other than its id.
+    #[error("questline {id} is stored under key {key}")]
+    QuestlineKeyMismatch { key: QuestId, id: QuestId },
+    /// A questline entry points at a quest that does not exist.
+    #[error("questline {questline} has an entry for missing quest {quest}")]
+    MissingEntryQuest { questline: QuestId, quest: QuestId },
+    /// A quest has more than one entry in the same questline.
+    #[error("questline {questline} lists quest {quest} more than once")]
+    DuplicateEntry { questline: QuestId, quest: QuestId },
+    /// `questline_order` names a questline that does not exist.
+    #[error("questline order names missing questline {questline}")]
+    MissingOrderedQuestline { questline: QuestId },
+    /// `questline_order` names a questline more than once.
+    #[error("questline order lists questline {questline} more than once")]
+    DuplicateOrderedQuestline { questline: QuestId },
+}
+
+impl QuestDatabase {
+    /// Every broken structural rule: map keys first, then questline entries
+    /// by questline, then the questline order.
+    pub fn invariant_violations(&self) -> Vec<InvariantViolation> {
+        let mut violations = Vec::new();
+
+        let mut keys: Vec<&QuestId> = self.quests.keys().collect();
+        keys.sort();
+        for key in keys {
+            let id = self.quests[key].id;
+            if id != *key {
+                violations.push(InvariantViolation::QuestKeyMismatch { key: *key, id });
+            }
+        }
+        let mut line_keys: Vec<&QuestId> = self.questlines.keys().collect();
+        line_keys.sort();
+        for key in &line_keys {
+            let id = self.questlines[*key].id;
+            if id != **key {
+                violations.push(InvariantViolation::QuestlineKeyMismatch { key: **key, id });
+            }
+        }
+
+        for key in &line_keys {
+            let mut seen = BTreeSet::new();
+            let mut duplicates = BTreeSet::new();
+            let mut missing = BTreeSet::new();
+            for entry in &self.questlines[*key].entries {
+                if !seen.insert(entry.quest_id) {
+                    duplicates.insert(entry.quest_id);
+                }
+                if !self.quests.contains_key(&entry.quest_id) {
+                    missing.insert(entry.quest_id);
+                }
+            }
+            violations.extend(missing.into_iter().map(|quest| {
+                InvariantViolation::MissingEntryQuest {
+                    questline: **key,
+                    quest,
+                }
+            }));
+            violations.extend(duplicates.into_iter().map(|quest| {
+                InvariantViolation::DuplicateEntry {
+                    questline: **key,
+                    quest,
+                }
+            }));
+        }
+
+        let mut seen = BTreeSet::new();
+        for questline in &self.questline_order {
+            if !self.questlines.contains_key(questline) {
+                violations.push(InvariantViolation::MissingOrderedQuestline {
+                    questline: *questline,
+                });
+            } else if !seen.insert(*questline) {
+                violations.push(InvariantViolation::DuplicateOrderedQuestline {
+                    questline: *questline,
+                });
+            }
+        }
+        violations
+    }
+
+    /// Fail with `InvalidFormat` listing the violations when any structural
+    /// rule is broken. Meant for tests of mutation code and as a check
+    /// before writing a database built in memory.
+    pub fn validate_invariants(&self) -> Result<()> {
+        let violations = self.invariant_violations();
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<String> = violations.iter().map(ToString::to_string).collect();
+        Err(ParseError::InvalidFormat(format!(
+            "database invariants violated: {}",
+            messages.join("; ")
+        )))
+    }
+}
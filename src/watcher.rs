@@ -0,0 +1,189 @@
+//! Progress file watcher.
+//!
+//! `ProgressWatcher` re-reads a `QuestProgress.json` file on each `poll` and
+//! reports what changed since the previous read as typed `ProgressEvent`s.
+//! Events serialize to tagged JSON and have a one-line `describe` rendering,
+//! so a caller can forward them to a chat webhook (e.g. Discord's
+//! `{"content": ...}` payload) without knowing the progress file format.
+//!
+//! A watcher running next to an editor can be fed the editor's
+//! `edit::DatabaseChange` events with `apply_changes`. The game keeps
+//! progress of deleted quests in the file; the watcher stops reporting it
+//! once it has seen the deletion.
+use crate::edit::DatabaseChange;
+use crate::error::Result;
+use crate::model::QuestDatabase;
+use crate::progress::{PlayerProgress, Timestamp, parse_progress_from_file};
+use crate::quest_id::QuestId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A change between two snapshots of player progress.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// The player completed the quest (or completed it again after a reset).
+    QuestCompleted {
+        player: String,
+        quest: QuestId,
+        timestamp: Timestamp,
+    },
+    /// The player claimed the rewards of a completed quest.
+    RewardClaimed { player: String, quest: QuestId },
+    /// The player's completion was removed (repeat reset or admin command).
+    CompletionReset { player: String, quest: QuestId },
+}
+
+impl ProgressEvent {
+    /// The quest the event is about.
+    pub fn quest(&self) -> QuestId {
+        match self {
+            ProgressEvent::QuestCompleted { quest, .. }
+            | ProgressEvent::RewardClaimed { quest, .. }
+            | ProgressEvent::CompletionReset { quest, .. } => *quest,
+        }
+    }
+
+    /// One-line human readable description, using quest names from `db`.
+    pub fn describe(&self, db: &QuestDatabase) -> String {
+        let name = |quest: &QuestId| {
+            db.quests
+                .get(quest)
+                .and_then(|q| q.name())
+                .map(crate::text::strip_formatting)
+                .unwrap_or_else(|| format!("quest {}", quest))
+        };
+        match self {
+            ProgressEvent::QuestCompleted { player, quest, .. } => {
+                format!("{} completed {}", player, name(quest))
+            }
+            ProgressEvent::RewardClaimed { player, quest } => {
+                format!("{} claimed the rewards of {}", player, name(quest))
+            }
+            ProgressEvent::CompletionReset { player, quest } => {
+                format!("{} can repeat {}", player, name(quest))
+            }
+        }
+    }
+}
+
+/// Events turning `old` into `new`, ordered by quest id then player.
+pub fn diff_progress(old: &PlayerProgress, new: &PlayerProgress) -> Vec<ProgressEvent> {
+    let mut quests: Vec<QuestId> = old
+        .quests
+        .keys()
+        .chain(new.quests.keys())
+        .copied()
+        .collect();
+    quests.sort();
+    quests.dedup();
+
+    let mut events = Vec::new();
+    for quest in quests {
+        let before = old.quests.get(&quest);
+        let after = new.quests.get(&quest);
+        let mut players: Vec<&str> = before
+            .into_iter()
+            .chain(after)
+            .flat_map(|p| p.completed.iter().map(|c| c.player.as_str()))
+            .collect();
+        players.sort_unstable();
+        players.dedup();
+        for player in players {
+            let was = before.and_then(|p| p.completion_of(player));
+            let is = after.and_then(|p| p.completion_of(player));
+            match (was, is) {
+                (_, Some(now)) if was.is_none_or(|w| w.timestamp != now.timestamp) => {
+                    events.push(ProgressEvent::QuestCompleted {
+                        player: player.to_string(),
+                        quest,
+                        timestamp: now.timestamp,
+                    });
+                    if now.claimed {
+                        events.push(ProgressEvent::RewardClaimed {
+                            player: player.to_string(),
+                            quest,
+                        });
+                    }
+                }
+                (Some(w), Some(now)) if now.claimed && !w.claimed => {
+                    events.push(ProgressEvent::RewardClaimed {
+                        player: player.to_string(),
+                        quest,
+                    });
+                }
+                (Some(_), None) => events.push(ProgressEvent::CompletionReset {
+                    player: player.to_string(),
+                    quest,
+                }),
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+/// Polls a progress file and reports changes since the previous poll.
+#[derive(Debug)]
+pub struct ProgressWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last: PlayerProgress,
+    /// Quests deleted from the database, whose events are dropped.
+    removed: HashSet<QuestId>,
+}
+
+impl ProgressWatcher {
+    /// Start watching `path`. The current contents are the baseline, so the
+    /// first `poll` only reports changes made after this call.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let modified = std::fs::metadata(&path)?.modified().ok();
+        let last = parse_progress_from_file(&path)?;
+        Ok(ProgressWatcher {
+            path,
+            modified,
+            last,
+            removed: HashSet::new(),
+        })
+    }
+
+    /// The most recently read progress.
+    pub fn progress(&self) -> &PlayerProgress {
+        &self.last
+    }
+
+    /// Re-read the file if its modification time changed and return the
+    /// events since the previous read. Returns no events when the file is
+    /// unchanged.
+    pub fn poll(&mut self) -> Result<Vec<ProgressEvent>> {
+        let modified = std::fs::metadata(&self.path)?.modified().ok();
+        if modified.is_some() && modified == self.modified {
+            return Ok(Vec::new());
+        }
+        let current = parse_progress_from_file(&self.path)?;
+        let mut events = diff_progress(&self.last, &current);
+        events.retain(|e| !self.removed.contains(&e.quest()));
+        self.last = current;
+        self.modified = modified;
+        Ok(events)
+    }
+
+    /// Follow edits of the quest database: quests removed by `changes` stop
+    /// producing events, and quests added (back) produce them again.
+    pub fn apply_changes(&mut self, changes: &[DatabaseChange]) {
+        for change in changes {
+            match change {
+                DatabaseChange::QuestRemoved { quest } => {
+                    self.removed.insert(quest.id);
+                }
+                DatabaseChange::QuestAdded { quest } => {
+                    self.removed.remove(&quest.id);
+                }
+                _ => {}
+            }
+        }
+    }
+}
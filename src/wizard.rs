@@ -0,0 +1,320 @@
+//! Retrieval quest generation from item lists.
+//!
+//! Authoring a chapter of "collect N of X" quests by hand is the most tedious
+//! part of building a questbook. `generate_retrieval_quests` takes rows of
+//! (item, count, chapter), typically read from a CSV file with `read_rows`,
+//! and adds one `bq_standard:retrieval` quest per row:
+//!
+//! - named after the item (`minecraft:iron_ingot` x16 becomes
+//!   "Collect 16 Iron Ingot"),
+//! - placed on the questline named like the chapter, which is created when
+//!   the pack has none,
+//! - requiring the previous quest of the same chapter, so each chapter forms
+//!   a chain continuing from the line's last existing entry,
+//! - laid out left to right on a grid, continuing after the line's existing
+//!   entries.
+//!
+//! New ids are allocated above the highest legacy id in use. The result is a
+//! plain `QuestDatabase`, so `db::write_default_quests_dir` writes it out.
+use crate::analysis::RETRIEVAL_TASK_ID;
+use crate::error::{ParseError, Result};
+use crate::index::split_damage;
+use crate::model::*;
+use crate::quest_id::QuestId;
+use crate::text::strip_formatting;
+use std::collections::HashMap;
+use std::io::Read;
+use std::ops::Range;
+
+/// One item to collect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetrievalRow {
+    /// Item id, e.g. `minecraft:iron_ingot`.
+    pub item: String,
+    /// Damage / meta value, written as a trailing `:<n>` in the CSV.
+    pub damage: Option<i32>,
+    /// Number of items required.
+    pub count: i32,
+    /// Name of the questline the quest goes on.
+    pub chapter: String,
+}
+
+/// Grid placement of generated questline entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridLayout {
+    /// Entries per row.
+    pub columns: usize,
+    /// Distance between neighbouring entries, in questline units.
+    pub spacing: i32,
+    /// Width and height of each entry.
+    pub size: i32,
+}
+
+impl Default for GridLayout {
+    fn default() -> Self {
+        GridLayout {
+            columns: 8,
+            spacing: 32,
+            size: 24,
+        }
+    }
+}
+
+/// Split CSV text into records (RFC 4180: quoted fields may contain commas,
+/// doubled quotes and newlines). Blank lines are skipped.
+pub(crate) fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ParseError::InvalidFormat(
+            "unterminated quoted CSV field".to_string(),
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// Read `item,count,chapter` rows. A header row naming the columns is
+/// skipped; items may carry a damage value as `mod:item:damage`.
+pub fn read_rows<R: Read>(mut r: R) -> Result<Vec<RetrievalRow>> {
+    let mut text = String::new();
+    r.read_to_string(&mut text)?;
+    let mut rows = Vec::new();
+    for (i, record) in parse_csv(&text)?.into_iter().enumerate() {
+        let line = i + 1;
+        let [item, count, chapter] = record.as_slice() else {
+            return Err(ParseError::InvalidFormat(format!(
+                "CSV record {}: expected item,count,chapter",
+                line
+            )));
+        };
+        if i == 0 && count.trim().eq_ignore_ascii_case("count") {
+            continue;
+        }
+        let count: i32 = count.trim().parse().map_err(|_| {
+            ParseError::InvalidFormat(format!("CSV record {}: invalid count {:?}", line, count))
+        })?;
+        if count < 1 {
+            return Err(ParseError::NumberOutOfRange {
+                field: format!("count (CSV record {})", line),
+                value: count.to_string(),
+            });
+        }
+        let (item, damage) = split_damage(item.trim());
+        rows.push(RetrievalRow {
+            item,
+            damage,
+            count,
+            chapter: chapter.trim().to_string(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Human-readable item name: `minecraft:iron_ingot` -> `Iron Ingot`.
+fn item_title(item: &str) -> String {
+    let path = item.rsplit_once(':').map_or(item, |(_, p)| p);
+    path.split(['_', '.'])
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quest_name(row: &RetrievalRow) -> String {
+    match row.count {
+        1 => format!("Collect {}", item_title(&row.item)),
+        n => format!("Collect {} {}", n, item_title(&row.item)),
+    }
+}
+
+pub(crate) fn properties(name: String) -> QuestProperties {
+    QuestProperties {
+        name,
+        desc: None,
+        desc_pages: Vec::new(),
+        icon: None,
+        is_main: None,
+        is_silent: None,
+        auto_claim: None,
+        global_share: None,
+        is_global: None,
+        locked_progress: None,
+        repeat_time: None,
+        repeat_relative: None,
+        simultaneous: None,
+        party_single_reward: None,
+        quest_logic: None,
+        task_logic: None,
+        visibility: None,
+        snd_complete: None,
+        snd_update: None,
+        extra: HashMap::new(),
+    }
+}
+
+fn retrieval_quest(id: QuestId, row: &RetrievalRow, prerequisite: Option<QuestId>) -> Quest {
+    let item = ItemStack {
+        id: row.item.clone(),
+        damage: row.damage,
+        count: Some(row.count),
+        oredict: None,
+        extra: HashMap::new(),
+    };
+    let task = Task {
+        index: Some(0),
+        task_id: RETRIEVAL_TASK_ID.to_string(),
+        required_items: vec![item.clone()],
+        ignore_nbt: None,
+        partial_match: None,
+        auto_consume: None,
+        consume: None,
+        group_detect: None,
+        options: HashMap::new(),
+    };
+    let mut props = properties(quest_name(row));
+    props.icon = Some(ItemStack {
+        count: Some(1),
+        ..item
+    });
+    let prerequisites: Vec<QuestId> = prerequisite.into_iter().collect();
+    Quest {
+        id,
+        properties: Some(props),
+        tasks: vec![task],
+        rewards: Vec::new(),
+        prerequisites: prerequisites.clone(),
+        required_prerequisites: prerequisites,
+        optional_prerequisites: Vec::new(),
+    }
+}
+
+/// The error for ids past `i64::MAX`: `count` ids after `first`.
+pub(crate) fn out_of_ids(first: i64, count: usize) -> ParseError {
+    ParseError::NumberOutOfRange {
+        field: "new quest id".to_string(),
+        value: format!("{} + {}", first, count),
+    }
+}
+
+/// `count` consecutive unused legacy ids above everything in `ids`. Fails
+/// with `NumberOutOfRange` when they would pass `i64::MAX`.
+pub(crate) fn free_ids<'a>(
+    ids: impl Iterator<Item = &'a QuestId>,
+    count: usize,
+) -> Result<Range<i64>> {
+    let highest = ids
+        .filter(|id| id.high_i64() == 0 && id.low_i64() >= 0)
+        .map(|id| id.low_i64())
+        .max();
+    let first = match highest {
+        None => 0,
+        Some(low) => low.checked_add(1).ok_or_else(|| out_of_ids(low, 1))?,
+    };
+    let end = i64::try_from(count)
+        .ok()
+        .and_then(|n| first.checked_add(n))
+        .ok_or_else(|| out_of_ids(first, count))?;
+    Ok(first..end)
+}
+
+/// Next unused legacy id above everything in `ids`.
+pub(crate) fn next_free_id<'a>(ids: impl Iterator<Item = &'a QuestId>) -> Result<i64> {
+    Ok(free_ids(ids, 1)?.start)
+}
+
+/// The questline named `chapter` (formatting ignored), created at the end of
+/// `questline_order` when missing.
+pub(crate) fn chapter_line<'a>(
+    db: &'a mut QuestDatabase,
+    chapter: &str,
+) -> Result<&'a mut QuestLine> {
+    let existing = db.questline_order.iter().copied().find(|lid| {
+        db.questlines
+            .get(lid)
+            .and_then(|l| l.properties.as_ref())
+            .is_some_and(|p| strip_formatting(&p.name) == chapter)
+    });
+    let lid = match existing {
+        Some(lid) => lid,
+        None => {
+            let lid = QuestId::from_parts_i64(0, next_free_id(db.questlines.keys())?);
+            db.questline_order.push(lid);
+            lid
+        }
+    };
+    Ok(db.questlines.entry(lid).or_insert_with(|| QuestLine {
+        id: lid,
+        properties: Some(QuestLineProperties {
+            name: chapter.to_string(),
+            ..Default::default()
+        }),
+        entries: Vec::new(),
+        extra: HashMap::new(),
+    }))
+}
+
+/// Add one retrieval quest per row to `db` and return the new ids in row
+/// order. Fails without touching `db` when `layout.columns` is zero.
+pub fn generate_retrieval_quests(
+    db: &mut QuestDatabase,
+    rows: &[RetrievalRow],
+    layout: &GridLayout,
+) -> Result<Vec<QuestId>> {
+    if layout.columns == 0 {
+        return Err(ParseError::NumberOutOfRange {
+            field: "columns".to_string(),
+            value: "0".to_string(),
+        });
+    }
+    let ids = free_ids(db.quests.keys(), rows.len())?;
+    let mut created = Vec::with_capacity(rows.len());
+    for (low, row) in ids.zip(rows) {
+        let line = chapter_line(db, &row.chapter)?;
+        let previous = line.entries.last().map(|e| e.quest_id);
+        let slot = line.entries.len();
+        let id = QuestId::from_parts_i64(0, low);
+        line.entries.push(QuestLineEntry {
+            index: None,
+            quest_id: id,
+            x: Some((slot % layout.columns) as i32 * layout.spacing),
+            y: Some((slot / layout.columns) as i32 * layout.spacing),
+            size_x: Some(layout.size),
+            size_y: Some(layout.size),
+            extra: HashMap::new(),
+        });
+        db.quests.insert(id, retrieval_quest(id, row, previous));
+        created.push(id);
+    }
+    Ok(created)
+}
@@ -0,0 +1,58 @@
+use better_questing_tools::export::anki::to_anki_csv;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use std::collections::HashMap;
+
+#[test]
+fn deck_has_headers_and_escaped_cards() {
+    let id = QuestId::from_parts(0, 1);
+    let props: QuestProperties = serde_json::from_value(serde_json::json!({
+        "name": "§6Bread, & butter",
+        "desc": "Bake \"something\".\nThen eat it.",
+    }))
+    .expect("properties");
+    let task: Task = serde_json::from_value(serde_json::json!({
+        "taskID": "bq_standard:retrieval",
+        "requiredItems": [{ "id": "minecraft:bread", "Count": 4 }],
+    }))
+    .expect("task");
+    let line_props: QuestLineProperties =
+        serde_json::from_value(serde_json::json!({ "name": "Stone Age" })).expect("line");
+    let lid = QuestId::from_parts(0, 100);
+    let line: QuestLine = QuestLine {
+        id: lid,
+        properties: Some(line_props),
+        entries: vec![QuestLineEntry {
+            index: None,
+            quest_id: id,
+            x: None,
+            y: None,
+            size_x: None,
+            size_y: None,
+            extra: HashMap::new(),
+        }],
+        extra: HashMap::new(),
+    };
+    let quest = Quest {
+        id,
+        properties: Some(props),
+        tasks: vec![task],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    };
+    let db = QuestDatabase {
+        settings: None,
+        quests: HashMap::from([(id, quest)]),
+        questlines: HashMap::from([(lid, line)]),
+        questline_order: vec![lid],
+    };
+
+    let csv = to_anki_csv(&db);
+    let expected = "#separator:Comma\n#html:true\n#tags column:3\n\
+        \"Bread, &amp; butter\",\
+        Bake &quot;something&quot;.<br>Then eat it.<br><br>Tasks:<br>- bq_standard:retrieval: 4x minecraft:bread,\
+        Stone_Age\n";
+    assert_eq!(csv, expected);
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "bumpalo")]
+
+use better_questing_tools::arena::{
+    ArenaQuestDatabase, ArenaValue, load_default_quests_dir_from_source,
+};
+use better_questing_tools::db::{MemoryDataSource, write_default_quests_dir_to_sink};
+use better_questing_tools::model::{QuestDatabase, QuestLine, QuestLineEntry};
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use bumpalo::Bump;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn pack() -> QuestDatabase {
+    let quests = (1..=3)
+        .map(|low| {
+            let v = json!({
+                "questIDHigh:4": 0,
+                "questIDLow:4": low,
+                "preRequisites:11": [low - 1],
+                "properties:10": { "betterquesting:10": {
+                    "name:8": format!("Quest {low}"),
+                    "customFlag:8": "kept",
+                }},
+            });
+            let q = parse_quest_from_reader(v.to_string().as_bytes()).expect("quest");
+            (q.id, q)
+        })
+        .collect();
+    QuestDatabase {
+        settings: None,
+        quests,
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn arena_copy_matches_heap_model() {
+    let db = pack();
+
+    let bump = Bump::new();
+    let arena = ArenaQuestDatabase::from_db(&bump, &db);
+    assert_eq!(arena.quests.len(), 3);
+    assert!(arena.quests.windows(2).all(|w| w[0].id < w[1].id));
+
+    let id = QuestId::from_parts(0, 2);
+    let quest = arena.quest(id).expect("quest 2");
+    assert_eq!(quest.name(), Some("Quest 2"));
+    assert_eq!(quest.prerequisites, db.quests[&id].prerequisites.as_slice());
+    let extra = quest.properties.expect("properties").extra;
+    assert_eq!(extra.get("customFlag"), Some(&ArenaValue::String("kept")));
+    assert_eq!(
+        extra.get("customFlag").map(ArenaValue::to_value),
+        Some(json!("kept"))
+    );
+    assert!(arena.quest(QuestId::from_parts(0, 9)).is_none());
+}
+
+#[test]
+fn loads_a_pack_straight_into_the_arena() {
+    let mut db = pack();
+    let line = QuestLine {
+        id: QuestId::from_parts(0, 7),
+        properties: None,
+        entries: vec![QuestLineEntry {
+            index: None,
+            quest_id: QuestId::from_parts(0, 3),
+            x: Some(10),
+            y: Some(20),
+            size_x: None,
+            size_y: None,
+            extra: HashMap::new(),
+        }],
+        extra: HashMap::new(),
+    };
+    db.questline_order.push(line.id);
+    db.questlines.insert(line.id, line);
+    let mut files = MemoryDataSource::new();
+    write_default_quests_dir_to_sink(&mut files, "DefaultQuests", &db).expect("write");
+
+    let bump = Bump::new();
+    let loaded = load_default_quests_dir_from_source(&bump, &files, "DefaultQuests").expect("load");
+    let copied = ArenaQuestDatabase::from_db(&bump, &db);
+    assert_eq!(loaded.quests, copied.quests);
+    assert_eq!(loaded.questlines.len(), 1);
+    assert_eq!(loaded.questlines[0].entries[0].x, Some(10));
+
+    // entries naming missing quests fail like the heap parser
+    let mut broken = MemoryDataSource::new();
+    db.quests.remove(&QuestId::from_parts(0, 3));
+    write_default_quests_dir_to_sink(&mut broken, "DefaultQuests", &db).expect("write");
+    assert!(load_default_quests_dir_from_source(&bump, &broken, "DefaultQuests").is_err());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use better_questing_tools::async_source::{
+    AsyncFsDataSource, AsyncQuestDataSource, parse_default_quests_dir_from_source_async,
+    parse_default_quests_dir_with_options_async,
+};
+use better_questing_tools::db::{
+    FsDataSource, MemoryDataSource, ParseOptions, parse_default_quests_dir_from_source,
+    write_default_quests_dir, write_default_quests_dir_to_sink,
+};
+use better_questing_tools::error::Result;
+use better_questing_tools::model::*;
+use common::{db, line, quest};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn sample_db() -> QuestDatabase {
+    db(
+        [1, 2].map(|low| quest(low).name(&format!("Quest {low}")).build()),
+        [line(100).name("Line").entry_at(2, 0, 0).build()],
+    )
+}
+
+#[tokio::test]
+async fn parses_from_the_filesystem() {
+    let dir = std::env::temp_dir().join(format!("bqt-async-{}", std::process::id()));
+    write_default_quests_dir(&sample_db(), &dir).unwrap();
+    let root = dir.to_string_lossy().into_owned();
+    let parsed = parse_default_quests_dir_from_source_async(&AsyncFsDataSource, &root).await;
+    let sync = parse_default_quests_dir_from_source(&FsDataSource, &root).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(parsed.unwrap(), sync);
+    assert_eq!(sync, sample_db());
+}
+
+/// Memory source counting the files fetched.
+struct Counting {
+    inner: MemoryDataSource,
+    reads: AtomicUsize,
+}
+
+impl AsyncQuestDataSource for Counting {
+    fn list_dir(&self, path: &str) -> impl Future<Output = Result<Vec<String>>> + Send {
+        self.inner.list_dir(path)
+    }
+    fn is_dir(&self, path: &str) -> impl Future<Output = bool> + Send {
+        self.inner.is_dir(path)
+    }
+    fn is_file(&self, path: &str) -> impl Future<Output = bool> + Send {
+        self.inner.is_file(path)
+    }
+    fn read_to_string(&self, path: &str) -> impl Future<Output = Result<String>> + Send {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.inner.read_to_string(path)
+    }
+}
+
+#[tokio::test]
+async fn custom_sources_are_fetched_once_per_file() {
+    let mut inner = MemoryDataSource::new();
+    write_default_quests_dir_to_sink(&mut inner, "bucket/pack", &sample_db()).unwrap();
+    inner.insert("bucket/pack/README.md", "ignored");
+    let source = Counting {
+        inner,
+        reads: AtomicUsize::new(0),
+    };
+    let outcome = parse_default_quests_dir_with_options_async(
+        &source,
+        "bucket/pack",
+        &ParseOptions::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(outcome.db, sample_db());
+    // Two quests, one QuestLine.json and one entry.
+    assert_eq!(source.reads.load(Ordering::Relaxed), 4);
+
+    assert!(
+        parse_default_quests_dir_from_source_async(&source, "bucket/missing")
+            .await
+            .is_err()
+    );
+}
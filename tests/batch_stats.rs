@@ -0,0 +1,79 @@
+mod common;
+
+use better_questing_tools::batch::{aggregate, analyze_packs, load_pack};
+use better_questing_tools::db::write_default_quests_dir;
+use better_questing_tools::model::*;
+use common::retrieval;
+
+/// Quest `low` after quest `low - 1`, with one empty task per type.
+fn quest(low: i32, task_types: &[&str]) -> Quest {
+    let tasks = task_types.iter().enumerate().map(|(i, t)| Task {
+        index: Some(i),
+        task_id: t.to_string(),
+        ..retrieval([])
+    });
+    let prereqs: &[i32] = if low > 1 { &[low - 1] } else { &[] };
+    common::quest(low)
+        .name(&format!("Quest {low}"))
+        .requires(prereqs)
+        .tasks(tasks)
+        .build()
+}
+
+fn pack(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+#[test]
+fn per_pack_stats_and_aggregation() {
+    let base = std::env::temp_dir().join(format!("bqt-batch-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&base);
+    let a = base.join("a");
+    let b = base.join("b");
+    write_default_quests_dir(
+        &pack(vec![
+            quest(1, &["bq_standard:checkbox"]),
+            quest(2, &["bq_standard:retrieval", "bq_standard:checkbox"]),
+        ]),
+        &a,
+    )
+    .unwrap();
+    write_default_quests_dir(
+        &pack(vec![
+            quest(1, &["bq_standard:retrieval"]),
+            quest(2, &["bq_standard:hunt"]),
+            quest(3, &["bq_standard:retrieval"]),
+            quest(4, &[]),
+        ]),
+        &b,
+    )
+    .unwrap();
+
+    let results = analyze_packs(&[a.clone(), base.join("missing"), b]);
+    std::fs::remove_dir_all(&base).ok();
+    assert_eq!(results.len(), 3);
+    let err = results[1].as_ref().unwrap_err();
+    let direct = load_pack(&base.join("missing")).unwrap_err();
+    assert_eq!(err.to_string(), direct.to_string());
+    let stats: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+
+    assert_eq!(stats[0].path, a);
+    assert_eq!(stats[0].quests, 2);
+    assert_eq!(stats[0].tasks, 3);
+    assert_eq!(stats[0].task_types["bq_standard:checkbox"], 2);
+    assert_eq!(stats[1].prerequisite_links, 3);
+
+    let summary = aggregate(&stats);
+    assert_eq!(summary.packs, 2);
+    assert_eq!(summary.avg_quests, 3.0);
+    assert_eq!(summary.avg_tasks_per_quest, 1.0);
+    assert_eq!(summary.avg_prerequisites_per_quest, 0.666667);
+    assert_eq!(
+        summary.common_task_types,
+        vec![
+            ("bq_standard:retrieval".to_string(), 2),
+            ("bq_standard:checkbox".to_string(), 1),
+            ("bq_standard:hunt".to_string(), 1),
+        ]
+    );
+}
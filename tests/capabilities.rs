@@ -0,0 +1,52 @@
+use better_questing_tools::capabilities::{
+    CAPABILITIES_VERSION, IdEncoding, InputFormat, capabilities, to_json,
+};
+
+#[test]
+fn reports_formats_encodings_and_handlers() {
+    let caps = capabilities();
+    assert_eq!(caps.version, CAPABILITIES_VERSION);
+    assert!(caps.supports(InputFormat::DefaultQuestsDir));
+    assert!(caps.require(InputFormat::QuestFile).is_ok());
+    assert!(caps.supports(InputFormat::FtbQuestsDir));
+    assert!(caps.supports(InputFormat::DefaultLoot));
+    assert!(caps.id_encodings.contains(&IdEncoding::UuidString));
+    assert!(caps.knows_task("bq_standard:retrieval"));
+    assert!(caps.knows_task("bq_standard:hunt"));
+    assert!(caps.knows_reward("bq_standard:choice"));
+    assert!(!caps.knows_task("bq_rf:rf_charge"));
+    assert_eq!(
+        caps.supports(InputFormat::NbtDat),
+        cfg!(feature = "nbt"),
+        "dat support follows the nbt feature"
+    );
+    assert_eq!(
+        caps.features.contains(&"zip".to_string()),
+        cfg!(feature = "zip")
+    );
+}
+
+#[cfg(not(feature = "nbt"))]
+#[test]
+fn missing_feature_is_named() {
+    let err = capabilities().require(InputFormat::NbtDat).unwrap_err();
+    assert!(matches!(
+        err,
+        better_questing_tools::error::ParseError::Unsupported(_)
+    ));
+    assert!(err.to_string().contains("`nbt` feature"));
+}
+
+#[cfg(feature = "nbt")]
+#[test]
+fn enabled_feature_is_available() {
+    assert!(capabilities().require(InputFormat::NbtDat).is_ok());
+}
+
+#[test]
+fn json_uses_snake_case_names() {
+    let json: serde_json::Value = serde_json::from_str(&to_json().unwrap()).unwrap();
+    assert_eq!(json["formats"][0]["format"], "default_quests_dir");
+    assert_eq!(json["formats"][5]["format"], "ftb_quests_dir");
+    assert_eq!(json["id_encodings"][2], "uuid_string");
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use better_questing_tools::analysis::{ChainEdit, flatten_suggestions};
+use better_questing_tools::index::ItemIndex;
+use better_questing_tools::model::*;
+use common::{db, item_reward, q, retrieval, stack};
+
+fn quest(low: i32, prereqs: &[i32], tasks: Vec<Task>, rewards: Vec<Reward>) -> Quest {
+    common::quest(low)
+        .requires(prereqs)
+        .tasks(tasks)
+        .rewards(rewards)
+        .build()
+}
+
+fn needs(item: &str) -> Vec<Task> {
+    vec![retrieval([stack(item, 1)])]
+}
+
+fn gives(item: &str) -> Vec<Reward> {
+    vec![item_reward([stack(item, 1)])]
+}
+
+#[test]
+fn long_single_dependent_chain_gets_suggestions() {
+    // 1 -> 2 -> 3 -> 4, with 5 branching off 4 (so the chain stops at 4).
+    // Quest 2 rewards the key quest 3 needs; quest 4 needs nothing from the chain.
+    let quests = vec![
+        quest(1, &[], vec![], vec![]),
+        quest(2, &[1], vec![], gives("mod:key")),
+        quest(3, &[2], needs("mod:key"), gives("mod:gem")),
+        quest(4, &[3], needs("minecraft:dirt"), gives("mod:gem")),
+        quest(5, &[4], vec![], vec![]),
+        quest(6, &[4], vec![], vec![]),
+    ];
+    let db = db(quests, []);
+    let index = ItemIndex::build(&db);
+
+    assert!(flatten_suggestions(&db, &index, 4).is_empty());
+    let found = flatten_suggestions(&db, &index, 3);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].chain, vec![q(1), q(2), q(3), q(4)]);
+    assert_eq!(
+        found[0].edits,
+        vec![
+            ChainEdit::Merge {
+                absorb: q(1),
+                keep: q(2)
+            },
+            ChainEdit::Parallelize {
+                quest: q(4),
+                current_parent: q(3),
+                attach_to: q(1)
+            },
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use better_questing_tools::diff::compare;
+use better_questing_tools::export::changelog::{Verbosity, to_markdown};
+use better_questing_tools::model::*;
+use common::{item_reward, line, stack};
+
+fn quest(id: i32, name: &str, bread: i32) -> Quest {
+    common::quest(id)
+        .name(name)
+        .rewards([item_reward([stack("minecraft:bread", bread)])])
+        .build()
+}
+
+fn db(quests: Vec<Quest>, entries: &[i32]) -> QuestDatabase {
+    common::db(
+        quests,
+        [line(100).name("Tier 3: MV").entries(entries).build()],
+    )
+}
+
+#[test]
+fn changelog_verbosity_levels() {
+    let old = db(vec![quest(1, "First Steps", 4)], &[1]);
+    let new = db(
+        vec![
+            quest(1, "First Steps", 8),
+            quest(2, "Pump", 1),
+            quest(3, "Motor", 1),
+        ],
+        &[1, 2, 3],
+    );
+    let diff = compare(&old, &new);
+
+    assert_eq!(
+        to_markdown(&diff, Verbosity::Brief),
+        "### Questlines\n\n- Added 2 quests to 'Tier 3: MV'\n\n\
+         ### Quests\n\n- Added 2 quests\n- Changed 1 quest\n\n"
+    );
+    let normal = to_markdown(&diff, Verbosity::Normal);
+    assert!(normal.contains("- Added 'Pump'\n- Added 'Motor'\n"));
+    assert!(normal.contains(
+        "- Reward of 'First Steps' changed from 4x minecraft:bread to 8x minecraft:bread\n"
+    ));
+    let detailed = to_markdown(&diff, Verbosity::Detailed);
+    assert!(detailed.contains("- Added 2 quests to 'Tier 3: MV': 'Pump', 'Motor'\n"));
+
+    assert_eq!(
+        to_markdown(&compare(&old, &old), Verbosity::Normal),
+        "No changes.\n"
+    );
+}
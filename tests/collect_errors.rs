@@ -0,0 +1,104 @@
+mod common;
+
+use better_questing_tools::db::{
+    MemoryDataSource, ParseOptions, PrerequisiteCheck, parse_default_quests_dir_collect,
+    parse_default_quests_dir_from_source,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::validate::ValidationIssue;
+use common::q;
+use serde_json::json;
+
+fn quest(low: i32, prereqs: &[i32]) -> String {
+    let refs: Vec<serde_json::Value> = prereqs
+        .iter()
+        .map(|p| json!({ "questIDHigh:4": 0, "questIDLow:4": p }))
+        .collect();
+    json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "properties:10": { "betterquesting:10": { "name:8": format!("Quest {}", low) } },
+        "preRequisites:9": refs,
+    })
+    .to_string()
+}
+
+fn entry(low: i32) -> String {
+    json!({ "questIDHigh:4": 0, "questIDLow:4": low }).to_string()
+}
+
+/// A pack with a broken quest file, a duplicate quest, a broken questline
+/// entry, a dangling questline entry and a missing prerequisite.
+fn broken_pack() -> MemoryDataSource {
+    [
+        ("pack/Quests/1.json", quest(1, &[])),
+        ("pack/Quests/2.json", quest(2, &[1, 9])),
+        ("pack/Quests/3.json", "{ broken".to_string()),
+        ("pack/Quests/4.json", quest(1, &[])),
+        (
+            "pack/QuestLines/0/QuestLine.json",
+            json!({ "questLineIDHigh:4": 0, "questLineIDLow:4": 100 }).to_string(),
+        ),
+        ("pack/QuestLines/0/1.json", entry(1)),
+        ("pack/QuestLines/0/2.json", "[".to_string()),
+        ("pack/QuestLines/0/3.json", entry(8)),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn collects_every_error_and_keeps_the_rest() {
+    let options = ParseOptions {
+        missing_prerequisites: PrerequisiteCheck::Strict,
+        ..Default::default()
+    };
+    let (db, errors) = parse_default_quests_dir_collect(&broken_pack(), "pack", &options);
+
+    let mut ids: Vec<QuestId> = db.quests.keys().copied().collect();
+    ids.sort();
+    assert_eq!(ids, vec![q(1), q(2)]);
+    let entries: Vec<QuestId> = db.questlines[&q(100)]
+        .entries
+        .iter()
+        .map(|e| e.quest_id)
+        .collect();
+    assert_eq!(entries, vec![q(1)]);
+
+    assert_eq!(errors.len(), 5, "{:#?}", errors);
+    assert!(matches!(&errors[0], ParseError::InFile { path, .. } if path == "pack/Quests/3.json"));
+    assert!(
+        matches!(&errors[1], ParseError::DuplicateQuestId(path) if path == "pack/Quests/4.json")
+    );
+    assert!(
+        matches!(&errors[2], ParseError::InFile { path, .. } if path == "pack/QuestLines/0/2.json")
+    );
+    assert!(matches!(
+        errors[3],
+        ParseError::MissingQuestReference { questline, quest_id }
+            if questline == q(100) && quest_id == q(8)
+    ));
+    assert!(matches!(
+        errors[4],
+        ParseError::Invalid(ValidationIssue::MissingPrerequisite { quest, missing })
+            if quest == q(2) && missing == q(9)
+    ));
+}
+
+#[test]
+fn the_ordinary_parse_stops_at_the_first_error() {
+    let err = parse_default_quests_dir_from_source(&broken_pack(), "pack").unwrap_err();
+    assert!(matches!(err, ParseError::InFile { path, .. } if path == "pack/Quests/3.json"));
+}
+
+#[test]
+fn unreadable_root_yields_an_empty_database() {
+    let (db, errors) = parse_default_quests_dir_collect(
+        &MemoryDataSource::new(),
+        "pack",
+        &ParseOptions::default(),
+    );
+    assert!(db.quests.is_empty());
+    assert!(matches!(errors[..], [ParseError::InvalidFormat(_)]));
+}
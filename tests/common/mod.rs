@@ -0,0 +1,216 @@
+//! Builders for the small databases the integration tests run on.
+//!
+//! Quest and questline ids are written as their low part (`q(3)` is
+//! `0:3`). `quest(3).name("Wood").requires(&[1, 2]).build()` makes a quest,
+//! `line(100).entries(&[1, 2]).build()` a questline listing quests, and `db`
+//! puts both into a database with the questlines in the given order.
+//! `retrieval` and `item_reward` make the item tasks and rewards most
+//! analyses look at, from `stack`s.
+#![allow(dead_code)]
+
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// The id `0:low`.
+pub fn q(low: i32) -> QuestId {
+    QuestId::from_parts(0, low)
+}
+
+/// Builder of a `Quest`, see `quest`.
+#[derive(Debug, Clone)]
+pub struct QuestBuilder(Quest);
+
+/// A quest without properties, tasks, rewards or prerequisites.
+pub fn quest(low: i32) -> QuestBuilder {
+    QuestBuilder(Quest {
+        id: q(low),
+        properties: None,
+        tasks: vec![],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    })
+}
+
+impl QuestBuilder {
+    /// Replace the id, e.g. with one whose high part is not 0.
+    pub fn id(mut self, id: QuestId) -> Self {
+        self.0.id = id;
+        self
+    }
+
+    /// Give the quest properties holding just `name`.
+    pub fn name(self, name: &str) -> Self {
+        self.properties(json!({ "name": name }))
+    }
+
+    /// Replace the properties with `props`, given as their JSON form.
+    pub fn properties(mut self, props: serde_json::Value) -> Self {
+        self.0.properties = Some(serde_json::from_value(props).expect("quest properties"));
+        self
+    }
+
+    /// Add required prerequisites.
+    pub fn requires(mut self, lows: &[i32]) -> Self {
+        for low in lows {
+            self.0.prerequisites.push(q(*low));
+            self.0.required_prerequisites.push(q(*low));
+        }
+        self
+    }
+
+    /// Add optional prerequisites; `prerequisites` keeps listing the
+    /// required ones only.
+    pub fn optional(mut self, lows: &[i32]) -> Self {
+        self.0
+            .optional_prerequisites
+            .extend(lows.iter().map(|low| q(*low)));
+        self
+    }
+
+    /// Set the `repeatTime` in ticks (-1 for not repeatable) of a quest
+    /// that already has properties.
+    pub fn repeat_time(mut self, ticks: i32) -> Self {
+        let props = self.0.properties.as_mut().expect("quest properties");
+        props.repeat_time = Some(ticks);
+        self
+    }
+
+    /// Add tasks.
+    pub fn tasks(mut self, tasks: impl IntoIterator<Item = Task>) -> Self {
+        self.0.tasks.extend(tasks);
+        self
+    }
+
+    /// Add rewards.
+    pub fn rewards(mut self, rewards: impl IntoIterator<Item = Reward>) -> Self {
+        self.0.rewards.extend(rewards);
+        self
+    }
+
+    /// The finished quest.
+    pub fn build(self) -> Quest {
+        self.0
+    }
+}
+
+/// Builder of a `QuestLine`, see `line`.
+#[derive(Debug, Clone)]
+pub struct LineBuilder(QuestLine);
+
+/// A questline without properties or entries.
+pub fn line(low: i32) -> LineBuilder {
+    LineBuilder(QuestLine {
+        id: q(low),
+        properties: None,
+        entries: vec![],
+        extra: HashMap::new(),
+    })
+}
+
+impl LineBuilder {
+    /// Give the questline properties holding just `name`.
+    pub fn name(mut self, name: &str) -> Self {
+        self.0.properties = Some(QuestLineProperties {
+            name: name.to_string(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add entries for these quests, without position or size.
+    pub fn entries(mut self, lows: &[i32]) -> Self {
+        self.0.entries.extend(lows.iter().map(|low| entry(*low)));
+        self
+    }
+
+    /// Add an entry for quest `low` at `(x, y)`, without size.
+    pub fn entry_at(mut self, low: i32, x: i32, y: i32) -> Self {
+        self.0.entries.push(QuestLineEntry {
+            x: Some(x),
+            y: Some(y),
+            ..entry(low)
+        });
+        self
+    }
+
+    /// Add a ready-made entry.
+    pub fn entry(mut self, entry: QuestLineEntry) -> Self {
+        self.0.entries.push(entry);
+        self
+    }
+
+    /// The finished questline.
+    pub fn build(self) -> QuestLine {
+        self.0
+    }
+}
+
+/// An entry for quest `low` without position or size.
+pub fn entry(low: i32) -> QuestLineEntry {
+    QuestLineEntry {
+        index: None,
+        quest_id: q(low),
+        x: None,
+        y: None,
+        size_x: None,
+        size_y: None,
+        extra: HashMap::new(),
+    }
+}
+
+/// A database of `quests` and `lines`, the questlines in the given order.
+pub fn db(
+    quests: impl IntoIterator<Item = Quest>,
+    lines: impl IntoIterator<Item = QuestLine>,
+) -> QuestDatabase {
+    let lines: Vec<QuestLine> = lines.into_iter().collect();
+    QuestDatabase {
+        settings: None,
+        quests: quests.into_iter().map(|q| (q.id, q)).collect(),
+        questline_order: lines.iter().map(|l| l.id).collect(),
+        questlines: lines.into_iter().map(|l| (l.id, l)).collect(),
+    }
+}
+
+/// A stack of `count` items `id` with damage 0.
+pub fn stack(id: &str, count: i32) -> ItemStack {
+    ItemStack {
+        id: id.to_string(),
+        damage: Some(0),
+        count: Some(count),
+        oredict: None,
+        extra: HashMap::new(),
+    }
+}
+
+/// A `bq_standard:retrieval` task asking for `items`, neither consuming nor
+/// auto-consuming them.
+pub fn retrieval(items: impl IntoIterator<Item = ItemStack>) -> Task {
+    Task {
+        index: Some(0),
+        task_id: "bq_standard:retrieval".to_string(),
+        required_items: items.into_iter().collect(),
+        ignore_nbt: None,
+        partial_match: None,
+        auto_consume: None,
+        consume: None,
+        group_detect: None,
+        options: HashMap::new(),
+    }
+}
+
+/// A `bq_standard:item` reward granting `items`.
+pub fn item_reward(items: impl IntoIterator<Item = ItemStack>) -> Reward {
+    Reward {
+        index: Some(0),
+        reward_id: "bq_standard:item".to_string(),
+        items: items.into_iter().collect(),
+        choices: vec![],
+        ignore_disabled: None,
+        extra: HashMap::new(),
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use better_questing_tools::export::dot::condensation_to_dot;
+use better_questing_tools::graph::{condensation, strongly_connected_components};
+use better_questing_tools::model::*;
+use common::q;
+
+fn quest(low: i32, name: &str, prereqs: &[i32]) -> Quest {
+    common::quest(low).name(name).requires(prereqs).build()
+}
+
+fn db(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+#[test]
+fn cycles_collapse_into_labelled_nodes() {
+    // 1 -> {2, 3, 4 cycle} -> 5, and 6 requires itself
+    let db = db(vec![
+        quest(1, "Start", &[]),
+        quest(2, "§aA", &[1, 4]),
+        quest(3, "B \"quoted\"", &[2]),
+        quest(4, "C", &[3]),
+        quest(5, "End", &[4, 99]),
+        quest(6, "Loop", &[6]),
+    ]);
+
+    assert_eq!(
+        strongly_connected_components(&db),
+        vec![vec![q(1)], vec![q(2), q(3), q(4)], vec![q(5)], vec![q(6)]]
+    );
+    let graph = condensation(&db);
+    assert!(graph.has_cycles());
+    assert_eq!(graph.edges, vec![(0, 1), (1, 2)]);
+    assert_eq!(graph.component_of(q(3)), Some(1));
+    let cyclic: Vec<bool> = graph.components.iter().map(|c| c.cyclic).collect();
+    assert_eq!(cyclic, vec![false, true, false, true]);
+
+    assert_eq!(
+        condensation_to_dot(&db),
+        "digraph condensation {\n\
+         \x20 c0 [label=\"Start (1)\"]\n\
+         \x20 c1 [label=\"Cycle of 3\\nA (2)\\nB \\\"quoted\\\" (3)\\nC (4)\", shape=box, color=red]\n\
+         \x20 c2 [label=\"End (5)\"]\n\
+         \x20 c3 [label=\"Cycle of 1\\nLoop (6)\", shape=box, color=red]\n\
+         \x20 c0 -> c1\n\
+         \x20 c1 -> c2\n\
+         }\n"
+    );
+}
+
+#[test]
+fn long_chain_does_not_overflow() {
+    let quests = (0..100_000)
+        .map(|i| match i {
+            0 => quest(i, "Step", &[]),
+            _ => quest(i, "Step", &[i - 1]),
+        })
+        .collect();
+    let graph = condensation(&db(quests));
+    assert_eq!(graph.components.len(), 100_000);
+    assert!(!graph.has_cycles());
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use better_questing_tools::analysis::{ConsumeIssue, audit_consumption, detect_softlocks};
+use better_questing_tools::index::{ItemIndex, ItemKey};
+use better_questing_tools::model::Task;
+use better_questing_tools::parser::parse_quest_from_reader;
+use common::{db, item_reward, q, retrieval, stack};
+use serde_json::json;
+
+const COIN: &str = "dreamcraft:CoinAdventure";
+
+/// A named quest after `prereqs`; `repeat_time` -1 means not repeatable.
+fn quest(low: i32, prereqs: &[i32], repeat_time: i32) -> common::QuestBuilder {
+    common::quest(low)
+        .name(&format!("Quest {low}"))
+        .repeat_time(repeat_time)
+        .requires(prereqs)
+}
+
+/// A retrieval task consuming `count` coins.
+fn consumes(count: i32) -> Task {
+    Task {
+        consume: Some(true),
+        ..retrieval([stack(COIN, count)])
+    }
+}
+
+#[test]
+fn tasks_and_rewards_parse_from_bq_keys() {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": { "name:8": "Quest 1" } },
+        "tasks:9": { "0:10": {
+            "taskID:8": "bq_standard:retrieval",
+            "index:3": 0,
+            "consume:1": 1,
+            "autoConsume:1": 0,
+            "requiredItems:9": { "0:10": { "id:8": "minecraft:bread", "Count:3": 4, "Damage:2": 0 } },
+        }},
+        "rewards:9": { "0:10": {
+            "rewardID:8": "bq_standard:item",
+            "index:3": 0,
+            "rewards:9": { "0:10": { "id:8": "minecraft:apple", "Count:3": 2, "Damage:2": 0 } },
+        }},
+    });
+    let q = parse_quest_from_reader(v.to_string().as_bytes()).expect("parse quest");
+    assert_eq!(q.tasks.len(), 1);
+    assert_eq!(q.tasks[0].task_id, "bq_standard:retrieval");
+    assert_eq!(q.tasks[0].consume, Some(true));
+    assert_eq!(q.tasks[0].required_items[0].count, Some(4));
+    assert_eq!(q.rewards.len(), 1);
+    assert_eq!(q.rewards[0].items[0].id, "minecraft:apple");
+}
+
+#[test]
+fn empty_item_lists_parse_and_broken_tasks_are_errors() {
+    let mut v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": { "name:8": "Quest 1" } },
+        "tasks:9": { "0:10": {
+            "taskID:8": "bq_standard:retrieval",
+            "index:3": 0,
+            "requiredItems:9": {},
+        }},
+        "rewards:9": { "0:10": { "rewardID:8": "bq_standard:item", "index:3": 0, "rewards:9": {} }},
+    });
+    let q = parse_quest_from_reader(v.to_string().as_bytes()).expect("parse quest");
+    assert!(q.tasks[0].required_items.is_empty());
+    assert!(q.rewards[0].items.is_empty());
+
+    // a task that does not deserialize is reported, not dropped
+    v["tasks:9"]["0:10"]["requiredItems:9"] = json!("bread");
+    let err = parse_quest_from_reader(v.to_string().as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("task 0"), "{err}");
+}
+
+#[test]
+fn auto_consume_without_consume_is_flagged() {
+    let task = Task {
+        auto_consume: Some(true),
+        ..retrieval([stack("minecraft:dirt", 1)])
+    };
+    let db = db([quest(1, &[], -1).tasks([task]).build()], []);
+    let findings = audit_consumption(&db, &ItemIndex::build(&db));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].issue, ConsumeIssue::AutoConsumeWithoutConsume);
+}
+
+#[test]
+fn consuming_finite_reward_needed_later_is_flagged() {
+    // quest 1 hands out a coin once; quest 2 consumes it; quest 3 (after 2) needs it again
+    let db = db(
+        [
+            quest(1, &[], -1)
+                .rewards([item_reward([stack(COIN, 1)])])
+                .build(),
+            quest(2, &[1], -1).tasks([consumes(1)]).build(),
+            quest(3, &[2], -1)
+                .tasks([retrieval([stack(COIN, 1)])])
+                .build(),
+        ],
+        [],
+    );
+    let findings = audit_consumption(&db, &ItemIndex::build(&db));
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].quest, q(2));
+    match &findings[0].issue {
+        ConsumeIssue::ConsumesFiniteItemNeededLater { item, later } => {
+            assert_eq!(
+                item,
+                &ItemKey {
+                    id: COIN.into(),
+                    damage: 0
+                }
+            );
+            assert_eq!(later, &vec![q(3)]);
+        }
+        other => panic!("unexpected issue {other:?}"),
+    }
+}
+
+#[test]
+fn renewable_rewards_are_not_flagged() {
+    let db = db(
+        [
+            quest(1, &[], 72000)
+                .rewards([item_reward([stack(COIN, 1)])])
+                .build(),
+            quest(2, &[1], -1).tasks([consumes(1)]).build(),
+            quest(3, &[2], -1)
+                .tasks([retrieval([stack(COIN, 1)])])
+                .build(),
+        ],
+        [],
+    );
+    assert!(audit_consumption(&db, &ItemIndex::build(&db)).is_empty());
+}
+
+#[test]
+fn parallel_consumers_of_a_single_reward_softlock() {
+    // quest 1 grants one coin; quests 2 and 3 both consume it and only depend on 1
+    let db = db(
+        [
+            quest(1, &[], -1)
+                .rewards([item_reward([stack(COIN, 1)])])
+                .build(),
+            quest(2, &[1], -1).tasks([consumes(1)]).build(),
+            quest(3, &[1], -1).tasks([consumes(1)]).build(),
+        ],
+        [],
+    );
+    let locks = detect_softlocks(&db, &ItemIndex::build(&db));
+    assert_eq!(locks.len(), 2);
+    assert_eq!(locks[0].stranded_quest, q(2));
+    assert_eq!(locks[0].sequence, vec![q(1), q(3)]);
+    assert_eq!((locks[0].needed, locks[0].available), (1, 0));
+    assert_eq!(locks[1].stranded_quest, q(3));
+}
+
+#[test]
+fn sufficient_supply_has_no_softlock() {
+    let db = db(
+        [
+            quest(1, &[], -1)
+                .rewards([item_reward([stack(COIN, 2)])])
+                .build(),
+            quest(2, &[1], -1).tasks([consumes(1)]).build(),
+            quest(3, &[1], -1).tasks([consumes(1)]).build(),
+        ],
+        [],
+    );
+    assert!(detect_softlocks(&db, &ItemIndex::build(&db)).is_empty());
+}
+
+#[test]
+fn supplier_outside_the_ancestors_is_no_softlock() {
+    // quest 1 grants the coin quest 2 consumes, but 2 does not depend on 1
+    let db = db(
+        [
+            quest(1, &[], -1)
+                .rewards([item_reward([stack(COIN, 1)])])
+                .build(),
+            quest(2, &[], -1).tasks([consumes(1)]).build(),
+        ],
+        [],
+    );
+    assert!(detect_softlocks(&db, &ItemIndex::build(&db)).is_empty());
+}
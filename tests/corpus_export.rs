@@ -0,0 +1,88 @@
+mod common;
+
+use better_questing_tools::export::corpus;
+use better_questing_tools::export::corpus::{QuestDocument, to_jsonl};
+use better_questing_tools::model::*;
+use common::{db, line};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest(low: i32, props: serde_json::Value, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .properties(props)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn sample_db() -> QuestDatabase {
+    let mut tools = quest(
+        2,
+        json!({ "name": "§6Tools", "desc": "Craft a §lpickaxe§r.\n\nPress key.inventory to check." }),
+        &[1],
+        &[3],
+    );
+    tools.tasks.push(Task {
+        index: Some(0),
+        task_id: "bq_standard:retrieval".to_string(),
+        required_items: vec![ItemStack {
+            id: "minecraft:wooden_pickaxe".to_string(),
+            damage: None,
+            count: Some(1),
+            oredict: None,
+            extra: HashMap::new(),
+        }],
+        ignore_nbt: None,
+        partial_match: None,
+        auto_consume: None,
+        consume: None,
+        group_detect: None,
+        options: HashMap::new(),
+    });
+    db(
+        [
+            quest(1, json!({ "name": "Wood" }), &[], &[]),
+            tools,
+            quest(3, json!({ "name": "" }), &[], &[]),
+        ],
+        [line(100).name("§aBasics").entries(&[2]).build()],
+    )
+}
+
+#[test]
+fn documents_carry_clean_text_and_stable_ids() {
+    let docs = corpus(&sample_db());
+    let ids: Vec<&str> = docs.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(ids, ["quest:1", "quest:2", "quest:3"]);
+    assert_eq!(docs[2].name, "Quest 3");
+
+    let tools = &docs[1];
+    assert_eq!(tools.name, "Tools");
+    assert_eq!(tools.questlines, ["Basics"]);
+    assert_eq!(tools.prerequisites, ["Wood"]);
+    assert_eq!(tools.optional_prerequisites, ["Quest 3"]);
+    assert_eq!(
+        tools.tasks,
+        ["bq_standard:retrieval: 1x minecraft:wooden_pickaxe"]
+    );
+    assert!(!tools.description.contains('§'));
+    assert_eq!(
+        tools.text,
+        format!(
+            "Quest: Tools\nQuestline: Basics\nRequires: Wood\nRequires one of: Quest 3\n\n{}\n\nTasks:\n- bq_standard:retrieval: 1x minecraft:wooden_pickaxe",
+            tools.description
+        )
+    );
+    assert_eq!(docs[0].text, "Quest: Wood");
+}
+
+#[test]
+fn jsonl_has_one_document_per_line() {
+    let db = sample_db();
+    let out = to_jsonl(&db).unwrap();
+    let parsed: Vec<QuestDocument> = out
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(parsed, corpus(&db));
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use better_questing_tools::analysis::{SimilarityOptions, similar_quests};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::{retrieval, stack};
+
+fn q(high: i32, low: i32) -> QuestId {
+    QuestId::from_parts(high, low)
+}
+
+fn quest(id: QuestId, name: &str, items: &[&str]) -> Quest {
+    let quest = common::quest(0).id(id).name(name);
+    if items.is_empty() {
+        return quest.build();
+    }
+    quest
+        .tasks([retrieval(items.iter().map(|i| stack(i, 1)))])
+        .build()
+}
+
+fn db(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+#[test]
+fn finds_renumbered_and_retouched_quests() {
+    let left = db(vec![
+        quest(q(0, 1), "Stone Age", &["minecraft:cobblestone"]),
+        quest(
+            q(0, 2),
+            "Iron Tools",
+            &["minecraft:iron_pickaxe", "minecraft:iron_axe"],
+        ),
+        quest(q(0, 3), "Welcome", &[]),
+    ]);
+    let right = db(vec![
+        quest(q(7, 10), "§lStone  age", &["minecraft:cobblestone"]),
+        quest(
+            q(7, 11),
+            "Iron Tool",
+            &["minecraft:iron_pickaxe", "minecraft:iron_axe"],
+        ),
+        quest(q(7, 12), "Iron Armour", &["minecraft:iron_chestplate"]),
+        quest(q(7, 13), "Welcome!", &[]),
+    ]);
+    let matches = similar_quests(&left, &right, &SimilarityOptions::default());
+    let pairs: Vec<(QuestId, QuestId)> = matches.iter().map(|m| (m.left, m.right)).collect();
+    assert_eq!(
+        pairs,
+        vec![
+            (q(0, 1), q(7, 10)),
+            (q(0, 2), q(7, 11)),
+            (q(0, 3), q(7, 13))
+        ]
+    );
+    assert_eq!(matches[0].score, 1.0);
+    assert_eq!(matches[1].task_similarity, Some(1.0));
+    assert_eq!(matches[2].task_similarity, None);
+    assert_eq!(matches[2].score, 1.0 - 1.0 / 8.0);
+}
+
+#[test]
+fn min_score_controls_what_is_reported() {
+    let left = db(vec![quest(
+        q(0, 2),
+        "Iron Tools",
+        &["minecraft:iron_pickaxe"],
+    )]);
+    let right = db(vec![quest(
+        q(0, 5),
+        "Iron Armour",
+        &["minecraft:iron_chestplate"],
+    )]);
+    assert!(similar_quests(&left, &right, &SimilarityOptions::default()).is_empty());
+    let loose = SimilarityOptions {
+        min_score: 0.0,
+        ..Default::default()
+    };
+    let matches = similar_quests(&left, &right, &loose);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].task_similarity, Some(1.0 / 3.0));
+}
+
+#[test]
+fn a_shared_task_type_alone_does_not_pair_quests() {
+    let left = db(vec![quest(q(0, 1), "Sand", &["minecraft:sand"])]);
+    let right = db(vec![quest(q(0, 2), "Gravel", &["minecraft:gravel"])]);
+    let loose = SimilarityOptions {
+        min_score: 0.0,
+        ..Default::default()
+    };
+    assert!(similar_quests(&left, &right, &loose).is_empty());
+}
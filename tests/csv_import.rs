@@ -0,0 +1,102 @@
+mod common;
+
+use better_questing_tools::error::ParseError;
+use better_questing_tools::import::{CsvMapping, from_csv, import_csv};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::q;
+
+const SHEET: &str = "Name,Desc,Items,Prerequisites,Chapter\n\
+Getting Wood,Punch a tree.,16x minecraft:log,,Basics\n\
+Planks,\"Craft planks, lots of them.\",64x minecraft:planks; minecraft:stick,Getting Wood,Basics\n\
+Tools,,minecraft:wool:14,Planks;Stone Age,\n\
+Stone Age,,,Getting Wood,Basics\n";
+
+fn required(db: &QuestDatabase, id: QuestId) -> Vec<QuestId> {
+    db.quests[&id].required_prerequisites.clone()
+}
+
+#[test]
+fn rows_become_quests_with_resolved_prerequisites() {
+    let db = from_csv(SHEET.as_bytes(), &CsvMapping::default()).unwrap();
+    assert_eq!(db.quests.len(), 4);
+    let props = db.quests[&q(1)].properties.as_ref().unwrap();
+    assert_eq!(props.name, "Planks");
+    assert_eq!(props.desc.as_deref(), Some("Craft planks, lots of them."));
+    let items: Vec<String> = db.quests[&q(1)].tasks[0]
+        .required_items
+        .iter()
+        .map(ItemStack::to_string)
+        .collect();
+    assert_eq!(items, ["64x minecraft:planks", "1x minecraft:stick"]);
+    assert_eq!(props.icon.as_ref().unwrap().count, Some(1));
+    assert_eq!(db.quests[&q(2)].tasks[0].required_items[0].damage, Some(14));
+    assert!(db.quests[&q(3)].tasks.is_empty());
+
+    assert_eq!(required(&db, q(1)), [q(0)]);
+    // a forward reference to a later row
+    assert_eq!(required(&db, q(2)), [q(1), q(3)]);
+
+    assert_eq!(db.questline_order.len(), 1);
+    let line = &db.questlines[&db.questline_order[0]];
+    assert_eq!(line.properties.as_ref().unwrap().name, "Basics");
+    let on_line: Vec<QuestId> = line.entries.iter().map(|e| e.quest_id).collect();
+    assert_eq!(on_line, [q(0), q(1), q(3)]);
+    assert_eq!(line.entries[1].x, Some(32));
+    assert!(db.invariant_violations().is_empty());
+}
+
+#[test]
+fn rows_are_added_to_an_existing_pack() {
+    let mut db = from_csv(SHEET.as_bytes(), &CsvMapping::default()).unwrap();
+    let mapping = CsvMapping {
+        name: "Title".to_string(),
+        prerequisites: Some("After".to_string()),
+        chapter: Some("Line".to_string()),
+        ..Default::default()
+    };
+    // "stone age" differs from the pack's "Stone Age" only in case
+    let sheet = "title,after,line\nIron,stone age,Basics\nSteel,Iron,Metals\n";
+    let ids = import_csv(&mut db, sheet.as_bytes(), &mapping).unwrap();
+    assert_eq!(ids, [q(4), q(5)]);
+    assert_eq!(required(&db, q(4)), [q(3)]);
+    assert_eq!(required(&db, q(5)), [q(4)]);
+    assert_eq!(db.questline_order.len(), 2);
+    assert_eq!(db.questlines[&db.questline_order[0]].entries.len(), 4);
+}
+
+#[test]
+fn bad_rows_fail_without_changes() {
+    let mut db = from_csv(SHEET.as_bytes(), &CsvMapping::default()).unwrap();
+    let before = db.clone();
+    let cases = [
+        (
+            "name,prerequisites\nA,Nowhere\n",
+            "CSV record 2: no quest named \"Nowhere\"",
+        ),
+        (
+            "name,prerequisites\nA,\nA,\nB,A\n",
+            "CSV record 4: 2 quests are named \"A\"",
+        ),
+        (
+            "name,prerequisites\nA,A\n",
+            "CSV record 2: quest requires itself",
+        ),
+        (
+            "name,items\nA,lots minecraft:log\n",
+            "CSV record 2: invalid item count",
+        ),
+        ("title\nA\n", "CSV has no \"name\" column"),
+        (
+            "name,desc\n,Orphan text\n",
+            "CSV record 2: missing quest name",
+        ),
+    ];
+    for (sheet, message) in cases {
+        match import_csv(&mut db, sheet.as_bytes(), &CsvMapping::default()) {
+            Err(ParseError::InvalidFormat(m)) => assert!(m.starts_with(message), "{}", m),
+            other => panic!("{:?} for {:?}", other, sheet),
+        }
+    }
+    assert_eq!(db, before);
+}
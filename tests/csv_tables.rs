@@ -0,0 +1,80 @@
+use better_questing_tools::export::csv::to_tables;
+use better_questing_tools::model::QuestDatabase;
+use better_questing_tools::parser::parse_quest_from_reader;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[test]
+fn database_flattens_into_four_tables() {
+    let quests = [
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": {
+                "name:8": "§aWood, and more", "desc:8": "Chop \"trees\"\n", "isMain:1": 1,
+            }},
+            "tasks:9": {
+                "0:10": { "taskID:8": "bq_standard:retrieval", "index:3": 0, "consume:1": 1,
+                    "requiredItems:9": {
+                        "0:10": { "id:8": "minecraft:log", "Count:3": 16, "Damage:2": 2, "OreDict:8": "" },
+                        "1:10": { "id:8": "minecraft:planks", "Count:3": 4, "Damage:2": 0, "OreDict:8": "plankWood" },
+                    }},
+                "1:10": { "taskID:8": "bq_standard:checkbox", "index:3": 1 },
+            },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:choice", "index:3": 0,
+                "choices:9": { "0:10": { "id:8": "minecraft:bread", "Count:3": 2 } } } },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 2,
+            "properties:10": { "betterquesting:10": { "name:8": "Tools" } },
+            "preRequisites:9": {
+                "0:10": { "questIDHigh:4": 0, "questIDLow:4": 1 },
+                "1:10": { "questIDHigh:4": 0, "questIDLow:4": 7 },
+            },
+            "optionalPreRequisites:9": { "0:10": { "questIDHigh:4": 0, "questIDLow:4": 7 } },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:xp", "index:3": 0 } },
+        }),
+    ];
+    let db = QuestDatabase {
+        settings: None,
+        quests: quests
+            .iter()
+            .map(|v| parse_quest_from_reader(v.to_string().as_bytes()).unwrap())
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+
+    let t = to_tables(&db);
+    assert_eq!(
+        t.quests,
+        "id,name,description,questlines,is_main,quest_logic,task_logic,repeat_time,visibility,tasks,rewards\n\
+         1,\"Wood, and more\",\"Chop \"\"trees\"\"\",,true,,,,,2,1\n\
+         2,Tools,,,,,,,,0,1\n"
+    );
+    assert_eq!(
+        t.tasks,
+        "quest_id,task_index,task_id,item,damage,count,oredict,consume\n\
+         1,0,bq_standard:retrieval,minecraft:log,2,16,,true\n\
+         1,0,bq_standard:retrieval,minecraft:planks,0,4,plankWood,true\n\
+         1,1,bq_standard:checkbox,,,,,\n"
+    );
+    assert_eq!(
+        t.rewards,
+        "quest_id,reward_index,reward_id,choice,item,damage,count,oredict\n\
+         1,0,bq_standard:choice,true,minecraft:bread,,2,\n\
+         2,0,bq_standard:xp,false,,,,\n"
+    );
+    assert_eq!(
+        t.prereqs,
+        "quest_id,prerequisite_id,optional\n2,1,false\n2,7,true\n"
+    );
+
+    let dir = std::env::temp_dir().join(format!("bqt-csv-tables-{}", std::process::id()));
+    t.write_to_dir(&dir).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("prereqs.csv")).unwrap(),
+        t.prereqs
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use better_questing_tools::export::cypher::{to_cypher, to_import_csv};
+use better_questing_tools::model::*;
+use common::q;
+use std::collections::HashMap;
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .name(name)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+#[test]
+fn cypher_script_and_import_csv() {
+    let lid = q(100);
+    let line_props: QuestLineProperties =
+        serde_json::from_value(serde_json::json!({ "name": "§6Getting Started" })).unwrap();
+    let db = QuestDatabase {
+        settings: None,
+        quests: [
+            quest(1, "Steve's Start", &[], &[]),
+            quest(2, "Wood", &[1, 99], &[]),
+            quest(3, "Tools", &[2], &[1]),
+        ]
+        .into_iter()
+        .map(|q| (q.id, q))
+        .collect(),
+        questlines: HashMap::from([(
+            lid,
+            QuestLine {
+                id: lid,
+                properties: Some(line_props),
+                entries: vec![QuestLineEntry {
+                    index: None,
+                    quest_id: q(1),
+                    x: None,
+                    y: None,
+                    size_x: None,
+                    size_y: None,
+                    extra: HashMap::new(),
+                }],
+                extra: HashMap::new(),
+            },
+        )]),
+        questline_order: vec![lid],
+    };
+
+    let cypher = to_cypher(&db);
+    let lines: Vec<&str> = cypher.lines().collect();
+    assert_eq!(lines.len(), 1 + 3 + 3);
+    assert_eq!(
+        lines[1],
+        r"CREATE (:Quest {id: '1', name: 'Steve\'s Start', questlines: ['Getting Started']});"
+    );
+    assert_eq!(
+        lines[6],
+        "MATCH (a:Quest {id: '3'}), (b:Quest {id: '2'}) CREATE (a)-[:REQUIRES {optional: false}]->(b);"
+    );
+    // the dangling prerequisite 99 is skipped
+    assert!(!cypher.contains("'99'"));
+
+    let (nodes, rels) = to_import_csv(&db);
+    assert_eq!(
+        nodes.lines().take(2).collect::<Vec<_>>(),
+        vec![
+            "id:ID(Quest),name,questlines:string[],:LABEL",
+            "1,Steve's Start,Getting Started,Quest",
+        ]
+    );
+    assert_eq!(
+        rels,
+        ":START_ID(Quest),:END_ID(Quest),optional:boolean,:TYPE\n\
+         2,1,false,REQUIRES\n3,1,true,REQUIRES\n3,2,false,REQUIRES\n"
+    );
+}
+
+#[test]
+fn legacy_prerequisites_are_linked() {
+    let mut legacy = quest(2, "Wood", &[1], &[]);
+    legacy.required_prerequisites.clear();
+    let db = common::db([quest(1, "Start", &[], &[]), legacy], []);
+    let (_, rels) = to_import_csv(&db);
+    assert_eq!(
+        rels,
+        ":START_ID(Quest),:END_ID(Quest),optional:boolean,:TYPE\n2,1,false,REQUIRES\n"
+    );
+}
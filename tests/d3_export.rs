@@ -0,0 +1,77 @@
+mod common;
+
+use better_questing_tools::export::d3::{D3Graph, to_d3, to_d3_json};
+use better_questing_tools::importance::compute_importance_scores;
+use better_questing_tools::model::*;
+use common::db;
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .name(name)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn line(low: i32, name: &str, quests: &[i32]) -> QuestLine {
+    common::line(low).name(name).entries(quests).build()
+}
+
+fn sample_db() -> QuestDatabase {
+    db(
+        [
+            quest(1, "Start", &[], &[]),
+            quest(2, "§aWood", &[1, 99], &[]),
+            quest(3, "Tools", &[2], &[1]),
+            quest(4, "Loose", &[], &[]),
+        ],
+        [line(100, "§6Basics", &[1, 2]), line(101, "Tools", &[3, 2])],
+    )
+}
+
+#[test]
+fn nodes_are_grouped_by_questline_and_links_follow_prerequisites() {
+    let db = sample_db();
+    let graph = to_d3(&db, None);
+    let nodes: Vec<(&str, &str, usize)> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), n.name.as_str(), n.group))
+        .collect();
+    assert_eq!(
+        nodes,
+        [
+            ("1", "Start", 1),
+            ("2", "Wood", 1),
+            ("3", "Tools", 2),
+            ("4", "Loose", 0)
+        ]
+    );
+    assert_eq!(graph.nodes[1].questline.as_deref(), Some("Basics"));
+    assert_eq!(graph.nodes[3].questline, None);
+    assert!(graph.nodes.iter().all(|n| n.value == 0.0));
+
+    let links: Vec<(&str, &str, bool)> = graph
+        .links
+        .iter()
+        .map(|l| (l.source.as_str(), l.target.as_str(), l.optional))
+        .collect();
+    assert_eq!(
+        links,
+        [("1", "2", false), ("1", "3", true), ("2", "3", false)]
+    );
+}
+
+#[test]
+fn json_carries_importance_values() {
+    let db = sample_db();
+    let scores = compute_importance_scores(&db, 0.5, false, true).unwrap();
+    let text = to_d3_json(&db, Some(&scores)).unwrap();
+    let v: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(v["nodes"][0]["id"], "1");
+    assert!(v["nodes"][3].get("questline").is_none());
+    assert!(v["links"][0]["value"].as_f64().unwrap() > 0.0);
+    let graph: D3Graph = serde_json::from_str(&text).unwrap();
+    let start = graph.nodes.iter().find(|n| n.id == "1").unwrap();
+    assert!(start.value > graph.nodes.iter().find(|n| n.id == "4").unwrap().value);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use better_questing_tools::diff::{PropertyChange, QuestChange, compare};
+use better_questing_tools::export::changelog::{Verbosity, to_markdown};
+use better_questing_tools::model::*;
+use common::q;
+use serde_json::{Value, json};
+
+fn props<T: serde::de::DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+fn quest(low: i32, properties: Value, prereqs: &[i32]) -> Quest {
+    common::quest(low)
+        .properties(properties)
+        .requires(prereqs)
+        .build()
+}
+
+fn db(quests: Vec<Quest>, line_props: Value) -> QuestDatabase {
+    let mut line = common::line(100).build();
+    line.properties = Some(props(line_props));
+    common::db(quests, [line])
+}
+
+#[test]
+fn property_changes_are_reported_per_field() {
+    let old = db(
+        vec![
+            quest(1, json!({ "name": "Start", "quest_logic": "AND" }), &[]),
+            quest(2, json!({ "name": "Wood", "desc": "Chop" }), &[1]),
+        ],
+        json!({ "name": "Tier 1", "visibility": "NORMAL" }),
+    );
+    let new = db(
+        vec![
+            quest(
+                1,
+                json!({ "name": "Start", "quest_logic": "OR", "repeat_time": 100 }),
+                &[],
+            ),
+            quest(2, json!({ "name": "Logs", "desc": "Chop trees" }), &[]),
+            quest(3, json!({ "name": "Stone" }), &[2]),
+        ],
+        json!({ "name": "Tier 1", "visibility": "HIDDEN" }),
+    );
+    let diff = compare(&old, &new);
+
+    assert_eq!(diff.added_quests[0].id, q(3));
+    assert_eq!(diff.changed_quests.len(), 2);
+    assert_eq!(
+        diff.changed_quests[0].changes,
+        vec![QuestChange::PropertiesChanged {
+            fields: vec![
+                PropertyChange {
+                    field: "quest_logic".to_string(),
+                    old: Some(json!("AND")),
+                    new: Some(json!("OR")),
+                },
+                PropertyChange {
+                    field: "repeat_time".to_string(),
+                    old: None,
+                    new: Some(json!(100)),
+                },
+            ]
+        }]
+    );
+    // name and description have their own change kinds
+    assert_eq!(
+        diff.changed_quests[1].changes,
+        vec![
+            QuestChange::Renamed {
+                old: "Wood".to_string(),
+                new: "Logs".to_string()
+            },
+            QuestChange::DescriptionChanged,
+            QuestChange::PrerequisitesChanged {
+                added: vec![],
+                removed: vec![q(1)]
+            },
+        ]
+    );
+    assert_eq!(
+        diff.changed_questlines[0].properties,
+        vec![PropertyChange {
+            field: "visibility".to_string(),
+            old: Some(json!("NORMAL")),
+            new: Some(json!("HIDDEN")),
+        }]
+    );
+
+    let v = serde_json::to_value(&diff).unwrap();
+    assert_eq!(
+        v["changed_quests"][0]["changes"][0]["kind"],
+        "properties_changed"
+    );
+    assert_eq!(
+        v["changed_quests"][0]["changes"][0]["fields"][0]["field"],
+        "quest_logic"
+    );
+
+    let md = to_markdown(&diff, Verbosity::Detailed);
+    assert!(md.contains("Setting quest_logic of 'Start' changed from AND to OR"));
+    assert!(md.contains("Setting repeat_time of 'Start' changed from unset to 100"));
+    assert!(md.contains("Setting visibility of questline 'Tier 1' changed from NORMAL to HIDDEN"));
+    let md = to_markdown(&diff, Verbosity::Normal);
+    assert!(md.contains("Settings of 'Start' changed (quest_logic, repeat_time)"));
+}
+
+#[test]
+fn identical_databases_have_an_empty_diff() {
+    let a = db(
+        vec![quest(1, json!({ "name": "Start" }), &[])],
+        json!({ "name": "L" }),
+    );
+    assert!(compare(&a, &a.clone()).is_empty());
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::validate::InvariantViolation;
+use common::{db, q};
+
+fn quest(low: i32) -> Quest {
+    common::quest(low).build()
+}
+
+fn line(low: i32, quests: &[i32]) -> QuestLine {
+    common::line(low).entries(quests).build()
+}
+
+fn sample() -> QuestDatabase {
+    db([quest(1), quest(2)], [line(10, &[1, 2])])
+}
+
+#[test]
+fn consistent_database_passes() {
+    let db = sample();
+    assert!(db.invariant_violations().is_empty());
+    db.validate_invariants().unwrap();
+}
+
+#[test]
+fn broken_rules_are_reported_in_order() {
+    let mut db = sample();
+    db.quests.insert(q(3), quest(4));
+    db.questlines.get_mut(&q(10)).unwrap().entries = line(10, &[2, 9, 2, 1]).entries;
+    db.questlines.insert(q(11), line(12, &[]));
+    db.questline_order = vec![q(10), q(20), q(10)];
+
+    assert_eq!(
+        db.invariant_violations(),
+        vec![
+            InvariantViolation::QuestKeyMismatch {
+                key: q(3),
+                id: q(4)
+            },
+            InvariantViolation::QuestlineKeyMismatch {
+                key: q(11),
+                id: q(12)
+            },
+            InvariantViolation::MissingEntryQuest {
+                questline: q(10),
+                quest: q(9)
+            },
+            InvariantViolation::DuplicateEntry {
+                questline: q(10),
+                quest: q(2)
+            },
+            InvariantViolation::MissingOrderedQuestline { questline: q(20) },
+            InvariantViolation::DuplicateOrderedQuestline { questline: q(10) },
+        ]
+    );
+    let err = db.validate_invariants().unwrap_err().to_string();
+    assert!(err.contains("quest 4 is stored under key 3"), "{err}");
+}
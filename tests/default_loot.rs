@@ -0,0 +1,102 @@
+use better_questing_tools::db::MemoryDataSource;
+use better_questing_tools::default_loot::{
+    LOOT_CHEST_ITEM, LootTable, parse_default_loot_from_source,
+};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use serde_json::json;
+
+fn loot_file() -> serde_json::Value {
+    let group = |id: i32, name: &str, weight: i32, item: &str| {
+        json!({
+            "ID:3": id,
+            "name:8": name,
+            "weight:3": weight,
+            "rewards:9": {
+                "0:10": {
+                    "weight:3": 3,
+                    "items:9": { "0:10": { "id:8": item, "Count:3": 2, "Damage:2": 0 } }
+                }
+            }
+        })
+    };
+    json!({
+        "groups:9": {
+            "0:10": group(0, "Common", 60, "minecraft:iron_ingot"),
+            "1:10": group(1, "Uncommon", 30, "minecraft:gold_ingot"),
+            "2:10": group(7, "Rare", 10, "minecraft:diamond"),
+        }
+    })
+}
+
+fn quest_with_chest(damage: i32) -> Quest {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": damage,
+        "properties:10": { "betterquesting:10": { "name:8": "Loot" } },
+        "rewards:9": {
+            "0:10": {
+                "rewardID:8": "bq_standard:item",
+                "rewards:9": { "0:10": { "id:8": LOOT_CHEST_ITEM, "Count:3": 1, "Damage:2": damage } }
+            }
+        }
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+#[test]
+fn parses_groups_and_pools() {
+    let table = LootTable::from_value(loot_file()).unwrap();
+    assert_eq!(table.groups.len(), 3);
+    let rare = table.group(7).unwrap();
+    assert_eq!(rare.name, "Rare");
+    assert_eq!(rare.weight, 10);
+    assert_eq!(rare.entries[0].weight, 3);
+    assert_eq!(rare.entries[0].items[0].id, "minecraft:diamond");
+    assert_eq!(rare.entries[0].items[0].count, Some(2));
+}
+
+#[test]
+fn is_found_next_to_the_quests_folder() {
+    let mut source = MemoryDataSource::new();
+    source.insert(
+        "config/betterquesting/DefaultLoot.json",
+        loot_file().to_string(),
+    );
+    let table = parse_default_loot_from_source(&source, "config/betterquesting/DefaultQuests")
+        .unwrap()
+        .unwrap();
+    assert_eq!(table.groups.len(), 3);
+    assert!(
+        parse_default_loot_from_source(&MemoryDataSource::new(), "DefaultQuests")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn chests_link_to_groups_by_rarity() {
+    let table = LootTable::from_value(loot_file()).unwrap();
+    // sorted by weight: Rare (0..10], Uncommon (10..40], Common (40..100]
+    assert_eq!(table.groups_for_rarity(0), vec![7, 1]);
+    assert_eq!(table.groups_for_rarity(100), vec![0]);
+
+    let db = QuestDatabase {
+        quests: [quest_with_chest(0), quest_with_chest(101)]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        ..Default::default()
+    };
+    let chests = table.chests(&db);
+    assert_eq!(chests.len(), 2);
+    assert_eq!(chests[0].rarity, 0);
+    assert_eq!(chests[0].groups, vec![7, 1]);
+    assert_eq!(chests[1].rarity, 101);
+    assert!(chests[1].groups.is_empty());
+}
+
+#[test]
+fn missing_groups_is_an_error() {
+    assert!(LootTable::from_value(json!({ "other:8": "x" })).is_err());
+}
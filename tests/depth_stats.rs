@@ -0,0 +1,92 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::stats::{
+    DepthStats, depth_histogram, depth_percentile, quest_depth_ranges, quest_depths,
+};
+use common::q;
+
+fn quest(low: i32, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn db(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+#[test]
+fn depth_is_the_longest_prerequisite_chain() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1], &[]),
+        quest(3, &[2], &[1]),
+        quest(4, &[1], &[]),
+        quest(5, &[99], &[]), // missing prerequisite is ignored
+        // 6 and 7 require each other and share depth 1 behind quest 1
+        quest(6, &[1, 7], &[]),
+        quest(7, &[6], &[]),
+        quest(8, &[7], &[]),
+    ]);
+    let depths = quest_depths(&db);
+    assert_eq!(depths[&q(1)], 0);
+    assert_eq!(depths[&q(3)], 2);
+    assert_eq!(depths[&q(5)], 0);
+    assert_eq!(depths[&q(6)], depths[&q(7)]);
+    assert_eq!(depths[&q(8)], 2);
+
+    let histogram = depth_histogram(&db);
+    assert_eq!(histogram, vec![2, 4, 2]);
+    assert_eq!(depth_percentile(&histogram, 50.0), 1);
+    assert_eq!(depth_percentile(&histogram, 90.0), 2);
+    assert_eq!(depth_percentile(&histogram, 0.0), 0);
+    assert_eq!(
+        DepthStats::of(&db),
+        DepthStats {
+            median: 1,
+            p90: 2,
+            max: 2
+        }
+    );
+}
+
+#[test]
+fn empty_database_has_zero_depth() {
+    let db = db(vec![]);
+    assert!(depth_histogram(&db).is_empty());
+    assert_eq!(DepthStats::of(&db), DepthStats::default());
+}
+
+#[test]
+fn depth_ranges_span_shortest_and_longest_chains() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1], &[]),
+        quest(3, &[2], &[]),
+        // reachable right after 1, or after the 1 -> 2 -> 3 chain
+        quest(4, &[1, 3], &[]),
+        quest(5, &[4], &[]),
+        // a cycle without outside prerequisites is a root
+        quest(6, &[7], &[]),
+        quest(7, &[6], &[]),
+        quest(8, &[7, 5], &[]),
+    ]);
+    let ranges = quest_depth_ranges(&db);
+    let range = |low: i32| {
+        let r = ranges[&q(low)];
+        (r.min, r.max)
+    };
+    assert_eq!(range(1), (0, 0));
+    assert_eq!(range(4), (1, 3));
+    assert_eq!(range(5), (2, 4));
+    assert_eq!(range(6), (0, 0));
+    assert_eq!(range(8), (1, 5));
+    let depths = quest_depths(&db);
+    assert!(
+        ranges
+            .iter()
+            .all(|(q, r)| depths[q] == r.max && r.min <= r.max)
+    );
+}
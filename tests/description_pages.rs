@@ -0,0 +1,81 @@
+use better_questing_tools::db::{parse_default_quests_dir, write_default_quests_dir};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::text::{render_description, render_pages};
+use serde_json::{Value, json};
+
+fn parse(props: Value) -> Quest {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": props },
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+#[test]
+fn single_description_has_no_extra_pages() {
+    let quest = parse(json!({ "name:8": "Plain", "desc:8": "Only page" }));
+    let props = quest.properties.unwrap();
+    assert_eq!(props.desc.as_deref(), Some("Only page"));
+    assert!(props.desc_pages.is_empty());
+    assert_eq!(props.pages(), ["Only page"]);
+}
+
+#[test]
+fn description_list_becomes_pages() {
+    let quest = parse(json!({
+        "name:8": "Listed",
+        "desc:9": { "0:8": "§lFirst", "1:8": "Second", "2:8": "Third" },
+    }));
+    let props = quest.properties.unwrap();
+    assert_eq!(props.desc.as_deref(), Some("§lFirst"));
+    assert_eq!(props.desc_pages, ["Second", "Third"]);
+    assert_eq!(render_pages(&props), ["First", "Second", "Third"]);
+    assert_eq!(render_description(&props), "First\n\nSecond\n\nThird");
+}
+
+#[test]
+fn numbered_description_keys_become_pages_in_order() {
+    let quest = parse(json!({
+        "name:8": "Numbered",
+        "desc:8": "One",
+        "desc10:8": "Ten",
+        "desc2:8": "Two",
+        "desc3:8": "Three",
+        "descColor:8": "not a page",
+    }));
+    let props = quest.properties.unwrap();
+    assert_eq!(props.pages(), ["One", "Two", "Three", "Ten"]);
+    assert!(!props.extra.contains_key("desc2"));
+    assert!(props.extra.contains_key("descColor"));
+}
+
+#[test]
+fn pages_survive_a_write_round_trip() {
+    let quest = parse(json!({
+        "name:8": "Round trip",
+        "desc:9": { "0:8": "First", "1:8": "Second" },
+        "desc3:8": "Third",
+    }));
+    let db = QuestDatabase {
+        settings: None,
+        quests: [(quest.id, quest)].into_iter().collect(),
+        questlines: Default::default(),
+        questline_order: Vec::new(),
+    };
+    let dir = std::env::temp_dir().join(format!("bqt-desc-pages-{}", std::process::id()));
+    write_default_quests_dir(&db, &dir).unwrap();
+    let back = parse_default_quests_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let props = back
+        .quests
+        .values()
+        .next()
+        .unwrap()
+        .properties
+        .clone()
+        .unwrap();
+    assert_eq!(props.pages(), ["First", "Second", "Third"]);
+}
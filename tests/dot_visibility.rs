@@ -0,0 +1,74 @@
+mod common;
+
+use better_questing_tools::export::dot::{
+    ConcealedQuests, DotOptions, quest_graph_to_dot, quest_graph_to_dot_with,
+};
+use better_questing_tools::model::*;
+use common::{db, q};
+
+fn quest(low: i32, name: &str, required: &[i32], visibility: Option<&str>) -> Quest {
+    common::quest(low)
+        .properties(serde_json::json!({ "name": name, "visibility": visibility }))
+        .requires(required)
+        .build()
+}
+
+fn sample_db() -> QuestDatabase {
+    db(
+        [
+            quest(1, "Start", &[], Some("ALWAYS")),
+            quest(2, "Boss", &[1], Some("secret")),
+            quest(3, "Cake", &[2], Some("HIDDEN")),
+            quest(4, "Wood", &[1], None),
+        ],
+        [],
+    )
+}
+
+#[test]
+fn visibility_is_parsed_ignoring_case() {
+    let db = sample_db();
+    assert_eq!(db.quests[&q(1)].visibility(), Some(QuestVisibility::Always));
+    assert_eq!(db.quests[&q(2)].visibility(), Some(QuestVisibility::Secret));
+    assert_eq!(db.quests[&q(4)].visibility(), None);
+    assert_eq!(QuestVisibility::parse("sometimes"), None);
+    assert!(QuestVisibility::Hidden.is_concealed());
+    assert!(!QuestVisibility::Completed.is_concealed());
+}
+
+#[test]
+fn show_matches_the_plain_export() {
+    let db = sample_db();
+    assert_eq!(
+        quest_graph_to_dot_with(&db, &DotOptions::default()),
+        quest_graph_to_dot(&db)
+    );
+}
+
+#[test]
+fn dim_hides_names_of_concealed_quests() {
+    let dot = quest_graph_to_dot_with(
+        &sample_db(),
+        &DotOptions {
+            concealed: ConcealedQuests::Dim,
+        },
+    );
+    assert!(!dot.contains("Boss") && !dot.contains("Cake"));
+    assert!(dot.contains("  q2 [label=\"??? (2)\", style=dashed, color=gray, fontcolor=gray]\n"));
+    assert!(dot.contains("  q1 -> q2 [weight=1.000000, label=\"1.000000\", color=gray]\n"));
+    assert!(dot.contains("  q1 -> q4 [weight=1.000000, label=\"1.000000\"]\n"));
+}
+
+#[test]
+fn exclude_drops_concealed_quests_and_counts_them() {
+    let dot = quest_graph_to_dot_with(
+        &sample_db(),
+        &DotOptions {
+            concealed: ConcealedQuests::Exclude,
+        },
+    );
+    assert_eq!(
+        dot,
+        "digraph quests {\n  label=\"2 hidden quests not shown\"\n  q1 [label=\"Start (1)\"]\n  q4 [label=\"Wood (4)\"]\n  q1 -> q4 [weight=1.000000, label=\"1.000000\"]\n}\n"
+    );
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::validate::{
+    DuplicateNameScope, ValidationIssue, ValidationOptions, validate, validate_with,
+};
+use common::{db, q};
+
+fn quest(low: i32, name: &str) -> Quest {
+    common::quest(low).name(name).build()
+}
+
+fn line(id: i32, quests: &[i32]) -> QuestLine {
+    common::line(id).entries(quests).build()
+}
+
+fn sample() -> QuestDatabase {
+    db(
+        [
+            quest(1, "Chapter Complete"),
+            quest(2, "§6Chapter Complete "),
+            quest(3, "Steel"),
+            quest(4, "Steel"),
+            quest(5, ""),
+            quest(6, ""),
+        ],
+        // the chapter finale is reused on purpose; the steel quest is a real
+        // duplicate inside one line
+        [line(100, &[1, 3, 4, 5, 6]), line(101, &[2, 3])],
+    )
+}
+
+#[test]
+fn duplicate_names_are_scoped_per_questline_by_default() {
+    assert_eq!(
+        validate(&sample()),
+        vec![ValidationIssue::DuplicateName {
+            name: "Steel".to_string(),
+            quests: vec![q(3), q(4)],
+            questline: Some(q(100)),
+        }]
+    );
+}
+
+#[test]
+fn global_scope_and_off() {
+    let global = ValidationOptions {
+        duplicate_names: DuplicateNameScope::Global,
+    };
+    assert_eq!(
+        validate_with(&sample(), &global),
+        vec![
+            ValidationIssue::DuplicateName {
+                name: "Chapter Complete".to_string(),
+                quests: vec![q(1), q(2)],
+                questline: None,
+            },
+            ValidationIssue::DuplicateName {
+                name: "Steel".to_string(),
+                quests: vec![q(3), q(4)],
+                questline: None,
+            },
+        ]
+    );
+    let off: ValidationOptions =
+        serde_json::from_value(serde_json::json!({ "duplicate_names": "off" })).unwrap();
+    assert!(validate_with(&sample(), &off).is_empty());
+}
@@ -0,0 +1,308 @@
+mod common;
+
+use better_questing_tools::db::{
+    QuestDataSink, parse_default_quests_dir, write_changes, write_changes_to_sink,
+    write_default_quests_dir,
+};
+use better_questing_tools::edit::{DatabaseChange, Editor, PrereqPositions};
+use better_questing_tools::error::Result;
+use better_questing_tools::model::*;
+use common::{db, line, q};
+use serde_json::json;
+
+fn quest(low: i32, prereqs: &[i32]) -> Quest {
+    common::quest(low)
+        .name(&format!("Quest {low}"))
+        .requires(prereqs)
+        .build()
+}
+
+fn entry(low: i32, x: i32) -> QuestLineEntry {
+    QuestLineEntry {
+        x: Some(x),
+        y: Some(0),
+        size_x: Some(24),
+        size_y: Some(24),
+        ..common::entry(low)
+    }
+}
+
+fn sample() -> QuestDatabase {
+    let line = line(100)
+        .name("Chapter")
+        .entry(entry(1, 0))
+        .entry(entry(2, 30))
+        .entry(entry(3, 60))
+        .build();
+    db([quest(1, &[]), quest(2, &[1]), quest(3, &[2])], [line])
+}
+
+#[test]
+fn edits_undo_and_redo_through_change_events() {
+    let original = sample();
+    let mut editor = Editor::new(original.clone());
+    editor
+        .set_property(q(1), "name", Some(json!("First steps")))
+        .unwrap();
+    editor.move_entry(q(100), q(3), 90, 30).unwrap();
+    editor.remove_quest(q(2)).unwrap();
+
+    let db = editor.db();
+    assert_eq!(db.quests[&q(1)].name(), Some("First steps"));
+    assert!(db.quests[&q(3)].prerequisites.is_empty());
+    let entries: Vec<_> = db.questlines[&q(100)]
+        .entries
+        .iter()
+        .map(|e| (e.quest_id, e.x))
+        .collect();
+    assert_eq!(entries, vec![(q(1), Some(0)), (q(3), Some(90))]);
+
+    // removing a quest is one step made of several events
+    let changes = editor.take_changes();
+    assert_eq!(changes.len(), 5);
+    assert!(matches!(
+        changes[2],
+        DatabaseChange::PrereqRemoved { quest, prerequisite, positions }
+            if quest == q(3) && prerequisite == q(2)
+                && positions.required_prerequisites == Some(0)
+                && positions.optional_prerequisites.is_none()
+    ));
+    assert_eq!(
+        serde_json::to_value(&changes[1]).unwrap()["kind"],
+        "entry_moved"
+    );
+
+    while editor.undo().unwrap() {}
+    assert_eq!(editor.db(), &original);
+    assert_eq!(editor.take_changes().len(), 5);
+    assert!(editor.redo().unwrap());
+    assert_eq!(editor.db().quests[&q(1)].name(), Some("First steps"));
+    assert!(editor.can_redo());
+
+    // a failed edit leaves the database and history untouched
+    let before = editor.db().clone();
+    assert!(editor.add_quest(quest(1, &[])).is_err());
+    assert!(editor.set_property(q(1), "name", None).is_err());
+    assert_eq!(editor.db(), &before);
+    assert!(editor.can_redo());
+
+    // so does writing a value the quest already has
+    editor
+        .set_property(q(1), "name", Some(json!("First steps")))
+        .unwrap();
+    assert!(editor.can_redo());
+}
+
+/// Records the paths the writer touches.
+#[derive(Default)]
+struct Recorder {
+    written: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl QuestDataSink for Recorder {
+    fn create_dir_all(&mut self, _path: &str) -> Result<()> {
+        Ok(())
+    }
+    fn write_file(&mut self, path: &str, _contents: &str) -> Result<()> {
+        self.written.push(path.to_string());
+        Ok(())
+    }
+    fn remove_file(&mut self, path: &str) -> Result<()> {
+        self.removed.push(path.to_string());
+        Ok(())
+    }
+}
+
+#[test]
+fn writer_rewrites_only_touched_files() {
+    let mut editor = Editor::new(sample());
+    editor.move_entry(q(100), q(3), 90, 30).unwrap();
+    editor.add_prerequisite(q(3), q(1), true).unwrap();
+    editor.remove_quest(q(2)).unwrap();
+    editor.add_quest(quest(4, &[3])).unwrap();
+    editor.add_entry(q(100), entry(4, 120)).unwrap();
+    let changes = editor.take_changes();
+
+    let mut sink = Recorder::default();
+    write_changes_to_sink(&mut sink, "root", editor.db(), &changes).unwrap();
+    assert_eq!(
+        sink.written,
+        vec![
+            "root/Quests/3.json",
+            "root/Quests/4.json",
+            "root/QuestLines/0/1.json",
+            "root/QuestLines/0/2.json",
+        ]
+    );
+    assert_eq!(sink.removed, vec!["root/Quests/2.json"]);
+
+    // on disk, the patched folder parses to the edited database
+    let root = std::env::temp_dir().join(format!("bqt-edit-{}", std::process::id()));
+    write_default_quests_dir(&sample(), &root).unwrap();
+    write_changes(editor.db(), &changes, &root).unwrap();
+    let parsed = parse_default_quests_dir(&root).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(parsed.quests.len(), 3);
+    assert_eq!(parsed.quests[&q(3)].optional_prerequisites, vec![q(1)]);
+    let line = &parsed.questlines[&q(100)];
+    let entries: Vec<_> = line.entries.iter().map(|e| (e.quest_id, e.x)).collect();
+    assert_eq!(
+        entries,
+        vec![(q(1), Some(0)), (q(3), Some(90)), (q(4), Some(120))]
+    );
+}
+
+#[test]
+fn transactions_group_edits_into_one_undo_step() {
+    let original = sample();
+    let mut editor = Editor::new(original.clone());
+    editor.begin();
+    editor.move_entry(q(100), q(1), 10, 10).unwrap();
+    editor.begin();
+    editor.remove_quest(q(3)).unwrap();
+    editor.commit().unwrap();
+    editor
+        .set_property(q(2), "quest_logic", Some(json!("OR")))
+        .unwrap();
+    assert!(editor.undo().is_err());
+    editor.commit().unwrap();
+    assert!(editor.commit().is_err());
+
+    assert_eq!(editor.db().quests.len(), 2);
+    assert!(editor.undo().unwrap());
+    assert_eq!(editor.db(), &original);
+    assert!(!editor.can_undo());
+    assert!(editor.redo().unwrap());
+    assert_eq!(editor.db().quests.len(), 2);
+
+    // rolled back edits leave no undo step behind
+    let committed = editor.db().clone();
+    editor.take_changes();
+    editor.begin();
+    editor.add_quest(quest(9, &[1])).unwrap();
+    editor.move_entry(q(100), q(2), 0, 90).unwrap();
+    editor.rollback().unwrap();
+    assert!(!editor.in_transaction());
+    assert_eq!(editor.db(), &committed);
+    assert_eq!(editor.take_changes().len(), 4);
+    assert!(editor.undo().unwrap());
+    assert_eq!(editor.db(), &original);
+    assert!(editor.rollback().is_err());
+}
+
+#[test]
+fn database_methods_keep_references_consistent() {
+    let mut db = sample();
+    db.validate_invariants().unwrap();
+    let removed = db.remove_quest(q(2)).unwrap();
+    assert_eq!(removed.len(), 3);
+    db.validate_invariants().unwrap();
+    assert!(!db.quests.contains_key(&q(2)));
+    assert!(db.quests[&q(3)].prerequisites.is_empty());
+    assert!(db.quests[&q(3)].required_prerequisites.is_empty());
+    assert!(
+        db.questlines[&q(100)]
+            .entries
+            .iter()
+            .all(|e| e.quest_id != q(2))
+    );
+
+    db.add_quest(quest(4, &[])).unwrap();
+    assert!(db.add_quest(quest(4, &[])).is_err());
+    db.add_prerequisite(q(4), q(3), true).unwrap();
+    assert_eq!(db.quests[&q(4)].optional_prerequisites, vec![q(3)]);
+    assert!(db.add_prerequisite(q(4), q(3), true).unwrap().is_empty());
+    assert!(db.add_prerequisite(q(4), q(9), false).is_err());
+    assert!(db.add_prerequisite(q(4), q(4), false).is_err());
+
+    let changes = db
+        .update_properties(q(4), |p| {
+            p.name = "Renamed".to_string();
+            p.is_main = Some(true);
+        })
+        .unwrap();
+    let fields: Vec<&str> = changes
+        .iter()
+        .map(|c| match c {
+            DatabaseChange::PropertyChanged { field, .. } => field.as_str(),
+            other => panic!("unexpected change {other:?}"),
+        })
+        .collect();
+    assert_eq!(fields, ["is_main", "name"]);
+    assert_eq!(db.quests[&q(4)].name(), Some("Renamed"));
+
+    db.move_questline_entry(q(100), q(3), 5, 7).unwrap();
+    let moved = db.questlines[&q(100)]
+        .entries
+        .iter()
+        .find(|e| e.quest_id == q(3))
+        .unwrap();
+    assert_eq!((moved.x, moved.y), (Some(5), Some(7)));
+    assert!(db.move_questline_entry(q(100), q(4), 0, 0).is_err());
+    db.validate_invariants().unwrap();
+}
+
+#[test]
+fn redundant_editor_edits_leave_no_undo_step() {
+    let original = sample();
+    let mut editor = Editor::new(original.clone());
+    editor.add_prerequisite(q(2), q(1), false).unwrap();
+    editor.add_entry(q(100), entry(3, 90)).unwrap();
+    editor
+        .set_property(q(1), "name", Some(json!("Quest 1")))
+        .unwrap();
+    editor.set_property(q(1), "unmodeled", None).unwrap();
+    assert!(!editor.can_undo());
+    assert!(!editor.undo().unwrap());
+    assert_eq!(editor.db().quests[&q(2)].required_prerequisites, vec![q(1)]);
+    assert_eq!(editor.db(), &original);
+
+    assert!(editor.add_prerequisite(q(2), q(2), false).is_err());
+    assert!(editor.add_prerequisite(q(2), q(9), false).is_err());
+    assert!(editor.add_entry(q(100), entry(9, 0)).is_err());
+    assert!(editor.take_changes().is_empty());
+}
+
+#[test]
+fn undo_puts_removed_prerequisites_back_in_place() {
+    let mut quest = common::quest(4).requires(&[1, 2, 3]).optional(&[2]).build();
+    // the legacy list may disagree with the required one
+    quest.prerequisites = vec![q(3), q(2)];
+    let mut original = sample();
+    original.quests.insert(q(4), quest);
+
+    let mut editor = Editor::new(original.clone());
+    editor.remove_prerequisite(q(4), q(2)).unwrap();
+    assert_eq!(
+        editor.take_changes(),
+        vec![DatabaseChange::PrereqRemoved {
+            quest: q(4),
+            prerequisite: q(2),
+            positions: PrereqPositions {
+                prerequisites: Some(1),
+                required_prerequisites: Some(1),
+                optional_prerequisites: Some(0),
+            },
+        }]
+    );
+    assert_eq!(
+        editor.db().quests[&q(4)].required_prerequisites,
+        vec![q(1), q(3)]
+    );
+    editor.undo().unwrap();
+    assert_eq!(editor.db(), &original);
+
+    editor.remove_quest(q(1)).unwrap();
+    editor.undo().unwrap();
+    assert_eq!(editor.db(), &original);
+
+    // adding fills only the list that lacks it
+    editor.add_prerequisite(q(4), q(1), false).unwrap();
+    assert_eq!(
+        editor.db().quests[&q(4)].prerequisites,
+        vec![q(3), q(2), q(1)]
+    );
+    editor.undo().unwrap();
+    assert_eq!(editor.db(), &original);
+}
@@ -0,0 +1,159 @@
+use better_questing_tools::export::ftb::to_ftb_quests;
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest(low: i32, prereqs: &[i32], tasks: serde_json::Value, rewards: serde_json::Value) -> Quest {
+    let prereqs: serde_json::Map<_, _> = prereqs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            (
+                format!("{i}:10"),
+                json!({ "questIDHigh:4": 0, "questIDLow:4": p }),
+            )
+        })
+        .collect();
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "preRequisites:9": prereqs,
+        "properties:10": { "betterquesting:10": {
+            "name:8": format!("Quest {low}"),
+            "desc:8": "First line\nSecond \"line\""
+        } },
+        "tasks:9": tasks,
+        "rewards:9": rewards,
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+fn entry(low: i32, x: i32) -> QuestLineEntry {
+    QuestLineEntry {
+        index: None,
+        quest_id: QuestId::from_parts(0, low),
+        x: Some(x),
+        y: Some(0),
+        size_x: None,
+        size_y: None,
+        extra: HashMap::new(),
+    }
+}
+
+fn line(low: i32, name: &str, entries: Vec<QuestLineEntry>) -> QuestLine {
+    QuestLine {
+        id: QuestId::from_parts(0, low),
+        properties: Some(serde_json::from_value(json!({ "name": name })).unwrap()),
+        entries,
+        extra: HashMap::new(),
+    }
+}
+
+fn pack() -> QuestDatabase {
+    let q1 = quest(
+        1,
+        &[],
+        json!({ "0:10": {
+            "taskID:8": "bq_standard:retrieval",
+            "consume:1": 1,
+            "requiredItems:9": { "0:10": { "id:8": "minecraft:log", "Count:3": 16, "Damage:2": 0 } }
+        } }),
+        json!({ "0:10": {
+            "rewardID:8": "bq_standard:item",
+            "rewards:9": { "0:10": { "id:8": "minecraft:apple", "Count:3": 3, "Damage:2": 0 } }
+        } }),
+    );
+    let q2 = quest(
+        2,
+        &[1],
+        json!({
+            "0:10": { "taskID:8": "bq_standard:hunt", "target:8": "minecraft:zombie", "required:3": 5 },
+            "1:10": { "taskID:8": "bq_rf:rf_charge" }
+        }),
+        json!({
+            "0:10": { "rewardID:8": "bq_standard:command", "command:8": "/say VAR_NAME did it" },
+            "1:10": { "rewardID:8": "bq_standard:choice" }
+        }),
+    );
+    let q3 = quest(3, &[2], json!({}), json!({}));
+    let lines = [
+        line(10, "Getting Started", vec![entry(1, 0), entry(2, 48)]),
+        line(11, "Getting Started", vec![entry(2, 24)]),
+    ];
+    QuestDatabase {
+        settings: None,
+        quests: [q1, q2, q3].into_iter().map(|q| (q.id, q)).collect(),
+        questline_order: lines.iter().map(|l| l.id).collect(),
+        questlines: lines.into_iter().map(|l| (l.id, l)).collect(),
+    }
+}
+
+#[test]
+fn writes_chapters_with_quests_and_links() {
+    let export = to_ftb_quests(&pack());
+    let paths: Vec<&str> = export.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "data.snbt",
+            "chapters/getting_started.snbt",
+            "chapters/getting_started_2.snbt",
+            "chapters/unsorted.snbt",
+        ]
+    );
+    let first = &export.files[1].snbt;
+    assert!(first.starts_with("{\n\tid: \""));
+    assert!(first.contains("\ttitle: \"Getting Started\"\n"));
+    assert!(first.contains("\t\t\tx: 2.0d\n"));
+    assert!(first.contains("description: [\"First line\", \"Second \\\"line\\\"\"]"));
+    assert!(first.contains("type: \"item\""));
+    assert!(first.contains("count: 16L"));
+    assert!(first.contains("consume_items: true"));
+    assert!(first.contains("count: 3\n"));
+    assert!(first.contains("type: \"kill\""));
+    assert!(first.contains("entity: \"minecraft:zombie\""));
+    assert!(first.contains("value: 5L"));
+    assert!(first.contains("command: \"/say @p did it\""));
+    assert!(first.contains("dependencies: [\""));
+
+    // quest 2 is placed in the first chapter and linked from the second
+    let second = &export.files[2].snbt;
+    assert!(second.contains("quest_links: ["));
+    assert!(second.contains("quests: []"));
+    assert!(export.files[3].snbt.contains("title: \"Quest 3\""));
+}
+
+#[test]
+fn dependencies_point_at_exported_quest_ids() {
+    let export = to_ftb_quests(&pack());
+    let first = &export.files[1].snbt;
+    let quest_ids: Vec<&str> = first
+        .lines()
+        .filter(|l| l.starts_with("\t\t\tid: \""))
+        .map(|l| &l[8..24])
+        .collect();
+    assert_eq!(quest_ids.len(), 2);
+    assert!(first.contains(&format!("dependencies: [\"{}\"]", quest_ids[0])));
+    assert_eq!(to_ftb_quests(&pack()), export);
+}
+
+#[test]
+fn lossy_conversions_are_noted() {
+    let export = to_ftb_quests(&pack());
+    let messages: Vec<&str> = export.notes.iter().map(|n| n.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "task `bq_rf:rf_charge` exported as a checkmark",
+            "reward `bq_standard:choice` not exported",
+        ]
+    );
+    assert!(
+        export
+            .notes
+            .iter()
+            .all(|n| n.quest == QuestId::from_parts(0, 2))
+    );
+}
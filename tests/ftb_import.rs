@@ -0,0 +1,188 @@
+use better_questing_tools::db::MemoryDataSource;
+use better_questing_tools::export::ftb::to_ftb_quests;
+use better_questing_tools::import::ftb::{from_ftb_source, parse_snbt};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+
+const BASICS: &str = r#"{
+	id: "00000000000000A1"
+	filename: "basics"
+	order_index: 1
+	title: "Basics"
+	subtitle: ["Start here"]
+	quests: [
+		{
+			id: "0000000000000001"
+			x: 1.0d
+			y: -0.5d
+			title: "Chop wood"
+			description: ["Punch a tree.", "{@pagebreak}", "Then another."]
+			icon: "minecraft:log"
+			tasks: [
+				{ id: "10", type: "item", item: { id: "minecraft:log", Count: 1b }, count: 16L, consume_items: true }
+				{ id: "11", type: "ftbquests:dimension", dimension: "minecraft:the_nether" }
+			]
+			rewards: [{ id: "20", type: "command", command: "/say @p chopped" }]
+		}
+		{
+			id: "0000000000000002"
+			x: 3.0d, y: 0.0d
+			title: 'It\'s dark'
+			dependencies: ["0000000000000001"]
+			dependency_requirement: "one_completed"
+			can_repeat: true
+			invisible: 1b
+			tasks: [{ id: "12", type: "kill", entity: "minecraft:zombie", value: 5L }]
+			rewards: [{ id: "21", type: "xp_levels", xp_levels: 3 }]
+		}
+	]
+}
+"#;
+
+const INTRO: &str = r#"{
+	id: "00000000000000A0"
+	filename: "intro"
+	order_index: 0
+	quests: []
+	quest_links: [{ id: "30", linked_quest: "0000000000000002", x: 0.0d, y: 2.0d }]
+}
+"#;
+
+fn source() -> MemoryDataSource {
+    [
+        ("quests/data.snbt", "{ version: 13 }"),
+        ("quests/chapters/basics.snbt", BASICS),
+        ("quests/chapters/intro.snbt", INTRO),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn parses_snbt_values() {
+    let value = parse_snbt("{a: 1b, 'b c': [I; 1, 2], d: 2.5f, e: \"x\\ny\", f: stone}").unwrap();
+    assert_eq!(
+        value,
+        json!({ "a": 1, "b c": [1, 2], "d": 2.5, "e": "x\ny", "f": "stone" })
+    );
+    assert!(parse_snbt("{a: 1").is_err());
+    assert!(parse_snbt("{a: 1} }").is_err());
+}
+
+#[test]
+fn parses_non_ascii_snbt() {
+    let value = parse_snbt("{a: [€], b: [I;], 'ß': \"Grüße\"}").unwrap();
+    assert_eq!(value, json!({ "a": ["€"], "b": [], "ß": "Grüße" }));
+}
+
+#[test]
+fn builds_questlines_in_chapter_order() {
+    let db = from_ftb_source(&source(), "quests").unwrap();
+    assert_eq!(
+        db.questline_order,
+        [QuestId::from_u64(0xA0), QuestId::from_u64(0xA1)]
+    );
+    let basics = &db.questlines[&QuestId::from_u64(0xA1)];
+    let props = basics.properties.as_ref().unwrap();
+    assert_eq!(props.name, "Basics");
+    assert_eq!(props.pages(), ["Start here"]);
+    let positions: Vec<_> = basics.entries.iter().map(|e| (e.x, e.y)).collect();
+    assert_eq!(positions, [(Some(24), Some(-12)), (Some(72), Some(0))]);
+
+    let intro = &db.questlines[&QuestId::from_u64(0xA0)];
+    assert_eq!(intro.entries[0].quest_id, QuestId::from_u64(2));
+    assert_eq!(intro.entries[0].y, Some(48));
+}
+
+#[test]
+fn converts_quests_tasks_and_rewards() {
+    let db = from_ftb_source(&source(), "quests").unwrap();
+    let wood = &db.quests[&QuestId::from_u64(1)];
+    let props = wood.properties.as_ref().unwrap();
+    assert_eq!(props.name, "Chop wood");
+    assert_eq!(props.pages(), ["Punch a tree.", "Then another."]);
+    assert_eq!(props.icon.as_ref().unwrap().id, "minecraft:log");
+    assert_eq!(wood.tasks[0].task_id, "bq_standard:retrieval");
+    assert_eq!(wood.tasks[0].required_items[0].count, Some(16));
+    assert_eq!(wood.tasks[0].consume, Some(true));
+    assert_eq!(wood.tasks[1].task_id, "ftbquests:dimension");
+    assert_eq!(
+        wood.tasks[1].options["dimension"],
+        json!("minecraft:the_nether")
+    );
+    assert_eq!(
+        wood.rewards[0].extra["command"],
+        json!("/say VAR_NAME chopped")
+    );
+
+    let dark = &db.quests[&QuestId::from_u64(2)];
+    let props = dark.properties.as_ref().unwrap();
+    assert_eq!(props.name, "It's dark");
+    assert_eq!(props.quest_logic.as_deref(), Some("OR"));
+    assert_eq!(props.repeat_time, Some(0));
+    assert_eq!(props.visibility_kind(), Some(QuestVisibility::Hidden));
+    assert_eq!(dark.required_prerequisites, [QuestId::from_u64(1)]);
+    let target = dark.tasks[0].hunt_target().unwrap();
+    assert_eq!(target.target_entity, "minecraft:zombie");
+    assert_eq!(target.required_kills, 5);
+    assert_eq!(dark.rewards[0].reward_id, "bq_standard:xp");
+    assert_eq!(dark.rewards[0].extra["isLevels"], json!(1));
+}
+
+#[test]
+fn reads_back_an_export() {
+    let db = from_ftb_source(&source(), "quests").unwrap();
+    let exported: MemoryDataSource = to_ftb_quests(&db)
+        .files
+        .into_iter()
+        .map(|f| (format!("quests/{}", f.path), f.snbt))
+        .collect();
+    let again = from_ftb_source(&exported, "quests").unwrap();
+    assert_eq!(again.quests.len(), 2);
+    assert_eq!(again.questline_order.len(), 2);
+    let names: Vec<_> = again
+        .questline_order
+        .iter()
+        .map(|id| {
+            again.questlines[id]
+                .properties
+                .as_ref()
+                .unwrap()
+                .name
+                .clone()
+        })
+        .collect();
+    assert_eq!(names, ["intro", "Basics"]);
+}
+
+#[test]
+fn rejects_folders_without_chapters() {
+    let source: MemoryDataSource = [("quests/data.snbt", "{}")].into_iter().collect();
+    assert!(from_ftb_source(&source, "quests").is_err());
+}
+
+#[test]
+fn chapters_with_equal_order_keep_natural_file_order() {
+    let chapter = |id: &str, name: &str| {
+        format!("{{ id: \"{id}\", filename: \"{name}\", title: \"{name}\", quests: [] }}")
+    };
+    let source: MemoryDataSource = [
+        (
+            "quests/chapters/part10.snbt",
+            chapter("00000000000000B2", "part10"),
+        ),
+        (
+            "quests/chapters/part2.snbt",
+            chapter("00000000000000B1", "part2"),
+        ),
+    ]
+    .into_iter()
+    .map(|(p, t)| (p.to_string(), t))
+    .collect();
+    let db = from_ftb_source(&source, "quests").unwrap();
+    assert_eq!(
+        db.questline_order,
+        [QuestId::from_u64(0xB1), QuestId::from_u64(0xB2)]
+    );
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use better_questing_tools::export::graphml::to_graphml;
+use better_questing_tools::model::*;
+use common::q;
+use std::collections::HashMap;
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .name(name)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+#[test]
+fn graphml_nodes_and_edges_carry_attributes() {
+    let lid = q(100);
+    let line_props: QuestLineProperties =
+        serde_json::from_value(serde_json::json!({ "name": "§6Getting Started" })).unwrap();
+    let db = QuestDatabase {
+        settings: None,
+        quests: [
+            quest(1, "Start & <Go>", &[], &[]),
+            quest(2, "Wood", &[1, 99], &[]),
+            quest(3, "Tools", &[2], &[1]),
+        ]
+        .into_iter()
+        .map(|q| (q.id, q))
+        .collect(),
+        questlines: HashMap::from([(
+            lid,
+            QuestLine {
+                id: lid,
+                properties: Some(line_props),
+                entries: vec![QuestLineEntry {
+                    index: None,
+                    quest_id: q(1),
+                    x: None,
+                    y: None,
+                    size_x: None,
+                    size_y: None,
+                    extra: HashMap::new(),
+                }],
+                extra: HashMap::new(),
+            },
+        )]),
+        questline_order: vec![lid],
+    };
+    let scores = HashMap::from([(q(1), 0.5), (q(2), 0.25)]);
+
+    let xml = to_graphml(&db, Some(&scores));
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains(
+        "    <node id=\"q1\">\n      <data key=\"name\">Start &amp; &lt;Go&gt;</data>\n      <data key=\"questline\">Getting Started</data>\n      <data key=\"importance\">0.500000</data>\n    </node>\n"
+    ));
+    assert!(
+        xml.contains("    <node id=\"q3\">\n      <data key=\"name\">Tools</data>\n    </node>\n")
+    );
+    let edges: Vec<&str> = xml.lines().filter(|l| l.contains("<edge ")).collect();
+    assert_eq!(
+        edges,
+        vec![
+            "    <edge id=\"e0\" source=\"q1\" target=\"q2\">",
+            "    <edge id=\"e1\" source=\"q1\" target=\"q3\">",
+            "    <edge id=\"e2\" source=\"q2\" target=\"q3\">",
+        ]
+    );
+    assert!(xml.contains(
+        "<edge id=\"e1\" source=\"q1\" target=\"q3\">\n      <data key=\"kind\">optional</data>"
+    ));
+    assert!(xml.contains(
+        "<data key=\"kind\">optional</data>\n      <data key=\"weight\">1.000000</data>"
+    ));
+    assert!(xml.ends_with("</graphml>\n"));
+    assert!(!to_graphml(&db, None).contains("key=\"importance\">"));
+}
@@ -1,46 +1,12 @@
-use std::io::Read;
-use std::path::PathBuf;
-use std::{collections::HashMap, fs, io::Cursor};
+use std::path::Path;
 
-use better_questing_tools::model::Quest;
-use better_questing_tools::parser::parse_quest_from_reader;
-use better_questing_tools::quest_id::QuestId;
-use zip::ZipArchive;
+use better_questing_tools::fixtures::sample_quests;
 
 /// Test to generate a Graphviz (DOT) diagram of the quests and their prerequisite edges.
 #[test]
 fn quest_graph_dot_snapshot() {
     // Collect quests from any zip in samples/ that contains DefaultQuests/Quests/*.json
-    let samples_dir = PathBuf::from("samples");
-    let entries = fs::read_dir(&samples_dir).expect("failed to read samples directory");
-
-    let mut quests: HashMap<QuestId, Quest> = HashMap::new();
-
-    for entry in entries.flatten() {
-        let zip_path = entry.path();
-        if zip_path.extension().and_then(|s| s.to_str()) != Some("zip") {
-            continue;
-        }
-        let data = fs::read(&zip_path).expect("failed to read samples zip");
-        let cursor = Cursor::new(data);
-        let mut archive = ZipArchive::new(cursor).expect("failed to open zip archive");
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i).expect("failed to access zip entry");
-            let name = file.name().to_string();
-            if !name.ends_with(".json") {
-                continue;
-            }
-            if !name.contains("config/betterquesting/DefaultQuests/Quests/") {
-                continue;
-            }
-            let mut buf: Vec<u8> = Vec::new();
-            file.read_to_end(&mut buf)
-                .expect("failed to read zip entry");
-            let cursor = Cursor::new(buf);
-            let quest = parse_quest_from_reader(cursor).expect("parse failed");
-            quests.entry(quest.id).or_insert(quest);
-        }
-    }
+    let quests = sample_quests(Path::new("samples")).expect("failed to read samples");
 
     // Change quests HashMap to a sorted Vec for deterministic output
     let mut quest_vec: Vec<_> = quests.iter().collect();
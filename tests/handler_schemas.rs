@@ -0,0 +1,75 @@
+use better_questing_tools::export::handlers::{ObservedKey, handler_schemas, to_json};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::schema::FieldScope;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+fn key(count: usize, types: &[(&str, usize)]) -> ObservedKey {
+    ObservedKey {
+        count,
+        types: types.iter().map(|(t, n)| (t.to_string(), *n)).collect(),
+    }
+}
+
+#[test]
+fn collects_keys_and_types_per_handler() {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": { "name:8": "Quest" } },
+        "tasks:9": {
+            "0:10": {
+                "taskID:8": "bq_standard:retrieval",
+                "index:3": 0,
+                "requiredItems:9": { "0:10": { "id:8": "minecraft:log", "Count:3": 1 } },
+                "consume:1": 1,
+            },
+            "1:10": { "taskID:8": "bq_rf:rf_charge", "index:3": 1, "rf:4": 1000 },
+            "2:10": { "taskID:8": "bq_rf:rf_charge", "index:3": 2, "rf:4": 2.5, "delExcess:1": 0 },
+        },
+        "rewards:9": {
+            "0:10": { "rewardID:8": "bq_standard:command", "index:3": 0, "command:8": "/say hi" },
+        },
+    });
+    let quest = parse_quest_from_reader(v.to_string().as_bytes()).unwrap();
+    let db = QuestDatabase {
+        settings: None,
+        quests: HashMap::from([(quest.id, quest)]),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+
+    let schemas = handler_schemas(&db);
+    let handlers: Vec<(FieldScope, &str, usize)> = schemas
+        .iter()
+        .map(|s| (s.scope, s.handler.as_str(), s.count))
+        .collect();
+    assert_eq!(
+        handlers,
+        vec![
+            (FieldScope::Task, "bq_rf:rf_charge", 2),
+            (FieldScope::Task, "bq_standard:retrieval", 1),
+            (FieldScope::Reward, "bq_standard:command", 1),
+        ]
+    );
+    assert_eq!(
+        schemas[0].keys,
+        BTreeMap::from([
+            ("delExcess".to_string(), key(1, &[("integer", 1)])),
+            ("rf".to_string(), key(2, &[("integer", 1), ("number", 1)])),
+        ])
+    );
+    assert_eq!(
+        schemas[1].keys,
+        BTreeMap::from([
+            ("consume".to_string(), key(1, &[("bool", 1)])),
+            ("requiredItems".to_string(), key(1, &[("array", 1)])),
+        ])
+    );
+    assert_eq!(schemas[2].keys.keys().collect::<Vec<_>>(), vec!["command"]);
+
+    let parsed: serde_json::Value = serde_json::from_str(&to_json(&db).unwrap()).unwrap();
+    assert_eq!(parsed[2]["scope"], "reward");
+    assert_eq!(parsed[0]["keys"]["rf"]["types"]["number"], 1);
+}
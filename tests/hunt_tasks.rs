@@ -0,0 +1,58 @@
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use serde_json::json;
+
+fn quest(tasks: serde_json::Value) -> Quest {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": { "name:8": "Monster Hunter" } },
+        "tasks:9": tasks,
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+#[test]
+fn hunt_targets_are_typed() {
+    let q = quest(json!({
+        "0:10": {
+            "index:3": 0,
+            "taskID:8": "bq_standard:hunt",
+            "target:8": "minecraft:zombie",
+            "required:3": 10,
+            "ignoreNBT:1": 0
+        },
+        "1:10": { "index:3": 1, "taskID:8": "bq_standard:checkbox" },
+        "2:10": { "index:3": 2, "taskID:8": "bq_standard:hunt", "target:8": "Creeper" }
+    }));
+    assert_eq!(
+        q.hunt_targets(),
+        vec![
+            HuntTarget {
+                target_entity: "minecraft:zombie".into(),
+                required_kills: 10,
+                ignore_nbt: false,
+            },
+            HuntTarget {
+                target_entity: "Creeper".into(),
+                required_kills: 1,
+                ignore_nbt: true,
+            },
+        ]
+    );
+    assert_eq!(q.tasks[1].hunt_target(), None);
+}
+
+#[test]
+fn hunt_without_target_or_with_bad_count_is_skipped() {
+    let q = quest(json!({
+        "0:10": { "index:3": 0, "taskID:8": "bq_standard:hunt" },
+        "1:10": {
+            "index:3": 1,
+            "taskID:8": "bq_standard:hunt",
+            "target:8": "minecraft:skeleton",
+            "required:4": 5_000_000_000i64
+        }
+    }));
+    assert!(q.hunt_targets().is_empty());
+}
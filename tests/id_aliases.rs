@@ -0,0 +1,157 @@
+mod common;
+
+use better_questing_tools::db::{
+    FsDataSource, ParseOptions, ParseWarning, parse_default_quests_dir_with_options,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::identity::IdAliases;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::{Value, json};
+
+const START: &str = "0f8b4c6e-1d2a-4b3c-9e8f-7a6b5c4d3e2f";
+const NEXT: &str = "a1b2c3d4-e5f6-4789-8abc-def012345678";
+
+fn uuid(s: &str) -> QuestId {
+    QuestId::from_uuid_str(s).unwrap()
+}
+
+fn legacy(low: i32) -> QuestId {
+    QuestId::from_parts(0, low)
+}
+
+fn uuid_quest(id: &str, name: &str, prereqs: Value) -> Value {
+    json!({
+        "questID:8": id,
+        "properties:10": {"betterquesting:10": {"name:8": name}},
+        "preRequisites:9": prereqs,
+    })
+}
+
+fn legacy_quest(low: i32, name: &str) -> Value {
+    json!({
+        "questIDHigh:4": 0, "questIDLow:4": low,
+        "properties:10": {"betterquesting:10": {"name:8": name}},
+    })
+}
+
+#[test]
+fn mapping_file_accepts_legacy_numbers_and_uuids() {
+    let text = format!("# migrated quests\n1 {START}\n\n2, {NEXT}\n3 = 4\n");
+    let aliases = IdAliases::read_mapping(text.as_bytes()).unwrap();
+    assert_eq!(
+        aliases.iter().collect::<Vec<_>>(),
+        vec![
+            (legacy(1), uuid(START)),
+            (legacy(2), uuid(NEXT)),
+            (legacy(3), legacy(4)),
+        ]
+    );
+    assert_eq!(aliases.resolve(legacy(2)), uuid(NEXT));
+    assert_eq!(aliases.resolve(legacy(9)), legacy(9));
+
+    let err = IdAliases::read_mapping("1 2 3\n".as_bytes()).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidFormat(m) if m.contains("line 1")));
+}
+
+#[test]
+fn parsing_with_aliases_unifies_phantom_duplicates() {
+    let root = std::env::temp_dir().join(format!("bqt-id-aliases-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let line_dir = root.join("QuestLines").join("0");
+    std::fs::create_dir_all(root.join("Quests")).unwrap();
+    std::fs::create_dir_all(&line_dir).unwrap();
+    let files = [
+        ("start", uuid_quest(START, "Start", json!({}))),
+        // the old copy of Start, still under its legacy id
+        ("start-old", legacy_quest(1, "Start")),
+        // Next still requires Start by its legacy id
+        (
+            "next",
+            uuid_quest(
+                NEXT,
+                "Next",
+                json!({ "0:10": { "questIDHigh:4": 0, "questIDLow:4": 1 } }),
+            ),
+        ),
+    ];
+    for (name, quest) in &files {
+        std::fs::write(
+            root.join("Quests").join(format!("{name}.json")),
+            quest.to_string(),
+        )
+        .unwrap();
+    }
+    let line = json!({
+        "questLineIDHigh:4": 0, "questLineIDLow:4": 5,
+        "properties:10": {"betterquesting:10": {"name:8": "Chapter"}},
+    });
+    std::fs::write(line_dir.join("QuestLine.json"), line.to_string()).unwrap();
+    for (i, quest) in [
+        json!({"questIDHigh:4": 0, "questIDLow:4": 1}),
+        json!({"questID:8": START}),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        std::fs::write(line_dir.join(format!("{i}.json")), quest.to_string()).unwrap();
+    }
+
+    // the heuristic finds the pair from the names alone
+    let quests: Vec<_> = files
+        .iter()
+        .map(|(_, v)| parse_quest_from_reader(v.to_string().as_bytes()).unwrap())
+        .collect();
+    let guessed = IdAliases::by_name(&better_questing_tools::model::QuestDatabase {
+        settings: None,
+        quests: quests.into_iter().map(|q| (q.id, q)).collect(),
+        questlines: Default::default(),
+        questline_order: vec![],
+    });
+    assert_eq!(
+        guessed.iter().collect::<Vec<_>>(),
+        vec![(legacy(1), uuid(START))]
+    );
+
+    let options = ParseOptions {
+        id_aliases: guessed,
+        ..Default::default()
+    };
+    let outcome =
+        parse_default_quests_dir_with_options(&FsDataSource, &root.to_string_lossy(), &options)
+            .unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(
+        outcome.warnings,
+        vec![ParseWarning::DuplicateAlias {
+            alias: legacy(1),
+            canonical: uuid(START)
+        }]
+    );
+    let db = outcome.db;
+    assert_eq!(db.quests.len(), 2);
+    assert_eq!(db.quests[&uuid(NEXT)].prerequisites, vec![uuid(START)]);
+    let entries: Vec<QuestId> = db.questlines[&legacy(5)]
+        .entries
+        .iter()
+        .map(|e| e.quest_id)
+        .collect();
+    assert_eq!(entries, vec![uuid(START)]);
+}
+
+#[test]
+fn questline_ids_are_not_quest_aliases() {
+    // questline 0:1 shares its id with the legacy quest aliased to START
+    let start =
+        parse_quest_from_reader(uuid_quest(START, "Start", json!({})).to_string().as_bytes())
+            .unwrap();
+    let mut db = common::db([start], [common::line(1).entries(&[1]).build()]);
+    let aliases: IdAliases = [(legacy(1), uuid(START))].into_iter().collect();
+    assert!(aliases.unify(&mut db).is_empty());
+
+    assert_eq!(db.questline_order, vec![legacy(1)]);
+    let line = &db.questlines[&legacy(1)];
+    assert_eq!(line.id, legacy(1));
+    assert_eq!(line.entries[0].quest_id, uuid(START));
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use better_questing_tools::error::ParseError;
+use better_questing_tools::merge::{ConflictStrategy, IdKind, check_collisions, merge};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::q;
+use std::collections::HashMap;
+
+fn db(quests: &[i32], lines: &[i32]) -> QuestDatabase {
+    common::db(
+        quests.iter().map(|low| common::quest(*low).build()),
+        lines.iter().map(|low| common::line(*low).build()),
+    )
+}
+
+#[test]
+fn collisions_and_remapped_ranges() {
+    let base = db(&[1, 2, 3, 10], &[0]);
+    let addon = db(&[2, 3, 4], &[0]);
+    let other = db(&[3, 20], &[]);
+    let report = check_collisions(&[&base, &addon, &other]).unwrap();
+
+    let quest_collisions: Vec<(QuestId, Vec<usize>)> = report
+        .collisions
+        .iter()
+        .filter(|c| c.kind == IdKind::Quest)
+        .map(|c| (c.id, c.sources.clone()))
+        .collect();
+    assert_eq!(
+        quest_collisions,
+        vec![(q(2), vec![0, 1]), (q(3), vec![0, 1, 2])]
+    );
+
+    // quest ids go above the highest quest id in use (20)
+    let addon_quests = &report.remaps[0];
+    assert_eq!((addon_quests.source, addon_quests.kind), (1, IdKind::Quest));
+    assert_eq!((addon_quests.first, addon_quests.last), (q(21), q(22)));
+    assert_eq!(addon_quests.mapping[&q(3)], q(22));
+    let addon_lines = &report.remaps[1];
+    assert_eq!(addon_lines.kind, IdKind::Questline);
+    assert_eq!(addon_lines.mapping[&q(0)], q(1));
+    let other_quests = &report.remaps[2];
+    assert_eq!(other_quests.source, 2);
+    assert_eq!(other_quests.mapping[&q(3)], q(23));
+
+    assert!(
+        check_collisions(&[&base, &db(&[5], &[1])])
+            .unwrap()
+            .is_clean()
+    );
+}
+
+/// Addon with quests 2 and 3 (3 requires 2 and base quest 10) on line 0.
+fn addon() -> QuestDatabase {
+    let mut addon = db(&[2, 3], &[0]);
+    let three = addon.quests.get_mut(&q(3)).unwrap();
+    three.prerequisites = vec![q(2), q(10)];
+    three.required_prerequisites = vec![q(2), q(10)];
+    addon.questlines.get_mut(&q(0)).unwrap().entries = [2, 3]
+        .iter()
+        .map(|low| QuestLineEntry {
+            index: None,
+            quest_id: q(*low),
+            x: None,
+            y: None,
+            size_x: None,
+            size_y: None,
+            extra: HashMap::new(),
+        })
+        .collect();
+    addon
+}
+
+#[test]
+fn merge_fails_on_collisions_by_default() {
+    let base = db(&[1, 2, 10], &[0]);
+    let err = merge(&[&base, &addon()], ConflictStrategy::default()).unwrap_err();
+    assert!(matches!(err, ParseError::DuplicateQuestId(id) if id == "2"));
+    let clean = merge(&[&base, &db(&[5], &[7])], ConflictStrategy::Error).unwrap();
+    assert_eq!(clean.quests.len(), 4);
+    assert_eq!(clean.questline_order, vec![q(0), q(7)]);
+}
+
+#[test]
+fn merge_keep_first_drops_later_duplicates() {
+    let base = db(&[1, 2, 10], &[0]);
+    let merged = merge(&[&base, &addon()], ConflictStrategy::KeepFirst).unwrap();
+    let mut ids: Vec<QuestId> = merged.quests.keys().copied().collect();
+    ids.sort();
+    assert_eq!(ids, vec![q(1), q(2), q(3), q(10)]);
+    assert!(merged.quests[&q(2)].prerequisites.is_empty());
+    assert!(merged.questlines[&q(0)].entries.is_empty());
+    assert_eq!(merged.questline_order, vec![q(0)]);
+}
+
+#[test]
+fn merge_remap_rewrites_references() {
+    let base = db(&[1, 2, 10], &[0]);
+    let merged = merge(&[&base, &addon()], ConflictStrategy::RemapNewIds).unwrap();
+    // addon quest 2 moves above every id in use; 3 and 10 are untouched
+    let mut ids: Vec<QuestId> = merged.quests.keys().copied().collect();
+    ids.sort();
+    assert_eq!(ids, vec![q(1), q(2), q(3), q(10), q(11)]);
+    assert_eq!(
+        merged.quests[&q(3)].required_prerequisites,
+        vec![q(11), q(10)]
+    );
+    // questline 0 collides too and moves to 1
+    assert_eq!(merged.questline_order, vec![q(0), q(1)]);
+    let entries: Vec<QuestId> = merged.questlines[&q(1)]
+        .entries
+        .iter()
+        .map(|e| e.quest_id)
+        .collect();
+    assert_eq!(entries, vec![q(11), q(3)]);
+}
+
+#[test]
+fn running_out_of_ids_is_an_error() {
+    let base = db(&[1], &[]);
+    let mut addon = db(&[1], &[]);
+    let last = QuestId::from_parts_i64(0, i64::MAX);
+    let mut quest = addon.quests[&q(1)].clone();
+    quest.id = last;
+    addon.quests.insert(last, quest);
+    assert!(matches!(
+        check_collisions(&[&base, &addon]),
+        Err(ParseError::NumberOutOfRange { .. })
+    ));
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::renumber::compact_ids;
+use common::q;
+use std::collections::HashMap;
+
+fn quest(low: i32, prereqs: &[i32]) -> Quest {
+    common::quest(low).requires(prereqs).build()
+}
+
+fn line(low: i32, quests: &[i32]) -> QuestLine {
+    common::line(low).entries(quests).build()
+}
+
+#[test]
+fn sparse_ids_become_contiguous() {
+    let db = QuestDatabase {
+        settings: None,
+        quests: [quest(7, &[]), quest(9000, &[7]), quest(42, &[7, 1])]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: [line(500, &[9000]), line(30, &[7, 42])]
+            .into_iter()
+            .map(|l| (l.id, l))
+            .collect(),
+        questline_order: vec![q(500), q(30)],
+    };
+    let (compact, mapping) = compact_ids(&db, 0).unwrap();
+
+    // quest 1 is referenced but missing, so new ids skip it
+    assert_eq!(
+        mapping.quests.into_iter().collect::<Vec<_>>(),
+        vec![(q(7), q(0)), (q(42), q(2)), (q(9000), q(3))]
+    );
+    assert_eq!(
+        mapping.questlines.into_iter().collect::<Vec<_>>(),
+        vec![(q(30), q(1)), (q(500), q(0))]
+    );
+    let mut ids: Vec<QuestId> = compact.quests.keys().copied().collect();
+    ids.sort();
+    assert_eq!(ids, vec![q(0), q(2), q(3)]);
+    assert_eq!(compact.quests[&q(3)].required_prerequisites, vec![q(0)]);
+    assert_eq!(compact.quests[&q(2)].prerequisites, vec![q(0), q(1)]);
+    assert_eq!(compact.questline_order, vec![q(0), q(1)]);
+    assert_eq!(compact.questlines[&q(0)].entries[0].quest_id, q(3));
+    let entries: Vec<QuestId> = compact.questlines[&q(1)]
+        .entries
+        .iter()
+        .map(|e| e.quest_id)
+        .collect();
+    assert_eq!(entries, vec![q(0), q(2)]);
+}
+
+#[test]
+fn compact_database_is_left_alone() {
+    let db = QuestDatabase {
+        settings: None,
+        quests: [quest(10, &[]), quest(11, &[10])]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+    let (compact, mapping) = compact_ids(&db, 10).unwrap();
+    assert!(mapping.is_identity());
+    assert_eq!(compact, db);
+}
+
+#[test]
+fn running_out_of_ids_is_an_error() {
+    let db = QuestDatabase {
+        settings: None,
+        quests: [quest(1, &[]), quest(2, &[1])]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+    let (compact, _) = compact_ids(&db, i64::MAX - 1).unwrap();
+    let mut ids: Vec<i64> = compact.quests.keys().map(|id| id.low_i64()).collect();
+    ids.sort();
+    assert_eq!(ids, vec![i64::MAX - 1, i64::MAX]);
+    assert!(matches!(
+        compact_ids(&db, i64::MAX),
+        Err(ParseError::NumberOutOfRange { .. })
+    ));
+}
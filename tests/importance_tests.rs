@@ -81,3 +81,188 @@ fn detect_cycle() {
         _ => panic!("expected cycle error"),
     }
 }
+
+#[test]
+fn save_load_and_compare_ranks() {
+    let a = qid(0, 1);
+    let b = qid(0, 2);
+    let c = qid(0, 3);
+    let d = qid(0, 4);
+    let old = HashMap::from([(a, 4.0), (b, 3.0), (c, 2.0), (d, 1.0)]);
+    let new = HashMap::from([(a, 1.0), (b, 3.0), (c, 2.0), (d, 4.0)]);
+
+    let path = std::env::temp_dir().join(format!("bqt-scores-{}.json", std::process::id()));
+    save(&old, &path).unwrap();
+    let loaded = load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, old);
+
+    // a falls from 1st to 4th, d climbs from 4th to 1st; b and c stay put
+    let shifts = compare(&loaded, &new, 2);
+    assert_eq!(shifts.len(), 2);
+    assert_eq!((shifts[0].quest, shifts[0].delta()), (a, -3));
+    assert_eq!((shifts[1].quest, shifts[1].delta()), (d, 3));
+    assert!(compare(&old, &new, 3).is_empty());
+}
+
+#[test]
+fn pagerank_rewards_deep_chains() {
+    // root <- a <- b <- c (chain) and hub <- x, y (two direct dependents)
+    let (root, a, b, c) = (qid(0, 1), qid(0, 2), qid(0, 3), qid(0, 4));
+    let (hub, x, y) = (qid(0, 5), qid(0, 6), qid(0, 7));
+    let db = make_db(vec![
+        (root, vec![]),
+        (a, vec![root]),
+        (b, vec![a]),
+        (c, vec![b]),
+        (hub, vec![]),
+        (x, vec![hub]),
+        (y, vec![hub]),
+    ]);
+
+    // one step ranks the hub above the chain root ...
+    let one_step = compute_importance_scores(&db, 0.25, false, false).unwrap();
+    assert!(one_step[&hub] > one_step[&root]);
+    // ... PageRank credits the root with the whole chain behind it
+    let scores = compute_importance_pagerank(&db, 0.85, 1e-12).unwrap();
+    assert!(scores[&root] > scores[&hub]);
+    assert!(scores[&root] > scores[&a] && scores[&a] > scores[&b] && scores[&b] > scores[&c]);
+    let total: f64 = scores.values().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn pagerank_tolerates_cycles_and_checks_parameters() {
+    let (a, b) = (qid(0, 1), qid(0, 2));
+    let db = make_db(vec![(a, vec![b]), (b, vec![a])]);
+    let scores = compute_importance_pagerank(&db, 0.85, 1e-12).unwrap();
+    assert!((scores[&a] - 0.5).abs() < 1e-9);
+
+    for (damping, tolerance) in [(1.0, 1e-9), (-0.1, 1e-9), (0.85, 0.0), (0.85, f64::NAN)] {
+        assert!(matches!(
+            compute_importance_pagerank(&db, damping, tolerance),
+            Err(ParseError::NumberOutOfRange { .. })
+        ));
+    }
+}
+
+#[test]
+fn full_propagation_sums_all_descendants() {
+    // root <- a <- b <- c (chain) and hub <- x, y (two direct dependents)
+    let (root, a, b, c) = (qid(0, 1), qid(0, 2), qid(0, 3), qid(0, 4));
+    let (hub, x, y) = (qid(0, 5), qid(0, 6), qid(0, 7));
+    let db = make_db(vec![
+        (root, vec![]),
+        (a, vec![root]),
+        (b, vec![a]),
+        (c, vec![b]),
+        (hub, vec![]),
+        (x, vec![hub]),
+        (y, vec![hub]),
+    ]);
+    let scores = compute_importance_scores_with(&db, 0.5, false, false, Propagation::Full).unwrap();
+    // bases: root, a, b = 1; c = 0; hub = 2
+    // root = 1 + 0.5 * (1 + 0.5 * (1 + 0.5 * 0)) = 1.75
+    assert!((scores[&root] - 1.75).abs() < 1e-12);
+    assert!((scores[&a] - 1.5).abs() < 1e-12);
+    assert!((scores[&hub] - 2.0).abs() < 1e-12);
+
+    // the default matches the one-step entry point
+    let one_step = compute_importance_scores(&db, 0.5, false, false).unwrap();
+    let with =
+        compute_importance_scores_with(&db, 0.5, false, false, Propagation::default()).unwrap();
+    assert_eq!(one_step, with);
+    assert!((one_step[&root] - 1.5).abs() < 1e-12);
+}
+
+#[test]
+fn full_propagation_counts_every_path() {
+    // diamond: top <- left, right <- bottom
+    let (top, left, right, bottom) = (qid(0, 1), qid(0, 2), qid(0, 3), qid(0, 4));
+    let db = make_db(vec![
+        (top, vec![]),
+        (left, vec![top]),
+        (right, vec![top]),
+        (bottom, vec![left, right]),
+    ]);
+    let scores = compute_importance_scores_with(&db, 0.5, false, false, Propagation::Full).unwrap();
+    // left = right = 1; top = 2 + 0.5 * (1 + 1)
+    assert!((scores[&top] - 3.0).abs() < 1e-12);
+    assert_eq!(scores[&bottom], 0.0);
+
+    let cyclic = make_db(vec![(left, vec![right]), (right, vec![left])]);
+    assert!(matches!(
+        compute_importance_scores_with(&cyclic, 0.5, false, false, Propagation::Full),
+        Err(ParseError::CycleDetected(_))
+    ));
+}
+
+#[test]
+fn config_matches_positional_arguments() {
+    let (root, a, b) = (qid(0, 1), qid(0, 2), qid(0, 3));
+    let db = make_db(vec![(root, vec![]), (a, vec![root]), (b, vec![root, a])]);
+    let config = ImportanceConfig::new()
+        .alpha(0.5)
+        .use_log(true)
+        .normalize(true)
+        .propagation(Propagation::Full);
+    assert_eq!(
+        compute_importance(&db, &config).unwrap(),
+        compute_importance_scores_with(&db, 0.5, true, true, Propagation::Full).unwrap()
+    );
+    assert!(matches!(
+        compute_importance(&db, &ImportanceConfig::new().alpha(1.5)),
+        Err(ParseError::AlphaOutOfRange(_))
+    ));
+}
+
+#[test]
+fn config_applies_custom_weights() {
+    // root <- a <- b, and b also requires root directly
+    let (root, a, b) = (qid(0, 1), qid(0, 2), qid(0, 3));
+    let db = make_db(vec![(root, vec![]), (a, vec![root]), (b, vec![root, a])]);
+
+    // only edges into `root` count, at triple weight
+    let config = ImportanceConfig::new()
+        .alpha(0.0)
+        .edge_weight(move |_, prereq, weight| if prereq == root { weight * 3.0 } else { 0.0 });
+    let scores = compute_importance(&db, &config).unwrap();
+    assert_eq!(scores[&root], 6.0);
+    assert_eq!(scores[&a], 0.0);
+
+    // doubling `a` doubles its base and what it hands on to `root`
+    let config = ImportanceConfig::new()
+        .alpha(0.5)
+        .quest_weight(move |quest| if quest.id == a { 2.0 } else { 1.0 });
+    let scores = compute_importance(&db, &config).unwrap();
+    // bases: root = 2, a = 2 * 1; root = 2 + 0.5 * (a 2 + b 0)
+    assert_eq!(scores[&a], 2.0);
+    assert_eq!(scores[&root], 3.0);
+}
+
+#[test]
+fn foundational_scores_count_transitive_descendants() {
+    // 1 <- 2 <- {3, 4, 5}: one-step importance sees a single dependent of 1,
+    // the foundational score credits it with all four quests behind it.
+    let db = make_db(vec![
+        (qid(0, 1), vec![]),
+        (qid(0, 2), vec![qid(0, 1)]),
+        (qid(0, 3), vec![qid(0, 2)]),
+        (qid(0, 4), vec![qid(0, 2)]),
+        (qid(0, 5), vec![qid(0, 2), qid(0, 99)]),
+        (qid(0, 6), vec![qid(0, 7)]),
+        (qid(0, 7), vec![qid(0, 6)]),
+    ]);
+    let counts = descendant_counts(&db);
+    assert_eq!(counts[&qid(0, 1)], 4);
+    assert_eq!(counts[&qid(0, 2)], 3);
+    assert_eq!(counts[&qid(0, 3)], 0);
+    // cycle members unlock each other but not themselves
+    assert_eq!(counts[&qid(0, 6)], 1);
+    // the missing prerequisite of 5 is skipped
+    assert!(!counts.contains_key(&qid(0, 99)));
+
+    let scores = compute_foundational_scores(&db);
+    assert!((scores[&qid(0, 1)] - 4.0 / 6.0).abs() < 1e-12);
+    assert_eq!(scores[&qid(0, 5)], 0.0);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use better_questing_tools::db::{parse_default_quests_dir, write_default_quests_dir_incremental};
+use better_questing_tools::model::*;
+use common::q;
+use std::time::{Duration, SystemTime};
+
+fn quest(low: i32, name: &str, prereqs: &[i32]) -> Quest {
+    common::quest(low).name(name).requires(prereqs).build()
+}
+
+fn line(id: i32, name: &str, entries: &[i32]) -> QuestLine {
+    let line = common::line(id).name(name);
+    entries
+        .iter()
+        .fold(line, |line, low| line.entry_at(*low, low * 30, 0))
+        .build()
+}
+
+fn mtime(path: &std::path::Path) -> SystemTime {
+    std::fs::metadata(path).unwrap().modified().unwrap()
+}
+
+#[test]
+fn only_changed_files_are_rewritten() {
+    let mut db = QuestDatabase {
+        settings: None,
+        quests: [
+            quest(1, "One", &[]),
+            quest(2, "Two", &[1]),
+            quest(3, "Three", &[2]),
+        ]
+        .into_iter()
+        .map(|q| (q.id, q))
+        .collect(),
+        questlines: [line(100, "First", &[1, 2, 3]), line(101, "Second", &[3])]
+            .into_iter()
+            .map(|l| (l.id, l))
+            .collect(),
+        questline_order: vec![q(100), q(101)],
+    };
+    let root = std::env::temp_dir().join(format!("bqt-incremental-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let first = write_default_quests_dir_incremental(&db, &root).unwrap();
+    assert_eq!(first.written.len(), 9);
+    assert_eq!(first.unchanged, 0);
+
+    // backdate everything so a rewrite would be visible
+    let past = SystemTime::now() - Duration::from_secs(3600);
+    let one = root.join("Quests/1.json");
+    for path in ["Quests/1.json", "Quests/3.json", "QuestLines/0/0.json"] {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(root.join(path))
+            .unwrap();
+        file.set_modified(past).unwrap();
+    }
+
+    let again = write_default_quests_dir_incremental(&db, &root).unwrap();
+    assert!(again.written.is_empty());
+    assert_eq!(again.unchanged, 9);
+
+    db.quests
+        .get_mut(&q(3))
+        .unwrap()
+        .properties
+        .as_mut()
+        .unwrap()
+        .name = "Three!".into();
+    db.quests.remove(&q(2));
+    db.questlines.get_mut(&q(100)).unwrap().entries.remove(1);
+    db.questlines.remove(&q(101));
+    db.questline_order.pop();
+    let summary = write_default_quests_dir_incremental(&db, &root).unwrap();
+
+    let rel = |p: &String| {
+        std::path::Path::new(p)
+            .strip_prefix(&root)
+            .unwrap()
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+    let written: Vec<String> = summary.written.iter().map(rel).collect();
+    // quest 3 was renamed and entry 1 of the first line is now quest 3
+    assert_eq!(written, vec!["Quests/3.json", "QuestLines/0/1.json"]);
+    let removed: Vec<String> = summary.removed.iter().map(rel).collect();
+    assert_eq!(
+        removed,
+        vec![
+            "QuestLines/0/2.json",
+            "QuestLines/1/0.json",
+            "QuestLines/1/QuestLine.json",
+            "Quests/2.json",
+        ]
+    );
+    assert!(!root.join("QuestLines/1").exists());
+    assert_eq!(mtime(&one), past);
+    assert_eq!(mtime(&root.join("QuestLines/0/0.json")), past);
+
+    let parsed = parse_default_quests_dir(&root).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(parsed.quests.len(), 2);
+    assert_eq!(parsed.quests[&q(3)].name(), Some("Three!"));
+    assert_eq!(parsed.questline_order, vec![q(100)]);
+}
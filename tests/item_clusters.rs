@@ -0,0 +1,41 @@
+mod common;
+
+use better_questing_tools::analysis::cluster_by_items;
+use better_questing_tools::model::*;
+use common::{db, q, retrieval, stack};
+
+fn quest(low: i32, items: &[&str]) -> Quest {
+    let quest = common::quest(low);
+    if items.is_empty() {
+        return quest.build();
+    }
+    quest
+        .tasks([retrieval(items.iter().map(|id| stack(id, 1)))])
+        .build()
+}
+
+#[test]
+fn groups_quests_with_overlapping_items() {
+    let quests = vec![
+        quest(1, &["gt:plate", "gt:bolt", "gt:screw"]),
+        quest(2, &["gt:plate", "gt:bolt", "gt:screw", "gt:rod"]),
+        quest(3, &["gt:plate", "minecraft:dirt"]),
+        quest(4, &["thaum:wand", "thaum:cap"]),
+        quest(5, &["thaum:wand", "thaum:cap"]),
+        quest(6, &[]),
+    ];
+    let db = db(quests, []);
+
+    // five quests with items, three clusters: {1,2}, {3}, {4,5}
+    let clusters = cluster_by_items(&db, 3);
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0].quests, vec![q(4), q(5)]);
+    assert_eq!(clusters[0].min_similarity, 1.0);
+    assert_eq!(clusters[1].quests, vec![q(1), q(2)]);
+    assert_eq!(clusters[1].shared_items.len(), 3);
+
+    // asking for fewer clusters pulls quest 3 in through the shared plate
+    let clusters = cluster_by_items(&db, 1);
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[1].quests, vec![q(1), q(2), q(3)]);
+}
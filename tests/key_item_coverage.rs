@@ -0,0 +1,67 @@
+use better_questing_tools::analysis::{KeyItem, coverage_report, read_key_items};
+use better_questing_tools::index::ItemIndex;
+use better_questing_tools::model::QuestDatabase;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+const KEY_ITEMS: &str = "\
+# tier 1
+ic2:te:3      # macerator
+ic2:te:12
+thermalexpansion:machine
+
+minecraft:diamond_pickaxe
+";
+
+fn db() -> QuestDatabase {
+    let quests = [
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": { "name:8": "Quest 1" } },
+            "tasks:9": { "0:10": { "taskID:8": "bq_standard:retrieval", "requiredItems:9": {
+                "0:10": { "id:8": "ic2:te", "Count:3": 1, "Damage:2": 3 },
+            }}},
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 2,
+            "properties:10": { "betterquesting:10": { "name:8": "Quest 2" } },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:item", "rewards:9": {
+                "0:10": { "id:8": "thermalexpansion:machine", "Count:3": 1, "Damage:2": 5 },
+            }}},
+        }),
+    ];
+    QuestDatabase {
+        settings: None,
+        quests: quests
+            .iter()
+            .map(|v| parse_quest_from_reader(v.to_string().as_bytes()).unwrap())
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn reports_key_items_no_quest_mentions() {
+    let items = read_key_items(KEY_ITEMS.as_bytes()).unwrap();
+    assert_eq!(items.len(), 4);
+    assert_eq!(
+        items[0],
+        KeyItem {
+            id: "ic2:te".to_string(),
+            damage: Some(3)
+        }
+    );
+    assert_eq!(items[2].damage, None);
+
+    let report = coverage_report(&ItemIndex::build(&db()), &items);
+    assert_eq!(report.items[0].required_by, vec![QuestId::from_parts(0, 1)]);
+    // no damage in the list matches every variant
+    assert_eq!(report.items[2].rewarded_by, vec![QuestId::from_parts(0, 2)]);
+    let missing: Vec<&str> = report.missing().iter().map(|k| k.id.as_str()).collect();
+    assert_eq!(missing, vec!["ic2:te", "minecraft:diamond_pickaxe"]);
+    assert_eq!(report.missing()[0].damage, Some(12));
+}
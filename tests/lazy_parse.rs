@@ -0,0 +1,90 @@
+use better_questing_tools::db::{FsDataSource, parse_default_quests_dir, write_default_quests_dir};
+use better_questing_tools::lazy::parse_default_quests_dir_lazy;
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest(low: i32, prereqs: &[i32]) -> Quest {
+    let pre: Vec<_> = prereqs
+        .iter()
+        .map(|p| json!({ "questIDHigh:4": 0, "questIDLow:4": p }))
+        .collect();
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "preRequisites:9": pre,
+        "properties:10": { "betterquesting:10": { "name:8": format!("Quest {low}") } },
+        "tasks:9": { "0:10": { "taskID:8": "bq_standard:checkbox", "index:3": 0 } },
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+fn pack() -> QuestDatabase {
+    QuestDatabase {
+        settings: None,
+        quests: [quest(1, &[]), quest(2, &[1]), quest(3, &[1, 2])]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn headers_first_bodies_on_demand() {
+    let db = pack();
+    let root = std::env::temp_dir().join(format!("bqt-lazy-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    write_default_quests_dir(&db, &root).expect("write");
+
+    let lazy = parse_default_quests_dir_lazy(&FsDataSource, &root.to_string_lossy()).expect("lazy");
+    let eager = parse_default_quests_dir(&root).expect("eager");
+    std::fs::remove_dir_all(&root).ok();
+
+    let id = QuestId::from_parts(0, 3);
+    let header = lazy.header(id).expect("header");
+    assert_eq!(header.name, "Quest 3");
+    assert_eq!(header.prerequisites, eager.quests[&id].prerequisites);
+    assert_eq!(lazy.loaded_count(), 0);
+
+    let full = lazy.quest(id).expect("known id").expect("body");
+    assert_eq!(full.tasks.len(), 1);
+    assert_eq!(lazy.loaded_count(), 1);
+    assert!(lazy.quest(QuestId::from_parts(0, 9)).is_none());
+
+    assert_eq!(
+        lazy.into_database().expect("all bodies").quests,
+        eager.quests
+    );
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn compressed_bodies_parse_identically() {
+    use better_questing_tools::lazy::parse_default_quests_dir_lazy_compressed;
+
+    let root = std::env::temp_dir().join(format!("bqt-lazy-lz4-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    write_default_quests_dir(&pack(), &root).expect("write");
+    let root_str = root.to_string_lossy().into_owned();
+    let plain = parse_default_quests_dir_lazy(&FsDataSource, &root_str).expect("plain");
+    let mut packed =
+        parse_default_quests_dir_lazy_compressed(&FsDataSource, &root_str).expect("packed");
+    std::fs::remove_dir_all(&root).ok();
+
+    assert!(packed.stored_len() < plain.stored_len());
+    let id = QuestId::from_parts(0, 2);
+    assert_eq!(
+        packed.quest(id).unwrap().unwrap(),
+        plain.quest(id).unwrap().unwrap()
+    );
+    packed.quests.get_mut(&id).unwrap().unload();
+    assert_eq!(packed.loaded_count(), 0);
+    assert_eq!(
+        packed.into_database().unwrap().quests,
+        plain.into_database().unwrap().quests
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use better_questing_tools::db::{
+    FsDataSource, ParseOptions, ParseWarning, parse_default_quests_dir_with_options,
+    write_default_quests_dir,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::q;
+use std::collections::HashMap;
+
+#[test]
+fn lenient_mode_drops_dangling_entries_with_warnings() {
+    let props: QuestProperties =
+        serde_json::from_value(serde_json::json!({ "name": "Only quest" })).unwrap();
+    let quest = Quest {
+        id: q(1),
+        properties: Some(props),
+        tasks: vec![],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    };
+    let entry = |low| QuestLineEntry {
+        index: None,
+        quest_id: q(low),
+        x: None,
+        y: None,
+        size_x: None,
+        size_y: None,
+        extra: HashMap::new(),
+    };
+    let lid = q(50);
+    let db = QuestDatabase {
+        settings: None,
+        quests: HashMap::from([(q(1), quest)]),
+        questlines: HashMap::from([(
+            lid,
+            QuestLine {
+                id: lid,
+                properties: None,
+                entries: vec![entry(1), entry(9), entry(8)],
+                extra: HashMap::new(),
+            },
+        )]),
+        questline_order: vec![lid],
+    };
+    let root = std::env::temp_dir().join(format!("bqt-lenient-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    write_default_quests_dir(&db, &root).expect("write");
+    let root_str = root.to_string_lossy().into_owned();
+
+    let strict =
+        parse_default_quests_dir_with_options(&FsDataSource, &root_str, &ParseOptions::default());
+    let lenient = parse_default_quests_dir_with_options(
+        &FsDataSource,
+        &root_str,
+        &ParseOptions {
+            strict_refs: false,
+            ..Default::default()
+        },
+    );
+    std::fs::remove_dir_all(&root).ok();
+
+    assert!(matches!(
+        strict,
+        Err(ParseError::MissingQuestReference { .. })
+    ));
+    let outcome = lenient.expect("lenient parse");
+    assert_eq!(
+        outcome.warnings,
+        vec![
+            ParseWarning::DanglingQuestlineEntry {
+                questline: lid,
+                quest_id: q(8)
+            },
+            ParseWarning::DanglingQuestlineEntry {
+                questline: lid,
+                quest_id: q(9)
+            },
+        ]
+    );
+    let entries: Vec<QuestId> = outcome.db.questlines[&lid]
+        .entries
+        .iter()
+        .map(|e| e.quest_id)
+        .collect();
+    assert_eq!(entries, vec![q(1)]);
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use better_questing_tools::lint::{
+    LintConfig, LintFinding, LintRule, Linter, Severity, ZeroCountItems, lint,
+};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::{db, q, retrieval, stack};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest(low: i32, props: serde_json::Value, tasks: Vec<Task>, required: &[i32]) -> Quest {
+    common::quest(low)
+        .properties(props)
+        .tasks(tasks)
+        .requires(required)
+        .build()
+}
+
+fn sample_db() -> QuestDatabase {
+    let mut reward_quest = quest(
+        3,
+        json!({ "name": "Loot", "desc": "Open it", "repeat_time": -5 }),
+        vec![retrieval([stack("minecraft:dirt", 1)])],
+        &[1],
+    );
+    reward_quest.rewards.push(Reward {
+        index: Some(0),
+        reward_id: "bq_standard:choice".to_string(),
+        items: vec![],
+        choices: vec![stack("minecraft:apple", 0)],
+        ignore_disabled: None,
+        extra: HashMap::new(),
+    });
+    db(
+        [
+            quest(
+                1,
+                json!({ "name": "Start", "desc": "Begin", "repeat_time": -1 }),
+                vec![retrieval([stack("minecraft:log", 0)])],
+                &[],
+            ),
+            quest(2, json!({ "name": "Lonely", "desc": "  " }), vec![], &[]),
+            reward_quest,
+        ],
+        [],
+    )
+}
+
+fn summary(findings: &[LintFinding]) -> Vec<(Severity, &str, QuestId)> {
+    findings
+        .iter()
+        .map(|f| (f.severity, f.rule.as_str(), f.subject))
+        .collect()
+}
+
+#[test]
+fn builtin_rules_report_by_severity() {
+    let findings = lint(&sample_db());
+    assert_eq!(
+        summary(&findings),
+        [
+            (Severity::Error, "zero-count-item", q(1)),
+            (Severity::Error, "zero-count-item", q(3)),
+            (Severity::Warning, "empty-tasks", q(2)),
+            (Severity::Warning, "negative-repeat-time", q(3)),
+            (Severity::Info, "disconnected-quest", q(2)),
+            (Severity::Info, "missing-description", q(2)),
+        ]
+    );
+    assert_eq!(findings[0].message, "task 0 item minecraft:log has count 0");
+    assert_eq!(
+        findings[1].message,
+        "reward 0 choice minecraft:apple has count 0"
+    );
+}
+
+#[test]
+fn config_changes_severities_and_disables_rules() {
+    let config: LintConfig = serde_json::from_value(json!({
+        "disabled": ["disconnected-quest", "no-such-rule"],
+        "severity": { "empty-tasks": "error", "missing-description": "warning" }
+    }))
+    .unwrap();
+    let (linter, unknown) = Linter::from_config(&config);
+    assert_eq!(unknown, ["no-such-rule"]);
+    assert!(!linter.rule_names().contains(&"disconnected-quest"));
+    let findings = linter.run(&sample_db());
+    assert_eq!(
+        summary(&findings)[..3],
+        [
+            (Severity::Error, "empty-tasks", q(2)),
+            (Severity::Error, "zero-count-item", q(1)),
+            (Severity::Error, "zero-count-item", q(3)),
+        ]
+    );
+    assert!(
+        findings
+            .iter()
+            .any(|f| f.rule == "missing-description" && f.severity == Severity::Warning)
+    );
+}
+
+/// Pack-specific rule: quest names must not be shouted.
+struct NoShouting;
+
+impl LintRule for NoShouting {
+    fn name(&self) -> &'static str {
+        "no-shouting"
+    }
+    fn check(&self, db: &QuestDatabase) -> Vec<(QuestId, String)> {
+        let mut out: Vec<(QuestId, String)> = db
+            .quests
+            .values()
+            .filter(|q| {
+                q.name()
+                    .is_some_and(|n| n.len() > 1 && n == n.to_uppercase())
+            })
+            .map(|q| (q.id, "name is all caps".to_string()))
+            .collect();
+        out.sort();
+        out
+    }
+}
+
+#[test]
+fn custom_rules_plug_in() {
+    let mut db = sample_db();
+    db.quests
+        .get_mut(&q(2))
+        .unwrap()
+        .properties
+        .as_mut()
+        .unwrap()
+        .name = "LONELY".to_string();
+    let mut linter = Linter::empty();
+    linter.add(NoShouting).add(ZeroCountItems);
+    assert!(linter.set_severity("no-shouting", Severity::Info));
+    assert!(!linter.set_severity("empty-tasks", Severity::Info));
+    let findings = linter.run(&db);
+    assert_eq!(
+        summary(&findings),
+        [
+            (Severity::Error, "zero-count-item", q(1)),
+            (Severity::Error, "zero-count-item", q(3)),
+            (Severity::Info, "no-shouting", q(2)),
+        ]
+    );
+}
@@ -0,0 +1,175 @@
+use better_questing_tools::lang::{Lang, extract_translations};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::text::render_description_with;
+use serde_json::json;
+use std::collections::HashMap;
+
+const LANG: &str = "\
+# Example pack
+betterquesting.quest.1.name=Getting Wood
+betterquesting.quest.1.desc=Punch a tree.\\nAny tree.
+betterquesting.line.1.name=Chapter 1
+item.apple.name=Apple
+equation=a=b
+not a translation
+";
+
+fn sample_db() -> QuestDatabase {
+    let quest = parse_quest_from_reader(
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": {
+                "name:8": "betterquesting.quest.1.name",
+                "desc:8": "betterquesting.quest.1.desc",
+                "desc2:8": "Eat a translate:item.apple.name",
+            }},
+        })
+        .to_string()
+        .as_bytes(),
+    )
+    .unwrap();
+    let line = QuestLine {
+        id: QuestId::from_parts(0, 1),
+        properties: Some(QuestLineProperties {
+            name: "betterquesting.line.1.name".to_string(),
+            desc: Some("Literal text".to_string()),
+            ..Default::default()
+        }),
+        entries: Vec::new(),
+        extra: HashMap::new(),
+    };
+    QuestDatabase {
+        settings: None,
+        quests: [(quest.id, quest)].into_iter().collect(),
+        questline_order: vec![line.id],
+        questlines: [(line.id, line)].into_iter().collect(),
+    }
+}
+
+#[test]
+fn lang_files_are_parsed() {
+    let lang = Lang::read_lang(LANG.as_bytes()).unwrap();
+    assert_eq!(lang.len(), 5);
+    assert_eq!(lang.get("equation"), Some("a=b"));
+    // escapes stay literal unless the file asks for them
+    assert_eq!(
+        lang.get("betterquesting.quest.1.desc"),
+        Some("Punch a tree.\\nAny tree.")
+    );
+    let escaped = Lang::read_lang(format!("#PARSE_ESCAPES\n{}", LANG).as_bytes()).unwrap();
+    assert_eq!(
+        escaped.get("betterquesting.quest.1.desc"),
+        Some("Punch a tree.\nAny tree.")
+    );
+
+    let json = Lang::read_json(
+        json!({ "betterquesting.quest.1.name": "Holz sammeln" })
+            .to_string()
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(
+        json.translate("betterquesting.quest.1.name"),
+        "Holz sammeln"
+    );
+    assert_eq!(json.translate("Untranslated"), "Untranslated");
+    assert!(Lang::read_json(json!({ "key": 1 }).to_string().as_bytes()).is_err());
+}
+
+#[test]
+fn localize_substitutes_translations_into_the_database() {
+    let lang = Lang::read_lang(format!("#PARSE_ESCAPES\n{}", LANG).as_bytes()).unwrap();
+    let mut db = sample_db();
+    assert_eq!(lang.localize(&mut db), 3);
+
+    let props = db
+        .quests
+        .values()
+        .next()
+        .unwrap()
+        .properties
+        .clone()
+        .unwrap();
+    assert_eq!(props.name, "Getting Wood");
+    assert_eq!(
+        props.pages(),
+        [
+            "Punch a tree.\nAny tree.",
+            "Eat a translate:item.apple.name"
+        ]
+    );
+    assert_eq!(
+        render_description_with(&props, &lang.expander()),
+        "Punch a tree.\nAny tree.\n\nEat a Apple"
+    );
+    let line = db.questlines.values().next().unwrap();
+    let line_props = line.properties.as_ref().unwrap();
+    assert_eq!(line_props.name, "Chapter 1");
+    assert_eq!(line_props.desc.as_deref(), Some("Literal text"));
+}
+
+#[test]
+fn language_files_load_by_extension() {
+    let dir = std::env::temp_dir().join(format!("bqt-lang-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("en_us.lang"), LANG).unwrap();
+    std::fs::write(dir.join("de_de.json"), r#"{"item.apple.name": "Apfel"}"#).unwrap();
+    let mut lang = Lang::from_file(&dir.join("en_us.lang")).unwrap();
+    lang.extend(Lang::from_file(&dir.join("de_de.json")).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(lang.get("item.apple.name"), Some("Apfel"));
+    assert_eq!(lang.get("betterquesting.line.1.name"), Some("Chapter 1"));
+}
+
+#[test]
+fn extraction_generates_keys_and_a_lang_file() {
+    let lang = Lang::read_lang(format!("#PARSE_ESCAPES\n{}", LANG).as_bytes()).unwrap();
+    let mut original = sample_db();
+    lang.localize(&mut original);
+
+    let mut db = original.clone();
+    let extracted = extract_translations(&mut db, "mypack");
+    let props = db
+        .quests
+        .values()
+        .next()
+        .unwrap()
+        .properties
+        .clone()
+        .unwrap();
+    assert_eq!(props.name, "mypack.quest.1.name");
+    assert_eq!(
+        props.pages(),
+        ["mypack.quest.1.desc", "mypack.quest.1.desc2"]
+    );
+    let line_props = db
+        .questlines
+        .values()
+        .next()
+        .unwrap()
+        .properties
+        .clone()
+        .unwrap();
+    assert_eq!(line_props.name, "mypack.line.1.name");
+    assert_eq!(line_props.desc.as_deref(), Some("mypack.line.1.desc"));
+    assert_eq!(extracted.len(), 5);
+    assert_eq!(extracted.get("mypack.line.1.desc"), Some("Literal text"));
+    // a second run finds nothing left to extract
+    assert!(extract_translations(&mut db.clone(), "mypack").is_empty());
+
+    let mut file = Vec::new();
+    extracted.write_lang(&mut file).unwrap();
+    let text = String::from_utf8(file).unwrap();
+    assert!(text.starts_with("#PARSE_ESCAPES\n"));
+    assert!(text.contains("mypack.quest.1.desc=Punch a tree.\\nAny tree.\n"));
+    let read_back = Lang::read_lang(text.as_bytes()).unwrap();
+    assert_eq!(read_back, extracted);
+    read_back.localize(&mut db);
+    assert_eq!(db, original);
+
+    let mut json = Vec::new();
+    extracted.write_json(&mut json).unwrap();
+    assert_eq!(Lang::read_json(json.as_slice()).unwrap(), extracted);
+}
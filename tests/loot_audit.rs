@@ -0,0 +1,147 @@
+mod common;
+
+use better_questing_tools::analysis::{
+    LootIssue, audit_loot, expected_values, reward_roll, roll_issues,
+};
+use better_questing_tools::index::ItemKey;
+use better_questing_tools::model::{ItemStack, Quest, Reward};
+use common::{db, item_reward, q, stack};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn weighted(id: &str, count: i32, weight: f64) -> ItemStack {
+    let mut item = stack(id, count);
+    item.extra.insert("weight".to_string(), json!(weight));
+    item
+}
+
+/// A named quest with a single reward, given in its JSON form.
+fn quest(low: i32, reward: Value) -> Quest {
+    let reward: Reward = serde_json::from_value(reward).expect("reward");
+    common::quest(low)
+        .name(&format!("Quest {low}"))
+        .rewards([reward])
+        .build()
+}
+
+fn key(id: &str) -> ItemKey {
+    ItemKey {
+        id: id.to_string(),
+        damage: 0,
+    }
+}
+
+fn prices() -> HashMap<ItemKey, f64> {
+    HashMap::from([
+        (key("minecraft:diamond"), 10.0),
+        (key("minecraft:iron_ingot"), 1.0),
+        (key("minecraft:bread"), 0.5),
+    ])
+}
+
+#[test]
+fn expected_value_weights_pools_and_takes_best_choice() {
+    let pools = quest(
+        1,
+        json!({
+            "reward_id": "lootbox:pool",
+            "index": 0,
+            "rewards": [stack("minecraft:bread", 4)],
+            "pools": [
+                { "weight": 3, "rewards": [stack("minecraft:iron_ingot", 4)] },
+                { "weight": 1, "rewards": [stack("minecraft:diamond", 2)] },
+            ],
+        }),
+    );
+    let roll = reward_roll(&pools.rewards[0]);
+    assert_eq!(roll.fixed.len(), 1);
+    assert_eq!(roll.pools.len(), 2);
+    assert_eq!(roll.pools[0].weight, Some(3.0));
+    assert!(roll_issues(&roll).is_empty());
+
+    let choice = quest(
+        2,
+        json!({
+            "reward_id": "bq_standard:choice",
+            "choices": [stack("minecraft:bread", 1), stack("minecraft:diamond", 1)],
+        }),
+    );
+    let random_choice = quest(
+        3,
+        json!({
+            "reward_id": "bq_standard:choice",
+            "choices": [
+                weighted("minecraft:diamond", 1, 0.25),
+                weighted("minecraft:iron_ingot", 2, 0.75),
+            ],
+        }),
+    );
+    assert!(reward_roll(&random_choice.rewards[0]).is_random());
+
+    let values = expected_values(&db([pools, choice, random_choice], []), &prices());
+    // 4 * 0.5 + (3/4 * 4 + 1/4 * 20)
+    assert!((values[&q(1)] - 10.0).abs() < 1e-9);
+    assert!((values[&q(2)] - 10.0).abs() < 1e-9);
+    // 0.25 * 10 + 0.75 * 2
+    assert!((values[&q(3)] - 4.0).abs() < 1e-9);
+}
+
+#[test]
+fn audit_flags_bad_weights_and_unobtainable_items() {
+    let fractions = quest(
+        1,
+        json!({
+            "reward_id": "bq_standard:choice",
+            "choices": [
+                weighted("minecraft:diamond", 1, 0.5),
+                weighted("minecraft:bread", 1, 0.25),
+            ],
+        }),
+    );
+    let broken = quest(
+        2,
+        json!({
+            "reward_id": "lootbox:pool",
+            "index": 0,
+            "pools": [
+                { "weight": 0, "rewards": [stack("minecraft:diamond", 1)] },
+                { "weight": -2, "rewards": [stack("minecraft:bread", 0)] },
+                { "weight": "often" },
+            ],
+        }),
+    );
+    let plain = common::quest(3)
+        .name("Quest 3")
+        .rewards([item_reward([stack("minecraft:air", 0)])])
+        .build();
+
+    let findings = audit_loot(&db([plain, broken, fractions], []));
+    let issues: Vec<(i64, LootIssue)> = findings
+        .into_iter()
+        .map(|f| (f.quest.low_i64(), f.issue))
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            (1, LootIssue::ProbabilitiesDoNotSumToOne { total: 0.75 }),
+            (
+                2,
+                LootIssue::UnobtainableItem {
+                    pool: 0,
+                    item: key("minecraft:diamond")
+                }
+            ),
+            (2, LootIssue::InvalidWeight { pool: 1 }),
+            (
+                2,
+                LootIssue::UnobtainableItem {
+                    pool: 1,
+                    item: key("minecraft:bread")
+                }
+            ),
+            (2, LootIssue::InvalidWeight { pool: 2 }),
+            (2, LootIssue::EmptyPool { pool: 2 }),
+            (2, LootIssue::NothingDrawable),
+        ]
+    );
+}
@@ -0,0 +1,125 @@
+use better_questing_tools::export::markdown::{questbook, questbook_index, write_questbook};
+use better_questing_tools::model::{QuestDatabase, QuestLine, QuestLineEntry};
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn line(id: i64, name: &str, desc: &str, quests: &[i64]) -> QuestLine {
+    QuestLine {
+        id: QuestId::from_parts_i64(0, id),
+        properties: Some(serde_json::from_value(json!({ "name": name, "desc": desc })).unwrap()),
+        entries: quests
+            .iter()
+            .map(|q| QuestLineEntry {
+                index: None,
+                quest_id: QuestId::from_parts_i64(0, *q),
+                x: None,
+                y: None,
+                size_x: None,
+                size_y: None,
+                extra: HashMap::new(),
+            })
+            .collect(),
+        extra: HashMap::new(),
+    }
+}
+
+fn sample_db() -> QuestDatabase {
+    let quests = [
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": {
+                "name:8": "§aGetting *Wood*", "desc:8": "Punch a tree.\nAny tree.\n\nReally.",
+            }},
+            "tasks:9": { "0:10": { "taskID:8": "bq_standard:retrieval", "index:3": 0,
+                "requiredItems:9": { "0:10": { "id:8": "minecraft:log", "Count:3": 16 } } } },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:item", "index:3": 0,
+                "rewards:9": { "0:10": { "id:8": "minecraft:apple", "Count:3": 2 } } } },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 2,
+            "properties:10": { "betterquesting:10": { "name:8": "Planks" } },
+            "preRequisites:9": { "0:10": { "questIDHigh:4": 0, "questIDLow:4": 1 } },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 3,
+            "properties:10": { "betterquesting:10": { "name:8": "Stone Age" } },
+            "preRequisites:9": {
+                "0:10": { "questIDHigh:4": 0, "questIDLow:4": 2 },
+                "1:10": { "questIDHigh:4": 0, "questIDLow:4": 4 },
+            },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 4,
+            "properties:10": { "betterquesting:10": { "name:8": "Hidden" } },
+        }),
+    ];
+    let lines = [
+        line(10, "§lChapter 1: Wood", "The beginning.", &[1, 2]),
+        line(11, "Stone", "", &[3]),
+    ];
+    QuestDatabase {
+        settings: None,
+        quests: quests
+            .iter()
+            .map(|v| parse_quest_from_reader(v.to_string().as_bytes()).unwrap())
+            .map(|q| (q.id, q))
+            .collect(),
+        questline_order: lines.iter().map(|l| l.id).collect(),
+        questlines: lines.into_iter().map(|l| (l.id, l)).collect(),
+    }
+}
+
+#[test]
+fn one_page_per_questline_with_linked_prerequisites() {
+    let db = sample_db();
+    let pages = questbook(&db);
+    let names: Vec<&str> = pages.iter().map(|p| p.file_name.as_str()).collect();
+    assert_eq!(names, ["01-chapter-1-wood.md", "02-stone.md"]);
+
+    assert_eq!(
+        pages[0].markdown,
+        "# Chapter 1: Wood\n\n\
+         The beginning.\n\n\
+         <a id=\"quest-1\"></a>\n\n\
+         ## Getting \\*Wood\\*\n\n\
+         Punch a tree.\\\nAny tree.\n\nReally.\n\n\
+         **Tasks:**\n\n\
+         - bq\\_standard:retrieval: 16x minecraft:log\n\n\
+         **Rewards:**\n\n\
+         - 2x minecraft:apple\n\n\
+         <a id=\"quest-2\"></a>\n\n\
+         ## Planks\n\n\
+         **Requires:**\n\n\
+         - [Getting \\*Wood\\*](#quest-1)\n"
+    );
+    assert!(
+        pages[1]
+            .markdown
+            .contains("**Requires:**\n\n- [Planks](01-chapter-1-wood.md#quest-2)\n- Hidden\n")
+    );
+}
+
+#[test]
+fn index_lists_pages_and_loose_quests() {
+    let db = sample_db();
+    let pages = questbook(&db);
+    assert_eq!(
+        questbook_index(&db, &pages),
+        "# Questbook\n\n\
+         - [Chapter 1: Wood](01-chapter-1-wood.md)\n\
+         - [Stone](02-stone.md)\n\n\
+         ## Quests on no questline\n\n\
+         - Hidden (4)\n"
+    );
+
+    let dir = std::env::temp_dir().join(format!("bqt-questbook-{}", std::process::id()));
+    write_questbook(&db, &dir).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("02-stone.md")).unwrap(),
+        pages[1].markdown
+    );
+    assert!(dir.join("README.md").exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use better_questing_tools::db::{
+    MemoryDataSource, QuestDataSink, QuestDataSource, parse_default_quests_dir_from_source,
+    write_default_quests_dir_to_sink,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use common::q;
+use std::collections::HashMap;
+
+fn quest_json(low: i32, name: &str) -> String {
+    serde_json::json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "properties:10": { "betterquesting:10": { "name:8": name } }
+    })
+    .to_string()
+}
+
+#[test]
+fn parses_a_synthetic_pack() {
+    let source: MemoryDataSource = [
+        ("pack/Quests/1.json", quest_json(1, "First")),
+        ("pack/Quests/2.json", quest_json(2, "Second")),
+        ("pack/Quests/notes.txt", "not a quest".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    assert!(source.is_dir("pack"));
+    assert!(source.is_dir("pack/Quests/"));
+    assert!(!source.is_dir("pack/QuestLines"));
+    assert_eq!(source.list_dir("pack").unwrap(), vec!["Quests".to_string()]);
+    assert_eq!(
+        source.list_dir("pack/Quests").unwrap(),
+        ["1.json", "2.json", "notes.txt"]
+    );
+
+    let db = parse_default_quests_dir_from_source(&source, "pack").unwrap();
+    assert_eq!(db.quests.len(), 2);
+    assert_eq!(db.quests[&q(2)].name(), Some("Second"));
+    assert!(db.questlines.is_empty());
+}
+
+#[test]
+fn missing_paths_are_io_errors() {
+    let source = MemoryDataSource::new();
+    assert!(matches!(
+        source.read_to_string("a.json"),
+        Err(ParseError::Io(_))
+    ));
+    assert!(matches!(source.list_dir("pack"), Err(ParseError::Io(_))));
+    assert!(parse_default_quests_dir_from_source(&source, "pack").is_err());
+}
+
+#[test]
+fn written_database_parses_back() {
+    let props: QuestProperties =
+        serde_json::from_value(serde_json::json!({ "name": "Only" })).unwrap();
+    let quest = Quest {
+        id: q(7),
+        properties: Some(props),
+        tasks: vec![],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    };
+    let line = QuestLine {
+        id: q(100),
+        properties: Some(QuestLineProperties {
+            name: "Line".to_string(),
+            ..Default::default()
+        }),
+        entries: vec![QuestLineEntry {
+            index: None,
+            quest_id: q(7),
+            x: Some(0),
+            y: Some(0),
+            size_x: None,
+            size_y: None,
+            extra: HashMap::new(),
+        }],
+        extra: HashMap::new(),
+    };
+    let db = QuestDatabase {
+        settings: None,
+        quests: [(quest.id, quest)].into_iter().collect(),
+        questline_order: vec![line.id],
+        questlines: [(line.id, line)].into_iter().collect(),
+    };
+
+    let mut memory = MemoryDataSource::new();
+    write_default_quests_dir_to_sink(&mut memory, "out/DefaultQuests", &db).unwrap();
+    assert!(memory.paths().all(|p| p.starts_with("out/DefaultQuests/")));
+    assert_eq!(
+        parse_default_quests_dir_from_source(&memory, "out/DefaultQuests").unwrap(),
+        db
+    );
+
+    let quest_file = memory
+        .paths()
+        .find(|p| p.contains("/Quests/"))
+        .unwrap()
+        .to_string();
+    memory.remove_file(&quest_file).unwrap();
+    assert!(memory.get(&quest_file).is_none());
+    assert!(memory.remove_file(&quest_file).is_err());
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "memmap2")]
+
+use better_questing_tools::parser::{parse_quest_from_file, parse_quest_from_file_mmap};
+
+#[test]
+fn mapped_and_buffered_parsing_agree() {
+    let path = std::env::temp_dir().join(format!("bqt-mmap-{}.json", std::process::id()));
+    let json = r#"{
+        "questIDHigh:4": 0,
+        "questIDLow:4": 7,
+        "properties:10": { "betterquesting:10": { "name:8": "Mapped" } }
+    }"#;
+    std::fs::write(&path, json).expect("write quest");
+    let mapped = parse_quest_from_file_mmap(&path).expect("mmap parse");
+    let buffered = parse_quest_from_file(&path).expect("buffered parse");
+    std::fs::remove_file(&path).ok();
+    assert_eq!(mapped, buffered);
+    assert_eq!(mapped.name(), Some("Mapped"));
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn mapped_archives_read_entries_on_demand() {
+    use better_questing_tools::archive::{MappedZipDataSource, ZipDataSource};
+    use better_questing_tools::db::QuestDataSource;
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("bqt-mmap-{}.zip", std::process::id()));
+    let root = "pack/config/betterquesting/DefaultQuests";
+    {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).expect("create zip"));
+        let opts = zip::write::SimpleFileOptions::default();
+        zip.start_file(format!("{root}/Quests/1.json"), opts)
+            .unwrap();
+        zip.write_all(
+            br#"{
+            "questIDHigh:4": 0,
+            "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": { "name:8": "Zipped" } }
+        }"#,
+        )
+        .unwrap();
+        zip.start_file("pack/manifest.json", opts).unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.finish().unwrap();
+    }
+    let mapped = MappedZipDataSource::open(&path).expect("map zip");
+    let loaded = ZipDataSource::open(&path).expect("read zip");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(mapped.find_default_quests().as_deref(), Some(root));
+    assert!(mapped.is_file(&format!("{root}/Quests/1.json")));
+    assert!(!mapped.is_file("pack/manifest.json"));
+    assert!(mapped.read_to_string("pack/manifest.json").is_err());
+    assert_eq!(
+        mapped.list_dir(root).unwrap(),
+        loaded.list_dir(root).unwrap()
+    );
+    assert_eq!(mapped.parse().unwrap(), loaded.parse().unwrap());
+}
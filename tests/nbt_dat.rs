@@ -0,0 +1,241 @@
+#![cfg(feature = "nbt")]
+
+use better_questing_tools::dat::{
+    parse_quest_database_dat, parse_quest_database_from_reader, read_nbt,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::quest_id::QuestId;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::Write;
+
+/// Minimal NBT writer for building fixtures.
+enum Tag {
+    Byte(i8),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Str(&'static str),
+    List(u8, Vec<Tag>),
+    Compound(Vec<(&'static str, Tag)>),
+}
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Str(_) => 8,
+            Tag::List(..) => 9,
+            Tag::Compound(_) => 10,
+        }
+    }
+
+    fn write_payload(&self, out: &mut Vec<u8>) {
+        match self {
+            Tag::Byte(b) => out.push(*b as u8),
+            Tag::Int(i) => out.extend(i.to_be_bytes()),
+            Tag::Long(l) => out.extend(l.to_be_bytes()),
+            Tag::Float(f) => out.extend(f.to_be_bytes()),
+            Tag::Str(s) => write_str(out, s),
+            Tag::List(elem, items) => {
+                out.push(*elem);
+                out.extend((items.len() as i32).to_be_bytes());
+                for item in items {
+                    item.write_payload(out);
+                }
+            }
+            Tag::Compound(fields) => {
+                for (name, tag) in fields {
+                    out.push(tag.id());
+                    write_str(out, name);
+                    tag.write_payload(out);
+                }
+                out.push(0);
+            }
+        }
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    out.extend((s.len() as u16).to_be_bytes());
+    out.extend(s.as_bytes());
+}
+
+fn document(root: Tag) -> Vec<u8> {
+    let mut out = vec![10];
+    write_str(&mut out, "");
+    root.write_payload(&mut out);
+    out
+}
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(bytes).unwrap();
+    enc.finish().unwrap()
+}
+
+fn quest(low: i64, name: &'static str, prereqs: Vec<Tag>) -> Tag {
+    Tag::Compound(vec![
+        ("questIDHigh", Tag::Long(0)),
+        ("questIDLow", Tag::Long(low)),
+        (
+            "properties",
+            Tag::Compound(vec![(
+                "betterquesting",
+                Tag::Compound(vec![("name", Tag::Str(name)), ("isMain", Tag::Byte(1))]),
+            )]),
+        ),
+        ("preRequisites", Tag::List(10, prereqs)),
+    ])
+}
+
+fn prereq(low: i64) -> Tag {
+    Tag::Compound(vec![
+        ("questIDHigh", Tag::Long(0)),
+        ("questIDLow", Tag::Long(low)),
+    ])
+}
+
+fn sample() -> Tag {
+    Tag::Compound(vec![
+        ("format", Tag::Str("2.0.0")),
+        (
+            "questSettings",
+            Tag::Compound(vec![(
+                "betterquesting",
+                Tag::Compound(vec![
+                    ("version", Tag::Str("3.0.0")),
+                    ("editMode", Tag::Byte(0)),
+                ]),
+            )]),
+        ),
+        (
+            "questDatabase",
+            Tag::List(
+                10,
+                vec![quest(1, "Start", vec![]), quest(2, "Next", vec![prereq(1)])],
+            ),
+        ),
+        (
+            "questLines",
+            Tag::List(
+                10,
+                vec![Tag::Compound(vec![
+                    ("questLineIDHigh", Tag::Long(0)),
+                    ("questLineIDLow", Tag::Long(7)),
+                    (
+                        "properties",
+                        Tag::Compound(vec![(
+                            "betterquesting",
+                            Tag::Compound(vec![("name", Tag::Str("Chapter 1"))]),
+                        )]),
+                    ),
+                    (
+                        "quests",
+                        Tag::List(
+                            10,
+                            vec![
+                                Tag::Compound(vec![
+                                    ("questIDHigh", Tag::Long(0)),
+                                    ("questIDLow", Tag::Long(2)),
+                                    ("x", Tag::Int(24)),
+                                    ("y", Tag::Int(0)),
+                                ]),
+                                Tag::Compound(vec![
+                                    ("questIDHigh", Tag::Long(0)),
+                                    ("questIDLow", Tag::Long(1)),
+                                    ("x", Tag::Int(0)),
+                                    ("sizeX", Tag::Float(24.0)),
+                                ]),
+                            ],
+                        ),
+                    ),
+                ])],
+            ),
+        ),
+    ])
+}
+
+#[test]
+fn gzipped_dat_file_parses_into_database() {
+    let dir = std::env::temp_dir().join(format!("bqt-nbt-dat-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("QuestDatabase.dat");
+    std::fs::write(&path, gzip(&document(sample()))).unwrap();
+
+    let db = parse_quest_database_dat(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(db.quests.len(), 2);
+    let next = &db.quests[&QuestId::from_parts(0, 2)];
+    assert_eq!(next.name(), Some("Next"));
+    assert_eq!(next.prerequisites, vec![QuestId::from_parts(0, 1)]);
+
+    let lid = QuestId::from_parts(0, 7);
+    assert_eq!(db.questline_order, vec![lid]);
+    let line = &db.questlines[&lid];
+    assert_eq!(line.properties.as_ref().unwrap().name, "Chapter 1");
+    // entries keep the stored list order
+    let entries: Vec<_> = line.entries.iter().map(|e| (e.quest_id, e.x)).collect();
+    assert_eq!(
+        entries,
+        vec![
+            (QuestId::from_parts(0, 2), Some(24)),
+            (QuestId::from_parts(0, 1), Some(0)),
+        ]
+    );
+    assert_eq!(db.settings.unwrap().version.as_deref(), Some("3.0.0"));
+}
+
+#[test]
+fn uncompressed_nbt_decodes_to_tagged_json() {
+    let bytes = document(Tag::Compound(vec![
+        ("name", Tag::Str("x")),
+        ("ids", Tag::List(3, vec![Tag::Int(4), Tag::Int(5)])),
+    ]));
+    let v = read_nbt(bytes.as_slice()).unwrap();
+    assert_eq!(
+        v,
+        serde_json::json!({"name:8": "x", "ids:9": {"0:3": 4, "1:3": 5}})
+    );
+}
+
+#[test]
+fn dangling_questline_entry_is_rejected() {
+    let root = Tag::Compound(vec![(
+        "questLines",
+        Tag::List(
+            10,
+            vec![Tag::Compound(vec![
+                ("questLineIDLow", Tag::Long(1)),
+                (
+                    "quests",
+                    Tag::List(10, vec![Tag::Compound(vec![("questIDLow", Tag::Long(9))])]),
+                ),
+            ])],
+        ),
+    )]);
+    let err = parse_quest_database_from_reader(document(root).as_slice()).unwrap_err();
+    assert!(matches!(err, ParseError::MissingQuestReference { .. }));
+}
+
+#[test]
+fn truncated_input_fails() {
+    let bytes = document(sample());
+    assert!(read_nbt(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn oversized_byte_array_length_fails_without_allocating() {
+    let mut bytes = vec![10];
+    write_str(&mut bytes, "");
+    bytes.push(7);
+    write_str(&mut bytes, "data");
+    bytes.extend(i32::MAX.to_be_bytes());
+    bytes.extend([1, 2, 3]);
+    let err = read_nbt(bytes.as_slice()).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidFormat(_)), "{err:?}");
+}
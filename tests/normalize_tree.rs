@@ -0,0 +1,44 @@
+use better_questing_tools::db::parse_default_quests_dir;
+use better_questing_tools::normalize::{canonical_json, normalize_tree};
+use serde_json::{Value, json};
+
+#[test]
+fn canonical_layout_orders_list_indices_numerically() {
+    let v =
+        json!({ "b:8": "x", "a:9": { "10:3": 3, "2:3": 2, "0:3": 1 }, "e:9": {}, "l": [1, []] });
+    assert_eq!(
+        canonical_json(&v),
+        "{\n  \"a:9\": {\n    \"0:3\": 1,\n    \"2:3\": 2,\n    \"10:3\": 3\n  },\n  \"b:8\": \"x\",\n  \"e:9\": {},\n  \"l\": [\n    1,\n    []\n  ]\n}\n"
+    );
+}
+
+#[test]
+fn tree_is_rewritten_once_without_semantic_changes() {
+    let root = std::env::temp_dir().join(format!("bqt-normalize-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("Quests")).unwrap();
+    let quest = r#"{"questIDLow:4":1,"questIDHigh:4":0,"properties:10":{"betterquesting:10":{"name:8":"Start","desc:8":"a \"quoted\" line"}},
+        "tasks:9":{}}"#;
+    let path = root.join("Quests/1.json");
+    std::fs::write(&path, quest).unwrap();
+    let before = parse_default_quests_dir(&root).unwrap();
+
+    let summary = normalize_tree(&root).unwrap();
+    assert_eq!(summary.written.len(), 1);
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("{\n  \"properties:10\": {\n"));
+    let old: Value = serde_json::from_str(quest).unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), old);
+    assert_eq!(parse_default_quests_dir(&root).unwrap(), before);
+
+    let again = normalize_tree(&root).unwrap();
+    assert!(again.written.is_empty());
+    assert_eq!(again.unchanged, 1);
+
+    // a broken file stops the run before anything is written
+    std::fs::write(&path, quest).unwrap();
+    std::fs::write(root.join("Quests/2.json"), "{ not json").unwrap();
+    assert!(normalize_tree(&root).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), quest);
+    std::fs::remove_dir_all(&root).unwrap();
+}
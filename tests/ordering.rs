@@ -0,0 +1,67 @@
+mod common;
+
+use better_questing_tools::db::{
+    MemoryDataSource, parse_default_quests_dir_from_source, write_default_quests_dir_to_sink,
+};
+use better_questing_tools::export::summary::summarize;
+use better_questing_tools::model::*;
+use better_questing_tools::ordering::{natural_cmp, questline_ids, sorted_quests};
+use better_questing_tools::quest_id::QuestId;
+use common::{db, q, quest};
+use std::cmp::Ordering;
+
+fn line(low: i32, quest: i32) -> QuestLine {
+    common::line(low)
+        .name(&format!("Line {low}"))
+        .entry_at(quest, 0, 0)
+        .build()
+}
+
+/// Twelve questlines in a shuffled order, one quest each.
+fn sample_db() -> QuestDatabase {
+    let order: Vec<i32> = vec![105, 111, 100, 103, 110, 101, 109, 102, 104, 108, 106, 107];
+    db(
+        (1..=12).map(|low| quest(low).name(&format!("Quest {low}")).build()),
+        order
+            .iter()
+            .enumerate()
+            .map(|(i, low)| line(*low, i as i32 + 1)),
+    )
+}
+
+#[test]
+fn natural_order_compares_digit_runs_by_value() {
+    let mut names = vec!["10", "2", "1", "line-10", "line-9", "01", "b", "a"];
+    names.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(names, ["01", "1", "2", "10", "a", "b", "line-9", "line-10"]);
+    assert_eq!(natural_cmp("x", "x"), Ordering::Equal);
+}
+
+#[test]
+fn unlisted_questlines_follow_the_order_by_id() {
+    let mut db = sample_db();
+    db.questline_order = vec![q(110), q(999), q(103), q(110)];
+    let ids = questline_ids(&db);
+    assert_eq!(ids.len(), 12);
+    assert_eq!(&ids[..2], [q(110), q(103)]);
+    let rest: Vec<QuestId> = ids[2..].to_vec();
+    let mut sorted = rest.clone();
+    sorted.sort();
+    assert_eq!(rest, sorted);
+
+    let quests: Vec<QuestId> = sorted_quests(&db).iter().map(|q| q.id).collect();
+    assert_eq!(quests, (1..=12).map(q).collect::<Vec<_>>());
+    // Exports list every questline, the unlisted ones too.
+    assert_eq!(summarize(&db).questlines.len(), 12);
+}
+
+#[test]
+fn questline_order_survives_a_write_and_parse() {
+    let db = sample_db();
+    let mut files = MemoryDataSource::new();
+    write_default_quests_dir_to_sink(&mut files, "pack", &db).unwrap();
+    for _ in 0..3 {
+        let parsed = parse_default_quests_dir_from_source(&files, "pack").unwrap();
+        assert_eq!(parsed.questline_order, db.questline_order);
+    }
+}
@@ -0,0 +1,139 @@
+use better_questing_tools::diff::QuestChange;
+use better_questing_tools::model::{Quest, QuestDatabase};
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::report::compare_databases;
+use better_questing_tools::validate::ValidationIssue;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest_json(id: i32, name: &str, prereqs: &[i32]) -> String {
+    let mut pre = serde_json::Map::new();
+    for (i, p) in prereqs.iter().enumerate() {
+        pre.insert(
+            format!("{}:10", i),
+            json!({ "questIDHigh:4": 0, "questIDLow:4": p }),
+        );
+    }
+    json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": id,
+        "preRequisites:9": pre,
+        "properties:10": { "betterquesting:10": { "name:8": name } },
+    })
+    .to_string()
+}
+
+fn db(quests: &[(i32, &str, &[i32])]) -> QuestDatabase {
+    let quests: HashMap<QuestId, Quest> = quests
+        .iter()
+        .map(|(id, name, pre)| {
+            let q = parse_quest_from_reader(quest_json(*id, name, pre).as_bytes()).expect("quest");
+            (q.id, q)
+        })
+        .collect();
+    QuestDatabase {
+        settings: None,
+        quests,
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn report_combines_diff_and_validation() {
+    let old = db(&[(1, "Start", &[]), (2, "Wood", &[1]), (3, "Stone", &[9])]);
+    let new = db(&[(1, "Start", &[]), (2, "Planks", &[1]), (4, "Iron", &[2, 8])]);
+    let report = compare_databases(&old, &new);
+
+    assert_eq!(report.old.quests, 3);
+    assert_eq!(report.new.quests, 3);
+    assert_eq!(report.old.depth.max, 1);
+    assert_eq!(report.new.depth.max, 2);
+    assert_eq!(report.diff.added_quests[0].id, QuestId::from_parts(0, 4));
+    assert_eq!(report.diff.removed_quests[0].name, "Stone");
+    assert_eq!(
+        report.diff.changed_quests[0].changes,
+        vec![QuestChange::Renamed {
+            old: "Wood".to_string(),
+            new: "Planks".to_string()
+        }]
+    );
+    // small packs never move more than the threshold
+    assert_eq!(report.importance_shifts, Some(vec![]));
+    assert_eq!(
+        report.new_issues,
+        vec![ValidationIssue::MissingPrerequisite {
+            quest: QuestId::from_parts(0, 4),
+            missing: QuestId::from_parts(0, 8)
+        }]
+    );
+    assert_eq!(
+        report.resolved_issues,
+        vec![ValidationIssue::MissingPrerequisite {
+            quest: QuestId::from_parts(0, 3),
+            missing: QuestId::from_parts(0, 9)
+        }]
+    );
+
+    let md = report.to_markdown();
+    assert!(md.contains("| Max depth | 1 | 2 |"));
+    assert!(md.contains("## Added quests\n\n- Iron (4)"));
+    assert!(md.contains("- Planks (2): renamed from \"Wood\" to \"Planks\""));
+    assert!(md.contains("## New issues\n\n- quest 4 requires missing quest 8"));
+    assert!(!md.contains("Importance shifts"));
+
+    let v: serde_json::Value = serde_json::from_str(&report.to_json().expect("json")).unwrap();
+    assert_eq!(v["new_issues"][0]["kind"], "missing_prerequisite");
+}
+
+#[test]
+fn cycles_disable_importance_shifts() {
+    let old = db(&[(1, "A", &[])]);
+    let new = db(&[(1, "A", &[2]), (2, "B", &[1])]);
+    let report = compare_databases(&old, &new);
+    assert_eq!(report.importance_shifts, None);
+    assert!(matches!(
+        report.new_issues[0],
+        ValidationIssue::PrerequisiteCycle { .. }
+    ));
+    assert!(report.to_markdown().contains("not computed"));
+}
+
+#[cfg(feature = "zip")]
+#[test]
+fn zip_archives_are_discovered_and_parsed() {
+    use better_questing_tools::archive::ZipDataSource;
+    use std::io::Write;
+
+    let mut buf = std::io::Cursor::new(Vec::new());
+    {
+        let mut zip = zip::ZipWriter::new(&mut buf);
+        let opts = zip::write::SimpleFileOptions::default();
+        let root = "MyPack/overrides/config/betterquesting/DefaultQuests";
+        zip.start_file(format!("{root}/Quests/1.json"), opts)
+            .unwrap();
+        zip.write_all(quest_json(1, "Start", &[]).as_bytes())
+            .unwrap();
+        zip.start_file(format!("{root}/Quests/2.json"), opts)
+            .unwrap();
+        zip.write_all(quest_json(2, "Next", &[1]).as_bytes())
+            .unwrap();
+        zip.start_file("MyPack/manifest.json", opts).unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.finish().unwrap();
+    }
+    buf.set_position(0);
+
+    let source = ZipDataSource::from_reader(buf).expect("zip");
+    assert_eq!(
+        source.find_default_quests().as_deref(),
+        Some("MyPack/overrides/config/betterquesting/DefaultQuests")
+    );
+    let db = source.parse().expect("parse");
+    assert_eq!(db.quests.len(), 2);
+    assert_eq!(
+        db.quests[&QuestId::from_parts(0, 2)].prerequisites,
+        vec![QuestId::from_parts(0, 1)]
+    );
+}
@@ -0,0 +1,71 @@
+use better_questing_tools::db::{MemoryDataSource, parse_default_quests_dir_from_source};
+use better_questing_tools::error::ParseError;
+use serde_json::json;
+
+fn quest(low: i32) -> serde_json::Value {
+    json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "properties:10": { "betterquesting:10": { "name:8": "Quest" } }
+    })
+}
+
+fn parse_with(path: &str, text: String) -> ParseError {
+    let line = json!({ "questLineIDHigh:4": 0, "questLineIDLow:4": 0 });
+    let source: MemoryDataSource = [
+        ("pack/Quests/1.json".to_string(), quest(1).to_string()),
+        (
+            "pack/QuestLines/0/QuestLine.json".to_string(),
+            line.to_string(),
+        ),
+        (path.to_string(), text),
+    ]
+    .into_iter()
+    .collect();
+    parse_default_quests_dir_from_source(&source, "pack").unwrap_err()
+}
+
+#[test]
+fn wrongly_typed_field_is_located() {
+    let mut broken = quest(2);
+    broken["questIDLow:4"] = json!("two");
+    let err = parse_with("pack/Quests/2.json", broken.to_string());
+    match &err {
+        ParseError::InFile {
+            path,
+            pointer,
+            source,
+        } => {
+            assert_eq!(path, "pack/Quests/2.json");
+            assert_eq!(pointer.as_deref(), Some("/questIDLow:4"));
+            assert!(matches!(**source, ParseError::Json(_)));
+        }
+        other => panic!("expected InFile, got {:?}", other),
+    }
+    assert!(
+        err.to_string()
+            .starts_with("pack/Quests/2.json at /questIDLow:4: json error:")
+    );
+}
+
+#[test]
+fn syntax_errors_name_the_file() {
+    let err = parse_with("pack/Quests/2.json", "{ not json".to_string());
+    assert!(matches!(
+        err,
+        ParseError::InFile { ref path, pointer: None, .. } if path == "pack/Quests/2.json"
+    ));
+}
+
+#[test]
+fn questline_entry_errors_name_the_file() {
+    let entry = json!({ "questIDHigh:4": 0, "questIDLow:4": 1, "x:3": 5_000_000_000i64 });
+    let err = parse_with("pack/QuestLines/0/1.json", entry.to_string());
+    match err {
+        ParseError::InFile { path, source, .. } => {
+            assert_eq!(path, "pack/QuestLines/0/1.json");
+            assert!(matches!(*source, ParseError::NumberOutOfRange { .. }));
+        }
+        other => panic!("expected InFile, got {:?}", other),
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use better_questing_tools::db::{
+    FsDataSource, ParseOptions, parse_default_quests_dir_with_options, write_default_quests_dir,
+};
+use better_questing_tools::metrics::{
+    ParseStage, parse_default_quests_dir_with_metrics, parse_default_quests_dir_with_progress,
+};
+use better_questing_tools::model::*;
+use common::{db, line};
+
+fn quest(low: i32) -> Quest {
+    common::quest(low).name(&format!("Quest {low}")).build()
+}
+
+fn sample_db() -> QuestDatabase {
+    db(
+        [quest(1), quest(2), quest(3)],
+        [line(100)
+            .name("Line")
+            .entry_at(1, 0, 0)
+            .entry_at(2, 0, 0)
+            .build()],
+    )
+}
+
+#[test]
+fn metrics_count_files_bytes_and_stages() {
+    let dir = std::env::temp_dir().join(format!("bqt-metrics-{}", std::process::id()));
+    write_default_quests_dir(&sample_db(), &dir).unwrap();
+    let root = dir.to_string_lossy();
+    let options = ParseOptions::default();
+    let (outcome, metrics) =
+        parse_default_quests_dir_with_metrics(&FsDataSource, &root, &options).unwrap();
+    let plain = parse_default_quests_dir_with_options(&FsDataSource, &root, &options).unwrap();
+    let total_bytes: u64 = walk(&dir).iter().map(|p| p.metadata().unwrap().len()).sum();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(outcome, plain);
+    assert_eq!((metrics.quests, metrics.questlines), (3, 1));
+    // Three quest files, one QuestLine.json and two entry files.
+    assert_eq!(metrics.files_parsed, 6);
+    assert_eq!(metrics.bytes_read, total_bytes);
+    assert!(metrics.files_seen >= metrics.files_parsed);
+
+    let stages: Vec<ParseStage> = metrics.per_stage.iter().map(|s| s.stage).collect();
+    assert_eq!(
+        stages,
+        [
+            ParseStage::Settings,
+            ParseStage::Quests,
+            ParseStage::Questlines,
+            ParseStage::References
+        ]
+    );
+    let parsed: Vec<usize> = metrics.per_stage.iter().map(|s| s.files_parsed).collect();
+    assert_eq!(parsed, [0, 3, 3, 0]);
+    assert_eq!(
+        metrics.per_stage.iter().map(|s| s.bytes_read).sum::<u64>(),
+        metrics.bytes_read
+    );
+    assert!(
+        metrics
+            .per_stage
+            .iter()
+            .map(|s| s.duration)
+            .sum::<std::time::Duration>()
+            <= metrics.duration
+    );
+}
+
+fn walk(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            out.extend(walk(&path));
+        } else {
+            out.push(path);
+        }
+    }
+    out
+}
+
+#[test]
+fn failed_parse_returns_the_error() {
+    let missing = std::env::temp_dir().join(format!("bqt-metrics-missing-{}", std::process::id()));
+    assert!(
+        parse_default_quests_dir_with_metrics(
+            &FsDataSource,
+            &missing.to_string_lossy(),
+            &ParseOptions::default()
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn progress_reports_every_file_read() {
+    let dir = std::env::temp_dir().join(format!("bqt-progress-{}", std::process::id()));
+    write_default_quests_dir(&sample_db(), &dir).unwrap();
+    let root = dir.to_string_lossy();
+    let options = ParseOptions {
+        deny_unknown_fields: true,
+        ..Default::default()
+    };
+    let mut reports = Vec::new();
+    let outcome = parse_default_quests_dir_with_progress(&FsDataSource, &root, &options, |p| {
+        reports.push((p.files_done, p.files_total, p.current_path.to_string()));
+    })
+    .unwrap();
+    let plain = parse_default_quests_dir_with_options(&FsDataSource, &root, &options).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(outcome, plain);
+    // Three quest files, one QuestLine.json and two entry files; the
+    // unknown-field check is not counted.
+    let done: Vec<usize> = reports.iter().map(|r| r.0).collect();
+    assert_eq!(done, [1, 2, 3, 4, 5, 6]);
+    assert!(reports.iter().all(|r| r.1 == 6));
+    assert!(reports[..3].iter().all(|r| r.2.contains("/Quests/")));
+    assert!(reports[3].2.ends_with("QuestLine.json"));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use better_questing_tools::db::{
+    FsDataSource, parse_default_quests_dir, parse_questline_only, write_default_quests_dir,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::{db, q};
+
+fn quest(low: i32, prereqs: &[i32]) -> Quest {
+    common::quest(low)
+        .name(&format!("Quest {low}"))
+        .requires(prereqs)
+        .build()
+}
+
+fn line(id: i32, entries: &[i32]) -> QuestLine {
+    let line = common::line(id).name(&format!("Line {id}"));
+    entries
+        .iter()
+        .fold(line, |line, low| line.entry_at(*low, low * 30, 0))
+        .build()
+}
+
+#[test]
+fn one_questline_loads_with_its_quests_only() {
+    let db = db(
+        [quest(1, &[]), quest(2, &[1]), quest(3, &[2]), quest(4, &[])],
+        [line(100, &[1, 2]), line(101, &[3, 2])],
+    );
+    let root = std::env::temp_dir().join(format!("bqt-partial-load-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    write_default_quests_dir(&db, &root).unwrap();
+    // a quest file not named after its id is found through the headers
+    std::fs::rename(
+        root.join("Quests/3.json"),
+        root.join("Quests/smelting.json"),
+    )
+    .unwrap();
+    let full = parse_default_quests_dir(&root).unwrap();
+    let root_str = root.to_string_lossy().into_owned();
+
+    let partial = parse_questline_only(&FsDataSource, &root_str, q(101)).unwrap();
+    let missing = parse_questline_only(&FsDataSource, &root_str, q(7));
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(partial.questline_order, vec![q(101)]);
+    assert_eq!(partial.questlines.len(), 1);
+    assert_eq!(partial.questlines[&q(101)], full.questlines[&q(101)]);
+    let mut ids: Vec<QuestId> = partial.quests.keys().copied().collect();
+    ids.sort();
+    assert_eq!(ids, vec![q(2), q(3)]);
+    assert_eq!(partial.quests[&q(3)], full.quests[&q(3)]);
+    // prerequisites outside the chapter are kept but not loaded
+    assert_eq!(partial.quests[&q(2)].prerequisites, vec![q(1)]);
+
+    assert!(matches!(missing, Err(ParseError::InvalidFormat(_))));
+}
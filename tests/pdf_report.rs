@@ -0,0 +1,110 @@
+#![cfg(feature = "pdf")]
+
+mod common;
+
+use better_questing_tools::db::write_default_quests_dir;
+use better_questing_tools::export::pdf::{PdfReportOptions, to_pdf, write_pdf_reports};
+use better_questing_tools::model::*;
+use common::q;
+use std::collections::HashMap;
+
+fn quest(low: i32, name: &str, required: &[i32]) -> Quest {
+    common::quest(low).name(name).requires(required).build()
+}
+
+fn chain_db(len: i32) -> QuestDatabase {
+    let line = QuestLine {
+        id: q(1000),
+        properties: Some(QuestLineProperties {
+            name: "§6Stone (Age)".to_string(),
+            ..Default::default()
+        }),
+        entries: (1..=len)
+            .map(|low| QuestLineEntry {
+                index: None,
+                quest_id: q(low),
+                x: Some(0),
+                y: Some(0),
+                size_x: None,
+                size_y: None,
+                extra: HashMap::new(),
+            })
+            .collect(),
+        extra: HashMap::new(),
+    };
+    QuestDatabase {
+        settings: None,
+        quests: (1..=len)
+            .map(|low| {
+                let prereqs: &[i32] = if low == 1 { &[] } else { &[low - 1] };
+                quest(low, &format!("Step {low}"), prereqs)
+            })
+            .map(|q| (q.id, q))
+            .collect(),
+        questline_order: vec![line.id],
+        questlines: [(line.id, line)].into_iter().collect(),
+    }
+}
+
+fn contains(haystack: &[u8], needle: &str) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|w| w == needle.as_bytes())
+}
+
+fn page_count(pdf: &[u8]) -> usize {
+    let text = String::from_utf8_lossy(pdf);
+    text.matches("/Type /Page\n").count() + text.matches("/Type /Page ").count()
+}
+
+#[test]
+fn report_lists_stats_questlines_and_ranking() {
+    let pdf = to_pdf(&chain_db(3), &PdfReportOptions::default());
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(contains(&pdf, "%%EOF"));
+    assert!(contains(&pdf, "(Quest pack report)"));
+    assert!(contains(&pdf, "(Statistics)"));
+    // Formatting codes are removed.
+    assert!(contains(&pdf, "(Stone (Age))"));
+    assert!(contains(
+        &pdf,
+        "(3 quests, 0 tasks, 0 rewards, depth 0 to 2)"
+    ));
+    assert!(contains(&pdf, "(Most important: Step 1, Step 2, Step 3)"));
+    assert!(contains(&pdf, "(Most important quests)"));
+    assert_eq!(page_count(&pdf), 1);
+}
+
+#[test]
+fn long_reports_span_pages() {
+    let options = PdfReportOptions {
+        title: Some("Review".to_string()),
+        top: 200,
+    };
+    let pdf = to_pdf(&chain_db(200), &options);
+    assert!(contains(&pdf, "(Review)"));
+    assert!(contains(&pdf, "(Step 200)"));
+    let pages = page_count(&pdf);
+    assert!(pages > 1);
+    assert!(contains(&pdf, &format!("({pages} / {pages})")));
+}
+
+#[test]
+fn batch_writes_one_report_per_pack() {
+    let base = std::env::temp_dir().join(format!("bqt-pdf-{}", std::process::id()));
+    let pack = base.join("gregtech");
+    write_default_quests_dir(&chain_db(2), &pack).unwrap();
+    let out = base.join("reports");
+    let results = write_pdf_reports(
+        &[pack.clone(), base.join("missing")],
+        &out,
+        &PdfReportOptions::default(),
+    );
+    let written = results[0].as_ref().unwrap().clone();
+    let bytes = std::fs::read(&written).unwrap();
+    std::fs::remove_dir_all(&base).unwrap();
+
+    assert_eq!(written, out.join("gregtech.pdf"));
+    assert!(contains(&bytes, "(gregtech)"));
+    assert!(results[1].is_err());
+}
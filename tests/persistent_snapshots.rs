@@ -0,0 +1,43 @@
+#![cfg(feature = "im")]
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::persistent::PersistentDatabase;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+
+fn quest(low: i32) -> Quest {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "properties:10": { "betterquesting:10": { "name:8": format!("Quest {low}") } },
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+fn pack() -> QuestDatabase {
+    QuestDatabase {
+        quests: (1..=3).map(quest).map(|q| (q.id, q)).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn restore_drops_edits_made_after_snapshot() {
+    let mut db = PersistentDatabase::from(pack());
+    let before = db.snapshot();
+
+    let id = QuestId::from_parts(0, 2);
+    db.quest_mut(id).unwrap().properties.as_mut().unwrap().name = "Renamed".into();
+    db.quests.remove(&QuestId::from_parts(0, 3));
+    assert_eq!(db.quest(id).unwrap().name(), Some("Renamed"));
+    assert_eq!(before.database().quest(id).unwrap().name(), Some("Quest 2"));
+
+    db.restore(&before);
+    assert_eq!(db.to_database(), pack());
+}
+
+#[test]
+fn round_trips_through_quest_database() {
+    let db = PersistentDatabase::from_database(pack());
+    assert_eq!(QuestDatabase::from(&db), pack());
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::progress::{PlayerProgress, parse_progress_from_reader};
+use common::q;
+use serde_json::json;
+
+const ALICE: &str = "1a2b3c4d-0000-0000-0000-000000000001";
+const BOB: &str = "1a2b3c4d-0000-0000-0000-000000000002";
+
+fn progress_json() -> serde_json::Value {
+    json!({ "questProgress:9": {
+        "0:10": {
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "completed:9": {
+                "0:10": { "uuid:8": ALICE, "timestamp:4": 1000, "claimed:1": 1 },
+                "1:10": { "uuid:8": BOB, "timestamp:4": 2000, "claimed:1": 0 },
+            },
+            "tasks:9": { "0:10": {
+                "index:3": 0, "taskID:8": "bq_standard:retrieval",
+                "completeUsers:9": { "0:8": ALICE, "1:8": BOB },
+            }},
+        },
+        "1:10": {
+            "questIDHigh:4": 0, "questIDLow:4": 2,
+            "completed:9": {},
+            "tasks:9": {
+                "0:10": {
+                    "index:3": 0,
+                    "completeUsers:9": { "0:10": { "uuid:8": ALICE } },
+                },
+                "1:10": {
+                    "index:3": 1,
+                    "userProgress:9": {
+                        "0:10": { "uuid:8": BOB, "data:11": [3, 0] },
+                        "1:10": { "uuid:8": ALICE, "value:3": 7 },
+                    },
+                },
+            },
+        },
+        "2:10": { "questIDHigh:4": 0, "questIDLow:4": 99, "completed:9": {} },
+    }})
+}
+
+#[test]
+fn task_progress_is_read_per_player() {
+    let progress = parse_progress_from_reader(progress_json().to_string().as_bytes()).unwrap();
+    let tasks = &progress.quests[&q(2)].tasks;
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].completed_by, [ALICE]);
+    assert_eq!(tasks[1].index, Some(1));
+    assert_eq!(tasks[1].players[0].player, BOB);
+    assert_eq!(tasks[1].players[0].data["data"], json!([3, 0]));
+    assert_eq!(
+        progress.quests[&q(1)].tasks[0].task_id.as_deref(),
+        Some("bq_standard:retrieval")
+    );
+
+    let alice = progress.player(ALICE);
+    assert_eq!(alice.completed, vec![q(1)]);
+    assert_eq!(alice.claimed, vec![q(1)]);
+    assert_eq!(alice.completed_tasks, vec![(q(1), 0), (q(2), 0)]);
+    assert_eq!(alice.task_progress.len(), 1);
+    assert_eq!(alice.task_progress[0].2["value"], json!(7));
+
+    let bob = progress.player(BOB);
+    assert_eq!(bob.completed, vec![q(1)]);
+    assert!(bob.claimed.is_empty());
+    assert_eq!(bob.completed_tasks, vec![(q(1), 0)]);
+    assert_eq!(bob.task_progress[0].0, q(2));
+}
+
+#[test]
+fn progress_links_back_to_database_quests() {
+    let progress = parse_progress_from_reader(progress_json().to_string().as_bytes()).unwrap();
+    let quest = |low| Quest {
+        id: q(low),
+        properties: None,
+        tasks: vec![],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    };
+    let db = QuestDatabase {
+        settings: None,
+        quests: [quest(1), quest(2)]
+            .into_iter()
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: Default::default(),
+        questline_order: vec![],
+    };
+    assert_eq!(progress.unknown_quests(&db), vec![q(99)]);
+}
+
+#[test]
+fn timestamps_round_trip_as_epoch_milliseconds() {
+    let progress = parse_progress_from_reader(progress_json().to_string().as_bytes()).unwrap();
+    let alice = progress.quests[&q(1)].completion_of(ALICE).unwrap();
+    assert_eq!(alice.timestamp.millis(), 1000);
+
+    let json = serde_json::to_value(&progress).unwrap();
+    let stored = &json["quests"]["1"]["completed"][0];
+    assert_eq!(stored["timestamp"], json!(1000));
+    let back: PlayerProgress = serde_json::from_value(json).unwrap();
+    assert_eq!(back, progress);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn timestamps_are_chrono_dates() {
+    use better_questing_tools::progress::Timestamp;
+    let ts = Timestamp::from_millis(86_400_000).unwrap();
+    let date = ts.to_datetime();
+    assert_eq!(date.to_rfc3339(), "1970-01-02T00:00:00+00:00");
+    assert_eq!(Timestamp::from(date), ts);
+    assert_eq!(Timestamp::from_millis(i64::MAX), None);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use better_questing_tools::analysis::find_cycles;
+use better_questing_tools::model::*;
+use common::{db, q};
+
+fn quest(low: i32, name: &str, prereqs: &[i32]) -> Quest {
+    common::quest(low).name(name).requires(prereqs).build()
+}
+
+#[test]
+fn every_cycle_is_reported_with_names_and_a_loop() {
+    let quests = vec![
+        quest(1, "Start", &[]),
+        // 2 -> 3 -> 4 -> 2, plus the shortcut 2 -> 4
+        quest(2, "§6Smelting", &[1, 4]),
+        quest(3, "Alloys", &[2]),
+        quest(4, "Machines", &[2, 3]),
+        quest(5, "Free", &[4]),
+        // a second, independent loop and a self-reference
+        quest(6, "Ping", &[7]),
+        quest(7, "Pong", &[6]),
+        quest(8, "Mirror", &[8]),
+    ];
+    let db = db(quests, []);
+
+    let cycles = find_cycles(&db);
+    assert_eq!(cycles.len(), 3);
+    assert_eq!(cycles[0].members, vec![q(2), q(3), q(4)]);
+    assert_eq!(cycles[0].names, vec!["Smelting", "Alloys", "Machines"]);
+    assert_eq!(cycles[0].path, vec![q(2), q(4)]);
+    assert_eq!(cycles[1].path, vec![q(6), q(7)]);
+    assert_eq!(cycles[2].members, vec![q(8)]);
+    assert_eq!(cycles[2].path, vec![q(8)]);
+}
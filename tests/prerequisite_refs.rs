@@ -0,0 +1,83 @@
+mod common;
+
+use better_questing_tools::db::{
+    MemoryDataSource, ParseOptions, ParseOutcome, ParseWarning, PrerequisiteCheck,
+    parse_default_quests_dir_with_options,
+};
+use better_questing_tools::error::{ParseError, Result};
+use better_questing_tools::validate::ValidationIssue;
+use common::q;
+
+fn quest_json(low: i32, prereqs: &[i32]) -> String {
+    let refs: Vec<serde_json::Value> = prereqs
+        .iter()
+        .map(|p| serde_json::json!({ "questIDHigh:4": 0, "questIDLow:4": p }))
+        .collect();
+    serde_json::json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "properties:10": { "betterquesting:10": { "name:8": format!("Quest {}", low) } },
+        "preRequisites:9": refs,
+    })
+    .to_string()
+}
+
+fn pack() -> MemoryDataSource {
+    [
+        ("pack/Quests/1.json", quest_json(1, &[])),
+        ("pack/Quests/2.json", quest_json(2, &[1, 7])),
+        ("pack/Quests/3.json", quest_json(3, &[5, 2])),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn parse(check: PrerequisiteCheck) -> Result<ParseOutcome> {
+    parse_default_quests_dir_with_options(
+        &pack(),
+        "pack",
+        &ParseOptions {
+            missing_prerequisites: check,
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn missing_prerequisites_are_ignored_by_default() {
+    let outcome = parse(PrerequisiteCheck::default()).unwrap();
+    assert!(outcome.warnings.is_empty());
+    assert_eq!(outcome.db.quests[&q(2)].prerequisites, vec![q(1), q(7)]);
+}
+
+#[test]
+fn warn_mode_reports_each_missing_prerequisite_and_keeps_it() {
+    let outcome = parse(PrerequisiteCheck::Warn).unwrap();
+    assert_eq!(
+        outcome.warnings,
+        vec![
+            ParseWarning::MissingPrerequisite {
+                quest: q(2),
+                missing: q(7)
+            },
+            ParseWarning::MissingPrerequisite {
+                quest: q(3),
+                missing: q(5)
+            },
+        ]
+    );
+    assert_eq!(outcome.db.quests[&q(3)].prerequisites, vec![q(5), q(2)]);
+}
+
+#[test]
+fn strict_mode_fails_on_the_first_missing_prerequisite() {
+    match parse(PrerequisiteCheck::Strict) {
+        Err(ParseError::Invalid(ValidationIssue::MissingPrerequisite { quest, missing })) => {
+            assert_eq!((quest, missing), (q(2), q(7)));
+        }
+        other => panic!(
+            "expected MissingPrerequisite, got {:?}",
+            other.map(|o| o.warnings)
+        ),
+    }
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use better_questing_tools::analysis::suggest_prerequisites;
+use better_questing_tools::index::{ItemIndex, ItemKey};
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::{item_reward, q, retrieval, stack};
+use std::collections::HashMap;
+
+fn quest(low: i32, prereqs: &[i32], needs: &[&str], rewards: Vec<Reward>) -> Quest {
+    let tasks = needs.iter().map(|item| retrieval([stack(item, 1)]));
+    common::quest(low)
+        .requires(prereqs)
+        .tasks(tasks)
+        .rewards(rewards)
+        .build()
+}
+
+fn gives(items: &[&str]) -> Vec<Reward> {
+    vec![item_reward(items.iter().map(|i| stack(i, 1)))]
+}
+
+fn offers(items: &[&str]) -> Vec<Reward> {
+    vec![Reward {
+        reward_id: "bq_standard:choice".to_string(),
+        choices: items.iter().map(|i| stack(i, 1)).collect(),
+        ..item_reward([])
+    }]
+}
+
+fn key(id: &str) -> ItemKey {
+    ItemKey {
+        id: id.to_string(),
+        damage: 0,
+    }
+}
+
+#[test]
+fn suggests_quests_introducing_required_items() {
+    let quests = vec![
+        quest(1, &[], &[], gives(&["mod:key", "mod:gear"])),
+        // Needs two items only quest 1 hands out, but does not depend on it.
+        quest(2, &[], &["mod:key", "mod:gear"], vec![]),
+        // Already depends on quest 1: nothing to suggest.
+        quest(3, &[1], &["mod:key"], vec![]),
+        // Two quests reward the gem; one only as a choice.
+        quest(4, &[], &[], gives(&["mod:gem"])),
+        quest(5, &[], &[], offers(&["mod:gem", "mod:dust"])),
+        quest(6, &[], &["mod:gem", "minecraft:dirt"], vec![]),
+        // Rewards what its own prerequisite needs: suggesting it would loop.
+        quest(7, &[], &["mod:orb"], vec![]),
+        quest(8, &[7], &[], gives(&["mod:orb"])),
+    ];
+    let db = QuestDatabase {
+        settings: None,
+        quests: quests.into_iter().map(|x| (x.id, x)).collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+    let suggestions = suggest_prerequisites(&db, &ItemIndex::build(&db));
+
+    let summary: Vec<(QuestId, QuestId, f64)> = suggestions
+        .iter()
+        .map(|s| (s.quest, s.prerequisite, s.confidence))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(q(2), q(1), 1.0), (q(6), q(4), 0.5), (q(6), q(5), 0.25)]
+    );
+    assert_eq!(suggestions[0].items, vec![key("mod:gear"), key("mod:key")]);
+    assert_eq!(suggestions[2].items, vec![key("mod:gem")]);
+}
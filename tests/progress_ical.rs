@@ -0,0 +1,82 @@
+use better_questing_tools::export::ical::{to_ical, upcoming};
+use better_questing_tools::model::*;
+use better_questing_tools::progress::{Timestamp, parse_progress_from_reader};
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+const PLAYER: &str = "0b2c5bd1-7b4e-4c1a-9a57-6f0f0a0b0c0d";
+
+fn quest(low: i32, name: &str, repeat_time: i32, relative: bool) -> Quest {
+    let props: QuestProperties = serde_json::from_value(json!({
+        "name": name,
+        "repeat_time": repeat_time,
+        "repeat_relative": relative,
+    }))
+    .expect("properties");
+    Quest {
+        id: QuestId::from_parts(0, low),
+        properties: Some(props),
+        tasks: vec![],
+        rewards: vec![],
+        prerequisites: vec![],
+        required_prerequisites: vec![],
+        optional_prerequisites: vec![],
+    }
+}
+
+fn progress_json(entries: &[(i32, i64)]) -> String {
+    let mut list = serde_json::Map::new();
+    for (i, (low, ts)) in entries.iter().enumerate() {
+        list.insert(
+            format!("{}:10", i),
+            json!({
+                "questIDHigh:4": 0,
+                "questIDLow:4": low,
+                "completed:9": { "0:10": { "uuid:8": PLAYER, "timestamp:4": ts, "claimed:1": 1 } },
+                "tasks:9": {},
+            }),
+        );
+    }
+    json!({ "questProgress:9": list }).to_string()
+}
+
+#[test]
+fn parses_progress_and_lists_upcoming_cooldowns() {
+    let day_ticks = 24 * 60 * 60 * 20;
+    let quests = vec![
+        quest(1, "Daily, relative", day_ticks, true),
+        quest(2, "Daily grid", day_ticks, false),
+        quest(3, "One-off", -1, true),
+    ];
+    let db = QuestDatabase {
+        settings: None,
+        quests: quests.into_iter().map(|q| (q.id, q)).collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+    // completed at 1970-01-02T06:00:00Z
+    let done = (24 + 6) * 3_600_000;
+    let progress =
+        parse_progress_from_reader(progress_json(&[(1, done), (2, done), (3, done)]).as_bytes())
+            .expect("progress");
+    assert_eq!(progress.players(), vec![PLAYER]);
+    let q1 = &progress.quests[&QuestId::from_parts(0, 1)];
+    assert!(q1.completion_of(PLAYER).expect("completion").claimed);
+
+    let now = Timestamp::from_millis(done).unwrap();
+    let events = upcoming(&db, &progress, now);
+    let at: Vec<(i64, i64)> = events
+        .iter()
+        .map(|a| (a.quest.low_i64(), a.available_at.millis()))
+        .collect();
+    // the grid-aligned quest resets at midnight, the relative one a day after completion
+    assert_eq!(at, vec![(2, 2 * 86_400_000), (1, done + 86_400_000)]);
+
+    let ical = to_ical(&db, &progress, now);
+    assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ical.ends_with("END:VCALENDAR\r\n"));
+    assert!(ical.contains("DTSTART:19700103T000000Z\r\n"));
+    assert!(ical.contains("SUMMARY:Daily\\, relative is available again\r\n"));
+    assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::overlay::{ProgressOverlay, QuestState, overlays};
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::progress::parse_progress_from_reader;
+use common::{db, q};
+use serde_json::json;
+
+const ALICE: &str = "1a2b3c4d-0000-0000-0000-000000000001";
+const BOB: &str = "1a2b3c4d-0000-0000-0000-000000000002";
+
+fn quest(low: i32, logic: Option<&str>, requires: &[i32]) -> Quest {
+    common::quest(low)
+        .properties(json!({
+            "name": format!("Quest {}", low),
+            "quest_logic": logic,
+        }))
+        .requires(requires)
+        .build()
+}
+
+fn sample_db() -> QuestDatabase {
+    let quests = [
+        quest(1, None, &[]),
+        quest(2, None, &[]),
+        quest(3, Some("AND"), &[1, 2]),
+        quest(4, Some("OR"), &[1, 2]),
+        quest(5, Some("XOR"), &[1, 2]),
+        quest(6, Some("NOR"), &[1]),
+        quest(7, Some("NAND"), &[1, 2]),
+        quest(8, None, &[1, 404]),
+    ];
+    db(quests, [])
+}
+
+fn completed(low: i32, players: &[(&str, bool)]) -> serde_json::Value {
+    let completed: serde_json::Map<String, serde_json::Value> = players
+        .iter()
+        .enumerate()
+        .map(|(i, (p, claimed))| {
+            (
+                format!("{}:10", i),
+                json!({ "uuid:8": p, "timestamp:4": 1000, "claimed:1": *claimed as i32 }),
+            )
+        })
+        .collect();
+    json!({ "questIDHigh:4": 0, "questIDLow:4": low, "completed:9": completed })
+}
+
+#[test]
+fn states_follow_completion_and_quest_logic() {
+    let v = json!({ "questProgress:9": {
+        "0:10": completed(1, &[(ALICE, true), (BOB, false)]),
+        "1:10": completed(2, &[(BOB, false)]),
+    }});
+    let progress = parse_progress_from_reader(v.to_string().as_bytes()).unwrap();
+    let db = sample_db();
+
+    let alice = ProgressOverlay::build(&db, &progress, ALICE);
+    assert_eq!(alice.state(q(1)), Some(QuestState::Claimed));
+    assert_eq!(alice.state(q(2)), Some(QuestState::Unlocked));
+    assert_eq!(alice.state(q(3)), Some(QuestState::Locked));
+    assert_eq!(alice.state(q(4)), Some(QuestState::Unlocked));
+    assert_eq!(alice.state(q(5)), Some(QuestState::Unlocked));
+    assert_eq!(alice.state(q(6)), Some(QuestState::Locked));
+    assert_eq!(alice.state(q(7)), Some(QuestState::Unlocked));
+    assert_eq!(alice.state(q(8)), Some(QuestState::Locked));
+    assert_eq!(alice.state(q(404)), None);
+
+    let bob = ProgressOverlay::build(&db, &progress, BOB);
+    assert_eq!(bob.quests_in(QuestState::Completed), [q(1), q(2)]);
+    assert_eq!(bob.state(q(3)), Some(QuestState::Unlocked));
+    assert_eq!(bob.state(q(5)), Some(QuestState::Locked));
+    assert_eq!(bob.state(q(7)), Some(QuestState::Locked));
+
+    let all = overlays(&db, &progress);
+    assert_eq!(
+        all.keys().map(String::as_str).collect::<Vec<_>>(),
+        [ALICE, BOB]
+    );
+    assert_eq!(all[BOB], bob);
+}
+
+#[test]
+fn without_progress_only_root_quests_are_unlocked() {
+    let progress = parse_progress_from_reader(json!({}).to_string().as_bytes()).unwrap();
+    let overlay = ProgressOverlay::build(&sample_db(), &progress, ALICE);
+    let counts = overlay.counts();
+    assert_eq!(counts[&QuestState::Unlocked], 4);
+    assert_eq!(counts[&QuestState::Locked], 4);
+    assert!(!counts.contains_key(&QuestState::Completed));
+}
+
+/// A quest as the parser builds it from a BetterQuesting file.
+fn parsed(low: i32, logic: &str, requires: &[i32]) -> Quest {
+    let prereqs: serde_json::Map<String, serde_json::Value> = requires
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            (
+                format!("{}:10", i),
+                json!({ "questIDHigh:4": 0, "questIDLow:4": p }),
+            )
+        })
+        .collect();
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "preRequisites:9": prereqs,
+        "properties:10": { "betterquesting:10": {
+            "name:8": format!("Quest {}", low),
+            "questLogic:8": logic,
+        } },
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).unwrap()
+}
+
+#[test]
+fn parsed_or_and_xor_quests_apply_their_logic() {
+    let quests = [
+        quest(1, None, &[]),
+        quest(2, None, &[]),
+        parsed(4, "OR", &[1, 2]),
+        parsed(5, "XOR", &[1, 2]),
+    ];
+    // the parser files OR/XOR prerequisites as optional
+    assert!(quests[2].required_prerequisites.is_empty());
+    let db = QuestDatabase {
+        settings: None,
+        quests: quests.into_iter().map(|q| (q.id, q)).collect(),
+        questlines: Default::default(),
+        questline_order: Vec::new(),
+    };
+
+    let none = parse_progress_from_reader(json!({}).to_string().as_bytes()).unwrap();
+    let overlay = ProgressOverlay::build(&db, &none, ALICE);
+    assert_eq!(overlay.state(q(4)), Some(QuestState::Locked));
+    assert_eq!(overlay.state(q(5)), Some(QuestState::Locked));
+
+    let v = json!({ "questProgress:9": {
+        "0:10": completed(1, &[(ALICE, true), (BOB, false)]),
+        "1:10": completed(2, &[(BOB, false)]),
+    }});
+    let progress = parse_progress_from_reader(v.to_string().as_bytes()).unwrap();
+    let alice = ProgressOverlay::build(&db, &progress, ALICE);
+    assert_eq!(alice.state(q(4)), Some(QuestState::Unlocked));
+    assert_eq!(alice.state(q(5)), Some(QuestState::Unlocked));
+    let bob = ProgressOverlay::build(&db, &progress, BOB);
+    assert_eq!(bob.state(q(4)), Some(QuestState::Unlocked));
+    assert_eq!(bob.state(q(5)), Some(QuestState::Locked));
+}
@@ -0,0 +1,113 @@
+use better_questing_tools::edit::DatabaseChange;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::progress::{PlayerProgress, Timestamp, parse_progress_from_reader};
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::watcher::{ProgressEvent, ProgressWatcher, diff_progress};
+use serde_json::json;
+
+/// Progress file with one completion per `(quest low id, player, timestamp, claimed)`.
+fn progress_json(entries: &[(i32, &str, i64, i32)]) -> String {
+    let mut list = serde_json::Map::new();
+    for (i, (low, player, ts, claimed)) in entries.iter().enumerate() {
+        list.insert(
+            format!("{}:10", i),
+            json!({
+                "questIDHigh:4": 0,
+                "questIDLow:4": low,
+                "completed:9": { "0:10": { "uuid:8": player, "timestamp:4": ts, "claimed:1": claimed } },
+            }),
+        );
+    }
+    json!({ "questProgress:9": list }).to_string()
+}
+
+fn progress(entries: &[(i32, &str, i64, i32)]) -> PlayerProgress {
+    parse_progress_from_reader(progress_json(entries).as_bytes()).expect("progress")
+}
+
+#[test]
+fn reports_completions_claims_and_resets() {
+    let old = progress(&[(1, "alice", 100, 0), (2, "bob", 100, 1)]);
+    let new = progress(&[(1, "alice", 100, 1), (3, "bob", 200, 0)]);
+    let q = |low| QuestId::from_parts(0, low);
+    assert_eq!(
+        diff_progress(&old, &new),
+        vec![
+            ProgressEvent::RewardClaimed {
+                player: "alice".into(),
+                quest: q(1)
+            },
+            ProgressEvent::CompletionReset {
+                player: "bob".into(),
+                quest: q(2)
+            },
+            ProgressEvent::QuestCompleted {
+                player: "bob".into(),
+                quest: q(3),
+                timestamp: Timestamp::from_millis(200).unwrap()
+            },
+        ]
+    );
+    assert!(diff_progress(&new, &new).is_empty());
+}
+
+#[test]
+fn events_serialize_with_a_tag() {
+    let event = ProgressEvent::QuestCompleted {
+        player: "alice".into(),
+        quest: QuestId::from_parts(0, 7),
+        timestamp: Timestamp::from_millis(5).unwrap(),
+    };
+    assert_eq!(
+        serde_json::to_value(&event).expect("json"),
+        json!({ "event": "quest_completed", "player": "alice", "quest": 7, "timestamp": 5 })
+    );
+    let back: ProgressEvent =
+        serde_json::from_value(serde_json::to_value(&event).expect("json")).expect("round trip");
+    assert_eq!(back, event);
+}
+
+#[test]
+fn watcher_skips_quests_removed_from_the_database() {
+    let path = std::env::temp_dir().join(format!("bqt-watch-{}.json", std::process::id()));
+    std::fs::write(&path, progress_json(&[])).unwrap();
+    let mut watcher = ProgressWatcher::new(&path).unwrap();
+
+    let quest = parse_quest_from_reader(
+        json!({
+            "questIDHigh:4": 0,
+            "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": { "name:8": "Gone" } },
+        })
+        .to_string()
+        .as_bytes(),
+    )
+    .unwrap();
+    let removed = DatabaseChange::QuestRemoved {
+        quest: Box::new(quest),
+    };
+    watcher.apply_changes(std::slice::from_ref(&removed));
+    std::fs::write(
+        &path,
+        progress_json(&[(1, "alice", 100, 0), (2, "alice", 100, 0)]),
+    )
+    .unwrap();
+    let events = watcher.poll().unwrap();
+    assert_eq!(
+        events.iter().map(ProgressEvent::quest).collect::<Vec<_>>(),
+        [QuestId::from_parts(0, 2)]
+    );
+
+    watcher.apply_changes(&[removed.inverse()]);
+    std::fs::write(
+        &path,
+        progress_json(&[(1, "alice", 200, 0), (2, "alice", 100, 0)]),
+    )
+    .unwrap();
+    let events = watcher.poll().unwrap();
+    assert_eq!(
+        events.iter().map(ProgressEvent::quest).collect::<Vec<_>>(),
+        [QuestId::from_parts(0, 1)]
+    );
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use better_questing_tools::analysis::namespace::NamespaceRule;
+use better_questing_tools::error::ParseError;
+use better_questing_tools::lint::{LintFinding, Severity};
+use better_questing_tools::project::{PROJECT_VERSION, Project, project_path};
+use better_questing_tools::quest_id::QuestId;
+use common::q;
+use serde_json::json;
+use std::path::Path;
+
+fn finding(rule: &str, subject: QuestId) -> LintFinding {
+    LintFinding {
+        rule: rule.to_string(),
+        severity: Severity::Warning,
+        subject,
+        message: String::new(),
+    }
+}
+
+#[test]
+fn project_path_is_a_sibling_of_the_pack() {
+    assert_eq!(
+        project_path(Path::new("config/betterquesting/DefaultQuests")),
+        Path::new("config/betterquesting/DefaultQuests.bqtproj")
+    );
+}
+
+#[test]
+fn round_trips_through_a_file_and_keeps_unknown_sections() {
+    let root = std::env::temp_dir().join(format!("bqt-project-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    let pack = root.join("DefaultQuests");
+
+    assert_eq!(Project::load_for_pack(&pack).unwrap(), Project::default());
+
+    let mut project = Project::default();
+    project.lint.disabled.push("disconnected-quest".to_string());
+    project.suppress("empty-tasks", Some(q(2)));
+    project.tags.add(q(1), "tech");
+    project.namespaces.rules.push(NamespaceRule {
+        name: "core".to_string(),
+        high_min: 0,
+        high_max: 9,
+        questlines: Vec::new(),
+    });
+    project.set_layout_lock(q(100), q(1), true);
+    project
+        .extra
+        .insert("futureTool".to_string(), json!({ "zoom": 2 }));
+    project.save_for_pack(&pack).unwrap();
+
+    let loaded = Project::load_for_pack(&pack).unwrap();
+    std::fs::remove_dir_all(&root).ok();
+    assert_eq!(loaded, project);
+    assert!(loaded.is_layout_locked(q(100), q(1)));
+    assert!(!loaded.is_layout_locked(q(100), q(2)));
+    assert_eq!(loaded.extra["futureTool"], json!({ "zoom": 2 }));
+}
+
+#[test]
+fn rejects_newer_and_missing_versions() {
+    let newer = json!({ "version": PROJECT_VERSION + 1 }).to_string();
+    assert!(matches!(
+        Project::from_reader(newer.as_bytes()),
+        Err(ParseError::InvalidFormat(_))
+    ));
+    assert!(Project::from_reader(&b"{}"[..]).is_err());
+    let minimal = Project::from_reader(&br#"{"version": 1}"#[..]).unwrap();
+    assert_eq!(minimal, Project::default());
+}
+
+#[test]
+fn suppressions_filter_findings() {
+    let mut project = Project::default();
+    project.suppress("empty-tasks", Some(q(2)));
+    project.suppress("missing-description", None);
+    let kept = project.unsuppressed(vec![
+        finding("empty-tasks", q(1)),
+        finding("empty-tasks", q(2)),
+        finding("missing-description", q(3)),
+    ]);
+    assert_eq!(kept, vec![finding("empty-tasks", q(1))]);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use better_questing_tools::error::ParseError;
+use better_questing_tools::index::QuestIndex;
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use common::q;
+use serde_json::json;
+
+fn sample_db() -> QuestDatabase {
+    let quests = [
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 1,
+            "properties:10": { "betterquesting:10": { "name:8": "§aGetting Wood" } },
+            "tasks:9": { "0:10": { "taskID:8": "bq_standard:retrieval", "index:3": 0,
+                "requiredItems:9": {
+                    "0:10": { "id:8": "minecraft:log", "Count:3": 16, "Damage:2": 0 },
+                    "1:10": { "id:8": "minecraft:log", "Count:3": 16, "Damage:2": 1 },
+                } } },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:item", "index:3": 0,
+                "rewards:9": { "0:10": { "id:8": "minecraft:apple", "Count:3": 2 } } } },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 2,
+            "properties:10": { "betterquesting:10": { "name:8": "Wood Tools" } },
+            "tasks:9": {
+                "0:10": { "taskID:8": "bq_standard:crafting", "index:3": 0,
+                    "requiredItems:9": { "0:10": { "id:8": "minecraft:wooden_pickaxe" } } },
+                "1:10": { "taskID:8": "bq_standard:retrieval", "index:3": 1,
+                    "requiredItems:9": { "0:10": { "id:8": "minecraft:log" } } },
+            },
+            "rewards:9": { "0:10": { "rewardID:8": "bq_standard:xp", "index:3": 0 } },
+        }),
+        json!({
+            "questIDHigh:4": 0, "questIDLow:4": 3,
+            "properties:10": { "betterquesting:10": { "name:8": "  getting wood " } },
+        }),
+    ];
+    QuestDatabase {
+        settings: None,
+        quests: quests
+            .iter()
+            .map(|v| parse_quest_from_reader(v.to_string().as_bytes()).unwrap())
+            .map(|q| (q.id, q))
+            .collect(),
+        questlines: Default::default(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn lookups_by_name_task_reward_and_item() {
+    let index = QuestIndex::build(&sample_db());
+
+    assert_eq!(index.by_name("Getting Wood"), [q(1), q(3)]);
+    assert!(index.by_name("Getting").is_empty());
+    assert_eq!(index.name_contains("WOOD"), vec![q(1), q(2), q(3)]);
+
+    assert_eq!(index.by_task_type("bq_standard:retrieval"), [q(1), q(2)]);
+    assert_eq!(index.by_task_type("bq_standard:crafting"), [q(2)]);
+    assert!(index.by_task_type("bq_standard:hunt").is_empty());
+    assert_eq!(
+        index.task_types(),
+        ["bq_standard:crafting", "bq_standard:retrieval"]
+    );
+
+    assert_eq!(index.by_reward_type("bq_standard:item"), [q(1)]);
+    assert_eq!(index.reward_types(), ["bq_standard:item", "bq_standard:xp"]);
+
+    // both damage values of the log count once for quest 1
+    assert_eq!(index.requiring_item("minecraft:log"), [q(1), q(2)]);
+    assert_eq!(index.requiring_item("minecraft:wooden_pickaxe"), [q(2)]);
+    assert!(index.requiring_item("minecraft:apple").is_empty());
+}
+
+#[test]
+fn names_resolve_exactly_then_loosely_then_fuzzily() {
+    let db = sample_db();
+    assert_eq!(db.resolve_name("Getting Wood").unwrap(), Some(q(1)));
+    assert_eq!(db.resolve_name("getting wood").unwrap(), Some(q(3)));
+    assert_eq!(db.resolve_name("wood   TOOLS").unwrap(), Some(q(2)));
+    assert_eq!(db.resolve_name("Wod Tools").unwrap(), Some(q(2)));
+    assert_eq!(db.resolve_name("Stone Age").unwrap(), None);
+
+    let err = db.resolve_name("GETTING WOOD").unwrap_err();
+    assert!(matches!(
+        &err,
+        ParseError::AmbiguousName { name, matches }
+            if name == "GETTING WOOD" && matches == &[q(1), q(3)]
+    ));
+    assert!(
+        err.to_string()
+            .contains("ambiguous quest name \"GETTING WOOD\": 2 quests match")
+    );
+    assert!(db.resolve_name("Getting Woods").is_err());
+}
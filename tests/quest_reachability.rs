@@ -0,0 +1,134 @@
+mod common;
+
+use better_questing_tools::graph::QuestGraph;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use common::q;
+use std::collections::HashSet;
+
+fn quest(low: i32, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn db(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+/// Reference answer: walk prerequisites from `b` and look for `a`.
+fn reaches(db: &QuestDatabase, a: QuestId, b: QuestId) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack: Vec<QuestId> = vec![b];
+    while let Some(x) = stack.pop() {
+        let Some(quest) = db.quests.get(&x) else {
+            continue;
+        };
+        for p in quest
+            .required_prerequisites
+            .iter()
+            .chain(&quest.optional_prerequisites)
+        {
+            if db.quests.contains_key(p) && seen.insert(*p) {
+                stack.push(*p);
+            }
+        }
+    }
+    seen.contains(&a)
+}
+
+#[test]
+fn ancestors_follow_chains_and_optional_links() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1], &[]),
+        quest(3, &[2], &[]),
+        quest(4, &[], &[3]),
+        quest(5, &[99], &[]), // missing prerequisite is ignored
+    ]);
+    let graph = QuestGraph::build(&db);
+    assert!(graph.is_ancestor(q(1), q(4)));
+    assert!(graph.is_ancestor(q(3), q(4)));
+    assert!(!graph.is_ancestor(q(4), q(1)));
+    assert!(!graph.is_ancestor(q(1), q(1)));
+    assert!(!graph.is_ancestor(q(1), q(5)));
+    assert!(!graph.is_ancestor(q(99), q(5)));
+    assert_eq!(graph.ancestors(q(4)), vec![q(1), q(2), q(3)]);
+    assert_eq!(graph.ancestor_count(q(5)), 0);
+}
+
+#[test]
+fn cycle_members_are_ancestors_of_each_other() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1, 3], &[]),
+        quest(3, &[2], &[]),
+        quest(4, &[3], &[]),
+    ]);
+    let graph = QuestGraph::build(&db);
+    assert!(graph.is_ancestor(q(2), q(2)));
+    assert!(graph.is_ancestor(q(3), q(2)));
+    assert_eq!(graph.ancestors(q(4)), vec![q(1), q(2), q(3)]);
+}
+
+#[test]
+fn matches_graph_walk_on_a_layered_pack() {
+    // 200 quests, each requiring a few earlier ones, plus a back edge.
+    let mut quests: Vec<Quest> = (0..200)
+        .map(|i| {
+            let pre: Vec<i32> = [i - 1, i / 2, i - 7]
+                .into_iter()
+                .filter(|p| *p >= 0 && *p < i && i % 5 != 0)
+                .collect();
+            quest(i, &pre, &[])
+        })
+        .collect();
+    quests[40].required_prerequisites.push(q(60));
+    let db = db(quests);
+    let graph = QuestGraph::build(&db);
+    for a in (0..200).step_by(7) {
+        for b in (0..200).step_by(3) {
+            assert_eq!(
+                graph.is_ancestor(q(a), q(b)),
+                reaches(&db, q(a), q(b)),
+                "{} -> {}",
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn database_queries_walk_both_directions() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1], &[]),
+        quest(3, &[2], &[]),
+        quest(4, &[], &[3]),
+        quest(5, &[99], &[]),
+    ]);
+    assert_eq!(db.ancestors_of(q(4)), vec![q(1), q(2), q(3)]);
+    assert_eq!(db.descendants_of(q(1)), vec![q(2), q(3), q(4)]);
+    assert_eq!(db.descendants_of(q(4)), vec![]);
+    assert_eq!(db.ancestors_of(q(5)), vec![]);
+    assert_eq!(db.descendants_of(q(99)), vec![]);
+}
+
+#[test]
+fn database_queries_terminate_on_cycles() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1, 3], &[]),
+        quest(3, &[2], &[]),
+        quest(4, &[3], &[]),
+    ]);
+    assert_eq!(db.ancestors_of(q(2)), vec![q(1), q(2), q(3)]);
+    assert_eq!(db.descendants_of(q(3)), vec![q(2), q(3), q(4)]);
+    assert_eq!(db.descendants_of(q(1)), vec![q(2), q(3), q(4)]);
+    let graph = QuestGraph::build(&db);
+    for i in 1..=4 {
+        assert_eq!(db.ancestors_of(q(i)), graph.ancestors(q(i)));
+    }
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::tags::{TAGS_EXTRA_KEY, TagFilter, TagMap};
+use common::{db, line, q, quest};
+
+fn make_db() -> QuestDatabase {
+    db(
+        (1..=3).map(|low| quest(low).name(&format!("Quest {low}")).build()),
+        [line(100).entries(&[1, 2]).build()],
+    )
+}
+
+#[test]
+fn sidecar_tags_filter_views_and_extra_markers() {
+    let sidecar = r#"{ "1": ["magic", "optional"], "2": ["tech"], "3": ["magic"] }"#;
+    let tags = TagMap::from_reader(sidecar.as_bytes()).expect("tags");
+    let mut db = make_db();
+
+    assert_eq!(tags.quests_with("magic"), vec![q(1), q(3)]);
+    assert_eq!(tags.counts(&db)["magic"], 2);
+
+    let filter = TagFilter {
+        all_of: vec!["magic".into()],
+        none_of: vec!["optional".into()],
+        ..Default::default()
+    };
+    let view = tags.filter_db(&db, &filter);
+    assert_eq!(view.quests.len(), 1);
+    assert!(view.quests.contains_key(&q(3)));
+    // quest 3 is not on the only questline, which therefore disappears
+    assert!(view.questlines.is_empty());
+    assert!(view.questline_order.is_empty());
+
+    tags.write_extra(&mut db);
+    let props = db.quests[&q(1)].properties.as_ref().unwrap();
+    assert_eq!(
+        props.extra[TAGS_EXTRA_KEY],
+        serde_json::json!(["magic", "optional"])
+    );
+    assert_eq!(TagMap::from_extra(&db), tags);
+}
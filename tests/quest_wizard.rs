@@ -0,0 +1,119 @@
+use better_questing_tools::db::{parse_default_quests_dir, write_default_quests_dir};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use better_questing_tools::wizard::{GridLayout, generate_retrieval_quests, read_rows};
+use std::collections::HashMap;
+
+const CSV: &str = "item,count,chapter\n\
+minecraft:log,16,Getting Started\n\
+minecraft:wool:14,1,\"Colors, Dyes\"\n\
+minecraft:iron_ingot,32,Getting Started\n\
+\n\
+minecraft:cobblestone,64,Getting Started\n";
+
+#[test]
+fn csv_rows_become_chained_retrieval_quests() {
+    let rows = read_rows(CSV.as_bytes()).unwrap();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[1].item, "minecraft:wool");
+    assert_eq!(rows[1].damage, Some(14));
+    assert_eq!(rows[1].chapter, "Colors, Dyes");
+
+    // an existing pack with one quest on "Getting Started"
+    let mut db = QuestDatabase {
+        settings: None,
+        quests: HashMap::new(),
+        questlines: HashMap::new(),
+        questline_order: Vec::new(),
+    };
+    let existing = generate_retrieval_quests(
+        &mut db,
+        &read_rows("minecraft:dirt,1,Getting Started".as_bytes()).unwrap(),
+        &GridLayout::default(),
+    )
+    .unwrap();
+    assert_eq!(existing, vec![QuestId::from_parts(0, 0)]);
+
+    let layout = GridLayout {
+        columns: 2,
+        spacing: 30,
+        size: 24,
+    };
+    let ids = generate_retrieval_quests(&mut db, &rows, &layout).unwrap();
+    let id = |low| QuestId::from_parts(0, low);
+    assert_eq!(ids, vec![id(1), id(2), id(3), id(4)]);
+    assert_eq!(db.questline_order.len(), 2);
+
+    let log = &db.quests[&id(1)];
+    assert_eq!(log.name(), Some("Collect 16 Log"));
+    assert_eq!(log.prerequisites, vec![id(0)]);
+    assert_eq!(log.tasks[0].task_id, "bq_standard:retrieval");
+    assert_eq!(log.tasks[0].required_items[0].count, Some(16));
+    assert_eq!(db.quests[&id(2)].name(), Some("Collect Wool"));
+    assert!(db.quests[&id(2)].prerequisites.is_empty());
+    assert_eq!(db.quests[&id(3)].prerequisites, vec![id(1)]);
+    assert_eq!(db.quests[&id(4)].name(), Some("Collect 64 Cobblestone"));
+
+    let start = &db.questlines[&db.questline_order[0]];
+    let grid: Vec<_> = start.entries.iter().map(|e| (e.x, e.y)).collect();
+    assert_eq!(
+        grid,
+        vec![
+            (Some(0), Some(0)),
+            (Some(30), Some(0)),
+            (Some(0), Some(30)),
+            (Some(30), Some(30)),
+        ]
+    );
+
+    // the result round-trips through the writer
+    let root = std::env::temp_dir().join(format!("bqt-wizard-{}", std::process::id()));
+    write_default_quests_dir(&db, &root).unwrap();
+    let parsed = parse_default_quests_dir(&root).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(parsed.quests.len(), 5);
+    assert_eq!(parsed.quests[&id(3)].prerequisites, vec![id(1)]);
+    assert_eq!(
+        parsed.quests[&id(2)].tasks[0].required_items[0].damage,
+        Some(14)
+    );
+}
+
+#[test]
+fn bad_rows_are_rejected() {
+    assert!(matches!(
+        read_rows("minecraft:log,many,Start".as_bytes()),
+        Err(ParseError::InvalidFormat(_))
+    ));
+    assert!(matches!(
+        read_rows("minecraft:log,0,Start".as_bytes()),
+        Err(ParseError::NumberOutOfRange { .. })
+    ));
+    assert!(read_rows("minecraft:log,1".as_bytes()).is_err());
+    assert!(read_rows("\"minecraft:log,1,Start".as_bytes()).is_err());
+}
+
+#[test]
+fn running_out_of_ids_is_an_error() {
+    let last = QuestId::from_parts_i64(0, i64::MAX - 1);
+    let mut db = QuestDatabase::default();
+    db.quests.insert(
+        last,
+        Quest {
+            id: last,
+            properties: None,
+            tasks: vec![],
+            rewards: vec![],
+            prerequisites: vec![],
+            required_prerequisites: vec![],
+            optional_prerequisites: vec![],
+        },
+    );
+    let rows = read_rows(CSV.as_bytes()).unwrap();
+    assert!(matches!(
+        generate_retrieval_quests(&mut db, &rows, &GridLayout::default()),
+        Err(ParseError::NumberOutOfRange { .. })
+    ));
+    assert_eq!(db.quests.len(), 1);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use better_questing_tools::analysis::{LayoutIssue, LayoutLimits, check_layout};
+use better_questing_tools::model::*;
+use common::q;
+
+fn entry(low: i32, x: i32, y: i32, size: Option<i32>) -> QuestLineEntry {
+    QuestLineEntry {
+        x: Some(x),
+        y: Some(y),
+        size_x: size,
+        size_y: size,
+        ..common::entry(low)
+    }
+}
+
+fn db(entries: Vec<QuestLineEntry>) -> QuestDatabase {
+    let line = entries
+        .into_iter()
+        .fold(common::line(100), |line, entry| line.entry(entry));
+    common::db([], [line.build()])
+}
+
+#[test]
+fn clean_grid_has_no_issues() {
+    let entries = (0..16)
+        .map(|i| entry(i, (i % 4) * 32, (i / 4) * 32, Some(24)))
+        .collect();
+    assert_eq!(check_layout(&db(entries), &LayoutLimits::default()), []);
+}
+
+#[test]
+fn reports_stacked_overlapping_and_stray_tiles() {
+    let entries = vec![
+        entry(1, 0, 0, None),
+        entry(2, 0, 0, Some(24)),
+        entry(3, 12, 12, None),
+        entry(4, 24, 0, None), // touches 1 and 2, does not overlap
+        entry(5, 100, 100, Some(0)),
+        entry(6, 90_000, 0, None),
+    ];
+    let issues = check_layout(&db(entries), &LayoutLimits::default());
+    let line = q(100);
+    assert_eq!(
+        issues,
+        [
+            LayoutIssue::DuplicatePosition {
+                questline: line,
+                x: 0,
+                y: 0,
+                quests: vec![q(1), q(2)],
+            },
+            LayoutIssue::Overlap {
+                questline: line,
+                first: q(1),
+                second: q(3),
+            },
+            LayoutIssue::Overlap {
+                questline: line,
+                first: q(2),
+                second: q(3),
+            },
+            LayoutIssue::Overlap {
+                questline: line,
+                first: q(3),
+                second: q(4),
+            },
+            LayoutIssue::EmptyTile {
+                questline: line,
+                quest: q(5),
+                size_x: 0,
+                size_y: 0,
+            },
+            LayoutIssue::OutOfBounds {
+                questline: line,
+                quest: q(6),
+                x: 90_000,
+                y: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn limits_are_configurable() {
+    let limits = LayoutLimits {
+        min_x: 0,
+        min_y: 0,
+        max_x: 64,
+        max_y: 64,
+        default_size: 32,
+    };
+    let entries = vec![entry(1, 0, 0, None), entry(2, 40, 0, None)];
+    let issues = check_layout(&db(entries), &limits);
+    assert_eq!(
+        issues,
+        [LayoutIssue::OutOfBounds {
+            questline: q(100),
+            quest: q(2),
+            x: 40,
+            y: 0,
+        }]
+    );
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use better_questing_tools::export::svg::{
+    SvgOptions, questline_svgs, questline_to_svg, questline_to_svg_with, write_questline_svgs,
+};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use common::{db, line, q};
+use serde_json::json;
+
+fn quest(low: i32, name: &str, prereqs: &[i32]) -> Quest {
+    let prereqs: serde_json::Map<_, _> = prereqs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            (
+                format!("{i}:10"),
+                json!({ "questIDHigh:4": 0, "questIDLow:4": p }),
+            )
+        })
+        .collect();
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "preRequisites:9": prereqs,
+        "properties:10": { "betterquesting:10": { "name:8": name } },
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+fn entry(low: i32, x: i32, y: i32) -> QuestLineEntry {
+    QuestLineEntry {
+        x: Some(x),
+        y: Some(y),
+        size_x: Some(24),
+        ..common::entry(low)
+    }
+}
+
+fn pack() -> QuestDatabase {
+    let line = line(100)
+        .name("§6Iron & Steel")
+        .entry(entry(1, 0, 0))
+        .entry(entry(2, 48, 0))
+        .entry(entry(9, 0, 48))
+        .build();
+    db(
+        [quest(1, "Smelt <iron>", &[]), quest(2, "Steel", &[1, 3])],
+        [line],
+    )
+}
+
+#[test]
+fn draws_tiles_captions_and_arrows() {
+    let db = pack();
+    let svg = questline_to_svg(&db, &db.questlines[&q(100)]);
+    assert!(
+        svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-24 -24 120 120\"")
+    );
+    assert!(svg.contains("<title>Iron &amp; Steel</title>"));
+    assert!(svg.contains("<rect x=\"48\" y=\"0\" width=\"24\" height=\"24\" rx=\"2\"/>"));
+    assert!(svg.contains(">Smelt &lt;iron&gt;</text>"));
+    // prerequisite 3 is not on the questline, so only one arrow
+    assert_eq!(svg.matches("<line ").count(), 1);
+    assert!(svg.contains("<line x1=\"24\" y1=\"12\" x2=\"48\" y2=\"12\""));
+    // the missing quest is drawn with its id
+    assert!(svg.contains("<g class=\"quest missing\">"));
+    assert!(svg.contains(&format!(">{}</text>", q(9))));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(!svg.contains("<a href"));
+}
+
+#[test]
+fn tiles_follow_the_layout_rules_and_can_link() {
+    let mut db = pack();
+    let line = db.questlines.get_mut(&q(100)).unwrap();
+    // no position counts as the origin, a stored zero size is kept
+    line.entries[1].x = None;
+    line.entries[1].y = None;
+    line.entries[2].size_x = Some(0);
+    let options = SvgOptions {
+        quest_links: true,
+        ..SvgOptions::default()
+    };
+    let svg = questline_to_svg_with(&db, &db.questlines[&q(100)], &options);
+    assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"24\" height=\"24\" rx=\"2\"/>"));
+    assert!(svg.contains("<rect x=\"0\" y=\"48\" width=\"0\" height=\"24\" rx=\"2\"/>"));
+    assert!(svg.contains("<a href=\"quest-2.html\"><g class=\"quest\">"));
+    // missing quests have no page to link to
+    assert!(svg.contains("  <g class=\"quest missing\">"));
+}
+
+#[test]
+fn one_file_per_questline() {
+    let db = pack();
+    let drawings = questline_svgs(&db, &SvgOptions::default());
+    assert_eq!(drawings.len(), 1);
+    assert_eq!(drawings[0].file_name, "01-iron-steel.svg");
+
+    let dir = std::env::temp_dir().join(format!("bqt-svg-{}", std::process::id()));
+    write_questline_svgs(&db, &dir, &SvgOptions::default()).unwrap();
+    let written = std::fs::read_to_string(dir.join("01-iron-steel.svg")).unwrap();
+    assert_eq!(written, drawings[0].svg);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use better_questing_tools::analysis::{EconomyThresholds, economy_report};
+use better_questing_tools::index::ItemKey;
+use better_questing_tools::model::{Quest, QuestDatabase};
+use better_questing_tools::quest_id::QuestId;
+use common::{item_reward, q, stack};
+use std::collections::HashMap;
+
+/// A quest rewarding `diamonds` diamonds, repeating every `repeat` ticks.
+fn quest(id: i32, repeat: i32, diamonds: i32) -> Quest {
+    common::quest(id)
+        .name(&format!("Quest {id}"))
+        .repeat_time(repeat)
+        .rewards([item_reward([stack("minecraft:diamond", diamonds)])])
+        .build()
+}
+
+fn prices() -> HashMap<ItemKey, f64> {
+    HashMap::from([(
+        ItemKey {
+            id: "minecraft:diamond".to_string(),
+            damage: 0,
+        },
+        10.0,
+    )])
+}
+
+#[test]
+fn reports_daily_value_and_flags_exploits() {
+    let day = 1_728_000;
+    let db = QuestDatabase {
+        settings: None,
+        quests: [
+            quest(1, -1, 64),      // not repeatable
+            quest(2, day, 1),      // daily: 10 a day
+            quest(3, day / 24, 5), // hourly: 1200 a day
+            quest(4, 0, 1),        // no cooldown
+            quest(5, 600, 0),      // no rewards
+        ]
+        .into_iter()
+        .map(|q| (q.id, q))
+        .collect(),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+    let report = economy_report(&db, &prices(), &EconomyThresholds::default());
+
+    let order: Vec<QuestId> = report.quests.iter().map(|e| e.quest).collect();
+    assert_eq!(order, vec![q(4), q(3), q(2), q(5)]);
+    assert_eq!(report.unbounded, vec![q(4)]);
+    assert_eq!(report.max_value_per_day, 1210.0);
+
+    let hourly = &report.quests[1];
+    assert_eq!(hourly.claims_per_day, Some(24.0));
+    assert_eq!(hourly.value_per_claim, 50.0);
+    assert_eq!(hourly.value_per_day, Some(1200.0));
+
+    let flagged: Vec<QuestId> = report.exploitable().map(|e| e.quest).collect();
+    assert_eq!(flagged, vec![q(4), q(3)]);
+}
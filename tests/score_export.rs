@@ -0,0 +1,67 @@
+mod common;
+
+use better_questing_tools::export::scores::{ranked_rows, to_csv, to_json};
+use better_questing_tools::export::{FLOAT_PRECISION, format_float, round_float};
+use better_questing_tools::importance::compute_importance_scores;
+use better_questing_tools::model::QuestDatabase;
+use common::{db, q, quest};
+use std::collections::HashMap;
+
+/// Wood (1) <- Planks (2) <- Sticks (3).
+fn chain() -> QuestDatabase {
+    db(
+        [
+            quest(1).name("§aWood").build(),
+            quest(2).name("Planks").requires(&[1]).build(),
+            quest(3).name("Sticks").requires(&[2]).build(),
+        ],
+        [],
+    )
+}
+
+#[test]
+fn csv_lists_the_computed_scores() {
+    let db = chain();
+    // one dependent each for Wood and Planks; Wood also gets half of
+    // Planks' base: 1 + 0.5 * 1
+    let scores = compute_importance_scores(&db, 0.5, false, false).unwrap();
+    assert_eq!(
+        to_csv(&db, &scores),
+        "rank,id,name,score\n\
+         1,1,Wood,1.500000\n\
+         2,2,Planks,1.000000\n\
+         3,3,Sticks,0.000000\n"
+    );
+}
+
+#[test]
+fn log_scores_are_written_with_fixed_precision() {
+    let db = chain();
+    // ln 2 for one dependent, Wood 1.5 * ln 2
+    let scores = compute_importance_scores(&db, 0.5, true, false).unwrap();
+    let rows = ranked_rows(&db, &scores);
+    let values: Vec<String> = rows
+        .iter()
+        .map(|r| format_float(r.score, FLOAT_PRECISION))
+        .collect();
+    assert_eq!(values, ["1.039721", "0.693147", "0.000000"]);
+    assert!(to_csv(&db, &scores).contains("1,1,Wood,1.039721\n"));
+    let json: serde_json::Value = serde_json::from_str(&to_json(&db, &scores).unwrap()).unwrap();
+    assert_eq!(json[0]["score"].to_string(), "1.039721");
+    assert_eq!(json[1]["name"], "Planks");
+    assert_eq!(json[1]["score"].to_string(), "0.693147");
+}
+
+#[test]
+fn scores_differing_in_noise_tie_and_rank_by_id() {
+    let db = chain();
+    let scores = HashMap::from([(q(3), 0.1 + 0.2), (q(2), 0.3), (q(1), 0.25)]);
+    let rows = ranked_rows(&db, &scores);
+    let ranked: Vec<_> = rows.iter().map(|r| (r.rank, r.id, r.score)).collect();
+    assert_eq!(
+        ranked,
+        vec![(1, q(2), 0.3), (2, q(3), 0.3), (3, q(1), 0.25)]
+    );
+    assert_eq!(round_float(0.1 + 0.2, FLOAT_PRECISION), 0.3);
+    assert_eq!(round_float(2.0 / 3.0, 2), 0.67);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "site")]
+
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::site::{build_site, write_site};
+use common::{db, line};
+use serde_json::json;
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .properties(json!({ "name": name, "desc": "Line one\nline <two>" }))
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn entry(low: i32, x: i32, y: i32) -> QuestLineEntry {
+    QuestLineEntry {
+        x: Some(x),
+        y: Some(y),
+        size_x: Some(24),
+        size_y: Some(24),
+        ..common::entry(low)
+    }
+}
+
+fn sample_db() -> QuestDatabase {
+    let line = line(100)
+        .name("§6Tier 1")
+        .entry(entry(1, 0, 0))
+        .entry(entry(2, 48, 0))
+        .entry(entry(3, 48, 48))
+        .build();
+    db(
+        [
+            quest(1, "Start", &[], &[]),
+            quest(2, "Tools & Toys", &[1], &[]),
+            quest(3, "Extra", &[], &[2]),
+            quest(4, "Secret", &[3, 9], &[]),
+        ],
+        [line],
+    )
+}
+
+#[test]
+fn site_has_index_line_and_quest_pages() {
+    let db = sample_db();
+    let pages = build_site(&db);
+    let paths: Vec<&str> = pages.iter().map(|p| p.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "index.html",
+            "line-100.html",
+            "quest-1.html",
+            "quest-2.html",
+            "quest-3.html",
+            "quest-4.html"
+        ]
+    );
+
+    let index = &pages[0].html;
+    assert!(index.contains("<a href=\"line-100.html\">Tier 1</a> (3 quests)"));
+    assert!(index.contains(
+        "<h2>Quests on no questline</h2>\n<ul>\n<li><a href=\"quest-4.html\">Secret</a></li>"
+    ));
+
+    let line = &pages[1].html;
+    assert!(line.contains("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-24 -24 120 120\""));
+    assert!(line.contains(
+        "<line x1=\"24\" y1=\"12\" x2=\"48\" y2=\"12\" class=\"prerequisite\" marker-end"
+    ));
+    assert!(line.contains(
+        "<line x1=\"60\" y1=\"24\" x2=\"60\" y2=\"48\" class=\"prerequisite\" stroke-dasharray"
+    ));
+    assert!(line.contains(
+        "<a href=\"quest-2.html\"><g class=\"quest\">\n    <title>Tools &amp; Toys (2)</title>"
+    ));
+
+    let tools = &pages[3].html;
+    assert!(tools.contains("<p>Line one<br>line &lt;two&gt;</p>"));
+    assert!(
+        tools.contains("<h2>Questlines</h2>\n<ul>\n<li><a href=\"line-100.html\">Tier 1</a></li>")
+    );
+    assert!(tools.contains("<h2>Requires</h2>\n<ul>\n<li><a href=\"quest-1.html\">Start</a></li>"));
+    assert!(tools.contains("<h2>Unlocks</h2>\n<ul>\n<li><a href=\"quest-3.html\">Extra</a></li>"));
+
+    let secret = &pages[5].html;
+    assert!(secret.contains("<li><a href=\"quest-3.html\">Extra</a></li>\n<li>Quest 9</li>"));
+}
+
+#[test]
+fn write_site_creates_every_page() {
+    let db = sample_db();
+    let dir = std::env::temp_dir().join(format!("bqt-site-{}", std::process::id()));
+    write_site(&db, &dir).unwrap();
+    for page in build_site(&db) {
+        assert_eq!(
+            std::fs::read_to_string(dir.join(&page.path)).unwrap(),
+            page.html
+        );
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
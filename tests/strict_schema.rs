@@ -0,0 +1,163 @@
+use better_questing_tools::db::{
+    FsDataSource, ParseOptions, parse_default_quests_dir_with_options, write_default_quests_dir,
+};
+use better_questing_tools::error::ParseError;
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::schema::{
+    FieldScope, levenshtein, quest_unknown_fields, suggest, unknown_fields,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn quest_json() -> Value {
+    json!({
+        "questIDHigh:4": 0, "questIDLow:4": 1,
+        "properties:10": { "betterquesting:10": {
+            "name:8": "Wood", "desc:8": "Chop", "desc2:8": "More",
+            "icon:10": { "id:8": "minecraft:log", "Count:3": 1, "Damage:2": 0 },
+            "isMain:1": 1, "repeatTime:3": -1,
+        }},
+        "tasks:9": { "0:10": {
+            "taskID:8": "bq_standard:retrieval", "index:3": 0, "consume:1": 0,
+            "requiredItems:9": { "0:10": { "id:8": "minecraft:log", "Count:3": 16 } },
+        }},
+        "rewards:9": {
+            "0:10": { "rewardID:8": "bq_standard:item", "index:3": 0,
+                "rewards:9": { "0:10": { "id:8": "minecraft:apple", "Count:3": 2 } } },
+            "1:10": { "rewardID:8": "othermod:custom", "index:3": 1, "anything:8": "goes" },
+        },
+        "preRequisites:9": {},
+    })
+}
+
+#[test]
+fn well_formed_quest_has_no_unknown_fields() {
+    assert_eq!(quest_unknown_fields(&quest_json(), "Quests/1.json"), []);
+}
+
+#[test]
+fn typos_are_reported_with_their_path() {
+    let mut v = quest_json();
+    v["properties:10"]["betterquesting:10"]["repeatTiem:3"] = json!(100);
+    v["tasks:9"]["0:10"]["requiredItems:9"]["0:10"]["Cont:3"] = json!(3);
+    v["rewards:9"]["0:10"]["ignoreDisabeld:1"] = json!(1);
+    v["questIDLo:4"] = json!(1);
+    let found = quest_unknown_fields(&v, "Quests/1.json");
+    let paths: Vec<(&str, FieldScope)> = found.iter().map(|f| (f.path.as_str(), f.scope)).collect();
+    assert_eq!(
+        paths,
+        [
+            ("questIDLo", FieldScope::Quest),
+            (
+                "properties.betterquesting.repeatTiem",
+                FieldScope::QuestProperties
+            ),
+            ("tasks[0].requiredItems[0].Cont", FieldScope::Item),
+            ("rewards[0].ignoreDisabeld", FieldScope::Reward),
+        ]
+    );
+    assert_eq!(found[1].key, "repeatTiem");
+    assert_eq!(
+        found[1].to_string(),
+        "Quests/1.json: unknown key `repeatTiem` at properties.betterquesting.repeatTiem, \
+         did you mean `repeatTime`?"
+    );
+    let suggestions: Vec<Option<&str>> = found.iter().map(|f| f.suggestion.as_deref()).collect();
+    assert_eq!(
+        suggestions,
+        [
+            Some("questIDLow"),
+            Some("repeatTime"),
+            Some("Count"),
+            Some("ignoreDisabled")
+        ]
+    );
+}
+
+#[test]
+fn suggestions_use_case_aliases_and_edit_distance() {
+    let props = FieldScope::QuestProperties;
+    assert_eq!(suggest(props, None, "autoclaim"), Some("autoClaim"));
+    assert_eq!(suggest(props, None, "auto_claim"), Some("autoClaim"));
+    assert_eq!(suggest(props, None, "quest_logic"), Some("questLogic"));
+    assert_eq!(suggest(props, None, "main"), Some("isMain"));
+    assert_eq!(suggest(props, None, "completelyDifferent"), None);
+    let retrieval = Some("bq_standard:retrieval");
+    assert_eq!(
+        suggest(FieldScope::Task, retrieval, "required_items"),
+        Some("requiredItems")
+    );
+    assert_eq!(
+        suggest(FieldScope::Task, retrieval, "partialMach"),
+        Some("partialMatch")
+    );
+    assert_eq!(
+        suggest(FieldScope::Reward, Some("bq_standard:item"), "items"),
+        Some("rewards")
+    );
+    // unknown task types have no table to suggest from
+    assert_eq!(suggest(FieldScope::Task, Some("mod:task"), "consum"), None);
+    assert_eq!(levenshtein("kitten", "sitting"), 3);
+    assert_eq!(levenshtein("", "abc"), 3);
+}
+
+#[test]
+fn strict_parse_rejects_unknown_fields_in_a_folder() {
+    let quest = parse_quest_from_reader(quest_json().to_string().as_bytes()).unwrap();
+    let line = QuestLine {
+        id: quest.id,
+        properties: Some(QuestLineProperties {
+            name: "Line".to_string(),
+            ..Default::default()
+        }),
+        entries: vec![QuestLineEntry {
+            index: None,
+            quest_id: quest.id,
+            x: Some(0),
+            y: Some(0),
+            size_x: Some(24),
+            size_y: Some(24),
+            extra: HashMap::new(),
+        }],
+        extra: HashMap::new(),
+    };
+    let db = QuestDatabase {
+        settings: Some(QuestSettings {
+            version: Some("3.0.0".to_string()),
+            extra: [("editMode".to_string(), json!(0))].into_iter().collect(),
+        }),
+        questline_order: vec![line.id],
+        questlines: [(line.id, line)].into_iter().collect(),
+        quests: [(quest.id, quest)].into_iter().collect(),
+    };
+    let dir = std::env::temp_dir().join(format!("bqt-strict-schema-{}", std::process::id()));
+    write_default_quests_dir(&db, &dir).unwrap();
+    let root = dir.to_string_lossy().to_string();
+    let strict = ParseOptions {
+        deny_unknown_fields: true,
+        ..Default::default()
+    };
+    // what the writer produces passes
+    assert_eq!(unknown_fields(&FsDataSource, &root).unwrap(), []);
+    assert!(parse_default_quests_dir_with_options(&FsDataSource, &root, &strict).is_ok());
+
+    let entry = dir.join("QuestLines/0/0.json");
+    let mut v: Value = serde_json::from_str(&std::fs::read_to_string(&entry).unwrap()).unwrap();
+    v["sizeZ:3"] = json!(1);
+    std::fs::write(&entry, v.to_string()).unwrap();
+    let lenient = parse_default_quests_dir_with_options(&FsDataSource, &root, &Default::default());
+    let result = parse_default_quests_dir_with_options(&FsDataSource, &root, &strict);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(lenient.is_ok());
+    match result {
+        Err(ParseError::UnknownFields(found)) => {
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].key, "sizeZ");
+            assert_eq!(found[0].scope, FieldScope::QuestlineEntry);
+            assert!(found[0].file.ends_with("QuestLines/0/0.json"));
+        }
+        other => panic!("expected UnknownFields, got {:?}", other),
+    }
+}
@@ -0,0 +1,74 @@
+use better_questing_tools::export::summary::summarize;
+use better_questing_tools::model::*;
+use better_questing_tools::quest_id::QuestId;
+use std::collections::HashMap;
+
+fn props(name: &str) -> QuestProperties {
+    serde_json::from_value(serde_json::json!({ "name": name })).expect("properties")
+}
+
+fn make_db() -> QuestDatabase {
+    let a = QuestId::from_parts(0, 1);
+    let b = QuestId::from_parts(0, 2);
+    let reward: Reward = serde_json::from_value(serde_json::json!({
+        "rewardID": "bq_standard:item",
+        "rewards": [{ "id": "minecraft:bread", "Count": 4 }],
+    }))
+    .expect("reward");
+    let mut quests = HashMap::new();
+    quests.insert(
+        a,
+        Quest {
+            id: a,
+            properties: Some(props("First Steps")),
+            tasks: vec![],
+            rewards: vec![reward],
+            prerequisites: vec![],
+            required_prerequisites: vec![],
+            optional_prerequisites: vec![],
+        },
+    );
+    quests.insert(
+        b,
+        Quest {
+            id: b,
+            properties: Some(props("Second Steps")),
+            tasks: vec![],
+            rewards: vec![],
+            prerequisites: vec![a],
+            required_prerequisites: vec![a],
+            optional_prerequisites: vec![],
+        },
+    );
+    QuestDatabase {
+        settings: None,
+        quests,
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    }
+}
+
+#[test]
+fn summary_lists_quests_in_id_order() {
+    let summary = summarize(&make_db());
+    assert_eq!(summary.quest_count, 2);
+    assert_eq!(summary.quests[0].name, "First Steps");
+    assert_eq!(summary.quests[0].rewards, vec!["4x minecraft:bread"]);
+    assert_eq!(summary.quests[1].prerequisites, vec!["1"]);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn summary_renders_as_toml() {
+    let out = better_questing_tools::export::summary::to_toml(&make_db()).expect("toml");
+    assert!(out.contains("[[quests]]"));
+    assert!(out.contains("name = \"Second Steps\""));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn summary_renders_as_yaml() {
+    let out = better_questing_tools::export::summary::to_yaml(&make_db()).expect("yaml");
+    assert!(out.contains("quest_count: 2"));
+    assert!(out.contains("- 4x minecraft:bread"));
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use better_questing_tools::error::ParseError;
+use better_questing_tools::graph::topological_order;
+use better_questing_tools::model::*;
+use common::q;
+
+fn quest(low: i32, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn db(quests: Vec<Quest>) -> QuestDatabase {
+    common::db(quests, [])
+}
+
+#[test]
+fn order_respects_prerequisites_with_smallest_id_first() {
+    let db = db(vec![
+        quest(5, &[], &[]),
+        quest(2, &[5], &[]),
+        quest(3, &[], &[]),
+        quest(1, &[3], &[2]),
+        quest(4, &[99], &[]), // missing prerequisite is ignored
+    ]);
+    let order = topological_order(&db).unwrap();
+    assert_eq!(order, vec![q(3), q(4), q(5), q(2), q(1)]);
+}
+
+#[test]
+fn cycles_are_reported() {
+    let db = db(vec![
+        quest(1, &[], &[]),
+        quest(2, &[1, 3], &[]),
+        quest(3, &[2], &[]),
+        quest(4, &[3], &[]),
+    ]);
+    match topological_order(&db) {
+        Err(ParseError::CycleDetected(members)) => assert_eq!(members, vec![q(2), q(3)]),
+        other => panic!("expected a cycle, got {:?}", other),
+    }
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "tui")]
+
+mod common;
+
+use better_questing_tools::model::*;
+use better_questing_tools::tui::{Explorer, TreeRow};
+use common::{db, q};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32]) -> Quest {
+    common::quest(low)
+        .name(name)
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn line(low: i32, name: &str, quests: &[i32]) -> QuestLine {
+    common::line(low).name(name).entries(quests).build()
+}
+
+fn sample() -> QuestDatabase {
+    db(
+        [
+            quest(1, "Wood", &[], &[]),
+            quest(2, "Stone Age", &[1], &[]),
+            quest(3, "§bIron", &[2], &[4]),
+            quest(4, "Secret Stash", &[], &[]),
+        ],
+        [line(10, "Basics", &[1, 2]), line(11, "Metals", &[3])],
+    )
+}
+
+fn press(explorer: &mut Explorer, code: KeyCode) -> bool {
+    explorer.handle_key(KeyEvent::from(code))
+}
+
+#[test]
+fn tree_navigation_and_prerequisite_jumps() {
+    let db = sample();
+    let mut ex = Explorer::new(&db);
+    assert_eq!(
+        ex.rows(),
+        &[
+            TreeRow::Line(Some(q(10))),
+            TreeRow::Line(Some(q(11))),
+            TreeRow::Line(None),
+        ]
+    );
+
+    press(&mut ex, KeyCode::Down);
+    press(&mut ex, KeyCode::Enter);
+    press(&mut ex, KeyCode::Down);
+    assert_eq!(ex.selected_quest(), Some(q(3)));
+
+    // prerequisites are listed by id: [1] Stone Age, [2] Secret Stash
+    press(&mut ex, KeyCode::Char('2'));
+    assert_eq!(ex.selected_row(), Some(TreeRow::Quest(None, q(4))));
+    press(&mut ex, KeyCode::Backspace);
+    assert_eq!(ex.selected_quest(), Some(q(3)));
+    press(&mut ex, KeyCode::Char('1'));
+    assert_eq!(ex.selected_row(), Some(TreeRow::Quest(Some(q(10)), q(2))));
+
+    // search wraps around and is case-insensitive
+    for c in "/wood".chars() {
+        press(&mut ex, KeyCode::Char(c));
+    }
+    press(&mut ex, KeyCode::Enter);
+    assert_eq!(ex.selected_quest(), Some(q(1)));
+    assert!(!ex.search("nothing"));
+
+    assert!(!press(&mut ex, KeyCode::Char('q')));
+}
+
+#[test]
+fn renders_tree_and_details() {
+    let db = sample();
+    let mut ex = Explorer::new(&db);
+    ex.select_quest(q(3));
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 16)).unwrap();
+    terminal.draw(|frame| ex.render(frame)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|c| c.symbol())
+        .collect();
+    assert!(screen.contains("▾ Metals"));
+    assert!(screen.contains("▸ Basics"));
+    assert!(screen.contains("in Metals"));
+    assert!(screen.contains("[2] Secret Stash (4) (optional)"));
+    assert!(!screen.contains("§"));
+}
@@ -0,0 +1,118 @@
+use better_questing_tools::db::parse_default_quests_dir;
+use better_questing_tools::error::ParseError;
+use better_questing_tools::lazy::parse_quest_header;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::progress::parse_progress_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+
+const START: &str = "0f8b4c6e-1d2a-4b3c-9e8f-7a6b5c4d3e2f";
+const NEXT: &str = "a1b2c3d4-e5f6-4789-8abc-def012345678";
+const LINE: &str = "5e4d3c2b-1a09-4876-b5a4-938271605f4e";
+
+fn quest(id: &str, name: &str, prereqs: &[&str]) -> serde_json::Value {
+    let prereqs: serde_json::Map<String, serde_json::Value> = prereqs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (format!("{}:8", i), json!(p)))
+        .collect();
+    json!({
+        "questID:8": id,
+        "properties:10": {"betterquesting:10": {"name:8": name}},
+        "preRequisites:9": prereqs,
+    })
+}
+
+#[test]
+fn uuid_keyed_pack_parses() {
+    let root = std::env::temp_dir().join(format!("bqt-uuid-ids-{}", std::process::id()));
+    let line_dir = root.join("QuestLines").join("0");
+    std::fs::create_dir_all(root.join("Quests")).unwrap();
+    std::fs::create_dir_all(&line_dir).unwrap();
+    for (id, q) in [
+        (START, quest(START, "Start", &[])),
+        (NEXT, quest(NEXT, "Next", &[START])),
+    ] {
+        std::fs::write(
+            root.join("Quests").join(format!("{}.json", id)),
+            q.to_string(),
+        )
+        .unwrap();
+    }
+    let line = json!({
+        "questLineID:8": LINE,
+        "properties:10": {"betterquesting:10": {"name:8": "Chapter"}},
+    });
+    std::fs::write(line_dir.join("QuestLine.json"), line.to_string()).unwrap();
+    let entry = json!({"questID:8": NEXT, "x:3": 24, "y:3": 0});
+    std::fs::write(line_dir.join("0.json"), entry.to_string()).unwrap();
+
+    let db = parse_default_quests_dir(&root).unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let start = QuestId::from_uuid_str(START).unwrap();
+    let next = QuestId::from_uuid_str(NEXT).unwrap();
+    assert_eq!(db.quests[&next].prerequisites, vec![start]);
+    assert_eq!(db.quests[&start].name(), Some("Start"));
+    let line = &db.questlines[&QuestId::from_uuid_str(LINE).unwrap()];
+    assert_eq!(line.entries[0].quest_id, next);
+    assert_eq!(next.to_string(), NEXT);
+}
+
+#[test]
+fn uuid_header_and_progress() {
+    let text = quest(NEXT, "Next", &[START]).to_string();
+    let header = parse_quest_header(&text).unwrap();
+    assert_eq!(header.id, QuestId::from_uuid_str(NEXT).unwrap());
+    assert_eq!(
+        header.prerequisites,
+        vec![QuestId::from_uuid_str(START).unwrap()]
+    );
+
+    let progress = json!({"questProgress:9": {"0:10": {
+        "questID:8": START,
+        "completed:9": {"0:10": {"uuid:8": "player", "timestamp:4": 1, "claimed:1": 1}},
+    }}});
+    let progress = parse_progress_from_reader(progress.to_string().as_bytes()).unwrap();
+    assert!(
+        progress
+            .quests
+            .contains_key(&QuestId::from_uuid_str(START).unwrap())
+    );
+}
+
+#[test]
+fn malformed_uuid_is_rejected() {
+    let text = quest("not-a-uuid", "Broken", &[]).to_string();
+    let err = parse_quest_from_reader(text.as_bytes()).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidFormat(msg) if msg.contains("questID")));
+
+    let text = quest(START, "Broken prereq", &["nope"]).to_string();
+    assert!(parse_quest_from_reader(text.as_bytes()).is_err());
+}
+
+#[test]
+fn integer_ids_are_read_as_the_low_part() {
+    let text = json!({
+        "questID:3": 5,
+        "properties:10": {"betterquesting:10": {"name:8": "Five"}},
+        "preRequisites:9": {"0:3": 4},
+    })
+    .to_string();
+    let quest = parse_quest_from_reader(text.as_bytes()).unwrap();
+    assert_eq!(quest.id, QuestId::from_parts(0, 5));
+    assert_eq!(quest.prerequisites, vec![QuestId::from_parts(0, 4)]);
+    assert_eq!(parse_quest_header(&text).unwrap().id, quest.id);
+
+    let progress = json!({"questProgress:9": {"0:10": {"questID:3": 5}}});
+    let progress = parse_progress_from_reader(progress.to_string().as_bytes()).unwrap();
+    assert!(progress.quests.contains_key(&QuestId::from_parts(0, 5)));
+
+    let text = json!({
+        "questID:1": true,
+        "properties:10": {"betterquesting:10": {"name:8": "Odd"}},
+    })
+    .to_string();
+    let err = parse_quest_from_reader(text.as_bytes()).unwrap_err();
+    assert!(matches!(err, ParseError::InvalidFormat(msg) if msg.contains("questID")));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use better_questing_tools::export::dot::quest_graph_to_dot;
+use better_questing_tools::export::matrix::AdjacencyMatrix;
+use better_questing_tools::model::*;
+use common::{db, q};
+
+fn quest(low: i32, name: &str, required: &[i32], optional: &[i32], logic: Option<&str>) -> Quest {
+    common::quest(low)
+        .properties(serde_json::json!({ "name": name, "quest_logic": logic }))
+        .requires(required)
+        .optional(optional)
+        .build()
+}
+
+fn sample_db() -> QuestDatabase {
+    db(
+        [
+            quest(1, "Start", &[], &[], None),
+            quest(2, "Wood", &[1], &[], None),
+            quest(3, "Tools", &[], &[1, 2, 9, 10], None),
+            quest(4, "Either", &[2, 3], &[], Some("XOR")),
+        ],
+        [],
+    )
+}
+
+#[test]
+fn dot_edges_carry_importance_weights() {
+    let dot = quest_graph_to_dot(&sample_db());
+    assert_eq!(
+        dot,
+        "digraph quests {\n\
+         \x20 q1 [label=\"Start (1)\"]\n\
+         \x20 q2 [label=\"Wood (2)\"]\n\
+         \x20 q3 [label=\"Tools (3)\"]\n\
+         \x20 q4 [label=\"Either (4)\"]\n\
+         \x20 q1 -> q2 [weight=1.000000, label=\"1.000000\"]\n\
+         \x20 q1 -> q3 [weight=0.250000, label=\"0.250000\", style=dashed]\n\
+         \x20 q2 -> q3 [weight=0.250000, label=\"0.250000\", style=dashed]\n\
+         \x20 q2 -> q4 [weight=0.000000, label=\"0.000000\"]\n\
+         \x20 q3 -> q4 [weight=0.000000, label=\"0.000000\"]\n\
+         }\n"
+    );
+}
+
+#[test]
+fn matrix_rows_are_prerequisites_and_columns_dependents() {
+    let matrix = AdjacencyMatrix::build(&sample_db());
+    assert_eq!(matrix.quests, vec![q(1), q(2), q(3), q(4)]);
+    assert_eq!(matrix.weights[0], vec![None, Some(1.0), Some(0.25), None]);
+    assert_eq!(matrix.weights[2][3], Some(0.0));
+    assert_eq!(
+        matrix.to_csv(),
+        ",1,2,3,4\n\
+         1,,1.000000,0.250000,\n\
+         2,,,0.250000,0.000000\n\
+         3,,,,0.000000\n\
+         4,,,,\n"
+    );
+}
@@ -0,0 +1,142 @@
+use better_questing_tools::db::{
+    MemoryDataSource, parse_default_quests_dir, parse_default_quests_dir_from_source,
+    write_default_quests_dir, write_default_quests_dir_to_sink,
+};
+use better_questing_tools::model::*;
+use better_questing_tools::parser::parse_quest_from_reader;
+use better_questing_tools::quest_id::QuestId;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn quest(low: i32, prereqs: &[i32], logic: &str) -> Quest {
+    let mut pre = serde_json::Map::new();
+    for (i, p) in prereqs.iter().enumerate() {
+        pre.insert(
+            format!("{}:10", i),
+            json!({ "questIDHigh:4": 0, "questIDLow:4": p }),
+        );
+    }
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": low,
+        "preRequisites:9": pre,
+        "properties:10": { "betterquesting:10": {
+            "name:8": format!("§aQuest {low}"),
+            "desc:8": "Gather things",
+            "isMain:1": 1,
+            "repeatTime:3": -1,
+            "questLogic:8": logic,
+            "bg_image:8": "kept as extra",
+        }},
+        "tasks:9": { "0:10": {
+            "taskID:8": "bq_standard:retrieval",
+            "index:3": 0,
+            "consume:1": 1,
+            "requiredItems:9": { "0:10": {
+                "id:8": "minecraft:log", "Count:3": 16, "Damage:2": 2, "OreDict:8": ""
+            }},
+        }},
+        "rewards:9": { "0:10": {
+            "rewardID:8": "bq_standard:item",
+            "index:3": 0,
+            "rewards:9": { "0:10": {
+                "id:8": "minecraft:bread", "Count:3": 4, "Damage:2": 0, "OreDict:8": ""
+            }},
+        }},
+    });
+    parse_quest_from_reader(v.to_string().as_bytes()).expect("quest")
+}
+
+#[test]
+fn written_folder_parses_back_to_the_same_database() {
+    let quests: HashMap<QuestId, Quest> = [
+        quest(1, &[], "AND"),
+        quest(2, &[1], "AND"),
+        quest(3, &[1, 2], "OR"),
+    ]
+    .into_iter()
+    .map(|q| (q.id, q))
+    .collect();
+    let lid = QuestId::from_parts(0, 7);
+    let line_props: QuestLineProperties = serde_json::from_value(json!({
+        "name": "Chapter 1",
+        "desc": "The beginning",
+        "visibility": "NORMAL",
+        "bg_image": "betterquesting:textures/gui/bg.png",
+        "bg_size": 256,
+    }))
+    .unwrap();
+    let line = QuestLine {
+        id: lid,
+        properties: Some(line_props),
+        entries: (1..=3)
+            .map(|low| QuestLineEntry {
+                index: None,
+                quest_id: QuestId::from_parts(0, low),
+                x: Some(low * 24),
+                y: Some(-8),
+                size_x: Some(24),
+                size_y: Some(24),
+                extra: HashMap::new(),
+            })
+            .collect(),
+        extra: HashMap::new(),
+    };
+    let db = QuestDatabase {
+        settings: Some(QuestSettings {
+            version: Some("3.0.0".to_string()),
+            extra: HashMap::from([("editMode".to_string(), json!(0))]),
+        }),
+        quests,
+        questlines: HashMap::from([(lid, line)]),
+        questline_order: vec![lid],
+    };
+
+    let root = std::env::temp_dir().join(format!("bqt-write-back-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    write_default_quests_dir(&db, &root).expect("write");
+
+    let text = std::fs::read_to_string(root.join("Quests/2.json")).expect("quest file");
+    assert!(text.contains("\"questIDLow:4\": 2"));
+    assert!(text.contains("\"1:10\"") || text.contains("\"0:10\""));
+    assert!(text.contains("\"name:8\""));
+
+    let back = parse_default_quests_dir(&root).expect("parse back");
+    std::fs::remove_dir_all(&root).ok();
+    assert_eq!(back.quests, db.quests);
+    assert_eq!(back.questlines, db.questlines);
+    assert_eq!(back.settings, db.settings);
+    let props = back.questlines[&lid].properties.as_ref().unwrap();
+    assert_eq!(props.bg_size, Some(256));
+    assert!(props.extra.is_empty());
+}
+
+#[test]
+fn tasks_and_rewards_without_items_round_trip() {
+    let v = json!({
+        "questIDHigh:4": 0,
+        "questIDLow:4": 5,
+        "properties:10": { "betterquesting:10": { "name:8": "Read the book" } },
+        "tasks:9": { "0:10": { "taskID:8": "bq_standard:checkbox", "index:3": 0 } },
+        "rewards:9": { "0:10": {
+            "rewardID:8": "bq_standard:xp", "index:3": 0, "amount:3": 5, "isLevels:1": 1
+        } },
+    });
+    let quest = parse_quest_from_reader(v.to_string().as_bytes()).expect("quest");
+    assert!(quest.tasks[0].required_items.is_empty());
+    let db = QuestDatabase {
+        settings: None,
+        quests: HashMap::from([(quest.id, quest)]),
+        questlines: HashMap::new(),
+        questline_order: vec![],
+    };
+
+    let mut files = MemoryDataSource::new();
+    write_default_quests_dir_to_sink(&mut files, "DefaultQuests", &db).expect("write");
+    let text = files
+        .get("DefaultQuests/Quests/5.json")
+        .expect("quest file");
+    assert!(!text.contains("requiredItems"));
+    let back = parse_default_quests_dir_from_source(&files, "DefaultQuests").expect("parse back");
+    assert_eq!(back.quests, db.quests);
+}